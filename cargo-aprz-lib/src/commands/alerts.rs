@@ -0,0 +1,182 @@
+//! Alerts raised when the appraisal of a workspace changes for the worse between runs.
+//!
+//! Each `[[alerts]]` rule of the configuration compares the current run against the previous run of the
+//! workspace recorded in the history store, so it requires a `[history]` section. Raised alerts are
+//! printed as warnings and posted to the `[notify]` webhook when one is configured.
+
+use super::config::AlertRule;
+use super::deps::DependencyType;
+use crate::HashMap;
+use crate::facts::CrateRef;
+use crate::history::{CrateRecord, RunRecord};
+use core::fmt;
+
+/// An alert raised by a rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alert {
+    /// Name of the rule that raised the alert
+    pub rule: String,
+
+    /// What changed
+    pub message: String,
+}
+
+impl fmt::Display for Alert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.rule, self.message)
+    }
+}
+
+/// Evaluate the alert rules against the changes between the previous and current runs of a workspace.
+///
+/// `dependency_types` tells through which dependency types each crate is reached. Crates missing from it,
+/// such as those appraised with the `crates` command, only match rules which don't restrict dependency types.
+pub fn evaluate_alerts(
+    rules: &[AlertRule],
+    previous: &RunRecord,
+    current: &RunRecord,
+    dependency_types: &HashMap<CrateRef, Vec<DependencyType>>,
+) -> Vec<Alert> {
+    let mut alerts = Vec::new();
+
+    for rule in rules {
+        if let Some(max_drop) = rule.workspace_score_drop
+            && let (Some(before), Some(after)) = (previous.score, current.score)
+            && before - after > max_drop
+        {
+            alerts.push(Alert {
+                rule: rule.name.clone(),
+                message: format!("workspace score dropped from {before:.0} to {after:.0}"),
+            });
+        }
+
+        for crate_record in &current.crates {
+            if !rule_applies(rule, crate_record, dependency_types) {
+                continue;
+            }
+
+            let Some(before) = previous_record(previous, crate_record) else {
+                continue;
+            };
+
+            if let Some(max_drop) = rule.score_drop
+                && let (Some(before_score), Some(after_score)) = (before.score, crate_record.score)
+                && before_score - after_score > max_drop
+            {
+                alerts.push(Alert {
+                    rule: rule.name.clone(),
+                    message: format!(
+                        "{} v{} score dropped from {before_score:.0} to {after_score:.0}",
+                        crate_record.name, crate_record.version
+                    ),
+                });
+            }
+
+            if rule.new_advisories && crate_record.vulnerabilities > before.vulnerabilities {
+                alerts.push(Alert {
+                    rule: rule.name.clone(),
+                    message: format!(
+                        "{} v{} is affected by {} known vulnerabilities, up from {}",
+                        crate_record.name, crate_record.version, crate_record.vulnerabilities, before.vulnerabilities
+                    ),
+                });
+            }
+        }
+    }
+
+    alerts
+}
+
+fn rule_applies(rule: &AlertRule, crate_record: &CrateRecord, dependency_types: &HashMap<CrateRef, Vec<DependencyType>>) -> bool {
+    if rule.dependency_types.is_empty() {
+        return true;
+    }
+
+    let crate_ref = CrateRef::new(&crate_record.name, crate_record.version.parse().ok());
+    dependency_types
+        .get(&crate_ref)
+        .is_some_and(|types| types.iter().any(|t| rule.dependency_types.contains(t)))
+}
+
+/// The record of the same crate in the previous run, preferring the same version when several were appraised.
+fn previous_record<'a>(previous: &'a RunRecord, crate_record: &CrateRecord) -> Option<&'a CrateRecord> {
+    let mut same_name = previous.crates.iter().filter(|c| c.name == crate_record.name);
+    let first = same_name.next()?;
+    Some(
+        core::iter::once(first)
+            .chain(same_name)
+            .find(|c| c.version == crate_record.version)
+            .unwrap_or(first),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn make_run(score: Option<f64>, crates: &[(&str, f64, u64)]) -> RunRecord {
+        RunRecord {
+            workspace: "payments".into(),
+            commit: None,
+            recorded_at: Utc::now(),
+            score,
+            passed: true,
+            crates: crates
+                .iter()
+                .map(|(name, score, vulnerabilities)| CrateRecord {
+                    name: (*name).into(),
+                    version: "1.0.0".into(),
+                    score: Some(*score),
+                    risk: None,
+                    vulnerabilities: *vulnerabilities,
+                })
+                .collect(),
+        }
+    }
+
+    fn make_rule(dependency_types: Vec<DependencyType>) -> AlertRule {
+        AlertRule {
+            name: "Regression".into(),
+            dependency_types,
+            score_drop: Some(10.0),
+            new_advisories: true,
+            workspace_score_drop: Some(5.0),
+        }
+    }
+
+    #[test]
+    fn test_score_drops_and_new_advisories() {
+        let previous = make_run(Some(80.0), &[("serde", 90.0, 0), ("leftpad", 60.0, 0), ("tokio", 85.0, 1)]);
+        let current = make_run(Some(78.0), &[("serde", 85.0, 0), ("leftpad", 40.0, 0), ("tokio", 85.0, 2), ("new", 10.0, 3)]);
+
+        let alerts = evaluate_alerts(&[make_rule(vec![])], &previous, &current, &HashMap::default());
+        let messages: Vec<_> = alerts.iter().map(|a| a.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "leftpad v1.0.0 score dropped from 60 to 40",
+                "tokio v1.0.0 is affected by 2 known vulnerabilities, up from 1",
+            ]
+        );
+
+        let current = make_run(Some(70.0), &[]);
+        let alerts = evaluate_alerts(&[make_rule(vec![])], &previous, &current, &HashMap::default());
+        assert_eq!(alerts[0].to_string(), "Regression: workspace score dropped from 80 to 70");
+    }
+
+    #[test]
+    fn test_dependency_type_filter() {
+        let previous = make_run(None, &[("serde", 90.0, 0), ("criterion", 90.0, 0)]);
+        let current = make_run(None, &[("serde", 50.0, 0), ("criterion", 50.0, 0)]);
+
+        let mut dependency_types = HashMap::default();
+        let version = "1.0.0".parse().ok();
+        let _ = dependency_types.insert(CrateRef::new("serde", version.clone()), vec![DependencyType::Standard]);
+        let _ = dependency_types.insert(CrateRef::new("criterion", version), vec![DependencyType::Dev]);
+
+        let alerts = evaluate_alerts(&[make_rule(vec![DependencyType::Standard])], &previous, &current, &dependency_types);
+        assert_eq!(alerts.len(), 1);
+        assert!(alerts[0].message.starts_with("serde"));
+    }
+}
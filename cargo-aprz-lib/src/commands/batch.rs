@@ -0,0 +1,407 @@
+//! Appraisal of many repositories at once.
+//!
+//! The batch command takes a list of git URLs or local workspace paths, such as all the services of an
+//! organization. Git repositories are cloned into the checkout directory on the first run and updated on
+//! later runs. The dependencies of every workspace are appraised exactly as `cargo aprz deps` would, and the
+//! results are combined into a cross-repository report ranking the repositories by dependency health.
+
+use super::deps::AppraisalArgs;
+use super::submit::{file_stem, git_commit};
+use super::{Host, process_dependencies};
+use crate::Result;
+use camino::{Utf8Path, Utf8PathBuf};
+use clap::Parser;
+use core::fmt::Write as _;
+use ohno::{IntoAppError, bail};
+use serde::Serialize;
+use std::fs;
+use std::io::Write;
+use tokio::process::Command;
+
+const LOG_TARGET: &str = "     batch";
+
+#[derive(Parser, Debug)]
+pub struct BatchArgs {
+    /// Repository to appraise, given as a git URL or the path of a local workspace. Can be repeated
+    #[arg(long = "repo", value_name = "URL|PATH")]
+    pub repos: Vec<String>,
+
+    /// File listing repositories to appraise, one git URL or local path per line
+    #[arg(long, value_name = "PATH")]
+    pub repos_file: Option<Utf8PathBuf>,
+
+    /// Directory where git repositories are cloned, and updated on later runs
+    #[arg(long, value_name = "PATH", default_value = "aprz-checkouts")]
+    pub checkout_dir: Utf8PathBuf,
+
+    /// Directory where the reports of every repository and the consolidated `index.html` and `summary.json`
+    /// reports are written
+    #[arg(long, value_name = "PATH")]
+    pub output_dir: Utf8PathBuf,
+
+    #[command(flatten)]
+    pub appraisal: AppraisalArgs,
+}
+
+/// Outcome of the appraisal of one repository, as written to the consolidated reports
+#[derive(Debug, Clone, Default, Serialize)]
+struct RepoResult {
+    name: String,
+    source: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    commit: Option<String>,
+
+    /// The workspace score
+    #[serde(skip_serializing_if = "Option::is_none")]
+    score: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    grade: Option<String>,
+
+    crates: u64,
+    high_risk: u64,
+    medium_risk: u64,
+
+    /// Name and score of the lowest-scoring crate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    worst: Option<(String, f64)>,
+
+    /// Name of the HTML report of the repository, relative to the output directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    report: Option<String>,
+
+    /// Why the repository couldn't be appraised
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl RepoResult {
+    /// Score used to rank repositories, the workspace score when known, or else the score of the worst crate
+    fn health(&self) -> f64 {
+        self.score.or_else(|| self.worst.as_ref().map(|(_, score)| *score)).unwrap_or(100.0)
+    }
+}
+
+pub async fn process_batch<H: Host>(host: &mut H, args: &BatchArgs) -> Result<()> {
+    let mut sources = args.repos.clone();
+    if let Some(path) = &args.repos_file {
+        let text = fs::read_to_string(path).into_app_err_with(|| format!("reading repository list '{path}'"))?;
+        sources.extend(parse_repo_list(&text));
+    }
+
+    if sources.is_empty() {
+        bail!("no repositories to appraise, use --repo or --repos-file");
+    }
+
+    let mut names: Vec<String> = Vec::with_capacity(sources.len());
+    for source in &sources {
+        let name = repo_name(source);
+        if names.contains(&name) {
+            bail!("more than one repository is named '{name}', their reports would overwrite each other");
+        }
+        names.push(name);
+    }
+
+    fs::create_dir_all(&args.output_dir).into_app_err_with(|| format!("creating output directory '{}'", args.output_dir))?;
+
+    let mut results = Vec::with_capacity(sources.len());
+    for (source, name) in sources.iter().zip(names) {
+        let _ = writeln!(host.output(), "Appraising repository '{name}'");
+        let result = appraise_repo(host, args, source, &name).await.unwrap_or_else(|e| {
+            log::error!(target: LOG_TARGET, "Could not appraise repository '{name}': {e:#}");
+            let _ = writeln!(host.error(), "ERROR: could not appraise repository '{name}': {e}");
+            RepoResult {
+                name,
+                source: source.clone(),
+                error: Some(e.to_string()),
+                ..RepoResult::default()
+            }
+        });
+        results.push(result);
+    }
+
+    rank(&mut results);
+
+    let summary_path = args.output_dir.join("summary.json");
+    fs::write(&summary_path, serde_json::to_string_pretty(&results)?).into_app_err_with(|| format!("writing '{summary_path}'"))?;
+
+    let index_path = args.output_dir.join("index.html");
+    fs::write(&index_path, render_index(&results)).into_app_err_with(|| format!("writing '{index_path}'"))?;
+
+    let _ = write!(host.output(), "{}", render_console(&results));
+    let _ = writeln!(host.output(), "Wrote the consolidated report to '{index_path}'");
+    Ok(())
+}
+
+/// Check out a repository, appraise its dependencies, and read back the outcome from its reports.
+async fn appraise_repo<H: Host>(host: &mut H, args: &BatchArgs, source: &str, name: &str) -> Result<RepoResult> {
+    let dir = if is_git_url(source) {
+        checkout(source, &args.checkout_dir.join(file_stem(name))).await?
+    } else {
+        Utf8PathBuf::from(source)
+    };
+
+    let stem = file_stem(name);
+    let html = args.output_dir.join(format!("{stem}.html"));
+    let json = args.output_dir.join(format!("{stem}.json"));
+    let summary = args.output_dir.join(format!("{stem}.summary.json"));
+
+    let deps_args = args.appraisal.deps_args(
+        &dir.join("Cargo.toml"),
+        &[("--html", &html), ("--json", &json), ("--summary-json", &summary)],
+    )?;
+    process_dependencies(host, &deps_args).await?;
+
+    let mut result = read_result(&read_json(&json)?, &read_json(&summary)?);
+    result.name = name.to_string();
+    result.source = source.to_string();
+    result.commit = git_commit(&dir);
+    result.report = Some(format!("{stem}.html"));
+    Ok(result)
+}
+
+/// Clone a git repository into `dir`, or bring it up to date when it was cloned by an earlier run.
+async fn checkout(url: &str, dir: &Utf8Path) -> Result<Utf8PathBuf> {
+    let mut command = Command::new("git");
+    if dir.join(".git").exists() {
+        log::info!(target: LOG_TARGET, "Updating '{dir}' from '{url}'");
+        let _ = command.arg("-C").arg(dir).args(["pull", "--ff-only", "--quiet"]);
+    } else {
+        log::info!(target: LOG_TARGET, "Cloning '{url}' into '{dir}'");
+        let _ = command.args(["clone", "--depth", "1", "--quiet", url]).arg(dir);
+    }
+
+    let output = command.output().await.into_app_err("running git")?;
+    if !output.status.success() {
+        bail!("checking out '{url}' failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    Ok(dir.to_path_buf())
+}
+
+fn read_json(path: &Utf8Path) -> Result<serde_json::Value> {
+    let text = fs::read_to_string(path).into_app_err_with(|| format!("reading '{path}'"))?;
+    serde_json::from_str(&text).into_app_err_with(|| format!("parsing '{path}'"))
+}
+
+/// Extract the outcome of a repository from its `--json` and `--summary-json` reports.
+fn read_result(report: &serde_json::Value, summary: &serde_json::Value) -> RepoResult {
+    RepoResult {
+        score: report["workspace"]["score"].as_f64(),
+        grade: report["workspace"]["grade"].as_str().map(ToString::to_string),
+        crates: summary["crates"].as_u64().unwrap_or_default(),
+        high_risk: summary["risk"]["high"].as_u64().unwrap_or_default(),
+        medium_risk: summary["risk"]["medium"].as_u64().unwrap_or_default(),
+        worst: summary["worst"]["name"]
+            .as_str()
+            .map(|name| (name.to_string(), summary["worst"]["score"].as_f64().unwrap_or_default())),
+        ..RepoResult::default()
+    }
+}
+
+/// Order repositories from the least to the most healthy, with those which couldn't be appraised last.
+fn rank(results: &mut [RepoResult]) {
+    results.sort_by(|a, b| {
+        a.error
+            .is_some()
+            .cmp(&b.error.is_some())
+            .then_with(|| a.health().partial_cmp(&b.health()).unwrap_or(core::cmp::Ordering::Equal))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+}
+
+/// Repositories listed in a file, one per line, ignoring blank lines and `#` comments.
+fn parse_repo_list(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|line| line.split_once('#').map_or(line, |(before, _)| before).trim())
+        .filter(|line| !line.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+fn is_git_url(source: &str) -> bool {
+    source.contains("://") || source.starts_with("git@")
+}
+
+/// Name of a repository, the last component of its URL or path without any `.git` suffix.
+fn repo_name(source: &str) -> String {
+    let trimmed = source.trim_end_matches(['/', '\\']);
+    let last = trimmed.rsplit(['/', '\\', ':']).next().unwrap_or(trimmed);
+    let name = last.strip_suffix(".git").unwrap_or(last);
+    if name.is_empty() || name == "." || name == ".." {
+        Utf8Path::new(source)
+            .canonicalize_utf8()
+            .ok()
+            .and_then(|path| path.file_name().map(ToString::to_string))
+            .unwrap_or_else(|| "workspace".to_string())
+    } else {
+        name.to_string()
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn score_text(result: &RepoResult) -> String {
+    match (result.score, &result.grade) {
+        (Some(score), Some(grade)) => format!("{score:.0} ({grade})"),
+        (Some(score), None) => format!("{score:.0}"),
+        _ => String::new(),
+    }
+}
+
+fn worst_text(result: &RepoResult) -> String {
+    result
+        .worst
+        .as_ref()
+        .map_or_else(String::new, |(name, score)| format!("{name} ({score:.0})"))
+}
+
+fn render_index(results: &[RepoResult]) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>cargo-aprz batch report</title>\n\
+         <style>body{font-family:sans-serif}table{border-collapse:collapse}th,td{padding:4px 8px;border:1px solid #ccc}\
+         .high,.failed{color:#c00}</style>\n</head>\n<body>\n<h1>Dependency health by repository</h1>\n\
+         <table>\n<tr><th>Repository</th><th>Commit</th><th>Score</th><th>Crates</th><th>High risk</th>\
+         <th>Medium risk</th><th>Worst crate</th></tr>\n",
+    );
+
+    for result in results {
+        let name = result.report.as_deref().map_or_else(
+            || escape(&result.name),
+            |report| format!("<a href=\"{}\">{}</a>", escape(report), escape(&result.name)),
+        );
+
+        if let Some(error) = &result.error {
+            let _ = writeln!(html, "<tr><td>{name}</td><td colspan=\"6\" class=\"failed\">{}</td></tr>", escape(error));
+            continue;
+        }
+
+        let commit = result.commit.as_deref().map_or("", |commit| commit.get(..12).unwrap_or(commit));
+        let _ = writeln!(
+            html,
+            "<tr><td>{name}</td><td><code>{}</code></td><td>{}</td><td>{}</td><td class=\"high\">{}</td><td>{}</td><td>{}</td></tr>",
+            escape(commit),
+            escape(&score_text(result)),
+            result.crates,
+            result.high_risk,
+            result.medium_risk,
+            escape(&worst_text(result)),
+        );
+    }
+
+    html.push_str("</table>\n</body>\n</html>\n");
+    html
+}
+
+fn render_console(results: &[RepoResult]) -> String {
+    let width = results.iter().map(|r| r.name.len()).max().unwrap_or_default();
+
+    let mut text = String::new();
+    for (rank, result) in results.iter().enumerate() {
+        let _ = write!(text, "{:>3}. {:<width$}  ", rank + 1, result.name);
+        if let Some(error) = &result.error {
+            let _ = writeln!(text, "FAILED: {error}");
+        } else {
+            let _ = writeln!(
+                text,
+                "score {:<8}  {} high risk, {} medium risk, worst {}",
+                score_text(result),
+                result.high_risk,
+                result.medium_risk,
+                worst_text(result)
+            );
+        }
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn make_result(name: &str, score: Option<f64>, worst: f64) -> RepoResult {
+        RepoResult {
+            name: name.into(),
+            source: format!("https://example.com/org/{name}.git"),
+            score,
+            crates: 10,
+            high_risk: 1,
+            worst: Some(("leftpad".into(), worst)),
+            report: Some(format!("{name}.html")),
+            ..RepoResult::default()
+        }
+    }
+
+    #[test]
+    fn test_repo_name() {
+        assert_eq!(repo_name("https://github.com/org/payments.git"), "payments");
+        assert_eq!(repo_name("https://github.com/org/payments/"), "payments");
+        assert_eq!(repo_name("git@github.com:org/search.git"), "search");
+        assert_eq!(repo_name("git@example.com:billing"), "billing");
+        assert_eq!(repo_name("../services/ledger"), "ledger");
+
+        assert!(is_git_url("https://github.com/org/payments.git"));
+        assert!(is_git_url("git@github.com:org/search.git"));
+        assert!(!is_git_url("../services/ledger"));
+    }
+
+    #[test]
+    fn test_parse_repo_list() {
+        let text = "# Payments team\nhttps://github.com/org/payments.git\n\n  ../services/ledger  # local checkout\n";
+        assert_eq!(parse_repo_list(text), ["https://github.com/org/payments.git", "../services/ledger"]);
+    }
+
+    #[test]
+    fn test_read_result() {
+        let report = json!({ "crates": [], "workspace": { "score": 72.5, "grade": "C", "crates": 3 } });
+        let summary = json!({
+            "passed": true,
+            "crates": 3,
+            "risk": { "low": 1, "medium": 1, "high": 1, "not_appraised": 0 },
+            "worst": { "name": "leftpad", "version": "1.0.0", "score": 20.0 },
+        });
+
+        let result = read_result(&report, &summary);
+        assert_eq!(result.score, Some(72.5));
+        assert_eq!(result.grade.as_deref(), Some("C"));
+        assert_eq!(result.crates, 3);
+        assert_eq!(result.high_risk, 1);
+        assert_eq!(result.worst, Some(("leftpad".into(), 20.0)));
+
+        let result = read_result(&json!({ "crates": [] }), &json!({ "crates": 0, "worst": null }));
+        assert!(result.score.is_none());
+        assert!(result.worst.is_none());
+    }
+
+    #[test]
+    fn test_rank_and_render() {
+        let failed = RepoResult {
+            name: "broken".into(),
+            error: Some("no Cargo.toml".into()),
+            ..RepoResult::default()
+        };
+        let mut results = vec![
+            failed,
+            make_result("payments", Some(80.0), 20.0),
+            make_result("search", None, 30.0),
+            make_result("billing<x>", Some(55.0), 10.0),
+        ];
+
+        rank(&mut results);
+        let names: Vec<_> = results.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["search", "billing<x>", "payments", "broken"]);
+
+        let html = render_index(&results);
+        assert!(html.contains("<a href=\"billing&lt;x&gt;.html\">billing&lt;x&gt;</a>"));
+        assert!(html.contains("class=\"failed\">no Cargo.toml"));
+
+        let console = render_console(&results);
+        assert!(console.starts_with("  1. search"));
+        assert!(console.contains("  4. broken      FAILED: no Cargo.toml"));
+    }
+}
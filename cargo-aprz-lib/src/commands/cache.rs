@@ -0,0 +1,236 @@
+//! Moving the cache between machines as a single bundle file.
+//!
+//! A bundle is a zstd-compressed tarball holding the crates.io tables along with the cached hosting and
+//! advisory data, so a machine without network access can appraise crates using data gathered elsewhere.
+
+use super::Host;
+use super::common::resolve_cache_dir;
+use super::config::Config;
+use crate::Result;
+use crate::facts::cache_lock::acquire_cache_lock;
+use camino::{Utf8Path, Utf8PathBuf};
+use clap::{Parser, Subcommand};
+use ohno::{IntoAppError, bail};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+
+/// The cache subdirectories that go into a bundle.
+const BUNDLED_DIRS: [&str; 3] = ["crates", "hosting", "advisories"];
+
+/// Where a bundle is unpacked before it replaces the cache content.
+const STAGING_DIR: &str = "import.staging";
+
+#[derive(Parser, Debug)]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    pub command: CacheCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CacheCommand {
+    /// Package the cached crates.io tables, hosting data, and advisories into a bundle file
+    Export(CacheBundleArgs),
+    /// Replace the cached crates.io tables, hosting data, and advisories with those of a bundle file
+    Import(CacheBundleArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct CacheBundleArgs {
+    /// Path to the bundle file (conventionally ending in `.tar.zst`)
+    #[arg(value_name = "PATH")]
+    pub bundle: Utf8PathBuf,
+
+    /// Path to configuration file, consulted for the `cache.dir` setting
+    #[arg(long, short = 'c', value_name = "PATH")]
+    pub config: Option<Utf8PathBuf>,
+
+    /// Directory where crate facts are cached (default is the `cache.dir` configuration setting, or the platform's cache directory)
+    #[arg(long, value_name = "PATH", env = "CARGO_APRZ_CACHE_DIR")]
+    pub cache_dir: Option<Utf8PathBuf>,
+}
+
+pub async fn manage_cache<H: Host>(host: &mut H, args: &CacheArgs) -> Result<()> {
+    match &args.command {
+        CacheCommand::Export(bundle_args) => {
+            let cache_dir = bundle_cache_dir(bundle_args)?;
+            let _lock = acquire_cache_lock(&cache_dir).await?;
+            let dirs = export_bundle(&cache_dir, &bundle_args.bundle)?;
+            let _ = writeln!(host.output(), "Exported {} from '{}' to '{}'", dirs.join(", "), cache_dir.display(), bundle_args.bundle);
+        }
+
+        CacheCommand::Import(bundle_args) => {
+            let cache_dir = bundle_cache_dir(bundle_args)?;
+            fs::create_dir_all(&cache_dir).into_app_err_with(|| format!("creating cache directory '{}'", cache_dir.display()))?;
+            let _lock = acquire_cache_lock(&cache_dir).await?;
+            let dirs = import_bundle(&cache_dir, &bundle_args.bundle)?;
+            let _ = writeln!(host.output(), "Imported {} from '{}' into '{}'", dirs.join(", "), bundle_args.bundle, cache_dir.display());
+        }
+    }
+
+    Ok(())
+}
+
+fn bundle_cache_dir(args: &CacheBundleArgs) -> Result<PathBuf> {
+    let config = match &args.config {
+        Some(path) => Config::load(path.parent().unwrap_or_else(|| Utf8Path::new(".")), Some(path))?,
+        None => Config::default(),
+    };
+
+    resolve_cache_dir(args.cache_dir.as_ref(), &config)
+}
+
+/// Write the bundled cache subdirectories present in `cache_dir` to a bundle, returning their names.
+fn export_bundle(cache_dir: &Path, bundle: &Utf8Path) -> Result<Vec<&'static str>> {
+    let dirs: Vec<_> = BUNDLED_DIRS.into_iter().filter(|dir| cache_dir.join(dir).is_dir()).collect();
+    if dirs.is_empty() {
+        bail!("there is no cached data to export in '{}'", cache_dir.display());
+    }
+
+    let file = File::create(bundle).into_app_err_with(|| format!("creating bundle file '{bundle}'"))?;
+    let encoder = zstd::Encoder::new(file, 0).into_app_err("creating zstd encoder")?;
+    let mut builder = tar::Builder::new(encoder);
+
+    for dir in &dirs {
+        builder
+            .append_dir_all(dir, cache_dir.join(dir))
+            .into_app_err_with(|| format!("adding '{dir}' to bundle file '{bundle}'"))?;
+    }
+
+    builder
+        .into_inner()
+        .and_then(zstd::Encoder::finish)
+        .and_then(|file| file.sync_all())
+        .into_app_err_with(|| format!("writing bundle file '{bundle}'"))?;
+
+    Ok(dirs)
+}
+
+/// Unpack a bundle into `cache_dir`, replacing the cache subdirectories it contains and returning their names.
+///
+/// The bundle is fully unpacked to a staging directory first, so a damaged bundle leaves the cache untouched.
+fn import_bundle(cache_dir: &Path, bundle: &Utf8Path) -> Result<Vec<&'static str>> {
+    let staging = cache_dir.join(STAGING_DIR);
+    if staging.exists() {
+        fs::remove_dir_all(&staging).into_app_err_with(|| format!("removing leftover staging directory '{}'", staging.display()))?;
+    }
+    fs::create_dir_all(&staging).into_app_err_with(|| format!("creating staging directory '{}'", staging.display()))?;
+
+    let result = unpack_bundle(&staging, bundle).and_then(|dirs| {
+        for dir in &dirs {
+            let target = cache_dir.join(dir);
+            if target.exists() {
+                fs::remove_dir_all(&target).into_app_err_with(|| format!("removing cache directory '{}'", target.display()))?;
+            }
+
+            fs::rename(staging.join(dir), &target).into_app_err_with(|| format!("moving imported data into '{}'", target.display()))?;
+        }
+
+        Ok(dirs)
+    });
+
+    let _ = fs::remove_dir_all(&staging);
+    result
+}
+
+fn unpack_bundle(staging: &Path, bundle: &Utf8Path) -> Result<Vec<&'static str>> {
+    let file = File::open(bundle).into_app_err_with(|| format!("opening bundle file '{bundle}'"))?;
+    let decoder = zstd::Decoder::new(file).into_app_err("creating zstd decoder")?;
+    let mut archive = tar::Archive::new(decoder);
+
+    let mut dirs = Vec::new();
+    for entry in archive.entries().into_app_err_with(|| format!("reading bundle file '{bundle}'"))? {
+        let mut entry = entry.into_app_err_with(|| format!("reading bundle file '{bundle}'"))?;
+        let path = entry.path().into_app_err("reading bundle entry path")?.into_owned();
+
+        let Some(Component::Normal(first)) = path.components().next() else {
+            bail!("bundle file '{bundle}' contains the unexpected entry '{}'", path.display());
+        };
+
+        let Some(dir) = BUNDLED_DIRS.into_iter().find(|dir| first.to_str() == Some(*dir)) else {
+            bail!("bundle file '{bundle}' contains the unexpected entry '{}'", path.display());
+        };
+
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+
+        let _ = entry
+            .unpack_in(staging)
+            .into_app_err_with(|| format!("unpacking '{}' from bundle file '{bundle}'", path.display()))?;
+    }
+
+    if dirs.is_empty() {
+        bail!("bundle file '{bundle}' contains no cached data");
+    }
+
+    Ok(dirs)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+
+    fn write_file(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_export_import_roundtrip() {
+        let tmp = tempfile::tempdir().unwrap();
+        let source = tmp.path().join("source");
+        write_file(&source.join("crates/tables/crates.bin"), "tables");
+        write_file(&source.join("advisories/last_synced.json"), "synced");
+        write_file(&source.join("packages/foo.crate"), "not bundled");
+
+        let bundle = Utf8PathBuf::from_path_buf(tmp.path().join("bundle.tar.zst")).unwrap();
+        assert_eq!(export_bundle(&source, &bundle).unwrap(), ["crates", "advisories"]);
+
+        let target = tmp.path().join("target");
+        write_file(&target.join("crates/stale.bin"), "stale");
+        write_file(&target.join("hosting/kept.json"), "kept");
+
+        assert_eq!(import_bundle(&target, &bundle).unwrap(), ["crates", "advisories"]);
+        assert_eq!(fs::read_to_string(target.join("crates/tables/crates.bin")).unwrap(), "tables");
+        assert_eq!(fs::read_to_string(target.join("advisories/last_synced.json")).unwrap(), "synced");
+        assert_eq!(fs::read_to_string(target.join("hosting/kept.json")).unwrap(), "kept");
+        assert!(!target.join("crates/stale.bin").exists());
+        assert!(!target.join("packages").exists());
+        assert!(!target.join(STAGING_DIR).exists());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_export_empty_cache() {
+        let tmp = tempfile::tempdir().unwrap();
+        let bundle = Utf8PathBuf::from_path_buf(tmp.path().join("bundle.tar.zst")).unwrap();
+        let err = export_bundle(tmp.path(), &bundle).unwrap_err();
+        assert!(err.to_string().contains("no cached data"));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_import_rejects_unexpected_entries() {
+        let tmp = tempfile::tempdir().unwrap();
+        let bundle = Utf8PathBuf::from_path_buf(tmp.path().join("bundle.tar.zst")).unwrap();
+
+        let encoder = zstd::Encoder::new(File::create(&bundle).unwrap(), 0).unwrap();
+        let mut builder = tar::Builder::new(encoder);
+        let mut header = tar::Header::new_gnu();
+        header.set_size(4);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, "config/evil.toml", &b"evil"[..]).unwrap();
+        let _ = builder.into_inner().unwrap().finish().unwrap();
+
+        let target = tmp.path().join("target");
+        write_file(&target.join("crates/kept.bin"), "kept");
+
+        let err = import_bundle(&target, &bundle).unwrap_err();
+        assert!(err.to_string().contains("unexpected entry"));
+        assert_eq!(fs::read_to_string(target.join("crates/kept.bin")).unwrap(), "kept");
+    }
+}
@@ -0,0 +1,297 @@
+//! Suggest point values for the eval expressions from crates labeled as good or bad.
+//!
+//! Every eval expression is evaluated once against each labeled crate. A coordinate descent then adjusts
+//! the points of one expression at a time, keeping any change that better separates the scores of the good
+//! crates from those of the bad crates, until no single change helps.
+
+use super::Host;
+use super::common::{Common, CommonArgs};
+use crate::expr::{ExpressionDisposition, evaluate};
+use crate::facts::CrateRef;
+use crate::metrics::flatten;
+use crate::{HashSet, Result};
+use camino::Utf8PathBuf;
+use chrono::Local;
+use clap::Parser;
+use ohno::{IntoAppError, bail};
+use std::fs;
+use std::io::Write;
+
+/// Upper bound on the number of passes over the expressions
+const MAX_PASSES: usize = 50;
+
+#[derive(Parser, Debug)]
+pub struct CalibrateArgs {
+    /// Crates that should score well (format: `crate_name` or `crate_name@version`)
+    #[arg(long, value_name = "CRATE", num_args = 1.., required = true)]
+    pub good: Vec<CrateRef>,
+
+    /// Crates that should score poorly (format: `crate_name` or `crate_name@version`)
+    #[arg(long, value_name = "CRATE", num_args = 1.., required = true)]
+    pub bad: Vec<CrateRef>,
+
+    /// Largest number of points to assign to a single expression
+    #[arg(long, default_value_t = 10, value_name = "POINTS")]
+    pub max_points: u32,
+
+    /// Write the suggested configuration to this file instead of the console
+    #[arg(long, value_name = "PATH")]
+    pub output: Option<Utf8PathBuf>,
+
+    #[command(flatten)]
+    pub common: CommonArgs,
+}
+
+pub async fn calibrate_crates<H: Host>(host: &mut H, args: &CalibrateArgs) -> Result<()> {
+    let mut common = Common::new(host, &args.common).await?;
+    if common.config.eval.is_empty() {
+        bail!("the configuration has no eval expressions to calibrate");
+    }
+
+    let crate_refs: Vec<CrateRef> = args.good.iter().chain(&args.bad).cloned().collect();
+    let crate_facts = common.process_crates(&crate_refs, true).await?;
+    let good_names: HashSet<&str> = args.good.iter().map(CrateRef::name).collect();
+
+    let now = Local::now();
+    let mut samples = Vec::with_capacity(crate_facts.len());
+    let mut skipped = Vec::new();
+    for facts in &crate_facts {
+        if !facts.crates_data.is_found() {
+            skipped.push(facts.crate_spec.name().to_string());
+            continue;
+        }
+
+        let metrics: Vec<_> = flatten(facts).collect();
+        let appraisal = evaluate(
+            &[],
+            &common.config.eval,
+            &metrics,
+            now,
+            common.config.medium_risk_threshold,
+            common.config.low_risk_threshold,
+        );
+
+        samples.push(Sample {
+            good: good_names.contains(facts.crate_spec.name()),
+            results: appraisal
+                .expression_outcomes
+                .iter()
+                .map(|outcome| match outcome.disposition {
+                    ExpressionDisposition::True => Some(true),
+                    ExpressionDisposition::False => Some(false),
+                    ExpressionDisposition::Failed(_) => None,
+                })
+                .collect(),
+        });
+    }
+
+    let num_good = samples.iter().filter(|sample| sample.good).count();
+    let num_bad = samples.len() - num_good;
+    if num_good == 0 || num_bad == 0 {
+        bail!("calibration needs at least one good and one bad crate that can be analyzed");
+    }
+
+    let threshold = common.config.low_risk_threshold;
+    let initial: Vec<u32> = common.config.eval.iter().map(|expr| expr.points().unwrap_or(1)).collect();
+    let suggested = calibrate(&samples, &initial, args.max_points, threshold);
+    let before = Separation::measure(&samples, &initial, threshold);
+    let after = Separation::measure(&samples, &suggested, threshold);
+
+    common.config.eval = common.config.eval.iter().zip(&suggested).map(|(expr, &points)| expr.with_points(points)).collect();
+    let config_toml = toml::to_string(&common.config).into_app_err("serializing the suggested configuration")?;
+    drop(common);
+
+    let text = format!(
+        "# Suggested by `cargo aprz calibrate` from {num_good} good and {num_bad} bad crate(s)\n\
+         # Crates on the expected side of the low risk threshold ({threshold}): {} -> {} of {}\n\
+         # Average score of good crates: {:.1} -> {:.1}\n\
+         # Average score of bad crates: {:.1} -> {:.1}\n\n\
+         {config_toml}",
+        before.correct,
+        after.correct,
+        samples.len(),
+        before.good_mean,
+        after.good_mean,
+        before.bad_mean,
+        after.bad_mean,
+    );
+
+    for name in &skipped {
+        let _ = writeln!(host.error(), "Could not analyze crate '{name}', leaving it out of the calibration");
+    }
+
+    if let Some(path) = &args.output {
+        fs::write(path, text).into_app_err_with(|| format!("writing suggested configuration to {path}"))?;
+        let _ = writeln!(host.output(), "Wrote suggested configuration to {path}");
+    } else {
+        let _ = write!(host.output(), "{text}");
+    }
+
+    Ok(())
+}
+
+/// How the eval expressions evaluated against one labeled crate
+#[derive(Debug)]
+struct Sample {
+    good: bool,
+
+    /// Whether each expression was true, or `None` if it failed to evaluate
+    results: Vec<Option<bool>>,
+}
+
+impl Sample {
+    /// Score the crate would get with the given points, computed the same way as during evaluation.
+    fn score(&self, points: &[u32]) -> f64 {
+        let (awarded, available) = self
+            .results
+            .iter()
+            .zip(points)
+            .fold((0, 0), |(awarded, available), (result, &points)| match result {
+                Some(true) => (awarded + points, available + points),
+                Some(false) => (awarded, available + points),
+                None => (awarded, available),
+            });
+
+        if available > 0 {
+            f64::from(awarded) / f64::from(available) * 100.0
+        } else {
+            100.0
+        }
+    }
+}
+
+/// How well a set of points separates the good crates from the bad ones
+#[derive(Debug, Clone, Copy)]
+struct Separation {
+    /// Good crates at or above the low risk threshold, plus bad crates below it
+    correct: usize,
+    good_mean: f64,
+    bad_mean: f64,
+}
+
+impl Separation {
+    #[expect(clippy::cast_precision_loss, reason = "Sample counts are tiny")]
+    fn measure(samples: &[Sample], points: &[u32], threshold: f64) -> Self {
+        let mut correct = 0;
+        let (mut good_total, mut good_count) = (0.0, 0_usize);
+        let (mut bad_total, mut bad_count) = (0.0, 0_usize);
+
+        for sample in samples {
+            let score = sample.score(points);
+            if sample.good {
+                good_total += score;
+                good_count += 1;
+                correct += usize::from(score >= threshold);
+            } else {
+                bad_total += score;
+                bad_count += 1;
+                correct += usize::from(score < threshold);
+            }
+        }
+
+        Self {
+            correct,
+            good_mean: if good_count > 0 { good_total / good_count as f64 } else { 0.0 },
+            bad_mean: if bad_count > 0 { bad_total / bad_count as f64 } else { 0.0 },
+        }
+    }
+
+    /// Prefer classifying more crates correctly, then a wider gap between the average scores.
+    fn is_better_than(&self, other: &Self) -> bool {
+        const MIN_GAP_IMPROVEMENT: f64 = 1e-9;
+
+        self.correct > other.correct
+            || (self.correct == other.correct && self.gap() > other.gap() + MIN_GAP_IMPROVEMENT)
+    }
+
+    fn gap(&self) -> f64 {
+        self.good_mean - self.bad_mean
+    }
+}
+
+/// Search for the points that best separate the good samples from the bad ones, starting from `initial`.
+///
+/// Points only change when that strictly improves the separation, so expressions that don't help tell the
+/// classes apart keep their current value.
+fn calibrate(samples: &[Sample], initial: &[u32], max_points: u32, threshold: f64) -> Vec<u32> {
+    let mut points: Vec<u32> = initial.iter().map(|&p| p.min(max_points)).collect();
+    let mut best = Separation::measure(samples, &points, threshold);
+
+    for _ in 0..MAX_PASSES {
+        let mut improved = false;
+
+        for i in 0..points.len() {
+            let mut best_value = points[i];
+            for candidate in 0..=max_points {
+                points[i] = candidate;
+                let separation = Separation::measure(samples, &points, threshold);
+                if separation.is_better_than(&best) {
+                    best = separation;
+                    best_value = candidate;
+                    improved = true;
+                }
+            }
+
+            points[i] = best_value;
+        }
+
+        if !improved {
+            break;
+        }
+    }
+
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(good: bool, results: &[Option<bool>]) -> Sample {
+        Sample {
+            good,
+            results: results.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_sample_score() {
+        let s = sample(true, &[Some(true), Some(false), None]);
+        assert!((s.score(&[3, 1, 5]) - 75.0).abs() < f64::EPSILON);
+        assert!((s.score(&[0, 0, 5]) - 100.0).abs() < f64::EPSILON);
+        assert!(s.score(&[0, 2, 5]).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_calibrate_favors_discriminating_expression() {
+        // The first expression holds for every crate, while the second only holds for the good ones
+        let samples = [
+            sample(true, &[Some(true), Some(true)]),
+            sample(true, &[Some(true), Some(true)]),
+            sample(false, &[Some(true), Some(false)]),
+            sample(false, &[Some(true), Some(false)]),
+        ];
+
+        let initial = [1, 1];
+        assert_eq!(Separation::measure(&samples, &initial, 40.0).correct, 2);
+
+        let points = calibrate(&samples, &initial, 10, 40.0);
+        let separation = Separation::measure(&samples, &points, 40.0);
+        assert_eq!(separation.correct, 4);
+        assert_eq!(points, [0, 1]);
+        assert!((separation.good_mean - 100.0).abs() < f64::EPSILON);
+        assert!(separation.bad_mean.abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_calibrate_keeps_points_without_signal() {
+        let samples = [sample(true, &[Some(true), None]), sample(false, &[Some(true), None])];
+        assert_eq!(calibrate(&samples, &[3, 4], 10, 70.0), [3, 4]);
+    }
+
+    #[test]
+    fn test_calibrate_clamps_to_max_points() {
+        let samples = [sample(true, &[Some(true)]), sample(false, &[Some(true)])];
+        assert_eq!(calibrate(&samples, &[20], 5, 70.0), [5]);
+    }
+}
@@ -0,0 +1,292 @@
+//! Translation of a cargo-deny `deny.toml` file into equivalent high-risk expressions.
+//!
+//! Only the parts of the cargo-deny configuration that map cleanly onto collected metrics
+//! are imported: `licenses.allow`, `bans.deny`, and the `advisories` severity settings.
+//! Everything else in the file is ignored.
+
+use crate::Result;
+use crate::expr::Expression;
+use camino::Utf8Path;
+use ohno::IntoAppError;
+use semver::Version;
+use serde::Deserialize;
+use std::fs;
+
+#[derive(Debug, Default, Deserialize)]
+struct DenyConfig {
+    #[serde(default)]
+    licenses: Licenses,
+
+    #[serde(default)]
+    bans: Bans,
+
+    #[serde(default)]
+    advisories: Advisories,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Licenses {
+    #[serde(default)]
+    allow: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Bans {
+    #[serde(default)]
+    deny: Vec<BanEntry>,
+}
+
+/// A banned crate, either as a `name[@version]` spec string or as a table.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum BanEntry {
+    Spec(String),
+    Table {
+        #[serde(alias = "crate")]
+        name: String,
+        version: Option<String>,
+    },
+}
+
+impl BanEntry {
+    /// Returns the banned crate name and the exact version, if the ban is restricted to one.
+    ///
+    /// Version ranges can't be expressed against the `crate.version` metric, so bans with
+    /// anything other than an exact version apply to all versions of the crate.
+    fn name_and_version(&self) -> (&str, Option<&str>) {
+        let (name, version) = match self {
+            Self::Spec(spec) => spec.split_once('@').map_or((spec.as_str(), None), |(n, v)| (n, Some(v))),
+            Self::Table { name, version } => {
+                // Newer cargo-deny versions allow `crate = "name@version"` in tables too
+                name.split_once('@')
+                    .map_or((name.as_str(), version.as_deref()), |(n, v)| (n, Some(v)))
+            }
+        };
+
+        let version = version
+            .map(|v| v.trim().trim_start_matches('=').trim())
+            .filter(|v| Version::parse(v).is_ok());
+        (name.trim(), version)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Advisories {
+    #[serde(default = "default_deny")]
+    vulnerability: String,
+
+    #[serde(default = "default_deny")]
+    unmaintained: String,
+
+    #[serde(default = "default_warn")]
+    unsound: String,
+
+    #[serde(default = "default_warn")]
+    yanked: String,
+}
+
+impl Default for Advisories {
+    fn default() -> Self {
+        Self {
+            vulnerability: default_deny(),
+            unmaintained: default_deny(),
+            unsound: default_warn(),
+            yanked: default_warn(),
+        }
+    }
+}
+
+fn default_deny() -> String {
+    "deny".to_string()
+}
+
+fn default_warn() -> String {
+    "warn".to_string()
+}
+
+/// cargo-deny uses `deny` as well as scope names (`all`, `workspace`, `transitive`) to mean
+/// that matching advisories fail the check.
+fn is_denied(level: &str) -> bool {
+    matches!(level, "deny" | "all" | "workspace" | "transitive")
+}
+
+/// Quote a string for use as a CEL string literal.
+fn cel_string(s: &str) -> String {
+    format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// Read a `deny.toml` file and translate it into high-risk expressions.
+pub fn import(path: &Utf8Path) -> Result<Vec<Expression>> {
+    let text = fs::read_to_string(path).into_app_err_with(|| format!("reading cargo-deny configuration file '{path}'"))?;
+    let deny: DenyConfig = toml::from_str(&text).into_app_err_with(|| format!("parsing cargo-deny configuration file '{path}'"))?;
+    translate(&deny)
+}
+
+fn translate(deny: &DenyConfig) -> Result<Vec<Expression>> {
+    let mut expressions = Vec::new();
+
+    if !deny.licenses.allow.is_empty() {
+        let allowed: Vec<_> = deny.licenses.allow.iter().map(|license| cel_string(license)).collect();
+        let expr = format!("license_allowed(crate.license, [{}])", allowed.join(", "));
+        expressions.push(Expression::new(
+            "cargo-deny: Allowed License",
+            Some("Crate license is in the cargo-deny licenses.allow list."),
+            &expr,
+            None,
+        )?);
+    }
+
+    for entry in &deny.bans.deny {
+        let (name, version) = entry.name_and_version();
+        let expr = version.map_or_else(
+            || format!("crate.name != {}", cel_string(name)),
+            |v| format!("!(crate.name == {} && crate.version == {})", cel_string(name), cel_string(v)),
+        );
+        expressions.push(Expression::new(
+            &format!("cargo-deny: Banned Crate {name}"),
+            Some("Crate is listed in the cargo-deny bans.deny list."),
+            &expr,
+            None,
+        )?);
+    }
+
+    if is_denied(&deny.advisories.vulnerability) {
+        expressions.push(Expression::new(
+            "cargo-deny: No Vulnerabilities",
+            Some("Crate version has no known vulnerabilities."),
+            "advisories.version_low_severity_vulnerabilities == 0 && \
+             advisories.version_medium_severity_vulnerabilities == 0 && \
+             advisories.version_high_severity_vulnerabilities == 0 && \
+             advisories.version_critical_severity_vulnerabilities == 0",
+            None,
+        )?);
+    }
+
+    if is_denied(&deny.advisories.unmaintained) {
+        expressions.push(Expression::new(
+            "cargo-deny: Maintained",
+            Some("Crate version has no unmaintained advisories."),
+            "advisories.version_unmaintained_warnings == 0",
+            None,
+        )?);
+    }
+
+    if is_denied(&deny.advisories.unsound) {
+        expressions.push(Expression::new(
+            "cargo-deny: Sound",
+            Some("Crate version has no unsoundness advisories."),
+            "advisories.version_unsound_warnings == 0",
+            None,
+        )?);
+    }
+
+    if is_denied(&deny.advisories.yanked) {
+        expressions.push(Expression::new(
+            "cargo-deny: Not Yanked",
+            Some("Crate version has not been yanked."),
+            "!stability.yanked",
+            None,
+        )?);
+    }
+
+    Ok(expressions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn translate_str(toml: &str) -> Vec<Expression> {
+        let deny: DenyConfig = toml::from_str(toml).unwrap();
+        translate(&deny).unwrap()
+    }
+
+    fn names(expressions: &[Expression]) -> Vec<&str> {
+        expressions.iter().map(Expression::name).collect()
+    }
+
+    #[test]
+    fn test_empty_file_uses_cargo_deny_defaults() {
+        let expressions = translate_str("");
+        assert_eq!(names(&expressions), vec!["cargo-deny: No Vulnerabilities", "cargo-deny: Maintained"]);
+    }
+
+    #[test]
+    fn test_licenses_allow() {
+        let expressions = translate_str(
+            r#"
+[licenses]
+allow = ["MIT", "Apache-2.0"]
+"#,
+        );
+        assert_eq!(expressions[0].expression(), "license_allowed(crate.license, ['MIT', 'Apache-2.0'])");
+    }
+
+    #[test]
+    fn test_bans_deny_forms() {
+        let expressions = translate_str(
+            r#"
+[advisories]
+vulnerability = "warn"
+unmaintained = "none"
+
+[bans]
+deny = [
+    "openssl",
+    "old-crate@=0.1.0",
+    { name = "foo", version = "1.2.3" },
+    { crate = "bar@2.0.0" },
+    { name = "baz", version = "*" },
+    { name = "qux", version = "<1.0" },
+]
+"#,
+        );
+        let exprs: Vec<_> = expressions.iter().map(Expression::expression).collect();
+        assert_eq!(
+            exprs,
+            vec![
+                "crate.name != 'openssl'",
+                "!(crate.name == 'old-crate' && crate.version == '0.1.0')",
+                "!(crate.name == 'foo' && crate.version == '1.2.3')",
+                "!(crate.name == 'bar' && crate.version == '2.0.0')",
+                "crate.name != 'baz'",
+                "crate.name != 'qux'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_advisory_levels() {
+        let expressions = translate_str(
+            r#"
+[advisories]
+vulnerability = "deny"
+unmaintained = "workspace"
+unsound = "deny"
+yanked = "deny"
+"#,
+        );
+        assert_eq!(
+            names(&expressions),
+            vec![
+                "cargo-deny: No Vulnerabilities",
+                "cargo-deny: Maintained",
+                "cargo-deny: Sound",
+                "cargo-deny: Not Yanked"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cel_string_escapes_quotes() {
+        assert_eq!(cel_string("it's"), "'it\\'s'");
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_import_missing_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = camino::Utf8PathBuf::try_from(tmp.path().join("deny.toml")).unwrap();
+        let _ = import(&path).unwrap_err();
+    }
+}
@@ -1,24 +1,218 @@
 //! Common processing logic shared between crates and deps commands.
 
-use super::ProgressReporter;
-use super::config::Config;
-use crate::Result;
-use crate::expr::{Risk, evaluate};
-use crate::facts::{Collector, CrateFacts, CrateRef, ProviderResult};
-use crate::metrics::flatten;
-use crate::reports::ReportableCrate;
-use crate::reports::{ConsoleOutputMode, generate_console, generate_csv, generate_html, generate_json, generate_xlsx};
-use camino::Utf8PathBuf;
+use super::{JsonProgress, ProgressReporter};
+use super::alerts::evaluate_alerts;
+use super::config::{Config, ScopeHandling};
+use super::deps::DependencyType;
+use super::dependency_track::upload_bom;
+use super::interrupt::{INTERRUPTED, cancel_on_ctrl_c, parse_deadline};
+use super::notify::{send_alerts, send_notification};
+use super::policy_pack::apply_policy_packs;
+#[cfg(feature = "script_hook")]
+use super::script_hook::run_script_hook;
+use super::submit::{Submission, file_stem, git_commit, submit_report, workspace_name};
+use super::teams::team_report_path;
+use super::token_discovery::discover_token;
+#[cfg(feature = "upload")]
+use super::upload::upload_reports;
+use crate::{HashMap, HashSet, Result};
+use crate::expr::{Risk, category_scores, evaluate};
+use crate::facts::{
+    Cancellation, Collector, CrateFacts, CrateRef, CustomHost, GitHubApp, HostingSettings, LocalSources, Progress, ProviderResult,
+    ReplacedSource, SourceReplacement,
+};
+use crate::history::{RunRecord, open_store};
+use crate::metrics::{MetricCategory, flatten};
+use crate::reports::{CategoryScore, DependencyGraph, Impact, Reachability, ReportableCrate};
+use crate::reports::{
+    ConsoleLayout, ConsoleOutputMode, generate_backstage, generate_comparison_console, generate_comparison_html, generate_console,
+    generate_csv, generate_cyclonedx, generate_dependabot, generate_graph, generate_html, generate_json, generate_mermaid, generate_renovate,
+    generate_summary, generate_timeline_console, generate_timeline_json, generate_vet, generate_xlsx, read_json_scores,
+    terminal_supports_hyperlinks,
+};
+use camino::{Utf8Path, Utf8PathBuf};
 use cargo_metadata::MetadataCommand;
-use chrono::Local;
+use chrono::{DateTime, Local, Utc};
 use clap::Args;
 use clap::ValueEnum;
+use core::fmt::Write as _;
 use core::time::Duration;
 use directories::BaseDirs;
-use ohno::IntoAppError;
+use ohno::{EnrichableExt, IntoAppError, bail};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use semver::Version;
 use std::fs;
 use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Instant;
+
+/// Determine the cache directory: the provided or configured path, or the default cache directory for the platform.
+pub fn resolve_cache_dir(cache_dir: Option<&Utf8PathBuf>, config: &Config) -> Result<PathBuf> {
+    if let Some(cache_path) = cache_dir.or(config.cache.dir.as_ref()) {
+        return Ok(cache_path.as_std_path().to_path_buf());
+    }
+
+    Ok(BaseDirs::new()
+        .into_app_err("could not determine cache directory")?
+        .cache_dir()
+        .join("cargo-aprz"))
+}
+
+/// Create the progress reporter selected on the command line.
+fn progress_reporter(args: &CommonArgs) -> Result<Arc<dyn Progress>> {
+    let delay = if args.log_level == LogLevel::None {
+        Duration::from_millis(300)
+    } else {
+        Duration::from_hours(365 * 24)
+    };
+
+    let use_colors_for_progress = match args.color {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => {
+            use std::io::{IsTerminal, stderr};
+            stderr().is_terminal()
+        }
+    };
+
+    Ok(match args.progress {
+        ProgressMode::Bar => Arc::new(ProgressReporter::new(delay, use_colors_for_progress)),
+        ProgressMode::Json => Arc::new(JsonProgress::new(progress_output(args.progress_fd)?)),
+    })
+}
+
+/// Gather the hosting API credentials, preferring those given on the command line over the configured ones.
+fn hosting_settings(args: &CommonArgs, config: &Config) -> Result<HostingSettings> {
+    let github_app = match (args.github_app_id, &args.github_app_key) {
+        (Some(app_id), Some(key_path)) => {
+            let key = fs::read_to_string(key_path).into_app_err_with(|| format!("reading GitHub App private key '{key_path}'"))?;
+            Some(GitHubApp::new(app_id, &key, args.github_app_installation_id)?)
+        }
+        _ => None,
+    };
+
+    // Tokens given on the command line take precedence over those of the configuration, and stored tokens are
+    // only looked up when neither gives any
+    let tokens = |given: &[String], configured: &[String], host: &str| {
+        let tokens = if given.is_empty() { configured } else { given };
+        if tokens.is_empty() && args.discover_tokens {
+            discover_token(host).into_iter().collect()
+        } else {
+            tokens.to_vec()
+        }
+    };
+    let github_tokens = if github_app.is_some() {
+        Vec::new()
+    } else {
+        tokens(&args.github_token, &config.hosting.github_tokens, "github.com")
+    };
+    let codeberg_tokens = tokens(&args.codeberg_token, &config.hosting.codeberg_tokens, "codeberg.org");
+    let custom_hosts = config
+        .hosting
+        .hosts
+        .iter()
+        .map(|host| CustomHost {
+            tokens: tokens(&[], &host.tokens, &host.domain),
+            ..host.clone()
+        })
+        .collect();
+
+    Ok(HostingSettings {
+        github_tokens,
+        github_app,
+        codeberg_tokens,
+        custom_hosts,
+    })
+}
+
+/// Determine the default console output mode, and the one restricted to the sections given on the command line.
+fn console_modes(args: &CommonArgs) -> (ConsoleOutputMode, Option<ConsoleOutputMode>) {
+    let hyperlinks = {
+        use std::io::{IsTerminal, stdout};
+        !args.no_hyperlinks && stdout().is_terminal() && terminal_supports_hyperlinks()
+    };
+    let default_console = ConsoleOutputMode {
+        hyperlinks,
+        only_failures: args.only_failures,
+        explain_crates: args.explain.clone(),
+        explain_categories: args.explain_category.clone(),
+        layout: args.console_layout,
+        ..ConsoleOutputMode::full()
+    };
+    let console = args.console.as_ref().map(|sections| ConsoleOutputMode {
+        appraisal: sections.contains(&ConsoleSection::Appraisal),
+        reasons: sections.contains(&ConsoleSection::Reasons),
+        metrics: sections.contains(&ConsoleSection::Metrics),
+        ..default_console.clone()
+    });
+
+    (default_console, console)
+}
+
+/// Point the collector at the sources of crate packages: local vendored or cached copies when requested, and the
+/// registry replacing crates.io in Cargo's configuration.
+async fn with_package_sources(collector: Collector, args: &CommonArgs, workspace_root: &Utf8Path, host: &mut impl super::Host) -> Collector {
+    // Metrics always come from crates.io, as mirrors don't serve its database, but the packages of the crates can
+    // come from the source replacing it
+    let replacement = SourceReplacement::find(workspace_root.as_std_path());
+    if let Some(replacement) = &replacement {
+        let _ = writeln!(
+            host.error(),
+            "WARNING: Cargo's configuration replaces crates.io with {replacement}, so metrics describe the crates.io releases"
+        );
+    }
+
+    let collector = if args.local_sources {
+        let vendor_dir = args
+            .vendor_dir
+            .as_ref()
+            .map(|dir| dir.clone().into_std_path_buf())
+            .or_else(|| replacement.as_ref()?.vendor_dir().map(Path::to_path_buf))
+            .unwrap_or_else(|| workspace_root.join("vendor").into_std_path_buf());
+        collector.with_local_sources(LocalSources::new(Some(vendor_dir)))
+    } else {
+        collector
+    };
+
+    match &replacement {
+        Some(replacement @ SourceReplacement { source: ReplacedSource::Registry(_), .. }) if !args.cache_readonly => {
+            match replacement.download_template(&reqwest::Client::new()).await {
+                Ok(template) => collector.with_package_mirror(template),
+                Err(e) => {
+                    let _ = writeln!(host.error(), "WARNING: downloading packages from crates.io, {e:#}");
+                    collector
+                }
+            }
+        }
+        _ => collector,
+    }
+}
+
+/// Open the destination of JSON progress events: the given file descriptor, or stderr.
+fn progress_output(fd: Option<i32>) -> Result<Box<dyn Write + Send>> {
+    let Some(fd) = fd else {
+        return Ok(Box::new(std::io::stderr()));
+    };
+
+    #[cfg(unix)]
+    {
+        use std::os::fd::{FromRawFd, OwnedFd};
+
+        if fd < 0 {
+            bail!("invalid progress file descriptor {fd}");
+        }
+
+        // SAFETY: the file descriptor is handed to us by the parent process, which opened it for our use
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+        Ok(Box::new(fs::File::from(fd)))
+    }
+
+    #[cfg(not(unix))]
+    {
+        bail!("writing progress events to file descriptor {fd} is only supported on Unix")
+    }
+}
 
 /// Color mode configuration for output
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
@@ -55,6 +249,16 @@ pub enum LogLevel {
     Trace,
 }
 
+/// How progress is reported while crate information is gathered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProgressMode {
+    /// Show progress bars on the terminal
+    Bar,
+
+    /// Write a JSON object per line describing each progress event, for other tools to render
+    Json,
+}
+
 /// Individual sections that can be shown in console output
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
 pub enum ConsoleSection {
@@ -70,14 +274,48 @@ pub enum ConsoleSection {
 
 /// Common arguments shared between crates and deps commands
 #[derive(Args, Debug)]
+#[expect(clippy::struct_excessive_bools, reason = "Each flag is an independent command-line switch")]
 pub struct CommonArgs {
-    /// GitHub personal access token
-    #[arg(long, value_name = "TOKEN", env = "GITHUB_TOKEN")]
-    pub github_token: Option<String>,
+    /// GitHub personal access token. Repeat the option, or give a comma-separated list, to spread requests across
+    /// several tokens
+    #[arg(long, value_name = "TOKEN", env = "GITHUB_TOKEN", value_delimiter = ',')]
+    pub github_token: Vec<String>,
+
+    /// ID of a GitHub App to authenticate as, in place of a personal access token
+    #[arg(long, value_name = "ID", env = "GITHUB_APP_ID", requires = "github_app_key")]
+    pub github_app_id: Option<u64>,
+
+    /// Path to the PEM-encoded private key of the GitHub App
+    #[arg(long, value_name = "PATH", env = "GITHUB_APP_PRIVATE_KEY_PATH", requires = "github_app_id")]
+    pub github_app_key: Option<Utf8PathBuf>,
+
+    /// Installation of the GitHub App to use, needed when the app is installed on several accounts
+    #[arg(long, value_name = "ID", env = "GITHUB_APP_INSTALLATION_ID", requires = "github_app_id")]
+    pub github_app_installation_id: Option<u64>,
+
+    /// Codeberg personal access token. Repeat the option, or give a comma-separated list, to spread requests across
+    /// several tokens
+    #[arg(long, value_name = "TOKEN", env = "CODEBERG_TOKEN", value_delimiter = ',')]
+    pub codeberg_token: Vec<String>,
+
+    /// Look up the GitHub and Codeberg tokens not given otherwise from the GitHub CLI, the OS keyring, or git's
+    /// credential helpers
+    #[arg(long)]
+    pub discover_tokens: bool,
 
-    /// Codeberg personal access token
-    #[arg(long, value_name = "TOKEN", env = "CODEBERG_TOKEN")]
-    pub codeberg_token: Option<String>,
+    /// Analyze the crate sources already on disk, in the vendor directory or Cargo's registry cache, instead of
+    /// downloading packages, and in place of repositories that can't be cloned
+    #[arg(long)]
+    pub local_sources: bool,
+
+    /// Directory holding the sources vendored with `cargo vendor` (default is the directory Cargo's configuration replaces
+    /// crates.io with, or `vendor` in the workspace root)
+    #[arg(long, value_name = "PATH", requires = "local_sources")]
+    pub vendor_dir: Option<Utf8PathBuf>,
+
+    /// Dependency-Track API key, used when the configuration file has a `dependency_track` section
+    #[arg(long, value_name = "KEY", env = "DEPENDENCY_TRACK_API_KEY")]
+    pub dependency_track_api_key: Option<String>,
 
     /// Path to Cargo.toml file
     #[arg(long, default_value = "Cargo.toml", value_name = "PATH")]
@@ -91,10 +329,22 @@ pub struct CommonArgs {
     #[arg(long, value_name = "WHEN", default_value = "auto")]
     pub color: ColorMode,
 
-    /// Directory where crate facts are cached
-    #[arg(long, value_name = "PATH")]
+    /// Don't turn crate names and URLs in console output into clickable terminal hyperlinks
+    #[arg(long)]
+    pub no_hyperlinks: bool,
+
+    /// Directory where crate facts are cached (default is the `cache.dir` configuration setting, or the platform's cache directory)
+    #[arg(long, value_name = "PATH", env = "CARGO_APRZ_CACHE_DIR")]
     pub cache_dir: Option<Utf8PathBuf>,
 
+    /// Control how progress is reported
+    #[arg(long, value_name = "MODE", default_value = "bar")]
+    pub progress: ProgressMode,
+
+    /// File descriptor to write JSON progress events to, instead of stderr (Unix only)
+    #[arg(long, value_name = "FD")]
+    pub progress_fd: Option<i32>,
+
     /// Set the logging level for diagnostic output
     #[arg(long, value_name = "LEVEL", default_value = "none", global = true)]
     pub log_level: LogLevel,
@@ -120,6 +370,82 @@ pub struct CommonArgs {
     #[arg(long, value_name = "SECTIONS", value_delimiter = ',', default_missing_value = "appraisal,reasons,metrics", num_args = 0..=1, help_heading = "Report Output")]
     pub console: Option<Vec<ConsoleSection>>,
 
+    /// Limit console output to the crates which fail a policy expression or aren't low risk, showing only the
+    /// expressions they fail
+    #[arg(long, help_heading = "Report Output")]
+    pub only_failures: bool,
+
+    /// Limit every report to the N lowest-scoring crates, worst first
+    #[arg(long, value_name = "N", help_heading = "Report Output")]
+    pub top: Option<usize>,
+
+    /// Arrange the metrics of console output by crate, or by category across all crates
+    #[arg(long, value_name = "LAYOUT", default_value = "crate", help_heading = "Report Output")]
+    pub console_layout: ConsoleLayout,
+
+    /// Limit the reasons and metrics shown in console output to the specified crates
+    #[arg(long, value_name = "CRATES", value_delimiter = ',', help_heading = "Report Output")]
+    pub explain: Vec<String>,
+
+    /// Limit the metrics shown in console output to the specified categories
+    #[arg(long, value_name = "CATEGORIES", value_delimiter = ',', ignore_case = true, help_heading = "Report Output")]
+    pub explain_category: Vec<MetricCategory>,
+
+    /// Output a `CycloneDX` SBOM including appraisal results to a JSON file
+    #[arg(long, value_name = "PATH", help_heading = "Report Output")]
+    pub cyclonedx: Option<Utf8PathBuf>,
+
+    /// Output a Backstage catalog-info annotations fragment summarizing dependency health to a YAML file
+    #[arg(long, value_name = "PATH", help_heading = "Report Output")]
+    pub backstage: Option<Utf8PathBuf>,
+
+    /// Output a small JSON summary of the run (overall outcome, crates per risk, worst crate, duration) for scripts
+    #[arg(long, value_name = "PATH", help_heading = "Report Output")]
+    pub summary_json: Option<Utf8PathBuf>,
+
+    /// Output Mermaid diagrams summarizing dependency risk to a Markdown file, including a flowchart of the
+    /// dependency graph when appraising workspace dependencies
+    #[arg(long, value_name = "PATH", help_heading = "Report Output")]
+    pub mermaid: Option<Utf8PathBuf>,
+
+    /// Output a Renovate configuration fragment prioritizing updates for risky crates to a JSON file
+    #[arg(long, value_name = "PATH", help_heading = "Report Output")]
+    pub renovate: Option<Utf8PathBuf>,
+
+    /// Output a Dependabot configuration fragment grouping updates for risky crates to a YAML file
+    #[arg(long, value_name = "PATH", help_heading = "Report Output")]
+    pub dependabot: Option<Utf8PathBuf>,
+
+    /// Output cargo-vet exemption entries for crates meeting the minimum score to a TOML file
+    #[arg(long, value_name = "PATH", help_heading = "Report Output")]
+    pub vet_exemptions: Option<Utf8PathBuf>,
+
+    /// Minimum appraisal score for a crate to be included in the cargo-vet exemptions (default is `low_risk_threshold`)
+    #[arg(long, value_name = "SCORE", help_heading = "Report Output")]
+    pub vet_min_score: Option<f64>,
+
+    /// cargo-vet criteria to assign to exempted crates
+    #[arg(long, value_name = "CRITERIA", default_value = "safe-to-deploy", help_heading = "Report Output")]
+    pub vet_criteria: String,
+
+    /// Previous JSON report to compare scores against, annotating each crate with its score change
+    #[arg(long, value_name = "PATH", help_heading = "Report Output")]
+    pub baseline: Option<Utf8PathBuf>,
+
+    /// Directory to write, for each crate, the crates.io rows, cached documents, and advisories its scores were derived from
+    #[arg(long, value_name = "PATH", help_heading = "Report Output")]
+    pub evidence_dir: Option<Utf8PathBuf>,
+
+    /// Upload generated report files to object storage (`s3://`, `gs://`, or `az://` URL).
+    /// The `{date}` and `{timestamp}` placeholders are replaced with the time of the run. Requires the `upload` feature
+    #[arg(long, value_name = "URL", help_heading = "Report Output")]
+    pub upload: Option<String>,
+
+    /// Post the JSON report, along with the workspace name and git commit, to a collector endpoint such as
+    /// the one provided by `cargo aprz serve`
+    #[arg(long, value_name = "URL", help_heading = "Report Output")]
+    pub report_to: Option<String>,
+
     /// Exit with status code 1 if any crate is appraised as high risk
     #[arg(long)]
     pub error_if_high_risk: bool,
@@ -131,21 +457,82 @@ pub struct CommonArgs {
     /// Ignore cached data and fetch everything fresh
     #[arg(long)]
     pub ignore_cached: bool,
+
+    /// Use the cache as is without ever writing or refreshing it, reporting any data served stale
+    #[arg(long, conflicts_with = "ignore_cached")]
+    pub cache_readonly: bool,
+
+    /// Stop gathering crate information once the run has lasted this long, such as `10m`, and report on the crates
+    /// analyzed by then. Crates left out are marked as not analyzed, and the run fails
+    #[arg(long, value_name = "DURATION", value_parser = parse_deadline)]
+    pub deadline: Option<Duration>,
 }
 
 pub struct Common<'a, H: super::Host> {
     pub collector: Collector,
+
+    /// Shared with the blocking task appraising the crates
     pub config: Config,
     pub metadata_cmd: MetadataCommand,
+
+    /// Crates depended upon by each team, used to write per-team copies of the file-based reports
+    pub team_crates: Vec<(String, HashSet<CrateRef>)>,
+
+    /// How much each crate counts towards the workspace score, empty unless appraising workspace dependencies
+    pub crate_weights: HashMap<CrateRef, f64>,
+
+    /// Dependency types through which each crate is reached, empty unless appraising workspace dependencies
+    pub crate_dependency_types: HashMap<CrateRef, Vec<DependencyType>>,
+
+    /// How much of the dependency graph relies on each crate, empty unless a risk matrix is requested
+    pub crate_impacts: HashMap<CrateRef, Impact>,
+
+    /// Crates overridden by a `[patch]` or `[replace]` section, along with the source they're replaced by
+    pub overridden_crates: Vec<(CrateRef, String)>,
+
+    /// The dependency graph of the appraised crates, unset unless a report drawing it is requested
+    pub dependency_graph: Option<DependencyGraph>,
+
+    /// Where to write the dependency graph as a `GraphViz` DOT file
+    pub graph: Option<Utf8PathBuf>,
+
+    /// The MSRV declared by the workspace whose dependencies are appraised, if any
+    workspace_msrv: Option<Arc<str>>,
     host: &'a mut H,
     color: ColorMode,
+    default_console: ConsoleOutputMode,
     error_if_high_risk: bool,
     error_if_medium_risk: bool,
     console: Option<ConsoleOutputMode>,
+    top: Option<usize>,
     html: Option<Utf8PathBuf>,
     excel: Option<Utf8PathBuf>,
     csv: Option<Utf8PathBuf>,
     json: Option<Utf8PathBuf>,
+    cyclonedx: Option<Utf8PathBuf>,
+    dependency_track_api_key: Option<String>,
+    backstage: Option<Utf8PathBuf>,
+    mermaid: Option<Utf8PathBuf>,
+    summary_json: Option<Utf8PathBuf>,
+    renovate: Option<Utf8PathBuf>,
+    dependabot: Option<Utf8PathBuf>,
+    vet_exemptions: Option<Utf8PathBuf>,
+    vet_min_score: Option<f64>,
+    vet_criteria: String,
+    baseline: Option<Utf8PathBuf>,
+    evidence_dir: Option<Utf8PathBuf>,
+    #[cfg(feature = "upload")]
+    upload: Option<String>,
+    report_to: Option<String>,
+    workspace_root: Utf8PathBuf,
+
+    /// Where the caches live, along with the state kept between runs
+    cache_dir: PathBuf,
+    started: Instant,
+    deadline: Option<Duration>,
+
+    /// Cancelled on Ctrl-C or once past the deadline, cutting the collection short
+    cancellation: Cancellation,
 }
 
 impl<'a, H: super::Host> Common<'a, H> {
@@ -157,6 +544,11 @@ impl<'a, H: super::Host> Common<'a, H> {
     pub async fn new(host: &'a mut H, args: &CommonArgs) -> Result<Self> {
         Self::init_logging(args.log_level);
 
+        #[cfg(not(feature = "upload"))]
+        if let Some(destination) = &args.upload {
+            bail!("cannot upload reports to '{destination}', cargo-aprz was built without the 'upload' feature");
+        }
+
         // Create metadata command for workspace operations
         let mut metadata_cmd = MetadataCommand::new();
         let _ = metadata_cmd.manifest_path(&args.manifest_path);
@@ -164,79 +556,139 @@ impl<'a, H: super::Host> Common<'a, H> {
         // Execute metadata command once and use it for both cache and config paths
         let metadata = metadata_cmd.exec().into_app_err("retrieving workspace metadata")?;
 
-        // Use workspace_root for config base path
-        let config_base_path = metadata.workspace_root;
+        // The workspace root is the base path for the config, and identifies the workspace to collector endpoints
+        let workspace_root = metadata.workspace_root;
 
         // Load config from the determined base path first (we need the cache TTL)
-        let config = Config::load(&config_base_path, args.config.as_ref())?;
+        let mut config = Config::load(&workspace_root, args.config.as_ref())?;
+        apply_policy_packs(&mut config).await?;
 
-        // Determine cache directory: use provided path or default cache directory for the platform
-        let cache_dir = if let Some(cache_path) = &args.cache_dir {
-            cache_path.as_std_path().to_path_buf()
-        } else {
-            BaseDirs::new()
-                .into_app_err("could not determine cache directory")?
-                .cache_dir()
-                .join("cargo-aprz")
-        };
+        // Determine cache directory: use the provided or configured path, or the default cache directory for the platform
+        let cache_dir = resolve_cache_dir(args.cache_dir.as_ref(), &config)?;
 
-        let delay = if args.log_level == LogLevel::None {
-            Duration::from_millis(300)
-        } else {
-            Duration::from_hours(365 * 24)
-        };
+        let progress = progress_reporter(args)?;
 
-        let use_colors_for_progress = match args.color {
-            ColorMode::Always => true,
-            ColorMode::Never => false,
-            ColorMode::Auto => {
-                use std::io::{IsTerminal, stderr};
-                stderr().is_terminal()
-            }
-        };
-
-        let progress_reporter = ProgressReporter::new(delay, use_colors_for_progress);
+        let hosting = hosting_settings(args, &config)?;
 
         let collector = Collector::new(
-            args.github_token.as_deref(),
-            args.codeberg_token.as_deref(),
+            hosting,
             &cache_dir,
-            config.crates_cache_ttl,
-            config.hosting_cache_ttl,
-            config.codebase_cache_ttl,
-            config.coverage_cache_ttl,
-            config.advisories_cache_ttl,
+            config.cache.ttl.crates,
+            config.cache.ttl.hosting,
+            config.cache.ttl.codebase,
+            config.cache.ttl.coverage,
+            config.cache.ttl.advisories,
+            config.cache.ttl.docs,
             args.ignore_cached,
-            progress_reporter,
+            args.cache_readonly,
+            progress,
         )
         .await?;
 
+        let collector = with_package_sources(collector, args, &workspace_root, host).await;
+
+        let cancellation = Cancellation::new();
+        let collector = collector.with_cancellation(cancellation.clone());
+
         // Create a fresh metadata command for the caller to use
         let mut metadata_cmd = MetadataCommand::new();
         let _ = metadata_cmd.manifest_path(&args.manifest_path);
 
-        let console = args.console.as_ref().map(|sections| ConsoleOutputMode {
-            appraisal: sections.contains(&ConsoleSection::Appraisal),
-            reasons: sections.contains(&ConsoleSection::Reasons),
-            metrics: sections.contains(&ConsoleSection::Metrics),
-        });
+        let (default_console, console) = console_modes(args);
 
         Ok(Self {
             collector,
             config,
             metadata_cmd,
+            team_crates: Vec::new(),
+            crate_weights: HashMap::default(),
+            crate_dependency_types: HashMap::default(),
+            crate_impacts: HashMap::default(),
+            overridden_crates: Vec::new(),
+            dependency_graph: None,
+            graph: None,
+            workspace_msrv: None,
             host,
             color: args.color,
+            default_console,
             error_if_high_risk: args.error_if_high_risk,
             error_if_medium_risk: args.error_if_medium_risk,
             console,
+            top: args.top,
             html: args.html.clone(),
             excel: args.excel.clone(),
             csv: args.csv.clone(),
             json: args.json.clone(),
+            cyclonedx: args.cyclonedx.clone(),
+            dependency_track_api_key: args.dependency_track_api_key.clone(),
+            backstage: args.backstage.clone(),
+            mermaid: args.mermaid.clone(),
+            summary_json: args.summary_json.clone(),
+            renovate: args.renovate.clone(),
+            dependabot: args.dependabot.clone(),
+            vet_exemptions: args.vet_exemptions.clone(),
+            vet_min_score: args.vet_min_score,
+            vet_criteria: args.vet_criteria.clone(),
+            baseline: args.baseline.clone(),
+            evidence_dir: args.evidence_dir.clone(),
+            #[cfg(feature = "upload")]
+            upload: args.upload.clone(),
+            report_to: args.report_to.clone(),
+            workspace_root,
+            cache_dir,
+            started: Instant::now(),
+            deadline: args.deadline,
+            cancellation,
         })
     }
 
+    /// Write a copy of each requested HTML, Excel, CSV, and JSON report for every team,
+    /// containing only the crates that team depends on. Returns the paths of the files written.
+    fn write_team_reports(&self, reportable_crates: &[ReportableCrate]) -> Result<Vec<Utf8PathBuf>> {
+        let mut written = Vec::new();
+
+        for (team, crate_refs) in &self.team_crates {
+            let team_crates: Vec<_> = reportable_crates
+                .iter()
+                .filter(|c| crate_refs.contains(&CrateRef::new(&c.name, Some(Version::clone(&c.version)))))
+                .cloned()
+                .collect();
+
+            if let Some(filename) = &self.html {
+                let path = team_report_path(filename, team);
+                let mut html = String::new();
+                generate_html(&team_crates, Local::now(), &mut html)?;
+                fs::write(&path, html)?;
+                written.push(path);
+            }
+
+            if let Some(filename) = &self.excel {
+                let path = team_report_path(filename, team);
+                let mut file = fs::File::create(&path)?;
+                generate_xlsx(&team_crates, &mut file)?;
+                written.push(path);
+            }
+
+            if let Some(filename) = &self.csv {
+                let path = team_report_path(filename, team);
+                let mut csv_output = String::new();
+                generate_csv(&team_crates, &mut csv_output)?;
+                fs::write(&path, csv_output)?;
+                written.push(path);
+            }
+
+            if let Some(filename) = &self.json {
+                let path = team_report_path(filename, team);
+                let mut json_output = String::new();
+                generate_json(&team_crates, &[], &mut json_output)?;
+                fs::write(&path, json_output)?;
+                written.push(path);
+            }
+        }
+
+        Ok(written)
+    }
+
     /// Initialize logger based on log level
     fn init_logging(log_level: LogLevel) {
         let level = match log_level {
@@ -250,15 +702,66 @@ impl<'a, H: super::Host> Common<'a, H> {
 
         let env = env_logger::Env::default().filter_or("RUST_LOG", level);
 
-        env_logger::Builder::from_env(env)
+        // The daemon appraises workspaces repeatedly, so logging may already be set up
+        let _ = env_logger::Builder::from_env(env)
             .format_timestamp(None)
             .format_module_path(false)
             .format_target(matches!(log_level, LogLevel::Debug | LogLevel::Trace))
-            .init();
+            .try_init();
+    }
+
+    /// Compare the MSRV of the appraised crates with the one declared by the workspace, enforcing the
+    /// `msrv_check` policy when one is configured.
+    pub fn check_msrv(&mut self, workspace_msrv: Option<&Version>) -> Result<()> {
+        let Some(msrv) = workspace_msrv else {
+            if self.config.msrv_check.is_some() {
+                let _ = writeln!(self.host.error(), "WARNING: skipping the MSRV check as the workspace doesn't declare a rust-version");
+            }
+            return Ok(());
+        };
+
+        let msrv = msrv.to_string();
+        if let Some(policy) = &self.config.msrv_check {
+            let expression = policy.to_expression(&msrv)?;
+            let points = policy.points;
+            if points.is_some() {
+                self.config.eval.push(expression);
+            } else {
+                self.config.high_risk.push(expression);
+            }
+        }
+
+        self.workspace_msrv = Some(msrv.into());
+        Ok(())
     }
 
-    pub async fn process_crates(&self, crates: &[CrateRef], suggestions: bool) -> Result<Vec<CrateFacts>> {
-        let results = self.collector.collect(crates, suggestions).await;
+    /// Gather the facts of the given crates, until interrupted or past the deadline.
+    pub async fn process_crates(&mut self, crates: &[CrateRef], suggestions: bool) -> Result<Vec<CrateFacts>> {
+        let _interrupt = cancel_on_ctrl_c(&self.cancellation);
+        let deadline = self
+            .deadline
+            .map(|deadline| self.cancellation.cancel_after(deadline.saturating_sub(self.started.elapsed()), "stopped at the deadline"));
+
+        // Tell the user as soon as the run is cancelled, as the requests under way can take a while to finish
+        let collect = self.collector.collect(crates, suggestions);
+        tokio::pin!(collect);
+        let results = tokio::select! {
+            results = &mut collect => results,
+            () = self.cancellation.cancelled() => {
+                let reason = self.cancellation.reason().unwrap_or_default();
+                let _ = writeln!(
+                    self.host.error(),
+                    "\nRun {reason}, reporting on the crates analyzed once the requests under way finish"
+                );
+                if reason == INTERRUPTED {
+                    let _ = writeln!(self.host.error(), "Press Ctrl-C again to quit");
+                }
+                collect.await
+            }
+        };
+        if let Some(deadline) = deadline {
+            deadline.abort();
+        }
 
         match results {
             Ok(facts_iter) => Ok(facts_iter.collect()),
@@ -269,8 +772,78 @@ impl<'a, H: super::Host> Common<'a, H> {
         }
     }
 
-    #[expect(clippy::too_many_lines, reason = "Function handles multiple report formats and evaluation logic")]
-    pub fn report(&mut self, processed_crates: impl IntoIterator<Item = CrateFacts>) -> Result<()> {
+    /// Log the data served past its TTL from a read-only cache.
+    fn report_stale_cache_entries(&mut self) {
+        let stale = self.collector.stale_cache_entries();
+        if stale.is_empty() {
+            return;
+        }
+
+        let _ = writeln!(self.host.error(), "\nServed {} stale cache entry(ies) from the read-only cache", stale.len());
+        for entry in &stale {
+            let _ = writeln!(self.host.error(), "  {entry}");
+        }
+    }
+
+    /// Warn that the crates overridden by the workspace are appraised using their crates.io release.
+    fn report_overridden_crates(&mut self) {
+        if self.overridden_crates.is_empty() {
+            return;
+        }
+
+        let _ = writeln!(
+            self.host.error(),
+            "WARNING: {} crate(s) are overridden by [patch] or [replace], so their appraisal reflects the crates.io release:",
+            self.overridden_crates.len()
+        );
+        for (crate_ref, source) in &self.overridden_crates {
+            let _ = writeln!(self.host.error(), "  {crate_ref} -> {source}");
+        }
+    }
+
+    /// Log how many of each provider's cache lookups were served from the cache rather than the network.
+    fn report_cache_stats(&mut self) {
+        let stats = self.collector.cache_stats();
+        if stats.is_empty() {
+            return;
+        }
+
+        let parts: Vec<_> = stats
+            .iter()
+            .map(|(name, stats)| format!("{name} {}/{}", stats.hits, stats.lookups()))
+            .collect();
+        let _ = writeln!(self.host.error(), "Cache hits: {}", parts.join(", "));
+    }
+
+    /// Log how much of the API quota of each hosting service the run used.
+    fn report_api_usage(&mut self) {
+        for usage in self.collector.api_usage() {
+            let mut line = format!("{} API: {} request(s)", usage.host, usage.requests);
+            if usage.rate_limited > 0 {
+                let _ = write!(line, ", rate limited {} time(s)", usage.rate_limited);
+            }
+
+            if let Some(remaining) = usage.remaining {
+                let _ = write!(line, ", {remaining} remaining");
+            }
+
+            if let Some(reset_at) = usage.reset_at {
+                let _ = write!(line, ", quota resets at {}", reset_at.with_timezone(&Local).format("%T"));
+            }
+
+            let _ = writeln!(self.host.error(), "{line}");
+        }
+    }
+
+    /// Log the crates that couldn't be analyzed, then flatten the others into metrics, evaluate them if needed,
+    /// and attach the scores from the baseline report.
+    #[expect(clippy::too_many_lines, reason = "Function reports every kind of analysis failure before building the crates")]
+    async fn prepare_reportable_crates(&mut self, processed_crates: impl IntoIterator<Item = CrateFacts>) -> Result<Vec<ReportableCrate>> {
+        self.report_stale_cache_entries();
+        self.report_overridden_crates();
+        self.report_cache_stats();
+        self.report_api_usage();
+
         // Filter out crates with missing core data (can't be reported)
         let (analyzable_crates, failed_crates): (Vec<_>, Vec<_>) =
             processed_crates.into_iter().partition(|facts| facts.crates_data.is_found());
@@ -320,114 +893,500 @@ impl<'a, H: super::Host> Common<'a, H> {
                             facts.crate_spec
                         );
                     }
-                    ProviderResult::Found(_) | ProviderResult::Unavailable(_) => {}
+                    ProviderResult::Found(_) | ProviderResult::Unavailable(_) | ProviderResult::Cancelled => {}
                 }
             }
         }
 
+        if let Some(dir) = &self.evidence_dir {
+            for facts in &analyzable_crates {
+                let _ = self
+                    .collector
+                    .write_evidence(facts, dir.as_std_path())
+                    .map_err(|e| e.enrich_with(|| format!("writing the evidence of crate '{}'", facts.crate_spec)))?;
+            }
+            let _ = writeln!(self.host.error(), "Wrote evidence for {} crate(s) to '{dir}'", analyzable_crates.len());
+        }
+
         // Flatten crate facts into metrics and optionally evaluate, creating ReportableCrate instances
         let has_expressions =
             !self.config.high_risk.is_empty() || !self.config.eval.is_empty();
-        let should_eval = has_expressions || self.error_if_high_risk || self.error_if_medium_risk;
-
-        let mut reportable_crates: Vec<ReportableCrate> = if should_eval {
-            analyzable_crates
-                .into_iter()
-                .map(|facts| {
-                    let metrics: Vec<_> = flatten(&facts).collect();
-                    let evaluation = evaluate(
-                        &self.config.high_risk,
-                        &self.config.eval,
-                        &metrics,
-                        Local::now(),
-                        self.config.medium_risk_threshold,
-                        self.config.low_risk_threshold,
-                    );
-
-                    ReportableCrate::new(
-                        Arc::clone(facts.crate_spec.name_arc()),
-                        Arc::clone(facts.crate_spec.version_arc()),
-                        metrics,
-                        Some(evaluation),
-                    )
-                })
-                .collect()
-        } else {
-            analyzable_crates
-                .into_iter()
-                .map(|facts| {
-                    let metrics: Vec<_> = flatten(&facts).collect();
-                    ReportableCrate::new(
-                        Arc::clone(facts.crate_spec.name_arc()),
-                        Arc::clone(facts.crate_spec.version_arc()),
-                        metrics,
-                        None,
-                    )
-                })
-                .collect()
-        };
+        let should_eval = has_expressions
+            || self.error_if_high_risk
+            || self.error_if_medium_risk
+            || self.vet_exemptions.is_some()
+            || self.backstage.is_some()
+            || self.mermaid.is_some()
+            || self.renovate.is_some()
+            || self.dependabot.is_some()
+            || self.summary_json.is_some()
+            || self.report_to.is_some()
+            || self.top.is_some();
+
+        // Flattening and appraising are CPU-bound once the facts are cached, so crates are processed in parallel, off
+        // the async runtime's workers. All crates are appraised as of the same time, and the results keep the order of
+        // the crates. The task borrows the configuration for its duration and hands it back
+        let now = Local::now();
+        let config = core::mem::take(&mut self.config);
+        let (config, mut reportable_crates) = tokio::task::spawn_blocking(move || {
+            let reportable_crates: Vec<ReportableCrate> = analyzable_crates
+                .into_par_iter()
+                .map(|facts| reportable_crate(&config, &facts, should_eval, now))
+                .collect();
+            (config, reportable_crates)
+        })
+        .await
+        .into_app_err("appraising crates")?;
+        self.config = config;
+
+        if !self.crate_weights.is_empty() || !self.crate_impacts.is_empty() {
+            for crate_info in &mut reportable_crates {
+                let crate_ref = CrateRef::new(&crate_info.name, Some(Version::clone(&crate_info.version)));
+                crate_info.weight = self.crate_weights.get(&crate_ref).copied();
+                crate_info.impact = self.crate_impacts.get(&crate_ref).copied();
+            }
+        }
+
+        if !self.crate_dependency_types.is_empty() {
+            for crate_info in &mut reportable_crates {
+                let crate_ref = CrateRef::new(&crate_info.name, Some(Version::clone(&crate_info.version)));
+                if let Some(types) = self.crate_dependency_types.get(&crate_ref) {
+                    let reachability = reachability(types);
+                    crate_info.reachability = Some(reachability);
+                    apply_scope(crate_info, self.config.dependency_scope.handling(reachability));
+                }
+            }
+        }
+
+        if let Some(msrv) = &self.workspace_msrv {
+            for crate_info in &mut reportable_crates {
+                crate_info.workspace_msrv = Some(Arc::clone(msrv));
+            }
+        }
+
+        let now = Utc::now();
+        let mut stale_crates = 0;
+        for crate_info in &mut reportable_crates {
+            let stale = self.config.cache.stale_after.stale_data(&crate_info.data_timestamps, now);
+            stale_crates += usize::from(!stale.is_empty());
+            crate_info.notes.extend(stale);
+        }
+
+        if stale_crates > 0 {
+            let _ = writeln!(
+                self.host.error(),
+                "WARNING: {stale_crates} crate(s) were appraised with stale data, which their notes detail"
+            );
+        }
+
+        if let Some(reason) = self.cancellation.reason() {
+            let not_analyzed = reportable_crates.iter().filter(|c| !c.cancelled_sources.is_empty()).count();
+            let _ = writeln!(
+                self.host.error(),
+                "WARNING: the run was {reason}, so {not_analyzed} crate(s) weren't fully analyzed and aren't appraised"
+            );
+        }
 
         // Sort crates by name and version for consistent ordering
         reportable_crates.sort_by(|a, b| a.name.as_ref().cmp(b.name.as_ref()).then_with(|| a.version.cmp(&b.version)));
 
-        let generating_reports = self.html.is_some() || self.excel.is_some() || self.csv.is_some() || self.json.is_some();
+        #[cfg(feature = "script_hook")]
+        if let Some(path) = &self.config.script_hook {
+            run_script_hook(path, &mut reportable_crates, self.config.medium_risk_threshold, self.config.low_risk_threshold)?;
+        }
+
+        if let Some(path) = &self.baseline {
+            let text = fs::read_to_string(path).into_app_err_with(|| format!("reading baseline report '{path}'"))?;
+            let scores = read_json_scores(&text).map_err(|e| e.enrich_with(|| format!("parsing baseline report '{path}'")))?;
+            for crate_info in &mut reportable_crates {
+                crate_info.previous_score = scores.get(crate_info.name.as_ref()).copied();
+            }
+        }
+
+        Ok(reportable_crates)
+    }
+
+    fn use_colors(&self) -> bool {
+        match self.color {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => {
+                use std::io::{IsTerminal, stdout};
+                stdout().is_terminal()
+            }
+        }
+    }
+
+    /// Write a side-by-side comparison of the crates to the console, and to an HTML file when `--html` is given.
+    pub async fn compare(&mut self, processed_crates: impl IntoIterator<Item = CrateFacts>) -> Result<()> {
+        let reportable_crates = self.prepare_reportable_crates(processed_crates).await?;
+        if reportable_crates.is_empty() {
+            return Ok(());
+        }
+
+        let mut console_output = String::new();
+        generate_comparison_console(&reportable_crates, self.use_colors(), &mut console_output)?;
+        let _ = write!(self.host.output(), "{console_output}");
+
+        if let Some(filename) = &self.html {
+            let mut html = String::new();
+            generate_comparison_html(&reportable_crates, Local::now(), &mut html)?;
+            fs::write(filename, html)?;
+        }
+
+        Ok(())
+    }
+
+    /// Report how the score of a crate evolved across the given releases.
+    pub async fn release_timeline(&mut self, processed_crates: impl IntoIterator<Item = CrateFacts>) -> Result<()> {
+        let reportable_crates = self.prepare_reportable_crates(processed_crates).await?;
+        if reportable_crates.is_empty() {
+            return Ok(());
+        }
+
+        let mut console_output = String::new();
+        generate_timeline_console(&reportable_crates, self.use_colors(), &mut console_output)?;
+        let _ = write!(self.host.output(), "{console_output}");
+
+        if let Some(filename) = &self.json {
+            let mut json = String::new();
+            generate_timeline_json(&reportable_crates, &mut json)?;
+            fs::write(filename, json)?;
+        }
+
+        Ok(())
+    }
+
+    #[expect(clippy::too_many_lines, reason = "Function handles multiple report formats")]
+    pub async fn report(&mut self, processed_crates: impl IntoIterator<Item = CrateFacts>) -> Result<()> {
+        let all_crates = self.prepare_reportable_crates(processed_crates).await?;
+        let top_crates = self.top.map(|count| lowest_scoring(&all_crates, count));
+        if let Some(top_crates) = &top_crates {
+            let _ = writeln!(
+                self.host.error(),
+                "Reporting the {} lowest-scoring of {} crate(s)",
+                top_crates.len(),
+                all_crates.len()
+            );
+        }
+        let reportable_crates = top_crates.as_deref().unwrap_or(&all_crates);
+
+        let generating_reports = self.html.is_some()
+            || self.excel.is_some()
+            || self.csv.is_some()
+            || self.json.is_some()
+            || self.cyclonedx.is_some()
+            || self.backstage.is_some()
+            || self.mermaid.is_some()
+            || self.renovate.is_some()
+            || self.dependabot.is_some()
+            || self.vet_exemptions.is_some()
+            || self.summary_json.is_some()
+            || self.report_to.is_some()
+            || self.graph.is_some();
 
         // Show console output if:
         // - --console flag is explicitly set, OR
         // - No reports are being generated AND no --error-if flag is set
         let error_if = self.error_if_high_risk || self.error_if_medium_risk;
-        let default_mode = ConsoleOutputMode::full();
         let console_mode = match &self.console {
             Some(mode) => Some(mode),
-            None if !generating_reports && !error_if => Some(&default_mode),
+            None if !generating_reports && !error_if => Some(&self.default_console),
             None => None,
         };
 
         if let Some(mode) = console_mode && !reportable_crates.is_empty() {
             let mut console_output = String::new();
-            let use_colors = match self.color {
-                ColorMode::Always => true,
-                ColorMode::Never => false,
-                ColorMode::Auto => {
-                    use std::io::{IsTerminal, stdout};
-                    stdout().is_terminal()
-                }
-            };
-            _ = generate_console(&reportable_crates, use_colors, mode, &mut console_output);
+            _ = generate_console(reportable_crates, self.use_colors(), mode, &mut console_output);
             let _ = write!(self.host.output(), "{console_output}");
         }
 
         if let Some(filename) = &self.html {
             let mut html = String::new();
-            generate_html(&reportable_crates, Local::now(), &mut html)?;
+            generate_html(reportable_crates, Local::now(), &mut html)?;
             fs::write(filename, html)?;
         }
 
         if let Some(filename) = &self.excel {
             let mut file = fs::File::create(filename)?;
-            generate_xlsx(&reportable_crates, &mut file)?;
+            generate_xlsx(reportable_crates, &mut file)?;
         }
 
         if let Some(filename) = &self.csv {
             let mut csv_output = String::new();
-            generate_csv(&reportable_crates, &mut csv_output)?;
+            generate_csv(reportable_crates, &mut csv_output)?;
             fs::write(filename, csv_output)?;
         }
 
         if let Some(filename) = &self.json {
             let mut json_output = String::new();
-            generate_json(&reportable_crates, &mut json_output)?;
+            generate_json(reportable_crates, &self.collector.api_usage(), &mut json_output)?;
             fs::write(filename, json_output)?;
         }
 
+        let upload_to_dependency_track = self.config.dependency_track.as_ref().zip(self.dependency_track_api_key.as_deref());
+        if self.cyclonedx.is_some() || upload_to_dependency_track.is_some() {
+            let mut bom = String::new();
+            generate_cyclonedx(reportable_crates, Local::now(), &mut bom)?;
+
+            if let Some((dt_config, api_key)) = upload_to_dependency_track {
+                upload_bom(dt_config, api_key, &bom).await?;
+            }
+
+            if let Some(filename) = &self.cyclonedx {
+                fs::write(filename, bom)?;
+            }
+        }
+
+        if let Some(filename) = &self.backstage {
+            let mut backstage_output = String::new();
+            generate_backstage(reportable_crates, &mut backstage_output)?;
+            fs::write(filename, backstage_output)?;
+        }
+
+        if let Some(filename) = &self.mermaid {
+            let mut mermaid_output = String::new();
+            generate_mermaid(reportable_crates, self.dependency_graph.as_ref(), &mut mermaid_output)?;
+            fs::write(filename, mermaid_output)?;
+        }
+
+        if let Some(filename) = &self.renovate {
+            let mut renovate_output = String::new();
+            generate_renovate(reportable_crates, &mut renovate_output)?;
+            fs::write(filename, renovate_output)?;
+        }
+
+        if let Some(filename) = &self.dependabot {
+            let mut dependabot_output = String::new();
+            generate_dependabot(reportable_crates, &mut dependabot_output)?;
+            fs::write(filename, dependabot_output)?;
+        }
+
+        if let Some(filename) = &self.vet_exemptions {
+            let min_score = self.vet_min_score.unwrap_or(self.config.low_risk_threshold);
+            let mut vet_output = String::new();
+            generate_vet(reportable_crates, min_score, &self.vet_criteria, &mut vet_output)?;
+            fs::write(filename, vet_output)?;
+        }
+
+        if let Some(filename) = &self.graph
+            && let Some(graph) = &self.dependency_graph
+        {
+            let mut graph_output = String::new();
+            generate_graph(reportable_crates, graph, &mut graph_output)?;
+            fs::write(filename, graph_output)?;
+        }
+
+        #[cfg_attr(not(feature = "upload"), expect(unused_variables, reason = "team reports are only gathered to be uploaded"))]
+        let team_files = self.write_team_reports(reportable_crates)?;
+
+        #[cfg(feature = "upload")]
+        if let Some(destination) = &self.upload {
+            let files: Vec<_> = [
+                &self.html,
+                &self.excel,
+                &self.csv,
+                &self.json,
+                &self.cyclonedx,
+                &self.backstage,
+                &self.mermaid,
+                &self.renovate,
+                &self.dependabot,
+                &self.vet_exemptions,
+                &self.graph,
+            ]
+            .into_iter()
+            .flatten()
+            .chain(&team_files)
+            .map(Utf8PathBuf::as_path)
+            .collect();
+
+            // The reports are already written locally, so a failed upload shouldn't mask the outcome of the appraisal
+            if let Err(e) = upload_reports(destination, &files, Local::now()).await {
+                let _ = writeln!(self.host.error(), "WARNING: {e}");
+            }
+        }
+
+        // A failed notification shouldn't mask the outcome of the appraisal itself
+        let notify_state = self.cache_dir.join("notify").join(format!("{}.json", file_stem(&workspace_name(&self.workspace_root))));
+        if let Some(notify) = &self.config.notify
+            && let Err(e) = send_notification(notify, &all_crates, &notify_state).await
+        {
+            let _ = writeln!(self.host.error(), "WARNING: {e}");
+        }
+
         // If --error-if-medium-risk flag is set, return error if any non-allowed crate is medium or high risk
         // If --error-if-high-risk flag is set, return error if any non-allowed crate is high risk
-        check_risk_errors(&reportable_crates, &self.config, self.error_if_medium_risk, self.error_if_high_risk)?;
+        let outcome = check_risk_errors(&all_crates, &self.config, self.error_if_medium_risk, self.error_if_high_risk)
+            .and_then(|()| self.check_complete());
+
+        if let Some(filename) = &self.summary_json {
+            let mut summary_output = String::new();
+            generate_summary(&all_crates, outcome.is_ok(), self.started.elapsed(), &mut summary_output)?;
+            fs::write(filename, summary_output)?;
+        }
+
+        if let Some(url) = &self.report_to {
+            let mut summary_output = String::new();
+            generate_summary(&all_crates, outcome.is_ok(), self.started.elapsed(), &mut summary_output)?;
+            let mut json_output = String::new();
+            generate_json(&all_crates, &self.collector.api_usage(), &mut json_output)?;
+
+            let submission =
+                Submission::new(&self.workspace_root, serde_json::from_str(&summary_output)?, serde_json::from_str(&json_output)?);
+            submit_report(url, &submission).await?;
+        }
+
+        // A partial run would look like a sudden change to the alerts and trends, so only complete runs are recorded
+        if let Some(history) = &self.config.history
+            && !self.cancellation.is_cancelled()
+        {
+            let store = open_store(&history.url).await?;
+            let run = RunRecord::new(workspace_name(&self.workspace_root), git_commit(&self.workspace_root), outcome.is_ok(), &all_crates);
+
+            if !self.config.alerts.is_empty()
+                && let Some(previous) = store.latest_run(&run.workspace).await?
+            {
+                let alerts = evaluate_alerts(&self.config.alerts, &previous, &run, &self.crate_dependency_types);
+                for alert in &alerts {
+                    let _ = writeln!(self.host.error(), "ALERT: {alert}");
+                }
+
+                // Like notifications, a failure to deliver alerts shouldn't mask the outcome of the appraisal
+                if !alerts.is_empty()
+                    && let Some(notify) = &self.config.notify
+                    && let Err(e) = send_alerts(notify, &run.workspace, &alerts).await
+                {
+                    let _ = writeln!(self.host.error(), "WARNING: {e}");
+                }
+            }
+
+            store.record_run(&run).await?;
+        }
+
+        outcome
+    }
+
+    /// Fail a run that was cancelled before every crate could be analyzed.
+    fn check_complete(&self) -> Result<()> {
+        if let Some(reason) = self.cancellation.reason() {
+            bail!("the run was {reason} before every crate could be analyzed");
+        }
 
         Ok(())
     }
 }
 
+/// Flatten the facts of a crate into metrics, appraising them with the configured expressions when `appraise` is set.
+///
+/// Crates whose data was cut short by a cancellation aren't appraised, as the missing data would count against them.
+fn reportable_crate(config: &Config, facts: &CrateFacts, appraise: bool, now: DateTime<Local>) -> ReportableCrate {
+    let metrics: Vec<_> = flatten(facts).collect();
+    let cancelled_sources = facts.cancelled_sources();
+    if !appraise || !cancelled_sources.is_empty() {
+        let mut crate_info = ReportableCrate::new(
+            Arc::clone(facts.crate_spec.name_arc()),
+            Arc::clone(facts.crate_spec.version_arc()),
+            metrics,
+            None,
+        );
+        crate_info.data_timestamps = facts.timestamps;
+        if !cancelled_sources.is_empty() {
+            crate_info
+                .notes
+                .push(format!("Not analyzed, the run was cancelled before gathering its {} data", cancelled_sources.join(", ")));
+            crate_info.cancelled_sources = cancelled_sources;
+        }
+        return crate_info;
+    }
+
+    let mut evaluation = evaluate(
+        &config.high_risk,
+        &config.eval,
+        &metrics,
+        now,
+        config.medium_risk_threshold,
+        config.low_risk_threshold,
+    );
+    config.aggregation.apply(
+        &mut evaluation,
+        &config.eval,
+        &config.category_weights,
+        config.medium_risk_threshold,
+        config.low_risk_threshold,
+    );
+
+    let category_scores = category_scores(&config.eval, &evaluation)
+        .into_iter()
+        .map(|(category, score)| {
+            let risk = config.category_risk(category, score);
+            (category, CategoryScore { score, risk })
+        })
+        .collect();
+
+    let mut crate_info = ReportableCrate::new(
+        Arc::clone(facts.crate_spec.name_arc()),
+        Arc::clone(facts.crate_spec.version_arc()),
+        metrics,
+        Some(evaluation),
+    );
+    crate_info.category_scores = category_scores;
+    crate_info.data_timestamps = facts.timestamps;
+    crate_info
+}
+
+/// How a crate reached through the given dependency types is exposed at runtime.
+fn reachability(types: &[DependencyType]) -> Reachability {
+    if types.contains(&DependencyType::Standard) {
+        Reachability::Runtime
+    } else if types.contains(&DependencyType::Build) {
+        Reachability::BuildOnly
+    } else {
+        Reachability::DevOnly
+    }
+}
+
+/// Lower the risk of a crate that isn't a runtime dependency, or exempt it from the risk checks, leaving a note
+/// so that reports tell why.
+fn apply_scope(crate_info: &mut ReportableCrate, handling: ScopeHandling) {
+    let Some(reachability) = crate_info.reachability else {
+        return;
+    };
+
+    match handling {
+        ScopeHandling::Enforce => {}
+        ScopeHandling::Downgrade => {
+            if let Some(appraisal) = &mut crate_info.appraisal {
+                let lowered = match appraisal.risk {
+                    Risk::High => Risk::Medium,
+                    Risk::Medium | Risk::Low => Risk::Low,
+                };
+
+                if lowered != appraisal.risk {
+                    crate_info
+                        .notes
+                        .push(format!("Lowered from {} to {lowered} as a {} dependency", appraisal.risk, reachability.as_str()));
+                    appraisal.risk = lowered;
+                }
+            }
+        }
+        ScopeHandling::Ignore => {
+            crate_info.scope_exempt = true;
+            crate_info.notes.push(format!("Exempt from risk checks as a {} dependency", reachability.as_str()));
+        }
+    }
+}
+
+/// The appraised crates with the lowest scores, worst first.
+fn lowest_scoring(crates: &[ReportableCrate], count: usize) -> Vec<ReportableCrate> {
+    let mut appraised: Vec<_> = crates
+        .iter()
+        .filter_map(|c| c.appraisal.as_ref().map(|appraisal| (c, appraisal.score)))
+        .collect();
+    appraised.sort_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(core::cmp::Ordering::Equal));
+    appraised.into_iter().take(count).map(|(c, _)| c.clone()).collect()
+}
+
 fn check_risk_errors(
     reportable_crates: &[ReportableCrate],
     config: &Config,
@@ -437,6 +1396,7 @@ fn check_risk_errors(
     if error_if_medium_risk {
         let has_rejected = reportable_crates.iter().any(|crate_info| {
             crate_info.appraisal.as_ref().is_some_and(|eval| matches!(eval.risk, Risk::Medium | Risk::High))
+                && !crate_info.scope_exempt
                 && !config.is_allowed(&crate_info.name, &crate_info.version)
         });
 
@@ -448,6 +1408,7 @@ fn check_risk_errors(
     if error_if_high_risk {
         let has_rejected = reportable_crates.iter().any(|crate_info| {
             crate_info.appraisal.as_ref().is_some_and(|eval| eval.risk == Risk::High)
+                && !crate_info.scope_exempt
                 && !config.is_allowed(&crate_info.name, &crate_info.version)
         });
 
@@ -456,16 +1417,126 @@ fn check_risk_errors(
         }
     }
 
+    if error_if_medium_risk || error_if_high_risk {
+        check_category_scores(reportable_crates, config)?;
+    }
+
     Ok(())
 }
 
+/// Reject crates scoring below the configured minimum in any metric category, even if their overall score is fine.
+fn check_category_scores(reportable_crates: &[ReportableCrate], config: &Config) -> Result<()> {
+    let min_scores = &config.check.min_category_scores;
+    if min_scores.is_empty() {
+        return Ok(());
+    }
+
+    let mut failures = Vec::new();
+    for crate_info in reportable_crates {
+        let Some(appraisal) = &crate_info.appraisal else {
+            continue;
+        };
+
+        if crate_info.scope_exempt || config.is_allowed(&crate_info.name, &crate_info.version) {
+            continue;
+        }
+
+        let scores = category_scores(&config.eval, appraisal);
+        for (category, min_score) in min_scores {
+            if let Some(score) = scores.get(category)
+                && score < min_score
+            {
+                failures.push(format!(
+                    "{} v{} scored {score:.0} in {category}, below the minimum of {min_score:.0}",
+                    crate_info.name, crate_info.version
+                ));
+            }
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(ohno::app_err!("one or more crates fell below a category minimum:\n  {}", failures.join("\n  ")))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::commands::config::AllowListEntry;
-    use crate::expr::{Appraisal, Risk};
+    use crate::expr::{Appraisal, ExpressionDisposition, ExpressionOutcome, Risk};
     use semver::{Version, VersionReq};
 
+    fn make_category_crate(name: &str, outcomes: &[(&str, ExpressionDisposition)]) -> ReportableCrate {
+        let outcomes = outcomes
+            .iter()
+            .map(|(n, d)| ExpressionOutcome::new(Arc::from(*n), Arc::from(*n), d.clone()))
+            .collect();
+        ReportableCrate::new(
+            Arc::from(name),
+            Arc::new(Version::new(1, 0, 0)),
+            vec![],
+            Some(Appraisal::new(Risk::Low, outcomes, 0, 0, 100.0)),
+        )
+    }
+
+    fn category_config() -> Config {
+        let mut config: Config = toml::from_str(
+            r#"
+            [check]
+            min_category_scores = { Activity = 40 }
+
+            [[eval]]
+            name = "popular"
+            expression = "usage.total_downloads > 1000"
+            points = 10
+
+            [[eval]]
+            name = "maintained"
+            expression = "activity.open_issues < 10"
+            "#,
+        )
+        .unwrap();
+        config.allow_list.push(AllowListEntry {
+            name: "allowed".to_string(),
+            version: VersionReq::STAR,
+        });
+        config
+    }
+
+    #[test]
+    fn test_check_category_scores() {
+        let config = category_config();
+        let popular = [("popular", ExpressionDisposition::True), ("maintained", ExpressionDisposition::False)];
+        let maintained = [("popular", ExpressionDisposition::False), ("maintained", ExpressionDisposition::True)];
+
+        check_risk_errors(&[make_category_crate("good", &maintained)], &config, false, true).unwrap();
+        check_risk_errors(&[make_category_crate("foo", &popular)], &config, false, false).unwrap();
+        check_risk_errors(&[make_category_crate("allowed", &popular)], &config, false, true).unwrap();
+
+        let err = check_risk_errors(&[make_category_crate("foo", &popular)], &config, false, true).unwrap_err();
+        assert!(err.to_string().contains("foo v1.0.0 scored 0 in Activity"), "{err}");
+    }
+
+    #[test]
+    fn test_lowest_scoring() {
+        let scored = |name: &str, score| {
+            ReportableCrate::new(
+                Arc::from(name),
+                Arc::new(Version::new(1, 0, 0)),
+                vec![],
+                Some(Appraisal::new(Risk::Low, vec![], 0, 0, score)),
+            )
+        };
+        let unappraised = ReportableCrate::new(Arc::from("unappraised"), Arc::new(Version::new(1, 0, 0)), vec![], None);
+        let crates = [scored("a", 80.0), unappraised, scored("b", 20.0), scored("c", 50.0), scored("d", 20.0)];
+
+        let names = |crates: Vec<ReportableCrate>| crates.iter().map(|c| c.name.to_string()).collect::<Vec<_>>();
+        assert_eq!(names(lowest_scoring(&crates, 3)), ["b", "d", "c"]);
+        assert_eq!(names(lowest_scoring(&crates, 10)), ["b", "d", "c", "a"]);
+    }
+
     fn make_crate(name: &str, version: Version, risk: Risk) -> ReportableCrate {
         ReportableCrate::new(
             Arc::from(name),
@@ -593,5 +1664,32 @@ mod tests {
         });
         check_risk_errors(&crates, &config, true, true).unwrap();
     }
-}
 
+    #[test]
+    fn test_reachability() {
+        assert_eq!(reachability(&[DependencyType::Dev, DependencyType::Standard]), Reachability::Runtime);
+        assert_eq!(reachability(&[DependencyType::Dev, DependencyType::Build]), Reachability::BuildOnly);
+        assert_eq!(reachability(&[DependencyType::Dev]), Reachability::DevOnly);
+    }
+
+    #[test]
+    fn test_apply_scope() {
+        let mut downgraded = make_crate("criterion", Version::new(0, 5, 1), Risk::High);
+        downgraded.reachability = Some(Reachability::DevOnly);
+        apply_scope(&mut downgraded, ScopeHandling::Downgrade);
+        assert_eq!(downgraded.appraisal.as_ref().unwrap().risk, Risk::Medium);
+        assert_eq!(downgraded.notes, ["Lowered from HIGH RISK to MEDIUM RISK as a dev-only dependency"]);
+
+        let mut ignored = make_crate("cc", Version::new(1, 2, 0), Risk::High);
+        ignored.reachability = Some(Reachability::BuildOnly);
+        apply_scope(&mut ignored, ScopeHandling::Ignore);
+        assert_eq!(ignored.appraisal.as_ref().unwrap().risk, Risk::High);
+        check_risk_errors(&[ignored], &Config::default(), true, true).unwrap();
+
+        let mut enforced = make_crate("serde", Version::new(1, 0, 0), Risk::High);
+        enforced.reachability = Some(Reachability::DevOnly);
+        apply_scope(&mut enforced, ScopeHandling::Enforce);
+        assert!(enforced.notes.is_empty());
+        let _ = check_risk_errors(&[enforced], &Config::default(), false, true).unwrap_err();
+    }
+}
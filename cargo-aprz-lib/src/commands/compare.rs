@@ -0,0 +1,22 @@
+use super::Host;
+use super::common::{Common, CommonArgs};
+use crate::Result;
+use crate::facts::CrateRef;
+use clap::Parser;
+
+#[derive(Parser, Debug)]
+pub struct CompareArgs {
+    /// Crates to compare (format: `crate_name` or `crate_name@version`)
+    #[arg(value_name = "CRATE", num_args = 2.., required = true)]
+    pub crates: Vec<CrateRef>,
+
+    #[command(flatten)]
+    pub common: CommonArgs,
+}
+
+pub async fn compare_crates<H: Host>(host: &mut H, args: &CompareArgs) -> Result<()> {
+    let mut common = Common::new(host, &args.common).await?;
+    let crate_facts = common.process_crates(&args.crates, true).await?;
+
+    common.compare(crate_facts.into_iter()).await
+}
@@ -1,12 +1,23 @@
+use super::cargo_deny;
+use super::deps::DependencyType;
+use super::presets;
 use crate::Result;
-use crate::expr::Expression;
+use crate::expr::{Aggregation, Expression, ProjectLicense, Risk, is_valid_snippet_name};
+use crate::facts::{CustomHost, DataTimestamps};
+use crate::metrics::{MetricCategory, metric_category};
+use crate::reports::Reachability;
 use camino::{Utf8Path, Utf8PathBuf};
+use chrono::{DateTime, Utc};
 use core::time::Duration;
 use ohno::{IntoAppError, app_err};
 use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::io;
+use std::sync::Arc;
+
+const LOG_TARGET: &str = "    config";
 
 /// The default configuration TOML content, embedded from `default_config.toml`
 pub const DEFAULT_CONFIG_TOML: &str = include_str!("../../default_config.toml");
@@ -31,6 +42,520 @@ impl AllowListEntry {
     }
 }
 
+/// A synthetic crate whose appraisal must produce the given outcomes, so that changes to expressions which alter
+/// their verdicts get noticed.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExpressionTest {
+    /// Name of the test, used when reporting failures
+    pub name: String,
+
+    /// Metric values of the synthetic crate. Metrics not listed keep the placeholder values used by `validate`
+    #[serde(default)]
+    pub metrics: BTreeMap<String, toml::Value>,
+
+    /// Time at which the expressions are evaluated, defaulting to the current time
+    #[serde(default)]
+    pub now: Option<toml::value::Datetime>,
+
+    /// Expected outcome of expressions, by expression name
+    #[serde(default)]
+    pub expect: BTreeMap<String, bool>,
+
+    /// Expected risk level of the synthetic crate
+    #[serde(default)]
+    pub risk: Option<Risk>,
+}
+
+/// The chat service a notification webhook belongs to, which determines the payload shape.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyFormat {
+    /// Slack incoming webhook (`{"text": ...}`)
+    #[default]
+    Slack,
+
+    /// Microsoft Teams incoming webhook (`MessageCard`)
+    Teams,
+}
+
+/// Settings for posting a summary to a chat webhook when crates are flagged.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct NotifyConfig {
+    /// The incoming webhook URL to post to
+    pub webhook_url: String,
+
+    /// The chat service the webhook belongs to
+    #[serde(default)]
+    pub format: NotifyFormat,
+
+    /// Message template. Supports `{flagged}`, `{total}`, `{min_risk}`, and `{crates}` placeholders
+    #[serde(default = "default_notify_template")]
+    pub template: String,
+
+    /// Minimum risk level that causes a crate to be included in the notification
+    #[serde(default = "default_notify_min_risk")]
+    pub min_risk: Risk,
+
+    /// Whether crates with known vulnerabilities in the appraised version are included regardless of risk
+    #[serde(default = "default_notify_on_advisories")]
+    pub on_advisories: bool,
+}
+
+/// Settings for uploading a `CycloneDX` SBOM to an OWASP Dependency-Track server.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct DependencyTrackConfig {
+    /// Base URL of the Dependency-Track API server
+    pub url: String,
+
+    /// Name of the Dependency-Track project to upload to
+    pub project_name: String,
+
+    /// Version of the Dependency-Track project to upload to
+    #[serde(default = "default_dependency_track_project_version")]
+    pub project_version: String,
+
+    /// Whether to create the project if it doesn't exist yet
+    #[serde(default = "default_dependency_track_auto_create")]
+    pub auto_create: bool,
+}
+
+/// Where to fetch a signed policy pack from, and the key its signature must verify against.
+///
+/// A pack comes from exactly one of a URL, a git repository, or a registry.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyPackSource {
+    /// URL of the policy pack, such as a release asset or a raw file in a git repository
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// URL of the pack's minisign signature, defaulting to the pack's URL with `.minisig` appended
+    #[serde(default)]
+    pub signature_url: Option<String>,
+
+    /// URL of a git repository holding the policy pack
+    #[serde(default)]
+    pub git: Option<String>,
+
+    /// Branch, tag, or commit of the git repository to read the pack from, defaulting to the repository's default branch
+    #[serde(default)]
+    pub rev: Option<String>,
+
+    /// Path of the pack within the git repository. The signature is read from the same path with `.minisig` appended
+    #[serde(default)]
+    pub path: Option<String>,
+
+    /// URL of a policy pack registry, which serves the versions of each pack it holds
+    #[serde(default)]
+    pub registry: Option<String>,
+
+    /// Name of the pack within the registry
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// The minisign public key the pack must be signed with, in base64
+    pub public_key: String,
+
+    /// A semver version requirement the pack's version must meet
+    #[serde(default)]
+    pub version: Option<VersionReq>,
+}
+
+/// Where a policy pack is fetched from, as given by a [`PolicyPackSource`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackLocation<'a> {
+    /// A pack file and its signature served over HTTP(S)
+    Url { url: &'a str, signature_url: Option<&'a str> },
+
+    /// A pack file and its signature at a revision of a git repository
+    Git { repo: &'a str, rev: &'a str, path: &'a str },
+
+    /// The latest version of a pack in a registry that meets the version requirement
+    Registry { registry: &'a str, name: &'a str },
+}
+
+impl PolicyPackSource {
+    /// Where the pack is fetched from.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error unless exactly one of `url`, `git`, or `registry` is given, along with the fields that go with it
+    pub fn location(&self) -> Result<PackLocation<'_>> {
+        let location = match (&self.url, &self.git, &self.registry) {
+            (Some(url), None, None) => PackLocation::Url {
+                url,
+                signature_url: self.signature_url.as_deref(),
+            },
+            (None, Some(repo), None) => PackLocation::Git {
+                repo,
+                rev: self.rev.as_deref().unwrap_or("HEAD"),
+                path: self.path.as_deref().ok_or_else(|| app_err!("policy_packs entry for '{repo}' is missing 'path'"))?,
+            },
+            (None, None, Some(registry)) => PackLocation::Registry {
+                registry: registry.trim_end_matches('/'),
+                name: self.name.as_deref().ok_or_else(|| app_err!("policy_packs entry for '{registry}' is missing 'name'"))?,
+            },
+            _ => return Err(app_err!("policy_packs entries must have exactly one of 'url', 'git', or 'registry'")),
+        };
+
+        let stray: &[(&str, bool)] = match location {
+            PackLocation::Url { .. } => &[("rev", self.rev.is_some()), ("path", self.path.is_some()), ("name", self.name.is_some())],
+            PackLocation::Git { .. } => &[("signature_url", self.signature_url.is_some()), ("name", self.name.is_some())],
+            PackLocation::Registry { .. } => &[
+                ("signature_url", self.signature_url.is_some()),
+                ("rev", self.rev.is_some()),
+                ("path", self.path.is_some()),
+            ],
+        };
+        if let Some((field, _)) = stray.iter().find(|(_, given)| *given) {
+            return Err(app_err!("policy_packs entry for '{location}' can't have '{field}'"));
+        }
+
+        Ok(location)
+    }
+}
+
+impl core::fmt::Display for PackLocation<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Url { url, .. } => write!(f, "{url}"),
+            Self::Git { repo, rev, path } => write!(f, "{repo}@{rev}:{path}"),
+            Self::Registry { registry, name } => write!(f, "{registry}/{name}"),
+        }
+    }
+}
+
+/// Settings for recording the outcome of every run in a history store.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct HistoryConfig {
+    /// URL of the history store, either `sqlite://<path>` or `postgres://<connection string>`.
+    /// Relative `SQLite` paths are resolved against the directory containing the configuration file
+    pub url: String,
+}
+
+/// A rule raising an alert when the appraisal of a workspace changes for the worse since the previous run.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct AlertRule {
+    /// Name of the rule, shown in the alerts it raises
+    pub name: String,
+
+    /// Dependency types of the crates the rule applies to, defaulting to all crates
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependency_types: Vec<DependencyType>,
+
+    /// Raise an alert when a crate's score drops by more than this many points
+    #[serde(default)]
+    pub score_drop: Option<f64>,
+
+    /// Raise an alert when a crate is affected by more known vulnerabilities than before
+    #[serde(default)]
+    pub new_advisories: bool,
+
+    /// Raise an alert when the workspace score drops by more than this many points
+    #[serde(default)]
+    pub workspace_score_drop: Option<f64>,
+}
+
+/// A policy penalizing crates whose downloads or dependents decline steeply, even when their counts are still large.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TrendPolicy {
+    /// Name of the expression generated for the policy
+    pub name: String,
+
+    /// Largest accepted decline over the last six months, in percent
+    pub max_decline: f64,
+
+    /// Points awarded to the crates within the accepted decline. Without points, the crates declining more
+    /// steeply are high risk
+    #[serde(default)]
+    pub points: Option<u32>,
+}
+
+impl TrendPolicy {
+    /// The expression enforcing the policy, checking the given trend metric.
+    fn to_expression(&self, metric: &str, subject: &str) -> Result<Expression> {
+        Expression::new(
+            &self.name,
+            Some(&format!("{subject} fell by at most {}% over the last six months.", self.max_decline)),
+            &format!("{metric} >= {:?}", -self.max_decline),
+            self.points,
+        )
+    }
+}
+
+/// A policy limiting how long a crate may go without adopting the fix of a vulnerability affecting it.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct AdvisoryPatchLagPolicy {
+    /// Name of the expression generated for the policy
+    pub name: String,
+
+    /// Largest accepted number of days since a fix was published for a vulnerability still affecting the crate
+    pub max_days: u64,
+
+    /// Points awarded to the crates within the accepted lag. Without points, the crates lagging further behind
+    /// are high risk
+    #[serde(default)]
+    pub points: Option<u32>,
+}
+
+impl AdvisoryPatchLagPolicy {
+    /// The expression enforcing the policy, checking the `advisories.version_oldest_unadopted_fix_days` metric.
+    fn to_expression(&self) -> Result<Expression> {
+        Expression::new(
+            &self.name,
+            Some(&format!("No vulnerability fix has gone unadopted for more than {} days.", self.max_days)),
+            &format!("advisories.version_oldest_unadopted_fix_days <= {}", self.max_days),
+            self.points,
+        )
+    }
+}
+
+/// A policy checking that dependencies build with the Rust version the workspace declares as its MSRV.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct MsrvCheckPolicy {
+    /// Name of the expression generated for the policy
+    #[serde(default = "default_msrv_check_name")]
+    pub name: String,
+
+    /// Points awarded to the crates building with the workspace's MSRV. Without points, the crates requiring a
+    /// newer Rust version are high risk
+    #[serde(default)]
+    pub points: Option<u32>,
+
+    /// Where the policy was defined, recorded when the configuration is loaded
+    #[serde(skip)]
+    pub origin: Option<String>,
+}
+
+impl MsrvCheckPolicy {
+    /// The expression enforcing the policy against the given workspace MSRV, crates declaring no MSRV passing.
+    pub fn to_expression(&self, workspace_msrv: &str) -> Result<Expression> {
+        let expression = Expression::new(
+            &self.name,
+            Some(&format!("The crate builds with Rust {workspace_msrv}, the MSRV of the workspace.")),
+            &format!("crate.minimum_rust == '' || rust_version_at_most(crate.minimum_rust, '{workspace_msrv}')"),
+            self.points,
+        )?;
+
+        Ok(match &self.origin {
+            Some(origin) => expression.with_origin(origin.as_str()),
+            None => expression,
+        })
+    }
+}
+
+/// Checks the licenses of crates against the license of the project depending on them.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct LicenseCompatibilityConfig {
+    /// SPDX identifier of the project's license, or `proprietary` for closed-source projects
+    pub project_license: String,
+
+    /// Name of the high-risk expression generated for the check
+    #[serde(default = "default_license_compatibility_name")]
+    pub name: String,
+}
+
+impl LicenseCompatibilityConfig {
+    /// The high-risk expression failing crates whose license conflicts with the project's.
+    fn to_expression(&self) -> Result<Expression> {
+        Expression::new(
+            &self.name,
+            Some(&format!("The crate's license is compatible with the project license, {}.", self.project_license)),
+            &format!("license_compatible(crate.license, '{}')", self.project_license),
+            None,
+        )
+    }
+}
+
+/// Additional conditions enforced by `--error-if-medium-risk` and `--error-if-high-risk`.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CheckConfig {
+    /// Minimum score (0..100) a crate must reach in each metric category, regardless of its overall score
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub min_category_scores: BTreeMap<MetricCategory, f64>,
+}
+
+/// How the failures of crates that aren't runtime dependencies are treated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScopeHandling {
+    /// Treat the crate like a runtime dependency
+    #[default]
+    Enforce,
+
+    /// Lower the risk of the crate by one level, high becoming medium and medium becoming low
+    Downgrade,
+
+    /// Report the crate without letting it fail the risk checks
+    Ignore,
+}
+
+/// How failures are treated for dependencies only reached through dev or build dependencies, which aren't
+/// exposed at runtime.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct DependencyScopeConfig {
+    /// Handling of crates only reached through dev dependencies
+    #[serde(default)]
+    pub dev: ScopeHandling,
+
+    /// Handling of crates only reached through build dependencies
+    #[serde(default)]
+    pub build: ScopeHandling,
+}
+
+impl DependencyScopeConfig {
+    /// How the failures of a crate reached as given are treated.
+    #[must_use]
+    pub const fn handling(&self, reachability: Reachability) -> ScopeHandling {
+        match reachability {
+            Reachability::Runtime => ScopeHandling::Enforce,
+            Reachability::BuildOnly => self.build,
+            Reachability::DevOnly => self.dev,
+        }
+    }
+}
+
+/// Settings for the hosting services repositories are queried from.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct HostingConfig {
+    /// GitHub tokens requests are spread across, unless tokens are given on the command line
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub github_tokens: Vec<String>,
+
+    /// Codeberg tokens requests are spread across, unless tokens are given on the command line
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub codeberg_tokens: Vec<String>,
+
+    /// Self-hosted GitHub Enterprise Server and Gitea instances, declared as `[[hosting.host]]` tables
+    #[serde(default, rename = "host", skip_serializing_if = "Vec::is_empty")]
+    pub hosts: Vec<CustomHost>,
+}
+
+/// Settings for the local cache of crate facts.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CacheConfig {
+    /// Directory where crate facts are cached, overridden by `--cache-dir` and `CARGO_APRZ_CACHE_DIR`.
+    /// Relative paths are resolved against the directory containing the configuration file
+    #[serde(default)]
+    pub dir: Option<Utf8PathBuf>,
+
+    /// How long each kind of cached data is kept before being refreshed
+    #[serde(default)]
+    pub ttl: CacheTtlConfig,
+
+    /// How old each kind of data can get before reports warn about it
+    #[serde(default)]
+    pub stale_after: StalenessConfig,
+}
+
+/// How old the data of each source can get before reports warn about it. Data only gets this old when it can't
+/// be refreshed, such as with a read-only cache or when running offline.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct StalenessConfig {
+    /// Age of the crates.io database dump past which it is stale
+    #[serde(default = "default_crates_staleness", with = "humantime_serde")]
+    pub crates: Duration,
+
+    /// Age of the hosting data past which it is stale
+    #[serde(default = "default_hosting_staleness", with = "humantime_serde")]
+    pub hosting: Duration,
+
+    /// Age of the advisory database past which it is stale
+    #[serde(default = "default_advisories_staleness", with = "humantime_serde")]
+    pub advisories: Duration,
+}
+
+impl StalenessConfig {
+    /// Describe the data of the sources a crate was appraised with that is older than its threshold.
+    #[must_use]
+    pub fn stale_data(&self, timestamps: &DataTimestamps, now: DateTime<Utc>) -> Vec<String> {
+        [
+            ("crates.io", timestamps.crates, self.crates),
+            ("Hosting", timestamps.hosting, self.hosting),
+            ("Advisory", timestamps.advisories, self.advisories),
+        ]
+        .into_iter()
+        .filter_map(|(source, acquired_at, threshold)| {
+            let acquired_at = acquired_at?;
+            let age = now.signed_duration_since(acquired_at).to_std().ok()?;
+            (age > threshold).then(|| {
+                format!(
+                    "{source} data is stale, having been acquired {} day(s) ago on {}",
+                    age.as_secs() / 86400,
+                    acquired_at.format("%Y-%m-%d")
+                )
+            })
+        })
+        .collect()
+    }
+}
+
+impl Default for StalenessConfig {
+    fn default() -> Self {
+        Self {
+            crates: default_crates_staleness(),
+            hosting: default_hosting_staleness(),
+            advisories: default_advisories_staleness(),
+        }
+    }
+}
+
+/// How long the data of each provider is kept in the cache before being refreshed.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CacheTtlConfig {
+    /// Duration to keep the crates.io database dump, which is republished daily
+    #[serde(default = "default_crates_ttl", with = "humantime_serde")]
+    pub crates: Duration,
+
+    /// Duration to keep hosting (GitHub/Codeberg) data before re-fetching
+    #[serde(default = "default_hosting_ttl", with = "humantime_serde")]
+    pub hosting: Duration,
+
+    /// Duration to keep cached codebases before re-fetching
+    #[serde(default = "default_codebase_ttl", with = "humantime_serde")]
+    pub codebase: Duration,
+
+    /// Duration to keep cached coverage data before re-fetching
+    #[serde(default = "default_coverage_ttl", with = "humantime_serde")]
+    pub coverage: Duration,
+
+    /// Duration to keep the advisory database before re-downloading
+    #[serde(default = "default_advisories_ttl", with = "humantime_serde")]
+    pub advisories: Duration,
+
+    /// Duration to keep docs.rs documentation data before re-fetching
+    #[serde(default = "default_docs_ttl", with = "humantime_serde")]
+    pub docs: Duration,
+}
+
+impl Default for CacheTtlConfig {
+    fn default() -> Self {
+        Self {
+            crates: default_crates_ttl(),
+            hosting: default_hosting_ttl(),
+            codebase: default_codebase_ttl(),
+            coverage: default_coverage_ttl(),
+            advisories: default_advisories_ttl(),
+            docs: default_docs_ttl(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
@@ -38,6 +563,10 @@ pub struct Config {
     #[serde(default)]
     pub allow_list: Vec<AllowListEntry>,
 
+    /// Named fragments of expression text, which expressions refer to as `snippets.<name>`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub snippets: BTreeMap<String, String>,
+
     /// Expressions that must ALL evaluate to true for the crate to avoid being flagged as high risk
     #[serde(default)]
     pub high_risk: Vec<Expression>,
@@ -46,6 +575,10 @@ pub struct Config {
     #[serde(default)]
     pub eval: Vec<Expression>,
 
+    /// Synthetic crates along with the expression outcomes they must produce, checked by the `validate` command
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub expression_tests: Vec<ExpressionTest>,
+
     /// Score threshold below which a crate is considered medium risk (0..100)
     #[serde(default = "default_medium_risk_threshold")]
     pub medium_risk_threshold: f64,
@@ -54,25 +587,130 @@ pub struct Config {
     #[serde(default = "default_low_risk_threshold")]
     pub low_risk_threshold: f64,
 
-    /// Duration to keep crates.io cache data before re-downloading
-    #[serde(default = "default_cache_ttl", with = "humantime_serde")]
-    pub crates_cache_ttl: Duration,
+    /// How the eval expression outcomes are combined into the overall score
+    #[serde(default)]
+    pub aggregation: Aggregation,
 
-    /// Duration to keep hosting cache data before re-fetching
-    #[serde(default = "default_cache_ttl", with = "humantime_serde")]
-    pub hosting_cache_ttl: Duration,
+    /// Relative weight of each metric category for the `weighted_mean` and `geometric_mean` aggregations.
+    /// Categories not listed have a weight of 1
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub category_weights: BTreeMap<MetricCategory, f64>,
 
-    /// Duration to keep cached codebases before re-fetching
-    #[serde(default = "default_cache_ttl", with = "humantime_serde")]
-    pub codebase_cache_ttl: Duration,
+    /// Medium and low risk thresholds of the score of each metric category, given as `[medium, low]`, which
+    /// decide how category scores are colored in reports. Categories not listed use `medium_risk_threshold`
+    /// and `low_risk_threshold`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub category_bands: BTreeMap<MetricCategory, [f64; 2]>,
 
-    /// Duration to keep cached coverage data before re-fetching
-    #[serde(default = "default_cache_ttl", with = "humantime_serde")]
-    pub coverage_cache_ttl: Duration,
+    /// Relative weight of each dependency type in the workspace score reported by the `deps` command.
+    /// Types not listed use the defaults of 1 for standard, 0.5 for build, and 0.25 for dev dependencies
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub dependency_type_weights: BTreeMap<DependencyType, f64>,
 
-    /// Duration to keep the advisory database cached before re-downloading
-    #[serde(default = "default_cache_ttl", with = "humantime_serde")]
-    pub advisories_cache_ttl: Duration,
+    /// Cache settings
+    #[serde(default)]
+    pub cache: CacheConfig,
+
+    /// Deprecated in favor of `cache.ttl.crates`
+    #[serde(default, with = "humantime_serde", skip_serializing)]
+    pub crates_cache_ttl: Option<Duration>,
+
+    /// Deprecated in favor of `cache.ttl.hosting`
+    #[serde(default, with = "humantime_serde", skip_serializing)]
+    pub hosting_cache_ttl: Option<Duration>,
+
+    /// Deprecated in favor of `cache.ttl.codebase`
+    #[serde(default, with = "humantime_serde", skip_serializing)]
+    pub codebase_cache_ttl: Option<Duration>,
+
+    /// Deprecated in favor of `cache.ttl.coverage`
+    #[serde(default, with = "humantime_serde", skip_serializing)]
+    pub coverage_cache_ttl: Option<Duration>,
+
+    /// Deprecated in favor of `cache.ttl.advisories`
+    #[serde(default, with = "humantime_serde", skip_serializing)]
+    pub advisories_cache_ttl: Option<Duration>,
+
+    /// Hosting service settings
+    #[serde(default)]
+    pub hosting: HostingConfig,
+
+    /// Additional conditions for the risk checks
+    #[serde(default)]
+    pub check: CheckConfig,
+
+    /// How failures are treated for dependencies that aren't exposed at runtime
+    #[serde(default)]
+    pub dependency_scope: DependencyScopeConfig,
+
+    /// Optional webhook notification settings
+    #[serde(default)]
+    pub notify: Option<NotifyConfig>,
+
+    /// Optional history store settings
+    #[serde(default)]
+    pub history: Option<HistoryConfig>,
+
+    /// Rules raising alerts when the appraisal changes for the worse since the previous run, requiring `history`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alerts: Vec<AlertRule>,
+
+    /// Optional Dependency-Track SBOM upload settings
+    #[serde(default)]
+    pub dependency_track: Option<DependencyTrackConfig>,
+
+    /// Path to a cargo-deny `deny.toml` file whose rules are imported as additional high-risk expressions.
+    /// Relative paths are resolved against the directory containing this configuration file
+    #[serde(default)]
+    pub import_cargo_deny: Option<String>,
+
+    /// Policies on the trend of monthly downloads, added as high-risk or eval expressions depending on whether
+    /// they award points
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub downloads_trend: Vec<TrendPolicy>,
+
+    /// Policies on the trend of the number of dependent crates, added like the `downloads_trend` policies
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependents_trend: Vec<TrendPolicy>,
+
+    /// Policies on how long fixes for vulnerabilities affecting a crate may go unadopted, added like the
+    /// `downloads_trend` policies
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub advisory_patch_lag: Vec<AdvisoryPatchLagPolicy>,
+
+    /// Check the MSRV of dependencies against the `rust-version` of the workspace, when appraising workspace
+    /// dependencies. The check becomes a high-risk or eval expression depending on whether it awards points
+    #[serde(default)]
+    pub msrv_check: Option<MsrvCheckPolicy>,
+
+    /// Fail crates whose license conflicts with the license of the project, according to a built-in compatibility
+    /// matrix
+    #[serde(default)]
+    pub license_compatibility: Option<LicenseCompatibilityConfig>,
+
+    /// Mapping of team names to the workspace packages they own, used by `deps --per-team`.
+    /// Takes precedence over CODEOWNERS
+    #[serde(default)]
+    pub teams: BTreeMap<String, Vec<String>>,
+
+    /// Paths to WebAssembly modules computing additional metrics, requiring the `wasm_plugins` feature.
+    /// Relative paths are resolved against the directory containing this configuration file
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub wasm_plugins: Vec<Utf8PathBuf>,
+
+    /// Path to a rhai script run once all crates are appraised, able to adjust scores, add notes, or veto crates.
+    /// Requires the `script_hook` feature. Relative paths are resolved against the directory containing this
+    /// configuration file
+    #[serde(default)]
+    pub script_hook: Option<Utf8PathBuf>,
+
+    /// Signed policy packs whose snippets and expressions are added to those of this configuration
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub policy_packs: Vec<PolicyPackSource>,
+
+    /// Built-in presets adjusting the expressions for crates in some crates.io categories, such as `cryptography`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub presets: Vec<String>,
 }
 
 const fn default_medium_risk_threshold() -> f64 {
@@ -83,17 +721,116 @@ const fn default_low_risk_threshold() -> f64 {
     70.0
 }
 
-const fn default_cache_ttl() -> Duration {
+const fn default_crates_ttl() -> Duration {
+    Duration::from_hours(24)
+}
+
+const fn default_hosting_ttl() -> Duration {
+    Duration::from_hours(6)
+}
+
+const fn default_codebase_ttl() -> Duration {
+    Duration::from_hours(24 * 7)
+}
+
+const fn default_coverage_ttl() -> Duration {
+    Duration::from_hours(24 * 7)
+}
+
+const fn default_advisories_ttl() -> Duration {
+    Duration::from_hours(1)
+}
+
+const fn default_docs_ttl() -> Duration {
+    Duration::from_hours(24 * 30)
+}
+
+const fn default_crates_staleness() -> Duration {
     Duration::from_hours(24 * 7)
 }
 
+const fn default_hosting_staleness() -> Duration {
+    Duration::from_hours(24 * 30)
+}
+
+const fn default_advisories_staleness() -> Duration {
+    Duration::from_hours(24 * 7)
+}
+
+fn default_license_compatibility_name() -> String {
+    "Compatible License".to_string()
+}
+
+fn default_msrv_check_name() -> String {
+    "Compatible MSRV".to_string()
+}
+
+fn default_notify_template() -> String {
+    "cargo-aprz flagged {flagged} of {total} crate(s) at {min_risk} or above:\n{crates}".to_string()
+}
+
+const fn default_notify_min_risk() -> Risk {
+    Risk::High
+}
+
+const fn default_notify_on_advisories() -> bool {
+    true
+}
+
+fn default_dependency_track_project_version() -> String {
+    "latest".to_string()
+}
+
+const fn default_dependency_track_auto_create() -> bool {
+    true
+}
+
 impl Config {
+    /// Weight of a dependency type in the workspace score.
+    #[must_use]
+    pub fn dependency_type_weight(&self, dep_type: DependencyType) -> f64 {
+        self.dependency_type_weights
+            .get(&dep_type)
+            .copied()
+            .unwrap_or_else(|| dep_type.default_workspace_weight())
+    }
+
+    /// The risk band a score falls in for the given metric category.
+    #[must_use]
+    pub fn category_risk(&self, category: MetricCategory, score: f64) -> Risk {
+        let [medium, low] = self
+            .category_bands
+            .get(&category)
+            .copied()
+            .unwrap_or([self.medium_risk_threshold, self.low_risk_threshold]);
+        Risk::from_score(score, medium, low)
+    }
+
     /// Check if a crate is on the allow list.
     #[must_use]
     pub fn is_allowed(&self, name: &str, version: &Version) -> bool {
         self.allow_list.iter().any(|entry| entry.matches(name, version))
     }
 
+    /// Move the cache lifetimes set with the top-level `<provider>_cache_ttl` keys used before `[cache.ttl]` into the table.
+    fn apply_legacy_cache_ttls(&mut self) {
+        let ttl = &mut self.cache.ttl;
+        let legacy = [
+            ("crates_cache_ttl", self.crates_cache_ttl.take(), "crates", &mut ttl.crates),
+            ("hosting_cache_ttl", self.hosting_cache_ttl.take(), "hosting", &mut ttl.hosting),
+            ("codebase_cache_ttl", self.codebase_cache_ttl.take(), "codebase", &mut ttl.codebase),
+            ("coverage_cache_ttl", self.coverage_cache_ttl.take(), "coverage", &mut ttl.coverage),
+            ("advisories_cache_ttl", self.advisories_cache_ttl.take(), "advisories", &mut ttl.advisories),
+        ];
+
+        for (key, value, provider, target) in legacy {
+            if let Some(value) = value {
+                log::warn!(target: LOG_TARGET, "The `{key}` setting is deprecated, use `{provider}` in the `[cache.ttl]` table instead");
+                *target = value;
+            }
+        }
+    }
+
     /// Load configuration from a file or use defaults
     ///
     /// # Errors
@@ -116,8 +853,83 @@ impl Config {
             }
         };
 
-        let config: Self = toml::from_str(&text).into_app_err_with(|| format!("parsing configuration file '{final_path}'"))?;
+        let mut config: Self = toml::from_str(&text).into_app_err_with(|| format!("parsing configuration file '{final_path}'"))?;
+        config.apply_legacy_cache_ttls();
+
+        // Plugins are loaded first, since their metrics can be referenced by the rest of the configuration
+        for plugin_path in &config.wasm_plugins {
+            load_wasm_plugin(&final_path.parent().unwrap_or(workspace_root).join(plugin_path))?;
+        }
+
         config.validate()?;
+        config.expand_snippets()?;
+
+        if let Some(cache_dir) = &mut config.cache.dir
+            && cache_dir.is_relative()
+        {
+            *cache_dir = final_path.parent().unwrap_or(workspace_root).join(&*cache_dir);
+        }
+
+        if let Some(script_path) = &mut config.script_hook
+            && script_path.is_relative()
+        {
+            *script_path = final_path.parent().unwrap_or(workspace_root).join(&*script_path);
+        }
+
+        if let Some(history) = &mut config.history
+            && let Some(path) = history.url.strip_prefix("sqlite://")
+            && Utf8Path::new(path).is_relative()
+        {
+            history.url = format!("sqlite://{}", final_path.parent().unwrap_or(workspace_root).join(path));
+        }
+
+        if let Some(deny_path) = &config.import_cargo_deny {
+            let deny_path = final_path.parent().unwrap_or(workspace_root).join(deny_path);
+            let origin: Arc<str> = Arc::from(deny_path.strip_prefix(workspace_root).unwrap_or(&deny_path).as_str());
+            let imported = cargo_deny::import(&deny_path)?;
+            config.high_risk.extend(imported.iter().map(|expr| expr.with_origin(Arc::clone(&origin))));
+        }
+
+        let mut policy_expressions = config
+            .downloads_trend
+            .iter()
+            .map(|policy| (policy, "usage.downloads_trend_6mo", "Monthly downloads"))
+            .chain(
+                config
+                    .dependents_trend
+                    .iter()
+                    .map(|policy| (policy, "usage.dependents_trend", "The number of dependent crates")),
+            )
+            .map(|(policy, metric, subject)| Ok((policy.points.is_some(), policy.to_expression(metric, subject)?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        for policy in &config.advisory_patch_lag {
+            policy_expressions.push((policy.points.is_some(), policy.to_expression()?));
+        }
+
+        if let Some(check) = &config.license_compatibility {
+            policy_expressions.push((false, check.to_expression()?));
+        }
+
+        for (awards_points, expression) in policy_expressions {
+            if awards_points {
+                config.eval.push(expression);
+            } else {
+                config.high_risk.push(expression);
+            }
+        }
+
+        presets::apply_presets(&mut config)?;
+
+        // Record where each policy was defined, so reports can explain why a rule exists
+        let display_path = final_path.strip_prefix(workspace_root).unwrap_or(&final_path).as_str();
+        for expr in config.high_risk.iter_mut().chain(config.eval.iter_mut()).filter(|expr| expr.origin().is_none()) {
+            *expr = expr.with_origin(policy_origin(display_path, &text, expr.name()));
+        }
+
+        if let Some(policy) = &mut config.msrv_check {
+            policy.origin = Some(policy_origin(display_path, &text, &policy.name));
+        }
 
         Ok(config)
     }
@@ -132,6 +944,15 @@ impl Config {
         Ok(())
     }
 
+    /// Replace the snippet references in the expressions with the text of the snippets
+    fn expand_snippets(&mut self) -> Result<()> {
+        for expr in self.high_risk.iter_mut().chain(self.eval.iter_mut()) {
+            *expr = expr.with_snippets(&self.snippets)?;
+        }
+
+        Ok(())
+    }
+
     /// Validate configuration values
     ///
     /// # Errors
@@ -160,14 +981,210 @@ impl Config {
             ));
         }
 
+        if let Some(name) = self.snippets.keys().find(|name| !is_valid_snippet_name(name)) {
+            return Err(app_err!("snippet name '{name}' must consist of letters, digits, and underscores, and not start with a digit"));
+        }
+
+        for test in &self.expression_tests {
+            if test.expect.is_empty() && test.risk.is_none() {
+                return Err(app_err!("expression test '{}' must set 'expect' or 'risk'", test.name));
+            }
+
+            if let Some(name) = test.metrics.keys().find(|name| metric_category(name).is_none()) {
+                return Err(app_err!("expression test '{}' sets unknown metric '{name}'", test.name));
+            }
+        }
+
+        for host in &self.hosting.hosts {
+            if host.domain.is_empty() || host.domain.contains('/') {
+                return Err(app_err!("hosting.host domain must be a bare domain name, got '{}'", host.domain));
+            }
+
+            if !host.api_base_url.starts_with("https://") && !host.api_base_url.starts_with("http://") {
+                return Err(app_err!(
+                    "hosting.host '{}' must have an http(s) api_base_url, got '{}'",
+                    host.domain,
+                    host.api_base_url
+                ));
+            }
+        }
+
+        for (category, weight) in &self.category_weights {
+            if !weight.is_finite() || *weight < 0.0 {
+                return Err(app_err!("category_weights.{category} must be a non-negative number, got {weight}"));
+            }
+        }
+
+        for (category, [medium, low]) in &self.category_bands {
+            if !(0.0..=100.0).contains(medium) || !(0.0..=100.0).contains(low) || medium >= low {
+                return Err(app_err!(
+                    "category_bands.{category} must be [medium, low] thresholds between 0 and 100, medium below low, got [{medium}, {low}]"
+                ));
+            }
+        }
+
+        for (dep_type, weight) in &self.dependency_type_weights {
+            if !weight.is_finite() || *weight < 0.0 {
+                return Err(app_err!("dependency_type_weights.{dep_type} must be a non-negative number, got {weight}"));
+            }
+        }
+
+        for (category, min_score) in &self.check.min_category_scores {
+            if !(0.0..=100.0).contains(min_score) {
+                return Err(app_err!("check.min_category_scores.{category} must be between 0 and 100, got {min_score}"));
+            }
+        }
+
+        #[cfg(not(feature = "script_hook"))]
+        if let Some(path) = &self.script_hook {
+            return Err(app_err!("cannot run script hook '{path}', cargo-aprz was built without the 'script_hook' feature"));
+        }
+
+        if let Some(notify) = &self.notify
+            && !(notify.webhook_url.starts_with("https://") || notify.webhook_url.starts_with("http://"))
+        {
+            return Err(app_err!("notify.webhook_url must be an http(s) URL, got '{}'", notify.webhook_url));
+        }
+
+        self.validate_alerts()?;
+
+        let trend_policies = self
+            .downloads_trend
+            .iter()
+            .map(|policy| ("downloads_trend", policy))
+            .chain(self.dependents_trend.iter().map(|policy| ("dependents_trend", policy)));
+        for (section, policy) in trend_policies {
+            if !policy.max_decline.is_finite() || policy.max_decline <= 0.0 || policy.max_decline > 100.0 {
+                return Err(app_err!(
+                    "{section} '{}' max_decline must be a percentage above 0 and at most 100, got {}",
+                    policy.name,
+                    policy.max_decline
+                ));
+            }
+        }
+
+        if let Some(check) = &self.license_compatibility
+            && ProjectLicense::parse(&check.project_license).is_none()
+        {
+            return Err(app_err!(
+                "license_compatibility.project_license must be a single supported SPDX identifier or 'proprietary', got '{}'",
+                check.project_license
+            ));
+        }
+
+        for pack in &self.policy_packs {
+            validate_policy_pack(pack)?;
+        }
+
+        Ok(())
+    }
+
+    /// Validate alert rules and the history store they compare runs against
+    fn validate_alerts(&self) -> Result<()> {
+        if !self.alerts.is_empty() && self.history.is_none() {
+            return Err(app_err!("alerts require a [history] section to compare runs against"));
+        }
+
+        for rule in &self.alerts {
+            if rule.score_drop.is_none() && !rule.new_advisories && rule.workspace_score_drop.is_none() {
+                return Err(app_err!(
+                    "alert '{}' must set 'score_drop', 'new_advisories', or 'workspace_score_drop'",
+                    rule.name
+                ));
+            }
+
+            if let Some(drop) = rule.score_drop.into_iter().chain(rule.workspace_score_drop).find(|d| !d.is_finite() || *d < 0.0) {
+                return Err(app_err!("alert '{}' score drops must be non-negative numbers, got {drop}", rule.name));
+            }
+        }
+
+        if let Some(history) = &self.history {
+            crate::history::check_url(&history.url)?;
+        }
+
         Ok(())
     }
 }
 
+#[cfg(feature = "wasm_plugins")]
+fn load_wasm_plugin(path: &Utf8Path) -> Result<()> {
+    crate::metrics::load_plugin(path)
+}
+
+#[cfg(not(feature = "wasm_plugins"))]
+fn load_wasm_plugin(path: &Utf8Path) -> Result<()> {
+    Err(app_err!("cannot load WebAssembly plugin '{path}', cargo-aprz was built without the 'wasm_plugins' feature"))
+}
+
 impl Default for Config {
     fn default() -> Self {
-        toml::from_str(DEFAULT_CONFIG_TOML).expect("default_config.toml should be valid TOML that deserializes to Config")
+        let mut config: Self =
+            toml::from_str(DEFAULT_CONFIG_TOML).expect("default_config.toml should be valid TOML that deserializes to Config");
+        for expr in config.high_risk.iter_mut().chain(config.eval.iter_mut()) {
+            *expr = expr.with_origin("default");
+        }
+
+        config
+    }
+}
+
+/// Check that a policy pack source names a usable location and public key.
+fn validate_policy_pack(pack: &PolicyPackSource) -> Result<()> {
+    let location = pack.location()?;
+    let url = match location {
+        PackLocation::Url { url, .. } => url,
+        PackLocation::Git { repo, rev, .. } => {
+            if rev.starts_with('-') {
+                return Err(app_err!("policy_packs.rev must name a branch, tag, or commit, got '{rev}'"));
+            }
+            repo
+        }
+        PackLocation::Registry { registry, name } => {
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+                return Err(app_err!("policy_packs.name must consist of letters, digits, dashes, and underscores, got '{name}'"));
+            }
+            registry
+        }
+    };
+
+    let schemes: &[&str] = if matches!(location, PackLocation::Git { .. }) {
+        &["https://", "http://", "ssh://", "git@"]
+    } else {
+        &["https://", "http://"]
+    };
+    if !schemes.iter().any(|scheme| url.starts_with(scheme)) {
+        return Err(app_err!("policy_packs URL '{url}' must start with one of {}", schemes.join(", ")));
     }
+
+    if let Err(e) = minisign_verify::PublicKey::from_base64(&pack.public_key) {
+        return Err(app_err!("policy_packs.public_key for '{location}' is not a valid minisign public key: {e}"));
+    }
+
+    Ok(())
+}
+
+/// Where the policy of the given name is defined in a configuration file, as `path:line`, or just `path` when
+/// the line can't be found, such as for policies with a default name.
+pub fn policy_origin(path: &str, text: &str, name: &str) -> String {
+    text.lines()
+        .position(|line| !line.trim_start().starts_with('#') && defines_name(line, name))
+        .map_or_else(|| path.to_string(), |index| format!("{path}:{}", index + 1))
+}
+
+/// Whether a line of TOML assigns the given string to a `name` key.
+fn defines_name(line: &str, name: &str) -> bool {
+    line.match_indices("name").any(|(index, _)| {
+        let (before, key) = line.split_at(index);
+        let preceded_by_key_char = before.ends_with(|c: char| c.is_alphanumeric() || c == '_' || c == '-');
+        !preceded_by_key_char
+            && key
+                .strip_prefix("name")
+                .and_then(|value| value.trim_start().strip_prefix('='))
+                .map(str::trim_start)
+                .and_then(|value| value.strip_prefix(['"', '\'']))
+                .and_then(|value| value.strip_prefix(name))
+                .is_some_and(|rest| rest.starts_with(['"', '\'']))
+    })
 }
 
 #[cfg(test)]
@@ -216,6 +1233,182 @@ mod tests {
         assert!(config.validate().is_err());
     }
 
+    #[test]
+    fn test_aggregation() {
+        assert_eq!(Config::default().aggregation, Aggregation::Sum);
+
+        let config: Config = toml::from_str("aggregation = \"geometric_mean\"\ncategory_weights = { Usage = 0.5 }").unwrap();
+        assert_eq!(config.aggregation, Aggregation::GeometricMean);
+        config.validate().unwrap();
+
+        let config: Config = toml::from_str("category_weights = { Usage = -1 }").unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_dependency_type_weights() {
+        let config: Config = toml::from_str("dependency_type_weights = { dev = 0, build = 0.75 }").unwrap();
+        config.validate().unwrap();
+        assert!(config.dependency_type_weight(DependencyType::Dev).abs() < f64::EPSILON);
+        assert!((config.dependency_type_weight(DependencyType::Build) - 0.75).abs() < f64::EPSILON);
+        assert!((config.dependency_type_weight(DependencyType::Standard) - 1.0).abs() < f64::EPSILON);
+
+        let config: Config = toml::from_str("dependency_type_weights = { standard = -2 }").unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_cache_ttls() {
+        let config = Config::default();
+        assert_eq!(config.cache.ttl.crates, Duration::from_hours(24));
+        assert_eq!(config.cache.ttl.advisories, Duration::from_hours(1));
+
+        let config: Config = toml::from_str("[cache.ttl]\nhosting = \"30m\"\ndocs = \"2 weeks\"").unwrap();
+        assert_eq!(config.cache.ttl.hosting, Duration::from_mins(30));
+        assert_eq!(config.cache.ttl.docs, Duration::from_hours(24 * 14));
+        assert_eq!(config.cache.ttl.crates, default_crates_ttl());
+
+        let result: core::result::Result<Config, _> = toml::from_str("[cache.ttl]\npackages = \"1h\"");
+        assert!(result.is_err(), "unknown providers should be rejected");
+    }
+
+    #[test]
+    fn test_legacy_cache_ttls() {
+        let mut config: Config = toml::from_str("hosting_cache_ttl = \"30m\"\nadvisories_cache_ttl = \"2h\"").unwrap();
+        config.apply_legacy_cache_ttls();
+        assert_eq!(config.cache.ttl.hosting, Duration::from_mins(30));
+        assert_eq!(config.cache.ttl.advisories, Duration::from_hours(2));
+        assert_eq!(config.cache.ttl.crates, default_crates_ttl());
+        assert_eq!(config.hosting_cache_ttl, None);
+    }
+
+    #[test]
+    fn test_stale_data() {
+        let config: Config = toml::from_str("[cache.stale_after]\nhosting = \"2 days\"").unwrap();
+        let staleness = &config.cache.stale_after;
+        assert_eq!(staleness.crates, default_crates_staleness());
+
+        let now = DateTime::parse_from_rfc3339("2024-06-10T00:00:00Z").unwrap().with_timezone(&Utc);
+        let timestamps = DataTimestamps {
+            crates: Some(now - chrono::Duration::days(1)),
+            hosting: Some(now - chrono::Duration::days(3)),
+            advisories: None,
+        };
+        assert_eq!(
+            staleness.stale_data(&timestamps, now),
+            ["Hosting data is stale, having been acquired 3 day(s) ago on 2024-06-07"]
+        );
+        assert!(staleness.stale_data(&DataTimestamps::default(), now).is_empty());
+    }
+
+    #[test]
+    fn test_category_bands() {
+        let config: Config = toml::from_str("[category_bands]\nActivity = [60, 85]").unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.category_risk(MetricCategory::Activity, 70.0), Risk::Medium);
+        assert_eq!(config.category_risk(MetricCategory::Usage, 70.0), Risk::Low);
+        assert_eq!(config.category_risk(MetricCategory::Usage, 20.0), Risk::High);
+
+        let config: Config = toml::from_str("[category_bands]\nActivity = [85, 60]").unwrap();
+        assert!(config.validate().is_err());
+        let _ = toml::from_str::<Config>("[category_bands]\nActivity = [60]").unwrap_err();
+    }
+
+    #[test]
+    fn test_custom_hosts() {
+        let config: Config = toml::from_str(
+            r#"
+            [[hosting.host]]
+            domain = "github.example.com"
+            api_base_url = "https://github.example.com/api/v3"
+            kind = "github"
+
+            [[hosting.host]]
+            domain = "gitea.example.com"
+            api_base_url = "https://gitea.example.com/api/v1"
+            kind = "gitea"
+            tokens = ["abc"]
+            "#,
+        )
+        .unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.hosting.hosts.len(), 2);
+        assert_eq!(config.hosting.hosts[1].kind, crate::facts::HostKind::Gitea);
+        assert_eq!(config.hosting.hosts[1].tokens, ["abc"]);
+
+        let config: Config =
+            toml::from_str("[[hosting.host]]\ndomain = \"git.example.com\"\napi_base_url = \"git.example.com/api\"\nkind = \"gitea\"")
+                .unwrap();
+        assert!(config.validate().is_err());
+
+        let result: core::result::Result<Config, _> =
+            toml::from_str("[[hosting.host]]\ndomain = \"git.example.com\"\napi_base_url = \"https://git.example.com\"\nkind = \"gitlab\"");
+        assert!(result.is_err(), "unknown host kinds should be rejected");
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_load_resolves_relative_cache_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = Utf8PathBuf::try_from(tmp.path().to_path_buf()).unwrap();
+        fs::write(root.join("aprz.toml"), "[cache]\ndir = \"target/aprz-cache\"").unwrap();
+
+        let config = Config::load(&root, None).unwrap();
+        assert_eq!(config.cache.dir, Some(root.join("target/aprz-cache")));
+        assert!(Config::default().cache.dir.is_none());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_load_expands_snippets() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = Utf8PathBuf::try_from(tmp.path().to_path_buf()).unwrap();
+        fs::write(
+            root.join("aprz.toml"),
+            r#"
+[snippets]
+popular = "usage.total_downloads > 1000"
+
+[[high_risk]]
+name = "Popular"
+expression = "snippets.popular"
+
+[[eval]]
+name = "Popular and Active"
+expression = "snippets.popular && activity.open_issues < 10"
+"#,
+        )
+        .unwrap();
+
+        let config = Config::load(&root, None).unwrap();
+        assert_eq!(config.high_risk[0].expression(), "(usage.total_downloads > 1000)");
+        assert_eq!(config.eval[0].expression(), "(usage.total_downloads > 1000) && activity.open_issues < 10");
+
+        fs::write(root.join("aprz.toml"), "[[eval]]\nname = \"Broken\"\nexpression = \"snippets.missing\"").unwrap();
+        let err = Config::load(&root, None).unwrap_err();
+        assert!(format!("{err:#}").contains("unknown snippet 'missing'"));
+    }
+
+    #[test]
+    fn test_validate_snippet_names() {
+        let config: Config = toml::from_str("[snippets]\n\"not-valid\" = \"true\"").unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("snippet name 'not-valid'"));
+    }
+
+    #[test]
+    fn test_min_category_scores() {
+        let config: Config = toml::from_str("[check]\nmin_category_scores = { Activity = 40 }").unwrap();
+        assert_eq!(config.check.min_category_scores.get(&MetricCategory::Activity), Some(&40.0));
+        config.validate().unwrap();
+
+        let config: Config = toml::from_str("[check]\nmin_category_scores = { Activity = 140 }").unwrap();
+        assert!(config.validate().is_err());
+
+        let config: Config = toml::from_str("[check]\nmin_category_scores = { Maintenance = 40 }").unwrap();
+        assert_eq!(config.check.min_category_scores.get(&MetricCategory::Activity), Some(&40.0));
+    }
+
     #[test]
     fn test_validate_valid_thresholds() {
         let config = Config { medium_risk_threshold: 30.0, low_risk_threshold: 70.0, ..Config::default() };
@@ -335,4 +1528,215 @@ version = "^2.0"
         assert!(config.is_allowed("another-crate", &Version::new(2, 5, 0)));
         assert!(!config.is_allowed("another-crate", &Version::new(1, 0, 0)));
     }
+
+    #[test]
+    fn test_default_config_has_no_notify() {
+        let config = Config::default();
+        assert!(config.notify.is_none());
+    }
+
+    #[test]
+    fn test_notify_section_defaults() {
+        let config: Config = toml::from_str(
+            r#"
+[notify]
+webhook_url = "https://hooks.example.com/abc"
+"#,
+        )
+        .unwrap();
+        config.validate().unwrap();
+        let notify = config.notify.unwrap();
+        assert_eq!(notify.format, NotifyFormat::Slack);
+        assert_eq!(notify.min_risk, Risk::High);
+        assert!(notify.on_advisories);
+        assert!(notify.template.contains("{crates}"));
+    }
+
+    #[test]
+    fn test_notify_section_explicit_values() {
+        let config: Config = toml::from_str(
+            r#"
+[notify]
+webhook_url = "https://hooks.example.com/abc"
+format = "teams"
+template = "{flagged} bad"
+min_risk = "medium"
+on_advisories = false
+"#,
+        )
+        .unwrap();
+        let notify = config.notify.unwrap();
+        assert_eq!(notify.format, NotifyFormat::Teams);
+        assert_eq!(notify.min_risk, Risk::Medium);
+        assert!(!notify.on_advisories);
+        assert_eq!(notify.template, "{flagged} bad");
+    }
+
+    #[test]
+    fn test_policy_origin() {
+        let text = r#"# name = "Popular"
+[[eval]]
+name = "Popular"
+[[high_risk]]
+display_name = 'Safe'
+high_risk = [{ name = 'Safe', expression = 'true' }]
+"#;
+        assert_eq!(policy_origin("aprz.toml", text, "Popular"), "aprz.toml:3");
+        assert_eq!(policy_origin("aprz.toml", text, "Safe"), "aprz.toml:6");
+        assert_eq!(policy_origin("aprz.toml", text, "Pop"), "aprz.toml");
+
+        let config = Config::default();
+        assert!(config.high_risk.iter().chain(&config.eval).all(|e| e.origin() == Some("default")));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_load_config_imports_cargo_deny() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config_path = Utf8PathBuf::try_from(tmp.path().join("aprz.toml")).unwrap();
+        fs::write(&config_path, "import_cargo_deny = \"deny.toml\"\n").unwrap();
+        fs::write(tmp.path().join("deny.toml"), "[licenses]\nallow = [\"MIT\"]\n").unwrap();
+
+        let workspace_root = Utf8PathBuf::try_from(tmp.path().to_path_buf()).unwrap();
+        let config = Config::load(&workspace_root, Some(&config_path)).unwrap();
+        assert!(config.high_risk.iter().any(|e| e.name() == "cargo-deny: Allowed License"));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_load_config_adds_policy_expressions() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config_path = Utf8PathBuf::try_from(tmp.path().join("aprz.toml")).unwrap();
+        fs::write(
+            &config_path,
+            r#"
+[[downloads_trend]]
+name = "Collapsing Downloads"
+max_decline = 60
+
+[[downloads_trend]]
+name = "Steady Downloads"
+max_decline = 20
+points = 5
+
+[[dependents_trend]]
+name = "Abandoned"
+max_decline = 50
+
+[[advisory_patch_lag]]
+name = "Fixes Adopted Quickly"
+max_days = 30
+points = 10
+"#,
+        )
+        .unwrap();
+
+        let workspace_root = Utf8PathBuf::try_from(tmp.path().to_path_buf()).unwrap();
+        let config = Config::load(&workspace_root, Some(&config_path)).unwrap();
+        assert!(config.high_risk.iter().any(|e| e.name() == "Collapsing Downloads"));
+        assert!(config.eval.iter().any(|e| e.name() == "Steady Downloads"));
+        assert!(config.high_risk.iter().any(|e| e.name() == "Abandoned"));
+        assert!(config.eval.iter().any(|e| e.name() == "Fixes Adopted Quickly"));
+
+        let origin = |name: &str| config.high_risk.iter().chain(&config.eval).find(|e| e.name() == name).and_then(Expression::origin);
+        assert_eq!(origin("Collapsing Downloads"), Some("aprz.toml:3"));
+        assert_eq!(origin("Steady Downloads"), Some("aprz.toml:7"));
+
+        let config = Config {
+            downloads_trend: vec![TrendPolicy {
+                name: "Invalid".into(),
+                max_decline: 150.0,
+                points: None,
+            }],
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_msrv_check_expression() {
+        let config: Config = toml::from_str("[msrv_check]\npoints = 4\n").unwrap();
+        let policy = config.msrv_check.unwrap();
+        assert_eq!(policy.name, "Compatible MSRV");
+
+        let expression = policy.to_expression("1.70").unwrap();
+        assert_eq!(expression.name(), "Compatible MSRV");
+        assert_eq!(expression.points(), Some(4));
+    }
+
+    #[test]
+    fn test_license_compatibility() {
+        let config: Config = toml::from_str("[license_compatibility]\nproject_license = \"proprietary\"\n").unwrap();
+        config.validate().unwrap();
+        let expression = config.license_compatibility.unwrap().to_expression().unwrap();
+        assert_eq!(expression.name(), "Compatible License");
+        assert_eq!(expression.expression(), "license_compatible(crate.license, 'proprietary')");
+
+        let config: Config = toml::from_str("[license_compatibility]\nproject_license = \"MIT OR Apache-2.0\"\n").unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_dependency_track_section_defaults() {
+        let config: Config = toml::from_str(
+            r#"
+[dependency_track]
+url = "https://dt.example.com"
+project_name = "my-service"
+"#,
+        )
+        .unwrap();
+        let dt = config.dependency_track.unwrap();
+        assert_eq!(dt.project_version, "latest");
+        assert!(dt.auto_create);
+    }
+
+    #[test]
+    fn test_validate_notify_rejects_non_http_url() {
+        let config: Config = toml::from_str(
+            r#"
+[notify]
+webhook_url = "hooks.example.com/abc"
+"#,
+        )
+        .unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "history_sqlite")]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_load_resolves_relative_history_path() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = Utf8PathBuf::try_from(tmp.path().to_path_buf()).unwrap();
+        fs::write(root.join("aprz.toml"), "[history]\nurl = \"sqlite://aprz-history.db\"").unwrap();
+
+        let config = Config::load(&root, None).unwrap();
+        assert_eq!(config.history.unwrap().url, format!("sqlite://{}", root.join("aprz-history.db")));
+    }
+
+    #[test]
+    fn test_validate_history_url() {
+        let config: Config = toml::from_str("[history]\nurl = \"mysql://localhost/aprz\"").unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_alerts() {
+        let config: Config = toml::from_str("[[alerts]]\nname = \"Regression\"\nscore_drop = 10").unwrap();
+        assert!(format!("{:#}", config.validate().unwrap_err()).contains("require a [history] section"));
+
+        let config: Config = toml::from_str(
+            r#"
+[history]
+url = "sqlite://history.db"
+
+[[alerts]]
+name = "Regression"
+dependency_types = ["standard"]
+"#,
+        )
+        .unwrap();
+        assert!(format!("{:#}", config.validate().unwrap_err()).contains("must set"));
+    }
 }
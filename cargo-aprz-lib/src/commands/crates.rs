@@ -3,6 +3,8 @@ use super::common::{Common, CommonArgs};
 use crate::Result;
 use crate::facts::CrateRef;
 use clap::Parser;
+use ohno::bail;
+use semver::Version;
 
 #[derive(Parser, Debug)]
 pub struct CratesArgs {
@@ -10,13 +12,61 @@ pub struct CratesArgs {
     #[arg(value_name = "CRATE")]
     pub crates: Vec<CrateRef>,
 
+    /// Lay out the metrics and policy outcomes of the crate side by side with those of another of its versions,
+    /// such as a candidate upgrade, instead of appraising the crate on its own
+    #[arg(long, value_name = "VERSION")]
+    pub compare_with: Option<Version>,
+
     #[command(flatten)]
     pub common: CommonArgs,
 }
 
 pub async fn process_crates<H: Host>(host: &mut H, args: &CratesArgs) -> Result<()> {
+    let comparison = args
+        .compare_with
+        .as_ref()
+        .map(|version| version_comparison(&args.crates, version))
+        .transpose()?;
+
     let mut common = Common::new(host, &args.common).await?;
+
+    if let Some(crates) = comparison {
+        let crate_facts = common.process_crates(&crates, true).await?;
+        return common.compare(crate_facts.into_iter()).await;
+    }
+
     let crate_facts = common.process_crates(&args.crates, true).await?;
 
-    common.report(crate_facts.into_iter())
+    common.report(crate_facts.into_iter()).await
+}
+
+/// The two versions of the single crate to compare with `--compare-with`.
+fn version_comparison(crates: &[CrateRef], version: &Version) -> Result<[CrateRef; 2]> {
+    let [crate_ref] = crates else {
+        bail!("--compare-with requires exactly one crate");
+    };
+
+    let other = CrateRef::new(crate_ref.name(), Some(version.clone()));
+    if *crate_ref == other {
+        bail!("cannot compare {crate_ref} with the same version");
+    }
+
+    Ok([crate_ref.clone(), other])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_comparison() {
+        let version = Version::new(2, 0, 0);
+        let crates = version_comparison(&["foo@1.2.0".parse().unwrap()], &version).unwrap();
+        assert_eq!(crates[0].to_string(), "foo@1.2.0");
+        assert_eq!(crates[1].to_string(), "foo@2.0.0");
+
+        let _ = version_comparison(&["foo".parse().unwrap(), "bar".parse().unwrap()], &version).unwrap_err();
+        let _ = version_comparison(&[], &version).unwrap_err();
+        let _ = version_comparison(&["foo@2.0.0".parse().unwrap()], &version).unwrap_err();
+    }
 }
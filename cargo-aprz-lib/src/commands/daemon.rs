@@ -0,0 +1,393 @@
+//! Periodic appraisal of workspaces.
+//!
+//! The daemon appraises the dependencies of a set of workspaces every `--interval`, exactly as `cargo aprz deps`
+//! would, writing the latest HTML report of each workspace to the output directory. Each run refreshes the
+//! cached crate facts that have expired, and records its outcome in the history store when the configuration
+//! has a `[history]` section, which in turn evaluates any `[[alerts]]` rules. With the `server` feature, the
+//! latest reports can also be served on a dashboard with `--listen`.
+
+use super::deps::AppraisalArgs;
+use super::submit::{file_stem, workspace_name};
+use super::{Host, process_dependencies};
+use crate::Result;
+use camino::Utf8PathBuf;
+use chrono::{DateTime, Local, NaiveTime};
+use clap::Parser;
+use core::time::Duration;
+use ohno::{IntoAppError, bail};
+use std::fs;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "server")]
+use core::net::SocketAddr;
+
+const LOG_TARGET: &str = "    daemon";
+
+#[derive(Parser, Debug)]
+pub struct DaemonArgs {
+    /// Workspace to appraise on every run, given as the path of its Cargo.toml file or of its root directory.
+    /// Can be repeated
+    #[arg(long, value_name = "PATH", required = true)]
+    pub workspace: Vec<Utf8PathBuf>,
+
+    /// Time between runs, such as `12h` or `1day`
+    #[arg(long, value_name = "DURATION", default_value = "24h", value_parser = parse_interval)]
+    pub interval: Duration,
+
+    /// Local time of day of the first run, such as `02:30`, so that runs and the cache refreshes they
+    /// perform happen off-peak (default is to start right away)
+    #[arg(long, value_name = "HH:MM", value_parser = parse_time_of_day)]
+    pub start_at: Option<NaiveTime>,
+
+    /// Directory where the latest HTML report of each workspace is written
+    #[arg(long, value_name = "PATH")]
+    pub output_dir: Utf8PathBuf,
+
+    /// Address on which to serve a dashboard of the latest reports
+    #[cfg(feature = "server")]
+    #[arg(long, value_name = "ADDR")]
+    pub listen: Option<SocketAddr>,
+
+    #[command(flatten)]
+    pub appraisal: AppraisalArgs,
+}
+
+/// A workspace appraised by the daemon
+#[derive(Debug, Clone)]
+struct Workspace {
+    name: String,
+    manifest_path: Utf8PathBuf,
+    report_file: String,
+}
+
+/// Outcome of the latest run of a workspace
+#[derive(Debug, Clone)]
+#[cfg_attr(not(feature = "server"), expect(dead_code, reason = "Only the dashboard reads the outcomes of the runs"))]
+struct RunStatus {
+    workspace: String,
+    report_file: String,
+    finished_at: DateTime<Local>,
+    error: Option<String>,
+}
+
+type Statuses = Arc<Mutex<Vec<RunStatus>>>;
+
+pub async fn run_daemon<H: Host>(host: &mut H, args: &DaemonArgs) -> Result<()> {
+    let workspaces = resolve_workspaces(&args.workspace)?;
+    fs::create_dir_all(&args.output_dir).into_app_err_with(|| format!("creating output directory '{}'", args.output_dir))?;
+
+    let statuses: Statuses = Arc::default();
+
+    #[cfg(feature = "server")]
+    if let Some(listen) = args.listen {
+        dashboard::start(listen, args.output_dir.clone(), Arc::clone(&statuses)).await?;
+        let _ = writeln!(host.output(), "Serving the latest reports on http://{listen}");
+    }
+
+    if let Some(start_at) = args.start_at {
+        let delay = delay_until(start_at, Local::now());
+        let _ = writeln!(host.output(), "Waiting until {start_at} for the first run");
+        tokio::time::sleep(delay).await;
+    }
+
+    #[expect(clippy::infinite_loop, reason = "The daemon runs until the process is interrupted")]
+    loop {
+        let started = tokio::time::Instant::now();
+
+        for workspace in &workspaces {
+            let status = run_once(host, args, workspace).await;
+            let mut statuses = statuses.lock().expect("daemon status lock must not be poisoned");
+            statuses.retain(|s| s.workspace != status.workspace);
+            statuses.push(status);
+            statuses.sort_by(|a, b| a.workspace.cmp(&b.workspace));
+        }
+
+        let next = Local::now() + args.interval.saturating_sub(started.elapsed());
+        let _ = writeln!(host.output(), "Next run at {}", next.format("%Y-%m-%d %H:%M"));
+        tokio::time::sleep_until(started + args.interval).await;
+    }
+}
+
+/// Appraise one workspace, returning the outcome instead of failing so that the daemon keeps going.
+async fn run_once<H: Host>(host: &mut H, args: &DaemonArgs, workspace: &Workspace) -> RunStatus {
+    let _ = writeln!(host.output(), "Appraising workspace '{}'", workspace.name);
+
+    let report_path = args.output_dir.join(&workspace.report_file);
+    let result = match args.appraisal.deps_args(&workspace.manifest_path, &[("--html", &report_path)]) {
+        Ok(deps_args) => process_dependencies(host, &deps_args).await,
+        Err(e) => Err(e),
+    };
+
+    let error = result.err().map(|e| {
+        log::error!(target: LOG_TARGET, "Could not appraise workspace '{}': {e:#}", workspace.name);
+        let _ = writeln!(host.error(), "ERROR: could not appraise workspace '{}': {e}", workspace.name);
+        e.to_string()
+    });
+
+    RunStatus {
+        workspace: workspace.name.clone(),
+        report_file: workspace.report_file.clone(),
+        finished_at: Local::now(),
+        error,
+    }
+}
+
+fn resolve_workspaces(paths: &[Utf8PathBuf]) -> Result<Vec<Workspace>> {
+    let mut workspaces: Vec<Workspace> = Vec::with_capacity(paths.len());
+
+    for path in paths {
+        let manifest_path = if path.is_dir() { path.join("Cargo.toml") } else { path.clone() };
+        let manifest_path = manifest_path
+            .canonicalize_utf8()
+            .into_app_err_with(|| format!("finding workspace manifest '{manifest_path}'"))?;
+        let root = manifest_path.parent().unwrap_or(&manifest_path);
+        let name = workspace_name(root);
+
+        if workspaces.iter().any(|w| w.name == name) {
+            bail!("more than one workspace is named '{name}', their reports would overwrite each other");
+        }
+
+        let report_file = format!("{}.html", file_stem(&name));
+        workspaces.push(Workspace {
+            name,
+            manifest_path,
+            report_file,
+        });
+    }
+
+    Ok(workspaces)
+}
+
+/// How long to wait from `now` until the next occurrence of the `at` time of day.
+fn delay_until(at: NaiveTime, now: DateTime<Local>) -> Duration {
+    let today = now.date_naive().and_time(at);
+    let next = if today > now.naive_local() {
+        today
+    } else {
+        today + chrono::Duration::days(1)
+    };
+    (next - now.naive_local()).to_std().unwrap_or_default()
+}
+
+fn parse_interval(s: &str) -> Result<Duration, String> {
+    let interval = humantime_serde::re::humantime::parse_duration(s).map_err(|e| e.to_string())?;
+    if interval.is_zero() {
+        return Err("the interval must be longer than zero".into());
+    }
+    Ok(interval)
+}
+
+fn parse_time_of_day(s: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(s, "%H:%M").map_err(|e| format!("expected a time such as 02:30: {e}"))
+}
+
+/// A dashboard of the latest report of every workspace.
+#[cfg(feature = "server")]
+mod dashboard {
+    use super::{LOG_TARGET, RunStatus, Statuses};
+    use crate::Result;
+    use axum::Router;
+    use axum::extract::{Path, State};
+    use axum::http::StatusCode;
+    use axum::response::Html;
+    use axum::routing::get;
+    use camino::Utf8PathBuf;
+    use core::fmt::Write as _;
+    use core::net::SocketAddr;
+    use ohno::IntoAppError;
+    use std::sync::Arc;
+
+    struct Dashboard {
+        output_dir: Utf8PathBuf,
+        statuses: Statuses,
+    }
+
+    /// Start serving the dashboard in the background.
+    pub async fn start(listen: SocketAddr, output_dir: Utf8PathBuf, statuses: Statuses) -> Result<()> {
+        let app = Router::new()
+            .route("/", get(index))
+            .route("/reports/{file}", get(report))
+            .with_state(Arc::new(Dashboard { output_dir, statuses }));
+
+        let listener = tokio::net::TcpListener::bind(listen)
+            .await
+            .into_app_err_with(|| format!("listening on {listen}"))?;
+
+        drop(tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, app).await {
+                log::error!(target: LOG_TARGET, "Stopped serving the dashboard: {e}");
+            }
+        }));
+
+        Ok(())
+    }
+
+    async fn index(State(dashboard): State<Arc<Dashboard>>) -> Html<String> {
+        let statuses = dashboard.statuses.lock().expect("daemon status lock must not be poisoned").clone();
+        Html(render_index(&statuses))
+    }
+
+    async fn report(State(dashboard): State<Arc<Dashboard>>, Path(file): Path<String>) -> Result<Html<String>, StatusCode> {
+        // Only serve the reports of known workspaces, so that no other file can be reached
+        let known = dashboard
+            .statuses
+            .lock()
+            .expect("daemon status lock must not be poisoned")
+            .iter()
+            .any(|s| s.report_file == file);
+        if !known {
+            return Err(StatusCode::NOT_FOUND);
+        }
+
+        tokio::fs::read_to_string(dashboard.output_dir.join(&file))
+            .await
+            .map(Html)
+            .map_err(|e| {
+                log::warn!(target: LOG_TARGET, "Could not read report '{file}': {e}");
+                StatusCode::NOT_FOUND
+            })
+    }
+
+    fn escape(s: &str) -> String {
+        s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+    }
+
+    pub(super) fn render_index(statuses: &[RunStatus]) -> String {
+        let mut html = String::from(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>cargo-aprz daemon</title>\n\
+             <style>body{font-family:sans-serif}table{border-collapse:collapse}th,td{padding:4px 8px;border:1px solid #ccc}\
+             .failed{color:#c00}</style>\n</head>\n<body>\n<h1>Latest reports</h1>\n",
+        );
+
+        if statuses.is_empty() {
+            html.push_str("<p>The first run is still pending.</p>\n");
+        } else {
+            html.push_str("<table>\n<tr><th>Workspace</th><th>Last run</th><th>Outcome</th></tr>\n");
+
+            for status in statuses {
+                let workspace = if status.error.is_some() {
+                    escape(&status.workspace)
+                } else {
+                    format!("<a href=\"/reports/{}\">{}</a>", escape(&status.report_file), escape(&status.workspace))
+                };
+                let outcome = status
+                    .error
+                    .as_deref()
+                    .map_or_else(|| "Appraised".to_string(), |e| format!("<span class=\"failed\">{}</span>", escape(e)));
+
+                let _ = writeln!(
+                    html,
+                    "<tr><td>{workspace}</td><td>{}</td><td>{outcome}</td></tr>",
+                    status.finished_at.format("%Y-%m-%d %H:%M")
+                );
+            }
+
+            html.push_str("</table>\n");
+        }
+
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::common::LogLevel;
+    use camino::Utf8Path;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("12h").unwrap(), Duration::from_secs(12 * 3600));
+        assert_eq!(parse_interval("1day").unwrap(), Duration::from_secs(24 * 3600));
+        let _ = parse_interval("0s").unwrap_err();
+        let _ = parse_interval("soon").unwrap_err();
+    }
+
+    #[test]
+    fn test_delay_until() {
+        let at = parse_time_of_day("02:30").unwrap();
+
+        let now = Local.with_ymd_and_hms(2025, 3, 10, 1, 0, 0).unwrap();
+        assert_eq!(delay_until(at, now), Duration::from_secs(90 * 60));
+
+        let now = Local.with_ymd_and_hms(2025, 3, 10, 3, 30, 0).unwrap();
+        assert_eq!(delay_until(at, now), Duration::from_secs(23 * 3600));
+
+        let _ = parse_time_of_day("2am").unwrap_err();
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_resolve_workspaces() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(tmp.path()).unwrap();
+        for name in ["payments", "search"] {
+            fs::create_dir_all(root.join(name)).unwrap();
+            fs::write(root.join(name).join("Cargo.toml"), "[package]\n").unwrap();
+        }
+
+        let workspaces = resolve_workspaces(&[root.join("payments"), root.join("search").join("Cargo.toml")]).unwrap();
+        assert_eq!(workspaces[0].name, "payments");
+        assert_eq!(workspaces[0].report_file, "payments.html");
+        assert!(workspaces[0].manifest_path.ends_with("payments/Cargo.toml"));
+        assert_eq!(workspaces[1].name, "search");
+
+        let _ = resolve_workspaces(&[root.join("payments"), root.join("payments")]).unwrap_err();
+        let _ = resolve_workspaces(&[root.join("missing")]).unwrap_err();
+    }
+
+    #[test]
+    fn test_deps_args() {
+        let args = DaemonArgs::try_parse_from([
+            "daemon",
+            "--workspace",
+            "payments",
+            "--output-dir",
+            "reports",
+            "--config",
+            "aprz.toml",
+            "--log-level",
+            "info",
+        ])
+        .unwrap();
+        assert_eq!(args.interval, Duration::from_secs(24 * 3600));
+
+        let report = Utf8Path::new("reports/payments.html");
+        let deps = args
+            .appraisal
+            .deps_args(Utf8Path::new("/src/payments/Cargo.toml"), &[("--html", report)])
+            .unwrap();
+        assert_eq!(deps.common.manifest_path, "/src/payments/Cargo.toml");
+        assert_eq!(deps.common.html.as_deref(), Some(report));
+        assert_eq!(deps.common.config.as_deref(), Some(Utf8Path::new("aprz.toml")));
+        assert_eq!(deps.common.log_level, LogLevel::Info);
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn test_render_index() {
+        let statuses = [
+            RunStatus {
+                workspace: "payments".into(),
+                report_file: "payments.html".into(),
+                finished_at: Local::now(),
+                error: None,
+            },
+            RunStatus {
+                workspace: "search<b>".into(),
+                report_file: "search_b_.html".into(),
+                finished_at: Local::now(),
+                error: Some("no Cargo.lock".into()),
+            },
+        ];
+
+        let html = dashboard::render_index(&statuses);
+        assert!(html.contains("<a href=\"/reports/payments.html\">payments</a>"));
+        assert!(html.contains("search&lt;b&gt;"));
+        assert!(html.contains("no Cargo.lock"));
+        assert!(dashboard::render_index(&[]).contains("still pending"));
+    }
+}
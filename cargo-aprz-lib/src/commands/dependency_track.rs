@@ -0,0 +1,95 @@
+//! Uploading `CycloneDX` SBOMs to an OWASP Dependency-Track server.
+
+use super::config::DependencyTrackConfig;
+use crate::Result;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use ohno::{IntoAppError, bail};
+use serde_json::json;
+
+const LOG_TARGET: &str = " dep-track";
+
+/// Build the JSON body expected by Dependency-Track's `PUT /api/v1/bom` endpoint.
+fn build_request_body(config: &DependencyTrackConfig, bom: &str) -> serde_json::Value {
+    json!({
+        "projectName": config.project_name,
+        "projectVersion": config.project_version,
+        "autoCreate": config.auto_create,
+        "bom": STANDARD.encode(bom),
+    })
+}
+
+/// Upload a `CycloneDX` SBOM to the configured Dependency-Track server.
+pub async fn upload_bom(config: &DependencyTrackConfig, api_key: &str, bom: &str) -> Result<()> {
+    let url = format!("{}/api/v1/bom", config.url.trim_end_matches('/'));
+
+    log::info!(target: LOG_TARGET, "Uploading SBOM for project '{}' to '{url}'", config.project_name);
+    let response = reqwest::Client::new()
+        .put(&url)
+        .header("X-Api-Key", api_key)
+        .json(&build_request_body(config, bom))
+        .send()
+        .await
+        .into_app_err("uploading SBOM to Dependency-Track")?;
+
+    let status = response.status();
+    if !status.is_success() {
+        bail!("Dependency-Track rejected the SBOM upload with HTTP status {status}");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn make_config(url: &str) -> DependencyTrackConfig {
+        DependencyTrackConfig {
+            url: url.to_string(),
+            project_name: "my-service".to_string(),
+            project_version: "latest".to_string(),
+            auto_create: true,
+        }
+    }
+
+    #[test]
+    fn test_build_request_body() {
+        let body = build_request_body(&make_config("https://dt.example.com"), "{}");
+        assert_eq!(body["projectName"], "my-service");
+        assert_eq!(body["projectVersion"], "latest");
+        assert_eq!(body["autoCreate"], true);
+        assert_eq!(body["bom"], "e30=");
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore = "Miri cannot call CreateIoCompletionPort")]
+    async fn test_upload_bom_sends_api_key() {
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/api/v1/bom"))
+            .and(header("X-Api-Key", "secret"))
+            .and(body_partial_json(json!({ "projectName": "my-service" })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = make_config(&format!("{}/", server.uri()));
+        upload_bom(&config, "secret", "{}").await.unwrap();
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore = "Miri cannot call CreateIoCompletionPort")]
+    async fn test_upload_bom_reports_rejection() {
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let _ = upload_bom(&make_config(&server.uri()), "bad", "{}").await.unwrap_err();
+    }
+}
@@ -1,16 +1,26 @@
 use super::Host;
-use super::common::{Common, CommonArgs};
+use super::common::{Common, CommonArgs, LogLevel};
+use super::config::Config;
+use super::submit::git_output;
+use super::teams::{TeamResolver, pattern_to_regex};
 use crate::Result;
 use crate::facts::CrateRef;
-use cargo_metadata::{CargoOpt, Dependency, DependencyKind, Node, Package, PackageId};
-use clap::{Parser, ValueEnum};
+use crate::reports::{DependencyGraph, Impact};
+use cargo_metadata::{CargoOpt, Dependency, DependencyKind, Node, Package, PackageId, Source};
+use camino::{Utf8Path, Utf8PathBuf};
+use clap::{Args, Parser, ValueEnum};
 use ohno::{IntoAppError, bail};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use crate::{HashMap, HashSet};
+use std::collections::BTreeMap;
 use strum::{Display, EnumString};
 
+const LOG_TARGET: &str = "      deps";
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, ValueEnum, Deserialize, Serialize, Display, EnumString)]
 #[value(rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
 #[strum(serialize_all = "lowercase")]
 pub enum DependencyType {
     /// Regular production dependencies
@@ -23,7 +33,20 @@ pub enum DependencyType {
     Build,
 }
 
+impl DependencyType {
+    /// Weight of the dependency type in the workspace score when the configuration doesn't set one.
+    #[must_use]
+    pub const fn default_workspace_weight(self) -> f64 {
+        match self {
+            Self::Standard => 1.0,
+            Self::Build => 0.5,
+            Self::Dev => 0.25,
+        }
+    }
+}
+
 #[derive(Parser, Debug)]
+#[expect(clippy::struct_excessive_bools, reason = "The deps command has several independent switches")]
 pub struct DepsArgs {
     /// Comma-separated list of dependency types to appraise
     #[arg(
@@ -54,10 +77,94 @@ pub struct DepsArgs {
     #[arg(long, help_heading = "Package Selection")]
     pub workspace: bool,
 
+    /// Exclude packages from processing, given as name globs such as `*-generated`, or as paths relative to the
+    /// workspace root such as `vendor/`
+    #[arg(long, value_name = "SPEC", help_heading = "Package Selection")]
+    pub exclude: Vec<String>,
+
+    /// Also write a copy of each report file per team, containing only the dependencies of the packages the team owns.
+    /// Ownership comes from the `teams` configuration table or, failing that, from CODEOWNERS
+    #[arg(long, help_heading = "Report Output")]
+    pub per_team: bool,
+
+    /// Path to the CODEOWNERS file used by `--per-team` (default is `.github/CODEOWNERS`, `CODEOWNERS`, or `docs/CODEOWNERS`)
+    #[arg(long, value_name = "PATH", requires = "per_team", help_heading = "Report Output")]
+    pub codeowners: Option<Utf8PathBuf>,
+
+    /// Give crates that many other crates depend upon more weight in the workspace score
+    #[arg(long, help_heading = "Report Output")]
+    pub weight_by_centrality: bool,
+
+    /// Place each crate on a likelihood × impact risk matrix, where impact reflects how much of the dependency
+    /// graph relies on the crate
+    #[arg(long, help_heading = "Report Output")]
+    pub risk_matrix: bool,
+
+    /// Limit the analysis to dependencies at most N levels away from the workspace packages, 1 being their direct
+    /// dependencies, to avoid appraising the full dependency closure on every run
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    pub depth: Option<u32>,
+
+    /// Output the dependency graph of the appraised crates, colored by risk and labeled with scores, to a `GraphViz` DOT file
+    #[arg(long, value_name = "PATH", help_heading = "Report Output")]
+    pub graph: Option<Utf8PathBuf>,
+
+    /// Only appraise the crates the current branch adds or bumps to a new version, by comparing `Cargo.lock` with its
+    /// state at the merge base of `HEAD` and `--base`. Combined with `--error-if-high-risk`, this gates pull requests
+    /// on what they introduce
+    #[arg(long, help_heading = "Package Selection")]
+    pub changed_only: bool,
+
+    /// Git revision the current branch is compared against by `--changed-only`
+    #[arg(long, value_name = "REF", default_value = "origin/main", requires = "changed_only", help_heading = "Package Selection")]
+    pub base: String,
+
     #[command(flatten)]
     pub common: CommonArgs,
 }
 
+/// Options of the appraisals run by the commands covering several workspaces, forwarded to the `deps` command
+#[derive(Args, Debug)]
+pub struct AppraisalArgs {
+    /// Path to configuration file (default is `aprz.toml`)
+    #[arg(long, short = 'c', value_name = "PATH")]
+    pub config: Option<Utf8PathBuf>,
+
+    /// Directory where crate facts are cached (default is the `cache.dir` configuration setting, or the platform's cache directory)
+    #[arg(long, value_name = "PATH", env = "CARGO_APRZ_CACHE_DIR")]
+    pub cache_dir: Option<Utf8PathBuf>,
+
+    /// Set the logging level for diagnostic output
+    #[arg(long, value_name = "LEVEL", default_value = "none")]
+    pub log_level: LogLevel,
+}
+
+impl AppraisalArgs {
+    /// Build the arguments of the `deps` command appraising the workspace at `manifest_path`, writing the
+    /// given reports, such as `("--html", path)`.
+    pub fn deps_args(&self, manifest_path: &Utf8Path, reports: &[(&str, &Utf8Path)]) -> Result<DepsArgs> {
+        let mut argv = vec!["deps".to_string(), "--manifest-path".to_string(), manifest_path.to_string()];
+
+        for (option, path) in reports {
+            argv.extend([(*option).to_string(), path.to_string()]);
+        }
+
+        if let Some(config) = &self.config {
+            argv.extend(["--config".to_string(), config.to_string()]);
+        }
+
+        if let Some(cache_dir) = &self.cache_dir {
+            argv.extend(["--cache-dir".to_string(), cache_dir.to_string()]);
+        }
+
+        if let Some(log_level) = self.log_level.to_possible_value() {
+            argv.extend(["--log-level".to_string(), log_level.get_name().to_string()]);
+        }
+
+        DepsArgs::try_parse_from(argv).into_app_err_with(|| format!("preparing the appraisal of '{manifest_path}'"))
+    }
+}
+
 pub async fn process_dependencies<H: Host>(host: &mut H, args: &DepsArgs) -> Result<()> {
     let mut common = Common::new(host, &args.common).await?;
 
@@ -95,41 +202,151 @@ pub async fn process_dependencies<H: Host>(host: &mut H, args: &DepsArgs) -> Res
         }
     }
 
-    if !args.package.is_empty() {
-        process_packages(
-            args,
-            &mut common,
-            &all_packages,
-            &resolve_index,
-            metadata
-                .workspace_members
-                .iter()
-                .filter_map(|id| all_packages.get(id).copied())
-                .filter(|p| args.package.contains(&p.name)),
-        )
-        .await
+    let config_teams = common.config.teams.clone();
+    let team_resolver = if args.per_team {
+        Some(TeamResolver::new(&config_teams, args.codeowners.as_deref(), &metadata.workspace_root)?)
+    } else {
+        None
+    };
+    let team_resolver = team_resolver.as_ref();
+
+    let members = || metadata.workspace_members.iter().filter_map(|id| all_packages.get(id).copied());
+    let selected: Vec<&Package> = if !args.package.is_empty() {
+        members().filter(|p| args.package.contains(&p.name)).collect()
     } else if args.workspace {
-        process_packages(
-            args,
-            &mut common,
-            &all_packages,
-            &resolve_index,
-            metadata.workspace_members.iter().filter_map(|id| all_packages.get(id).copied()),
-        )
-        .await
+        members().collect()
     } else if let Some(root) = metadata.root_package() {
-        process_packages(args, &mut common, &all_packages, &resolve_index, core::iter::once(root)).await
+        // The package of the given manifest, which is a workspace member when the manifest is nested in a workspace
+        vec![root]
+    } else if metadata.workspace_default_members.is_available() && !metadata.workspace_default_members.is_empty() {
+        // Virtual workspace, default to the members listed in `default-members`
+        metadata
+            .workspace_default_members
+            .iter()
+            .filter_map(|id| all_packages.get(id).copied())
+            .collect()
     } else {
-        // Virtual workspace, default to all members
-        process_packages(
-            args,
-            &mut common,
-            &all_packages,
-            &resolve_index,
-            metadata.workspace_members.iter().filter_map(|id| all_packages.get(id).copied()),
-        )
-        .await
+        // Virtual workspace without `default-members`, default to all members
+        members().collect()
+    };
+
+    let exclusions = Exclusions::new(&args.exclude, &metadata.workspace_root)?;
+    let targets: Vec<&Package> = selected.into_iter().filter(|p| !exclusions.excludes(p)).collect();
+    if targets.is_empty() {
+        bail!("all selected packages are excluded");
+    }
+
+    // Dependencies must build with the oldest Rust version any of the appraised packages supports
+    common.check_msrv(targets.iter().filter_map(|p| p.rust_version.as_ref()).min())?;
+
+    let base_crates = if args.changed_only {
+        Some(locked_crates_at_merge_base(&metadata.workspace_root, &args.base)?)
+    } else {
+        None
+    };
+
+    process_packages(
+        args,
+        &mut common,
+        &all_packages,
+        &resolve_index,
+        team_resolver,
+        base_crates.as_ref(),
+        targets.into_iter(),
+    )
+    .await
+}
+
+/// The crates locked in `Cargo.lock` at the merge base of `HEAD` and `base`, empty when the file didn't exist then.
+fn locked_crates_at_merge_base(workspace_root: &Utf8Path, base: &str) -> Result<HashSet<CrateRef>> {
+    let Some(merge_base) = git_output(workspace_root, &["merge-base", "HEAD", base]) else {
+        bail!("could not find the merge base of HEAD and '{base}' in '{workspace_root}', make sure the revision was fetched");
+    };
+
+    log::info!(target: LOG_TARGET, "Comparing Cargo.lock with its state at {merge_base}");
+    git_output(workspace_root, &["show", &format!("{merge_base}:./Cargo.lock")])
+        .map_or_else(|| Ok(HashSet::default()), |text| parse_lockfile(&text))
+}
+
+#[derive(Deserialize)]
+struct Lockfile {
+    #[serde(default)]
+    package: Vec<LockedPackage>,
+}
+
+#[derive(Deserialize)]
+struct LockedPackage {
+    name: String,
+    version: semver::Version,
+}
+
+/// The crates locked in the text of a `Cargo.lock` file.
+fn parse_lockfile(text: &str) -> Result<HashSet<CrateRef>> {
+    let lockfile: Lockfile = toml::from_str(text).into_app_err("parsing Cargo.lock")?;
+    Ok(lockfile
+        .package
+        .into_iter()
+        .map(|package| CrateRef::new(&package.name, Some(package.version)))
+        .collect())
+}
+
+/// Workspace members excluded with `--exclude`
+struct Exclusions<'a> {
+    names: Vec<Regex>,
+    paths: Vec<Regex>,
+    workspace_root: &'a Utf8Path,
+}
+
+impl<'a> Exclusions<'a> {
+    /// Parse the exclusion specs, those containing a slash being paths and the others name globs.
+    fn new(specs: &[String], workspace_root: &'a Utf8Path) -> Result<Self> {
+        let mut names = Vec::new();
+        let mut paths = Vec::new();
+
+        for spec in specs {
+            if spec.contains('/') {
+                paths.push(pattern_to_regex(spec).into_app_err_with(|| format!("invalid exclusion '{spec}'"))?);
+            } else {
+                names.push(name_glob_to_regex(spec).into_app_err_with(|| format!("invalid exclusion '{spec}'"))?);
+            }
+        }
+
+        Ok(Self {
+            names,
+            paths,
+            workspace_root,
+        })
+    }
+
+    fn excludes(&self, package: &Package) -> bool {
+        if self.names.iter().any(|re| re.is_match(&package.name)) {
+            return true;
+        }
+
+        package
+            .manifest_path
+            .parent()
+            .and_then(|dir| dir.strip_prefix(self.workspace_root).ok())
+            .is_some_and(|dir| {
+                let dir = dir.as_str().replace('\\', "/");
+                self.paths.iter().any(|re| re.is_match(&dir))
+            })
+    }
+}
+
+/// Translate a package name glob, where `*` matches any run of characters and `?` any single one, into an
+/// anchored regular expression.
+fn name_glob_to_regex(glob: &str) -> core::result::Result<Regex, regex::Error> {
+    let mut re = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' => re.push_str(".*"),
+            '?' => re.push('.'),
+            c => re.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
     }
+    re.push('$');
+    Regex::new(&re)
 }
 
 async fn process_packages<'a, H: Host>(
@@ -137,6 +354,8 @@ async fn process_packages<'a, H: Host>(
     common: &mut Common<'_, H>,
     all_packages: &HashMap<&'a PackageId, &'a Package>,
     resolve_index: &HashMap<&'a PackageId, &'a Node>,
+    team_resolver: Option<&TeamResolver<'_>>,
+    base_crates: Option<&HashSet<CrateRef>>,
     target_packages: impl Iterator<Item = &'a Package>,
 ) -> Result<()> {
     let should_process = |dep_type: &DependencyType| {
@@ -153,25 +372,229 @@ async fn process_packages<'a, H: Host>(
         .filter(|dt| should_process(dt))
         .collect();
 
+    let mut team_crates: BTreeMap<String, HashSet<CrateRef>> = BTreeMap::new();
+    let mut roots = Vec::new();
+
     for package in target_packages {
+        roots.push(&package.id);
+        let package_start = crate_dep_pairs.len();
         for &dep_type in &active_dep_types {
             crate_dep_pairs.extend(build_transitive_deps(
                 all_packages,
                 resolve_index,
                 &package.id,
                 dep_type,
+                args.depth,
             ));
         }
+
+        if let Some(resolver) = team_resolver {
+            for team in resolver.teams_for(package) {
+                team_crates
+                    .entry(team)
+                    .or_default()
+                    .extend(crate_dep_pairs[package_start..].iter().map(|(crate_ref, _)| crate_ref.clone()));
+            }
+        }
+    }
+
+    common.team_crates = team_crates.into_iter().collect();
+    let dependents = args.weight_by_centrality.then(|| count_dependents(all_packages, resolve_index));
+    common.crate_weights = workspace_weights(&common.config, &crate_dep_pairs, dependents.as_ref());
+    for (crate_ref, dep_type) in &crate_dep_pairs {
+        let types = common.crate_dependency_types.entry(crate_ref.clone()).or_default();
+        if !types.contains(dep_type) {
+            types.push(*dep_type);
+        }
+    }
+
+    if args.risk_matrix {
+        let total = resolve_index.len();
+        common.crate_impacts = count_transitive_dependents(all_packages, resolve_index)
+            .into_iter()
+            .map(|(crate_ref, count)| (crate_ref, Impact::from_dependents(count, total)))
+            .collect();
     }
 
     // Fetch facts for each crate (no suggestions for deps command)
-    let crate_refs: Vec<CrateRef> = crate_dep_pairs.into_iter().map(|(crate_ref, _)| crate_ref).collect();
+    let mut crate_refs: Vec<CrateRef> = crate_dep_pairs.into_iter().map(|(crate_ref, _)| crate_ref).collect();
+
+    // With `--changed-only`, crates already locked at the merge base were vetted before and are left out
+    if let Some(base_crates) = base_crates {
+        crate_refs.retain(|crate_ref| !base_crates.contains(crate_ref));
+        log::info!(target: LOG_TARGET, "Appraising {} crates added or updated since the merge base", crate_refs.len());
+    }
+
+    if args.graph.is_some() || args.common.mermaid.is_some() {
+        common.dependency_graph = Some(dependency_graph(all_packages, resolve_index, &roots, &crate_refs));
+        common.graph.clone_from(&args.graph);
+    }
+
+    common.overridden_crates = find_overridden_crates(all_packages, resolve_index)
+        .into_iter()
+        .filter(|(crate_ref, _)| crate_refs.contains(crate_ref))
+        .collect();
     let facts = common
         .process_crates(&crate_refs, false)
         .await?;
 
     // Report the facts
-    common.report(facts.into_iter())
+    common.report(facts.into_iter()).await
+}
+
+/// Extract the part of the resolved graph linking the root packages to the appraised crates.
+fn dependency_graph<'a>(
+    all_packages: &HashMap<&'a PackageId, &'a Package>,
+    resolve_index: &HashMap<&'a PackageId, &'a Node>,
+    roots: &[&PackageId],
+    crate_refs: &[CrateRef],
+) -> DependencyGraph {
+    let crate_ref_of = |pkg_id: &PackageId| all_packages.get(pkg_id).map(|pkg| CrateRef::new(&pkg.name, Some(pkg.version.clone())));
+    let appraised: HashSet<&CrateRef> = crate_refs.iter().collect();
+
+    let roots: Vec<CrateRef> = roots.iter().filter_map(|pkg_id| crate_ref_of(pkg_id)).collect();
+    let mut edges = Vec::new();
+    let mut seen = HashSet::default();
+
+    for (pkg_id, node) in resolve_index {
+        let Some(from) = crate_ref_of(pkg_id) else {
+            continue;
+        };
+
+        if !roots.contains(&from) && !appraised.contains(&from) {
+            continue;
+        }
+
+        for dep in &node.deps {
+            if let Some(to) = crate_ref_of(&dep.pkg)
+                && appraised.contains(&to)
+                && seen.insert((from.clone(), to.clone()))
+            {
+                edges.push((from.clone(), to));
+            }
+        }
+    }
+
+    edges.sort_by_cached_key(|(from, to)| (from.to_string(), to.to_string()));
+    DependencyGraph { roots, edges }
+}
+
+/// Find the dependencies declared as coming from crates.io which resolve to a package from another source,
+/// because a `[patch]` or `[replace]` section overrides them. Returns each along with the source it resolves to.
+fn find_overridden_crates<'a>(
+    all_packages: &HashMap<&'a PackageId, &'a Package>,
+    resolve_index: &HashMap<&'a PackageId, &'a Node>,
+) -> Vec<(CrateRef, String)> {
+    let mut overridden: Vec<(CrateRef, String)> = Vec::new();
+
+    for (pkg_id, node) in resolve_index {
+        let Some(pkg) = all_packages.get(pkg_id) else {
+            continue;
+        };
+
+        for node_dep in &node.deps {
+            let Some(dep_pkg) = all_packages.get(&node_dep.pkg) else {
+                continue;
+            };
+
+            let kind = node_dep.dep_kinds.first().map_or(DependencyKind::Normal, |dk| dk.kind);
+            let declared_from_crates_io = find_dep_declaration(pkg, &node_dep.name, kind)
+                .is_some_and(|decl| decl.path.is_none() && decl.source.as_ref().is_some_and(Source::is_crates_io));
+            if !declared_from_crates_io || dep_pkg.source.as_ref().is_some_and(Source::is_crates_io) {
+                continue;
+            }
+
+            let crate_ref = CrateRef::new(&dep_pkg.name, Some(dep_pkg.version.clone()));
+            if overridden.iter().all(|(existing, _)| *existing != crate_ref) {
+                let source = dep_pkg.source.as_ref().map_or_else(
+                    || dep_pkg.manifest_path.parent().unwrap_or(&dep_pkg.manifest_path).to_string(),
+                    |source| source.repr.clone(),
+                );
+                overridden.push((crate_ref, source));
+            }
+        }
+    }
+
+    overridden.sort_by_cached_key(|(crate_ref, _)| crate_ref.to_string());
+    overridden
+}
+
+/// Compute how much each crate counts towards the workspace score.
+///
+/// A crate reached through several dependency types takes the weight of the heaviest one. When dependent
+/// counts are given, the weight grows logarithmically with the number of crates depending on the crate.
+fn workspace_weights(
+    config: &Config,
+    crate_dep_pairs: &[(CrateRef, DependencyType)],
+    dependents: Option<&HashMap<CrateRef, usize>>,
+) -> HashMap<CrateRef, f64> {
+    let mut weights: HashMap<CrateRef, f64> = HashMap::default();
+    for (crate_ref, dep_type) in crate_dep_pairs {
+        let weight = config.dependency_type_weight(*dep_type);
+        let entry = weights.entry(crate_ref.clone()).or_insert(weight);
+        *entry = entry.max(weight);
+    }
+
+    if let Some(dependents) = dependents {
+        for (crate_ref, weight) in &mut weights {
+            let count = dependents.get(crate_ref).copied().unwrap_or(0);
+            *weight *= centrality_factor(count);
+        }
+    }
+
+    weights
+}
+
+/// Multiplier applied to a crate's workspace weight given how many crates depend upon it.
+#[expect(clippy::cast_precision_loss, reason = "dependent counts are far below the precision limit")]
+fn centrality_factor(dependents: usize) -> f64 {
+    1.0 + (dependents as f64).ln_1p()
+}
+
+/// Count the distinct packages that depend directly upon each package of the resolved graph.
+fn count_dependents<'a>(
+    all_packages: &HashMap<&'a PackageId, &'a Package>,
+    resolve_index: &HashMap<&'a PackageId, &'a Node>,
+) -> HashMap<CrateRef, usize> {
+    let mut counts: HashMap<CrateRef, usize> = HashMap::default();
+    for node in resolve_index.values() {
+        let dependencies: HashSet<&PackageId> = node.deps.iter().map(|dep| &dep.pkg).collect();
+        for pkg_id in dependencies {
+            if let Some(pkg) = all_packages.get(pkg_id) {
+                *counts.entry(CrateRef::new(&pkg.name, Some(pkg.version.clone()))).or_default() += 1;
+            }
+        }
+    }
+
+    counts
+}
+
+/// Count the distinct packages that depend upon each package of the resolved graph, directly or transitively.
+fn count_transitive_dependents<'a>(
+    all_packages: &HashMap<&'a PackageId, &'a Package>,
+    resolve_index: &HashMap<&'a PackageId, &'a Node>,
+) -> HashMap<CrateRef, usize> {
+    let mut reverse: HashMap<&PackageId, Vec<&PackageId>> = HashMap::default();
+    for node in resolve_index.values() {
+        for dep in &node.deps {
+            reverse.entry(&dep.pkg).or_default().push(&node.id);
+        }
+    }
+
+    let mut counts: HashMap<CrateRef, usize> = HashMap::default();
+    for (pkg_id, pkg) in all_packages {
+        let mut seen: HashSet<&PackageId> = HashSet::default();
+        let mut queue: Vec<&PackageId> = reverse.get(pkg_id).cloned().unwrap_or_default();
+        while let Some(dependent) = queue.pop() {
+            if dependent != *pkg_id && seen.insert(dependent) {
+                queue.extend(reverse.get(dependent).into_iter().flatten());
+            }
+        }
+
+        let _ = counts.insert(CrateRef::new(&pkg.name, Some(pkg.version.clone())), seen.len());
+    }
+
+    counts
 }
 
 /// Expand a set of features transitively using the package's feature declarations.
@@ -299,6 +722,7 @@ fn build_transitive_deps<'a>(
     resolve_index: &HashMap<&'a PackageId, &'a Node>,
     target_package_id: &PackageId,
     dependency_type: DependencyType,
+    max_depth: Option<u32>,
 ) -> HashSet<(CrateRef, DependencyType)> {
     let initial_kind = match dependency_type {
         DependencyType::Standard => DependencyKind::Normal,
@@ -307,8 +731,8 @@ fn build_transitive_deps<'a>(
     };
 
     let mut result = HashSet::default();
-    let mut visited_features: HashMap<&PackageId, HashSet<String>> = HashMap::default();
-    let mut queue: Vec<(&PackageId, HashSet<String>)> = Vec::new();
+    let mut visited: HashMap<&PackageId, (HashSet<String>, u32)> = HashMap::default();
+    let mut queue: Vec<(&PackageId, HashSet<String>, u32)> = Vec::new();
 
     // Seed the queue with the target package's direct deps of the requested kind
     if let Some(target_pkg) = all_packages.get(target_package_id)
@@ -326,24 +750,26 @@ fn build_transitive_deps<'a>(
                         continue;
                     }
                     let features = compute_dep_features(target_pkg, dep_decl, &expanded_root);
-                    queue.push((&node_dep.pkg, features));
+                    queue.push((&node_dep.pkg, features, 1));
                 } else {
-                    queue.push((&node_dep.pkg, HashSet::default()));
+                    queue.push((&node_dep.pkg, HashSet::default(), 1));
                 }
             }
         }
     }
 
-    while let Some((pkg_id, activated_features)) = queue.pop() {
-        // Only re-process if we have new features to consider
-        let was_seen = visited_features.contains_key(pkg_id);
-        let entry = visited_features.entry(pkg_id).or_default();
-        let prev_len = entry.len();
-        entry.extend(activated_features);
-        if was_seen && entry.len() == prev_len {
+    while let Some((pkg_id, activated_features, depth)) = queue.pop() {
+        // Only re-process if we have new features to consider, or reached the package through a shorter path
+        let was_seen = visited.contains_key(pkg_id);
+        let (features, min_depth) = visited.entry(pkg_id).or_insert_with(|| (HashSet::default(), depth));
+        let prev_len = features.len();
+        features.extend(activated_features);
+        if was_seen && features.len() == prev_len && depth >= *min_depth {
             continue;
         }
-        let all_features = entry.clone();
+        *min_depth = depth.min(*min_depth);
+        let all_features = features.clone();
+        let depth = *min_depth;
 
         if let Some(pkg) = all_packages.get(pkg_id) {
             _ = result.insert((CrateRef::new(&pkg.name, Some(pkg.version.clone())), dependency_type));
@@ -351,7 +777,9 @@ fn build_transitive_deps<'a>(
             let expanded = expand_features(pkg, &all_features);
 
             // Follow Normal edges for transitive deps, filtered by feature activation
-            if let Some(node) = resolve_index.get(pkg_id) {
+            if max_depth.is_none_or(|max| depth < max)
+                && let Some(node) = resolve_index.get(pkg_id)
+            {
                 for node_dep in &node.deps {
                     if node_dep.dep_kinds.iter().any(|dk| dk.kind == DependencyKind::Normal) {
                         if let Some(dep_decl) =
@@ -363,9 +791,9 @@ fn build_transitive_deps<'a>(
                                 continue;
                             }
                             let dep_features = compute_dep_features(pkg, dep_decl, &expanded);
-                            queue.push((&node_dep.pkg, dep_features));
+                            queue.push((&node_dep.pkg, dep_features, depth + 1));
                         } else {
-                            queue.push((&node_dep.pkg, HashSet::default()));
+                            queue.push((&node_dep.pkg, HashSet::default(), depth + 1));
                         }
                     }
                 }
@@ -578,6 +1006,20 @@ mod tests {
         assert!(!is_optional_dep_active(&weak_features, &pkg, "itoa"));
     }
 
+    #[test]
+    fn centrality_factor_grows_slowly() {
+        assert!((centrality_factor(0) - 1.0).abs() < f64::EPSILON);
+        assert!((centrality_factor(1) - (1.0 + 2.0_f64.ln())).abs() < 1e-12);
+        assert!(centrality_factor(100) < 6.0);
+        assert!(centrality_factor(100) > centrality_factor(10));
+    }
+
+    #[test]
+    fn dependency_type_default_weights() {
+        assert!(DependencyType::Standard.default_workspace_weight() > DependencyType::Build.default_workspace_weight());
+        assert!(DependencyType::Build.default_workspace_weight() > DependencyType::Dev.default_workspace_weight());
+    }
+
     #[test]
     fn compute_dep_features_basic(){
         let pkg = make_package(MINIMAL_PKG);
@@ -635,4 +1077,173 @@ mod tests {
         assert!(features.contains("serde"));
         assert!(features.contains("extra_feature"));
     }
+
+    #[test]
+    fn find_overridden_crates_detects_patched_dependencies() {
+        const CRATES_IO: &str = "registry+https://github.com/rust-lang/crates.io-index";
+
+        let package = |name: &str, id: &str, source: Option<&str>, deps: &[&str]| {
+            let dependencies: Vec<_> = deps
+                .iter()
+                .map(|dep| {
+                    serde_json::json!({
+                        "name": dep, "source": CRATES_IO, "req": "^1", "kind": null,
+                        "optional": false, "uses_default_features": true,
+                        "features": [], "target": null, "rename": null
+                    })
+                })
+                .collect();
+            make_package(
+                &serde_json::json!({
+                    "name": name, "version": "1.0.0", "id": id, "source": source,
+                    "dependencies": dependencies, "targets": [], "features": {},
+                    "manifest_path": format!("/{name}/Cargo.toml"), "categories": [], "keywords": [],
+                    "edition": "2021", "metadata": null
+                })
+                .to_string(),
+            )
+        };
+
+        let app = package("app", "app 1.0.0 (path+file:///app)", None, &["serde", "fork", "local"]);
+        let serde = package("serde", "serde 1.0.0", Some(CRATES_IO), &[]);
+        let fork = package("fork", "fork 1.0.0 (git)", Some("git+https://github.com/me/fork?branch=fix#abc123"), &[]);
+        let local = package("local", "local 1.0.0 (path)", None, &[]);
+
+        let node: Node = serde_json::from_value(serde_json::json!({
+            "id": "app 1.0.0 (path+file:///app)",
+            "deps": [
+                { "name": "serde", "pkg": "serde 1.0.0", "dep_kinds": [{ "kind": null, "target": null }] },
+                { "name": "fork", "pkg": "fork 1.0.0 (git)", "dep_kinds": [{ "kind": null, "target": null }] },
+                { "name": "local", "pkg": "local 1.0.0 (path)", "dep_kinds": [{ "kind": null, "target": null }] }
+            ],
+            "dependencies": [],
+            "features": []
+        }))
+        .unwrap();
+
+        let all_packages: HashMap<_, _> = [&app, &serde, &fork, &local].into_iter().map(|p| (&p.id, p)).collect();
+        let resolve_index: HashMap<_, _> = core::iter::once((&node.id, &node)).collect();
+
+        let overridden: Vec<_> = find_overridden_crates(&all_packages, &resolve_index)
+            .into_iter()
+            .map(|(crate_ref, source)| format!("{crate_ref} -> {source}"))
+            .collect();
+        assert_eq!(
+            overridden,
+            ["fork@1.0.0 -> git+https://github.com/me/fork?branch=fix#abc123", "local@1.0.0 -> /local"]
+        );
+    }
+
+    #[test]
+    fn test_parse_lockfile() {
+        let text = r#"
+version = 4
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = ["serde"]
+
+[[package]]
+name = "serde"
+version = "1.0.210"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "c8e3592472072e6e22e0a54d5904d9febf8508f65fb8552499a1abc7d1078c3a"
+"#;
+        let crates = parse_lockfile(text).unwrap();
+        assert_eq!(crates.len(), 2);
+        assert!(crates.contains(&CrateRef::new("serde", Some("1.0.210".parse().unwrap()))));
+        assert!(!crates.contains(&CrateRef::new("serde", Some("1.0.211".parse().unwrap()))));
+
+        assert!(parse_lockfile("").unwrap().is_empty());
+        let _ = parse_lockfile("[[package]]\nname = \"broken\"\n").unwrap_err();
+    }
+
+    #[test]
+    fn test_exclusions() {
+        let member = |name: &str, dir: &str| {
+            make_package(
+                &serde_json::json!({
+                    "name": name, "version": "0.1.0", "id": format!("{name} 0.1.0 (path+file:///repo/{dir})"), "source": null,
+                    "dependencies": [], "targets": [], "features": {},
+                    "manifest_path": format!("/repo/{dir}/Cargo.toml"), "categories": [], "keywords": [],
+                    "edition": "2021", "metadata": null
+                })
+                .to_string(),
+            )
+        };
+
+        let specs = ["*-generated".to_string(), "proto?".to_string(), "vendor/".to_string()];
+        let exclusions = Exclusions::new(&specs, Utf8Path::new("/repo")).unwrap();
+
+        assert!(exclusions.excludes(&member("api-generated", "crates/api-generated")));
+        assert!(exclusions.excludes(&member("proto3", "crates/proto3")));
+        assert!(exclusions.excludes(&member("zlib-sys", "vendor/zlib-sys")));
+        assert!(!exclusions.excludes(&member("proto", "crates/proto")));
+        assert!(!exclusions.excludes(&member("api", "crates/api")));
+        assert!(!exclusions.excludes(&member("vendor", "crates/vendor")));
+    }
+
+    #[test]
+    fn test_build_transitive_deps_depth() {
+        const CRATES_IO: &str = "registry+https://github.com/rust-lang/crates.io-index";
+
+        // app -> a -> b -> c, with app also depending on c directly
+        let chain = [("app", &["a", "c"][..]), ("a", &["b"]), ("b", &["c"]), ("c", &[])];
+        let packages: Vec<Package> = chain
+            .iter()
+            .map(|(name, deps)| {
+                let dependencies: Vec<_> = deps
+                    .iter()
+                    .map(|dep| {
+                        serde_json::json!({
+                            "name": dep, "source": CRATES_IO, "req": "^1", "kind": null,
+                            "optional": false, "uses_default_features": true,
+                            "features": [], "target": null, "rename": null
+                        })
+                    })
+                    .collect();
+                make_package(
+                    &serde_json::json!({
+                        "name": name, "version": "1.0.0", "id": format!("{name} 1.0.0"), "source": CRATES_IO,
+                        "dependencies": dependencies, "targets": [], "features": {},
+                        "manifest_path": format!("/{name}/Cargo.toml"), "categories": [], "keywords": [],
+                        "edition": "2021", "metadata": null
+                    })
+                    .to_string(),
+                )
+            })
+            .collect();
+        let nodes: Vec<Node> = chain
+            .iter()
+            .map(|(name, deps)| {
+                let deps: Vec<_> = deps
+                    .iter()
+                    .map(|dep| {
+                        serde_json::json!({ "name": dep, "pkg": format!("{dep} 1.0.0"), "dep_kinds": [{ "kind": null, "target": null }] })
+                    })
+                    .collect();
+                serde_json::from_value(serde_json::json!({
+                    "id": format!("{name} 1.0.0"), "deps": deps, "dependencies": [], "features": []
+                }))
+                .unwrap()
+            })
+            .collect();
+
+        let all_packages: HashMap<_, _> = packages.iter().map(|p| (&p.id, p)).collect();
+        let resolve_index: HashMap<_, _> = nodes.iter().map(|n| (&n.id, n)).collect();
+        let names = |max_depth: Option<u32>| {
+            let mut names: Vec<String> =
+                build_transitive_deps(&all_packages, &resolve_index, &packages[0].id, DependencyType::Standard, max_depth)
+                    .into_iter()
+                    .map(|(crate_ref, _)| crate_ref.name().to_string())
+                    .collect();
+            names.sort();
+            names
+        };
+
+        assert_eq!(names(Some(1)), ["a", "c"]);
+        assert_eq!(names(Some(2)), ["a", "b", "c"]);
+        assert_eq!(names(None), ["a", "b", "c"]);
+    }
 }
@@ -32,11 +32,11 @@ impl TestHost {
 #[cfg(test)]
 impl Host for TestHost {
     fn output(&mut self) -> impl Write {
-        std::io::Cursor::new(&mut self.output_buf)
+        &mut self.output_buf
     }
 
     fn error(&mut self) -> impl Write {
-        std::io::Cursor::new(&mut self.error_buf)
+        &mut self.error_buf
     }
 
     fn exit(&mut self, _code: i32) {
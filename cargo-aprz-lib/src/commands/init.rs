@@ -1,11 +1,21 @@
+//! Generation of a starting configuration file.
+//!
+//! By default, the configuration written is the default one. With `--interactive`, a few questions about the
+//! project pick the license dependencies must be compatible with, the risk bands, and the CI provider, with
+//! answers suggested by probing the workspace. The workspace's dependencies also decide which category presets
+//! to enable, and a snippet running the appraisal in CI is printed once the configuration is written.
+
 use super::Host;
-use super::config::Config;
+use super::config::{Config, DEFAULT_CONFIG_TOML};
 use crate::Result;
-use camino::Utf8PathBuf;
-use cargo_metadata::MetadataCommand;
+use crate::expr::ProjectLicense;
+use camino::{Utf8Path, Utf8PathBuf};
+use cargo_metadata::{Metadata, MetadataCommand};
 use clap::Parser;
-use ohno::IntoAppError;
-use std::io::Write;
+use core::fmt::Write as _;
+use ohno::{IntoAppError, bail};
+use std::fs;
+use std::io::{BufRead, Write};
 
 #[derive(Parser, Debug)]
 pub struct InitArgs {
@@ -16,16 +26,158 @@ pub struct InitArgs {
     /// Path to Cargo.toml file
     #[arg(long, default_value = "Cargo.toml", value_name = "PATH")]
     pub manifest_path: Utf8PathBuf,
+
+    /// Ask about the project and write a configuration tailored to the answers
+    #[arg(long)]
+    pub interactive: bool,
+}
+
+/// How much risk the project is willing to take on from its dependencies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RiskAppetite {
+    Strict,
+    Balanced,
+    Lenient,
+}
+
+impl RiskAppetite {
+    fn parse(answer: &str) -> Option<Self> {
+        match answer.to_ascii_lowercase().as_str() {
+            "strict" => Some(Self::Strict),
+            "balanced" => Some(Self::Balanced),
+            "lenient" => Some(Self::Lenient),
+            _ => None,
+        }
+    }
+
+    /// The medium and low risk thresholds of the risk bands
+    const fn thresholds(self) -> (f64, f64) {
+        match self {
+            Self::Strict => (50.0, 80.0),
+            Self::Balanced => (30.0, 70.0),
+            Self::Lenient => (20.0, 60.0),
+        }
+    }
+
+    /// The option making CI fail on the crates that are too risky
+    const fn error_option(self) -> &'static str {
+        match self {
+            Self::Strict => "--error-if-medium-risk",
+            Self::Balanced | Self::Lenient => "--error-if-high-risk",
+        }
+    }
+}
+
+/// The CI service the appraisal runs on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CiProvider {
+    GitHub,
+    GitLab,
+    None,
+}
+
+impl CiProvider {
+    fn parse(answer: &str) -> Option<Self> {
+        match answer.to_ascii_lowercase().as_str() {
+            "github" => Some(Self::GitHub),
+            "gitlab" => Some(Self::GitLab),
+            "none" => Some(Self::None),
+            _ => None,
+        }
+    }
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::GitHub => "github",
+            Self::GitLab => "gitlab",
+            Self::None => "none",
+        }
+    }
+}
+
+/// What the workspace tells about the project, used to suggest answers
+#[derive(Debug, Default)]
+struct WorkspaceProbe {
+    /// License of the workspace's packages, when a supported one is shared by all of them
+    license: Option<String>,
+
+    /// CI service the workspace is already configured for
+    ci: Option<CiProvider>,
+
+    /// Category presets relevant to the workspace's dependencies
+    presets: Vec<&'static str>,
+}
+
+impl WorkspaceProbe {
+    fn new(metadata: &Metadata) -> Self {
+        let members = metadata.workspace_packages();
+
+        // Dual-licensed packages can be used under their first supported alternative
+        let licenses: Vec<Option<&str>> = members
+            .iter()
+            .map(|package| {
+                let license = package.license.as_deref()?;
+                license.split(" OR ").map(str::trim).find(|l| ProjectLicense::parse(l).is_some())
+            })
+            .collect();
+        let license = match licenses.first() {
+            Some(Some(first)) if licenses.iter().all(|l| l == &Some(*first)) => Some((*first).to_string()),
+            _ => None,
+        };
+
+        let root = &metadata.workspace_root;
+        let ci = if root.join(".github").join("workflows").is_dir() {
+            Some(CiProvider::GitHub)
+        } else if root.join(".gitlab-ci.yml").is_file() {
+            Some(CiProvider::GitLab)
+        } else {
+            None
+        };
+
+        let in_category = |slugs: &[&str]| {
+            metadata
+                .packages
+                .iter()
+                .filter(|package| !metadata.workspace_members.contains(&package.id))
+                .flat_map(|package| &package.categories)
+                .any(|category| {
+                    slugs
+                        .iter()
+                        .any(|slug| category == slug || category.starts_with(&format!("{slug}::")))
+                })
+        };
+
+        let mut presets = Vec::new();
+        if in_category(&["cryptography"]) {
+            presets.push("cryptography");
+        }
+        if in_category(&["command-line-utilities", "development-tools"]) {
+            presets.push("dev-tools");
+        }
+
+        Self { license, ci, presets }
+    }
+}
+
+/// The answers to the questions of the interactive mode
+#[derive(Debug)]
+struct Answers {
+    project_license: String,
+    appetite: RiskAppetite,
+    ci: CiProvider,
 }
 
 pub fn init_config<H: Host>(host: &mut H, args: &InitArgs) -> Result<()> {
+    if args.interactive {
+        let metadata = workspace_metadata(&args.manifest_path)?;
+        let output = args.output.clone().unwrap_or_else(|| metadata.workspace_root.join("aprz.toml"));
+        return init_interactive(host, &mut std::io::stdin().lock(), &WorkspaceProbe::new(&metadata), &output);
+    }
+
     let output = if let Some(path) = &args.output {
         path.clone()
     } else {
-        let mut metadata_cmd = MetadataCommand::new();
-        let _ = metadata_cmd.manifest_path(&args.manifest_path);
-        let metadata = metadata_cmd.exec().into_app_err("retrieving workspace metadata")?;
-        metadata.workspace_root.join("aprz.toml")
+        workspace_metadata(&args.manifest_path)?.workspace_root.join("aprz.toml")
     };
 
     Config::save_default(&output)?;
@@ -33,6 +185,132 @@ pub fn init_config<H: Host>(host: &mut H, args: &InitArgs) -> Result<()> {
     Ok(())
 }
 
+fn workspace_metadata(manifest_path: &Utf8Path) -> Result<Metadata> {
+    let mut metadata_cmd = MetadataCommand::new();
+    let _ = metadata_cmd.manifest_path(manifest_path);
+    metadata_cmd.exec().into_app_err("retrieving workspace metadata")
+}
+
+/// Ask the questions, then write the tailored configuration to `output` and print a CI snippet.
+fn init_interactive<H: Host>(host: &mut H, input: &mut impl BufRead, probe: &WorkspaceProbe, output: &Utf8Path) -> Result<()> {
+    let project_license = ask(
+        host,
+        input,
+        "Project license (an SPDX identifier, or 'proprietary')",
+        probe.license.as_deref().unwrap_or("proprietary"),
+        |answer| ProjectLicense::parse(answer).map(|_| answer.to_string()),
+    )?;
+    let appetite = ask(
+        host,
+        input,
+        "Risk appetite (strict, balanced, lenient)",
+        "balanced",
+        RiskAppetite::parse,
+    )?;
+    let ci = ask(
+        host,
+        input,
+        "CI provider (github, gitlab, none)",
+        probe.ci.unwrap_or(CiProvider::None).name(),
+        CiProvider::parse,
+    )?;
+
+    for preset in &probe.presets {
+        let _ = writeln!(
+            host.output(),
+            "Enabling the '{preset}' preset, as some dependencies are in its categories"
+        );
+    }
+
+    let answers = Answers {
+        project_license,
+        appetite,
+        ci,
+    };
+    fs::write(output, tailored_config(&answers, &probe.presets)).into_app_err_with(|| format!("writing configuration to {output}"))?;
+    let _ = writeln!(host.output(), "Generated configuration file: {output}");
+
+    if let Some(snippet) = ci_snippet(&answers) {
+        let _ = writeln!(
+            host.output(),
+            "\nTo appraise the dependencies in CI, add this to your pipeline:\n\n{snippet}"
+        );
+    }
+
+    Ok(())
+}
+
+/// Ask a question until the answer parses, using `default` for empty answers.
+fn ask<H: Host, T>(host: &mut H, input: &mut impl BufRead, question: &str, default: &str, parse: impl Fn(&str) -> Option<T>) -> Result<T> {
+    let mut line = String::new();
+
+    loop {
+        let _ = write!(host.output(), "{question} [{default}]: ");
+        let _ = host.output().flush();
+
+        line.clear();
+        if input.read_line(&mut line).into_app_err("reading from the console")? == 0 {
+            let _ = writeln!(host.output());
+            bail!("the input ended before all questions were answered");
+        }
+
+        let answer = match line.trim() {
+            "" => default,
+            answer => answer,
+        };
+
+        if let Some(value) = parse(answer) {
+            return Ok(value);
+        }
+
+        let _ = writeln!(host.error(), "'{answer}' isn't a valid answer");
+    }
+}
+
+/// The default configuration, adjusted to the answers and with the given presets enabled.
+fn tailored_config(answers: &Answers, presets: &[&str]) -> String {
+    let (medium, low) = answers.appetite.thresholds();
+    let mut text = DEFAULT_CONFIG_TOML
+        .replacen("medium_risk_threshold = 30.0", &format!("medium_risk_threshold = {medium:.1}"), 1)
+        .replacen("low_risk_threshold = 70.0", &format!("low_risk_threshold = {low:.1}"), 1);
+
+    // Top-level keys must come before the first table, so the presets go right after the last default one
+    if !presets.is_empty() {
+        let list: Vec<String> = presets.iter().map(|preset| format!("\"{preset}\"")).collect();
+        text = text.replacen(
+            "aggregation = \"sum\"\n",
+            &format!("aggregation = \"sum\"\n\npresets = [{}]\n", list.join(", ")),
+            1,
+        );
+    }
+
+    let _ = write!(text, "\n[license_compatibility]\nproject_license = \"{}\"\n", answers.project_license);
+    text
+}
+
+/// A snippet running the appraisal on the chosen CI service.
+fn ci_snippet(answers: &Answers) -> Option<String> {
+    let command = format!("cargo aprz deps {}", answers.appetite.error_option());
+    match answers.ci {
+        CiProvider::GitHub => Some(format!(
+            "      - name: Appraise dependencies
+        env:
+          GITHUB_TOKEN: ${{{{ secrets.GITHUB_TOKEN }}}}
+        run: |
+          cargo install --locked cargo-aprz
+          {command}"
+        )),
+        CiProvider::GitLab => Some(format!(
+            "appraise-dependencies:
+  image: rust:latest
+  script:
+    - cargo install --locked cargo-aprz
+    - {command}"
+        )),
+        CiProvider::None => None,
+    }
+}
+
 #[cfg(test)]
 #[cfg_attr(coverage_nightly, coverage(off))]
 mod tests {
@@ -46,6 +324,7 @@ mod tests {
         let args = InitArgs {
             output: None,
             manifest_path: Utf8PathBuf::from("Cargo.toml"),
+            interactive: false,
         };
 
         // This exercises the else branch (line 25) where MetadataCommand
@@ -54,7 +333,7 @@ mod tests {
         let generated = metadata.workspace_root.join("aprz.toml");
         let had_existing = generated.as_std_path().exists();
         let existing_contents = had_existing
-            .then(|| std::fs::read(generated.as_std_path()).expect("read existing aprz.toml"));
+            .then(|| fs::read(generated.as_std_path()).expect("read existing aprz.toml"));
 
         let result = init_config(&mut host, &args);
         assert!(result.is_ok(), "init_config should succeed: {result:?}");
@@ -67,9 +346,57 @@ mod tests {
 
         // Restore original file or clean up the generated one
         if let Some(contents) = existing_contents {
-            std::fs::write(generated.as_std_path(), contents).expect("restore aprz.toml");
+            fs::write(generated.as_std_path(), contents).expect("restore aprz.toml");
         } else {
-            let _ = std::fs::remove_file(generated.as_std_path());
+            let _ = fs::remove_file(generated.as_std_path());
         }
     }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_init_interactive() {
+        let tmp = tempfile::tempdir().unwrap();
+        let output = Utf8PathBuf::from_path_buf(tmp.path().join("aprz.toml")).unwrap();
+        let probe = WorkspaceProbe {
+            license: Some("MIT".into()),
+            ci: Some(CiProvider::GitHub),
+            presets: vec!["cryptography"],
+        };
+
+        let mut host = TestHost::new();
+        let mut input = std::io::Cursor::new("GPL-3.0-only\nreckless\nstrict\n\n");
+        init_interactive(&mut host, &mut input, &probe, &output).unwrap();
+
+        let config: Config = toml::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        assert!((config.medium_risk_threshold - 50.0).abs() < f64::EPSILON);
+        assert!((config.low_risk_threshold - 80.0).abs() < f64::EPSILON);
+        assert_eq!(config.presets, ["cryptography"]);
+        assert_eq!(config.license_compatibility.unwrap().project_license, "GPL-3.0-only");
+
+        let output_text = String::from_utf8_lossy(&host.output_buf);
+        assert!(output_text.contains("Project license (an SPDX identifier, or 'proprietary') [MIT]"));
+        assert!(output_text.contains("cargo aprz deps --error-if-medium-risk"));
+        assert!(String::from_utf8_lossy(&host.error_buf).contains("'reckless' isn't a valid answer"));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_init_interactive_defaults() {
+        let tmp = tempfile::tempdir().unwrap();
+        let output = Utf8PathBuf::from_path_buf(tmp.path().join("aprz.toml")).unwrap();
+
+        let mut host = TestHost::new();
+        let mut input = std::io::Cursor::new("\n\n\n");
+        init_interactive(&mut host, &mut input, &WorkspaceProbe::default(), &output).unwrap();
+
+        let config: Config = toml::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        assert!((config.medium_risk_threshold - 30.0).abs() < f64::EPSILON);
+        assert!(config.presets.is_empty());
+        assert_eq!(config.license_compatibility.unwrap().project_license, "proprietary");
+        assert!(!String::from_utf8_lossy(&host.output_buf).contains("add this to your pipeline"));
+
+        let mut input = std::io::Cursor::new("MIT\n");
+        let err = init_interactive(&mut TestHost::new(), &mut input, &WorkspaceProbe::default(), &output).unwrap_err();
+        assert!(err.to_string().contains("input ended"));
+    }
 }
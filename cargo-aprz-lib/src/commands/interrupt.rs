@@ -0,0 +1,78 @@
+//! Graceful handling of Ctrl-C while crate information is gathered.
+//!
+//! The first Ctrl-C during collection cancels the work that hasn't started yet, letting the requests under way
+//! finish so that a report can be made from what was gathered. A second Ctrl-C, or one at any other time, exits
+//! right away as it would without the handler.
+//!
+//! The listener only cancels the collection, leaving it to the command to tell the user through its host.
+
+use crate::facts::Cancellation;
+use core::time::Duration;
+use std::sync::{Mutex, MutexGuard, Once, PoisonError};
+
+/// Exit code of a process interrupted by SIGINT, by shell convention
+const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// The reason given for a collection cancelled by Ctrl-C
+pub const INTERRUPTED: &str = "interrupted";
+
+/// The cancellation of the collection under way, if any
+static CURRENT: Mutex<Option<Cancellation>> = Mutex::new(None);
+
+/// Once listened to, Ctrl-C no longer stops the process, so a single listener lives as long as the process
+static LISTENER: Once = Once::new();
+
+/// Keeps Ctrl-C cancelling the collection until dropped.
+#[derive(Debug)]
+#[must_use]
+pub struct InterruptGuard;
+
+impl Drop for InterruptGuard {
+    fn drop(&mut self) {
+        *current() = None;
+    }
+}
+
+/// Cancel `cancellation` on Ctrl-C until the returned guard is dropped.
+pub fn cancel_on_ctrl_c(cancellation: &Cancellation) -> InterruptGuard {
+    *current() = Some(cancellation.clone());
+    LISTENER.call_once(|| drop(tokio::spawn(listen())));
+    InterruptGuard
+}
+
+/// The cancellation slot, which only ever holds a handle, so a panic while holding the lock can't leave it inconsistent.
+fn current() -> MutexGuard<'static, Option<Cancellation>> {
+    CURRENT.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+async fn listen() {
+    while tokio::signal::ctrl_c().await.is_ok() {
+        let cancellation = current().take();
+        match cancellation {
+            Some(cancellation) if !cancellation.is_cancelled() => cancellation.cancel(INTERRUPTED),
+            _ => std::process::exit(INTERRUPTED_EXIT_CODE),
+        }
+    }
+}
+
+/// Parse the `--deadline` option, such as `10m` or `1h 30m`.
+pub fn parse_deadline(s: &str) -> Result<Duration, String> {
+    let deadline = humantime_serde::re::humantime::parse_duration(s).map_err(|e| e.to_string())?;
+    if deadline.is_zero() {
+        return Err("the deadline must be longer than zero".into());
+    }
+    Ok(deadline)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_deadline() {
+        assert_eq!(parse_deadline("10m"), Ok(Duration::from_mins(10)));
+        assert_eq!(parse_deadline("1h 30m"), Ok(Duration::from_mins(90)));
+        let _ = parse_deadline("0s").unwrap_err();
+        let _ = parse_deadline("soon").unwrap_err();
+    }
+}
@@ -0,0 +1,37 @@
+use super::Host;
+use super::common::{Common, CommonArgs};
+use crate::Result;
+use crate::facts::CrateRef;
+use clap::Parser;
+use ohno::bail;
+use std::collections::BTreeSet;
+
+#[derive(Parser, Debug)]
+pub struct InventoryArgs {
+    /// crates.io logins of the users or teams whose crates to appraise, such as `dtolnay` or `github:rust-lang:libs`.
+    /// The name of a GitHub organization covers the crates owned by all of its teams
+    #[arg(value_name = "LOGIN", required = true)]
+    pub owners: Vec<String>,
+
+    #[command(flatten)]
+    pub common: CommonArgs,
+}
+
+pub async fn appraise_inventory<H: Host>(host: &mut H, args: &InventoryArgs) -> Result<()> {
+    let mut common = Common::new(host, &args.common).await?;
+
+    // Crates co-owned by several of the logins are only appraised once
+    let mut names = BTreeSet::new();
+    for owner in &args.owners {
+        let owned = common.collector.owned_crates(owner).await;
+        if owned.is_empty() {
+            bail!("no crates owned by '{owner}' were found in the crates.io database dump");
+        }
+        names.extend(owned);
+    }
+
+    let crates: Vec<_> = names.iter().map(|name| CrateRef::new(name, None)).collect();
+    let crate_facts = common.process_crates(&crates, false).await?;
+
+    common.report(crate_facts.into_iter()).await
+}
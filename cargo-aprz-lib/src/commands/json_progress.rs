@@ -0,0 +1,270 @@
+//! Progress reporting as a stream of JSON events, so other tools can render their own progress display.
+//!
+//! Each event is written as a single line holding a JSON object whose `event` field identifies its kind:
+//!
+//! - `phase`: a new phase of processing started
+//! - `progress`: the progress of the phase changed, or that of one of its providers when `provider` is present
+//! - `message`: a message to show the user
+//! - `done`: processing is complete
+
+use crate::facts::{Progress, TopicProgress};
+use core::fmt::{Debug, Formatter};
+use core::time::Duration;
+use serde::Serialize;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinHandle;
+
+/// How often the progress callbacks are polled for changes.
+const POLL_INTERVAL_MS: u64 = 250;
+
+type TopicsCallback = Box<dyn Fn() -> Vec<TopicProgress> + Send + Sync>;
+
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum ProgressEvent<'a> {
+    Phase {
+        phase: &'a str,
+    },
+    Progress {
+        phase: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        provider: Option<&'a str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        completed: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        total: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        message: Option<&'a str>,
+    },
+    Message {
+        message: &'a str,
+    },
+    Done,
+}
+
+enum PhaseCallback {
+    None,
+    Determinate(Box<dyn Fn() -> (u64, u64, String) + Send + Sync>),
+    Indeterminate(Box<dyn Fn() -> String + Send + Sync>),
+}
+
+struct State {
+    output: Box<dyn Write + Send>,
+    phase: String,
+    callback: PhaseCallback,
+    topics_callback: Option<TopicsCallback>,
+
+    /// The progress events written by the last poll, to only write those that changed.
+    last_events: Vec<String>,
+}
+
+impl State {
+    fn write(&mut self, event: &ProgressEvent<'_>) {
+        if let Ok(line) = serde_json::to_string(event) {
+            let _ = writeln!(self.output, "{line}");
+            let _ = self.output.flush();
+        }
+    }
+
+    /// Write the progress events that changed since the last poll.
+    fn poll(&mut self) {
+        let phase = self.phase.as_str();
+        let mut events = Vec::new();
+
+        let event = match &self.callback {
+            PhaseCallback::None => None,
+            PhaseCallback::Determinate(callback) => {
+                let (total, completed, message) = callback();
+                Some(serde_json::to_string(&ProgressEvent::Progress {
+                    phase,
+                    provider: None,
+                    completed: Some(completed),
+                    total: Some(total),
+                    message: Some(message.as_str()),
+                }))
+            }
+            PhaseCallback::Indeterminate(callback) => Some(serde_json::to_string(&ProgressEvent::Progress {
+                phase,
+                provider: None,
+                completed: None,
+                total: None,
+                message: Some(callback().as_str()),
+            })),
+        };
+        events.extend(event.and_then(Result::ok));
+
+        for topic in self.topics_callback.as_ref().map_or_else(Vec::new, |callback| callback()) {
+            events.extend(
+                serde_json::to_string(&ProgressEvent::Progress {
+                    phase,
+                    provider: Some(topic.name),
+                    completed: Some(topic.completed),
+                    total: Some(topic.total),
+                    message: None,
+                })
+                .ok(),
+            );
+        }
+
+        for event in events.iter().filter(|event| !self.last_events.contains(event)) {
+            let _ = writeln!(self.output, "{event}");
+        }
+
+        let _ = self.output.flush();
+        self.last_events = events;
+    }
+}
+
+/// Reports progress as a stream of JSON events.
+pub struct JsonProgress {
+    state: Arc<Mutex<State>>,
+    poll_task: JoinHandle<()>,
+}
+
+impl JsonProgress {
+    /// Create a progress reporter writing its events to `output`.
+    #[must_use]
+    pub fn new(output: Box<dyn Write + Send>) -> Self {
+        let state = Arc::new(Mutex::new(State {
+            output,
+            phase: String::new(),
+            callback: PhaseCallback::None,
+            topics_callback: None,
+            last_events: Vec::new(),
+        }));
+
+        Self {
+            poll_task: tokio::spawn(poll_task(Arc::clone(&state))),
+            state,
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().expect("lock poisoned")
+    }
+}
+
+impl Progress for JsonProgress {
+    fn set_phase(&self, phase: &str) {
+        let mut state = self.state();
+        phase.clone_into(&mut state.phase);
+        state.callback = PhaseCallback::None;
+        state.topics_callback = None;
+        state.last_events.clear();
+        state.write(&ProgressEvent::Phase { phase });
+    }
+
+    fn set_determinate(&self, callback: Box<dyn Fn() -> (u64, u64, String) + Send + Sync + 'static>) {
+        let mut state = self.state();
+        state.callback = PhaseCallback::Determinate(callback);
+        state.topics_callback = None;
+    }
+
+    fn set_indeterminate(&self, callback: Box<dyn Fn() -> String + Send + Sync + 'static>) {
+        let mut state = self.state();
+        state.callback = PhaseCallback::Indeterminate(callback);
+        state.topics_callback = None;
+    }
+
+    fn set_topics(&self, callback: Box<dyn Fn() -> Vec<TopicProgress> + Send + Sync + 'static>) {
+        self.state().topics_callback = Some(callback);
+    }
+
+    fn println(&self, msg: &str) {
+        self.state().write(&ProgressEvent::Message { message: msg });
+    }
+
+    fn done(&self) {
+        self.poll_task.abort();
+
+        // Report the final state before signaling completion
+        let mut state = self.state();
+        state.poll();
+        state.write(&ProgressEvent::Done);
+    }
+}
+
+impl Debug for JsonProgress {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("JsonProgress")
+            .field("state", &"<state>")
+            .field("poll_task", &"<task>")
+            .finish()
+    }
+}
+
+/// Background task that periodically writes the progress events that changed.
+async fn poll_task(state: Arc<Mutex<State>>) {
+    let mut interval = tokio::time::interval(Duration::from_millis(POLL_INTERVAL_MS));
+    #[expect(clippy::infinite_loop, reason = "task runs until aborted")]
+    loop {
+        let _ = interval.tick().await;
+        state.lock().expect("lock poisoned").poll();
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use crate::facts::TopicStatus;
+    use std::sync::PoisonError;
+
+    /// A writer whose output remains accessible once handed over.
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap_or_else(PoisonError::into_inner).write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuf {
+        fn lines(&self) -> Vec<String> {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap().lines().map(str::to_string).collect()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_json_progress_events() {
+        let buf = SharedBuf::default();
+        let progress = JsonProgress::new(Box::new(buf.clone()));
+
+        progress.set_phase("Preparing");
+        progress.set_indeterminate(Box::new(|| "Downloading advisory database".to_string()));
+        progress.state().poll();
+        progress.state().poll();
+
+        progress.set_phase("Querying");
+        progress.set_determinate(Box::new(|| (4, 1, "1/4 repos".to_string())));
+        progress.set_topics(Box::new(|| {
+            vec![TopicProgress {
+                name: "repos",
+                total: 4,
+                completed: 1,
+                status: TopicStatus::Active,
+            }]
+        }));
+        progress.println("hello");
+        progress.done();
+
+        assert_eq!(
+            buf.lines(),
+            [
+                r#"{"event":"phase","phase":"Preparing"}"#,
+                r#"{"event":"progress","phase":"Preparing","message":"Downloading advisory database"}"#,
+                r#"{"event":"phase","phase":"Querying"}"#,
+                r#"{"event":"message","message":"hello"}"#,
+                r#"{"event":"progress","phase":"Querying","completed":1,"total":4,"message":"1/4 repos"}"#,
+                r#"{"event":"progress","phase":"Querying","provider":"repos","completed":1,"total":4}"#,
+                r#"{"event":"done"}"#,
+            ]
+        );
+    }
+}
@@ -6,7 +6,7 @@
 //!
 //! # Implementation Model
 //!
-//! The module is organized around four main commands:
+//! The module is organized around these main commands:
 //!
 //! ## Commands
 //!
@@ -14,8 +14,24 @@
 //!   against policy expressions, and generate reports
 //! - **deps**: Analyze all dependencies in a workspace, similar to crates command
 //!   but automatically discovers crates from Cargo.lock
+//! - **compare**: Analyze alternative crates and lay them out side by side
+//! - **inventory**: Appraise every crate owned by a set of crates.io users or teams,
+//!   ranking the crates they publish
+//! - **releases**: Appraise the latest releases of a crate one by one, showing how
+//!   its score evolved over time
+//! - **calibrate**: Suggest point values for the eval expressions from crates labeled
+//!   as good or bad
+//! - **cache**: Export the cache to a bundle file, or import one, for use on machines
+//!   without network access
+//! - **repl**: Interactively evaluate expressions against the metrics of a crate
 //! - **init**: Generate a default configuration file with example expressions
 //! - **validate**: Check configuration file syntax and expression validity
+//! - **batch**: Analyze the dependencies of many repositories, cloning or updating
+//!   them as needed, and rank the repositories by dependency health
+//! - **daemon**: Appraise the dependencies of a set of workspaces periodically, keeping
+//!   their latest reports and the history store up to date
+//! - **serve**: Collect the results submitted by other runs with `--report-to` and
+//!   show them on a dashboard, available with the `server` feature
 //!
 //! ## Execution Flow
 //!
@@ -35,23 +51,59 @@
 //! Configuration is managed through a TOML file with two expression lists
 //! (`high_risk`, `eval`) that define the evaluation policy.
 
+mod alerts;
+mod batch;
+mod cache;
+mod calibrate;
+mod cargo_deny;
 mod common;
+mod compare;
 mod config;
 mod crates;
+mod daemon;
+mod dependency_track;
 mod deps;
 mod host;
 mod init;
+mod interrupt;
+mod inventory;
+mod json_progress;
+mod notify;
+mod policy_pack;
+mod presets;
 mod progress_reporter;
+mod releases;
+mod repl;
 mod run;
+#[cfg(feature = "script_hook")]
+mod script_hook;
+#[cfg(feature = "server")]
+mod serve;
+mod submit;
+mod teams;
+mod token_discovery;
+#[cfg(feature = "upload")]
+mod upload;
 mod validate;
 
 #[cfg(debug_assertions)]
 pub use config::Config;
 
+pub use batch::{BatchArgs, process_batch};
+pub use cache::{CacheArgs, manage_cache};
+pub use calibrate::{CalibrateArgs, calibrate_crates};
+pub use compare::{CompareArgs, compare_crates};
 pub use crates::{CratesArgs, process_crates};
+pub use daemon::{DaemonArgs, run_daemon};
 pub use deps::{DepsArgs, process_dependencies};
 pub use host::Host;
 pub use init::{InitArgs, init_config};
+pub use inventory::{InventoryArgs, appraise_inventory};
+pub use json_progress::JsonProgress;
 pub use progress_reporter::ProgressReporter;
+pub use releases::{ReleasesArgs, score_releases};
+pub use repl::{ReplArgs, run_repl};
 pub use run::run;
+#[cfg(feature = "server")]
+pub use serve::{ServeArgs, serve};
 pub use validate::{ValidateArgs, validate_config};
@@ -0,0 +1,331 @@
+//! Webhook notifications summarizing flagged crates.
+//!
+//! When a `[notify]` section is present in the configuration, a short summary is posted
+//! to a Slack or Teams incoming webhook whenever a run newly flags a crate, or raises
+//! an alert.
+//!
+//! A crate is newly flagged when its risk rises to the configured minimum, or when a new advisory affects
+//! it. The risk and advisories of every crate are kept in a state file between runs, so that a crate which
+//! stays flagged is only reported once.
+
+use super::alerts::Alert;
+use super::config::{NotifyConfig, NotifyFormat};
+use crate::Result;
+use crate::expr::Risk;
+use crate::reports::ReportableCrate;
+use core::fmt::Write;
+use ohno::{IntoAppError, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+const LOG_TARGET: &str = "    notify";
+
+/// What the previous run knew about each crate, keyed by crate name
+type NotifyState = BTreeMap<String, CrateState>;
+
+/// The risk and advisories of a crate as of the previous run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct CrateState {
+    risk: Option<Risk>,
+    vulnerabilities: u64,
+}
+
+impl CrateState {
+    fn new(crate_info: &ReportableCrate) -> Self {
+        Self {
+            risk: crate_info.appraisal.as_ref().map(|a| a.risk),
+            vulnerabilities: crate_info.vulnerability_count(),
+        }
+    }
+}
+
+/// Select the crates which should be mentioned in a notification: those whose risk rose to the minimum
+/// risk since the previous run, and those affected by more advisories than before.
+///
+/// Crates are matched with their previous state by name, so upgrading a crate to a flagged version counts too.
+fn flagged_crates<'a>(crates: &'a [ReportableCrate], config: &NotifyConfig, previous: &NotifyState) -> Vec<&'a ReportableCrate> {
+    crates
+        .iter()
+        .filter(|c| {
+            let before = previous.get(&*c.name).copied().unwrap_or_default();
+            let now = CrateState::new(c);
+            let entered_band = now.risk >= Some(config.min_risk) && before.risk < Some(config.min_risk);
+            let new_advisory = config.on_advisories && now.vulnerabilities > before.vulnerabilities;
+            entered_band || new_advisory
+        })
+        .collect()
+}
+
+/// Render the notification text by expanding the placeholders in the configured template.
+fn build_message(newly_flagged: &[&ReportableCrate], crate_count: usize, config: &NotifyConfig) -> String {
+    let mut crate_lines = String::new();
+    for crate_info in newly_flagged {
+        let _ = write!(crate_lines, "- {} v{}", crate_info.name, crate_info.version);
+        if let Some(appraisal) = &crate_info.appraisal {
+            let _ = write!(crate_lines, ": {} (score {:.0})", appraisal.risk, appraisal.score);
+        }
+        if crate_info.vulnerability_count() > 0 {
+            crate_lines.push_str(" [vulnerable]");
+        }
+        crate_lines.push('\n');
+    }
+
+    config
+        .template
+        .replace("{flagged}", &newly_flagged.len().to_string())
+        .replace("{total}", &crate_count.to_string())
+        .replace("{min_risk}", &config.min_risk.to_string())
+        .replace("{crates}", crate_lines.trim_end())
+}
+
+/// Wrap the message in the payload shape expected by the target chat service.
+fn build_payload(message: &str, format: NotifyFormat) -> serde_json::Value {
+    match format {
+        NotifyFormat::Slack => json!({ "text": message }),
+        NotifyFormat::Teams => json!({
+            "@type": "MessageCard",
+            "@context": "https://schema.org/extensions",
+            "summary": "cargo-aprz dependency appraisal",
+            "title": "cargo-aprz dependency appraisal",
+            "text": message.replace('\n', "<br>"),
+        }),
+    }
+}
+
+/// Post a summary of the newly flagged crates to the configured webhook, comparing against the state kept
+/// in `state_path` by the previous run.
+///
+/// Nothing is sent when no crate newly meets the notification criteria. The state is only updated once the
+/// notification is delivered, so that a failed delivery is retried by the next run.
+pub async fn send_notification(config: &NotifyConfig, crates: &[ReportableCrate], state_path: &Path) -> Result<()> {
+    let previous = load_state(state_path);
+    let flagged = flagged_crates(crates, config, &previous);
+    if flagged.is_empty() {
+        log::debug!(target: LOG_TARGET, "No crates newly met the notification criteria, skipping webhook");
+    } else {
+        let message = build_message(&flagged, crates.len(), config);
+        log::info!(target: LOG_TARGET, "Posting notification for {} newly flagged crate(s)", flagged.len());
+        post_message(config, &message).await?;
+    }
+
+    let state: NotifyState = crates.iter().map(|c| (c.name.to_string(), CrateState::new(c))).collect();
+    save_state(state_path, &state)
+}
+
+/// Read the state left by the previous run, treating a missing or unreadable file as no previous run.
+fn load_state(path: &Path) -> NotifyState {
+    let Ok(text) = fs::read_to_string(path) else {
+        return NotifyState::new();
+    };
+
+    serde_json::from_str(&text).unwrap_or_else(|e| {
+        log::warn!(target: LOG_TARGET, "Could not parse notification state '{}': {e}", path.display());
+        NotifyState::new()
+    })
+}
+
+fn save_state(path: &Path, state: &NotifyState) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).into_app_err_with(|| format!("creating directory '{}'", dir.display()))?;
+    }
+
+    let text = serde_json::to_string_pretty(state).into_app_err("serializing notification state")?;
+    fs::write(path, text).into_app_err_with(|| format!("writing notification state '{}'", path.display()))
+}
+
+/// Post the alerts raised for a workspace to the configured webhook.
+pub async fn send_alerts(config: &NotifyConfig, workspace: &str, alerts: &[Alert]) -> Result<()> {
+    let mut message = format!("cargo-aprz raised {} alert(s) for {workspace}:", alerts.len());
+    for alert in alerts {
+        let _ = write!(message, "\n- {alert}");
+    }
+
+    log::info!(target: LOG_TARGET, "Posting {} alert(s)", alerts.len());
+    post_message(config, &message).await
+}
+
+async fn post_message(config: &NotifyConfig, message: &str) -> Result<()> {
+    let payload = build_payload(message, config.format);
+    let response = reqwest::Client::new()
+        .post(&config.webhook_url)
+        .json(&payload)
+        .send()
+        .await
+        .into_app_err("sending webhook notification")?;
+
+    let status = response.status();
+    if !status.is_success() {
+        bail!("webhook notification failed with HTTP status {status}");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::{Appraisal, Risk};
+    use crate::metrics::{Metric, MetricCategory, MetricDef, MetricValue};
+    use semver::Version;
+    use std::sync::Arc;
+    use wiremock::matchers::{body_partial_json, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    static VULN_DEF: MetricDef = MetricDef {
+        name: "advisories.version_high_severity_vulnerabilities",
+        description: "High severity vulnerabilities",
+        category: MetricCategory::Advisories,
+        extractor: |_| None,
+        default_value: || None,
+    };
+
+    fn make_crate(name: &str, risk: Risk, vulnerabilities: u64) -> ReportableCrate {
+        ReportableCrate::new(
+            Arc::from(name),
+            Arc::new(Version::new(1, 0, 0)),
+            vec![Metric::with_value(&VULN_DEF, MetricValue::UInt(vulnerabilities))],
+            Some(Appraisal::new(risk, vec![], 10, 2, 20.0)),
+        )
+    }
+
+    fn make_config(webhook_url: &str) -> NotifyConfig {
+        NotifyConfig {
+            webhook_url: webhook_url.to_string(),
+            format: NotifyFormat::Slack,
+            template: "{flagged}/{total} {min_risk}\n{crates}".to_string(),
+            min_risk: Risk::High,
+            on_advisories: true,
+        }
+    }
+
+    fn state(crates: &[ReportableCrate]) -> NotifyState {
+        crates.iter().map(|c| (c.name.to_string(), CrateState::new(c))).collect()
+    }
+
+    #[test]
+    fn test_flagged_crates_respects_min_risk() {
+        let crates = vec![make_crate("a", Risk::High, 0), make_crate("b", Risk::Medium, 0), make_crate("c", Risk::Low, 0)];
+        let mut config = make_config("https://example.com");
+        assert_eq!(flagged_crates(&crates, &config, &NotifyState::new()).len(), 1);
+
+        config.min_risk = Risk::Medium;
+        assert_eq!(flagged_crates(&crates, &config, &NotifyState::new()).len(), 2);
+    }
+
+    #[test]
+    fn test_flagged_crates_includes_vulnerable_crates() {
+        let crates = vec![make_crate("a", Risk::Low, 1)];
+        let mut config = make_config("https://example.com");
+        assert_eq!(flagged_crates(&crates, &config, &NotifyState::new()).len(), 1);
+
+        config.on_advisories = false;
+        assert!(flagged_crates(&crates, &config, &NotifyState::new()).is_empty());
+    }
+
+    #[test]
+    fn test_flagged_crates_only_reports_transitions() {
+        let config = make_config("https://example.com");
+        let previous = state(&[make_crate("a", Risk::High, 0), make_crate("b", Risk::Medium, 0), make_crate("c", Risk::Low, 1)]);
+        let crates = vec![
+            make_crate("a", Risk::High, 0),
+            make_crate("b", Risk::High, 0),
+            make_crate("c", Risk::Low, 1),
+            make_crate("d", Risk::Low, 2),
+        ];
+
+        let flagged: Vec<_> = flagged_crates(&crates, &config, &previous).iter().map(|c| c.name.to_string()).collect();
+        assert_eq!(flagged, ["b", "d"]);
+    }
+
+    #[test]
+    fn test_build_message_expands_placeholders() {
+        let crates = vec![make_crate("a", Risk::High, 1), make_crate("b", Risk::Low, 0)];
+        let config = make_config("https://example.com");
+        let flagged = flagged_crates(&crates, &config, &NotifyState::new());
+        let message = build_message(&flagged, crates.len(), &config);
+        assert_eq!(message, "1/2 HIGH RISK\n- a v1.0.0: HIGH RISK (score 20) [vulnerable]");
+    }
+
+    #[test]
+    fn test_build_payload_shapes() {
+        let slack = build_payload("hello\nworld", NotifyFormat::Slack);
+        assert_eq!(slack["text"], "hello\nworld");
+
+        let teams = build_payload("hello\nworld", NotifyFormat::Teams);
+        assert_eq!(teams["@type"], "MessageCard");
+        assert_eq!(teams["text"], "hello<br>world");
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore = "Miri cannot call CreateIoCompletionPort")]
+    async fn test_send_notification_posts_payload() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({ "text": "1/1 HIGH RISK\n- a v1.0.0: HIGH RISK (score 20)" })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let state_path = tmp.path().join("notify").join("workspace.json");
+        let crates = vec![make_crate("a", Risk::High, 0)];
+        send_notification(&make_config(&server.uri()), &crates, &state_path).await.unwrap();
+
+        // The crate stays flagged, so the next run has nothing new to report
+        send_notification(&make_config(&server.uri()), &crates, &state_path).await.unwrap();
+        assert_eq!(load_state(&state_path), state(&crates));
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore = "Miri cannot call CreateIoCompletionPort")]
+    async fn test_send_alerts_posts_payload() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({ "text": "cargo-aprz raised 1 alert(s) for payments:\n- Regression: leftpad dropped" })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let alerts = [Alert {
+            rule: "Regression".into(),
+            message: "leftpad dropped".into(),
+        }];
+        send_alerts(&make_config(&server.uri()), "payments", &alerts).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore = "Miri cannot call CreateIoCompletionPort")]
+    async fn test_send_notification_skips_when_nothing_flagged() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let crates = vec![make_crate("a", Risk::Low, 0)];
+        send_notification(&make_config(&server.uri()), &crates, &tmp.path().join("workspace.json")).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore = "Miri cannot call CreateIoCompletionPort")]
+    async fn test_send_notification_reports_http_failure() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let state_path = tmp.path().join("workspace.json");
+        let crates = vec![make_crate("a", Risk::High, 0)];
+        let _ = send_notification(&make_config(&server.uri()), &crates, &state_path).await.unwrap_err();
+        assert!(!state_path.exists());
+    }
+}
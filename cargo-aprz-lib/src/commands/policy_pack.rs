@@ -0,0 +1,389 @@
+//! Signed policy packs, letting a security team publish a curated policy that many repositories share.
+//!
+//! A policy pack is a TOML file with a `name`, a `version`, and `snippets`, `high_risk`, and `eval` entries
+//! written just like those of the configuration file. Packs are read along with a minisign signature from an
+//! HTTP(S) URL, from a revision of a git repository, or from a registry serving the versions of each pack it
+//! holds. A pack is only used once its signature verifies against the public key configured for it.
+
+use super::config::{Config, PackLocation, PolicyPackSource, policy_origin};
+use crate::Result;
+use crate::expr::{Expression, is_valid_snippet_name};
+use crate::facts::codebase::read_remote_files;
+use minisign_verify::{PublicKey, Signature};
+use ohno::{IntoAppError, app_err, bail};
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+const LOG_TARGET: &str = "    policy";
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PolicyPack {
+    name: String,
+    version: Version,
+
+    #[serde(default)]
+    snippets: BTreeMap<String, String>,
+
+    #[serde(default)]
+    high_risk: Vec<Expression>,
+
+    #[serde(default)]
+    eval: Vec<Expression>,
+}
+
+/// The versions of a pack a registry holds, served as `<registry>/<name>/index.json`.
+#[derive(Debug, Deserialize)]
+struct RegistryIndex {
+    versions: Vec<Version>,
+}
+
+/// Fetch the policy packs listed in the configuration, and add their snippets and expressions to it.
+///
+/// # Errors
+///
+/// Returns an error if a pack can't be fetched, its signature doesn't verify, its version doesn't meet the
+/// configured requirement, or its content conflicts with the configuration
+pub async fn apply_policy_packs(config: &mut Config) -> Result<()> {
+    if config.policy_packs.is_empty() {
+        return Ok(());
+    }
+
+    let client = reqwest::Client::new();
+    let mut packs = Vec::with_capacity(config.policy_packs.len());
+    for source in &config.policy_packs {
+        let location = source.location()?;
+        let (pack_bytes, signature, listed_version) = fetch_pack(&client, source, location).await?;
+
+        let pack = verify_pack(source, &pack_bytes, &signature, listed_version.as_ref())?;
+        log::info!(target: LOG_TARGET, "Using policy pack '{}' v{} from '{location}'", pack.name, pack.version);
+        packs.push((pack, location.to_string(), pack_bytes));
+    }
+
+    for (pack, location, pack_bytes) in packs {
+        // The pack's text was checked to be UTF-8 when verifying it
+        let text = String::from_utf8_lossy(&pack_bytes);
+        merge_pack(config, pack, |name| policy_origin(&location, &text, name))?;
+    }
+
+    Ok(())
+}
+
+/// Fetch a pack and its signature from wherever the source says, along with the version a registry lists the pack as.
+async fn fetch_pack(
+    client: &reqwest::Client,
+    source: &PolicyPackSource,
+    location: PackLocation<'_>,
+) -> Result<(Vec<u8>, String, Option<Version>)> {
+    let mut listed_version = None;
+    let (pack_bytes, signature_bytes) = match location {
+        PackLocation::Url { url, signature_url } => {
+            let signature_url = signature_url.map_or_else(|| format!("{url}.minisig"), ToString::to_string);
+            (fetch(client, url).await?, fetch(client, &signature_url).await?)
+        }
+
+        PackLocation::Git { repo, rev, path } => {
+            let signature_path = format!("{path}.minisig");
+            let mut files = read_remote_files(repo, rev, &[path, &signature_path]).await?.into_iter();
+            match (files.next(), files.next()) {
+                (Some(pack_bytes), Some(signature_bytes)) => (pack_bytes, signature_bytes),
+                _ => bail!("reading policy pack '{location}'"),
+            }
+        }
+
+        PackLocation::Registry { registry, name } => {
+            let version = registry_version(client, source, registry, name).await?;
+            let url = format!("{registry}/{name}/{version}.toml");
+            listed_version = Some(version);
+            (fetch(client, &url).await?, fetch(client, &format!("{url}.minisig")).await?)
+        }
+    };
+
+    let signature = String::from_utf8(signature_bytes).into_app_err_with(|| format!("reading signature of policy pack '{location}'"))?;
+    Ok((pack_bytes, signature, listed_version))
+}
+
+/// Pick the latest version of a pack in a registry that meets the source's version requirement.
+async fn registry_version(client: &reqwest::Client, source: &PolicyPackSource, registry: &str, name: &str) -> Result<Version> {
+    let index_url = format!("{registry}/{name}/index.json");
+    let index: RegistryIndex =
+        serde_json::from_slice(&fetch(client, &index_url).await?).into_app_err_with(|| format!("parsing '{index_url}'"))?;
+
+    let req = source.version.clone().unwrap_or(VersionReq::STAR);
+    index
+        .versions
+        .into_iter()
+        .filter(|version| req.matches(version))
+        .max()
+        .ok_or_else(|| app_err!("registry '{registry}' has no version of policy pack '{name}' meeting requirement '{req}'"))
+}
+
+async fn fetch(client: &reqwest::Client, url: &str) -> Result<Vec<u8>> {
+    let response = client.get(url).send().await.into_app_err_with(|| format!("fetching '{url}'"))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        bail!("fetching '{url}' failed with HTTP status {status}");
+    }
+
+    let bytes = response.bytes().await.into_app_err_with(|| format!("reading '{url}'"))?;
+    Ok(bytes.to_vec())
+}
+
+/// Check the pack's signature and version, returning the parsed pack. `listed_version` is the version a registry
+/// listed the pack as.
+fn verify_pack(source: &PolicyPackSource, pack_bytes: &[u8], signature: &str, listed_version: Option<&Version>) -> Result<PolicyPack> {
+    let url = source.location()?;
+    let public_key =
+        PublicKey::from_base64(&source.public_key).map_err(|e| app_err!("invalid public key for policy pack '{url}': {e}"))?;
+    let signature = Signature::decode(signature).map_err(|e| app_err!("invalid signature for policy pack '{url}': {e}"))?;
+    public_key
+        .verify(pack_bytes, &signature, false)
+        .map_err(|e| app_err!("signature of policy pack '{url}' doesn't verify: {e}"))?;
+
+    let text = core::str::from_utf8(pack_bytes).into_app_err_with(|| format!("reading policy pack '{url}'"))?;
+    let pack: PolicyPack = toml::from_str(text).into_app_err_with(|| format!("parsing policy pack '{url}'"))?;
+
+    if let Some(req) = &source.version
+        && !req.matches(&pack.version)
+    {
+        bail!("policy pack '{}' from '{url}' has version {}, which doesn't meet requirement '{req}'", pack.name, pack.version);
+    }
+
+    // A registry serves each version of a pack at its own URL, which mustn't hold an older signed pack instead
+    if let (PackLocation::Registry { name, .. }, Some(listed)) = (url, listed_version)
+        && (pack.name != name || pack.version != *listed)
+    {
+        bail!("policy pack from '{url}' is '{}' v{} rather than v{listed}", pack.name, pack.version);
+    }
+
+    Ok(pack)
+}
+
+/// Add the pack's snippets and expressions to the configuration, expanding snippet references in the latter and
+/// recording the origin `origin` gives for each expression name.
+fn merge_pack(config: &mut Config, pack: PolicyPack, origin: impl Fn(&str) -> String) -> Result<()> {
+    for (name, text) in pack.snippets {
+        if !is_valid_snippet_name(&name) {
+            bail!("snippet name '{name}' in policy pack '{}' must consist of letters, digits, and underscores", pack.name);
+        }
+
+        if config.snippets.get(&name).is_some_and(|existing| *existing != text) {
+            bail!("snippet '{name}' in policy pack '{}' conflicts with a snippet of the same name", pack.name);
+        }
+
+        let _ = config.snippets.insert(name, text);
+    }
+
+    for expr in pack.high_risk {
+        config.high_risk.push(expr.with_origin(origin(expr.name())).with_snippets(&config.snippets)?);
+    }
+
+    for expr in pack.eval {
+        config.eval.push(expr.with_origin(origin(expr.name())).with_snippets(&config.snippets)?);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use minisign::KeyPair;
+    use std::io::Cursor;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const PACK: &str = r#"
+name = "acme-security"
+version = "1.2.0"
+
+[snippets]
+maintained = "activity.days_since_last_commit < 365"
+
+[[high_risk]]
+name = "Maintained"
+expression = "snippets.maintained"
+"#;
+
+    fn sign(key_pair: &KeyPair, content: &str) -> String {
+        minisign::sign(Some(&key_pair.pk), &key_pair.sk, Cursor::new(content), None, None)
+            .unwrap()
+            .into_string()
+    }
+
+    fn make_source(key_pair: &KeyPair, version: Option<&str>) -> PolicyPackSource {
+        PolicyPackSource {
+            url: Some("https://policies.example.com/acme.toml".into()),
+            public_key: key_pair.pk.to_base64(),
+            version: version.map(|v| v.parse().unwrap()),
+            ..PolicyPackSource::default()
+        }
+    }
+
+    #[test]
+    fn test_verify_pack() {
+        let key_pair = KeyPair::generate_unencrypted_keypair().unwrap();
+        let signature = sign(&key_pair, PACK);
+
+        let pack = verify_pack(&make_source(&key_pair, Some("^1.2")), PACK.as_bytes(), &signature, None).unwrap();
+        assert_eq!(pack.name, "acme-security");
+        assert_eq!(pack.high_risk.len(), 1);
+
+        let err = verify_pack(&make_source(&key_pair, Some("^2")), PACK.as_bytes(), &signature, None).unwrap_err();
+        assert!(format!("{err:#}").contains("doesn't meet requirement"));
+
+        let tampered = PACK.replace("365", "3650");
+        let err = verify_pack(&make_source(&key_pair, None), tampered.as_bytes(), &signature, None).unwrap_err();
+        assert!(format!("{err:#}").contains("doesn't verify"));
+
+        let other_key_pair = KeyPair::generate_unencrypted_keypair().unwrap();
+        let _ = verify_pack(&make_source(&other_key_pair, None), PACK.as_bytes(), &signature, None).unwrap_err();
+    }
+
+    #[test]
+    fn test_merge_pack() {
+        let key_pair = KeyPair::generate_unencrypted_keypair().unwrap();
+        let pack = verify_pack(&make_source(&key_pair, None), PACK.as_bytes(), &sign(&key_pair, PACK), None).unwrap();
+
+        let mut config = Config::default();
+        let high_risk_count = config.high_risk.len();
+        merge_pack(&mut config, pack, |name| policy_origin("acme.toml", PACK, name)).unwrap();
+
+        assert_eq!(config.high_risk.len(), high_risk_count + 1);
+        assert_eq!(config.high_risk[high_risk_count].expression(), "(activity.days_since_last_commit < 365)");
+        assert_eq!(config.high_risk[high_risk_count].origin(), Some("acme.toml:9"));
+
+        let pack = verify_pack(&make_source(&key_pair, None), PACK.as_bytes(), &sign(&key_pair, PACK), None).unwrap();
+        let mut config = Config::default();
+        let _ = config.snippets.insert("maintained".into(), "true".into());
+        let err = merge_pack(&mut config, pack, ToString::to_string).unwrap_err();
+        assert!(format!("{err:#}").contains("conflicts"));
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore = "Miri cannot call CreateIoCompletionPort")]
+    async fn test_apply_policy_packs_fetches_signed_pack() {
+        let key_pair = KeyPair::generate_unencrypted_keypair().unwrap();
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/acme.toml"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(PACK))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/acme.toml.minisig"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(sign(&key_pair, PACK)))
+            .mount(&server)
+            .await;
+
+        let mut config = Config::default();
+        let high_risk_count = config.high_risk.len();
+        config.policy_packs.push(PolicyPackSource {
+            url: Some(format!("{}/acme.toml", server.uri())),
+            ..make_source(&key_pair, None)
+        });
+
+        apply_policy_packs(&mut config).await.unwrap();
+        assert_eq!(config.high_risk.len(), high_risk_count + 1);
+        assert_eq!(config.high_risk[high_risk_count].name(), "Maintained");
+    }
+
+    #[test]
+    fn test_location() {
+        let key_pair = KeyPair::generate_unencrypted_keypair().unwrap();
+        let git = PolicyPackSource {
+            url: None,
+            git: Some("https://github.com/acme/policies.git".into()),
+            path: Some("acme.toml".into()),
+            ..make_source(&key_pair, None)
+        };
+        assert_eq!(git.location().unwrap().to_string(), "https://github.com/acme/policies.git@HEAD:acme.toml");
+
+        let registry = PolicyPackSource {
+            url: None,
+            registry: Some("https://policies.example.com/".into()),
+            name: Some("acme-security".into()),
+            ..make_source(&key_pair, None)
+        };
+        assert_eq!(registry.location().unwrap().to_string(), "https://policies.example.com/acme-security");
+
+        let both = PolicyPackSource {
+            git: Some("https://github.com/acme/policies.git".into()),
+            ..make_source(&key_pair, None)
+        };
+        assert!(format!("{:#}", both.location().unwrap_err()).contains("exactly one"));
+
+        let stray = PolicyPackSource {
+            rev: Some("v1".into()),
+            ..make_source(&key_pair, None)
+        };
+        assert!(format!("{:#}", stray.location().unwrap_err()).contains("can't have 'rev'"));
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore = "Miri cannot spawn processes")]
+    async fn test_apply_policy_packs_reads_pack_from_git() {
+        let key_pair = KeyPair::generate_unencrypted_keypair().unwrap();
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::write(temp.path().join("acme.toml"), PACK).unwrap();
+        std::fs::write(temp.path().join("acme.toml.minisig"), sign(&key_pair, PACK)).unwrap();
+        for args in [&["init", "--quiet"][..], &["add", "."], &["-c", "user.name=t", "-c", "user.email=t@t", "commit", "--quiet", "-m", "pack"]] {
+            let status = std::process::Command::new("git").arg("-C").arg(temp.path()).args(args).status().unwrap();
+            assert!(status.success());
+        }
+
+        let mut config = Config::default();
+        let high_risk_count = config.high_risk.len();
+        config.policy_packs.push(PolicyPackSource {
+            url: None,
+            git: Some(format!("file://{}", temp.path().display())),
+            path: Some("acme.toml".into()),
+            ..make_source(&key_pair, None)
+        });
+
+        apply_policy_packs(&mut config).await.unwrap();
+        assert_eq!(config.high_risk.len(), high_risk_count + 1);
+        assert!(config.high_risk[high_risk_count].origin().unwrap().ends_with("@HEAD:acme.toml:9"));
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore = "Miri cannot call CreateIoCompletionPort")]
+    async fn test_apply_policy_packs_picks_registry_version() {
+        let key_pair = KeyPair::generate_unencrypted_keypair().unwrap();
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/acme-security/index.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"versions": ["1.0.0", "1.2.0", "2.0.0"]}"#))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/acme-security/1.2.0.toml"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(PACK))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/acme-security/1.2.0.toml.minisig"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(sign(&key_pair, PACK)))
+            .mount(&server)
+            .await;
+
+        let source = PolicyPackSource {
+            url: None,
+            registry: Some(server.uri()),
+            name: Some("acme-security".into()),
+            ..make_source(&key_pair, Some("^1"))
+        };
+
+        let mut config = Config::default();
+        let high_risk_count = config.high_risk.len();
+        config.policy_packs.push(source);
+        apply_policy_packs(&mut config).await.unwrap();
+        assert_eq!(config.high_risk.len(), high_risk_count + 1);
+
+        // A pack served under a version other than its own is rejected
+        let pack = verify_pack(&config.policy_packs[0], PACK.as_bytes(), &sign(&key_pair, PACK), Some(&Version::new(1, 3, 0)));
+        assert!(format!("{:#}", pack.unwrap_err()).contains("rather than v1.3.0"));
+    }
+}
@@ -0,0 +1,286 @@
+//! Built-in scoring presets, adjusting expectations for crates in some crates.io categories.
+//!
+//! A preset lists the categories it applies to, along with `high_risk` and `eval` entries written just like those
+//! of the configuration file. An entry whose `replaces` key names an expression of the configuration replaces that
+//! expression for crates in the preset's categories, leaving other crates to the original, and the combined
+//! expression is named after both. Other `high_risk` entries are added, only ever flagging crates in the preset's
+//! categories. Other `eval` entries are ignored, as points only some crates could earn would skew the scores of the
+//! rest.
+
+use super::config::{Config, policy_origin};
+use crate::Result;
+use crate::expr::Expression;
+use ohno::{IntoAppError, bail};
+use serde::Deserialize;
+
+const LOG_TARGET: &str = "   presets";
+
+/// The built-in presets, by name, along with their TOML text
+const PRESETS: &[(&str, &str)] = &[
+    ("cryptography", include_str!("../../presets/cryptography.toml")),
+    ("dev-tools", include_str!("../../presets/dev-tools.toml")),
+];
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Preset {
+    description: String,
+
+    /// Slugs of the crates.io categories the preset applies to, subcategories included
+    categories: Vec<String>,
+
+    #[serde(default)]
+    high_risk: Vec<PresetExpression>,
+
+    #[serde(default)]
+    eval: Vec<PresetExpression>,
+}
+
+/// An expression of a preset, along with the name of the configuration expression it stands in for, if any
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PresetExpression {
+    name: String,
+    replaces: Option<String>,
+    description: Option<String>,
+    expression: String,
+    points: Option<u32>,
+}
+
+impl PresetExpression {
+    fn to_expression(&self) -> Result<Expression> {
+        Expression::new(&self.name, self.description.as_deref(), &self.expression, self.points)
+    }
+}
+
+/// Names of the built-in presets.
+fn preset_names() -> impl Iterator<Item = &'static str> {
+    PRESETS.iter().map(|(name, _)| *name)
+}
+
+/// Apply the presets listed in the configuration to its expressions.
+///
+/// # Errors
+///
+/// Returns an error if a preset is unknown or one of its expressions can't be combined with the configuration's
+pub fn apply_presets(config: &mut Config) -> Result<()> {
+    for name in config.presets.clone() {
+        let Some((_, text)) = PRESETS.iter().find(|(preset, _)| *preset == name) else {
+            bail!(
+                "unknown preset '{name}', expected one of: {}",
+                preset_names().collect::<Vec<_>>().join(", ")
+            );
+        };
+
+        let preset: Preset = toml::from_str(text).into_app_err_with(|| format!("parsing preset '{name}'"))?;
+        log::info!(target: LOG_TARGET, "Using preset '{name}': {}", preset.description);
+        merge_preset(config, &name, &preset, |expr_name| {
+            policy_origin(&format!("presets/{name}.toml"), text, expr_name)
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Combine the expressions of the preset called `name` with those of the configuration, recording the origin `origin`
+/// gives for each expression name.
+///
+/// Replacing an expression the configuration doesn't have, as when it was renamed, is warned about: the preset's
+/// `high_risk` entry is added nonetheless, while its `eval` entry is ignored.
+fn merge_preset(config: &mut Config, name: &str, preset: &Preset, origin: impl Fn(&str) -> String) -> Result<()> {
+    let condition = category_condition(&preset.categories);
+
+    for entry in &preset.high_risk {
+        let expr = entry.to_expression()?.with_origin(origin(&entry.name));
+        let existing = entry
+            .replaces
+            .as_deref()
+            .and_then(|target| find_replaced(name, &mut config.high_risk, target));
+        if let Some(existing) = existing {
+            *existing = scoped(&condition, &expr, Some(&*existing))?;
+        } else {
+            config.high_risk.push(scoped(&condition, &expr, None)?);
+        }
+    }
+
+    for entry in &preset.eval {
+        let expr = entry.to_expression()?.with_origin(origin(&entry.name));
+        let existing = entry
+            .replaces
+            .as_deref()
+            .and_then(|target| find_replaced(name, &mut config.eval, target));
+        if let Some(existing) = existing {
+            *existing = scoped(&condition, &expr, Some(&*existing))?;
+        } else {
+            log::debug!(target: LOG_TARGET, "Ignoring preset expression '{}', as it replaces no expression of the configuration", entry.name);
+        }
+    }
+
+    Ok(())
+}
+
+/// The expression named `target` a preset replaces, warning when the configuration doesn't have it.
+fn find_replaced<'a>(preset: &str, expressions: &'a mut [Expression], target: &str) -> Option<&'a mut Expression> {
+    let found = expressions.iter_mut().find(|expr| expr.name() == target);
+    if found.is_none() {
+        log::warn!(target: LOG_TARGET, "Preset '{preset}' replaces expression '{target}', which the configuration doesn't have");
+    }
+
+    found
+}
+
+/// A CEL condition holding for crates in any of the given categories or their subcategories.
+fn category_condition(categories: &[String]) -> String {
+    let tests: Vec<String> = categories
+        .iter()
+        .map(|slug| format!("c == '{slug}' || c.startsWith('{slug}::')"))
+        .collect();
+    format!("crate.categories.exists(c, {})", tests.join(" || "))
+}
+
+/// An expression evaluating `entry` for crates meeting `condition`, and `fallback` for the others, or passing
+/// them when there's no fallback. A combined expression is named after both, so its name reflects both checks.
+fn scoped(condition: &str, entry: &Expression, fallback: Option<&Expression>) -> Result<Expression> {
+    let otherwise = fallback.map_or("true", Expression::expression);
+    let text = format!("({condition}) ? ({}) : ({otherwise})", entry.expression());
+    let description = entry.description().or_else(|| fallback.and_then(Expression::description));
+    let points = entry.points().or_else(|| fallback.and_then(Expression::points));
+    let name = match fallback {
+        Some(fallback) if fallback.name() != entry.name() => format!("{} / {}", fallback.name(), entry.name()),
+        _ => entry.name().to_string(),
+    };
+
+    let expr = Expression::new(&name, description, &text, points)?;
+    Ok(match entry.origin_arc() {
+        Some(origin) => expr.with_origin(origin),
+        None => expr,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::{ExpressionDisposition, evaluate};
+    use crate::metrics::{Metric, MetricValue, default_metrics};
+    use chrono::Local;
+
+    fn metrics(categories: &[&str], downloads: u64) -> Vec<Metric> {
+        let mut metrics: Vec<Metric> = default_metrics().collect();
+        for metric in &mut metrics {
+            match metric.name() {
+                "crate.categories" => {
+                    metric.value = Some(MetricValue::List(
+                        categories.iter().map(|c| MetricValue::String((*c).into())).collect(),
+                    ));
+                }
+                "usage.total_downloads_last_90_days" => metric.value = Some(MetricValue::UInt(downloads)),
+                _ => {}
+            }
+        }
+
+        metrics
+    }
+
+    fn disposition(config: &Config, metrics: &[Metric], name: &str) -> ExpressionDisposition {
+        let appraisal = evaluate(&config.high_risk, &config.eval, metrics, Local::now(), 30.0, 70.0);
+        appraisal.expression_outcomes.into_iter().find(|o| &*o.name == name).unwrap().disposition
+    }
+
+    fn outcome(config: &Config, metrics: &[Metric], name: &str) -> Option<bool> {
+        match disposition(config, metrics, name) {
+            ExpressionDisposition::True => Some(true),
+            ExpressionDisposition::False => Some(false),
+            ExpressionDisposition::Failed(_) => None,
+        }
+    }
+
+    fn config_with_presets(presets: &[&str]) -> Config {
+        Config {
+            presets: presets.iter().map(ToString::to_string).collect(),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_presets_parse() {
+        for (name, text) in PRESETS {
+            let preset: Preset = toml::from_str(text).unwrap_or_else(|e| panic!("preset '{name}' should parse: {e}"));
+            assert!(!preset.categories.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_cryptography_preset() {
+        let mut config = config_with_presets(&["cryptography"]);
+        let high_risk_count = config.high_risk.len();
+        apply_presets(&mut config).unwrap();
+
+        assert_eq!(config.high_risk.len(), high_risk_count + 3);
+        let coverage = "Test Coverage (cryptography)";
+        assert_eq!(outcome(&config, &metrics(&["cryptography"], 0), coverage), Some(false));
+        assert_eq!(
+            outcome(&config, &metrics(&["cryptography::cryptocurrencies"], 0), coverage),
+            Some(false)
+        );
+        assert_eq!(outcome(&config, &metrics(&["parsing"], 0), coverage), Some(true));
+
+        let origin = config.high_risk.iter().find(|e| e.name() == coverage).and_then(Expression::origin);
+        assert!(origin.is_some_and(|origin| origin.starts_with("presets/cryptography.toml:")));
+    }
+
+    #[test]
+    fn test_dev_tools_preset() {
+        let mut config = config_with_presets(&["dev-tools"]);
+        let eval_count = config.eval.len();
+        apply_presets(&mut config).unwrap();
+
+        assert_eq!(config.eval.len(), eval_count);
+
+        // The placeholder metrics trip the default high-risk expressions, which would leave the eval expressions unevaluated
+        config.high_risk.clear();
+        let popular = "Popular Crate (>= 5,000 downloads in 90 days) / Popular Tool (>= 1,000 downloads in 90 days)";
+        assert_eq!(outcome(&config, &metrics(&["command-line-utilities"], 2000), popular), Some(true));
+        assert_eq!(
+            outcome(&config, &metrics(&["development-tools::cargo-plugins"], 2000), popular),
+            Some(true)
+        );
+        assert_eq!(outcome(&config, &metrics(&["parsing"], 2000), popular), Some(false));
+        assert_eq!(outcome(&config, &metrics(&["parsing"], 6000), popular), Some(true));
+    }
+
+    #[test]
+    fn test_preset_replacing_missing_expression() {
+        let preset: Preset = toml::from_str(
+            r#"
+            description = "Test"
+            categories = ["parsing"]
+
+            [[high_risk]]
+            name = "Renamed (parsing)"
+            replaces = "Renamed"
+            expression = "true"
+
+            [[eval]]
+            name = "Renamed Eval (parsing)"
+            replaces = "Renamed Eval"
+            expression = "true"
+            "#,
+        )
+        .unwrap();
+
+        let mut config = Config::default();
+        let (high_risk_count, eval_count) = (config.high_risk.len(), config.eval.len());
+        merge_preset(&mut config, "test", &preset, |_| "test".to_string()).unwrap();
+
+        assert_eq!(config.high_risk.len(), high_risk_count + 1);
+        assert_eq!(config.eval.len(), eval_count);
+        assert!(config.high_risk.iter().any(|e| e.name() == "Renamed (parsing)"));
+    }
+
+    #[test]
+    fn test_unknown_preset() {
+        let mut config = config_with_presets(&["gaming"]);
+        let err = apply_presets(&mut config).unwrap_err();
+        assert!(format!("{err:#}").contains("unknown preset 'gaming'"));
+    }
+}
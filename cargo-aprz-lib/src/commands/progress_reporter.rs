@@ -1,13 +1,14 @@
-use crate::facts::Progress;
+use crate::facts::{Progress, TopicProgress};
 use core::fmt::{Debug, Formatter};
 use core::sync::atomic::{AtomicBool, Ordering};
 use core::time::Duration;
-use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use indicatif::{HumanDuration, MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use tokio::task::JoinHandle;
 
 type ProgressCallback = Box<dyn Fn() -> (u64, u64, String) + Send + Sync>;
+type TopicsCallback = Arc<dyn Fn() -> Vec<TopicProgress> + Send + Sync>;
 
 /// Refresh rate for progress updates (10 Hz).
 const REFRESH_INTERVAL_MS: u64 = 100;
@@ -16,12 +17,14 @@ const DETERMINATE_TEMPLATE: &str = "{prefix:>12.bold.cyan} [{bar:25}] {msg}";
 const DETERMINATE_TEMPLATE_NO_COLOR: &str = "{prefix:>12} [{bar:25}] {msg}";
 const INDETERMINATE_TEMPLATE: &str = "{prefix:>12.bold.cyan} [{spinner}] {msg}";
 const INDETERMINATE_TEMPLATE_NO_COLOR: &str = "{prefix:>12} [{spinner}] {msg}";
+const TOPIC_TEMPLATE: &str = "{prefix:>12} [{bar:25}] {msg}";
 
 struct DelayedProgressState {
     visible_after: Instant,
     visible: AtomicBool,
     is_indeterminate: AtomicBool,
     phase_start_time: Mutex<Instant>,
+    topics_callback: Mutex<Option<TopicsCallback>>,
 }
 
 impl Debug for DelayedProgressState {
@@ -31,13 +34,18 @@ impl Debug for DelayedProgressState {
             .field("visible", &self.visible)
             .field("is_indeterminate", &self.is_indeterminate)
             .field("phase_start_time", &"<Instant>")
+            .field("topics_callback", &"<callback>")
             .finish()
     }
 }
 
-/// A progress bar that delays showing itself until a threshold is reached.
+/// A progress display that delays showing itself until a threshold is reached.
+///
+/// The display consists of a bar for the overall progress of the current phase, followed by a bar for each topic
+/// progressing concurrently, when there are any.
 #[derive(Clone)]
 pub struct ProgressReporter {
+    multi: MultiProgress,
     bar: ProgressBar,
     state: Arc<DelayedProgressState>,
     message_callback: Arc<Mutex<ProgressCallback>>,
@@ -52,24 +60,28 @@ impl ProgressReporter {
     /// When `use_colors` is false, progress bar chrome is rendered without ANSI styling.
     #[must_use]
     pub fn new(delay: Duration, use_colors: bool) -> Self {
-        let bar = ProgressBar::hidden();
-        bar.set_draw_target(ProgressDrawTarget::hidden());
+        let multi = MultiProgress::with_draw_target(ProgressDrawTarget::hidden());
+        let bar = multi.add(ProgressBar::new(0));
 
         let state = Arc::new(DelayedProgressState {
             visible_after: Instant::now() + delay,
             visible: AtomicBool::new(false),
             is_indeterminate: AtomicBool::new(false),
             phase_start_time: Mutex::new(Instant::now()),
+            topics_callback: Mutex::new(None),
         });
 
         let message_callback = Arc::new(Mutex::new(Box::new(|| (0u64, 0u64, String::new())) as ProgressCallback));
 
         Self {
             refresh_task: Arc::new(tokio::spawn(refresh_task(
+                multi.clone(),
                 bar.clone(),
                 Arc::clone(&state),
                 Arc::clone(&message_callback),
+                use_colors,
             ))),
+            multi,
             bar,
             state,
             message_callback,
@@ -88,6 +100,7 @@ impl Progress for ProgressReporter {
     /// Configure determinate progress reporting with a (total, current, message) callback.
     fn set_determinate(&self, callback: Box<dyn Fn() -> (u64, u64, String) + Send + Sync + 'static>) {
         *self.message_callback.lock().expect("lock poisoned") = callback;
+        *self.state.topics_callback.lock().expect("lock poisoned") = None;
         self.state.is_indeterminate.store(false, Ordering::Relaxed);
         self.bar.disable_steady_tick();
         self.bar.set_length(0);
@@ -108,6 +121,7 @@ impl Progress for ProgressReporter {
             (0, 0, message)
         });
         *self.state.phase_start_time.lock().expect("lock poisoned") = Instant::now();
+        *self.state.topics_callback.lock().expect("lock poisoned") = None;
         self.state.is_indeterminate.store(true, Ordering::Relaxed);
         self.bar.enable_steady_tick(Duration::from_millis(REFRESH_INTERVAL_MS));
 
@@ -179,9 +193,14 @@ impl Progress for ProgressReporter {
         );
     }
 
+    /// Show a bar for each topic below the overall progress bar.
+    fn set_topics(&self, callback: Box<dyn Fn() -> Vec<TopicProgress> + Send + Sync + 'static>) {
+        *self.state.topics_callback.lock().expect("lock poisoned") = Some(Arc::from(callback));
+    }
+
     /// Print a message line without disrupting the progress indicator.
     fn println(&self, msg: &str) {
-        self.bar.suspend(|| eprintln!("{msg}"));
+        self.multi.suspend(|| eprintln!("{msg}"));
     }
 
     /// Finish and clear the progress indicator.
@@ -189,6 +208,7 @@ impl Progress for ProgressReporter {
         self.refresh_task.abort();
         if self.state.visible.load(Ordering::Relaxed) {
             self.bar.finish_and_clear();
+            let _ = self.multi.clear();
         }
     }
 
@@ -200,6 +220,7 @@ impl Progress for ProgressReporter {
 impl Debug for ProgressReporter {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("ProgressReporter")
+            .field("multi", &self.multi)
             .field("bar", &self.bar)
             .field("state", &self.state)
             .field("message_callback", &"<callback>")
@@ -209,16 +230,24 @@ impl Debug for ProgressReporter {
     }
 }
 
-/// Background refresh task that periodically updates the progress bar.
-async fn refresh_task(bar: ProgressBar, state: Arc<DelayedProgressState>, callback: Arc<Mutex<ProgressCallback>>) {
+/// Background refresh task that periodically updates the progress bars.
+async fn refresh_task(
+    multi: MultiProgress,
+    bar: ProgressBar,
+    state: Arc<DelayedProgressState>,
+    callback: Arc<Mutex<ProgressCallback>>,
+    use_colors: bool,
+) {
     let mut interval = tokio::time::interval(Duration::from_millis(REFRESH_INTERVAL_MS));
+    let mut topic_bars: Vec<(&'static str, ProgressBar)> = Vec::new();
+
     #[expect(clippy::infinite_loop, reason = "task runs until aborted")]
     loop {
         let _ = interval.tick().await;
 
         if !state.visible.load(Ordering::Relaxed) && Instant::now() >= state.visible_after {
             state.visible.store(true, Ordering::Relaxed);
-            bar.set_draw_target(ProgressDrawTarget::stderr_with_hz(10));
+            multi.set_draw_target(ProgressDrawTarget::stderr_with_hz(10));
         }
 
         if state.visible.load(Ordering::Relaxed) {
@@ -236,11 +265,65 @@ async fn refresh_task(bar: ProgressBar, state: Arc<DelayedProgressState>, callba
                 message = format!("{elapsed_secs}s: {message}");
             }
 
+            let topics_callback = state.topics_callback.lock().expect("lock poisoned").clone();
+            let topics = topics_callback.map_or_else(Vec::new, |topics_callback| topics_callback());
+
+            // The topic bars detail the overall progress, so the overall bar only shows the totals
+            if !topics.is_empty() {
+                message = format!("{position}/{length} requests");
+            }
+
             if length > 0 {
                 bar.set_length(length);
                 bar.set_position(position);
             }
             bar.set_message(message);
+
+            update_topic_bars(&multi, &mut topic_bars, &topics, use_colors);
         }
     }
 }
+
+/// Add, update, and remove the topic bars to match the current state of the topics.
+fn update_topic_bars(
+    multi: &MultiProgress,
+    topic_bars: &mut Vec<(&'static str, ProgressBar)>,
+    topics: &[TopicProgress],
+    use_colors: bool,
+) {
+    topic_bars.retain(|(name, topic_bar)| {
+        let keep = topics.iter().any(|topic| topic.name == *name);
+        if !keep {
+            topic_bar.finish_and_clear();
+            multi.remove(topic_bar);
+        }
+        keep
+    });
+
+    for topic in topics {
+        let index = topic_bars.iter().position(|(name, _)| *name == topic.name).unwrap_or_else(|| {
+            let topic_bar = multi.add(ProgressBar::new(topic.total));
+            topic_bar.set_style(
+                ProgressStyle::default_bar()
+                    .template(TOPIC_TEMPLATE)
+                    .expect("could not create progress bar style")
+                    .progress_chars("=> "),
+            );
+            topic_bars.push((topic.name, topic_bar));
+            topic_bars.len() - 1
+        });
+
+        let topic_bar = &topic_bars[index].1;
+
+        topic_bar.set_length(topic.total);
+        topic_bar.set_position(topic.completed);
+
+        let counts = topic.status.style(format!("{}/{} {}", topic.completed, topic.total, topic.name), use_colors);
+        let message = if topic.completed < topic.total && topic.completed > 0 {
+            format!("{counts}, {} left", HumanDuration(topic_bar.eta()))
+        } else {
+            counts
+        };
+        topic_bar.set_message(message);
+    }
+}
@@ -0,0 +1,44 @@
+use super::Host;
+use super::common::{Common, CommonArgs};
+use crate::Result;
+use crate::facts::CrateRef;
+use clap::Parser;
+
+#[derive(Parser, Debug)]
+pub struct ReleasesArgs {
+    /// Crate whose releases to appraise (format: `crate_name` or `crate_name@version`), the given version
+    /// or else the latest one being the newest release appraised
+    #[arg(value_name = "CRATE")]
+    pub crate_ref: CrateRef,
+
+    /// Number of releases to appraise, going back from the newest one
+    #[arg(long, value_name = "N", default_value = "10", value_parser = clap::value_parser!(u32).range(1..))]
+    pub last: u32,
+
+    #[command(flatten)]
+    pub common: CommonArgs,
+}
+
+pub async fn score_releases<H: Host>(host: &mut H, args: &ReleasesArgs) -> Result<()> {
+    let mut common = Common::new(host, &args.common).await?;
+    let mut releases = Vec::new();
+
+    // Each release only knows the one before it, so releases are appraised one at a time going backwards
+    let mut next = Some(args.crate_ref.clone());
+    while let Some(crate_ref) = next.take()
+        && releases.len() < args.last as usize
+    {
+        let Some(facts) = common.process_crates(core::slice::from_ref(&crate_ref), releases.is_empty()).await?.pop() else {
+            break;
+        };
+
+        next = facts
+            .crates_data
+            .as_ref()
+            .and_then(|data| data.version_data.previous_version.clone())
+            .map(|version| CrateRef::new(crate_ref.name(), Some(version)));
+        releases.push(facts);
+    }
+
+    common.release_timeline(releases).await
+}
@@ -0,0 +1,188 @@
+//! Interactive evaluation of expressions against the metrics of a real crate.
+//!
+//! Each line entered is evaluated as a CEL expression and its value is printed, which shortens the loop of
+//! editing and validating expressions while authoring a policy. Lines starting with `:` are commands.
+
+use super::Host;
+use super::common::{Common, CommonArgs};
+use super::config::Config;
+use crate::Result;
+use crate::expr::{ExpressionDisposition, evaluate, evaluate_to_string};
+use crate::facts::CrateRef;
+use crate::metrics::{Metric, flatten};
+use crate::reports::format_metric_value;
+use chrono::Local;
+use clap::Parser;
+use ohno::{IntoAppError, bail};
+use std::io::{BufRead, Write};
+
+const HELP: &str = "\
+Enter an expression to evaluate it against the crate's metrics, for example `activity.open_issues < 100`.
+
+Commands:
+  :metrics [PREFIX]  List the crate's metrics, optionally only those whose name starts with PREFIX
+  :policy            Appraise the crate using the expressions of the configuration
+  :help              Show this help
+  :quit              Leave the REPL";
+
+#[derive(Parser, Debug)]
+pub struct ReplArgs {
+    /// Crate to evaluate expressions against (format: `crate_name` or `crate_name@version`)
+    #[arg(long = "crate", value_name = "CRATE")]
+    pub crate_ref: CrateRef,
+
+    #[command(flatten)]
+    pub common: CommonArgs,
+}
+
+pub async fn run_repl<H: Host>(host: &mut H, args: &ReplArgs) -> Result<()> {
+    let mut common = Common::new(host, &args.common).await?;
+    let crate_facts = common.process_crates(core::slice::from_ref(&args.crate_ref), true).await?;
+    let config = core::mem::take(&mut common.config);
+    drop(common);
+
+    let Some(facts) = crate_facts.into_iter().find(|facts| facts.crates_data.is_found()) else {
+        bail!("could not find information on crate '{}'", args.crate_ref);
+    };
+
+    let metrics: Vec<_> = flatten(&facts).collect();
+    let _ = writeln!(host.output(), "Loaded the metrics of {}. Enter :help for help.", facts.crate_spec);
+
+    repl(host, std::io::stdin().lock(), &metrics, &config)
+}
+
+/// Read lines from `input` until it ends or the user quits, evaluating each one.
+fn repl<H: Host>(host: &mut H, mut input: impl BufRead, metrics: &[Metric], config: &Config) -> Result<()> {
+    let mut line = String::new();
+
+    loop {
+        let _ = write!(host.output(), "aprz> ");
+        let _ = host.output().flush();
+
+        line.clear();
+        if input.read_line(&mut line).into_app_err("reading from the console")? == 0 {
+            let _ = writeln!(host.output());
+            return Ok(());
+        }
+
+        let line = line.trim();
+        match line.split_once(' ').unwrap_or((line, "")) {
+            ("", _) => {}
+            (":quit" | ":q", _) => return Ok(()),
+            (":help" | ":h", _) => {
+                let _ = writeln!(host.output(), "{HELP}");
+            }
+            (":metrics", prefix) => {
+                for metric in metrics.iter().filter(|metric| metric.name().starts_with(prefix.trim())) {
+                    let value = metric.value.as_ref().map_or_else(|| "(missing)".to_string(), format_metric_value);
+                    let _ = writeln!(host.output(), "{} = {value}", metric.name());
+                }
+            }
+            (":policy", _) => write_policy(host, metrics, config),
+            (command, _) if command.starts_with(':') => {
+                let _ = writeln!(host.error(), "Unknown command '{command}', enter :help for help");
+            }
+            _ => match evaluate_to_string(line, metrics, Local::now()) {
+                Ok(value) => {
+                    let _ = writeln!(host.output(), "{value}");
+                }
+                Err(e) => {
+                    let _ = writeln!(host.error(), "error: {e}");
+                }
+            },
+        }
+    }
+}
+
+/// Appraise the crate with the configured expressions and show the outcome of each.
+fn write_policy<H: Host>(host: &mut H, metrics: &[Metric], config: &Config) {
+    let mut appraisal = evaluate(
+        &config.high_risk,
+        &config.eval,
+        metrics,
+        Local::now(),
+        config.medium_risk_threshold,
+        config.low_risk_threshold,
+    );
+    config.aggregation.apply(
+        &mut appraisal,
+        &config.eval,
+        &config.category_weights,
+        config.medium_risk_threshold,
+        config.low_risk_threshold,
+    );
+
+    for outcome in &appraisal.expression_outcomes {
+        let disposition = match &outcome.disposition {
+            ExpressionDisposition::True => "true".to_string(),
+            ExpressionDisposition::False => "false".to_string(),
+            ExpressionDisposition::Failed(msg) => format!("failed: {msg}"),
+        };
+        let _ = writeln!(host.output(), "  {}: {disposition}", outcome.name);
+    }
+
+    let _ = writeln!(host.output(), "{}, score {:.0}", appraisal.risk, appraisal.score);
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use crate::commands::host::TestHost;
+    use crate::metrics::{MetricCategory, MetricDef, MetricValue};
+
+    static STARS_DEF: MetricDef = MetricDef {
+        name: "community.repo_stars",
+        description: "Stars",
+        category: MetricCategory::Community,
+        extractor: |_| None,
+        default_value: || None,
+    };
+
+    static ISSUES_DEF: MetricDef = MetricDef {
+        name: "activity.open_issues",
+        description: "Open issues",
+        category: MetricCategory::Activity,
+        extractor: |_| None,
+        default_value: || None,
+    };
+
+    fn run(input: &str, config: &Config) -> (String, String) {
+        let metrics = [Metric::with_value(&STARS_DEF, MetricValue::UInt(150)), Metric::new(&ISSUES_DEF)];
+        let mut host = TestHost::new();
+        repl(&mut host, input.as_bytes(), &metrics, config).unwrap();
+        (String::from_utf8(host.output_buf).unwrap(), String::from_utf8(host.error_buf).unwrap())
+    }
+
+    #[test]
+    fn test_repl_evaluates_expressions() {
+        let (output, error) = run("community.repo_stars > 100\n\ncommunity.repo_stars +\n:bogus\n:quit\nignored\n", &Config::default());
+        assert!(output.contains("aprz> true\n"), "{output}");
+        assert!(!output.contains("ignored"), "{output}");
+        assert!(error.contains("error: could not parse expression"), "{error}");
+        assert!(error.contains("Unknown command ':bogus'"), "{error}");
+    }
+
+    #[test]
+    fn test_repl_metrics_command() {
+        let (output, _) = run(":metrics activity.\n", &Config::default());
+        assert!(output.contains("activity.open_issues = (missing)"), "{output}");
+        assert!(!output.contains("community.repo_stars"), "{output}");
+    }
+
+    #[test]
+    fn test_repl_policy_command() {
+        let config: Config = toml::from_str(
+            r#"
+[[eval]]
+name = "Starred"
+expression = "community.repo_stars > 100"
+"#,
+        )
+        .unwrap();
+
+        let (output, _) = run(":policy\n", &config);
+        assert!(output.contains("  Starred: true"), "{output}");
+        assert!(output.contains("LOW RISK, score 100"), "{output}");
+    }
+}
@@ -1,6 +1,12 @@
 //! Command dispatch logic for cargo-aprz
 
-use super::{CratesArgs, DepsArgs, InitArgs, ValidateArgs, init_config, process_crates, process_dependencies, validate_config};
+use super::{
+    BatchArgs, CacheArgs, CalibrateArgs, CompareArgs, CratesArgs, DaemonArgs, DepsArgs, InitArgs, InventoryArgs, ReleasesArgs, ReplArgs,
+    ValidateArgs, appraise_inventory, calibrate_crates, compare_crates, init_config, manage_cache, process_batch, process_crates,
+    process_dependencies, run_daemon, run_repl, score_releases, validate_config,
+};
+#[cfg(feature = "server")]
+use super::{ServeArgs, serve};
 use crate::Host;
 use clap::builder::Styles;
 use std::io::Write;
@@ -40,10 +46,29 @@ enum AprzSubcommand {
     Crates(Box<CratesArgs>),
     /// Analyze workspace dependencies and generate quality reports
     Deps(Box<DepsArgs>),
+    /// Compare alternative crates side by side
+    Compare(Box<CompareArgs>),
+    /// Show how the score of a crate evolved across its releases
+    Releases(Box<ReleasesArgs>),
+    /// Analyze every crate owned by crates.io users or teams and rank them
+    Inventory(Box<InventoryArgs>),
+    /// Suggest expression points from crates labeled as good or bad
+    Calibrate(Box<CalibrateArgs>),
+    /// Interactively evaluate expressions against the metrics of a crate
+    Repl(Box<ReplArgs>),
+    /// Export or import the cache as a bundle file, for use on machines without network access
+    Cache(CacheArgs),
+    /// Analyze the dependencies of many repositories and rank them by dependency health
+    Batch(Box<BatchArgs>),
+    /// Periodically analyze the dependencies of a set of workspaces, keeping their latest reports up to date
+    Daemon(Box<DaemonArgs>),
     /// Generate a default configuration file
     Init(InitArgs),
     /// Validate a configuration file
     Validate(ValidateArgs),
+    /// Collect results submitted with `--report-to` and serve a dashboard of them
+    #[cfg(feature = "server")]
+    Serve(ServeArgs),
 }
 
 /// Dispatch command-line arguments to the appropriate handler
@@ -69,8 +94,18 @@ where
     let result = match &args.command {
         AprzSubcommand::Crates(crates_args) => process_crates(host, crates_args).await,
         AprzSubcommand::Deps(deps_args) => process_dependencies(host, deps_args).await,
+        AprzSubcommand::Compare(compare_args) => compare_crates(host, compare_args).await,
+        AprzSubcommand::Releases(releases_args) => score_releases(host, releases_args).await,
+        AprzSubcommand::Inventory(inventory_args) => appraise_inventory(host, inventory_args).await,
+        AprzSubcommand::Calibrate(calibrate_args) => calibrate_crates(host, calibrate_args).await,
+        AprzSubcommand::Repl(repl_args) => run_repl(host, repl_args).await,
+        AprzSubcommand::Cache(cache_args) => manage_cache(host, cache_args).await,
+        AprzSubcommand::Batch(batch_args) => process_batch(host, batch_args).await,
+        AprzSubcommand::Daemon(daemon_args) => run_daemon(host, daemon_args).await,
         AprzSubcommand::Init(init_args) => init_config(host, init_args),
-        AprzSubcommand::Validate(validate_args) => validate_config(host, validate_args),
+        AprzSubcommand::Validate(validate_args) => validate_config(host, validate_args).await,
+        #[cfg(feature = "server")]
+        AprzSubcommand::Serve(serve_args) => serve(host, serve_args).await,
     };
 
     if let Err(e) = result {
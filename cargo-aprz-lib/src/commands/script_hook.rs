@@ -0,0 +1,167 @@
+//! Post-processing of the appraisals by a user-provided rhai script.
+//!
+//! The script runs once all crates are appraised, with a `crates` array in scope holding a map for each
+//! crate. Each map has the crate's `name`, `version`, `score`, `risk`, and `metrics`, along with `veto`
+//! and `notes` entries the script can change. Changes made to `score`, `veto`, and `notes` are applied to
+//! the appraisals, while changes to other entries are ignored.
+
+use crate::Result;
+use crate::expr::Risk;
+use crate::reports::ReportableCrate;
+use camino::Utf8Path;
+use ohno::{app_err, bail};
+use serde::Deserialize;
+use serde_json::json;
+
+/// The entries of a crate's map which the script may change
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ScriptOutcome {
+    score: Option<f64>,
+    veto: bool,
+    notes: Vec<String>,
+}
+
+/// Run the script at `path` over the appraised crates, applying the changes it makes.
+///
+/// A changed score is clamped to 0..100 and determines the crate's risk, unless the crate is vetoed, in
+/// which case it's high risk regardless of its score. Scores and vetoes of crates that weren't appraised
+/// are ignored.
+pub fn run_script_hook(
+    path: &Utf8Path,
+    crates: &mut [ReportableCrate],
+    medium_risk_threshold: f64,
+    low_risk_threshold: f64,
+) -> Result<()> {
+    let input: Vec<serde_json::Value> = crates.iter().map(crate_to_json).collect();
+
+    let engine = rhai::Engine::new();
+    let mut scope = rhai::Scope::new();
+    let _ = scope.push_dynamic(
+        "crates",
+        rhai::serde::to_dynamic(&input).map_err(|e| app_err!("preparing the crates for script hook '{path}': {e}"))?,
+    );
+
+    engine
+        .run_file_with_scope(&mut scope, path.as_std_path().to_path_buf())
+        .map_err(|e| app_err!("running script hook '{path}': {e}"))?;
+
+    let output: rhai::Dynamic = scope
+        .get_value("crates")
+        .ok_or_else(|| app_err!("script hook '{path}' removed the 'crates' variable"))?;
+    let outcomes: Vec<ScriptOutcome> =
+        rhai::serde::from_dynamic(&output).map_err(|e| app_err!("reading the crates modified by script hook '{path}': {e}"))?;
+
+    if outcomes.len() != crates.len() {
+        bail!("script hook '{path}' must not add or remove crates");
+    }
+
+    for (crate_info, outcome) in crates.iter_mut().zip(outcomes) {
+        apply_outcome(crate_info, outcome, medium_risk_threshold, low_risk_threshold);
+    }
+
+    Ok(())
+}
+
+fn crate_to_json(crate_info: &ReportableCrate) -> serde_json::Value {
+    let metrics: serde_json::Map<String, serde_json::Value> = crate_info
+        .metrics
+        .iter()
+        .filter_map(|metric| Some((metric.name().to_string(), metric.value.as_ref()?.to_json())))
+        .collect();
+
+    json!({
+        "name": crate_info.name,
+        "version": crate_info.version.to_string(),
+        "score": crate_info.appraisal.as_ref().map(|a| a.score),
+        "risk": crate_info.appraisal.as_ref().map(|a| a.risk),
+        "metrics": metrics,
+        "veto": false,
+        "notes": crate_info.notes,
+    })
+}
+
+fn apply_outcome(crate_info: &mut ReportableCrate, outcome: ScriptOutcome, medium_risk_threshold: f64, low_risk_threshold: f64) {
+    if let Some(appraisal) = &mut crate_info.appraisal {
+        if let Some(score) = outcome.score
+            && (score - appraisal.score).abs() > f64::EPSILON
+        {
+            appraisal.score = score.clamp(0.0, 100.0);
+            appraisal.risk = Risk::from_score(appraisal.score, medium_risk_threshold, low_risk_threshold);
+        }
+
+        if outcome.veto {
+            appraisal.risk = Risk::High;
+        }
+    }
+
+    crate_info.notes = outcome.notes;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::Appraisal;
+    use std::sync::Arc;
+
+    fn make_crate(name: &str, score: f64) -> ReportableCrate {
+        let risk = Risk::from_score(score, 30.0, 70.0);
+        ReportableCrate::new(name.into(), Arc::new("1.0.0".parse().unwrap()), vec![], Some(Appraisal::new(risk, vec![], 10, 5, score)))
+    }
+
+    fn write_script(script: &str) -> (tempfile::TempDir, camino::Utf8PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = camino::Utf8PathBuf::try_from(dir.path().join("hook.rhai")).unwrap();
+        std::fs::write(&path, script).unwrap();
+        (dir, path)
+    }
+
+    fn run_script(script: &str, crates: &mut [ReportableCrate]) -> Result<()> {
+        let (_dir, path) = write_script(script);
+        run_script_hook(&path, crates, 30.0, 70.0)
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_script_adjusts_scores_and_adds_notes() {
+        let mut crates = [make_crate("internal-utils", 50.0), make_crate("leftpad", 80.0)];
+        run_script(
+            r#"
+for i in 0..crates.len() {
+    if crates[i].name.starts_with("internal-") {
+        crates[i].score = 90.0;
+        crates[i].notes.push("Maintained in-house");
+    }
+}
+"#,
+            &mut crates,
+        )
+        .unwrap();
+
+        let appraisal = crates[0].appraisal.as_ref().unwrap();
+        assert!((appraisal.score - 90.0).abs() < f64::EPSILON);
+        assert_eq!(appraisal.risk, Risk::Low);
+        assert_eq!(crates[0].notes, ["Maintained in-house"]);
+        assert_eq!(crates[1].appraisal.as_ref().unwrap().risk, Risk::Low);
+        assert!(crates[1].notes.is_empty());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_script_vetoes_crate() {
+        let mut crates = [make_crate("leftpad", 95.0)];
+        run_script("crates[0].veto = true;", &mut crates).unwrap();
+
+        let appraisal = crates[0].appraisal.as_ref().unwrap();
+        assert_eq!(appraisal.risk, Risk::High);
+        assert!((appraisal.score - 95.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_script_must_keep_crates() {
+        let mut crates = [make_crate("leftpad", 95.0)];
+        assert!(run_script("crates.clear();", &mut crates).is_err());
+        assert!(run_script("this is not rhai", &mut crates).is_err());
+    }
+}
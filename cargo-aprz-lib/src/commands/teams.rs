@@ -0,0 +1,240 @@
+//! Assignment of workspace packages to owning teams for per-team reports.
+//!
+//! Teams are taken from the `teams` table of the configuration file when present, and
+//! otherwise from a GitHub-style `CODEOWNERS` file matched against each package's manifest.
+
+use crate::Result;
+use camino::{Utf8Path, Utf8PathBuf};
+use cargo_metadata::Package;
+use ohno::{IntoAppError, bail};
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::fs;
+
+/// Locations searched for a `CODEOWNERS` file, relative to the workspace root, in the order GitHub uses.
+const CODEOWNERS_LOCATIONS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// A single `CODEOWNERS` rule.
+#[derive(Debug)]
+struct Rule {
+    pattern: Regex,
+    owners: Vec<String>,
+}
+
+/// A parsed `CODEOWNERS` file.
+#[derive(Debug, Default)]
+pub struct CodeOwners {
+    rules: Vec<Rule>,
+}
+
+impl CodeOwners {
+    /// Parse the contents of a `CODEOWNERS` file.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut rules = Vec::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split_once('#').map_or(line, |(before, _)| before).trim();
+            let mut parts = line.split_whitespace();
+            let Some(pattern) = parts.next() else {
+                continue;
+            };
+
+            let regex =
+                pattern_to_regex(pattern).into_app_err_with(|| format!("invalid CODEOWNERS pattern '{pattern}' on line {}", index + 1))?;
+            rules.push(Rule {
+                pattern: regex,
+                owners: parts.map(str::to_string).collect(),
+            });
+        }
+
+        Ok(Self { rules })
+    }
+
+    /// Returns the owners of a path relative to the repository root.
+    ///
+    /// As with GitHub, the last matching rule takes precedence, and a matching rule without
+    /// owners leaves the path unowned.
+    pub fn owners(&self, path: &str) -> &[String] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.pattern.is_match(path))
+            .map_or(&[], |rule| rule.owners.as_slice())
+    }
+}
+
+/// Translate a gitignore-style `CODEOWNERS` pattern into an anchored regular expression.
+pub fn pattern_to_regex(pattern: &str) -> core::result::Result<Regex, regex::Error> {
+    let dir_only = pattern.ends_with('/');
+    let trimmed = pattern.trim_end_matches('/');
+
+    // A pattern containing a slash anywhere but at the end is relative to the repository root
+    let anchored = trimmed.contains('/');
+    let trimmed = trimmed.trim_start_matches('/');
+
+    let mut re = String::from(if anchored { "^" } else { "^(?:.*/)?" });
+    let mut rest = trimmed;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("**/") {
+            re.push_str("(?:.*/)?");
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("**") {
+            re.push_str(".*");
+            rest = after;
+        } else {
+            let mut chars = rest.chars();
+            match chars.next() {
+                Some('*') => re.push_str("[^/]*"),
+                Some('?') => re.push_str("[^/]"),
+                Some(c) => re.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+                None => {}
+            }
+            rest = chars.as_str();
+        }
+    }
+
+    // Patterns match the path itself or anything beneath it, directory patterns only the latter
+    re.push_str(if dir_only { "/.*$" } else { "(?:/.*)?$" });
+    Regex::new(&re)
+}
+
+/// Maps workspace packages to the teams that own them.
+#[derive(Debug)]
+pub struct TeamResolver<'a> {
+    config_teams: &'a BTreeMap<String, Vec<String>>,
+    codeowners: CodeOwners,
+    workspace_root: &'a Utf8Path,
+}
+
+impl<'a> TeamResolver<'a> {
+    /// Create a resolver from the configured `teams` table, falling back to a `CODEOWNERS` file.
+    ///
+    /// When `codeowners_path` is `None`, the standard GitHub locations under the workspace root are searched.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no team information is available or the `CODEOWNERS` file can't be parsed.
+    pub fn new(
+        config_teams: &'a BTreeMap<String, Vec<String>>,
+        codeowners_path: Option<&Utf8Path>,
+        workspace_root: &'a Utf8Path,
+    ) -> Result<Self> {
+        let codeowners_path: Option<Utf8PathBuf> = codeowners_path.map(Utf8Path::to_path_buf).or_else(|| {
+            CODEOWNERS_LOCATIONS
+                .iter()
+                .map(|location| workspace_root.join(location))
+                .find(|path| path.is_file())
+        });
+
+        let codeowners = match &codeowners_path {
+            Some(path) => {
+                let text = fs::read_to_string(path).into_app_err_with(|| format!("reading CODEOWNERS file '{path}'"))?;
+                CodeOwners::parse(&text)?
+            }
+            None if config_teams.is_empty() => {
+                bail!("per-team reports require a `teams` table in the configuration file or a CODEOWNERS file");
+            }
+            None => CodeOwners::default(),
+        };
+
+        Ok(Self {
+            config_teams,
+            codeowners,
+            workspace_root,
+        })
+    }
+
+    /// Returns the teams owning a workspace package.
+    pub fn teams_for(&self, package: &Package) -> Vec<String> {
+        let configured: Vec<_> = self
+            .config_teams
+            .iter()
+            .filter(|(_, packages)| packages.iter().any(|p| *p == package.name.as_str()))
+            .map(|(team, _)| team.clone())
+            .collect();
+        if !configured.is_empty() {
+            return configured;
+        }
+
+        let manifest = package
+            .manifest_path
+            .strip_prefix(self.workspace_root)
+            .unwrap_or(package.manifest_path.as_path());
+        self.codeowners.owners(manifest.as_str().replace('\\', "/").as_str()).to_vec()
+    }
+}
+
+/// Derive the per-team variant of a report file name, e.g. `report.html` becomes `report.org-web.html` for `@org/web`.
+pub fn team_report_path(path: &Utf8Path, team: &str) -> Utf8PathBuf {
+    let slug: String = team
+        .trim_start_matches('@')
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .collect();
+
+    let file_name = match (path.file_stem(), path.extension()) {
+        (Some(stem), Some(ext)) => format!("{stem}.{slug}.{ext}"),
+        (Some(stem), None) => format!("{stem}.{slug}"),
+        _ => slug,
+    };
+    path.with_file_name(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODEOWNERS: &str = "
+# Default owners
+*                   @org/platform
+
+/crates/web/        @org/web
+crates/**/db        @org/data @alice
+*.md                @org/docs
+/crates/web/vendor/
+";
+
+    #[test]
+    fn test_owners_last_match_wins() {
+        let owners = CodeOwners::parse(CODEOWNERS).unwrap();
+        assert_eq!(owners.owners("Cargo.toml"), ["@org/platform"]);
+        assert_eq!(owners.owners("crates/web/Cargo.toml"), ["@org/web"]);
+        assert_eq!(owners.owners("crates/storage/db/Cargo.toml"), ["@org/data", "@alice"]);
+        assert_eq!(owners.owners("crates/web/README.md"), ["@org/docs"]);
+        assert!(owners.owners("crates/web/vendor/Cargo.toml").is_empty());
+    }
+
+    #[test]
+    fn test_directory_pattern_does_not_match_file() {
+        let owners = CodeOwners::parse("/docs/ @org/docs\n").unwrap();
+        assert!(owners.owners("docs").is_empty());
+        assert_eq!(owners.owners("docs/guide.md"), ["@org/docs"]);
+    }
+
+    #[test]
+    fn test_unanchored_pattern_matches_at_any_depth() {
+        let owners = CodeOwners::parse("build.rs @org/build\n").unwrap();
+        assert_eq!(owners.owners("build.rs"), ["@org/build"]);
+        assert_eq!(owners.owners("crates/foo/build.rs"), ["@org/build"]);
+        assert!(owners.owners("crates/foo/build.rs.bak").is_empty());
+    }
+
+    #[test]
+    fn test_team_report_path() {
+        assert_eq!(team_report_path(Utf8Path::new("out/report.html"), "@org/web"), "out/report.org-web.html");
+        assert_eq!(team_report_path(Utf8Path::new("report"), "data"), "report.data");
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_resolver_requires_team_information() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = Utf8PathBuf::try_from(tmp.path().to_path_buf()).unwrap();
+        let teams = BTreeMap::new();
+        let _ = TeamResolver::new(&teams, None, &root).unwrap_err();
+
+        fs::create_dir(root.join(".github")).unwrap();
+        fs::write(root.join(".github/CODEOWNERS"), "* @org/platform\n").unwrap();
+        let _ = TeamResolver::new(&teams, None, &root).unwrap();
+    }
+}
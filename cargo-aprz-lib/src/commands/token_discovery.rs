@@ -0,0 +1,108 @@
+//! Discovery of hosting tokens the user already stored elsewhere.
+//!
+//! With `--discover-tokens`, hosts without a token on the command line or in the environment get one from
+//! the GitHub CLI (`gh auth token`), then from the OS keyring when built with the `keyring` feature, and
+//! else from git's credential helpers. The keyring is the macOS Keychain, the Windows Credential Manager,
+//! or the Secret Service on Linux, where tokens are stored for the `cargo-aprz` service under the host's
+//! domain as the user name. Lookups never prompt, and hosts without a stored token stay unauthenticated.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const LOG_TARGET: &str = "    tokens";
+
+/// The service the tokens of `cargo-aprz` are stored under in the OS keyring
+#[cfg(feature = "keyring")]
+const KEYRING_SERVICE: &str = "cargo-aprz";
+
+/// Look up a stored token for the given host domain, such as `github.com`.
+pub fn discover_token(host: &str) -> Option<String> {
+    if host == "github.com"
+        && let Some(token) = gh_auth_token(host)
+    {
+        log::info!(target: LOG_TARGET, "Using the {host} token of the GitHub CLI");
+        return Some(token);
+    }
+
+    #[cfg(feature = "keyring")]
+    if let Some(token) = keyring_token(host) {
+        log::info!(target: LOG_TARGET, "Using the {host} token of the OS keyring");
+        return Some(token);
+    }
+
+    let token = git_credential(host)?;
+    log::info!(target: LOG_TARGET, "Using the {host} token of git's credential helpers");
+    Some(token)
+}
+
+fn gh_auth_token(host: &str) -> Option<String> {
+    let output = Command::new("gh")
+        .args(["auth", "token", "--hostname", host])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let token = String::from_utf8(output.stdout).ok()?;
+    let token = token.trim();
+    (!token.is_empty()).then(|| token.to_string())
+}
+
+#[cfg(feature = "keyring")]
+fn keyring_token(host: &str) -> Option<String> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, host).ok()?;
+    match entry.get_password() {
+        Ok(token) => Some(token.trim().to_string()).filter(|token| !token.is_empty()),
+        Err(keyring::Error::NoEntry) => None,
+        Err(e) => {
+            log::debug!(target: LOG_TARGET, "Could not read the {host} token from the OS keyring: {e}");
+            None
+        }
+    }
+}
+
+fn git_credential(host: &str) -> Option<String> {
+    let mut child = Command::new("git")
+        .args(["credential", "fill"])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GCM_INTERACTIVE", "never")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    // The request is terminated by a blank line, and closing stdin lets git answer
+    child.stdin.take()?.write_all(format!("protocol=https\nhost={host}\n\n").as_bytes()).ok()?;
+
+    let output = child.wait_with_output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    parse_credential(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Extract the password from the answer of `git credential fill`.
+fn parse_credential(text: &str) -> Option<String> {
+    text.lines()
+        .find_map(|line| line.strip_prefix("password="))
+        .filter(|password| !password.is_empty())
+        .map(ToString::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_credential() {
+        let answer = "protocol=https\nhost=codeberg.org\nusername=someone\npassword=abc123\n";
+        assert_eq!(parse_credential(answer).as_deref(), Some("abc123"));
+        assert_eq!(parse_credential("protocol=https\nhost=codeberg.org\npassword=\n"), None);
+        assert_eq!(parse_credential(""), None);
+    }
+}
@@ -0,0 +1,182 @@
+//! Uploading generated reports to object storage.
+//!
+//! Destinations are URLs such as `s3://bucket/reports/{date}`, `gs://bucket/reports`, or
+//! `az://container/reports`. Credentials are obtained from each provider's standard
+//! environment-based configuration (e.g. `AWS_ACCESS_KEY_ID`, `GOOGLE_SERVICE_ACCOUNT`,
+//! `AZURE_STORAGE_ACCOUNT_NAME`).
+
+use crate::Result;
+use camino::Utf8Path;
+use chrono::{DateTime, Local};
+use object_store::ObjectStore;
+use object_store::aws::AmazonS3Builder;
+use object_store::azure::MicrosoftAzureBuilder;
+use object_store::gcp::GoogleCloudStorageBuilder;
+use object_store::path::Path as ObjectPath;
+use ohno::{IntoAppError, bail};
+use std::fs;
+use url::Url;
+
+const LOG_TARGET: &str = "    upload";
+
+/// The object storage services reports can be uploaded to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StorageService {
+    S3,
+    Gcs,
+    Azure,
+}
+
+/// A parsed upload destination.
+#[derive(Debug, PartialEq, Eq)]
+struct Destination {
+    service: StorageService,
+    bucket: String,
+    prefix: String,
+}
+
+/// Expand the `{date}` and `{timestamp}` placeholders in a destination template.
+fn expand_template(template: &str, now: DateTime<Local>) -> String {
+    template
+        .replace("{date}", &now.format("%Y-%m-%d").to_string())
+        .replace("{timestamp}", &now.format("%Y%m%dT%H%M%S").to_string())
+}
+
+fn parse_destination(destination: &str) -> Result<Destination> {
+    let url = Url::parse(destination).into_app_err_with(|| format!("parsing upload destination '{destination}'"))?;
+
+    let service = match url.scheme() {
+        "s3" => StorageService::S3,
+        "gs" => StorageService::Gcs,
+        "az" => StorageService::Azure,
+        other => bail!("unsupported upload destination scheme '{other}', expected one of s3, gs, or az"),
+    };
+
+    let Some(bucket) = url.host_str().filter(|h| !h.is_empty()) else {
+        bail!("upload destination '{destination}' does not specify a bucket or container");
+    };
+
+    Ok(Destination {
+        service,
+        bucket: bucket.to_string(),
+        prefix: url.path().trim_matches('/').to_string(),
+    })
+}
+
+fn build_store(destination: &Destination) -> Result<Box<dyn ObjectStore>> {
+    let store: Box<dyn ObjectStore> = match destination.service {
+        StorageService::S3 => Box::new(
+            AmazonS3Builder::from_env()
+                .with_bucket_name(&destination.bucket)
+                .build()
+                .into_app_err("configuring S3 client")?,
+        ),
+        StorageService::Gcs => Box::new(
+            GoogleCloudStorageBuilder::from_env()
+                .with_bucket_name(&destination.bucket)
+                .build()
+                .into_app_err("configuring Google Cloud Storage client")?,
+        ),
+        StorageService::Azure => Box::new(
+            MicrosoftAzureBuilder::from_env()
+                .with_container_name(&destination.bucket)
+                .build()
+                .into_app_err("configuring Azure Blob Storage client")?,
+        ),
+    };
+
+    Ok(store)
+}
+
+async fn upload_files(store: &dyn ObjectStore, prefix: &str, files: &[&Utf8Path]) -> Result<()> {
+    let prefix = ObjectPath::from(prefix);
+    for file in files {
+        let Some(file_name) = file.file_name() else {
+            continue;
+        };
+
+        let data = fs::read(file).into_app_err_with(|| format!("reading report '{file}' for upload"))?;
+        let location = prefix.child(file_name);
+
+        log::info!(target: LOG_TARGET, "Uploading '{file}' to '{location}'");
+        let _ = store
+            .put(&location, data.into())
+            .await
+            .into_app_err_with(|| format!("uploading report '{file}' to '{location}'"))?;
+    }
+
+    Ok(())
+}
+
+/// Upload the given report files to the object storage location described by `destination_template`.
+pub async fn upload_reports(destination_template: &str, files: &[&Utf8Path], now: DateTime<Local>) -> Result<()> {
+    if files.is_empty() {
+        return Ok(());
+    }
+
+    let destination = parse_destination(&expand_template(destination_template, now))?;
+    let store = build_store(&destination)?;
+    upload_files(store.as_ref(), &destination.prefix, files).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use camino::Utf8PathBuf;
+    use chrono::TimeZone;
+    use object_store::memory::InMemory;
+
+    fn test_time() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 3, 7, 14, 5, 9).unwrap()
+    }
+
+    #[test]
+    fn test_expand_template() {
+        assert_eq!(expand_template("s3://b/reports/{date}", test_time()), "s3://b/reports/2026-03-07");
+        assert_eq!(expand_template("s3://b/{timestamp}", test_time()), "s3://b/20260307T140509");
+        assert_eq!(expand_template("s3://b/static", test_time()), "s3://b/static");
+    }
+
+    #[test]
+    fn test_parse_destination_services() {
+        let dest = parse_destination("s3://my-bucket/reports/2026-03-07/").unwrap();
+        assert_eq!(
+            dest,
+            Destination {
+                service: StorageService::S3,
+                bucket: "my-bucket".to_string(),
+                prefix: "reports/2026-03-07".to_string(),
+            }
+        );
+
+        assert_eq!(parse_destination("gs://bucket").unwrap().service, StorageService::Gcs);
+        assert_eq!(parse_destination("az://container/x").unwrap().service, StorageService::Azure);
+        assert_eq!(parse_destination("gs://bucket").unwrap().prefix, "");
+    }
+
+    #[test]
+    fn test_parse_destination_rejects_unknown_scheme() {
+        let _ = parse_destination("ftp://bucket/reports").unwrap_err();
+        let _ = parse_destination("not a url").unwrap_err();
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    async fn test_upload_files_to_memory_store() {
+        let tmp = tempfile::tempdir().unwrap();
+        let report = Utf8PathBuf::try_from(tmp.path().join("report.json")).unwrap();
+        fs::write(&report, "{}").unwrap();
+
+        let store = InMemory::new();
+        upload_files(&store, "reports/2026-03-07", &[report.as_path()]).await.unwrap();
+
+        let data = store
+            .get(&ObjectPath::from("reports/2026-03-07/report.json"))
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap();
+        assert_eq!(data.as_ref(), b"{}");
+    }
+}
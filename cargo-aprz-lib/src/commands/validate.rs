@@ -1,14 +1,22 @@
 use super::Host;
-use super::config::Config;
+use super::config::{Config, ExpressionTest};
+use super::policy_pack::apply_policy_packs;
 use crate::Result;
-use crate::expr::{ExpressionDisposition, evaluate};
-use crate::metrics::default_metrics;
+use crate::expr::{Appraisal, ExpressionDisposition, Risk, evaluate, metric_references};
+use crate::metrics::{Metric, MetricCategory, MetricValue, default_metrics, metric_category, metric_names};
+use crate::reports::{ReportedCrate, read_json_crates};
 use camino::{Utf8Path, Utf8PathBuf};
 use cargo_metadata::MetadataCommand;
-use chrono::Local;
+use chrono::{DateTime, Local};
 use clap::Parser;
-use ohno::{IntoAppError, app_err};
+use ohno::{EnrichableExt, IntoAppError, app_err, bail};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
 use std::io::Write;
+use strsim::normalized_damerau_levenshtein;
+
+/// Minimum similarity for a metric name to be suggested in place of an unknown one
+const MIN_SUGGESTION_SCORE: f64 = 0.7;
 
 #[derive(Parser, Debug)]
 pub struct ValidateArgs {
@@ -19,9 +27,14 @@ pub struct ValidateArgs {
     /// Path to Cargo.toml file
     #[arg(long, default_value = "Cargo.toml", value_name = "PATH")]
     pub manifest_path: Utf8PathBuf,
+
+    /// Re-score the crates of a JSON report from a previous run under the configuration, listing the crates
+    /// whose risk band would change
+    #[arg(long, value_name = "PATH")]
+    pub against: Option<Utf8PathBuf>,
 }
 
-pub fn validate_config<H: Host>(host: &mut H, args: &ValidateArgs) -> Result<()> {
+pub async fn validate_config<H: Host>(host: &mut H, args: &ValidateArgs) -> Result<()> {
     let config_path = if let Some(path) = &args.config {
         path.clone()
     } else {
@@ -35,19 +48,103 @@ pub fn validate_config<H: Host>(host: &mut H, args: &ValidateArgs) -> Result<()>
         return Err(app_err!("could not find configuration file '{config_path}'"));
     }
 
-    validate_config_inner(&config_path)?;
+    let summary = validate_config_inner(&config_path, args.against.as_deref()).await?;
 
     let _ = writeln!(host.output(), "Configuration file at '{config_path}' is valid");
+    if summary.tests_run > 0 {
+        let _ = writeln!(host.output(), "All {} expression test(s) passed", summary.tests_run);
+    }
+
+    if !summary.unreferenced_metrics.is_empty() {
+        let _ = writeln!(
+            host.output(),
+            "{} of {} metrics are not referenced by any expression:",
+            summary.unreferenced_metrics.len(),
+            metric_names().count()
+        );
+
+        let mut by_category: BTreeMap<MetricCategory, Vec<&str>> = BTreeMap::new();
+        for name in &summary.unreferenced_metrics {
+            if let Some(category) = metric_category(name) {
+                by_category.entry(category).or_default().push(name);
+            }
+        }
+
+        for (category, names) in by_category {
+            let _ = writeln!(host.output(), "  {category}: {}", names.join(", "));
+        }
+    }
+
+    if let (Some(report_path), Some(impact)) = (&args.against, &summary.impact) {
+        let _ = writeln!(
+            host.output(),
+            "Re-scored {} crate(s) from '{report_path}', {} of which change risk band",
+            impact.rescored,
+            impact.changes.len()
+        );
+        for change in &impact.changes {
+            let previous = change.previous_score.map_or_else(String::new, |score| format!(" (score {score:.0})"));
+            let _ = writeln!(
+                host.output(),
+                "  {} v{}: {}{previous} -> {} (score {:.0})",
+                change.name,
+                change.version,
+                change.previous_risk,
+                change.risk,
+                change.score
+            );
+        }
+    }
+
     Ok(())
 }
 
-/// Validates a configuration file by loading it and checking expression evaluation
+/// What validating a configuration file found, beyond the file being valid
+#[derive(Debug)]
+struct ValidationSummary {
+    /// Number of expression tests run, all of which passed
+    tests_run: usize,
+
+    /// Metrics that no expression refers to, empty when there are no expressions
+    unreferenced_metrics: Vec<&'static str>,
+
+    /// How the configuration changes the appraisals of a previous run, when given a report of one
+    impact: Option<Impact>,
+}
+
+/// How a configuration changes the appraisals recorded in a previous report
+#[derive(Debug)]
+struct Impact {
+    /// Number of appraised crates in the report
+    rescored: usize,
+
+    /// The crates whose risk band changes, those becoming riskier first
+    changes: Vec<BandChange>,
+}
+
+/// A crate whose risk band changes under a configuration
+#[derive(Debug)]
+struct BandChange {
+    name: String,
+    version: String,
+    previous_risk: Risk,
+    previous_score: Option<f64>,
+    risk: Risk,
+    score: f64,
+}
+
+/// Validates a configuration file by loading it, checking the metrics the expressions refer to and that the
+/// expressions evaluate, and running the expression tests. Given the path of a JSON report, the crates in it are
+/// then re-scored under the configuration.
 ///
 /// # Errors
 ///
-/// Returns an error if the config file cannot be loaded, parsed, or if expressions fail to evaluate
-fn validate_config_inner(config_path: &Utf8Path) -> Result<()> {
-    let config = Config::load(config_path.parent().unwrap_or_else(|| Utf8Path::new(".")), Some(&config_path.to_path_buf()))?;
+/// Returns an error if the config file cannot be loaded, parsed, if expressions refer to unknown metrics or fail to
+/// evaluate, if an expression test fails, or if the report can't be read
+async fn validate_config_inner(config_path: &Utf8Path, against: Option<&Utf8Path>) -> Result<ValidationSummary> {
+    let mut config = Config::load(config_path.parent().unwrap_or_else(|| Utf8Path::new(".")), Some(&config_path.to_path_buf()))?;
+    apply_policy_packs(&mut config).await?;
+    let unreferenced_metrics = check_metric_references(&config)?;
 
     // Validate that all expressions can be evaluated against default metrics (only if any are defined)
     if !config.high_risk.is_empty() || !config.eval.is_empty() {
@@ -68,7 +165,197 @@ fn validate_config_inner(config_path: &Utf8Path) -> Result<()> {
         }
     }
 
-    Ok(())
+    let tests_run = run_expression_tests(&config)?;
+    let impact = if let Some(report_path) = against {
+        let text = fs::read_to_string(report_path).into_app_err_with(|| format!("reading report '{report_path}'"))?;
+        let crates = read_json_crates(&text).map_err(|e| e.enrich_with(|| format!("parsing report '{report_path}'")))?;
+        Some(rescore(&config, &crates, Local::now()))
+    } else {
+        None
+    };
+
+    Ok(ValidationSummary {
+        tests_run,
+        unreferenced_metrics,
+        impact,
+    })
+}
+
+/// Re-score the appraised crates of a previous report, collecting those whose risk band changes
+fn rescore(config: &Config, crates: &[ReportedCrate], now: DateTime<Local>) -> Impact {
+    let mut rescored = 0;
+    let mut changes = Vec::new();
+    for reported in crates {
+        let Some(previous_risk) = reported.risk else {
+            continue;
+        };
+
+        rescored += 1;
+        let appraisal = appraise(config, &reported.metrics, now);
+        if appraisal.risk != previous_risk {
+            changes.push(BandChange {
+                name: reported.name.clone(),
+                version: reported.version.clone(),
+                previous_risk,
+                previous_score: reported.score,
+                risk: appraisal.risk,
+                score: appraisal.score,
+            });
+        }
+    }
+
+    // Crates moving to a riskier band are what would break CI, so they come first
+    changes.sort_by(|a, b| (b.risk > b.previous_risk).cmp(&(a.risk > a.previous_risk)).then_with(|| a.name.cmp(&b.name)));
+
+    Impact { rescored, changes }
+}
+
+/// Appraise a crate with the given metrics under the configuration
+fn appraise(config: &Config, metrics: &[Metric], now: DateTime<Local>) -> Appraisal {
+    let mut appraisal = evaluate(
+        &config.high_risk,
+        &config.eval,
+        metrics,
+        now,
+        config.medium_risk_threshold,
+        config.low_risk_threshold,
+    );
+    config.aggregation.apply(
+        &mut appraisal,
+        &config.eval,
+        &config.category_weights,
+        config.medium_risk_threshold,
+        config.low_risk_threshold,
+    );
+    appraisal
+}
+
+/// Check that the expressions only refer to known metrics, returning the metrics that no expression refers to
+///
+/// # Errors
+///
+/// Returns an error listing every reference to an unknown metric, along with the closest known metric name
+fn check_metric_references(config: &Config) -> Result<Vec<&'static str>> {
+    let mut used_metrics = BTreeSet::new();
+    let mut problems = Vec::new();
+
+    for expr in config.high_risk.iter().chain(&config.eval) {
+        let references = metric_references(expr.expression());
+        used_metrics.extend(references.metrics);
+
+        for unknown in references.unknown {
+            let suggestion = metric_names()
+                .map(|name| (name, normalized_damerau_levenshtein(name, &unknown)))
+                .filter(|(_, score)| *score >= MIN_SUGGESTION_SCORE)
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map_or_else(String::new, |(name, _)| format!(", did you mean '{name}'?"));
+            problems.push(format!("  expression '{}' refers to unknown metric '{unknown}'{suggestion}", expr.name()));
+        }
+    }
+
+    if !problems.is_empty() {
+        bail!("{} reference(s) to unknown metrics:\n{}", problems.len(), problems.join("\n"));
+    }
+
+    if config.high_risk.is_empty() && config.eval.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    Ok(metric_names().filter(|name| !used_metrics.contains(name)).collect())
+}
+
+/// Appraise the synthetic crate of each expression test and check the outcomes, returning the number of tests run
+///
+/// # Errors
+///
+/// Returns an error describing every failed test, with a line for each of its mismatches
+fn run_expression_tests(config: &Config) -> Result<usize> {
+    let mut failed = 0;
+    let mut failures = Vec::new();
+
+    for test in &config.expression_tests {
+        if let Err(mismatches) = run_expression_test(config, test) {
+            failed += 1;
+            failures.extend(mismatches.iter().map(|mismatch| format!("  {}: {mismatch}", test.name)));
+        }
+    }
+
+    if !failures.is_empty() {
+        return Err(app_err!(
+            "{} of {} expression test(s) failed:\n{}",
+            failed,
+            config.expression_tests.len(),
+            failures.join("\n")
+        ));
+    }
+
+    Ok(config.expression_tests.len())
+}
+
+fn run_expression_test(config: &Config, test: &ExpressionTest) -> core::result::Result<(), Vec<String>> {
+    let mut metrics: Vec<Metric> = default_metrics().collect();
+    for metric in &mut metrics {
+        if let Some(value) = test.metrics.get(metric.name()) {
+            let converted =
+                metric_value(value, metric.value.as_ref()).ok_or_else(|| vec![format!("invalid value for metric '{}'", metric.name())])?;
+            metric.value = Some(converted);
+        }
+    }
+
+    let now = match &test.now {
+        Some(now) => DateTime::parse_from_rfc3339(&now.to_string())
+            .map_err(|e| vec![format!("invalid 'now' time '{now}': {e}")])?
+            .with_timezone(&Local),
+        None => Local::now(),
+    };
+
+    let appraisal = appraise(config, &metrics, now);
+
+    let mut mismatches = Vec::new();
+    for (name, expected) in &test.expect {
+        if !config.high_risk.iter().chain(&config.eval).any(|expr| expr.name() == name.as_str()) {
+            mismatches.push(format!("there is no expression named '{name}'"));
+            continue;
+        }
+
+        let outcome = appraisal.expression_outcomes.iter().find(|outcome| &*outcome.name == name.as_str());
+        match outcome.map(|outcome| &outcome.disposition) {
+            Some(ExpressionDisposition::Failed(msg)) => mismatches.push(format!("expression '{name}' failed: {msg}")),
+            Some(disposition) => {
+                let actual = matches!(disposition, ExpressionDisposition::True);
+                if actual != *expected {
+                    mismatches.push(format!("expected '{name}' to be {expected}, but it was {actual}"));
+                }
+            }
+            None => mismatches.push(format!("expression '{name}' was not evaluated because a high-risk expression was triggered")),
+        }
+    }
+
+    if let Some(risk) = test.risk
+        && risk != appraisal.risk
+    {
+        mismatches.push(format!("expected {risk}, but it was {} with a score of {:.0}", appraisal.risk, appraisal.score));
+    }
+
+    if mismatches.is_empty() { Ok(()) } else { Err(mismatches) }
+}
+
+/// Convert a TOML value to a metric value, using the placeholder value of the metric to tell integers from floats.
+fn metric_value(value: &toml::Value, placeholder: Option<&MetricValue>) -> Option<MetricValue> {
+    match value {
+        toml::Value::Integer(i) if matches!(placeholder, Some(MetricValue::Float(_))) => {
+            #[expect(clippy::cast_precision_loss, reason = "test metric values are small enough to be exact")]
+            let f = *i as f64;
+            Some(MetricValue::Float(f))
+        }
+        toml::Value::Integer(i) => u64::try_from(*i).ok().map(MetricValue::UInt),
+        toml::Value::Float(f) => Some(MetricValue::Float(*f)),
+        toml::Value::Boolean(b) => Some(MetricValue::Boolean(*b)),
+        toml::Value::String(s) => Some(MetricValue::String(s.as_str().into())),
+        toml::Value::Datetime(dt) => DateTime::parse_from_rfc3339(&dt.to_string()).ok().map(|dt| MetricValue::DateTime(dt.to_utc())),
+        toml::Value::Array(values) => values.iter().map(|v| metric_value(v, None)).collect::<Option<_>>().map(MetricValue::List),
+        toml::Value::Table(_) => None,
+    }
 }
 
 #[cfg(test)]
@@ -78,6 +365,14 @@ mod tests {
     use crate::commands::init::{InitArgs, init_config};
     use crate::commands::host::TestHost;
 
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread().build().expect("Failed to build runtime")
+    }
+
+    fn run_validate(host: &mut TestHost, args: &ValidateArgs) -> Result<()> {
+        runtime().block_on(validate_config(host, args))
+    }
+
     #[test]
     #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
     fn test_default_config_is_valid() {
@@ -89,6 +384,7 @@ mod tests {
         let init_args = InitArgs {
             output: Some(config_path.clone()),
             manifest_path: Utf8PathBuf::from("Cargo.toml"),
+            interactive: false,
         };
         init_config(&mut init_host, &init_args).expect("init_config should succeed");
 
@@ -97,8 +393,9 @@ mod tests {
         let args = ValidateArgs {
             config: Some(config_path),
             manifest_path: Utf8PathBuf::from("Cargo.toml"),
+            against: None,
         };
-        let result = validate_config(&mut host, &args);
+        let result = run_validate(&mut host, &args);
 
         assert!(result.is_ok(), "Default configuration should validate successfully: {result:?}");
     }
@@ -126,7 +423,7 @@ mod tests {
         let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
         let config_path = Utf8PathBuf::from(temp_dir.path().to_string_lossy().to_string()).join("invalid_syntax.toml");
 
-        std::fs::write(
+        fs::write(
             &config_path,
             r#"
 # Missing closing bracket
@@ -141,8 +438,9 @@ expression = "true"
         let args = ValidateArgs {
             config: Some(config_path),
             manifest_path: Utf8PathBuf::from("Cargo.toml"),
+            against: None,
         };
-        let result = validate_config(&mut host, &args);
+        let result = run_validate(&mut host, &args);
 
         assert!(result.is_err(), "Invalid TOML syntax should fail validation");
 
@@ -159,7 +457,7 @@ expression = "true"
         let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
         let config_path = Utf8PathBuf::from(temp_dir.path().to_string_lossy().to_string()).join("unknown_field.toml");
 
-        std::fs::write(
+        fs::write(
             &config_path,
             r#"
 high_risk = []
@@ -173,8 +471,9 @@ unknown_field = "value"
         let args = ValidateArgs {
             config: Some(config_path),
             manifest_path: Utf8PathBuf::from("Cargo.toml"),
+            against: None,
         };
-        let result = validate_config(&mut host, &args);
+        let result = run_validate(&mut host, &args);
 
         assert!(result.is_err(), "Unknown field should fail validation");
 
@@ -191,7 +490,7 @@ unknown_field = "value"
         let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
         let config_path = Utf8PathBuf::from(temp_dir.path().to_string_lossy().to_string()).join("invalid_expression.toml");
 
-        std::fs::write(
+        fs::write(
             &config_path,
             r#"
 [[high_risk]]
@@ -206,8 +505,9 @@ expression = "this is not a valid CEL expression !!!"
         let args = ValidateArgs {
             config: Some(config_path),
             manifest_path: Utf8PathBuf::from("Cargo.toml"),
+            against: None,
         };
-        let result = validate_config(&mut host, &args);
+        let result = run_validate(&mut host, &args);
 
         assert!(result.is_err(), "Invalid expression syntax should fail validation");
 
@@ -224,7 +524,7 @@ expression = "this is not a valid CEL expression !!!"
         let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
         let config_path = Utf8PathBuf::from(temp_dir.path().to_string_lossy().to_string()).join("invalid_duration.toml");
 
-        std::fs::write(
+        fs::write(
             &config_path,
             r#"
 crates_cache_ttl = "not a valid duration"
@@ -236,8 +536,9 @@ crates_cache_ttl = "not a valid duration"
         let args = ValidateArgs {
             config: Some(config_path),
             manifest_path: Utf8PathBuf::from("Cargo.toml"),
+            against: None,
         };
-        let result = validate_config(&mut host, &args);
+        let result = run_validate(&mut host, &args);
 
         assert!(result.is_err(), "Invalid duration format should fail validation");
 
@@ -254,7 +555,7 @@ crates_cache_ttl = "not a valid duration"
         let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
         let config_path = Utf8PathBuf::from(temp_dir.path().to_string_lossy().to_string()).join("nonexistent_metric.toml");
 
-        std::fs::write(
+        fs::write(
             &config_path,
             r#"
 [[eval]]
@@ -269,8 +570,9 @@ expression = "this_metric_does_not_exist > 100"
         let args = ValidateArgs {
             config: Some(config_path),
             manifest_path: Utf8PathBuf::from("Cargo.toml"),
+            against: None,
         };
-        let result = validate_config(&mut host, &args);
+        let result = run_validate(&mut host, &args);
 
         assert!(result.is_err(), "Expression referencing nonexistent metric should fail validation");
 
@@ -287,7 +589,7 @@ expression = "this_metric_does_not_exist > 100"
         let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
         let config_path = Utf8PathBuf::from(temp_dir.path().to_string_lossy().to_string()).join("type_mismatch.toml");
 
-        std::fs::write(
+        fs::write(
             &config_path,
             r#"
 [[high_risk]]
@@ -302,8 +604,9 @@ expression = "crates.downloads + 'string'"
         let args = ValidateArgs {
             config: Some(config_path),
             manifest_path: Utf8PathBuf::from("Cargo.toml"),
+            against: None,
         };
-        let result = validate_config(&mut host, &args);
+        let result = run_validate(&mut host, &args);
 
         assert!(result.is_err(), "Expression with type mismatch should fail validation");
 
@@ -320,7 +623,7 @@ expression = "crates.downloads + 'string'"
         let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
         let config_path = Utf8PathBuf::from(temp_dir.path().to_string_lossy().to_string()).join("non_boolean.toml");
 
-        std::fs::write(
+        fs::write(
             &config_path,
             r#"
 [[eval]]
@@ -335,8 +638,9 @@ expression = "crates.downloads"
         let args = ValidateArgs {
             config: Some(config_path),
             manifest_path: Utf8PathBuf::from("Cargo.toml"),
+            against: None,
         };
-        let result = validate_config(&mut host, &args);
+        let result = run_validate(&mut host, &args);
 
         assert!(result.is_err(), "Expression returning non-boolean should fail validation");
 
@@ -353,14 +657,15 @@ expression = "crates.downloads"
         let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
         let config_path = Utf8PathBuf::from(temp_dir.path().to_string_lossy().to_string()).join("empty.toml");
 
-        std::fs::write(&config_path, "# Empty config file\n").expect("Failed to write test config");
+        fs::write(&config_path, "# Empty config file\n").expect("Failed to write test config");
 
         let mut host = TestHost::new();
         let args = ValidateArgs {
             config: Some(config_path),
             manifest_path: Utf8PathBuf::from("Cargo.toml"),
+            against: None,
         };
-        let result = validate_config(&mut host, &args);
+        let result = run_validate(&mut host, &args);
 
         assert!(result.is_ok(), "Empty config should be valid (uses defaults)");
     }
@@ -371,14 +676,16 @@ expression = "crates.downloads"
         let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
         let config_path = Utf8PathBuf::from(temp_dir.path().to_string_lossy().to_string()).join("only_ttls.toml");
 
-        std::fs::write(
+        fs::write(
             &config_path,
             r#"
-crates_cache_ttl = "1h"
-hosting_cache_ttl = "2h"
-codebase_cache_ttl = "3h"
-coverage_cache_ttl = "4h"
-advisories_cache_ttl = "5h"
+[cache.ttl]
+crates = "1h"
+hosting = "2h"
+codebase = "3h"
+coverage = "4h"
+advisories = "5h"
+docs = "6h"
 "#,
         )
         .expect("Failed to write test config");
@@ -387,8 +694,9 @@ advisories_cache_ttl = "5h"
         let args = ValidateArgs {
             config: Some(config_path),
             manifest_path: Utf8PathBuf::from("Cargo.toml"),
+            against: None,
         };
-        let result = validate_config(&mut host, &args);
+        let result = run_validate(&mut host, &args);
 
         assert!(
             result.is_ok(),
@@ -402,14 +710,15 @@ advisories_cache_ttl = "5h"
         let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
         let config_path = Utf8PathBuf::from(temp_dir.path().to_string_lossy().to_string()).join("bad_threshold.toml");
 
-        std::fs::write(&config_path, "medium_risk_threshold = -1.0\n").expect("Failed to write test config");
+        fs::write(&config_path, "medium_risk_threshold = -1.0\n").expect("Failed to write test config");
 
         let mut host = TestHost::new();
         let args = ValidateArgs {
             config: Some(config_path),
             manifest_path: Utf8PathBuf::from("Cargo.toml"),
+            against: None,
         };
-        let result = validate_config(&mut host, &args);
+        let result = run_validate(&mut host, &args);
 
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("medium_risk_threshold must be between 0 and 100"));
@@ -421,14 +730,15 @@ advisories_cache_ttl = "5h"
         let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
         let config_path = Utf8PathBuf::from(temp_dir.path().to_string_lossy().to_string()).join("bad_threshold.toml");
 
-        std::fs::write(&config_path, "medium_risk_threshold = 101.0\n").expect("Failed to write test config");
+        fs::write(&config_path, "medium_risk_threshold = 101.0\n").expect("Failed to write test config");
 
         let mut host = TestHost::new();
         let args = ValidateArgs {
             config: Some(config_path),
             manifest_path: Utf8PathBuf::from("Cargo.toml"),
+            against: None,
         };
-        let result = validate_config(&mut host, &args);
+        let result = run_validate(&mut host, &args);
 
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("medium_risk_threshold must be between 0 and 100"));
@@ -440,14 +750,15 @@ advisories_cache_ttl = "5h"
         let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
         let config_path = Utf8PathBuf::from(temp_dir.path().to_string_lossy().to_string()).join("bad_threshold.toml");
 
-        std::fs::write(&config_path, "low_risk_threshold = -5.0\n").expect("Failed to write test config");
+        fs::write(&config_path, "low_risk_threshold = -5.0\n").expect("Failed to write test config");
 
         let mut host = TestHost::new();
         let args = ValidateArgs {
             config: Some(config_path),
             manifest_path: Utf8PathBuf::from("Cargo.toml"),
+            against: None,
         };
-        let result = validate_config(&mut host, &args);
+        let result = run_validate(&mut host, &args);
 
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("low_risk_threshold must be between 0 and 100"));
@@ -459,14 +770,15 @@ advisories_cache_ttl = "5h"
         let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
         let config_path = Utf8PathBuf::from(temp_dir.path().to_string_lossy().to_string()).join("bad_threshold.toml");
 
-        std::fs::write(&config_path, "low_risk_threshold = 200.0\n").expect("Failed to write test config");
+        fs::write(&config_path, "low_risk_threshold = 200.0\n").expect("Failed to write test config");
 
         let mut host = TestHost::new();
         let args = ValidateArgs {
             config: Some(config_path),
             manifest_path: Utf8PathBuf::from("Cargo.toml"),
+            against: None,
         };
-        let result = validate_config(&mut host, &args);
+        let result = run_validate(&mut host, &args);
 
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("low_risk_threshold must be between 0 and 100"));
@@ -478,7 +790,7 @@ advisories_cache_ttl = "5h"
         let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
         let config_path = Utf8PathBuf::from(temp_dir.path().to_string_lossy().to_string()).join("bad_threshold.toml");
 
-        std::fs::write(
+        fs::write(
             &config_path,
             "medium_risk_threshold = 80.0\nlow_risk_threshold = 50.0\n",
         )
@@ -488,8 +800,9 @@ advisories_cache_ttl = "5h"
         let args = ValidateArgs {
             config: Some(config_path),
             manifest_path: Utf8PathBuf::from("Cargo.toml"),
+            against: None,
         };
-        let result = validate_config(&mut host, &args);
+        let result = run_validate(&mut host, &args);
 
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("medium_risk_threshold (80) must be less than low_risk_threshold (50)"));
@@ -501,7 +814,7 @@ advisories_cache_ttl = "5h"
         let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
         let config_path = Utf8PathBuf::from(temp_dir.path().to_string_lossy().to_string()).join("bad_threshold.toml");
 
-        std::fs::write(
+        fs::write(
             &config_path,
             "medium_risk_threshold = 50.0\nlow_risk_threshold = 50.0\n",
         )
@@ -511,8 +824,9 @@ advisories_cache_ttl = "5h"
         let args = ValidateArgs {
             config: Some(config_path),
             manifest_path: Utf8PathBuf::from("Cargo.toml"),
+            against: None,
         };
-        let result = validate_config(&mut host, &args);
+        let result = run_validate(&mut host, &args);
 
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("must be less than"));
@@ -524,7 +838,7 @@ advisories_cache_ttl = "5h"
         let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
         let config_path = Utf8PathBuf::from(temp_dir.path().to_string_lossy().to_string()).join("custom_thresholds.toml");
 
-        std::fs::write(
+        fs::write(
             &config_path,
             "medium_risk_threshold = 25.0\nlow_risk_threshold = 75.0\n",
         )
@@ -534,9 +848,154 @@ advisories_cache_ttl = "5h"
         let args = ValidateArgs {
             config: Some(config_path),
             manifest_path: Utf8PathBuf::from("Cargo.toml"),
+            against: None,
         };
-        let result = validate_config(&mut host, &args);
+        let result = run_validate(&mut host, &args);
 
         assert!(result.is_ok(), "Valid custom thresholds should pass validation: {result:?}");
     }
+
+    const EXPRESSION_TESTS_CONFIG: &str = r#"
+[[high_risk]]
+name = "Recent"
+expression = "days_since(stability.crate_created_at) < 365"
+
+[[eval]]
+name = "Popular"
+expression = "usage.total_downloads > 1000"
+
+[[expression_tests]]
+name = "popular new crate"
+now = 2024-06-01T00:00:00Z
+metrics = { "stability.crate_created_at" = 2024-01-01T00:00:00Z, "usage.total_downloads" = 5000 }
+expect = { Recent = true, Popular = true }
+risk = "low"
+"#;
+
+    fn validate_text(text: &str) -> (Result<()>, TestHost) {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let config_path = Utf8PathBuf::from(temp_dir.path().to_string_lossy().to_string()).join("tests.toml");
+        fs::write(&config_path, text).expect("Failed to write test config");
+
+        let mut host = TestHost::new();
+        let args = ValidateArgs {
+            config: Some(config_path),
+            manifest_path: Utf8PathBuf::from("Cargo.toml"),
+            against: None,
+        };
+        (run_validate(&mut host, &args), host)
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_expression_tests_pass() {
+        let (result, host) = validate_text(EXPRESSION_TESTS_CONFIG);
+        assert!(result.is_ok(), "Expression tests should pass: {result:?}");
+        assert!(String::from_utf8_lossy(&host.output_buf).contains("All 1 expression test(s) passed"));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_expression_tests_fail() {
+        let text = format!(
+            "{EXPRESSION_TESTS_CONFIG}{}",
+            r#"
+[[expression_tests]]
+name = "unpopular old crate"
+now = 2024-06-01T00:00:00Z
+metrics = { "stability.crate_created_at" = 2020-01-01T00:00:00Z, "usage.total_downloads" = 10 }
+expect = { Recent = true, Popular = false, Missing = true }
+"#
+        );
+
+        let (result, _) = validate_text(&text);
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("1 of 2 expression test(s) failed"), "{err}");
+        assert!(err.contains("unpopular old crate: expected 'Recent' to be true, but it was false"), "{err}");
+        assert!(err.contains("'Popular' was not evaluated"), "{err}");
+        assert!(err.contains("there is no expression named 'Missing'"), "{err}");
+    }
+
+    #[test]
+    fn test_metric_value_conversion() {
+        let float = MetricValue::Float(0.0);
+        assert!(matches!(metric_value(&toml::Value::Integer(5), Some(&float)), Some(MetricValue::Float(f)) if (f - 5.0).abs() < f64::EPSILON));
+        assert!(matches!(metric_value(&toml::Value::Integer(5), None), Some(MetricValue::UInt(5))));
+        assert!(metric_value(&toml::Value::Integer(-5), None).is_none());
+        assert!(matches!(
+            metric_value(&toml::Value::Array(vec![toml::Value::String("a".into())]), None),
+            Some(MetricValue::List(values)) if values.len() == 1
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_unknown_metric_suggestion() {
+        let (result, _) = validate_text(
+            r#"
+[[eval]]
+name = "Few Issues"
+expression = "activity.open_isues < 10"
+"#,
+        );
+
+        let err = result.unwrap_err().to_string();
+        assert!(
+            err.contains("expression 'Few Issues' refers to unknown metric 'activity.open_isues', did you mean 'activity.open_issues'?"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_unreferenced_metrics() {
+        let (result, host) = validate_text(
+            r#"
+[[eval]]
+name = "Few Issues"
+expression = "activity.open_issues < 10"
+"#,
+        );
+        assert!(result.is_ok(), "{result:?}");
+
+        let output = String::from_utf8_lossy(&host.output_buf);
+        let total = metric_names().count();
+        assert!(output.contains(&format!("{} of {total} metrics are not referenced", total - 1)), "{output}");
+        assert!(output.contains("  Activity: "), "{output}");
+        assert!(
+            !output.lines().flat_map(|line| line.split([':', ',']).skip(1)).any(|name| name.trim() == "activity.open_issues"),
+            "{output}"
+        );
+
+        let (result, host) = validate_text("# Empty config file\n");
+        assert!(result.is_ok(), "{result:?}");
+        assert!(!String::from_utf8_lossy(&host.output_buf).contains("not referenced"));
+    }
+
+    #[test]
+    fn test_rescore_report() {
+        let config: Config = toml::from_str(
+            r#"
+[[eval]]
+name = "Popular"
+expression = "usage.total_downloads > 1000"
+"#,
+        )
+        .unwrap();
+
+        let report = r#"{"crates": [
+            {"name": "popular", "version": "1.0.0", "appraisal": {"result": "LOW RISK (score = 100)", "score": 100.0},
+             "metrics": {"usage.total_downloads": 5000}},
+            {"name": "obscure", "version": "0.2.0", "appraisal": {"result": "LOW RISK (score = 100)", "score": 100.0},
+             "metrics": {"usage.total_downloads": 10}},
+            {"name": "unappraised", "version": "1.0.0", "metrics": {}}
+        ]}"#;
+        let impact = rescore(&config, &read_json_crates(report).unwrap(), Local::now());
+
+        assert_eq!(impact.rescored, 2);
+        assert_eq!(impact.changes.len(), 1);
+        let change = &impact.changes[0];
+        assert_eq!((change.name.as_str(), change.version.as_str()), ("obscure", "0.2.0"));
+        assert_eq!((change.previous_risk, change.risk), (Risk::Low, Risk::High));
+    }
 }
@@ -0,0 +1,160 @@
+//! Ways to combine the outcomes of the eval expressions into an overall score.
+
+use super::{Appraisal, Expression, Risk, category_scores};
+use crate::metrics::MetricCategory;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// How the overall score of a crate is computed from its eval expressions.
+///
+/// Apart from `Sum`, aggregations work on the category scores computed by [`category_scores`], so eval
+/// expressions that don't refer to any metric don't contribute to them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Aggregation {
+    /// The share of all points that were awarded, so strengths in one area make up for weaknesses in another
+    #[default]
+    Sum,
+
+    /// The mean of the category scores, weighted by the configured category weights
+    WeightedMean,
+
+    /// The weighted geometric mean of the category scores, which drops sharply when any category scores poorly
+    GeometricMean,
+
+    /// The score of the weakest category
+    MinimumCategory,
+}
+
+impl Aggregation {
+    /// Recompute the score and risk of an appraisal using this aggregation.
+    ///
+    /// Categories missing from `weights` have a weight of 1. Appraisals without any category score, including
+    /// those flagged by a high-risk expression, are left untouched.
+    pub fn apply(
+        self,
+        appraisal: &mut Appraisal,
+        eval: &[Expression],
+        weights: &BTreeMap<MetricCategory, f64>,
+        medium_risk_threshold: f64,
+        low_risk_threshold: f64,
+    ) {
+        if self == Self::Sum {
+            return;
+        }
+
+        let weighted: Vec<(f64, f64)> = category_scores(eval, appraisal)
+            .into_iter()
+            .map(|(category, score)| (score, weights.get(&category).copied().unwrap_or(1.0)))
+            .filter(|(_, weight)| *weight > 0.0)
+            .collect();
+
+        let total_weight: f64 = weighted.iter().map(|(_, weight)| weight).sum();
+        if weighted.is_empty() || total_weight <= 0.0 {
+            return;
+        }
+
+        let score = match self {
+            Self::Sum => return,
+            Self::WeightedMean => weighted.iter().map(|(score, weight)| score * weight).sum::<f64>() / total_weight,
+            Self::GeometricMean => {
+                if weighted.iter().any(|(score, _)| *score <= 0.0) {
+                    0.0
+                } else {
+                    let log_mean = weighted.iter().map(|(score, weight)| (score / 100.0).ln() * weight).sum::<f64>() / total_weight;
+                    log_mean.exp() * 100.0
+                }
+            }
+            Self::MinimumCategory => weighted.iter().map(|(score, _)| *score).fold(f64::INFINITY, f64::min),
+        };
+
+        appraisal.score = score;
+        appraisal.risk = Risk::from_score(score, medium_risk_threshold, low_risk_threshold);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::{ExpressionDisposition, ExpressionOutcome};
+    use std::sync::Arc;
+
+    const MEDIUM_THRESHOLD: f64 = 30.0;
+    const LOW_THRESHOLD: f64 = 70.0;
+
+    /// An appraisal where Usage scores 100, Activity 0, and Community 50, with a points-based score of 60.
+    fn setup() -> (Vec<Expression>, Appraisal) {
+        let eval = vec![
+            Expression::new("popular", None, "usage.total_downloads > 1000", Some(5)).unwrap(),
+            Expression::new("maintained", None, "activity.open_issues < 10", Some(3)).unwrap(),
+            Expression::new("starred", None, "community.repo_stars > 100", Some(1)).unwrap(),
+            Expression::new("forked", None, "community.repo_forks > 10", Some(1)).unwrap(),
+        ];
+
+        let outcome = |name: &str, disposition| ExpressionOutcome::new(Arc::from(name), Arc::from(name), disposition);
+        let appraisal = Appraisal::new(
+            Risk::Medium,
+            vec![
+                outcome("popular", ExpressionDisposition::True),
+                outcome("maintained", ExpressionDisposition::False),
+                outcome("starred", ExpressionDisposition::True),
+                outcome("forked", ExpressionDisposition::False),
+            ],
+            10,
+            6,
+            60.0,
+        );
+
+        (eval, appraisal)
+    }
+
+    fn aggregate(aggregation: Aggregation, weights: &BTreeMap<MetricCategory, f64>) -> Appraisal {
+        let (eval, mut appraisal) = setup();
+        aggregation.apply(&mut appraisal, &eval, weights, MEDIUM_THRESHOLD, LOW_THRESHOLD);
+        appraisal
+    }
+
+    #[test]
+    fn test_sum_leaves_appraisal_unchanged() {
+        let appraisal = aggregate(Aggregation::Sum, &BTreeMap::new());
+        assert!((appraisal.score - 60.0).abs() < f64::EPSILON);
+        assert_eq!(appraisal.risk, Risk::Medium);
+    }
+
+    #[test]
+    fn test_weighted_mean() {
+        let appraisal = aggregate(Aggregation::WeightedMean, &BTreeMap::new());
+        assert!((appraisal.score - 50.0).abs() < 1e-9);
+        assert_eq!(appraisal.risk, Risk::Medium);
+
+        let weights = BTreeMap::from([(MetricCategory::Usage, 2.0), (MetricCategory::Community, 0.0)]);
+        let appraisal = aggregate(Aggregation::WeightedMean, &weights);
+        assert!((appraisal.score - 200.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_geometric_mean() {
+        let appraisal = aggregate(Aggregation::GeometricMean, &BTreeMap::new());
+        assert!(appraisal.score.abs() < f64::EPSILON);
+        assert_eq!(appraisal.risk, Risk::High);
+
+        let weights = BTreeMap::from([(MetricCategory::Activity, 0.0)]);
+        let appraisal = aggregate(Aggregation::GeometricMean, &weights);
+        assert!((appraisal.score - 5000.0_f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_minimum_category() {
+        let appraisal = aggregate(Aggregation::MinimumCategory, &BTreeMap::new());
+        assert!(appraisal.score.abs() < f64::EPSILON);
+        assert_eq!(appraisal.risk, Risk::High);
+    }
+
+    #[test]
+    fn test_no_categories_leaves_appraisal_unchanged() {
+        let (eval, mut appraisal) = setup();
+        appraisal.expression_outcomes.clear();
+        Aggregation::MinimumCategory.apply(&mut appraisal, &eval, &BTreeMap::new(), MEDIUM_THRESHOLD, LOW_THRESHOLD);
+        assert!((appraisal.score - 60.0).abs() < f64::EPSILON);
+    }
+}
@@ -8,6 +8,10 @@ pub struct Appraisal {
     pub available_points: u32,
     pub awarded_points: u32,
     pub score: f64,
+
+    /// Points of the eval expressions that couldn't be evaluated, usually because the metrics they
+    /// depend on weren't available
+    pub unevaluated_points: u32,
 }
 
 impl Appraisal {
@@ -25,6 +29,69 @@ impl Appraisal {
             available_points,
             awarded_points,
             score,
+            unevaluated_points: 0,
         }
     }
+
+    /// Record the points of the eval expressions that couldn't be evaluated.
+    #[must_use]
+    pub const fn with_unevaluated_points(mut self, unevaluated_points: u32) -> Self {
+        self.unevaluated_points = unevaluated_points;
+        self
+    }
+
+    /// Fraction of the eval points, between 0 and 1, that the score is based on.
+    #[must_use]
+    pub fn confidence(&self) -> f64 {
+        let total = self.available_points + self.unevaluated_points;
+        if total == 0 {
+            1.0
+        } else {
+            f64::from(self.available_points) / f64::from(total)
+        }
+    }
+
+    /// How far the score could move if the unevaluated expressions had been evaluated.
+    ///
+    /// The score could be as low as if every unevaluated expression had been false, or as high as if
+    /// they had all been true. The margin is the larger of the two distances from the score.
+    #[must_use]
+    pub fn score_margin(&self) -> f64 {
+        let total = self.available_points + self.unevaluated_points;
+        if self.unevaluated_points == 0 || total == 0 {
+            return 0.0;
+        }
+
+        let lowest = f64::from(self.awarded_points) / f64::from(total) * 100.0;
+        let highest = f64::from(self.awarded_points + self.unevaluated_points) / f64::from(total) * 100.0;
+        (self.score - lowest).max(highest - self.score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fully_evaluated() {
+        let appraisal = Appraisal::new(Risk::Medium, vec![], 10, 5, 50.0);
+        assert!((appraisal.confidence() - 1.0).abs() < f64::EPSILON);
+        assert!(appraisal.score_margin().abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_partially_evaluated() {
+        // 6 of 8 evaluated points awarded, with 2 points that couldn't be evaluated: the
+        // score of 75 could range from 60 to 80
+        let appraisal = Appraisal::new(Risk::Low, vec![], 8, 6, 75.0).with_unevaluated_points(2);
+        assert!((appraisal.confidence() - 0.8).abs() < f64::EPSILON);
+        assert!((appraisal.score_margin() - 15.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_nothing_evaluated() {
+        let appraisal = Appraisal::new(Risk::Low, vec![], 0, 0, 100.0).with_unevaluated_points(3);
+        assert!(appraisal.confidence().abs() < f64::EPSILON);
+        assert!((appraisal.score_margin() - 100.0).abs() < f64::EPSILON);
+    }
 }
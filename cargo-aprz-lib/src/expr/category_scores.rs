@@ -0,0 +1,107 @@
+//! Scores of a crate broken down by metric category.
+//!
+//! An eval expression counts towards every category of the metrics it refers to. A category's score is computed
+//! like the overall score, but only from the expressions that count towards it.
+
+use super::references::metric_references;
+use super::{Appraisal, Expression, ExpressionDisposition};
+use crate::metrics::{MetricCategory, metric_category};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Compute the score of each metric category from the eval expressions of an appraisal.
+///
+/// Categories without any evaluated expression are absent from the result, as is everything when the eval
+/// expressions were skipped because a high-risk expression was triggered.
+#[must_use]
+pub fn category_scores(eval: &[Expression], appraisal: &Appraisal) -> BTreeMap<MetricCategory, f64> {
+    let outcomes = &appraisal.expression_outcomes;
+    let Some(eval_outcomes) = outcomes.get(outcomes.len().saturating_sub(eval.len())..) else {
+        return BTreeMap::new();
+    };
+
+    if eval_outcomes.len() != eval.len() || eval.iter().zip(eval_outcomes).any(|(expr, outcome)| expr.name() != &*outcome.name) {
+        return BTreeMap::new();
+    }
+
+    let mut points: BTreeMap<MetricCategory, (u32, u32)> = BTreeMap::new();
+    for (expr, outcome) in eval.iter().zip(eval_outcomes) {
+        let expr_points = expr.points().unwrap_or(1);
+        let awarded = match outcome.disposition {
+            ExpressionDisposition::True => expr_points,
+            ExpressionDisposition::False => 0,
+            ExpressionDisposition::Failed(_) => continue,
+        };
+
+        for category in expression_categories(expr) {
+            let entry = points.entry(category).or_default();
+            entry.0 += awarded;
+            entry.1 += expr_points;
+        }
+    }
+
+    points
+        .into_iter()
+        .filter(|(_, (_, available))| *available > 0)
+        .map(|(category, (awarded, available))| (category, f64::from(awarded) / f64::from(available) * 100.0))
+        .collect()
+}
+
+/// Find the categories of the metrics an expression refers to.
+fn expression_categories(expr: &Expression) -> BTreeSet<MetricCategory> {
+    metric_references(expr.expression()).metrics.into_iter().filter_map(metric_category).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::{ExpressionOutcome, Risk};
+    use std::sync::Arc;
+
+    fn outcome(name: &str, disposition: ExpressionDisposition) -> ExpressionOutcome {
+        ExpressionOutcome::new(Arc::from(name), Arc::from(name), disposition)
+    }
+
+    #[test]
+    fn test_expression_categories() {
+        let expr = Expression::new("e", None, "activity.open_issues < 10 && crate.keywords.size() > 0", None).unwrap();
+        let categories: Vec<_> = expression_categories(&expr).into_iter().collect();
+        assert_eq!(categories, [MetricCategory::Metadata, MetricCategory::Activity]);
+
+        let expr = Expression::new("e", None, "unknown > 5", None).unwrap();
+        assert!(expression_categories(&expr).is_empty());
+    }
+
+    #[test]
+    fn test_category_scores() {
+        let eval = [
+            Expression::new("issues", None, "activity.open_issues < 10", Some(3)).unwrap(),
+            Expression::new("name", None, "crate.name != ''", Some(1)).unwrap(),
+            Expression::new("both", None, "activity.open_issues < 5 && crate.name != ''", Some(1)).unwrap(),
+            Expression::new("failed", None, "crate.license == 'MIT'", Some(5)).unwrap(),
+        ];
+        let appraisal = Appraisal::new(
+            Risk::Low,
+            vec![
+                outcome("issues", ExpressionDisposition::False),
+                outcome("name", ExpressionDisposition::True),
+                outcome("both", ExpressionDisposition::True),
+                outcome("failed", ExpressionDisposition::Failed("oops".to_string())),
+            ],
+            5,
+            2,
+            40.0,
+        );
+
+        let scores = category_scores(&eval, &appraisal);
+        assert_eq!(scores.len(), 2);
+        assert!((scores[&MetricCategory::Activity] - 25.0).abs() < f64::EPSILON);
+        assert!((scores[&MetricCategory::Metadata] - 100.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_category_scores_when_eval_skipped() {
+        let eval = [Expression::new("issues", None, "activity.open_issues < 10", None).unwrap()];
+        let appraisal = Appraisal::new(Risk::High, vec![outcome("gate", ExpressionDisposition::False)], 0, 0, 0.0);
+        assert!(category_scores(&eval, &appraisal).is_empty());
+    }
+}
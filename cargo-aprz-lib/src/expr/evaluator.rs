@@ -3,6 +3,7 @@
 //! This module provides functionality to evaluate crates against configured
 //! expressions and determine if they should be ACCEPTED, DENIED, or NOT EVALUATED.
 
+use super::functions::{PERCENTILE_METRICS, add_functions};
 use super::{Appraisal, Expression, ExpressionDisposition, ExpressionOutcome, Risk};
 use crate::metrics::{Metric, MetricValue};
 use cel_interpreter::{Context, Program, Value, objects::Map};
@@ -23,7 +24,9 @@ use std::sync::Arc;
 /// Expression evaluation failures are captured as [`ExpressionDisposition::Failed`]
 /// rather than causing the function to fail.
 ///
-/// Expressions without explicit points default to 1 point each.
+/// Expressions without explicit points default to 1 point each. The points of eval expressions that fail
+/// to evaluate are left out of the score and recorded as unevaluated, which lets reports show how much the
+/// score could change had the missing metrics been available.
 pub fn evaluate(
     high_risk: &[Expression],
     eval: &[Expression],
@@ -54,7 +57,8 @@ pub fn evaluate(
             expr.name_arc(),
             expr.description_or_expression_arc(),
             disposition,
-        ));
+        )
+        .with_origin(expr.origin_arc()));
     }
 
     if high_risk_triggered {
@@ -67,6 +71,7 @@ pub fn evaluate(
 
     let mut available_points: u32 = 0;
     let mut awarded_points: u32 = 0;
+    let mut unevaluated_points: u32 = 0;
     let mut outcomes = high_risk_outcomes;
     outcomes.reserve(eval.len());
 
@@ -83,13 +88,17 @@ pub fn evaluate(
                 available_points += points;
                 ExpressionDisposition::False
             },
-            Err(e) => ExpressionDisposition::Failed(e),
+            Err(e) => {
+                unevaluated_points += points;
+                ExpressionDisposition::Failed(e)
+            }
         };
         outcomes.push(ExpressionOutcome::new(
             expr.name_arc(),
             expr.description_or_expression_arc(),
             disposition,
-        ));
+        )
+        .with_origin(expr.origin_arc()));
     }
 
     // No expressions means nothing to fail, so default to a perfect score
@@ -99,15 +108,54 @@ pub fn evaluate(
         100.0
     };
 
-    let risk = if score >= low_risk_threshold {
-        Risk::Low
-    } else if score >= medium_risk_threshold {
-        Risk::Medium
-    } else {
-        Risk::High
-    };
+    let risk = Risk::from_score(score, medium_risk_threshold, low_risk_threshold);
+
+    Appraisal::new(risk, outcomes, available_points, awarded_points, score).with_unevaluated_points(unevaluated_points)
+}
+
+/// Evaluate an arbitrary expression against metrics, returning its value rendered as text
+///
+/// Unlike [`evaluate`], the expression can produce a value of any type, which makes this suitable for exploring
+/// the metrics of a crate while authoring expressions.
+///
+/// # Errors
+///
+/// Returns a description of the problem if the expression can't be parsed or evaluated.
+pub fn evaluate_to_string(
+    expression: &str,
+    metrics: impl IntoIterator<Item: core::borrow::Borrow<Metric>>,
+    now: DateTime<Local>,
+) -> Result<String, String> {
+    let program = compile_interactive(expression).map_err(|e| format!("could not parse expression: {e}"))?;
+    let context = build_cel_context(metrics, now);
+    let value = program.execute(&context).map_err(|e| e.to_string())?;
+    Ok(format_value(&value))
+}
+
+/// Compile an expression typed interactively, which is often incomplete.
+///
+/// The CEL parser panics rather than failing on some incomplete input, such as a trailing operator, so the panic
+/// is turned into an error to keep the session going.
+fn compile_interactive(expression: &str) -> Result<Program, String> {
+    std::panic::catch_unwind(|| Program::compile(expression)).map_or_else(
+        |_| Err("the expression is incomplete".to_string()),
+        |program| program.map_err(|e| e.to_string()),
+    )
+}
 
-    Appraisal::new(risk, outcomes, available_points, awarded_points, score)
+/// Render a CEL value the way it would be written in an expression
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Int(i) => i.to_string(),
+        Value::UInt(u) => format!("{u}u"),
+        Value::Float(f) => format!("{f:?}"),
+        Value::String(s) => format!("{s:?}"),
+        Value::Timestamp(ts) => format!("timestamp('{}')", ts.to_rfc3339()),
+        Value::List(values) => format!("[{}]", values.iter().map(format_value).collect::<Vec<_>>().join(", ")),
+        other => format!("{other:?}"),
+    }
 }
 
 /// Evaluates a pre-parsed boolean expression against a context
@@ -129,12 +177,19 @@ fn build_cel_context(metrics: impl IntoIterator<Item: core::borrow::Borrow<Metri
     // Build nested map structure for dotted metric names
     let mut root_map: crate::HashMap<&str, std::collections::HashMap<Arc<String>, Value>> = crate::hash_map_with_capacity(16);
     let mut flat_vars: Vec<(&str, Value)> = Vec::with_capacity(16);
+    let mut percentiles = crate::HashMap::default();
 
     for metric in metrics {
         let metric: &Metric = metric.borrow();
         let cel_value = metric.value.as_ref().map_or(Value::Null, convert_metric_value);
         let name = metric.name();
 
+        if let Some(MetricValue::Float(value)) = &metric.value
+            && PERCENTILE_METRICS.iter().any(|(_, percentile_metric)| *percentile_metric == name)
+        {
+            let _ = percentiles.insert(name.to_string(), *value);
+        }
+
         // Split on first dot only
         if let Some((prefix, suffix)) = name.split_once('.') {
             let _ = root_map
@@ -161,6 +216,8 @@ fn build_cel_context(metrics: impl IntoIterator<Item: core::borrow::Borrow<Metri
     // Add now variable
     context.add_variable_from_value("now", Value::Timestamp(now.fixed_offset()));
 
+    add_functions(&mut context, now.fixed_offset(), percentiles);
+
     context
 }
 
@@ -212,6 +269,18 @@ mod tests {
         assert!(outcome.expression_outcomes.is_empty());
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_evaluate_to_string() {
+        let metrics = [Metric::with_value(&STARS_DEF, MetricValue::UInt(150))];
+
+        assert_eq!(evaluate_to_string("stars", &metrics, test_timestamp()).unwrap(), "150u");
+        assert_eq!(evaluate_to_string("stars > 100u", &metrics, test_timestamp()).unwrap(), "true");
+        assert_eq!(evaluate_to_string("[1, 'a', 2.5]", &metrics, test_timestamp()).unwrap(), r#"[1, "a", 2.5]"#);
+        assert!(evaluate_to_string("(stars", &metrics, test_timestamp()).unwrap_err().contains("could not parse"));
+        let _ = evaluate_to_string("missing + 1", &metrics, test_timestamp()).unwrap_err();
+    }
+
     #[test]
     #[cfg_attr(miri, ignore)]
     fn test_evaluation_outcome_creation() {
@@ -646,6 +715,7 @@ mod tests {
         let outcome = evaluate(&[], &[], Vec::<Metric>::new(), test_timestamp(), MEDIUM_THRESHOLD, LOW_THRESHOLD);
         assert_eq!(outcome.available_points, 0);
         assert_eq!(outcome.awarded_points, 0);
+        assert_eq!(outcome.unevaluated_points, 0);
         assert!((outcome.score - 100.0).abs() < 0.001);
         assert_eq!(outcome.risk, Risk::Low);
     }
@@ -747,6 +817,9 @@ mod tests {
         assert!((outcome.score - 100.0).abs() < 0.001);
         assert_eq!(outcome.risk, Risk::Low);
         assert!(matches!(outcome.expression_outcomes[1].disposition, ExpressionDisposition::Failed(_)));
+        assert_eq!(outcome.unevaluated_points, 5);
+        assert!((outcome.confidence() - 0.5).abs() < 0.001);
+        assert!((outcome.score_margin() - 50.0).abs() < 0.001);
     }
 
     #[test]
@@ -1,8 +1,10 @@
 //! Boolean expression evaluation for filtering crates
 
+use super::snippets::expand_snippets;
 use cel_interpreter::Program;
 use serde::de::Error as DeError;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 /// A boolean expression that can be evaluated against crate metrics
@@ -12,6 +14,7 @@ pub struct Expression {
     description: Option<Arc<str>>,
     points: Option<u32>,
     program: Arc<Program>,
+    origin: Option<Arc<str>>,
 
     #[expect(clippy::struct_field_names, reason = "Field name matches struct name intentionally for clarity")]
     expression_string: Arc<str>,
@@ -29,7 +32,6 @@ impl Expression {
         Arc::clone(&self.name)
     }
 
-    #[cfg(any(test, debug_assertions))]
     #[must_use]
     pub fn description(&self) -> Option<&str> {
         self.description.as_deref()
@@ -46,7 +48,36 @@ impl Expression {
         self.points
     }
 
-    #[cfg(any(test, debug_assertions))]
+    /// Returns a copy of this expression worth `points` points.
+    #[must_use]
+    pub fn with_points(&self, points: u32) -> Self {
+        Self {
+            points: Some(points),
+            ..self.clone()
+        }
+    }
+
+    /// Where the expression was defined, such as `aprz.toml:12`, if known.
+    #[must_use]
+    pub fn origin(&self) -> Option<&str> {
+        self.origin.as_deref()
+    }
+
+    /// Returns a cheap clone of the origin as an `Arc<str>`.
+    #[must_use]
+    pub fn origin_arc(&self) -> Option<Arc<str>> {
+        self.origin.clone()
+    }
+
+    /// Returns a copy of this expression recorded as defined at `origin`.
+    #[must_use]
+    pub fn with_origin(&self, origin: impl Into<Arc<str>>) -> Self {
+        Self {
+            origin: Some(origin.into()),
+            ..self.clone()
+        }
+    }
+
     #[must_use]
     pub fn expression(&self) -> &str {
         &self.expression_string
@@ -101,14 +132,30 @@ impl<'de> Deserialize<'de> for Expression {
             description: data.description,
             points: data.points,
             program: Arc::new(program),
+            origin: None,
             expression_string: data.expression,
         })
     }
 }
 
-#[cfg(test)]
 impl Expression {
-    /// Create a new expression by parsing an expression string (test helper).
+    /// Returns a copy of this expression with its snippet references replaced by the text of the snippets.
+    pub(crate) fn with_snippets(&self, snippets: &BTreeMap<String, String>) -> crate::Result<Self> {
+        use ohno::EnrichableExt;
+
+        let expanded = expand_snippets(&self.expression_string, snippets)
+            .map_err(|e| e.enrich_with(|| format!("expanding expression '{}'", self.name)))?;
+        if *expanded == *self.expression_string {
+            return Ok(self.clone());
+        }
+
+        let description = self.description.as_deref();
+        let mut expanded = Self::new(&self.name, description, &expanded, self.points)?;
+        expanded.origin.clone_from(&self.origin);
+        Ok(expanded)
+    }
+
+    /// Create a new expression by parsing an expression string.
     pub(crate) fn new(name: &str, description: Option<&str>, expression: &str, points: Option<u32>) -> crate::Result<Self> {
         use ohno::app_err;
 
@@ -119,6 +166,7 @@ impl Expression {
             description: description.map(Arc::from),
             points,
             program: Arc::new(program),
+            origin: None,
             expression_string: Arc::from(expression),
         })
     }
@@ -155,6 +203,32 @@ mod tests {
         assert!(expr.description.is_none());
     }
 
+    #[test]
+    fn test_with_points() {
+        let expr = Expression::new("simple_check", Some("desc"), "x > 5", None).unwrap();
+        let weighted = expr.with_points(7);
+
+        assert_eq!(weighted.points(), Some(7));
+        assert_eq!(weighted.name(), "simple_check");
+        assert_eq!(weighted.expression(), "x > 5");
+        assert_eq!(expr.points(), None);
+    }
+
+    #[test]
+    fn test_with_snippets() {
+        let snippets = BTreeMap::from([("popular".to_string(), "stars > 100".to_string())]);
+
+        let expr = Expression::new("check", None, "snippets.popular && x > 5", Some(3)).unwrap().with_origin("aprz.toml:4");
+        let expanded = expr.with_snippets(&snippets).unwrap();
+        assert_eq!(expanded.expression(), "(stars > 100) && x > 5");
+        assert_eq!(expanded.points(), Some(3));
+        assert_eq!(expanded.origin(), Some("aprz.toml:4"));
+
+        let expr = Expression::new("check", None, "snippets.missing", None).unwrap();
+        let err = expr.with_snippets(&snippets).unwrap_err();
+        assert!(err.to_string().contains("unknown snippet 'missing'"));
+    }
+
     #[test]
     fn test_create_expression_invalid() {
         let expr = Expression::new(
@@ -13,6 +13,9 @@ pub struct ExpressionOutcome {
     pub name: Arc<str>,
     pub description: Arc<str>,
     pub disposition: ExpressionDisposition,
+
+    /// Where the expression was defined, such as `aprz.toml:12` or `default`, if known
+    pub origin: Option<Arc<str>>,
 }
 
 impl ExpressionOutcome {
@@ -23,7 +26,15 @@ impl ExpressionOutcome {
             name,
             description,
             disposition,
+            origin: None,
         }
     }
+
+    /// Record where the expression was defined.
+    #[must_use]
+    pub fn with_origin(mut self, origin: Option<Arc<str>>) -> Self {
+        self.origin = origin;
+        self
+    }
 }
 
@@ -0,0 +1,205 @@
+//! Domain functions available to expressions, on top of those built into CEL.
+//!
+//! - `semver_matches(version, req)`: whether a version string satisfies a semver requirement
+//! - `days_since(timestamp)`: whole days elapsed between a timestamp and the time of evaluation
+//! - `matches(regex, s)`: whether a regular expression matches anywhere in a string
+//! - `percentile(metric_name)`: the percentage of crates.io crates ranking below this crate for a metric
+//! - `rust_version_at_most(version, max)`: whether a Rust version such as `1.70` is no newer than another
+//! - `license_compatible(license, project_license)`: whether an SPDX license expression is compatible with the
+//!   license of the project depending on the crate
+//! - `license_allowed(license, allowed)`: whether an SPDX license expression can be satisfied with the licenses of a
+//!   list of allowed identifiers
+
+use crate::HashMap;
+use cel_interpreter::{Context, ExecutionError, ResolveResult, Value};
+use super::licenses::{ProjectLicense, license_allowed, license_compatible};
+use chrono::{DateTime, FixedOffset};
+use core::cmp::Ordering;
+use regex::Regex;
+use semver::{Version, VersionReq};
+use std::sync::Arc;
+
+/// Metrics accepted by `percentile`, along with the metric holding their percentile.
+pub const PERCENTILE_METRICS: [(&str, &str); 3] = [
+    ("usage.total_downloads", "usage.downloads_percentile"),
+    ("usage.dependent_crates", "usage.dependents_percentile"),
+    ("stability.crate_created_at", "stability.age_percentile"),
+];
+
+/// Register the domain functions in a CEL context.
+///
+/// `percentiles` maps the percentile metrics listed in [`PERCENTILE_METRICS`] to their value for the crate.
+pub fn add_functions(context: &mut Context<'_>, now: DateTime<FixedOffset>, percentiles: HashMap<String, f64>) {
+    context.add_function("semver_matches", |version: Arc<String>, req: Arc<String>| semver_matches(&version, &req));
+    context.add_function("days_since", move |timestamp: Value| days_since(&timestamp, now));
+    context.add_function("matches", |regex: Arc<String>, s: Arc<String>| matches(&regex, &s));
+    context.add_function("percentile", move |metric: Arc<String>| percentile(&metric, &percentiles));
+    context.add_function("rust_version_at_most", |version: Arc<String>, max: Arc<String>| rust_version_at_most(&version, &max));
+    context.add_function("license_compatible", |license: Arc<String>, project_license: Arc<String>| {
+        check_license_compatible(&license, &project_license)
+    });
+    context.add_function("license_allowed", |license: Arc<String>, allowed: Arc<Vec<Value>>| {
+        check_license_allowed(&license, &allowed)
+    });
+}
+
+/// Compare two Rust versions such as `1.70` or `1.70.0`, missing components counting as zero.
+///
+/// Returns `None` if either version isn't made of at most three numeric components.
+#[must_use]
+pub fn compare_rust_versions(a: &str, b: &str) -> Option<Ordering> {
+    fn parse(version: &str) -> Option<[u64; 3]> {
+        let mut components = [0; 3];
+        let mut parts = version.trim().split('.');
+        for component in &mut components {
+            if let Some(part) = parts.next() {
+                *component = part.parse().ok()?;
+            }
+        }
+
+        parts.next().is_none().then_some(components)
+    }
+
+    Some(parse(a)?.cmp(&parse(b)?))
+}
+
+fn semver_matches(version: &str, req: &str) -> ResolveResult {
+    let version = Version::parse(version)
+        .map_err(|e| ExecutionError::function_error("semver_matches", format!("invalid version '{version}': {e}")))?;
+    let req = VersionReq::parse(req)
+        .map_err(|e| ExecutionError::function_error("semver_matches", format!("invalid version requirement '{req}': {e}")))?;
+
+    Ok(Value::Bool(req.matches(&version)))
+}
+
+fn rust_version_at_most(version: &str, max: &str) -> ResolveResult {
+    compare_rust_versions(version, max)
+        .map(|ordering| Value::Bool(ordering != Ordering::Greater))
+        .ok_or_else(|| ExecutionError::function_error("rust_version_at_most", format!("invalid Rust version '{version}' or '{max}'")))
+}
+
+fn check_license_allowed(license: &str, allowed: &[Value]) -> ResolveResult {
+    let allowed = allowed
+        .iter()
+        .map(|value| match value {
+            Value::String(id) => Ok(id.as_str()),
+            other => Err(ExecutionError::function_error("license_allowed", format!("expected license identifiers, got {other:?}"))),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Value::Bool(license_allowed(license, &allowed)))
+}
+
+fn check_license_compatible(license: &str, project_license: &str) -> ResolveResult {
+    let project = ProjectLicense::parse(project_license).ok_or_else(|| {
+        ExecutionError::function_error("license_compatible", format!("unsupported project license '{project_license}'"))
+    })?;
+
+    Ok(Value::Bool(license_compatible(license, project)))
+}
+
+fn days_since(timestamp: &Value, now: DateTime<FixedOffset>) -> ResolveResult {
+    match timestamp {
+        Value::Timestamp(ts) => Ok(Value::Int(now.signed_duration_since(ts).num_days())),
+        other => Err(ExecutionError::function_error("days_since", format!("expected a timestamp, got '{other:?}'"))),
+    }
+}
+
+fn matches(regex: &str, s: &str) -> ResolveResult {
+    let regex = Regex::new(regex).map_err(|e| ExecutionError::function_error("matches", format!("invalid regex '{regex}': {e}")))?;
+    Ok(Value::Bool(regex.is_match(s)))
+}
+
+fn percentile(metric: &str, percentiles: &HashMap<String, f64>) -> ResolveResult {
+    let Some((_, percentile_metric)) = PERCENTILE_METRICS.iter().find(|(name, _)| *name == metric) else {
+        let supported: Vec<_> = PERCENTILE_METRICS.iter().map(|(name, _)| *name).collect();
+        return Err(ExecutionError::function_error(
+            "percentile",
+            format!("no percentile is known for metric '{metric}', expected one of {}", supported.join(", ")),
+        ));
+    };
+
+    percentiles
+        .get(*percentile_metric)
+        .map(|value| Value::Float(*value))
+        .ok_or_else(|| ExecutionError::function_error("percentile", format!("metric '{metric}' has no value")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cel_interpreter::Program;
+    use chrono::TimeZone;
+
+    fn context() -> Context<'static> {
+        let now = FixedOffset::east_opt(0).unwrap().with_ymd_and_hms(2024, 1, 15, 0, 0, 0).unwrap();
+        let mut percentiles = HashMap::default();
+        let _ = percentiles.insert("usage.downloads_percentile".to_string(), 87.5);
+
+        let mut context = Context::default();
+        add_functions(&mut context, now, percentiles);
+        context.add_variable_from_value("created", Value::Timestamp(now - chrono::Duration::days(10)));
+        context
+    }
+
+    fn compile(expression: &str) -> Program {
+        Program::compile(expression).unwrap()
+    }
+
+    fn eval(expression: &str) -> ResolveResult {
+        compile(expression).execute(&context())
+    }
+
+    #[test]
+    fn test_semver_matches() {
+        assert_eq!(eval("semver_matches('1.2.3', '^1.0')").unwrap(), Value::Bool(true));
+        assert_eq!(eval("semver_matches('2.0.0', '^1.0')").unwrap(), Value::Bool(false));
+        let _ = eval("semver_matches('nope', '^1.0')").unwrap_err();
+        let _ = eval("semver_matches('1.0.0', '!!')").unwrap_err();
+    }
+
+    #[test]
+    fn test_rust_version_at_most() {
+        assert_eq!(eval("rust_version_at_most('1.70', '1.70.0')").unwrap(), Value::Bool(true));
+        assert_eq!(eval("rust_version_at_most('1.65.2', '1.70')").unwrap(), Value::Bool(true));
+        assert_eq!(eval("rust_version_at_most('1.80', '1.70')").unwrap(), Value::Bool(false));
+        let _ = eval("rust_version_at_most('', '1.70')").unwrap_err();
+        let _ = eval("rust_version_at_most('1.2.3.4', '1.70')").unwrap_err();
+    }
+
+    #[test]
+    fn test_license_compatible() {
+        assert_eq!(eval("license_compatible('MIT OR Apache-2.0', 'proprietary')").unwrap(), Value::Bool(true));
+        assert_eq!(eval("license_compatible('GPL-3.0-only', 'Apache-2.0')").unwrap(), Value::Bool(false));
+        let _ = eval("license_compatible('MIT', 'Custom-1.0')").unwrap_err();
+    }
+
+    #[test]
+    fn test_license_allowed() {
+        assert_eq!(eval("license_allowed('MIT OR GPL-3.0', ['MIT', 'Apache-2.0'])").unwrap(), Value::Bool(true));
+        assert_eq!(eval("license_allowed('GPL-3.0 AND MIT', ['MIT'])").unwrap(), Value::Bool(false));
+        assert_eq!(eval("license_allowed('MIT-0', ['MIT'])").unwrap(), Value::Bool(false));
+        let _ = eval("license_allowed('MIT', [1])").unwrap_err();
+    }
+
+    #[test]
+    fn test_days_since() {
+        assert_eq!(eval("days_since(created)").unwrap(), Value::Int(10));
+        let _ = eval("days_since('2024-01-01')").unwrap_err();
+    }
+
+    #[test]
+    fn test_matches() {
+        assert_eq!(eval("matches('^tokio(-.*)?$', 'tokio-util')").unwrap(), Value::Bool(true));
+        assert_eq!(eval("matches('^tokio$', 'tokio-util')").unwrap(), Value::Bool(false));
+        let _ = eval("matches('(', 'x')").unwrap_err();
+    }
+
+    #[test]
+    fn test_percentile() {
+        assert_eq!(eval("percentile('usage.total_downloads')").unwrap(), Value::Float(87.5));
+        let _ = eval("percentile('usage.dependent_crates')").unwrap_err();
+        let err = eval("percentile('crate.name')").unwrap_err();
+        assert!(err.to_string().contains("usage.total_downloads"));
+    }
+}
@@ -0,0 +1,268 @@
+//! Compatibility of the SPDX license expressions of crates with the license of the project depending on them.
+//!
+//! Licenses are sorted into a few families sharing the same obligations, and a built-in matrix tells which
+//! families a project can depend upon given the family of its own license. Rust crates are linked statically,
+//! so the LGPL counts as copyleft for the purpose of the matrix. A license expression is compatible when its
+//! `OR` alternatives leave at least one way to use the crate in which every license is compatible.
+//! Licenses missing from the matrix are never compatible, as nothing is known about them.
+//!
+//! Expressions can also be checked against an explicit list of allowed license identifiers, as cargo-deny does.
+
+/// A family of licenses sharing the same obligations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Family {
+    /// Permissive licenses, compatible with the GPL
+    Permissive,
+
+    /// The Apache license, which is permissive but incompatible with version 2 of the GPL
+    Apache,
+
+    /// The file-level copyleft of the Mozilla Public License
+    Mpl,
+
+    /// Versions 2.0 and 2.1 of the LGPL, which can be turned into version 2 or later of the GPL
+    Lgpl2,
+
+    /// Version 3 of the LGPL
+    Lgpl3,
+
+    /// Version 2 of the GPL, without the option of a later version
+    Gpl2Only,
+
+    /// Version 2 or any later version of the GPL
+    Gpl2OrLater,
+
+    /// Version 3 of the GPL
+    Gpl3,
+
+    /// Version 3 of the Affero GPL
+    Agpl3,
+}
+
+const PERMISSIVE_LICENSES: &[&str] = &[
+    "0BSD",
+    "BSD-1-Clause",
+    "BSD-2-Clause",
+    "BSD-3-Clause",
+    "BSL-1.0",
+    "CC0-1.0",
+    "CDLA-Permissive-2.0",
+    "ISC",
+    "MIT",
+    "MIT-0",
+    "NCSA",
+    "PSF-2.0",
+    "Python-2.0",
+    "Unicode-3.0",
+    "Unicode-DFS-2016",
+    "Unlicense",
+    "WTFPL",
+    "X11",
+    "Zlib",
+];
+
+impl Family {
+    fn of(license: &str) -> Option<Self> {
+        let (base, or_later) = license.strip_suffix('+').or_else(|| license.strip_suffix("-or-later")).map_or_else(
+            || (license.strip_suffix("-only").unwrap_or(license), false),
+            |base| (base, true),
+        );
+
+        match base {
+            "Apache-2.0" => Some(Self::Apache),
+            "MPL-2.0" => Some(Self::Mpl),
+            "LGPL-2.0" | "LGPL-2.1" => Some(Self::Lgpl2),
+            "LGPL-3.0" => Some(Self::Lgpl3),
+            "GPL-2.0" if or_later => Some(Self::Gpl2OrLater),
+            "GPL-2.0" => Some(Self::Gpl2Only),
+            "GPL-3.0" => Some(Self::Gpl3),
+            "AGPL-3.0" => Some(Self::Agpl3),
+            _ if PERMISSIVE_LICENSES.contains(&base) => Some(Self::Permissive),
+            _ => None,
+        }
+    }
+}
+
+/// The license of a project, against which the licenses of its dependencies are checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProjectLicense(Option<Family>);
+
+impl ProjectLicense {
+    /// Parse a single SPDX license identifier, or `proprietary` for closed-source projects.
+    ///
+    /// Returns `None` for licenses missing from the compatibility matrix.
+    #[must_use]
+    pub fn parse(license: &str) -> Option<Self> {
+        let license = license.trim();
+        if license.eq_ignore_ascii_case("proprietary") {
+            return Some(Self(None));
+        }
+
+        Family::of(license).map(|family| Self(Some(family)))
+    }
+
+    /// Whether the project can depend on code under a license of the given family.
+    const fn accepts(self, dependency: Family) -> bool {
+        use Family::{Agpl3, Apache, Gpl2Only, Gpl2OrLater, Gpl3, Lgpl2, Lgpl3, Mpl, Permissive};
+
+        match self.0 {
+            None | Some(Permissive | Apache | Mpl) => matches!(dependency, Permissive | Apache | Mpl),
+            Some(Lgpl2) => matches!(dependency, Permissive | Mpl | Lgpl2),
+            Some(Lgpl3) => matches!(dependency, Permissive | Apache | Mpl | Lgpl2 | Lgpl3),
+            Some(Gpl2Only) => matches!(dependency, Permissive | Mpl | Lgpl2 | Gpl2Only | Gpl2OrLater),
+            Some(Gpl2OrLater) => true,
+            Some(Gpl3 | Agpl3) => !matches!(dependency, Gpl2Only),
+        }
+    }
+}
+
+/// Whether a crate under the given SPDX license expression can be used by a project under `project`.
+///
+/// Unknown licenses and malformed expressions are reported as incompatible.
+#[must_use]
+pub fn license_compatible(expression: &str, project: ProjectLicense) -> bool {
+    satisfiable(expression, &|license| Family::of(license).is_some_and(|family| project.accepts(family)))
+}
+
+/// Whether a crate under the given SPDX license expression can be used under the allowed licenses only.
+///
+/// Identifiers are compared whole, and every license of an `AND` must be allowed. Malformed expressions are
+/// reported as not allowed.
+#[must_use]
+pub fn license_allowed(expression: &str, allowed: &[impl AsRef<str>]) -> bool {
+    satisfiable(expression, &|license| allowed.iter().any(|allowed| allowed.as_ref() == license))
+}
+
+/// Whether the `OR` alternatives of an expression leave a way to use the crate in which every license is accepted.
+fn satisfiable(expression: &str, accept: &dyn Fn(&str) -> bool) -> bool {
+    let spaced = expression.replace('(', " ( ").replace(')', " ) ").replace('/', " OR ");
+    let tokens: Vec<&str> = spaced.split_whitespace().collect();
+
+    let mut parser = Parser { tokens: &tokens, pos: 0, accept };
+    parser.or_expression().is_some_and(|accepted| accepted && parser.pos == tokens.len())
+}
+
+/// Recursive descent evaluation of an SPDX license expression, yielding `None` when it is malformed
+struct Parser<'a> {
+    tokens: &'a [&'a str],
+    pos: usize,
+    accept: &'a dyn Fn(&str) -> bool,
+}
+
+impl Parser<'_> {
+    fn next_is(&self, token: &str) -> bool {
+        self.tokens.get(self.pos).is_some_and(|t| *t == token)
+    }
+
+    fn or_expression(&mut self) -> Option<bool> {
+        let mut compatible = self.and_expression()?;
+        while self.next_is("OR") {
+            self.pos += 1;
+            compatible |= self.and_expression()?;
+        }
+
+        Some(compatible)
+    }
+
+    fn and_expression(&mut self) -> Option<bool> {
+        let mut compatible = self.term()?;
+        while self.next_is("AND") {
+            self.pos += 1;
+            compatible &= self.term()?;
+        }
+
+        Some(compatible)
+    }
+
+    fn term(&mut self) -> Option<bool> {
+        if self.next_is("(") {
+            self.pos += 1;
+            let compatible = self.or_expression()?;
+            if !self.next_is(")") {
+                return None;
+            }
+
+            self.pos += 1;
+            return Some(compatible);
+        }
+
+        let license = *self.tokens.get(self.pos)?;
+        if matches!(license, "AND" | "OR" | "WITH" | ")") {
+            return None;
+        }
+        self.pos += 1;
+
+        // Exceptions only ever grant additional permissions, so the base license decides
+        if self.next_is("WITH") {
+            self.pos += 1;
+            let _exception = self.tokens.get(self.pos)?;
+            self.pos += 1;
+        }
+
+        Some((self.accept)(license))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(license: &str) -> ProjectLicense {
+        ProjectLicense::parse(license).unwrap()
+    }
+
+    #[test]
+    fn test_project_license_parse() {
+        assert!(ProjectLicense::parse("Proprietary").is_some());
+        assert!(ProjectLicense::parse("GPL-3.0-or-later").is_some());
+        assert!(ProjectLicense::parse("MIT OR Apache-2.0").is_none());
+        assert!(ProjectLicense::parse("SSPL-1.0").is_none());
+    }
+
+    #[test]
+    fn test_proprietary_project() {
+        let proprietary = project("proprietary");
+        assert!(license_compatible("MIT OR Apache-2.0", proprietary));
+        assert!(license_compatible("MPL-2.0", proprietary));
+        assert!(!license_compatible("GPL-3.0-only", proprietary));
+        assert!(!license_compatible("LGPL-2.1-or-later", proprietary));
+        assert!(license_compatible("GPL-3.0 OR MIT", proprietary));
+        assert!(!license_compatible("MIT AND GPL-2.0", proprietary));
+        assert!(!license_compatible("LicenseRef-Custom", proprietary));
+        assert!(!license_compatible("", proprietary));
+    }
+
+    #[test]
+    fn test_gpl_projects() {
+        assert!(!license_compatible("Apache-2.0", project("GPL-2.0-only")));
+        assert!(license_compatible("Apache-2.0", project("GPL-2.0-or-later")));
+        assert!(license_compatible("Apache-2.0", project("GPL-3.0")));
+        assert!(license_compatible("LGPL-2.1 AND MIT", project("GPL-2.0")));
+        assert!(!license_compatible("GPL-2.0-only", project("GPL-3.0-only")));
+        assert!(license_compatible("GPL-2.0+", project("GPL-3.0-only")));
+        assert!(license_compatible("AGPL-3.0-only", project("AGPL-3.0-or-later")));
+    }
+
+    #[test]
+    fn test_expression_syntax() {
+        let mit = project("MIT");
+        assert!(license_compatible("(MIT OR Apache-2.0) AND Unicode-3.0", mit));
+        assert!(license_compatible("MIT/Apache-2.0", mit));
+        assert!(license_compatible("Apache-2.0 WITH LLVM-exception", mit));
+        assert!(!license_compatible("(MIT OR GPL-3.0) AND GPL-2.0", mit));
+        assert!(!license_compatible("(MIT", mit));
+        assert!(!license_compatible("MIT OR", mit));
+        assert!(!license_compatible("MIT Apache-2.0", mit));
+    }
+
+    #[test]
+    fn test_license_allowed() {
+        let allowed = ["MIT", "Apache-2.0"];
+        assert!(license_allowed("MIT", &allowed));
+        assert!(license_allowed("MIT OR GPL-3.0", &allowed));
+        assert!(license_allowed("(MIT OR Apache-2.0) AND Apache-2.0 WITH LLVM-exception", &allowed));
+        assert!(!license_allowed("GPL-3.0 AND MIT", &allowed));
+        assert!(!license_allowed("MIT-0", &allowed));
+        assert!(!license_allowed("", &allowed));
+    }
+}
@@ -12,27 +12,43 @@
 //! 2. **Eval**: Each expression has a point value (default 1). All expressions are
 //!    evaluated and a score is computed as `granted_points / total_points * 100`.
 //!    The score is compared against configurable thresholds to determine the risk level.
+//!    An [`Aggregation`] other than the default sum can then replace the score with one
+//!    computed from the per-category scores, for a weakest-link model.
 //!
 //! Each tier contains a list of [`Expression`] objects parsed from user configuration.
+//! Expressions can refer to named snippets of shared expression text as `snippets.<name>`.
 //! Expressions are compiled once at startup for efficiency and validated to ensure
 //! they reference only valid metric names.
 //!
 //! The [`evaluate`] function is the main entry point. For each crate, it:
-//! - Builds a CEL context with all metric values as variables
+//! - Builds a CEL context with all metric values as variables, along with domain
+//!   functions such as `semver_matches` and `percentile`
 //! - Evaluates expressions in order (high-risk-if-any, then eval)
 //! - Returns an [`Appraisal`] with the risk level, score, and reasons
 //!
 //! The CEL context is created once per crate and reused for all expressions,
 //! significantly improving performance when evaluating multiple expressions.
 
+mod aggregation;
 mod appraisal;
+mod category_scores;
 mod evaluator;
 mod expression;
 mod expression_outcome;
+mod functions;
+mod licenses;
+mod references;
 mod risk;
+mod snippets;
 
+pub use aggregation::Aggregation;
 pub use appraisal::Appraisal;
-pub use evaluator::evaluate;
+pub use category_scores::category_scores;
+pub use evaluator::{evaluate, evaluate_to_string};
 pub use expression::Expression;
 pub use expression_outcome::{ExpressionDisposition, ExpressionOutcome};
+pub use functions::compare_rust_versions;
+pub use licenses::ProjectLicense;
+pub use references::{MetricReferences, metric_references};
 pub use risk::Risk;
+pub use snippets::is_valid_snippet_name;
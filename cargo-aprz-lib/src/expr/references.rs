@@ -0,0 +1,65 @@
+//! The metrics an expression refers to.
+//!
+//! Metric names are dotted, so a reference like `crate.keywords.size()` is matched by trimming trailing segments
+//! until a metric name is found. References are found anywhere in the expression text, including string literals,
+//! so that metrics named in calls such as `percentile('usage.total_downloads')` count too.
+
+use crate::metrics::metric_names;
+use std::collections::BTreeSet;
+
+/// The metrics an expression refers to, along with the dotted references that look like metrics but aren't.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct MetricReferences {
+    /// Names of the metrics referred to
+    pub metrics: BTreeSet<&'static str>,
+
+    /// References starting with the prefix of a metric name, such as `activity.`, that don't name a metric
+    pub unknown: BTreeSet<String>,
+}
+
+/// Find the metrics an expression refers to.
+#[must_use]
+pub fn metric_references(expression: &str) -> MetricReferences {
+    let mut references = MetricReferences::default();
+
+    for token in expression.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.')) {
+        let mut name = token;
+        let mut found = false;
+        while !name.is_empty() {
+            if let Some(metric) = metric_names().find(|metric| *metric == name) {
+                let _ = references.metrics.insert(metric);
+                found = true;
+                break;
+            }
+
+            name = name.rsplit_once('.').map_or("", |(head, _)| head);
+        }
+
+        if !found
+            && let Some((prefix, _)) = token.split_once('.')
+            && metric_names().any(|metric| metric.split_once('.').is_some_and(|(metric_prefix, _)| metric_prefix == prefix))
+        {
+            let _ = references.unknown.insert(token.to_string());
+        }
+    }
+
+    references
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metric_references() {
+        let references = metric_references("activity.open_issues < 10 && crate.keywords.size() > 0 && x.y == 'a.b'");
+        assert_eq!(references.metrics.into_iter().collect::<Vec<_>>(), ["activity.open_issues", "crate.keywords"]);
+        assert!(references.unknown.is_empty());
+
+        let references = metric_references("percentile('usage.total_downloads') > 50.0 && activity.open_isues < 5");
+        assert_eq!(references.metrics.into_iter().collect::<Vec<_>>(), ["usage.total_downloads"]);
+        assert_eq!(references.unknown.into_iter().collect::<Vec<_>>(), ["activity.open_isues"]);
+
+        assert_eq!(metric_references("unknown > 5"), MetricReferences::default());
+    }
+}
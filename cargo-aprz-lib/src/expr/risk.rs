@@ -1,11 +1,30 @@
+use serde::{Deserialize, Serialize};
+
 /// The risk level assigned to a crate after policy evaluation.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///
+/// Variants are ordered from least to most risky.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Risk {
     Low,
     Medium,
     High,
 }
 
+impl Risk {
+    /// The risk level of an eval score given the configured thresholds.
+    #[must_use]
+    pub fn from_score(score: f64, medium_risk_threshold: f64, low_risk_threshold: f64) -> Self {
+        if score >= low_risk_threshold {
+            Self::Low
+        } else if score >= medium_risk_threshold {
+            Self::Medium
+        } else {
+            Self::High
+        }
+    }
+}
+
 impl core::fmt::Display for Risk {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
@@ -0,0 +1,136 @@
+//! Named expression fragments shared between expressions.
+//!
+//! An expression refers to a snippet as `snippets.<name>`, which is replaced by the parenthesized text of the
+//! snippet before the expression is compiled. Snippets can refer to other snippets, as long as they don't do so
+//! in a cycle. References within string literals are left alone.
+
+use crate::Result;
+use ohno::bail;
+use std::collections::BTreeMap;
+
+/// Prefix of a snippet reference.
+const SNIPPET_PREFIX: &str = "snippets.";
+
+/// Replace every snippet reference in `expression` with the text of the snippet.
+///
+/// # Errors
+///
+/// Returns an error if a referenced snippet doesn't exist or if snippets refer to each other in a cycle.
+pub fn expand_snippets(expression: &str, snippets: &BTreeMap<String, String>) -> Result<String> {
+    expand(expression, snippets, &mut Vec::new())
+}
+
+fn expand<'a>(expression: &str, snippets: &'a BTreeMap<String, String>, active: &mut Vec<&'a str>) -> Result<String> {
+    let mut result = String::with_capacity(expression.len());
+    let mut quote = None;
+    let mut rest = expression;
+
+    while let Some(c) = rest.chars().next() {
+        if let Some(q) = quote {
+            // Copy escaped characters verbatim so an escaped quote doesn't end the literal
+            let len = if c == '\\' { rest.chars().take(2).map(char::len_utf8).sum() } else { c.len_utf8() };
+            if c == q {
+                quote = None;
+            }
+
+            let (literal, tail) = rest.split_at(len);
+            result.push_str(literal);
+            rest = tail;
+            continue;
+        }
+
+        if c == '\'' || c == '"' {
+            quote = Some(c);
+        } else if let Some(after) = rest.strip_prefix(SNIPPET_PREFIX)
+            && !result.ends_with(|c: char| is_identifier_char(c) || c == '.')
+        {
+            let name_len = after.find(|c: char| !is_identifier_char(c)).unwrap_or(after.len());
+            let (name, tail) = after.split_at(name_len);
+
+            let Some((name, text)) = snippets.get_key_value(name) else {
+                bail!("reference to unknown snippet '{name}'");
+            };
+
+            if active.contains(&name.as_str()) {
+                bail!("snippet '{name}' refers to itself through {}", active.join(" -> "));
+            }
+
+            active.push(name);
+            let expanded = expand(text, snippets, active)?;
+            let _ = active.pop();
+
+            result.push('(');
+            result.push_str(&expanded);
+            result.push(')');
+            rest = tail;
+            continue;
+        }
+
+        result.push(c);
+        rest = rest.split_at(c.len_utf8()).1;
+    }
+
+    Ok(result)
+}
+
+/// Whether a snippet name is usable in a reference.
+#[must_use]
+pub fn is_valid_snippet_name(name: &str) -> bool {
+    !name.is_empty() && !name.starts_with(|c: char| c.is_ascii_digit()) && name.chars().all(is_identifier_char)
+}
+
+const fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snippets(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries.iter().map(|(name, text)| ((*name).to_string(), (*text).to_string())).collect()
+    }
+
+    #[test]
+    fn test_expand_snippets() {
+        let snippets = snippets(&[
+            ("recent", "days_since(stability.version_created_at) < 365"),
+            ("active", "snippets.recent && activity.open_issues < 100"),
+        ]);
+
+        assert_eq!(expand_snippets("crate.name != ''", &snippets).unwrap(), "crate.name != ''");
+        assert_eq!(
+            expand_snippets("!snippets.active || x", &snippets).unwrap(),
+            "!((days_since(stability.version_created_at) < 365) && activity.open_issues < 100) || x"
+        );
+    }
+
+    #[test]
+    fn test_expand_snippets_ignores_string_literals() {
+        let snippets = snippets(&[("a", "true")]);
+        assert_eq!(
+            expand_snippets(r#"x == 'snippets.a' && y == "it\"s snippets.a" && snippets.a"#, &snippets).unwrap(),
+            r#"x == 'snippets.a' && y == "it\"s snippets.a" && (true)"#
+        );
+        assert_eq!(expand_snippets("my_snippets.a", &snippets).unwrap(), "my_snippets.a");
+    }
+
+    #[test]
+    fn test_expand_snippets_errors() {
+        let snippets = snippets(&[("a", "snippets.b"), ("b", "snippets.a")]);
+        let err = expand_snippets("snippets.c", &snippets).unwrap_err();
+        assert!(err.to_string().contains("unknown snippet 'c'"));
+
+        let err = expand_snippets("snippets.a", &snippets).unwrap_err();
+        assert!(err.to_string().contains("snippet 'a' refers to itself through a -> b"));
+    }
+
+    #[test]
+    fn test_is_valid_snippet_name() {
+        assert!(is_valid_snippet_name("actively_maintained"));
+        assert!(is_valid_snippet_name("_x1"));
+        assert!(!is_valid_snippet_name(""));
+        assert!(!is_valid_snippet_name("1x"));
+        assert!(!is_valid_snippet_name("a-b"));
+    }
+}
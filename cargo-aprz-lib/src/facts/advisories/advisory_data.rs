@@ -1,6 +1,10 @@
+use chrono::NaiveDate;
 use rustsec::advisory::{Informational, Severity};
 use serde::{Deserialize, Serialize};
 
+/// Average number of days in a year, used to turn fix ages into years
+const DAYS_PER_YEAR: f64 = 365.25;
+
 /// Advisory counts for vulnerabilities and warnings.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[expect(clippy::struct_field_names, reason = "all fields represent counts, suffix improves clarity")]
@@ -24,6 +28,17 @@ pub struct AdvisoryData {
 
     /// Advisory counts across all versions of the crate (historical).
     pub total: AdvisoryCounts,
+
+    /// Days since a fix was published for the oldest vulnerability still affecting the queried version.
+    #[serde(default)]
+    pub oldest_unadopted_fix_days: u64,
+
+    /// Severity-weighted penalty for the vulnerabilities affecting the queried version that have a fix available.
+    ///
+    /// Each vulnerability contributes its severity weight (1 for low up to 4 for critical), plus that weight again
+    /// for every year its fix has gone unadopted.
+    #[serde(default)]
+    pub unadopted_fix_penalty: f64,
 }
 
 impl AdvisoryCounts {
@@ -58,8 +73,76 @@ impl AdvisoryData {
         self.per_version.count_advisory(advisory);
     }
 
+    /// Account for how long a fix has been available for an advisory affecting the queried version.
+    ///
+    /// An advisory's publication date is used as the date its fix became available, since advisories are
+    /// normally published together with the patched release. Advisories without a patched version or a CVSS
+    /// severity are ignored.
+    pub(super) fn record_unadopted_fix(&mut self, advisory: &rustsec::Advisory, today: NaiveDate) {
+        if advisory.versions.patched().is_empty() || advisory.metadata.informational.is_some() {
+            return;
+        }
+
+        let Some(weight) = advisory.metadata.cvss.as_ref().and_then(|cvss| severity_weight(cvss.severity())) else {
+            return;
+        };
+
+        let Ok(published) = NaiveDate::parse_from_str(advisory.metadata.date.as_str(), "%Y-%m-%d") else {
+            return;
+        };
+
+        self.add_unadopted_fix(weight, u64::try_from((today - published).num_days()).unwrap_or(0));
+    }
+
+    #[expect(clippy::cast_precision_loss, reason = "Fix ages are far below the precision limit")]
+    fn add_unadopted_fix(&mut self, weight: f64, age_days: u64) {
+        self.oldest_unadopted_fix_days = self.oldest_unadopted_fix_days.max(age_days);
+        self.unadopted_fix_penalty += weight * (1.0 + age_days as f64 / DAYS_PER_YEAR);
+    }
+
     /// Count an advisory across all versions (historical).
     pub(super) fn count_advisory_historical(&mut self, advisory: &rustsec::Advisory) {
         self.total.count_advisory(advisory);
     }
 }
+
+/// Weight of a vulnerability in the unadopted fix penalty, or `None` for advisories without a severity.
+const fn severity_weight(severity: Severity) -> Option<f64> {
+    match severity {
+        Severity::None => None,
+        Severity::Low => Some(1.0),
+        Severity::Medium => Some(2.0),
+        Severity::High => Some(3.0),
+        Severity::Critical => Some(4.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_unadopted_fix() {
+        let mut data = AdvisoryData::default();
+
+        // A fresh fix for a medium vulnerability costs just its weight
+        data.add_unadopted_fix(2.0, 0);
+        assert_eq!(data.oldest_unadopted_fix_days, 0);
+        assert!((data.unadopted_fix_penalty - 2.0).abs() < f64::EPSILON);
+
+        // A critical fix left unadopted for two years costs three times its weight
+        data.add_unadopted_fix(4.0, 731);
+        assert_eq!(data.oldest_unadopted_fix_days, 731);
+        assert!((data.unadopted_fix_penalty - 14.0).abs() < 0.01);
+
+        data.add_unadopted_fix(1.0, 10);
+        assert_eq!(data.oldest_unadopted_fix_days, 731);
+    }
+
+    #[test]
+    fn test_severity_weight() {
+        assert_eq!(severity_weight(Severity::None), None);
+        assert_eq!(severity_weight(Severity::Low), Some(1.0));
+        assert_eq!(severity_weight(Severity::Critical), Some(4.0));
+    }
+}
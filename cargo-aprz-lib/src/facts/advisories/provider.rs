@@ -3,7 +3,9 @@ use crate::Result;
 use crate::facts::ProviderResult;
 use crate::facts::cache::{Cache, CacheResult};
 use crate::facts::crate_spec::CrateSpec;
+use crate::facts::path_utils::sanitize_path_component;
 use crate::facts::progress::Progress;
+use chrono::{DateTime, Utc};
 use compact_str::CompactString;
 use core::time::Duration;
 use ohno::IntoAppError;
@@ -12,7 +14,8 @@ use rustsec::{
     repository::git::{DEFAULT_URL, Repository},
 };
 use crate::HashMap;
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 /// Log target for advisories provider
@@ -21,6 +24,10 @@ const LOG_TARGET: &str = "advisories";
 #[derive(Debug)]
 pub struct Provider {
     database: Arc<Database>,
+    synced_at: Option<DateTime<Utc>>,
+
+    /// Clone of the advisory database repository
+    repo_path: PathBuf,
 }
 
 const DATABASE_FETCH_TIMEOUT: Duration = Duration::from_secs(60);
@@ -34,20 +41,50 @@ impl Provider {
         let sync_filename = "last_synced.json";
         let repo_path = cache_dir.join("repo");
 
-        let needs_fetch = matches!(cache.load::<()>(sync_filename), CacheResult::Miss);
-
-        if needs_fetch {
-            download_db(&repo_path, progress.as_ref())
-                .await
-                .into_app_err("downloading the advisory database")?;
-            cache.save(sync_filename, &())?;
-        }
+        // The time of the last sync is recorded in the file, whose age decides when to sync again
+        let synced_at = match cache.load::<DateTime<Utc>>(sync_filename) {
+            CacheResult::Data(synced_at) => Some(synced_at),
+            CacheResult::NoData(_) => None,
+            CacheResult::Miss => {
+                download_db(&repo_path, progress.as_ref())
+                    .await
+                    .into_app_err("downloading the advisory database")?;
+                let synced_at = Utc::now();
+                cache.save(sync_filename, &synced_at)?;
+                Some(synced_at)
+            }
+        };
 
         Ok(Self {
             database: Arc::new(open_db(&repo_path, progress.as_ref()).await.into_app_err("opening the advisory database")?),
+            synced_at,
+            repo_path,
         })
     }
 
+    /// When the advisory database was last synced, if known.
+    #[must_use]
+    pub const fn synced_at(&self) -> Option<DateTime<Utc>> {
+        self.synced_at
+    }
+
+    /// The files of the advisory database describing the advisories filed against a crate, sorted by name.
+    #[must_use]
+    pub fn advisory_files(&self, crate_name: &str) -> Vec<PathBuf> {
+        let dir = self.repo_path.join("crates").join(sanitize_path_component(crate_name));
+        let Ok(entries) = fs::read_dir(&dir) else {
+            return Vec::new();
+        };
+
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+            .collect();
+        files.sort();
+        files
+    }
+
     pub async fn get_advisory_data(
         &self,
         crates: impl IntoIterator<Item = CrateSpec> + Send + 'static,
@@ -78,6 +115,7 @@ where
         ));
     }
 
+    let today = Utc::now().date_naive();
     let crate_count = crate_map.len();
     let mut advisories_checked = 0;
     let mut advisories_matched = 0;
@@ -95,6 +133,7 @@ where
                     data.count_advisory_historical(advisory);
                     if advisory.versions.is_vulnerable(crate_spec.version()) {
                         data.count_advisory_for_version(advisory);
+                        data.record_unadopted_fix(advisory, today);
                     }
                 }
             }
@@ -2,9 +2,13 @@
 //!
 //! [`Cache`] wraps a cache directory and TTL so that callers
 //! don't need to thread those values through every load/save call.
+//!
+//! A read-only cache serves whatever it holds regardless of age, never writes, and reports
+//! missing entries as unavailable data so that providers don't try to fetch them.
 
 use crate::Result;
 use chrono::{DateTime, Utc};
+use core::sync::atomic::{AtomicU64, Ordering};
 use core::time::Duration;
 use ohno::IntoAppError;
 use serde::{Deserialize, Serialize};
@@ -12,6 +16,7 @@ use std::fs;
 use std::fs::File;
 use std::io::{BufReader, BufWriter, Write};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 const LOG_TARGET: &str = "     cache";
 
@@ -45,12 +50,61 @@ enum EnvelopePayload<T> {
     NoData(String),
 }
 
+/// The reason given for data missing from a read-only cache.
+pub const NOT_IN_READ_ONLY_CACHE: &str = "not present in the read-only cache";
+
+/// Shared record of the cache entries served past their TTL by read-only caches.
+#[derive(Debug, Clone, Default)]
+pub struct StaleEntries(Arc<Mutex<Vec<String>>>);
+
+impl StaleEntries {
+    /// Record a cache entry that was served past its TTL.
+    pub fn record(&self, entry: String) {
+        self.0.lock().expect("stale entries lock must not be poisoned").push(entry);
+    }
+
+    /// Returns the recorded entries, sorted.
+    #[must_use]
+    pub fn sorted(&self) -> Vec<String> {
+        let mut entries = self.0.lock().expect("stale entries lock must not be poisoned").clone();
+        entries.sort();
+        entries
+    }
+}
+
+/// Number of cache lookups that were served from the cache, and that missed it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl CacheStats {
+    /// Total number of lookups.
+    #[must_use]
+    pub const fn lookups(&self) -> u64 {
+        self.hits + self.misses
+    }
+}
+
+#[derive(Debug, Default)]
+struct CacheCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
 /// A TTL-aware, directory-backed JSON cache.
 #[derive(Debug, Clone)]
 pub struct Cache {
     dir: PathBuf,
     ttl: Duration,
     ignore: bool,
+
+    /// Set for read-only caches, where expired entries are served and recorded here
+    read_only: Option<StaleEntries>,
+
+    /// Shared between clones, so the lookups of all users of the cache are counted together
+    counters: Arc<CacheCounters>,
 }
 
 impl Cache {
@@ -61,18 +115,59 @@ impl Cache {
             dir: cache_dir.into(),
             ttl: cache_ttl,
             ignore: ignore_cache,
+            read_only: None,
+            counters: Arc::default(),
         }
     }
 
+    /// Make the cache read-only, recording the entries served past their TTL in `stale`.
+    #[must_use]
+    pub fn read_only(mut self, stale: &StaleEntries) -> Self {
+        self.read_only = Some(stale.clone());
+        self
+    }
+
     /// Returns the cache directory.
     #[must_use]
     pub fn dir(&self) -> &Path {
         &self.dir
     }
 
+    /// Returns the path of a cache entry by filename, if the cache holds one, whatever its age.
+    #[must_use]
+    pub fn entry_path(&self, filename: &str) -> Option<PathBuf> {
+        let path = self.dir.join(filename);
+        path.is_file().then_some(path)
+    }
+
+    /// Returns the number of lookups served from the cache and missing it so far.
+    #[must_use]
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.counters.hits.load(Ordering::Relaxed),
+            misses: self.counters.misses.load(Ordering::Relaxed),
+        }
+    }
+
     /// Load a cache entry by filename (relative to the cache directory).
     #[must_use]
     pub fn load<T>(&self, filename: &str) -> CacheResult<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        match self.lookup(filename) {
+            CacheResult::Miss => {
+                let _ = self.counters.misses.fetch_add(1, Ordering::Relaxed);
+                self.miss()
+            }
+            result => {
+                let _ = self.counters.hits.fetch_add(1, Ordering::Relaxed);
+                result
+            }
+        }
+    }
+
+    fn lookup<T>(&self, filename: &str) -> CacheResult<T>
     where
         T: for<'de> Deserialize<'de>,
     {
@@ -113,7 +208,11 @@ impl Cache {
                     age_duration.as_secs_f64() / 86400.0,
                     self.ttl.as_secs_f64() / 86400.0
                 );
-                return CacheResult::Miss;
+
+                let Some(stale) = &self.read_only else {
+                    return CacheResult::Miss;
+                };
+                stale.record(path.display().to_string());
             }
 
             log::debug!(target: LOG_TARGET, "Cache hit for {filename} (age: {:.1} days)", age_duration.as_secs_f64() / 86400.0);
@@ -125,6 +224,15 @@ impl Cache {
         }
     }
 
+    /// The result of a cache miss, which read-only caches report as unavailable data.
+    fn miss<T>(&self) -> CacheResult<T> {
+        if self.read_only.is_some() {
+            CacheResult::NoData(NOT_IN_READ_ONLY_CACHE.to_string())
+        } else {
+            CacheResult::Miss
+        }
+    }
+
     /// Save data to the cache under the given filename.
     pub fn save<T>(&self, filename: &str, data: &T) -> Result<()>
     where
@@ -147,8 +255,12 @@ impl Cache {
         self.write_envelope(filename, &envelope)
     }
 
-    /// Write an envelope to disk.
+    /// Write an envelope to disk, unless the cache is read-only.
     fn write_envelope<T: Serialize>(&self, filename: &str, envelope: &Envelope<T>) -> Result<()> {
+        if self.read_only.is_some() {
+            return Ok(());
+        }
+
         let path = self.dir.join(filename);
 
         if let Some(parent) = path.parent() {
@@ -253,6 +365,46 @@ mod tests {
         assert!(matches!(cache.load::<TestData>("old.json"), CacheResult::Miss));
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn read_only_serves_expired_entry_as_stale() {
+        let tmp = tempfile::tempdir().unwrap();
+        let envelope = Envelope {
+            timestamp: Utc::now() - chrono::Duration::hours(2),
+            payload: EnvelopePayload::Data(TestData { name: "old".to_string(), value: 1 }),
+        };
+        let file = File::create(tmp.path().join("old.json")).unwrap();
+        serde_json::to_writer(file, &envelope).unwrap();
+
+        let stale = StaleEntries::default();
+        let cache = make_cache(tmp.path(), 3600).read_only(&stale);
+        match cache.load::<TestData>("old.json") {
+            CacheResult::Data(d) => assert_eq!(d.name, "old"),
+            other => panic!("expected Data, got {other:?}"),
+        }
+
+        let entries = stale.sorted();
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].ends_with("old.json"));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn read_only_reports_missing_entry_as_no_data_and_never_writes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let stale = StaleEntries::default();
+        let cache = make_cache(tmp.path(), 3600).read_only(&stale);
+
+        match cache.load::<TestData>("missing.json") {
+            CacheResult::NoData(reason) => assert_eq!(reason, NOT_IN_READ_ONLY_CACHE),
+            other => panic!("expected NoData, got {other:?}"),
+        }
+
+        cache.save("item.json", &TestData { name: "new".to_string(), value: 1 }).unwrap();
+        assert!(!tmp.path().join("item.json").exists());
+        assert!(stale.sorted().is_empty());
+    }
+
     #[test]
     #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
     fn load_future_timestamp_treated_as_fresh() {
@@ -358,4 +510,20 @@ mod tests {
         let cache = make_cache(tmp.path(), ttl_seconds.cast_unsigned());
         assert!(matches!(cache.load::<TestData>("boundary.json"), CacheResult::Miss));
     }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn stats_count_hits_and_misses_across_clones() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = make_cache(tmp.path(), 3600);
+        let clone = cache.clone();
+
+        cache.save_no_data("gone.json", "missing").unwrap();
+        let _ = cache.load::<TestData>("gone.json");
+        let _ = clone.load::<TestData>("absent.json");
+        let _ = clone.load::<TestData>("absent.json");
+
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 2 });
+        assert_eq!(cache.stats().lookups(), 3);
+    }
 }
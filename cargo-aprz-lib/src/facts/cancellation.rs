@@ -0,0 +1,105 @@
+//! Cancellation of the collection of facts, such as on Ctrl-C or once a deadline passes.
+//!
+//! Providers check the cancellation before starting each unit of work, and give up on the work that hasn't started
+//! yet, leaving [`ProviderResult::Cancelled`](super::ProviderResult::Cancelled) in place of its data. Work already
+//! under way is left to finish, so caches never hold partial entries.
+
+use compact_str::CompactString;
+use core::time::Duration;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// A cancellation shared by everything collecting facts for a run, which only ever goes from active to cancelled.
+#[derive(Debug, Clone)]
+pub struct Cancellation {
+    /// Why the run was cancelled, once it is
+    reason: Arc<watch::Sender<Option<CompactString>>>,
+}
+
+impl Default for Cancellation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cancellation {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            reason: Arc::new(watch::Sender::new(None)),
+        }
+    }
+
+    /// Cancel the run, describing why, such as `interrupted`. Only the first reason is kept.
+    pub fn cancel(&self, reason: impl Into<CompactString>) {
+        let reason = reason.into();
+        let _ = self.reason.send_if_modified(|current| {
+            if current.is_some() {
+                return false;
+            }
+
+            *current = Some(reason);
+            true
+        });
+    }
+
+    /// Cancel the run once `duration` elapsed, unless it's cancelled otherwise first.
+    pub fn cancel_after(&self, duration: Duration, reason: impl Into<CompactString>) -> tokio::task::JoinHandle<()> {
+        let this = self.clone();
+        let reason = reason.into();
+        tokio::spawn(async move {
+            tokio::select! {
+                () = tokio::time::sleep(duration) => this.cancel(reason),
+                () = this.cancelled() => {}
+            }
+        })
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.reason.borrow().is_some()
+    }
+
+    /// Why the run was cancelled, if it was.
+    #[must_use]
+    pub fn reason(&self) -> Option<CompactString> {
+        self.reason.borrow().clone()
+    }
+
+    /// Wait until the run is cancelled.
+    pub async fn cancelled(&self) {
+        let mut receiver = self.reason.subscribe();
+
+        // The sender lives as long as `self`, so waiting can't fail
+        let _ = receiver.wait_for(Option::is_some).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_keeps_first_reason() {
+        let cancellation = Cancellation::new();
+        assert!(!cancellation.is_cancelled());
+        assert_eq!(cancellation.reason(), None);
+
+        let clone = cancellation.clone();
+        clone.cancel("interrupted");
+        cancellation.cancel("past the deadline");
+        assert!(cancellation.is_cancelled());
+        assert_eq!(cancellation.reason().as_deref(), Some("interrupted"));
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore = "Miri cannot call CreateIoCompletionPort on Windows")]
+    async fn test_cancel_after() {
+        let cancellation = Cancellation::new();
+        cancellation.cancel_after(Duration::from_millis(10), "past the deadline").await.unwrap();
+        assert_eq!(cancellation.reason().as_deref(), Some("past the deadline"));
+
+        // Waiting on a cancelled run returns right away
+        cancellation.cancelled().await;
+    }
+}
@@ -2,6 +2,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
+#[expect(clippy::struct_excessive_bools, reason = "The flags are facts backing boolean metrics")]
 pub struct CodebaseData {
     pub source_files_analyzed: u64,
     pub source_files_with_errors: u64,
@@ -10,15 +11,27 @@ pub struct CodebaseData {
     pub comment_lines: u64,
     pub unsafe_count: u64,
     pub example_count: u64,
+    pub doc_test_count: u64,
     pub transitive_dependencies: u64,
     pub workflows_detected: bool,
     pub miri_detected: bool,
     pub clippy_detected: bool,
+    pub ci_platform_count: u64,
+    pub ci_linux: bool,
+    pub ci_windows: bool,
+    pub ci_macos: bool,
+    pub ci_arm: bool,
+    pub ci_wasm: bool,
+    pub has_contributing: bool,
+    pub has_code_of_conduct: bool,
+    pub has_governance: bool,
     pub contributors: u64,
     pub commits_last_90_days: u64,
     pub commits_last_180_days: u64,
     pub commits_last_365_days: u64,
     pub commit_count: u64,
+    pub signed_commits_ratio: Option<f64>,
+    pub latest_tag_signed: Option<bool>,
     pub first_commit_at: DateTime<Utc>,
     pub last_commit_at: DateTime<Utc>,
 }
@@ -1,5 +1,5 @@
 use super::provider::LOG_TARGET;
-use crate::Result;
+use crate::{HashMap, Result};
 use chrono::{DateTime, Utc};
 use core::time::Duration;
 use ohno::{IntoAppError, bail};
@@ -213,6 +213,130 @@ pub async fn get_commit_stats(repo_path: &Path, day_windows: &[i64]) -> Result<C
     })
 }
 
+/// Number of recent commits inspected when computing the share of signed commits.
+const SIGNATURE_SAMPLE_SIZE: usize = 100;
+
+/// Compute the fraction of the most recent commits that carry a signature, from 0 to 1.
+///
+/// Signatures are detected from the `gpgsig` header of the raw commit objects rather than verified,
+/// which covers GPG, SSH, X.509, and Sigstore (gitsign) signatures without needing any keys.
+/// Returns `None` for a repository without commits.
+pub async fn get_signed_commit_ratio(repo_path: &Path) -> Result<Option<f64>> {
+    let path_str = path_str(repo_path)?;
+    let max_count = format!("--max-count={SIGNATURE_SAMPLE_SIZE}");
+    let output = run_git_with_timeout(&["-C", path_str, "log", &max_count, "--format=raw"]).await?;
+    check_git_output(&output, "git log")?;
+
+    Ok(signed_commit_ratio(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Compute the fraction of signed commits in `git log --format=raw` output.
+#[expect(clippy::cast_precision_loss, reason = "commit counts are bounded by SIGNATURE_SAMPLE_SIZE")]
+fn signed_commit_ratio(raw_log: &str) -> Option<f64> {
+    let mut commits = 0u64;
+    let mut signed = 0u64;
+
+    // Headers start at column 0 while commit messages are indented, so a header match is unambiguous
+    for line in raw_log.lines() {
+        if line.starts_with("commit ") {
+            commits += 1;
+        } else if line.starts_with("gpgsig ") || line.starts_with("gpgsig-sha256 ") {
+            signed += 1;
+        }
+    }
+
+    (commits > 0).then(|| signed as f64 / commits as f64)
+}
+
+/// Check whether the most recently created tag of the repository carries a signature.
+///
+/// Repositories are cloned without tags, so the tag objects are fetched first, without any file contents. As
+/// for commits, the signature is detected rather than verified. Lightweight tags can't be signed. Returns `None`
+/// for a repository without tags.
+pub async fn get_latest_tag_signed(repo_path: &Path) -> Result<Option<bool>> {
+    let path_str = path_str(repo_path)?;
+    let output = run_git_with_timeout(&[
+        "-C",
+        path_str,
+        "fetch",
+        "--quiet",
+        "--no-tags",
+        "--filter=blob:none",
+        "origin",
+        "+refs/tags/*:refs/tags/*",
+    ])
+    .await?;
+    check_git_output(&output, "git fetch")?;
+
+    let output = run_git_with_timeout(&[
+        "-C",
+        path_str,
+        "for-each-ref",
+        "--sort=-creatordate",
+        "--count=1",
+        "--format=%(objecttype)%00%(contents:signature)",
+        "refs/tags",
+    ])
+    .await?;
+    check_git_output(&output, "git for-each-ref")?;
+
+    Ok(latest_tag_signed(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Tell whether the tag described by `git for-each-ref --format=%(objecttype)%00%(contents:signature)` is signed.
+fn latest_tag_signed(output: &str) -> Option<bool> {
+    let (object_type, signature) = output.split_once('\0')?;
+    Some(object_type == "tag" && !signature.trim().is_empty())
+}
+
+/// List the files in the repository tree at a commit, mapping each path to its git blob ID.
+///
+/// Only tree objects are read, so this works in the blobless clones used for analysis.
+pub async fn list_tree_blobs(repo_path: &Path, commit: &str) -> Result<HashMap<String, String>> {
+    let path_str = path_str(repo_path)?;
+    let output = run_git_with_timeout(&["-C", path_str, "ls-tree", "-r", "-z", "--full-tree", commit]).await?;
+    check_git_output(&output, "git ls-tree")?;
+
+    Ok(parse_tree_blobs(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Read files at a revision of a remote repository, without keeping a clone of it.
+///
+/// Only the commit the revision names is fetched, into a scratch repository removed once the files are read.
+pub async fn read_remote_files(repo_url: &str, rev: &str, paths: &[&str]) -> Result<Vec<Vec<u8>>> {
+    let scratch = tempfile::tempdir().into_app_err("creating scratch repository directory")?;
+    let scratch_path = path_str(scratch.path())?;
+
+    let output = run_git_with_timeout(&["init", "--quiet", "--bare", scratch_path]).await?;
+    check_git_output(&output, "git init")?;
+
+    let output = run_git_with_timeout(&["-C", scratch_path, "fetch", "--quiet", "--depth", "1", "--", repo_url, rev]).await?;
+    check_git_output(&output, &format!("fetching '{rev}' from '{repo_url}'"))?;
+
+    let mut files = Vec::with_capacity(paths.len());
+    for path in paths {
+        let output = run_git_with_timeout(&["-C", scratch_path, "show", &format!("FETCH_HEAD:{path}")]).await?;
+        check_git_output(&output, &format!("reading '{path}' at '{rev}' of '{repo_url}'"))?;
+        files.push(output.stdout);
+    }
+
+    Ok(files)
+}
+
+/// Parse `git ls-tree -r -z` output into a map from path to blob ID.
+fn parse_tree_blobs(output: &str) -> HashMap<String, String> {
+    // Each record is `<mode> SP <type> SP <object> TAB <path>`
+    output
+        .split('\0')
+        .filter_map(|record| {
+            let (info, path) = record.split_once('\t')?;
+            let mut fields = info.split(' ');
+            let (_mode, kind, id) = (fields.next()?, fields.next()?, fields.next()?);
+            (kind == "blob").then(|| (path.to_string(), id.to_string()))
+        })
+        .collect()
+}
+
 async fn run_git_with_timeout(args: &[&str]) -> Result<std::process::Output> {
     let child = Command::new("git")
         .args(args)
@@ -475,6 +599,58 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_signed_commit_ratio() {
+        let raw_log = "\
+commit 1111111111111111111111111111111111111111
+tree 2222222222222222222222222222222222222222
+author A <a@example.com> 1700000000 +0000
+committer A <a@example.com> 1700000000 +0000
+gpgsig -----BEGIN SSH SIGNATURE-----
+ U1NIU0lH
+ -----END SSH SIGNATURE-----
+
+    signed commit
+    gpgsig in a message does not count
+
+commit 3333333333333333333333333333333333333333
+tree 2222222222222222222222222222222222222222
+author A <a@example.com> 1700000000 +0000
+committer A <a@example.com> 1700000000 +0000
+
+    unsigned commit
+";
+        assert_eq!(signed_commit_ratio(raw_log), Some(0.5));
+        assert_eq!(signed_commit_ratio(""), None);
+    }
+
+    #[test]
+    fn test_latest_tag_signed() {
+        let signed = "tag\0-----BEGIN SSH SIGNATURE-----\nU1NIU0lH\n-----END SSH SIGNATURE-----\n\n";
+        assert_eq!(latest_tag_signed(signed), Some(true));
+        assert_eq!(latest_tag_signed("tag\0\n"), Some(false));
+        assert_eq!(latest_tag_signed("commit\0\n"), Some(false));
+        assert_eq!(latest_tag_signed(""), None);
+    }
+
+    #[test]
+    fn test_parse_tree_blobs() {
+        let output = "100644 blob ce013625030ba8dba906f756967f9e9ca394464a\tREADME.md\0\
+                      160000 commit 1111111111111111111111111111111111111111\tvendor/sub\0\
+                      100755 blob e69de29bb2d1d6434b8b29ae775ad8c2e48c5391\tsrc/my file.rs\0";
+        let blobs = parse_tree_blobs(output);
+        assert_eq!(blobs.len(), 2);
+        assert_eq!(blobs["README.md"], "ce013625030ba8dba906f756967f9e9ca394464a");
+        assert_eq!(blobs["src/my file.rs"], "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore = "Miri cannot run external commands")]
+    async fn test_get_signed_commit_ratio_unsigned() {
+        let (_tmp, repo_path) = create_test_repo();
+        assert_eq!(get_signed_commit_ratio(&repo_path).await.unwrap(), Some(0.0));
+    }
+
     #[tokio::test]
     #[cfg_attr(miri, ignore = "Miri cannot run external commands")]
     async fn test_get_repo_clone_from_bare() {
@@ -492,6 +668,22 @@ mod tests {
         assert!(matches!(status, RepoStatus::Ok));
     }
 
+    #[tokio::test]
+    #[cfg_attr(miri, ignore = "Miri cannot run external commands")]
+    async fn test_get_latest_tag_signed_fetches_tags() {
+        let (tmp, bare_path) = create_bare_repo_with_commit();
+        let clone_path = tmp.path().join("clone");
+        let bare_url = Url::from_file_path(&bare_path).unwrap();
+        let _ = get_repo(&clone_path, &bare_url).await.unwrap();
+        assert_eq!(get_latest_tag_signed(&clone_path).await.unwrap(), None);
+
+        let _ = std::process::Command::new("git")
+            .args(["-C", bare_path.to_str().unwrap(), "tag", "v1.0.0"])
+            .output()
+            .unwrap();
+        assert_eq!(get_latest_tag_signed(&clone_path).await.unwrap(), Some(false));
+    }
+
     #[tokio::test]
     #[cfg_attr(miri, ignore = "Miri cannot run external commands")]
     async fn test_get_repo_reclones_when_git_dir_missing() {
@@ -1,4 +1,4 @@
-//! Detector for CI tool usage in GitHub Actions CI workflows.
+//! Detector for CI tool and platform usage in GitHub Actions CI workflows.
 
 use super::provider::LOG_TARGET;
 use crate::Result;
@@ -8,13 +8,43 @@ use std::io::{BufRead, BufReader};
 use std::path::Path;
 
 #[derive(Debug, Default, Clone)]
+#[expect(clippy::struct_excessive_bools, reason = "Tools and CI platforms are detected independently of each other")]
 pub struct GitHubWorkflowInfo {
     pub workflows_detected: bool,
     pub clippy_detected: bool,
     pub miri_detected: bool,
+    pub linux_detected: bool,
+    pub windows_detected: bool,
+    pub macos_detected: bool,
+    pub arm_detected: bool,
+    pub wasm_detected: bool,
 }
 
-/// Detect if Miri and Clippy are mentioned in GitHub Actions CI
+impl GitHubWorkflowInfo {
+    /// Number of distinct platforms exercised in CI
+    #[must_use]
+    pub fn platform_count(&self) -> u64 {
+        [
+            self.linux_detected,
+            self.windows_detected,
+            self.macos_detected,
+            self.arm_detected,
+            self.wasm_detected,
+        ]
+        .into_iter()
+        .filter(|&detected| detected)
+        .count() as u64
+    }
+}
+
+// Runner labels and target triple fragments identifying each platform
+const LINUX_PATTERNS: &[&str] = &["ubuntu", "linux"];
+const WINDOWS_PATTERNS: &[&str] = &["windows"];
+const MACOS_PATTERNS: &[&str] = &["macos", "apple-darwin"];
+const ARM_PATTERNS: &[&str] = &["aarch64", "arm64", "armv7", "-arm"];
+const WASM_PATTERNS: &[&str] = &["wasm32"];
+
+/// Detect if Miri and Clippy are mentioned in GitHub Actions CI, and which platforms the workflows run on or target
 pub fn sniff_github_workflows(repo_path: impl AsRef<Path>) -> Result<GitHubWorkflowInfo> {
     const MAX_WORKFLOW_FILES: usize = 100;
 
@@ -69,10 +99,12 @@ pub fn sniff_github_workflows(repo_path: impl AsRef<Path>) -> Result<GitHubWorkf
                 usage.clippy_detected = true;
             }
 
-            if usage.miri_detected && usage.clippy_detected {
-                // early exit...
-                return Ok(usage);
-            }
+            let mentions = |patterns: &[&str]| patterns.iter().any(|pattern| lower.contains(pattern));
+            usage.linux_detected |= mentions(LINUX_PATTERNS);
+            usage.windows_detected |= mentions(WINDOWS_PATTERNS);
+            usage.macos_detected |= mentions(MACOS_PATTERNS);
+            usage.arm_detected |= mentions(ARM_PATTERNS);
+            usage.wasm_detected |= mentions(WASM_PATTERNS);
         }
     }
 
@@ -277,12 +309,68 @@ steps:
         assert!(!result.miri_detected);
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_workflows_with_platform_matrix() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let workflows_dir = temp_dir.path().join(".github").join("workflows");
+        fs::create_dir_all(&workflows_dir).unwrap();
+
+        fs::write(
+            workflows_dir.join("ci.yml"),
+            "
+name: CI
+on: [push]
+jobs:
+  test:
+    strategy:
+      matrix:
+        os: [ubuntu-latest, windows-latest, macos-latest]
+    runs-on: ${{ matrix.os }}
+    steps:
+      - run: cargo test
+  wasm:
+    runs-on: ubuntu-latest
+    steps:
+      - run: cargo build --target wasm32-unknown-unknown
+",
+        )
+        .unwrap();
+
+        let result = sniff_github_workflows(temp_dir.path()).unwrap();
+
+        assert!(result.linux_detected);
+        assert!(result.windows_detected);
+        assert!(result.macos_detected);
+        assert!(!result.arm_detected);
+        assert!(result.wasm_detected);
+        assert_eq!(result.platform_count(), 4);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_workflows_with_arm_runner() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let workflows_dir = temp_dir.path().join(".github").join("workflows");
+        fs::create_dir_all(&workflows_dir).unwrap();
+
+        fs::write(workflows_dir.join("ci.yml"), "runs-on: ubuntu-24.04-arm").unwrap();
+
+        let result = sniff_github_workflows(temp_dir.path()).unwrap();
+
+        assert!(result.linux_detected);
+        assert!(result.arm_detected);
+        assert!(!result.windows_detected);
+        assert_eq!(result.platform_count(), 2);
+    }
+
     #[test]
     fn test_github_workflow_info_default() {
         let info = GitHubWorkflowInfo::default();
         assert!(!info.workflows_detected);
         assert!(!info.clippy_detected);
         assert!(!info.miri_detected);
+        assert_eq!(info.platform_count(), 0);
     }
 
     #[test]
@@ -291,6 +379,7 @@ steps:
             workflows_detected: true,
             clippy_detected: true,
             miri_detected: false,
+            ..GitHubWorkflowInfo::default()
         };
 
         let info2 = info1.clone();
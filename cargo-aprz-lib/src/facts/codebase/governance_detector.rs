@@ -0,0 +1,88 @@
+//! Detector for community governance files in a repository.
+
+use std::fs;
+use std::path::Path;
+
+/// Directories searched for governance files, relative to the repository root, mirroring the
+/// locations GitHub recognizes for community health files.
+const SEARCH_DIRS: [&str; 3] = ["", ".github", "docs"];
+
+#[derive(Debug, Default, Clone)]
+pub struct GovernanceInfo {
+    pub has_contributing: bool,
+    pub has_code_of_conduct: bool,
+    pub has_governance: bool,
+}
+
+/// Detect `CONTRIBUTING`, `CODE_OF_CONDUCT`, and `GOVERNANCE` files in a repository.
+///
+/// File names are matched case-insensitively and with any extension, so `CONTRIBUTING.md`,
+/// `contributing.rst`, and `CONTRIBUTING` are all recognized.
+pub fn sniff_governance_files(repo_path: impl AsRef<Path>) -> GovernanceInfo {
+    let mut info = GovernanceInfo::default();
+
+    for dir in SEARCH_DIRS {
+        let Ok(entries) = fs::read_dir(repo_path.as_ref().join(dir)) else {
+            continue;
+        };
+
+        for entry in entries.filter_map(Result::ok) {
+            if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                continue;
+            }
+
+            let name = entry.file_name().to_string_lossy().to_uppercase();
+            let stem = name.split_once('.').map_or(name.as_str(), |(stem, _)| stem);
+            match stem {
+                "CONTRIBUTING" => info.has_contributing = true,
+                "CODE_OF_CONDUCT" | "CODE-OF-CONDUCT" => info.has_code_of_conduct = true,
+                "GOVERNANCE" => info.has_governance = true,
+                _ => {}
+            }
+        }
+    }
+
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_no_governance_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::write(tmp.path().join("README.md"), "").unwrap();
+
+        let info = sniff_governance_files(tmp.path());
+        assert!(!info.has_contributing);
+        assert!(!info.has_code_of_conduct);
+        assert!(!info.has_governance);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_governance_files_in_standard_locations() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::write(tmp.path().join("contributing.md"), "").unwrap();
+        fs::create_dir(tmp.path().join(".github")).unwrap();
+        fs::write(tmp.path().join(".github").join("CODE_OF_CONDUCT.md"), "").unwrap();
+        fs::create_dir(tmp.path().join("docs")).unwrap();
+        fs::write(tmp.path().join("docs").join("GOVERNANCE"), "").unwrap();
+
+        let info = sniff_governance_files(tmp.path());
+        assert!(info.has_contributing);
+        assert!(info.has_code_of_conduct);
+        assert!(info.has_governance);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_directories_are_ignored() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::create_dir(tmp.path().join("governance")).unwrap();
+
+        assert!(!sniff_governance_files(tmp.path()).has_governance);
+    }
+}
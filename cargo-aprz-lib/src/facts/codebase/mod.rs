@@ -1,8 +1,10 @@
 mod codebase_data;
 mod git;
 mod github_workflow_analyzer;
+mod governance_detector;
 mod provider;
 mod source_file_analyzer;
 
 pub use codebase_data::CodebaseData;
+pub use git::read_remote_files;
 pub use provider::Provider;
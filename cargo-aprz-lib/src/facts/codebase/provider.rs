@@ -1,9 +1,11 @@
 use super::{CodebaseData, git, source_file_analyzer};
 use crate::Result;
-use crate::facts::ProviderResult;
+use crate::facts::{Cancellation, ProviderResult};
 use crate::facts::cache::{Cache, CacheResult};
 use crate::facts::codebase::github_workflow_analyzer::{GitHubWorkflowInfo, sniff_github_workflows};
+use crate::facts::codebase::governance_detector::{GovernanceInfo, sniff_governance_files};
 use crate::facts::crate_spec::{self, CrateSpec};
+use crate::facts::local_sources::LocalSources;
 use crate::facts::path_utils::sanitize_path_component;
 use crate::facts::repo_spec::RepoSpec;
 use crate::facts::request_tracker::{RequestTracker, TrackedTopic};
@@ -28,6 +30,8 @@ const MAX_CONCURRENT_REQUESTS: usize = 5;
 pub struct Provider {
     cache: Cache,
     throttler: Arc<Throttler>,
+    local_sources: Option<Arc<LocalSources>>,
+    cancellation: Cancellation,
 }
 
 const METADATA_TIMEOUT: Duration = Duration::from_mins(5);
@@ -38,11 +42,14 @@ const GIT_REPO_TIMEOUT: Duration = Duration::from_mins(5);
 struct RepoData {
     metadata: Arc<Metadata>,
     workflows: GitHubWorkflowInfo,
+    governance: GovernanceInfo,
     contributor_count: u64,
     commits_last_90_days: u64,
     commits_last_180_days: u64,
     commits_last_365_days: u64,
     commit_count: u64,
+    signed_commits_ratio: Option<f64>,
+    latest_tag_signed: Option<bool>,
     first_commit_at: DateTime<Utc>,
     last_commit_at: DateTime<Utc>,
 }
@@ -53,9 +60,31 @@ impl Provider {
         Self {
             cache,
             throttler: Throttler::new(MAX_CONCURRENT_REQUESTS),
+            local_sources: None,
+            cancellation: Cancellation::new(),
         }
     }
 
+    /// Analyze the sources of crates found unpacked on disk when their repository can't be synced.
+    #[must_use]
+    pub fn with_local_sources(mut self, local_sources: Arc<LocalSources>) -> Self {
+        self.local_sources = Some(local_sources);
+        self
+    }
+
+    /// Stop starting repository syncs once `cancellation` is cancelled.
+    #[must_use]
+    pub fn with_cancellation(mut self, cancellation: Cancellation) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// The cached document holding the analysis of a crate's codebase, if any.
+    #[must_use]
+    pub fn cached_document(&self, crate_spec: &CrateSpec) -> Option<PathBuf> {
+        self.cache.entry_path(&Self::get_data_filename(crate_spec.name(), crate_spec.repo_spec()?))
+    }
+
     pub async fn get_codebase_data(
         &self,
         crates: impl IntoIterator<Item = CrateSpec> + Send + 'static,
@@ -129,6 +158,25 @@ impl Provider {
             })
     }
 
+    /// List the files of a previously synced repository at a given commit, mapping each path to its git blob ID.
+    ///
+    /// Returns `None` if the repository isn't available locally or the commit can't be read from it.
+    pub async fn get_tree_blobs(&self, repo_spec: &RepoSpec, commit: &str) -> Option<HashMap<String, String>> {
+        let repo_path = self.get_repo_cache_path(repo_spec);
+        if !repo_path.join(".git").exists() {
+            return None;
+        }
+
+        let _permit = self.throttler.acquire().await;
+        match git::list_tree_blobs(&repo_path, commit).await {
+            Ok(blobs) => Some(blobs),
+            Err(e) => {
+                log::debug!(target: LOG_TARGET, "Could not list files of commit {commit} in repository '{repo_spec}': {e:#}");
+                None
+            }
+        }
+    }
+
     /// Fetch repository data and analyze all its crates, writing cache files per-crate.
     async fn fetch_and_analyze_repo(
         self,
@@ -136,17 +184,28 @@ impl Provider {
         crates: Vec<CrateSpec>,
         tracker: RequestTracker,
     ) -> Vec<(CrateSpec, ProviderResult<CodebaseData>)> {
-        let _permit = self.throttler.acquire().await;
+        let Some(_permit) = self.throttler.acquire_unless_cancelled(&self.cancellation).await else {
+            tracker.complete_request(TrackedTopic::Codebase);
+            return crates.into_iter().map(|crate_spec| (crate_spec, ProviderResult::Cancelled)).collect();
+        };
+
         // Sync the git repo first — failures here are transient (network) and should not be cached
         let repo_path = self.get_repo_cache_path(&repo_spec);
         match Self::sync_repo(&repo_path, &repo_spec).await {
             Err(e) => {
-                tracker.complete_request(TrackedTopic::Codebase);
+                // Crates whose sources are on disk can still be analyzed, which is all locked-down environments get
                 let error = Arc::new(e);
-                return crates
-                    .into_iter()
-                    .map(|crate_spec| (crate_spec, ProviderResult::Error(Arc::clone(&error))))
-                    .collect();
+                let provider = &self;
+                let results = join_all(crates.into_iter().map(|crate_spec| {
+                    let error = Arc::clone(&error);
+                    async move {
+                        let result = provider.analyze_local_crate(&crate_spec).await.unwrap_or(ProviderResult::Error(error));
+                        (crate_spec, result)
+                    }
+                }))
+                .await;
+                tracker.complete_request(TrackedTopic::Codebase);
+                return results;
             }
             Ok(git::RepoStatus::NotFound) => {
                 let reason = format!("repository '{repo_spec}' not found");
@@ -257,9 +316,11 @@ impl Provider {
 
         log::debug!(target: LOG_TARGET, "Gathering commit statistics for repository '{repo_spec}'");
 
-        let (contributor_count, commit_stats) = tokio::join!(
+        let (contributor_count, commit_stats, signed_commits_ratio, latest_tag_signed) = tokio::join!(
             git::count_contributors(repo_path),
             git::get_commit_stats(repo_path, &[90, 180, 365]),
+            git::get_signed_commit_ratio(repo_path),
+            git::get_latest_tag_signed(repo_path),
         );
 
         let contributor_count = match contributor_count {
@@ -283,6 +344,16 @@ impl Provider {
             }
         };
 
+        let signed_commits_ratio = signed_commits_ratio.unwrap_or_else(|e| {
+            log::warn!(target: LOG_TARGET, "Could not check commit signatures for '{repo_spec}': {e:#}");
+            None
+        });
+
+        let latest_tag_signed = latest_tag_signed.unwrap_or_else(|e| {
+            log::warn!(target: LOG_TARGET, "Could not check tag signatures for '{repo_spec}': {e:#}");
+            None
+        });
+
         log::debug!(target: LOG_TARGET, "Detecting workflows in repository '{repo_spec}'");
 
         let repo_path_owned = repo_path.to_path_buf();
@@ -296,16 +367,26 @@ impl Provider {
             }
         };
 
+        log::debug!(target: LOG_TARGET, "Detecting governance files in repository '{repo_spec}'");
+
+        let repo_path_owned = repo_path.to_path_buf();
+        let governance = spawn_blocking(move || sniff_governance_files(&repo_path_owned))
+            .await
+            .expect("task must not panic");
+
         log::debug!(target: LOG_TARGET, "Analyzed repository '{repo_spec}', found {} packages", metadata.packages.len());
 
         Ok(RepoData {
             metadata: Arc::new(metadata),
             workflows,
+            governance,
             contributor_count,
             commits_last_90_days: commit_stats.commits_per_window[0],
             commits_last_180_days: commit_stats.commits_per_window[1],
             commits_last_365_days: commit_stats.commits_per_window[2],
             commit_count: commit_stats.commit_count,
+            signed_commits_ratio,
+            latest_tag_signed,
             first_commit_at: commit_stats.first_commit_at,
             last_commit_at: commit_stats.last_commit_at,
         })
@@ -355,15 +436,27 @@ impl Provider {
             unsafe_count: 0,
             source_files_with_errors: 0,
             example_count: example_count as u64,
+            doc_test_count: 0,
             transitive_dependencies: transitive_dependencies as u64,
             workflows_detected: repo_data.workflows.workflows_detected,
             miri_detected: repo_data.workflows.miri_detected,
             clippy_detected: repo_data.workflows.clippy_detected,
+            ci_platform_count: repo_data.workflows.platform_count(),
+            ci_linux: repo_data.workflows.linux_detected,
+            ci_windows: repo_data.workflows.windows_detected,
+            ci_macos: repo_data.workflows.macos_detected,
+            ci_arm: repo_data.workflows.arm_detected,
+            ci_wasm: repo_data.workflows.wasm_detected,
+            has_contributing: repo_data.governance.has_contributing,
+            has_code_of_conduct: repo_data.governance.has_code_of_conduct,
+            has_governance: repo_data.governance.has_governance,
             contributors: repo_data.contributor_count,
             commits_last_90_days: repo_data.commits_last_90_days,
             commits_last_180_days: repo_data.commits_last_180_days,
             commits_last_365_days: repo_data.commits_last_365_days,
             commit_count: repo_data.commit_count,
+            signed_commits_ratio: repo_data.signed_commits_ratio,
+            latest_tag_signed: repo_data.latest_tag_signed,
             first_commit_at: repo_data.first_commit_at,
             last_commit_at: repo_data.last_commit_at,
         };
@@ -386,6 +479,62 @@ impl Provider {
         (crate_spec, result)
     }
 
+    /// Analyze the sources of a crate found unpacked on disk, for when its repository can't be synced.
+    ///
+    /// Only the metrics derived from the sources are available this way, so facts that need the repository,
+    /// such as its history and CI workflows, are left empty. The result isn't cached, so the repository is
+    /// analyzed once it can be synced again.
+    async fn analyze_local_crate(&self, crate_spec: &CrateSpec) -> Option<ProviderResult<CodebaseData>> {
+        let crate_path = self.local_sources.as_ref()?.find(crate_spec)?;
+        log::info!(target: LOG_TARGET, "Analyzing local sources of {crate_spec} in '{}'", crate_path.display());
+
+        let examples_dir = crate_path.join("examples");
+        let example_count = spawn_blocking(move || count_examples(&examples_dir))
+            .await
+            .expect("task must not panic");
+
+        let mut codebase_data = CodebaseData {
+            source_files_analyzed: 0,
+            production_lines: 0,
+            test_lines: 0,
+            comment_lines: 0,
+            unsafe_count: 0,
+            source_files_with_errors: 0,
+            example_count,
+            doc_test_count: 0,
+            transitive_dependencies: 0,
+            workflows_detected: false,
+            miri_detected: false,
+            clippy_detected: false,
+            ci_platform_count: 0,
+            ci_linux: false,
+            ci_windows: false,
+            ci_macos: false,
+            ci_arm: false,
+            ci_wasm: false,
+            has_contributing: false,
+            has_code_of_conduct: false,
+            has_governance: false,
+            contributors: 0,
+            commits_last_90_days: 0,
+            commits_last_180_days: 0,
+            commits_last_365_days: 0,
+            commit_count: 0,
+            signed_commits_ratio: None,
+            latest_tag_signed: None,
+            first_commit_at: DateTime::UNIX_EPOCH,
+            last_commit_at: DateTime::UNIX_EPOCH,
+        };
+
+        Some(match Self::analyze_source_files(&crate_path, &mut codebase_data).await {
+            Ok(()) => ProviderResult::Found(codebase_data),
+            Err(e) => {
+                let reason = format!("{:#}", e.enrich_with(|| format!("analyzing local sources of {crate_spec}")));
+                ProviderResult::Unavailable(reason.into())
+            }
+        })
+    }
+
     /// Analyze source files in a crate directory
     ///
     /// Walks the `src/` directory and analyzes each Rust file using the source analyzer,
@@ -479,6 +628,7 @@ impl Provider {
                     codebase_data.test_lines += file_stats.test_lines;
                     codebase_data.comment_lines += file_stats.comment_lines;
                     codebase_data.unsafe_count += file_stats.unsafe_count;
+                    codebase_data.doc_test_count += file_stats.doc_test_count;
 
                     if file_stats.has_errors {
                         codebase_data.source_files_with_errors += 1;
@@ -560,6 +710,26 @@ impl Provider {
     }
 }
 
+/// Count the examples Cargo discovers automatically: `.rs` files directly in the examples directory, and
+/// subdirectories holding a `main.rs`.
+fn count_examples(examples_dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(examples_dir) else {
+        return 0;
+    };
+
+    entries
+        .filter_map(core::result::Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            if path.is_dir() {
+                path.join("main.rs").is_file()
+            } else {
+                path.extension().is_some_and(|ext| ext == "rs")
+            }
+        })
+        .count() as u64
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -615,6 +785,21 @@ mod tests {
         assert!(path_str.contains("tokio"));
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_count_examples() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert_eq!(count_examples(&tmp.path().join("examples")), 0);
+
+        let examples = tmp.path().join("examples");
+        fs::create_dir_all(examples.join("server")).unwrap();
+        fs::create_dir_all(examples.join("assets")).unwrap();
+        fs::write(examples.join("server").join("main.rs"), "fn main() {}").unwrap();
+        fs::write(examples.join("hello.rs"), "fn main() {}").unwrap();
+        fs::write(examples.join("README.md"), "").unwrap();
+        assert_eq!(count_examples(&examples), 2);
+    }
+
     #[test]
     fn test_get_data_filename_sanitized() {
         let url = url::Url::parse("https://evil.com/../../etc/passwd").unwrap();
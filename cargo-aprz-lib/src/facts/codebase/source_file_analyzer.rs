@@ -4,12 +4,16 @@ use ra_ap_syntax::{
 };
 use crate::HashSet;
 
+/// Code block attributes under which rustdoc still treats a block as Rust, and so as a doc test
+const DOC_TEST_ATTRIBUTES: &[&str] = &["rust", "ignore", "should_panic", "no_run", "compile_fail", "test_harness", "standalone_crate"];
+
 #[derive(Debug)]
 pub struct SourceFileInfo {
     pub production_lines: u64,
     pub test_lines: u64,
     pub comment_lines: u64,
     pub unsafe_count: u64,
+    pub doc_test_count: u64,
     pub has_errors: bool,
 }
 
@@ -20,6 +24,8 @@ struct SourceFileAnalyzer<'a> {
     test_lines: HashSet<usize>,
     comment_lines: HashSet<usize>,
     unsafe_count: usize,
+    doc_test_count: usize,
+    in_doc_code_block: bool,
     test_context_depth: usize,
 }
 
@@ -37,6 +43,8 @@ impl<'a> SourceFileAnalyzer<'a> {
             test_lines: HashSet::default(),
             comment_lines: HashSet::default(),
             unsafe_count: 0,
+            doc_test_count: 0,
+            in_doc_code_block: false,
             test_context_depth: 0,
         }
     }
@@ -83,6 +91,7 @@ impl<'a> SourceFileAnalyzer<'a> {
         // Handle comments
         if matches!(kind, SyntaxKind::COMMENT) {
             self.record_comment_lines_from_token(token);
+            self.record_doc_tests_from_comment(token.text());
         }
 
         // Count unsafe keywords
@@ -175,6 +184,37 @@ impl<'a> SourceFileAnalyzer<'a> {
         }
     }
 
+    /// Count the code blocks of a doc comment that rustdoc runs as doc tests
+    fn record_doc_tests_from_comment(&mut self, text: &str) {
+        let (body, block) = if text.starts_with("////") || text.starts_with("/***") {
+            return;
+        } else if let Some(rest) = text.strip_prefix("///").or_else(|| text.strip_prefix("//!")) {
+            (rest, false)
+        } else if let Some(rest) = text.strip_prefix("/**").or_else(|| text.strip_prefix("/*!")) {
+            (rest.strip_suffix("*/").unwrap_or(rest), true)
+        } else {
+            return;
+        };
+
+        for line in body.lines() {
+            let mut line = line.trim_start();
+            if block {
+                line = line.trim_start_matches('*').trim_start();
+            }
+
+            if let Some(info) = line.strip_prefix("```") {
+                if self.in_doc_code_block {
+                    self.in_doc_code_block = false;
+                } else {
+                    self.in_doc_code_block = true;
+                    if is_doc_test(info) {
+                        self.doc_test_count += 1;
+                    }
+                }
+            }
+        }
+    }
+
     /// Record lines that contain code
     fn record_code_lines(&mut self, node: &SyntaxNode) {
         let text_range = node.text_range();
@@ -219,6 +259,13 @@ impl<'a> SourceFileAnalyzer<'a> {
     }
 }
 
+/// Whether a code block with the given info string is a doc test, which is the case for blocks without a language
+fn is_doc_test(info: &str) -> bool {
+    info.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|attr| !attr.is_empty())
+        .all(|attr| DOC_TEST_ATTRIBUTES.contains(&attr) || attr.starts_with("edition"))
+}
+
 pub fn analyze_source_file(source_file: &str) -> SourceFileInfo {
     let parse = SourceFile::parse(source_file, Edition::CURRENT);
     let has_errors = !parse.errors().is_empty();
@@ -232,6 +279,7 @@ pub fn analyze_source_file(source_file: &str) -> SourceFileInfo {
         test_lines: analyzer.test_lines.len() as u64,
         comment_lines: analyzer.comment_lines.len() as u64,
         unsafe_count: analyzer.unsafe_count as u64,
+        doc_test_count: analyzer.doc_test_count as u64,
         has_errors,
     }
 }
@@ -313,6 +361,48 @@ unsafe trait UnsafeTrait {}
         assert!(stats.unsafe_count >= 4);
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri detects UB in external rowan crate")]
+    fn test_doc_test_counting() {
+        let source = r#"
+//! ```
+//! let crate_level = 1;
+//! ```
+
+/// ```rust,no_run
+/// let a = 1;
+/// ```
+///
+/// ```toml
+/// key = "value"
+/// ```
+fn documented() {}
+
+/**
+ * ```edition2021
+ * let b = 2;
+ * ```
+ */
+fn block_documented() {}
+
+// ```
+// not documentation
+// ```
+fn commented() {}
+"#;
+        let stats = analyze_source_file(source);
+        assert_eq!(stats.doc_test_count, 3);
+    }
+
+    #[test]
+    fn test_is_doc_test() {
+        assert!(is_doc_test(""));
+        assert!(is_doc_test("rust"));
+        assert!(is_doc_test("should_panic, edition2021"));
+        assert!(!is_doc_test("text"));
+        assert!(!is_doc_test("rust,json"));
+    }
+
     #[test]
     #[cfg_attr(miri, ignore = "Miri detects UB in external rowan crate")]
     fn test_test_function_attribute() {
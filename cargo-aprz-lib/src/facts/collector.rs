@@ -1,13 +1,20 @@
-use super::cache::Cache;
+use super::cache::{Cache, CacheStats, StaleEntries};
 use super::cache_lock::{CacheLockGuard, acquire_cache_lock};
-use super::crate_facts::CrateFacts;
+use super::crate_facts::{CrateFacts, DataTimestamps};
 use super::crate_spec::CrateSpec;
+use super::docs::count_api_changes;
+use super::evidence;
+use super::hosting::{ApiUsage, HostingSettings};
+use super::local_sources::LocalSources;
+use super::package::count_repo_differences;
 use super::progress::Progress;
 use super::request_tracker::RequestTracker;
-use super::{CrateRef, CratesData, ProviderResult};
+use super::{Cancellation, CrateRef, CratesData, ProviderResult, registered_providers};
 use crate::Result;
 use chrono::Utc;
+use compact_str::CompactString;
 use core::time::Duration;
+use futures_util::future::join_all;
 use ohno::IntoAppError;
 use crate::{HashMap, HashSet};
 use std::fs;
@@ -22,8 +29,12 @@ pub struct Collector {
     codebase_provider: super::codebase::Provider,
     coverage_provider: super::coverage::Provider,
     docs_provider: super::docs::Provider,
+    package_provider: super::package::Provider,
     progress: Arc<dyn Progress>,
-    _cache_lock: CacheLockGuard,
+    stale_entries: Option<StaleEntries>,
+    caches: Vec<(&'static str, Cache)>,
+    cancellation: Cancellation,
+    _cache_lock: Option<CacheLockGuard>,
 }
 
 impl core::fmt::Debug for Collector {
@@ -35,59 +46,192 @@ impl core::fmt::Debug for Collector {
             .field("codebase_provider", &self.codebase_provider)
             .field("coverage_provider", &self.coverage_provider)
             .field("docs_provider", &self.docs_provider)
+            .field("package_provider", &self.package_provider)
             .field("progress", &"<dyn Progress>")
+            .field("stale_entries", &self.stale_entries)
+            .field("caches", &self.caches)
+            .field("cancellation", &self.cancellation)
             .finish_non_exhaustive()
     }
 }
 
 impl Collector {
-    #[expect(clippy::too_many_arguments, reason = "all cache TTL parameters are necessary for configuration")]
+    #[expect(clippy::too_many_arguments, reason = "all cache parameters are necessary for configuration")]
     pub async fn new(
-        github_token: Option<&str>,
-        codeberg_token: Option<&str>,
-        cache_dir: impl AsRef<Path>,
+        hosting: HostingSettings,
+        cache_dir: &Path,
         crates_cache_ttl: Duration,
         hosting_cache_ttl: Duration,
         codebase_cache_ttl: Duration,
         coverage_cache_ttl: Duration,
         advisories_cache_ttl: Duration,
+        docs_cache_ttl: Duration,
         ignore_cached: bool,
-        progress: impl Progress + 'static,
+        read_only: bool,
+        progress: Arc<dyn Progress>,
     ) -> Result<Self> {
-        let progress: Arc<dyn Progress> = Arc::new(progress);
         progress.set_phase("Preparing");
 
-        let crates_cache_dir = create_cache_dir(&cache_dir, "crates")?;
-        let hosting_cache_dir = create_cache_dir(&cache_dir, "hosting")?;
-        let codebase_cache_dir = create_cache_dir(&cache_dir, "codebase")?;
-        let coverage_cache_dir = create_cache_dir(&cache_dir, "coverage")?;
-        let advisories_cache_dir = create_cache_dir(&cache_dir, "advisories")?;
-        let docs_cache_dir = create_cache_dir(&cache_dir, "docs")?;
+        // A read-only cache is used as is, so its directories aren't created and it isn't locked
+        let cache_dir_for = |name: &str| {
+            if read_only {
+                Ok(cache_dir.join(name))
+            } else {
+                create_cache_dir(cache_dir, name)
+            }
+        };
+
+        let crates_cache_dir = cache_dir_for("crates")?;
+        let hosting_cache_dir = cache_dir_for("hosting")?;
+        let codebase_cache_dir = cache_dir_for("codebase")?;
+        let coverage_cache_dir = cache_dir_for("coverage")?;
+        let advisories_cache_dir = cache_dir_for("advisories")?;
+        let docs_cache_dir = cache_dir_for("docs")?;
+        let package_cache_dir = cache_dir_for("packages")?;
 
         // Acquire cache lock to prevent concurrent access
-        let cache_lock = acquire_cache_lock(cache_dir.as_ref()).await?;
+        let cache_lock = if read_only { None } else { Some(acquire_cache_lock(cache_dir).await?) };
+
+        let stale_entries = read_only.then(StaleEntries::default);
+        let make_cache = |dir: PathBuf, ttl: Duration| {
+            let cache = Cache::new(dir, ttl, ignore_cached);
+            match &stale_entries {
+                Some(stale) => cache.read_only(stale),
+                None => cache,
+            }
+        };
+
+        let hosting_cache = make_cache(hosting_cache_dir, hosting_cache_ttl);
+        let codebase_cache = make_cache(codebase_cache_dir, codebase_cache_ttl);
+        let coverage_cache = make_cache(coverage_cache_dir, coverage_cache_ttl);
+        let advisories_cache = make_cache(advisories_cache_dir, advisories_cache_ttl);
+        let docs_cache = make_cache(docs_cache_dir, docs_cache_ttl);
+        let package_cache = make_cache(package_cache_dir, Duration::MAX);
+
+        // Clones share their lookup statistics, so these are kept around to report them
+        let caches = vec![
+            ("hosting", hosting_cache.clone()),
+            ("codebase", codebase_cache.clone()),
+            ("coverage", coverage_cache.clone()),
+            ("advisories", advisories_cache.clone()),
+            ("docs", docs_cache.clone()),
+            ("packages", package_cache.clone()),
+        ];
+
+        let now = Utc::now();
+        let crates_provider = super::crates::Provider::new(
+            &crates_cache_dir,
+            crates_cache_ttl,
+            Arc::clone(&progress),
+            now,
+            ignore_cached,
+            read_only,
+            None,
+        )
+        .await?;
 
-        let hosting_cache = Cache::new(hosting_cache_dir, hosting_cache_ttl, ignore_cached);
-        let codebase_cache = Cache::new(codebase_cache_dir, codebase_cache_ttl, ignore_cached);
-        let coverage_cache = Cache::new(coverage_cache_dir, coverage_cache_ttl, ignore_cached);
-        let advisories_cache = Cache::new(advisories_cache_dir, advisories_cache_ttl, ignore_cached);
-        let docs_cache = Cache::new(docs_cache_dir, Duration::MAX, ignore_cached);
+        if let Some(stale) = &stale_entries {
+            let created_at = crates_provider.created_at();
+            if now.signed_duration_since(created_at).to_std().is_ok_and(|age| age >= crates_cache_ttl) {
+                stale.record(format!("crates.io database dump from {}", created_at.format("%Y-%m-%d")));
+            }
+        }
 
         Ok(Self {
-            crates_provider: super::crates::Provider::new(&crates_cache_dir, crates_cache_ttl, Arc::clone(&progress), Utc::now(), ignore_cached, None).await?,
+            crates_provider,
 
             advisories_provider: super::advisories::Provider::new(&advisories_cache, Arc::clone(&progress))
                 .await?,
 
-            hosting_provider: super::hosting::Provider::new(github_token, codeberg_token, hosting_cache)?,
+            hosting_provider: super::hosting::Provider::new(hosting, hosting_cache)?,
             codebase_provider: super::codebase::Provider::new(codebase_cache),
             coverage_provider: super::coverage::Provider::new(coverage_cache, None),
             docs_provider: super::docs::Provider::new(docs_cache, None),
+            package_provider: super::package::Provider::new(package_cache, None),
             progress,
+            stale_entries,
+            caches,
+            cancellation: Cancellation::new(),
             _cache_lock: cache_lock,
         })
     }
 
+    /// Analyze the sources of crates found unpacked on disk instead of downloading their packages, and
+    /// in place of their repositories when those can't be synced.
+    #[must_use]
+    pub fn with_local_sources(mut self, local_sources: LocalSources) -> Self {
+        let local_sources = Arc::new(local_sources);
+        self.package_provider = self.package_provider.with_local_sources(Arc::clone(&local_sources));
+        self.codebase_provider = self.codebase_provider.with_local_sources(local_sources);
+        self
+    }
+
+    /// Download packages from a registry mirror, given the download URL template of its `config.json`.
+    #[must_use]
+    pub fn with_package_mirror(mut self, download_template: String) -> Self {
+        self.package_provider = self.package_provider.with_download_template(download_template);
+        self
+    }
+
+    /// Stop gathering facts once `cancellation` is cancelled, leaving the crates whose data wasn't gathered yet
+    /// with [`ProviderResult::Cancelled`] in its place.
+    #[must_use]
+    pub fn with_cancellation(mut self, cancellation: Cancellation) -> Self {
+        self.hosting_provider = self.hosting_provider.with_cancellation(cancellation.clone());
+        self.codebase_provider = self.codebase_provider.with_cancellation(cancellation.clone());
+        self.coverage_provider = self.coverage_provider.with_cancellation(cancellation.clone());
+        self.docs_provider = self.docs_provider.with_cancellation(cancellation.clone());
+        self.package_provider = self.package_provider.with_cancellation(cancellation.clone());
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// Returns the cache entries served past their TTL, when the cache is read-only.
+    #[must_use]
+    pub fn stale_cache_entries(&self) -> Vec<String> {
+        self.stale_entries.as_ref().map_or_else(Vec::new, StaleEntries::sorted)
+    }
+
+    /// Returns the cache lookup statistics of each provider that looked anything up so far.
+    #[must_use]
+    pub fn cache_stats(&self) -> Vec<(&'static str, CacheStats)> {
+        self.caches
+            .iter()
+            .map(|(name, cache)| (*name, cache.stats()))
+            .filter(|(_, stats)| stats.lookups() > 0)
+            .collect()
+    }
+
+    /// Returns the API quota used on each hosting service queried so far.
+    #[must_use]
+    pub fn api_usage(&self) -> Vec<ApiUsage> {
+        self.hosting_provider.api_usage()
+    }
+
+    /// Names of the crates owned by a crates.io user or team, sorted.
+    pub async fn owned_crates(&self, login: &str) -> Vec<CompactString> {
+        self.crates_provider.owned_crates(login).await
+    }
+
+    /// Write the evidence bundle of a crate within `dir`, holding the documents its facts were derived from.
+    ///
+    /// Returns the directory of the bundle.
+    pub fn write_evidence(&self, facts: &CrateFacts, dir: &Path) -> Result<PathBuf> {
+        let spec = &facts.crate_spec;
+        let documents: Vec<_> = [
+            ("hosting", self.hosting_provider.cached_document(spec)),
+            ("codebase", self.codebase_provider.cached_document(spec)),
+            ("coverage", self.coverage_provider.cached_document(spec)),
+            ("docs", self.docs_provider.cached_document(spec)),
+            ("package", self.package_provider.cached_document(spec)),
+        ]
+        .into_iter()
+        .filter_map(|(source, path)| Some((source, path?)))
+        .collect();
+
+        evidence::write_bundle(dir, facts, &documents, &self.advisories_provider.advisory_files(spec.name()))
+    }
+
     /// Collect facts for multiple crates
     pub async fn collect(
         &self,
@@ -140,6 +284,9 @@ impl Collector {
                     codebase_data: ProviderResult::Unavailable("not queried".into()),
                     coverage_data: ProviderResult::Unavailable("not queried".into()),
                     docs_data: ProviderResult::Unavailable("not queried".into()),
+                    package_data: ProviderResult::Unavailable("not queried".into()),
+                    custom_data: HashMap::default(),
+                    timestamps: DataTimestamps::default(),
                 };
                 (crate_spec, facts)
             })
@@ -152,14 +299,17 @@ impl Collector {
             .collect();
 
         if !all_queryable_specs.is_empty() {
-            let (advisory_iter, docs_iter, hosting_iter, codebase_iter, coverage_iter) = tokio::join!(
+            let custom_providers = registered_providers();
+            let (advisory_iter, docs_iter, package_iter, hosting_iter, codebase_iter, coverage_iter, custom_results) = tokio::join!(
                 self.advisories_provider.get_advisory_data(all_queryable_specs.clone()),
                 self.docs_provider.get_docs_data(all_queryable_specs.clone(), &request_tracker),
+                self.package_provider.get_package_data(all_queryable_specs.clone(), &request_tracker),
                 self.hosting_provider
                     .get_hosting_data(all_queryable_specs.clone(), &request_tracker),
                 self.codebase_provider
                     .get_codebase_data(all_queryable_specs.clone(), &request_tracker),
-                self.coverage_provider.get_coverage_data(all_queryable_specs, &request_tracker),
+                self.coverage_provider.get_coverage_data(all_queryable_specs.clone(), &request_tracker),
+                join_all(custom_providers.iter().map(|provider| provider.get_facts(&all_queryable_specs))),
             );
 
             macro_rules! update_facts {
@@ -174,13 +324,97 @@ impl Collector {
 
             update_facts!(advisory_iter, advisory_data);
             update_facts!(docs_iter, docs_data);
+            update_facts!(package_iter, package_data);
             update_facts!(hosting_iter, hosting_data);
             update_facts!(codebase_iter, codebase_data);
             update_facts!(coverage_iter, coverage_data);
+
+            for (provider, results) in custom_providers.iter().zip(custom_results) {
+                for (crate_spec, result) in results {
+                    if let Some(facts) = facts_map.get_mut(&crate_spec) {
+                        let _ = facts.custom_data.insert(provider.name(), result);
+                    }
+                }
+            }
+
+            // Comparisons look at data beyond the crates themselves, so a cancelled run skips them
+            if !self.cancellation.is_cancelled() {
+                self.compare_packages(&mut facts_map).await;
+                self.compare_apis(&mut facts_map).await;
+            }
+        }
+
+        let dump_created_at = self.crates_provider.created_at();
+        let advisories_synced_at = self.advisories_provider.synced_at();
+        for facts in facts_map.values_mut() {
+            facts.timestamps = DataTimestamps {
+                crates: facts.crates_data.is_found().then_some(dump_created_at),
+                hosting: facts.hosting_data.as_ref().and_then(|data| data.fetched_at),
+                advisories: advisories_synced_at.filter(|_| facts.advisory_data.is_found()),
+            };
         }
 
         facts_map.into_values().collect()
     }
+
+    /// Compare published packages against the repository commits they were built from.
+    ///
+    /// This relies on the repositories synced by the codebase provider, so it runs once all providers are done.
+    async fn compare_packages(&self, facts_map: &mut HashMap<CrateSpec, CrateFacts>) {
+        let comparisons = join_all(facts_map.iter().filter_map(|(crate_spec, facts)| {
+            let package = facts.package_data.as_ref()?;
+            let commit = package.vcs_commit.as_deref()?;
+            let repo_spec = crate_spec.repo_spec()?;
+            if !facts.codebase_data.is_found() {
+                return None;
+            }
+
+            Some(async move {
+                let differences = self
+                    .codebase_provider
+                    .get_tree_blobs(repo_spec, commit)
+                    .await
+                    .map(|blobs| count_repo_differences(package, &blobs));
+                (crate_spec.clone(), differences)
+            })
+        }))
+        .await;
+
+        for (crate_spec, differences) in comparisons {
+            if let Some(facts) = facts_map.get_mut(&crate_spec)
+                && let ProviderResult::Found(package) = &mut facts.package_data
+            {
+                package.repo_differences = differences;
+            }
+        }
+    }
+
+    /// Compare the public API of each crate against the one of its previous release.
+    async fn compare_apis(&self, facts_map: &mut HashMap<CrateSpec, CrateFacts>) {
+        let comparisons = join_all(facts_map.iter().filter_map(|(crate_spec, facts)| {
+            let docs = facts.docs_data.as_ref()?;
+            let previous = facts.crates_data.as_ref()?.version_data.previous_version.clone()?;
+            let previous_spec = CrateSpec::from_arcs(Arc::clone(crate_spec.name_arc()), Arc::new(previous));
+
+            Some(async move {
+                let churn = self
+                    .docs_provider
+                    .get_public_api(&previous_spec)
+                    .await
+                    .map(|previous_api| count_api_changes(&previous_api, &docs.public_api));
+                (crate_spec.clone(), churn)
+            })
+        }))
+        .await;
+
+        for (crate_spec, churn) in comparisons {
+            if let Some(facts) = facts_map.get_mut(&crate_spec)
+                && let ProviderResult::Found(docs) = &mut facts.docs_data
+            {
+                docs.api_churn = churn;
+            }
+        }
+    }
 }
 
 /// Create a cache directory by joining a base path with a name
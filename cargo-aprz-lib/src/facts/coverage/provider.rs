@@ -1,6 +1,6 @@
 use super::CoverageData;
 use crate::Result;
-use crate::facts::ProviderResult;
+use crate::facts::{Cancellation, ProviderResult};
 use crate::facts::cache::{Cache, CacheResult};
 use crate::facts::crate_spec::{self, CrateSpec};
 use crate::facts::path_utils::sanitize_path_component;
@@ -10,6 +10,7 @@ use crate::facts::throttler::Throttler;
 use futures_util::future::join_all;
 use ohno::{EnrichableExt, IntoAppError};
 use regex::Regex;
+use std::path::PathBuf;
 use std::sync::{Arc, LazyLock};
 
 const LOG_TARGET: &str = "  coverage";
@@ -26,6 +27,7 @@ pub struct Provider {
     cache: Cache,
     base_url: String,
     throttler: Arc<Throttler>,
+    cancellation: Cancellation,
 }
 
 impl Provider {
@@ -41,9 +43,23 @@ impl Provider {
             cache,
             base_url: base_url.unwrap_or(CODECOV_BASE_URL).to_string(),
             throttler: Throttler::new(MAX_CONCURRENT_REQUESTS),
+            cancellation: Cancellation::new(),
         }
     }
 
+    /// Stop starting requests once `cancellation` is cancelled.
+    #[must_use]
+    pub fn with_cancellation(mut self, cancellation: Cancellation) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// The cached document holding the code coverage of a crate's repository, if any.
+    #[must_use]
+    pub fn cached_document(&self, crate_spec: &CrateSpec) -> Option<PathBuf> {
+        self.cache.entry_path(&Self::get_cache_filename(crate_spec.repo_spec()?))
+    }
+
     pub async fn get_coverage_data(
         &self,
         crates: impl IntoIterator<Item = CrateSpec> + Send + 'static,
@@ -75,7 +91,11 @@ impl Provider {
 
     /// Get code coverage data for a single repository
     async fn fetch_coverage_data_for_repo(&self, repo_spec: RepoSpec, tracker: RequestTracker) -> (RepoSpec, ProviderResult<CoverageData>) {
-        let _permit = self.throttler.acquire().await;
+        let Some(_permit) = self.throttler.acquire_unless_cancelled(&self.cancellation).await else {
+            tracker.complete_request(TrackedTopic::Coverage);
+            return (repo_spec, ProviderResult::Cancelled);
+        };
+
         let result = self.fetch_coverage_data_for_repo_core(&repo_spec).await;
         tracker.complete_request(TrackedTopic::Coverage);
 
@@ -6,6 +6,9 @@ use super::crate_spec::CrateSpec;
 use super::crates::CratesData;
 use super::docs::DocsData;
 use super::hosting::HostingData;
+use super::package::PackageData;
+use crate::HashMap;
+use chrono::{DateTime, Utc};
 
 /// Comprehensive facts about a crate collected from various providers
 #[derive(Debug)]
@@ -17,4 +20,46 @@ pub struct CrateFacts {
     pub codebase_data: ProviderResult<CodebaseData>,
     pub coverage_data: ProviderResult<CoverageData>,
     pub docs_data: ProviderResult<DocsData>,
+    pub package_data: ProviderResult<PackageData>,
+    /// Facts collected by custom providers, keyed by provider name
+    pub custom_data: HashMap<&'static str, ProviderResult<serde_json::Value>>,
+    /// When the data of the sources that can go stale was acquired
+    pub timestamps: DataTimestamps,
+}
+
+impl CrateFacts {
+    /// Names of the sources whose data wasn't gathered because the run was cancelled.
+    #[must_use]
+    pub fn cancelled_sources(&self) -> Vec<&'static str> {
+        let mut sources: Vec<&'static str> = [
+            ("hosting", self.hosting_data.is_cancelled()),
+            ("advisories", self.advisory_data.is_cancelled()),
+            ("codebase", self.codebase_data.is_cancelled()),
+            ("coverage", self.coverage_data.is_cancelled()),
+            ("docs", self.docs_data.is_cancelled()),
+            ("package", self.package_data.is_cancelled()),
+        ]
+        .into_iter()
+        .filter_map(|(name, cancelled)| cancelled.then_some(name))
+        .collect();
+
+        let mut custom: Vec<&'static str> =
+            self.custom_data.iter().filter(|(_, result)| result.is_cancelled()).map(|(name, _)| *name).collect();
+        custom.sort_unstable();
+        sources.extend(custom);
+        sources
+    }
+}
+
+/// When the data of each source that can go stale was acquired, `None` for sources without data for the crate
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DataTimestamps {
+    /// When the crates.io database dump was published
+    pub crates: Option<DateTime<Utc>>,
+
+    /// When the data of the crate's repository was fetched from its hosting service
+    pub hosting: Option<DateTime<Utc>>,
+
+    /// When the advisory database was last synced
+    pub advisories: Option<DateTime<Utc>>,
 }
@@ -51,6 +51,11 @@ impl CrateSpec {
     pub const fn version_arc(&self) -> &Arc<Version> {
         &self.version
     }
+
+    #[must_use]
+    pub const fn repo_spec(&self) -> Option<&RepoSpec> {
+        self.repo_spec.as_ref()
+    }
 }
 
 /// Group crate by their repos
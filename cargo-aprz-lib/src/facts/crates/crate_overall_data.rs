@@ -67,6 +67,18 @@ pub struct CrateOverallData {
     /// **Source**: `crate_downloads.csv` from the `crate_downloads` table, `downloads` field
     pub downloads: u64,
 
+    /// Percentage of crates on crates.io with fewer total downloads than this crate.
+    ///
+    /// **Source**: `crate_downloads.csv` from the `crate_downloads` table, compared against the
+    /// distribution of `downloads` across all crates
+    pub downloads_percentile: f64,
+
+    /// Percentage of crates on crates.io that were first published after this crate.
+    ///
+    /// **Source**: `crates.csv` from the `crates` table, compared against the distribution of
+    /// `created_at` across all crates
+    pub age_percentile: f64,
+
     /// Number of unique crates that depend on this crate (as counted from the database dump).
     /// This represents how many other crates list this one as a dependency.
     ///
@@ -76,6 +88,18 @@ pub struct CrateOverallData {
     /// 3. Count unique dependent `crate_ids`
     pub dependents: u64,
 
+    /// Percentage of crates on crates.io with fewer unique dependents than this crate.
+    ///
+    /// **Source**: Computed like `dependents` for every crate in the database dump
+    pub dependents_percentile: f64,
+
+    /// Percentage change of `dependents` over the last six months, or `None` if no earlier count is known
+    /// or the crate had no dependents back then.
+    ///
+    /// **Source**: `dependents` compared against the count recorded from an earlier database dump, as
+    /// snapshots of the dependent counts are kept whenever a new dump is processed
+    pub dependents_trend: Option<f64>,
+
     /// Number of different versions of this crate published within the last 90 days.
     /// This helps assess the release frequency and stability of the crate.
     ///
@@ -96,4 +120,13 @@ pub struct CrateOverallData {
     /// **Source**: Computed from `versions.csv` from the `versions` table
     /// - Count versions for this `crate_id` where `created_at` is within the last 365 days
     pub versions_last_365_days: u64,
+
+    /// Median number of days this crate took to allow new releases of its dependencies which its
+    /// requirements excluded when they came out, or `None` if there were no such releases.
+    /// Releases that the latest version still excludes count up to the time of the dump.
+    ///
+    /// **Source**: Computed from multi-table join:
+    /// 1. `dependencies.csv` from the `dependencies` table (requirements of every version of this crate)
+    /// 2. `versions.csv` from the `versions` table (when each version of this crate and of its dependencies was published)
+    pub dep_update_latency_days: Option<u64>,
 }
@@ -1,8 +1,10 @@
 use super::rust_edition::RustEdition;
+use crate::facts::interner;
 use chrono::{DateTime, NaiveDate, Utc};
 use compact_str::CompactString;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
+use std::sync::Arc;
 use url::Url;
 
 /// Version-specific crate information.
@@ -34,7 +36,8 @@ pub struct CrateVersionData {
     /// Indicates the license(s) under which this version is distributed.
     ///
     /// **Source**: `versions.csv` from the `versions` table, `license` field
-    pub license: CompactString,
+    #[serde(deserialize_with = "interner::deserialize")]
+    pub license: Arc<str>,
 
     /// Optional minimum Rust version (MSRV) required to compile this crate.
     /// Format is a semantic version string (e.g., "1.70.0").
@@ -73,6 +76,20 @@ pub struct CrateVersionData {
     /// **Source**: `versions.csv` from the `versions` table, `yanked` field
     pub yanked: bool,
 
+    /// Names of the dependencies whose version requirements can only be satisfied by yanked releases.
+    /// Dev-dependencies are not considered.
+    ///
+    /// **Source**: `dependencies.csv` from the `dependencies` table, `req` and `kind` fields
+    /// - Each requirement is matched against the `num` and `yanked` fields of the dependency's rows in `versions.csv`
+    /// - Sorted alphabetically
+    pub yanked_dependencies: Vec<CompactString>,
+
+    /// The release which immediately precedes this version, if any.
+    /// Yanked releases and pre-releases are not considered.
+    ///
+    /// **Source**: `versions.csv` from the `versions` table, `num` and `yanked` fields
+    pub previous_version: Option<semver::Version>,
+
     /// Total download count for this specific version.
     ///
     /// **Source**: `versions.csv` from the `versions` table, `downloads` field
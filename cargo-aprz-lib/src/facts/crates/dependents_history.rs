@@ -0,0 +1,154 @@
+//! Dependent counts of crates across successive database dumps.
+//!
+//! A database dump only tells how many crates depend on a crate right now, which says nothing about
+//! dependents migrating away from an abandoned crate. So whenever the distributions are computed from a
+//! new dump, the dependent counts of all crates are also recorded as a snapshot, at most one every
+//! [`SNAPSHOT_INTERVAL_DAYS`] days. The trend of a crate compares its current dependent count against the
+//! oldest snapshot within the last [`TREND_WINDOW_DAYS`] days.
+
+use super::tables::CrateId;
+use crate::HashMap;
+use crate::Result;
+use chrono::{DateTime, Utc};
+use ohno::IntoAppError;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+const LOG_TARGET: &str = "    crates";
+
+/// Name of the file holding the snapshots, within the tables directory
+const HISTORY_FILE_NAME: &str = "dependents_history.json";
+
+/// Minimum number of days between two snapshots, which keeps the file small
+const SNAPSHOT_INTERVAL_DAYS: i64 = 28;
+
+/// Number of days the trend looks back over
+const TREND_WINDOW_DAYS: i64 = 183;
+
+/// Snapshots older than this many days are dropped, as they can no longer serve as a baseline
+const MAX_SNAPSHOT_AGE_DAYS: i64 = TREND_WINDOW_DAYS + SNAPSHOT_INTERVAL_DAYS;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct Snapshot {
+    /// Creation time of the database dump the snapshot was taken from
+    created_at: DateTime<Utc>,
+
+    /// Dependent counts by crate id, leaving out crates without dependents
+    dependents: HashMap<u64, u64>,
+}
+
+/// Snapshots of the dependent counts of all crates, from the oldest to the newest.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct DependentsHistory {
+    snapshots: Vec<Snapshot>,
+}
+
+impl DependentsHistory {
+    /// Load the snapshots kept in the tables directory, if any.
+    pub fn load(tables_root: impl AsRef<Path>) -> Self {
+        let path = tables_root.as_ref().join(HISTORY_FILE_NAME);
+        fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    /// Record the dependent counts computed from the database dump created at `created_at`, unless the
+    /// latest snapshot is too recent.
+    pub fn record(tables_root: impl AsRef<Path>, created_at: DateTime<Utc>, dependents: &HashMap<CrateId, u64>) -> Result<()> {
+        let path = tables_root.as_ref().join(HISTORY_FILE_NAME);
+        let mut history = Self::load(tables_root);
+
+        if !history.add_snapshot(created_at, dependents) {
+            return Ok(());
+        }
+
+        log::debug!(target: LOG_TARGET, "Recording the dependent counts of the database dump created at {created_at}");
+        let text = serde_json::to_string(&history)?;
+        fs::write(&path, text).into_app_err_with(|| format!("writing '{}'", path.display()))
+    }
+
+    /// Add a snapshot and drop the expired ones, returning whether the history changed.
+    fn add_snapshot(&mut self, created_at: DateTime<Utc>, dependents: &HashMap<CrateId, u64>) -> bool {
+        if self
+            .snapshots
+            .last()
+            .is_some_and(|latest| (created_at - latest.created_at).num_days() < SNAPSHOT_INTERVAL_DAYS)
+        {
+            return false;
+        }
+
+        self.snapshots
+            .retain(|s| (created_at - s.created_at).num_days() <= MAX_SNAPSHOT_AGE_DAYS);
+        self.snapshots.push(Snapshot {
+            created_at,
+            dependents: dependents
+                .iter()
+                .filter(|&(_, &count)| count > 0)
+                .map(|(crate_id, &count)| (crate_id.0, count))
+                .collect(),
+        });
+
+        true
+    }
+
+    /// Percentage change of the dependent count of a crate over the last six months, using the oldest snapshot
+    /// in that window as the baseline.
+    ///
+    /// Returns `None` when no snapshot is old enough, or when the crate had no dependents in the baseline.
+    #[expect(clippy::cast_precision_loss, reason = "Dependent counts are far below 2^52")]
+    pub fn trend(&self, crate_id: CrateId, dependents: u64, now: DateTime<Utc>) -> Option<f64> {
+        let baseline = self.snapshots.iter().find(|s| {
+            let age = (now - s.created_at).num_days();
+            (SNAPSHOT_INTERVAL_DAYS..=TREND_WINDOW_DAYS).contains(&age)
+        })?;
+
+        let before = *baseline.dependents.get(&crate_id.0)?;
+        Some((dependents as f64 - before as f64) * 100.0 / before as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn counts(entries: &[(u64, u64)]) -> HashMap<CrateId, u64> {
+        entries.iter().map(|&(id, count)| (CrateId(id), count)).collect()
+    }
+
+    #[test]
+    fn test_add_snapshot() {
+        let now = DateTime::parse_from_rfc3339("2025-07-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let mut history = DependentsHistory::default();
+
+        assert!(history.add_snapshot(now - Duration::days(300), &counts(&[(1, 10)])));
+        assert!(history.add_snapshot(now - Duration::days(150), &counts(&[(1, 40), (2, 0)])));
+        assert!(!history.add_snapshot(now - Duration::days(140), &counts(&[(1, 50)])));
+        assert_eq!(history.snapshots.len(), 2);
+        assert!(!history.snapshots[1].dependents.contains_key(&2));
+
+        // The oldest snapshot has expired by then
+        assert!(history.add_snapshot(now, &counts(&[(1, 30)])));
+        assert_eq!(history.snapshots.len(), 2);
+        assert_eq!(history.snapshots[0].created_at, now - Duration::days(150));
+    }
+
+    #[test]
+    fn test_trend() {
+        let now = DateTime::parse_from_rfc3339("2025-07-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let mut history = DependentsHistory::default();
+        assert_eq!(history.trend(CrateId(1), 30, now), None);
+
+        let _ = history.add_snapshot(now - Duration::days(250), &counts(&[(1, 100), (2, 5)]));
+        let _ = history.add_snapshot(now - Duration::days(150), &counts(&[(1, 40)]));
+        let _ = history.add_snapshot(now - Duration::days(10), &counts(&[(1, 35), (2, 1)]));
+
+        assert_eq!(history.trend(CrateId(1), 30, now), Some(-25.0));
+        assert_eq!(history.trend(CrateId(1), 60, now), Some(50.0));
+
+        // Crates without dependents in the baseline have no trend
+        assert_eq!(history.trend(CrateId(2), 8, now), None);
+    }
+}
@@ -0,0 +1,211 @@
+//! Distributions of crate statistics across all of crates.io.
+//!
+//! Absolute thresholds such as "at least 100,000 downloads" rot as the ecosystem grows. To let policies
+//! rank a crate relative to its peers instead, the distributions of a few statistics are computed once
+//! from each database dump and persisted next to the binary tables. Each distribution is stored as a
+//! fixed number of quantiles, which keeps the file small while giving percentiles a resolution of 0.1%.
+
+use super::dependents_history::DependentsHistory;
+use super::tables::{CrateId, Table, TableMgr, VersionId};
+use crate::Result;
+use crate::{HashMap, HashSet};
+use chrono::{DateTime, Utc};
+use ohno::IntoAppError;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+const LOG_TARGET: &str = "    crates";
+
+/// Name of the file holding the distributions, within the tables directory
+const DISTRIBUTIONS_FILE_NAME: &str = "distributions.json";
+
+/// Number of quantiles kept for each distribution
+const NUM_QUANTILES: usize = 1000;
+
+/// Quantiles of the download counts, ages, and dependent counts of all crates in a database dump.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Distributions {
+    /// Creation time of the database dump the distributions were computed from
+    created_at: DateTime<Utc>,
+    downloads: Vec<u64>,
+    age_days: Vec<u64>,
+    dependents: Vec<u64>,
+}
+
+impl Distributions {
+    /// Load the distributions for the tables managed by `table_mgr`, computing them if they are missing
+    /// or were computed from a different database dump, and saving them unless `read_only` is set.
+    ///
+    /// Computing the distributions from a new dump also records its dependent counts in the [`DependentsHistory`].
+    pub fn load_or_compute(tables_root: impl AsRef<Path>, table_mgr: &TableMgr, read_only: bool) -> Self {
+        let path = tables_root.as_ref().join(DISTRIBUTIONS_FILE_NAME);
+
+        if let Ok(text) = fs::read_to_string(&path)
+            && let Ok(distributions) = serde_json::from_str::<Self>(&text)
+            && distributions.created_at == table_mgr.created_at()
+        {
+            return distributions;
+        }
+
+        log::info!(target: LOG_TARGET, "Computing crate statistics distributions");
+        let dependent_counts = dependent_counts(table_mgr);
+        let distributions = Self::compute(table_mgr, &dependent_counts);
+
+        if !read_only {
+            if let Err(e) = distributions.save(&path) {
+                log::debug!(target: LOG_TARGET, "Could not save distributions to '{}': {e:#}", path.display());
+            }
+
+            // A new dump is also the time to take a snapshot of the dependent counts
+            if let Err(e) = DependentsHistory::record(tables_root, distributions.created_at, &dependent_counts) {
+                log::debug!(target: LOG_TARGET, "Could not record dependent counts: {e:#}");
+            }
+        }
+
+        distributions
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        let text = serde_json::to_string(self)?;
+        fs::write(path, text).into_app_err_with(|| format!("writing '{}'", path.display()))
+    }
+
+    fn compute(table_mgr: &TableMgr, dependent_counts: &HashMap<CrateId, u64>) -> Self {
+        let created_at = table_mgr.created_at();
+
+        let downloads: Vec<u64> = table_mgr.crate_downloads_table().iter().map(|(row, _)| row.downloads).collect();
+
+        let age_days: Vec<u64> = table_mgr
+            .crates_table()
+            .iter()
+            .map(|(row, _)| u64::try_from((created_at - row.created_at).num_days()).unwrap_or(0))
+            .collect();
+
+        // Crates without dependents are absent from the map, but still part of the distribution
+        let num_crates = age_days.len();
+        let mut dependents: Vec<u64> = dependent_counts.values().copied().collect();
+        dependents.resize(num_crates.max(dependents.len()), 0);
+
+        Self {
+            created_at,
+            downloads: quantiles(downloads),
+            age_days: quantiles(age_days),
+            dependents: quantiles(dependents),
+        }
+    }
+
+    /// Percentage of crates with fewer total downloads than `downloads`.
+    #[must_use]
+    pub fn downloads_percentile(&self, downloads: u64) -> f64 {
+        percentile(&self.downloads, downloads)
+    }
+
+    /// Percentage of crates that are younger than a crate created at `crate_created_at`.
+    #[must_use]
+    pub fn age_percentile(&self, crate_created_at: DateTime<Utc>) -> f64 {
+        let age_days = u64::try_from((self.created_at - crate_created_at).num_days()).unwrap_or(0);
+        percentile(&self.age_days, age_days)
+    }
+
+    /// Percentage of crates with fewer dependents than `dependents`.
+    #[must_use]
+    pub fn dependents_percentile(&self, dependents: u64) -> f64 {
+        percentile(&self.dependents, dependents)
+    }
+}
+
+/// Count the unique crates depending on each crate, leaving out crates without dependents.
+fn dependent_counts(table_mgr: &TableMgr) -> HashMap<CrateId, u64> {
+    let version_to_crate: HashMap<VersionId, CrateId> = table_mgr
+        .versions_table()
+        .iter_lean()
+        .map(|(row, _)| (row.id, row.crate_id))
+        .collect();
+
+    let mut dependent_pairs: HashSet<(CrateId, CrateId)> = HashSet::default();
+    for (row, _) in table_mgr.dependencies_table().iter() {
+        if let Some(&dependent) = version_to_crate.get(&row.version_id) {
+            let _ = dependent_pairs.insert((row.crate_id, dependent));
+        }
+    }
+
+    let mut dependent_counts: HashMap<CrateId, u64> = HashMap::default();
+    for (depended_upon, _) in dependent_pairs {
+        *dependent_counts.entry(depended_upon).or_insert(0) += 1;
+    }
+
+    dependent_counts
+}
+
+/// Reduce a set of values to `NUM_QUANTILES` evenly spaced quantiles.
+fn quantiles(mut values: Vec<u64>) -> Vec<u64> {
+    if values.is_empty() {
+        return values;
+    }
+
+    values.sort_unstable();
+    (0..NUM_QUANTILES).map(|i| values[i * values.len() / NUM_QUANTILES]).collect()
+}
+
+/// Percentage of the quantiles strictly below `value`.
+#[expect(clippy::cast_precision_loss, reason = "Quantile counts are tiny")]
+fn percentile(quantiles: &[u64], value: u64) -> f64 {
+    if quantiles.is_empty() {
+        return 0.0;
+    }
+
+    let below = quantiles.partition_point(|&q| q < value);
+    below as f64 * 100.0 / quantiles.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quantiles() {
+        assert!(quantiles(Vec::new()).is_empty());
+
+        let q = quantiles((0..10_000).rev().collect());
+        assert_eq!(q.len(), NUM_QUANTILES);
+        assert_eq!(q[0], 0);
+        assert_eq!(q[1], 10);
+        assert_eq!(q[NUM_QUANTILES - 1], 9990);
+    }
+
+    #[test]
+    fn test_percentile() {
+        let q = quantiles((0..10_000).collect());
+        assert!(percentile(&q, 0).abs() < f64::EPSILON);
+        assert!((percentile(&q, 5000) - 50.0).abs() < f64::EPSILON);
+        assert!((percentile(&q, 9001) - 90.1).abs() < 1e-9);
+        assert!((percentile(&q, 1_000_000) - 100.0).abs() < f64::EPSILON);
+        assert!(percentile(&[], 42).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_percentile_with_ties() {
+        // Most crates have no dependents, so none of them should rank above the others
+        let mut values = vec![0; 900];
+        values.extend(1..=100);
+        let q = quantiles(values);
+        assert!(percentile(&q, 0).abs() < f64::EPSILON);
+        assert!((percentile(&q, 1) - 90.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_age_percentile() {
+        let now = DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let distributions = Distributions {
+            created_at: now,
+            downloads: Vec::new(),
+            age_days: quantiles((0..1000).collect()),
+            dependents: Vec::new(),
+        };
+
+        assert!(distributions.age_percentile(now).abs() < f64::EPSILON);
+        assert!((distributions.age_percentile(now - chrono::Duration::days(250)) - 25.0).abs() < f64::EPSILON);
+        assert!(distributions.age_percentile(now + chrono::Duration::days(5)).abs() < f64::EPSILON);
+    }
+}
@@ -6,6 +6,8 @@
 mod crate_overall_data;
 mod crate_version_data;
 mod crates_data;
+mod dependents_history;
+mod distributions;
 mod owner;
 mod owner_kind;
 mod provider;
@@ -17,4 +19,9 @@ pub use crate_overall_data::CrateOverallData;
 #[cfg(test)]
 pub use crate_version_data::CrateVersionData;
 pub use crates_data::CratesData;
+#[cfg(test)]
+pub use owner::Owner;
+pub use owner::owner_repo_mismatch;
+#[cfg(test)]
+pub use owner_kind::OwnerKind;
 pub use provider::Provider;
@@ -1,14 +1,18 @@
 //! Owner type.
 
 use super::owner_kind::OwnerKind;
+use crate::facts::RepoSpec;
+use crate::facts::interner;
 use compact_str::CompactString;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 /// A crate owner (can be a user or team).
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Owner {
     /// The login name of the team or user.
-    pub login: CompactString,
+    #[serde(deserialize_with = "interner::deserialize")]
+    pub login: Arc<str>,
 
     /// The kind of the owner (`user` or `team`).
     pub kind: OwnerKind,
@@ -16,3 +20,80 @@ pub struct Owner {
     /// The display name of the team or user.
     pub name: Option<CompactString>,
 }
+
+impl Owner {
+    /// The GitHub account this owner maps to.
+    ///
+    /// Users are known by their GitHub login, while teams have logins of the form `github:<org>:<team>`,
+    /// in which case the organization is returned.
+    #[must_use]
+    pub fn github_account(&self) -> Option<&str> {
+        match self.kind {
+            OwnerKind::User => Some(&self.login),
+            OwnerKind::Team => {
+                let mut parts = self.login.split(':');
+                match (parts.next(), parts.next()) {
+                    (Some("github"), Some(org)) if !org.is_empty() => Some(org),
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
+/// Determine whether none of a crate's owners appears related to the GitHub repository it links to.
+///
+/// Returns `None` when the repository isn't hosted on GitHub or when the crate has no known owners, since
+/// there is then nothing to compare.
+#[must_use]
+pub fn owner_repo_mismatch(owners: &[Owner], repo: &RepoSpec) -> Option<bool> {
+    if !repo.host().eq_ignore_ascii_case("github.com") || owners.is_empty() {
+        return None;
+    }
+
+    Some(
+        !owners
+            .iter()
+            .filter_map(Owner::github_account)
+            .any(|account| account.eq_ignore_ascii_case(repo.owner())),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use url::Url;
+
+    fn owner(login: &str, kind: OwnerKind) -> Owner {
+        Owner {
+            login: login.into(),
+            kind,
+            name: None,
+        }
+    }
+
+    fn repo(url: &str) -> RepoSpec {
+        RepoSpec::parse(&Url::parse(url).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_github_account() {
+        assert_eq!(owner("dtolnay", OwnerKind::User).github_account(), Some("dtolnay"));
+        assert_eq!(owner("github:rust-lang:libs", OwnerKind::Team).github_account(), Some("rust-lang"));
+        assert_eq!(owner("gitlab:group:team", OwnerKind::Team).github_account(), None);
+    }
+
+    #[test]
+    fn test_owner_repo_mismatch() {
+        let tokio = repo("https://github.com/tokio-rs/tokio");
+
+        assert_eq!(owner_repo_mismatch(&[owner("github:Tokio-RS:core", OwnerKind::Team)], &tokio), Some(false));
+        assert_eq!(owner_repo_mismatch(&[owner("carllerche", OwnerKind::User)], &tokio), Some(true));
+        assert_eq!(owner_repo_mismatch(&[owner("tokio-rs", OwnerKind::User)], &tokio), Some(false));
+        assert_eq!(owner_repo_mismatch(&[], &tokio), None);
+        assert_eq!(
+            owner_repo_mismatch(&[owner("someone", OwnerKind::User)], &repo("https://codeberg.org/someone/thing")),
+            None
+        );
+    }
+}
@@ -1,23 +1,26 @@
 use super::crate_overall_data::CrateOverallData;
 use super::crate_version_data::CrateVersionData;
 use super::crates_data::CratesData;
+use super::dependents_history::DependentsHistory;
+use super::distributions::Distributions;
 use super::owner::Owner;
 use super::owner_kind::OwnerKind as PublicOwnerKind;
 use super::tables::OwnerKind as TableOwnerKind;
 use super::tables::{
-    CategoriesTableIndex, CategoryId, CrateId, CratesTableIndex, KeywordId, KeywordsTableIndex, Table, TableMgr, TeamId, TeamsTableIndex,
-    UserId, UsersTableIndex, VersionId, VersionsTableIndex,
+    CategoriesTableIndex, CategoryId, CrateId, CratesTableIndex, DependencyKind, KeywordId, KeywordsTableIndex, Table, TableMgr, TeamId,
+    TeamsTableIndex, UserId, UsersTableIndex, VersionId, VersionsTableIndex,
 };
 use crate::Result;
 use crate::facts::CrateRef;
 use crate::facts::ProviderResult;
 use crate::facts::crate_spec::CrateSpec;
+use crate::facts::interner::intern;
 use crate::facts::progress::Progress;
 use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use compact_str::{CompactString, ToCompactString};
 use core::fmt::Debug;
 use core::time::Duration;
-use semver::Version as SemverVersion;
+use semver::{Version as SemverVersion, VersionReq};
 use std::collections::BTreeMap;
 use crate::{HashMap, HashSet, hash_map_with_capacity, hash_set_with_capacity};
 use std::path::Path;
@@ -39,6 +42,8 @@ pub const DEFAULT_DUMP_URL: &str = "https://static.crates.io/db-dump.tar.gz";
 #[derive(Debug, Clone)]
 pub struct Provider {
     table_mgr: Arc<TableMgr>,
+    distributions: Arc<Distributions>,
+    dependents_history: Arc<DependentsHistory>,
     now: DateTime<Utc>,
 }
 
@@ -53,6 +58,8 @@ struct PerCrateData {
     versions_last_90_days: u64,
     versions_last_180_days: u64,
     versions_last_365_days: u64,
+    dep_update_latency_days: Option<u64>,
+    releases: Vec<SemverVersion>,
 }
 
 // Type aliases for complex return types from phase methods
@@ -78,18 +85,36 @@ impl Provider {
         progress: Arc<dyn Progress>,
         now: DateTime<Utc>,
         ignore_cached: bool,
+        read_only: bool,
         dump_url: Option<&str>,
     ) -> Result<Self> {
         let cache_dir = cache_dir.as_ref().to_path_buf();
         let url = Url::parse(dump_url.unwrap_or(DEFAULT_DUMP_URL))?;
-        let table_mgr = TableMgr::new(&url, &cache_dir, cache_ttl, now, ignore_cached, progress).await?;
+        let table_mgr = Arc::new(TableMgr::new(&url, &cache_dir, cache_ttl, now, ignore_cached, read_only, Arc::clone(&progress)).await?);
+
+        progress.set_indeterminate(Box::new(|| "Preparing crates.io distributions".to_string()));
+        let mgr = Arc::clone(&table_mgr);
+        let (distributions, dependents_history) = tokio::task::spawn_blocking(move || {
+            let distributions = Distributions::load_or_compute(&cache_dir, &mgr, read_only);
+            (distributions, DependentsHistory::load(&cache_dir))
+        })
+        .await
+        .expect("tasks must not panic");
 
         Ok(Self {
-            table_mgr: Arc::new(table_mgr),
+            table_mgr,
+            distributions: Arc::new(distributions),
+            dependents_history: Arc::new(dependents_history),
             now,
         })
     }
 
+    /// Creation time of the crates.io database dump the provider works from.
+    #[must_use]
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.table_mgr.created_at()
+    }
+
     /// Get crate data for multiple crates.
     ///
     /// Accepts `CrateRef` which may or may not have a version specified. If no version is specified,
@@ -99,7 +124,7 @@ impl Provider {
     /// the resolved version. Each result indicates whether the crate was found, not found, or the
     /// version was not found.
     ///
-    /// This method orchestrates an 8-phase optimized query pipeline with parallelization:
+    /// This method orchestrates a 9-phase optimized query pipeline with parallelization:
     /// 1. Build crate name to ID maps and allocate per-crate data structures
     /// 2. Build version requirement maps and track crates needing latest version
     /// 3. Discover dependency relationships for dependent counting
@@ -107,7 +132,8 @@ impl Provider {
     /// 5. Load lookup tables in parallel (categories, keywords, users, teams)
     /// 6. Populate crate data by scanning join tables (owners, categories, keywords)
     /// 7. Collect download statistics in parallel (overall and monthly)
-    /// 8. Count dependents and assemble final results
+    /// 8. Count dependents
+    /// 9. Find dependencies that only resolve to yanked releases and assemble final results
     pub async fn get_crates_data(
         &self,
         crates: &[CrateRef],
@@ -131,6 +157,64 @@ impl Provider {
             .into_iter()
     }
 
+    /// Names of the crates owned by a user or team, sorted.
+    ///
+    /// `login` is matched case-insensitively against the GitHub logins of users and the logins of teams, such as
+    /// `github:rust-lang:libs`. A login without a colon also matches the teams of the GitHub organization of that name.
+    pub async fn owned_crates(&self, login: &str) -> Vec<CompactString> {
+        let provider = self.clone();
+        let login = login.to_compact_string();
+        tokio::task::spawn_blocking(move || provider.find_owned_crates(&login))
+            .await
+            .expect("tasks must not panic")
+    }
+
+    fn find_owned_crates(&self, login: &str) -> Vec<CompactString> {
+        let users: HashSet<UserId> = self
+            .table_mgr
+            .users_table()
+            .iter()
+            .filter(|(row, _)| row.gh_login.eq_ignore_ascii_case(login))
+            .map(|(row, _)| row.id)
+            .collect();
+        let teams: HashSet<TeamId> = self
+            .table_mgr
+            .teams_table()
+            .iter()
+            .filter(|(row, _)| team_login_matches(row.login, login))
+            .map(|(row, _)| row.id)
+            .collect();
+
+        let crate_ids: HashSet<CrateId> = self
+            .table_mgr
+            .crate_owners_table()
+            .iter()
+            .filter(|(row, _)| match row.owner() {
+                TableOwnerKind::User(user_id) => users.contains(&user_id),
+                TableOwnerKind::Team(team_id) => teams.contains(&team_id),
+            })
+            .map(|(row, _)| row.crate_id)
+            .collect();
+
+        let mut names: Vec<CompactString> = self
+            .table_mgr
+            .crates_table()
+            .iter()
+            .filter(|(row, _)| crate_ids.contains(&row.id))
+            .map(|(row, _)| row.name.into())
+            .collect();
+        names.sort_unstable();
+
+        log::info!(
+            target: LOG_TARGET,
+            "Found {} crate(s) owned by '{login}' across {} user(s) and {} team(s)",
+            names.len(),
+            users.len(),
+            teams.len()
+        );
+        names
+    }
+
     fn collect_crate_data(&self, requested: Vec<CrateRef>, suggestions: bool) -> Vec<(CrateSpec, ProviderResult<CratesData>)> {
         let start_time = std::time::Instant::now();
         let requested_names: HashSet<&str> = requested.iter().map(CrateRef::name).collect();
@@ -166,6 +250,12 @@ impl Provider {
         // Phase 8: Count dependents
         count_dependents(&mut crate_data, &crate_to_dependent_versions, &version_id_to_crate_id);
 
+        // Phase 9: Find dependencies pinned to yanked releases
+        let yanked_dependencies = self.phase9_find_yanked_dependencies(&version_ids);
+
+        // Phase 10: Measure how quickly crates adopt new releases of their dependencies
+        self.phase10_measure_dependency_update_latency(&mut crate_data, &all_version_to_crate);
+
         let results: Vec<_> = requested
             .into_iter()
             .map(|crate_ref| {
@@ -182,6 +272,7 @@ impl Provider {
                     &teams,
                     &version_monthly_downloads,
                     &crate_monthly_downloads,
+                    &yanked_dependencies,
                     &suggestions_map,
                 )
             })
@@ -269,6 +360,8 @@ impl Provider {
                             versions_last_90_days: 0,
                             versions_last_180_days: 0,
                             versions_last_365_days: 0,
+                            dep_update_latency_days: None,
+                            releases: Vec::new(),
                         },
                     );
 
@@ -457,6 +550,10 @@ impl Provider {
                 let row = self.table_mgr.versions_table().get(index);
 
                 let _ = all_version_to_crate.insert(lean_row.id, lean_row.crate_id);
+                if !row.yanked && row.num.pre.is_empty() {
+                    data.releases.push(row.num.clone());
+                }
+
                 if row.created_at >= cutoff_365 {
                     data.versions_last_365_days += 1;
                     if row.created_at >= cutoff_180 {
@@ -571,6 +668,168 @@ impl Provider {
         self.aggregate_all_monthly_downloads(version_ids, all_version_to_crate, crate_data)
     }
 
+    /// Phase 9: Find dependencies whose requirements only match yanked releases.
+    ///
+    /// Scans the dependencies table for the requirements of the queried versions, ignoring
+    /// dev-dependencies since they don't affect users of a crate. Then scans the versions table
+    /// for the releases of the depended-upon crates to see which requirements can only be
+    /// satisfied by yanked releases, and finally the crates table to name those dependencies.
+    ///
+    /// Returns a map of queried version ID to the sorted names of its yanked-only dependencies.
+    fn phase9_find_yanked_dependencies(&self, version_ids: &HashSet<VersionId>) -> HashMap<VersionId, Vec<CompactString>> {
+        let mut requirements: HashMap<CrateId, Vec<(VersionId, VersionReq)>> = HashMap::default();
+        for (row, _) in self.table_mgr.dependencies_table().iter() {
+            if row.kind() == DependencyKind::Dev || !version_ids.contains(&row.version_id) {
+                continue;
+            }
+
+            match VersionReq::parse(row.req) {
+                Ok(req) => requirements.entry(row.crate_id).or_default().push((row.version_id, req)),
+                Err(e) => {
+                    log::debug!(target: LOG_TARGET_DB_CONTENT, "Invalid requirement '{}' on crate ID {:?} for version ID {:?}: {e}", row.req, row.crate_id, row.version_id);
+                }
+            }
+        }
+
+        if requirements.is_empty() {
+            return HashMap::default();
+        }
+
+        // For every (dependent version, dependency) pair with at least one matching release, whether any match isn't yanked
+        let mut has_unyanked_match: HashMap<(VersionId, CrateId), bool> = HashMap::default();
+        for (lean_row, index) in self.table_mgr.versions_table().iter_lean() {
+            if let Some(reqs) = requirements.get(&lean_row.crate_id) {
+                let row = self.table_mgr.versions_table().get(index);
+                for (version_id, req) in reqs {
+                    if req.matches(&row.num) {
+                        *has_unyanked_match.entry((*version_id, lean_row.crate_id)).or_default() |= !row.yanked;
+                    }
+                }
+            }
+        }
+
+        let yanked_only: Vec<(VersionId, CrateId)> = has_unyanked_match
+            .into_iter()
+            .filter(|(_, unyanked)| !unyanked)
+            .map(|(pair, _)| pair)
+            .collect();
+
+        if yanked_only.is_empty() {
+            return HashMap::default();
+        }
+
+        let needed_names: HashSet<CrateId> = yanked_only.iter().map(|(_, crate_id)| *crate_id).collect();
+        let mut names: HashMap<CrateId, CompactString> = hash_map_with_capacity(needed_names.len());
+        for (row, _) in self.table_mgr.crates_table().iter() {
+            if needed_names.contains(&row.id) {
+                let _ = names.insert(row.id, row.name.to_compact_string());
+                if names.len() == needed_names.len() {
+                    break;
+                }
+            }
+        }
+
+        let mut result: HashMap<VersionId, Vec<CompactString>> = HashMap::default();
+        for (version_id, crate_id) in yanked_only {
+            if let Some(name) = names.get(&crate_id) {
+                result.entry(version_id).or_default().push(name.clone());
+            }
+        }
+
+        for deps in result.values_mut() {
+            deps.sort();
+        }
+
+        result
+    }
+
+    /// Phase 10: Measure how quickly crates adopt new releases of their dependencies.
+    ///
+    /// Scans the dependencies table for the requirements of every version of our crates, ignoring
+    /// dev-dependencies, and then the versions table for the publication times of those versions and
+    /// of the releases of the depended-upon crates. Each crate is given the median number of days it
+    /// took to allow the dependency releases its requirements excluded when they came out.
+    fn phase10_measure_dependency_update_latency(
+        &self,
+        crate_data: &mut HashMap<CrateId, PerCrateData>,
+        all_version_to_crate: &HashMap<VersionId, CrateId>,
+    ) {
+        // Requirements of our versions, keyed by (dependent crate, dependency) so only actual pairs are visited
+        let mut requirements: HashMap<(CrateId, CrateId), HashMap<VersionId, VersionReq>> = HashMap::default();
+        let mut dependencies: HashSet<CrateId> = HashSet::default();
+        for (row, _) in self.table_mgr.dependencies_table().iter() {
+            if row.kind() == DependencyKind::Dev {
+                continue;
+            }
+
+            let Some(&crate_id) = all_version_to_crate.get(&row.version_id) else {
+                continue;
+            };
+
+            if let Ok(req) = VersionReq::parse(row.req) {
+                let _ = requirements
+                    .entry((crate_id, row.crate_id))
+                    .or_default()
+                    .insert(row.version_id, req);
+                let _ = dependencies.insert(row.crate_id);
+            }
+        }
+
+        if requirements.is_empty() {
+            return;
+        }
+
+        let mut versions: HashMap<CrateId, Vec<(DateTime<Utc>, VersionId)>> = hash_map_with_capacity(crate_data.len());
+        let mut releases: HashMap<CrateId, Vec<(DateTime<Utc>, SemverVersion)>> = hash_map_with_capacity(dependencies.len());
+        for (lean_row, index) in self.table_mgr.versions_table().iter_lean() {
+            let ours = all_version_to_crate.contains_key(&lean_row.id);
+            let depended_upon = dependencies.contains(&lean_row.crate_id);
+            if !ours && !depended_upon {
+                continue;
+            }
+
+            let row = self.table_mgr.versions_table().get(index);
+            if ours {
+                versions.entry(lean_row.crate_id).or_default().push((row.created_at, lean_row.id));
+            }
+
+            if depended_upon && !row.yanked {
+                releases.entry(lean_row.crate_id).or_default().push((row.created_at, row.num.clone()));
+            }
+        }
+
+        for list in versions.values_mut() {
+            list.sort_by_key(|(created_at, _)| *created_at);
+        }
+
+        for list in releases.values_mut() {
+            list.sort_by_key(|(created_at, _)| *created_at);
+        }
+
+        let mut latencies: HashMap<CrateId, Vec<i64>> = hash_map_with_capacity(versions.len());
+        for ((crate_id, dependency), reqs) in &requirements {
+            let (Some(crate_versions), Some(dependency_releases)) = (versions.get(crate_id), releases.get(dependency)) else {
+                continue;
+            };
+
+            let dependent: Vec<_> = crate_versions
+                .iter()
+                .map(|(created_at, version_id)| (*created_at, reqs.get(version_id)))
+                .collect();
+
+            latencies
+                .entry(*crate_id)
+                .or_default()
+                .extend(adoption_latencies(&dependent, dependency_releases, self.now));
+        }
+
+        for (crate_id, days) in latencies {
+            if let Some(data) = crate_data.get_mut(&crate_id) {
+                data.dep_update_latency_days = median_days(days);
+            }
+        }
+    }
+
     /// Assemble a single query result from collected data.
     ///
     /// Checks for crate existence and version existence, then assembles the full result
@@ -590,6 +849,7 @@ impl Provider {
         teams: &HashMap<TeamId, TeamsTableIndex>,
         version_monthly_downloads: &HashMap<VersionId, Vec<(NaiveDate, u64)>>,
         crate_monthly_downloads: &HashMap<CrateId, Vec<(NaiveDate, u64)>>,
+        yanked_dependencies: &HashMap<VersionId, Vec<CompactString>>,
         suggestions: &HashMap<CompactString, Vec<CompactString>>,
     ) -> (CrateSpec, ProviderResult<CratesData>) {
         // Check if the crate exists
@@ -637,6 +897,7 @@ impl Provider {
             teams,
             version_monthly_downloads,
             crate_monthly_downloads,
+            yanked_dependencies,
         );
 
         // Build the CrateSpec with the resolved version and repository information if available
@@ -769,12 +1030,54 @@ impl Provider {
         (version_result, crate_result)
     }
 
+    /// Resolve the owners of a crate to the users and teams they name.
+    fn resolve_owners(
+        &self,
+        crate_name: &str,
+        owners: &[TableOwnerKind],
+        users: &HashMap<UserId, UsersTableIndex>,
+        teams: &HashMap<TeamId, TeamsTableIndex>,
+    ) -> Vec<Owner> {
+        owners
+            .iter()
+            .filter_map(|table_owner_kind| match table_owner_kind {
+                TableOwnerKind::User(user_id) => {
+                    if let Some(&user_index) = users.get(user_id) {
+                        let row = self.table_mgr.users_table().get(user_index);
+                        Some(Owner {
+                            login: intern(row.gh_login),
+                            kind: PublicOwnerKind::User,
+                            name: (!row.name.is_empty()).then(|| row.name.into()),
+                        })
+                    } else {
+                        log::debug!(target: LOG_TARGET_DB_CONTENT, "User ID {user_id:?} for crate '{crate_name}' not found in users table");
+                        None
+                    }
+                }
+                TableOwnerKind::Team(team_id) => {
+                    if let Some(&team_index) = teams.get(team_id) {
+                        let row = self.table_mgr.teams_table().get(team_index);
+                        let name = (!row.name.is_empty()).then(|| row.name.into());
+                        Some(Owner {
+                            login: intern(row.login),
+                            kind: PublicOwnerKind::Team,
+                            name,
+                        })
+                    } else {
+                        log::debug!(target: LOG_TARGET_DB_CONTENT, "Team ID {team_id:?} for crate '{crate_name}' not found in teams table");
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+
     #[expect(clippy::too_many_arguments, reason = "Helper method needs access to many data structures")]
     fn assemble_result(
         &self,
         crate_name: &str,
-        _version: &SemverVersion,
-        _timestamp: DateTime<Utc>,
+        version: &SemverVersion,
+        timestamp: DateTime<Utc>,
         crate_id: CrateId,
         version_id: VersionId,
         version_index: VersionsTableIndex,
@@ -785,63 +1088,34 @@ impl Provider {
         teams: &HashMap<TeamId, TeamsTableIndex>,
         version_monthly_downloads: &HashMap<VersionId, Vec<(NaiveDate, u64)>>,
         crate_monthly_downloads: &HashMap<CrateId, Vec<(NaiveDate, u64)>>,
+        yanked_dependencies: &HashMap<VersionId, Vec<CompactString>>,
     ) -> CratesData {
+        let per_crate_data = crate_data.get(&crate_id).expect("Crate data must exist");
+
         let version_row = self.table_mgr.versions_table().get(version_index);
         let version_data = CrateVersionData {
             description: version_row.description.into(),
             homepage: version_row.homepage(),
             documentation: version_row.documentation(),
-            license: version_row.license.into(),
+            license: intern(version_row.license),
             rust_version: version_row.rust_version.into(),
             edition: version_row.edition(),
             features: version_row.features(),
             created_at: version_row.created_at,
             updated_at: version_row.updated_at,
             yanked: version_row.yanked,
+            yanked_dependencies: yanked_dependencies.get(&version_id).cloned().unwrap_or_default(),
+            previous_version: previous_release(&per_crate_data.releases, version),
             downloads: version_row.downloads,
             monthly_downloads: version_monthly_downloads.get(&version_id).cloned().unwrap_or_default(),
         };
 
-        let per_crate_data = crate_data.get(&crate_id).expect("Crate data must exist");
-
         let crate_row = self.table_mgr.crates_table().get(per_crate_data.crate_index);
         let created_at = crate_row.created_at;
         let updated_at = crate_row.updated_at;
         let repository = crate_row.repository();
 
-        let owners: Vec<Owner> = per_crate_data
-            .owners
-            .iter()
-            .filter_map(|table_owner_kind| match table_owner_kind {
-                TableOwnerKind::User(user_id) => {
-                    if let Some(&user_index) = users.get(user_id) {
-                        let row = self.table_mgr.users_table().get(user_index);
-                        Some(Owner {
-                            login: row.gh_login.into(),
-                            kind: PublicOwnerKind::User,
-                            name: (!row.name.is_empty()).then(|| row.name.into()),
-                        })
-                    } else {
-                        log::debug!(target: LOG_TARGET_DB_CONTENT, "User ID {user_id:?} for crate '{crate_name}' not found in users table");
-                        None
-                    }
-                }
-                TableOwnerKind::Team(team_id) => {
-                    if let Some(&team_index) = teams.get(team_id) {
-                        let row = self.table_mgr.teams_table().get(team_index);
-                        let name = (!row.name.is_empty()).then(|| row.name.into());
-                        Some(Owner {
-                            login: row.login.into(),
-                            kind: PublicOwnerKind::Team,
-                            name,
-                        })
-                    } else {
-                        log::debug!(target: LOG_TARGET_DB_CONTENT, "Team ID {team_id:?} for crate '{crate_name}' not found in teams table");
-                        None
-                    }
-                }
-            })
-            .collect();
+        let owners = self.resolve_owners(crate_name, &per_crate_data.owners, users, teams);
 
         let category_list: Vec<CompactString> = per_crate_data
             .categories
@@ -882,10 +1156,15 @@ impl Provider {
                 owners,
                 monthly_downloads: crate_monthly_downloads.get(&crate_id).cloned().unwrap_or_default(),
                 downloads: per_crate_data.downloads,
+                downloads_percentile: self.distributions.downloads_percentile(per_crate_data.downloads),
+                age_percentile: self.distributions.age_percentile(created_at),
                 dependents: per_crate_data.dependents,
+                dependents_percentile: self.distributions.dependents_percentile(per_crate_data.dependents),
+                dependents_trend: self.dependents_history.trend(crate_id, per_crate_data.dependents, timestamp),
                 versions_last_90_days: per_crate_data.versions_last_90_days,
                 versions_last_180_days: per_crate_data.versions_last_180_days,
                 versions_last_365_days: per_crate_data.versions_last_365_days,
+                dep_update_latency_days: per_crate_data.dep_update_latency_days,
             },
         )
     }
@@ -907,6 +1186,18 @@ fn monthly_btree_to_vec<K: Eq + core::hash::Hash>(
         .collect()
 }
 
+/// Whether the login of a team, such as `github:rust-lang:libs`, matches the login looked up.
+///
+/// Besides the full login, a bare GitHub organization name matches all of the organization's teams.
+fn team_login_matches(team_login: &str, login: &str) -> bool {
+    if team_login.eq_ignore_ascii_case(login) {
+        return true;
+    }
+
+    let mut parts = team_login.split(':');
+    !login.contains(':') && parts.next() == Some("github") && parts.next().is_some_and(|org| org.eq_ignore_ascii_case(login))
+}
+
 /// Normalize a crate name for similarity matching by converting to lowercase and removing separators.
 fn normalize_name_into(name: &str, buffer: &mut CompactString) {
     buffer.clear();
@@ -952,5 +1243,152 @@ fn count_dependents(
     }
 }
 
+/// Compute how many days a crate took to allow each release of a dependency that its requirement excluded.
+///
+/// `dependent` holds the versions of the crate in chronological order along with their requirement on the
+/// dependency, if any, while `releases` holds the releases of the dependency in chronological order. Only
+/// releases newer than anything the requirement in effect allowed are considered. Releases which the latest
+/// version still excludes count up to `now`, whereas those the crate stopped depending on are ignored.
+fn adoption_latencies(
+    dependent: &[(DateTime<Utc>, Option<&VersionReq>)],
+    releases: &[(DateTime<Utc>, SemverVersion)],
+    now: DateTime<Utc>,
+) -> Vec<i64> {
+    let mut latencies = Vec::new();
+
+    // The requirement in effect and the newest earlier release it allows, carried over while the requirement is unchanged
+    let mut allowed: Option<(&VersionReq, Option<&SemverVersion>)> = None;
+
+    for (i, (released_at, num)) in releases.iter().enumerate() {
+        let current = dependent.partition_point(|(created_at, _)| created_at <= released_at);
+        let Some((_, Some(req))) = current.checked_sub(1).and_then(|index| dependent.get(index)) else {
+            allowed = None;
+            continue;
+        };
+
+        let newest_allowed = match allowed {
+            Some((tracked, newest)) if tracked == *req => newest,
+            _ => releases[..i].iter().map(|(_, n)| n).filter(|n| req.matches(n)).max(),
+        };
+
+        let num_allowed = req.matches(num);
+        allowed = Some((req, if num_allowed { newest_allowed.max(Some(num)) } else { newest_allowed }));
+
+        if !num.pre.is_empty() || num_allowed || newest_allowed.is_none_or(|allowed| num <= allowed) {
+            continue;
+        }
+
+        let mut adopted_at = Some(now);
+        for (created_at, req) in &dependent[current..] {
+            match req {
+                None => {
+                    adopted_at = None;
+                    break;
+                }
+                Some(req) if req.matches(num) => {
+                    adopted_at = Some(*created_at);
+                    break;
+                }
+                Some(_) => {}
+            }
+        }
+
+        if let Some(adopted_at) = adopted_at {
+            latencies.push((adopted_at - *released_at).num_days());
+        }
+    }
+
+    latencies
+}
+
+/// The median of a list of day counts, or `None` if the list is empty.
+fn median_days(mut days: Vec<i64>) -> Option<u64> {
+    days.sort_unstable();
+    days.get(days.len() / 2).map(|&median| u64::try_from(median).unwrap_or(0))
+}
+
+/// The most recent of `releases` which precedes `version`, if any.
+fn previous_release(releases: &[SemverVersion], version: &SemverVersion) -> Option<SemverVersion> {
+    releases.iter().filter(|release| *release < version).max().cloned()
+}
+
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(&format!("{s}T00:00:00Z")).unwrap().with_timezone(&Utc)
+    }
+
+    fn releases(list: &[(&str, &str)]) -> Vec<(DateTime<Utc>, SemverVersion)> {
+        list.iter().map(|(d, v)| (date(d), v.parse().unwrap())).collect()
+    }
+
+    #[test]
+    fn test_adoption_latencies() {
+        let v1 = VersionReq::parse("^1.0").unwrap();
+        let v2 = VersionReq::parse("^2.0").unwrap();
+        let releases = releases(&[
+            ("2024-01-01", "1.0.0"),
+            ("2024-02-01", "1.1.0"),
+            ("2024-03-01", "2.0.0-rc.1"),
+            ("2024-04-01", "2.0.0"),
+            ("2024-05-01", "1.2.0"),
+            ("2024-06-01", "3.0.0"),
+        ]);
+        let dependent = [
+            (date("2024-01-15"), Some(&v1)),
+            (date("2024-04-11"), Some(&v1)),
+            (date("2024-05-01"), Some(&v2)),
+        ];
+
+        // 2.0.0 was adopted after 30 days, and 3.0.0 is still pending after 30 days. The 1.1.0 and 1.2.0
+        // releases were allowed, and the release candidate doesn't count.
+        assert_eq!(adoption_latencies(&dependent, &releases, date("2024-07-01")), [30, 30]);
+    }
+
+    #[test]
+    fn test_adoption_latencies_ignores_dropped_dependencies() {
+        let v1 = VersionReq::parse("^1.0").unwrap();
+        let releases = releases(&[("2024-01-01", "1.0.0"), ("2024-02-01", "2.0.0")]);
+        let dependent = [(date("2024-01-15"), Some(&v1)), (date("2024-03-01"), None)];
+
+        assert!(adoption_latencies(&dependent, &releases, date("2024-07-01")).is_empty());
+    }
+
+    #[test]
+    fn test_adoption_latencies_ignores_releases_before_first_version() {
+        let v2 = VersionReq::parse("^2.0").unwrap();
+        let releases = releases(&[("2024-01-01", "1.0.0"), ("2024-02-01", "2.0.0")]);
+        let dependent = [(date("2024-03-01"), Some(&v2))];
+
+        assert!(adoption_latencies(&dependent, &releases, date("2024-07-01")).is_empty());
+    }
+
+    #[test]
+    fn test_median_days() {
+        assert_eq!(median_days(vec![]), None);
+        assert_eq!(median_days(vec![30, 5, 100]), Some(30));
+        assert_eq!(median_days(vec![-1]), Some(0));
+    }
+
+    #[test]
+    fn test_previous_release() {
+        let releases: Vec<SemverVersion> = ["0.9.0", "1.0.0", "1.1.0", "2.0.0"].iter().map(|v| v.parse().unwrap()).collect();
+
+        assert_eq!(previous_release(&releases, &"1.1.0".parse().unwrap()), Some("1.0.0".parse().unwrap()));
+        assert_eq!(previous_release(&releases, &"1.2.0-rc.1".parse().unwrap()), Some("1.1.0".parse().unwrap()));
+        assert_eq!(previous_release(&releases, &"0.9.0".parse().unwrap()), None);
+    }
+
+    #[test]
+    fn test_team_login_matches() {
+        assert!(team_login_matches("github:rust-lang:libs", "github:rust-lang:libs"));
+        assert!(team_login_matches("github:rust-lang:libs", "GitHub:Rust-Lang:Libs"));
+        assert!(team_login_matches("github:rust-lang:libs", "rust-lang"));
+        assert!(team_login_matches("github:Tokio-RS:core", "tokio-rs"));
+        assert!(!team_login_matches("github:rust-lang:libs", "github:rust-lang:compiler"));
+        assert!(!team_login_matches("github:rust-lang:libs", "rust"));
+        assert!(!team_login_matches("gitlab:rust-lang:libs", "rust-lang"));
+    }
+}
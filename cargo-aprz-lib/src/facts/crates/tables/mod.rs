@@ -83,7 +83,7 @@ pub use crate_owners_table::{CrateOwnerRow, CrateOwnersTable, OwnerKind};
 pub use crates_categories_table::{CratesCategoriesRow, CratesCategoriesTable};
 pub use crates_keywords_table::{CratesKeywordsRow, CratesKeywordsTable};
 pub use crates_table::{CrateRow, CratesTable, CratesTableIndex};
-pub use dependencies_table::DependenciesTable;
+pub use dependencies_table::{DependenciesTable, DependencyKind};
 pub use ids::{CategoryId, CrateId, KeywordId, TeamId, UserId, VersionId};
 pub use keywords_table::{KeywordsTable, KeywordsTableIndex};
 pub use row_iter::RowIter;
@@ -103,8 +103,5 @@ pub use metadata_table::{MetadataRow, MetadataTable};
 #[cfg(all_tables)]
 pub use reserved_crate_names_table::{ReservedCrateNamesRow, ReservedCrateNamesTable};
 
-#[cfg(all_fields)]
-pub use dependencies_table::DependencyKind;
-
 #[cfg(all_fields)]
 pub use ids::DependencyId;
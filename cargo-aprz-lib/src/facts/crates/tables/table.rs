@@ -11,10 +11,10 @@ use std::io::{BufWriter, Read as IoRead, Seek, SeekFrom, Write};
 use std::path::Path;
 
 #[cfg(all_fields)]
-const FORMAT_MAGIC: u64 = 0xC0DE_C0DE_C0DE_0010;
+const FORMAT_MAGIC: u64 = 0xC0DE_C0DE_C0DE_0012;
 
 #[cfg(not(all_fields))]
-const FORMAT_MAGIC: u64 = 0xC0DE_C0DE_C0DE_0011;
+const FORMAT_MAGIC: u64 = 0xC0DE_C0DE_C0DE_0013;
 
 pub const TABLE_HEADER_SIZE: usize = 24; // 8 bytes magic + 8 bytes count + 8 bytes timestamp
 
@@ -279,10 +279,18 @@ impl TableMgr {
         max_ttl: Duration,
         now: DateTime<Utc>,
         ignore_cached: bool,
+        read_only: bool,
         progress: Arc<dyn Progress>,
     ) -> Result<Self> {
         let tables_root = tables_root.as_ref();
 
+        // A read-only cache is used whatever its age, and never replaced by a fresh download
+        if read_only {
+            log::info!("Opening the crates database from the read-only cache");
+            return Self::open_tables_from_scratch(tables_root, Duration::MAX, now, progress.as_ref())
+                .map_err(|e| e.enrich("opening the crates database from the read-only cache"));
+        }
+
         if !ignore_cached {
             log::info!("Opening the crates database");
             let result = Self::open_tables_from_scratch(tables_root, max_ttl, now, progress.as_ref());
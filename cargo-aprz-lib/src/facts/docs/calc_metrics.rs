@@ -9,7 +9,7 @@ use crate::Result;
 use crate::facts::CrateSpec;
 use ohno::{IntoAppError, app_err};
 use regex::Regex;
-use crate::HashMap;
+use crate::{HashMap, HashSet};
 use std::io::Read;
 use std::sync::LazyLock;
 
@@ -96,6 +96,8 @@ pub fn calculate_docs_metrics(reader: impl Read, crate_spec: &CrateSpec) -> Resu
 
     log::debug!(target: LOG_TARGET, "Found rustdoc JSON format version {format_version} for {crate_spec}");
 
+    let public_api = collect_public_api(&json_value);
+
     let metrics = match format_version {
         50 => calculate_metrics_v50(json_value, crate_spec)?,
         51 => calculate_metrics_v51(json_value, crate_spec)?,
@@ -115,9 +117,44 @@ pub fn calculate_docs_metrics(reader: impl Read, crate_spec: &CrateSpec) -> Resu
 
     Ok(DocsData {
         metrics,
+        public_api,
+        api_churn: None,
     })
 }
 
+/// Collect the public items of the crate from the `paths` table of rustdoc JSON.
+///
+/// The `paths` table has kept the same shape across the supported format versions, so it's read
+/// untyped. Each item is rendered as its kind followed by its path, which is what's compared
+/// between releases.
+fn collect_public_api(json_value: &serde_json::Value) -> Vec<String> {
+    let Some(paths) = json_value.get("paths").and_then(serde_json::Value::as_object) else {
+        return Vec::new();
+    };
+
+    let mut items: Vec<String> = paths
+        .values()
+        .filter(|summary| summary.get("crate_id").and_then(serde_json::Value::as_u64) == Some(0))
+        .filter_map(|summary| {
+            let kind = summary.get("kind")?.as_str()?;
+            let path: Vec<&str> = summary.get("path")?.as_array()?.iter().filter_map(serde_json::Value::as_str).collect();
+            Some(format!("{kind} {}", path.join("::")))
+        })
+        .collect();
+
+    items.sort_unstable();
+    items.dedup();
+    items
+}
+
+/// Count the public items added or removed between two releases.
+#[must_use]
+pub fn count_api_changes(previous: &[String], current: &[String]) -> u64 {
+    let previous: HashSet<&String> = previous.iter().collect();
+    let current: HashSet<&String> = current.iter().collect();
+    previous.symmetric_difference(&current).count() as u64
+}
+
 /// Process crate items and calculate documentation metrics
 ///
 /// This generic function works with items from any rustdoc-types version by accepting
@@ -520,4 +557,29 @@ mod tests {
         assert!((data.metrics.doc_coverage_percentage - 100.0).abs() < f64::EPSILON);
         assert_eq!(data.metrics.undocumented_elements, 0);
     }
+
+    // -----------------------------------------------------------------------
+    // Public API
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn public_api_lists_local_paths() {
+        let mut json = make_rustdoc_json("my_crate", None, &[]);
+        json["paths"]["1"] = json!({ "crate_id": 0, "path": ["my_crate", "Foo"], "kind": "struct" });
+        json["paths"]["2"] = json!({ "crate_id": 1, "path": ["std", "string", "String"], "kind": "struct" });
+        let reader = serde_json::to_vec(&json).unwrap();
+
+        let data = calculate_docs_metrics(reader.as_slice(), &crate_spec("my_crate")).unwrap();
+        assert_eq!(data.public_api, ["module my_crate", "struct my_crate::Foo"]);
+    }
+
+    #[test]
+    fn api_changes_count_additions_and_removals() {
+        let previous = ["fn a".to_string(), "fn b".to_string(), "struct C".to_string()];
+        let current = ["fn a".to_string(), "fn d".to_string(), "struct C".to_string(), "struct E".to_string()];
+
+        assert_eq!(count_api_changes(&previous, &current), 3);
+        assert_eq!(count_api_changes(&previous, &previous), 0);
+        assert_eq!(count_api_changes(&[], &current), 4);
+    }
 }
@@ -3,6 +3,12 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct DocsData {
     pub metrics: DocsMetrics,
+    /// Public items of the crate, each rendered as its kind followed by its path (e.g. `function foo::bar`), sorted
+    pub public_api: Vec<String>,
+    /// Number of public items added or removed since the previous release, `None` if the comparison
+    /// couldn't be made. This is computed after collection and isn't cached.
+    #[serde(skip)]
+    pub api_churn: Option<u64>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
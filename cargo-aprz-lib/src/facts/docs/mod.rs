@@ -2,5 +2,6 @@ mod calc_metrics;
 mod docs_data;
 mod provider;
 
+pub use calc_metrics::count_api_changes;
 pub use docs_data::{DocsData, DocsMetrics};
 pub use provider::Provider;
@@ -5,7 +5,7 @@ use crate::facts::crate_spec::CrateSpec;
 use crate::facts::path_utils::sanitize_path_component;
 use crate::facts::request_tracker::{RequestTracker, TrackedTopic};
 use crate::facts::throttler::Throttler;
-use crate::facts::ProviderResult;
+use crate::facts::{Cancellation, ProviderResult};
 use futures::stream::TryStreamExt;
 use futures_util::future::join_all;
 use ohno::{EnrichableExt, IntoAppError, app_err};
@@ -27,6 +27,7 @@ pub struct Provider {
     cache: Cache,
     base_url: String,
     throttler: Arc<Throttler>,
+    cancellation: Cancellation,
 }
 
 impl Provider {
@@ -43,9 +44,23 @@ impl Provider {
             cache,
             base_url: base_url.unwrap_or(DOCS_BASE_URL).to_string(),
             throttler: Throttler::new(MAX_CONCURRENT_REQUESTS),
+            cancellation: Cancellation::new(),
         }
     }
 
+    /// Stop starting downloads once `cancellation` is cancelled.
+    #[must_use]
+    pub fn with_cancellation(mut self, cancellation: Cancellation) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// The cached document holding the documentation data of a crate, if any.
+    #[must_use]
+    pub fn cached_document(&self, crate_spec: &CrateSpec) -> Option<PathBuf> {
+        self.cache.entry_path(&Self::get_cache_filename(crate_spec))
+    }
+
     /// Get documentation data for multiple crates
     pub async fn get_docs_data(
         &self,
@@ -72,8 +87,31 @@ impl Provider {
         })
     }
 
-    async fn fetch_docs_for_crate(self, crate_spec: CrateSpec, tracker: RequestTracker) -> (CrateSpec, ProviderResult<DocsData>) {
+    /// Get the public API of a crate version, as recorded in [`DocsData::public_api`].
+    ///
+    /// This is used to look at releases other than the ones being appraised, so failures are only logged.
+    pub async fn get_public_api(&self, crate_spec: &CrateSpec) -> Option<Vec<String>> {
         let _permit = self.throttler.acquire().await;
+        match self.fetch_docs_for_crate_core(crate_spec).await {
+            ProviderResult::Found(data) => Some(data.public_api),
+            ProviderResult::Unavailable(reason) => {
+                log::debug!(target: LOG_TARGET, "Public API unavailable for {crate_spec}: {reason}");
+                None
+            }
+            ProviderResult::Error(e) => {
+                log::debug!(target: LOG_TARGET, "Could not fetch public API for {crate_spec}: {e:#}");
+                None
+            }
+            _ => None,
+        }
+    }
+
+    async fn fetch_docs_for_crate(self, crate_spec: CrateSpec, tracker: RequestTracker) -> (CrateSpec, ProviderResult<DocsData>) {
+        let Some(_permit) = self.throttler.acquire_unless_cancelled(&self.cancellation).await else {
+            tracker.complete_request(TrackedTopic::Docs);
+            return (crate_spec, ProviderResult::Cancelled);
+        };
+
         let result = self.fetch_docs_for_crate_core(&crate_spec).await;
         tracker.complete_request(TrackedTopic::Docs);
 
@@ -0,0 +1,160 @@
+//! Evidence bundles holding the documents the facts of a crate were derived from.
+//!
+//! A bundle is a directory per crate holding the crates.io rows of the crate, copies of the documents cached
+//! by the providers, and the advisories filed against the crate, along with an `index.json` describing where
+//! each file came from and when its data was acquired. Bundles let the scores of a crate be audited long after
+//! the caches moved on.
+
+use super::ProviderResult;
+use super::crate_facts::CrateFacts;
+use super::path_utils::sanitize_path_component;
+use crate::Result;
+use ohno::IntoAppError;
+use serde_json::{Value, json};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the file describing the contents of a bundle
+const INDEX_FILENAME: &str = "index.json";
+
+/// Write the evidence bundle of a crate within `dir`, replacing any earlier bundle of the same release.
+///
+/// `documents` pairs the name of each source with the cached document it read, and `advisories` lists
+/// the advisory database entries of the crate. Returns the directory of the bundle.
+pub fn write_bundle(dir: &Path, facts: &CrateFacts, documents: &[(&'static str, PathBuf)], advisories: &[PathBuf]) -> Result<PathBuf> {
+    let spec = &facts.crate_spec;
+    let bundle_dir = dir.join(format!(
+        "{}-{}",
+        sanitize_path_component(spec.name()),
+        sanitize_path_component(&spec.version().to_string())
+    ));
+
+    if bundle_dir.exists() {
+        fs::remove_dir_all(&bundle_dir).into_app_err_with(|| format!("removing evidence directory '{}'", bundle_dir.display()))?;
+    }
+    fs::create_dir_all(&bundle_dir).into_app_err_with(|| format!("creating evidence directory '{}'", bundle_dir.display()))?;
+
+    let mut files = Vec::new();
+
+    if let ProviderResult::Found(crates_data) = &facts.crates_data {
+        let value = serde_json::to_value(crates_data).into_app_err("serializing crates.io data")?;
+        write_json(&bundle_dir.join("crates.json"), &value)?;
+        files.push(json!({ "source": "crates", "file": "crates.json" }));
+    }
+
+    for (source, origin) in documents {
+        let file = format!("{source}.json");
+        copy(origin, &bundle_dir.join(&file))?;
+        files.push(json!({ "source": source, "file": file, "origin": origin.display().to_string() }));
+    }
+
+    let mut custom: Vec<_> = facts
+        .custom_data
+        .iter()
+        .filter_map(|(name, result)| match result {
+            ProviderResult::Found(value) => Some((*name, value)),
+            _ => None,
+        })
+        .collect();
+    custom.sort_unstable_by_key(|(name, _)| *name);
+    for (name, value) in custom {
+        let file = format!("custom-{}.json", sanitize_path_component(name));
+        write_json(&bundle_dir.join(&file), value)?;
+        files.push(json!({ "source": name, "file": file }));
+    }
+
+    if !advisories.is_empty() {
+        let advisories_dir = bundle_dir.join("advisories");
+        fs::create_dir_all(&advisories_dir).into_app_err_with(|| format!("creating evidence directory '{}'", advisories_dir.display()))?;
+
+        for origin in advisories {
+            let Some(name) = origin.file_name() else {
+                continue;
+            };
+            copy(origin, &advisories_dir.join(name))?;
+            let file = format!("advisories/{}", name.to_string_lossy());
+            files.push(json!({ "source": "advisories", "file": file, "origin": origin.display().to_string() }));
+        }
+    }
+
+    let index = json!({
+        "name": spec.name(),
+        "version": spec.version().to_string(),
+        "acquired": {
+            "crates": facts.timestamps.crates,
+            "hosting": facts.timestamps.hosting,
+            "advisories": facts.timestamps.advisories,
+        },
+        "files": files,
+    });
+    write_json(&bundle_dir.join(INDEX_FILENAME), &index)?;
+
+    Ok(bundle_dir)
+}
+
+fn copy(from: &Path, to: &Path) -> Result<()> {
+    let _ = fs::copy(from, to).into_app_err_with(|| format!("copying '{}' to '{}'", from.display(), to.display()))?;
+    Ok(())
+}
+
+fn write_json(path: &Path, value: &Value) -> Result<()> {
+    let text = serde_json::to_string_pretty(value).into_app_err_with(|| format!("serializing '{}'", path.display()))?;
+    fs::write(path, text).into_app_err_with(|| format!("writing '{}'", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HashMap;
+    use crate::facts::{CrateSpec, DataTimestamps};
+    use semver::Version;
+    use std::sync::Arc;
+
+    fn make_facts() -> CrateFacts {
+        let mut custom_data = HashMap::default();
+        let _ = custom_data.insert("approvals", ProviderResult::Found(json!({ "approved": true })));
+
+        CrateFacts {
+            crate_spec: CrateSpec::from_arcs(Arc::from("test-crate"), Arc::new(Version::new(1, 2, 3))),
+            crates_data: ProviderResult::Unavailable("not queried".into()),
+            hosting_data: ProviderResult::Unavailable("not queried".into()),
+            advisory_data: ProviderResult::Unavailable("not queried".into()),
+            codebase_data: ProviderResult::Unavailable("not queried".into()),
+            coverage_data: ProviderResult::Unavailable("not queried".into()),
+            docs_data: ProviderResult::Unavailable("not queried".into()),
+            package_data: ProviderResult::Unavailable("not queried".into()),
+            custom_data,
+            timestamps: DataTimestamps::default(),
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_write_bundle() {
+        let temp = tempfile::tempdir().unwrap();
+        let hosting = temp.path().join("repo.json");
+        let advisory = temp.path().join("RUSTSEC-2024-0001.md");
+        fs::write(&hosting, r#"{"stars":1}"#).unwrap();
+        fs::write(&advisory, "advisory").unwrap();
+
+        let out = temp.path().join("evidence");
+        let facts = make_facts();
+        let bundle = write_bundle(&out, &facts, &[("hosting", hosting)], core::slice::from_ref(&advisory)).unwrap();
+        assert_eq!(bundle, out.join("test-crate-1.2.3"));
+
+        // Writing again replaces the earlier bundle rather than mixing with it
+        fs::write(bundle.join("stale.json"), "{}").unwrap();
+        let bundle = write_bundle(&out, &facts, &[], &[advisory]).unwrap();
+
+        assert!(!bundle.join("stale.json").exists());
+        assert!(!bundle.join("hosting.json").exists());
+        assert_eq!(fs::read_to_string(bundle.join("advisories/RUSTSEC-2024-0001.md")).unwrap(), "advisory");
+        assert!(bundle.join("custom-approvals.json").is_file());
+
+        let index: Value = serde_json::from_str(&fs::read_to_string(bundle.join(INDEX_FILENAME)).unwrap()).unwrap();
+        assert_eq!(index["name"], "test-crate");
+        assert_eq!(index["version"], "1.2.3");
+        let files: Vec<_> = index["files"].as_array().unwrap().iter().map(|f| f["file"].as_str().unwrap()).collect();
+        assert_eq!(files, ["custom-approvals.json", "advisories/RUSTSEC-2024-0001.md"]);
+    }
+}
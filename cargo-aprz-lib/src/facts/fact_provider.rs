@@ -0,0 +1,117 @@
+//! Extension point for fact providers compiled in by users of the library.
+//!
+//! Organizations wanting to appraise crates against their own data, such as an internal vulnerability
+//! database or a registry of approved crates, can implement [`FactProvider`] and register it with
+//! [`register_provider`] before calling [`crate::run`]. The facts each provider returns are stored in
+//! [`CrateFacts::custom_data`] under the provider's name, and the metrics it declares are extracted from
+//! them alongside the built-in ones, so they can be used in expressions and show up in reports.
+
+use super::{CrateFacts, CrateSpec, ProviderResult};
+use crate::metrics::MetricDef;
+use futures::future::BoxFuture;
+use std::sync::{Arc, RwLock};
+
+/// A source of facts about crates, along with the metrics derived from them
+pub trait FactProvider: Send + Sync {
+    /// Name of the provider, under which its facts are stored in [`CrateFacts::custom_data`]
+    fn name(&self) -> &'static str;
+
+    /// Definitions of the metrics computed from this provider's facts
+    ///
+    /// Metric names must not collide with the built-in ones, and their first component becomes the
+    /// namespace used to refer to them in expressions.
+    fn metric_defs(&self) -> &'static [MetricDef];
+
+    /// Collect facts for a batch of crates
+    ///
+    /// Crates missing from the result are treated as if no facts were available for them.
+    fn get_facts<'a>(&'a self, crates: &'a [CrateSpec]) -> BoxFuture<'a, Vec<(CrateSpec, ProviderResult<serde_json::Value>)>>;
+}
+
+static PROVIDERS: RwLock<Vec<Arc<dyn FactProvider>>> = RwLock::new(Vec::new());
+
+/// Register a custom fact provider, to be queried on every appraisal
+pub fn register_provider(provider: Arc<dyn FactProvider>) {
+    PROVIDERS.write().expect("provider registry must not be poisoned").push(provider);
+}
+
+/// Return the registered custom fact providers
+pub fn registered_providers() -> Vec<Arc<dyn FactProvider>> {
+    PROVIDERS.read().expect("provider registry must not be poisoned").clone()
+}
+
+/// Return the metric definitions declared by the registered custom fact providers
+pub fn registered_metric_defs() -> Vec<&'static MetricDef> {
+    registered_providers()
+        .iter()
+        .flat_map(|provider| provider.metric_defs().iter())
+        .collect()
+}
+
+impl CrateFacts {
+    /// Get the facts collected by the custom provider with the given name
+    #[must_use]
+    pub fn custom_facts(&self, provider: &str) -> Option<&serde_json::Value> {
+        self.custom_data.get(provider).and_then(ProviderResult::as_ref)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HashMap;
+    use crate::facts::DataTimestamps;
+    use crate::metrics::{MetricCategory, MetricValue};
+    use semver::Version;
+    use serde_json::json;
+
+    const APPROVAL_METRICS: &[MetricDef] = &[MetricDef {
+        name: "internal.approved",
+        description: "Whether the crate is on the internal approval list",
+        category: MetricCategory::Trustworthiness,
+        extractor: |facts| {
+            facts
+                .custom_facts("approvals")
+                .and_then(|data| data["approved"].as_bool())
+                .map(MetricValue::Boolean)
+        },
+        default_value: || Some(MetricValue::Boolean(false)),
+    }];
+
+    fn make_facts(custom_data: HashMap<&'static str, ProviderResult<serde_json::Value>>) -> CrateFacts {
+        CrateFacts {
+            crate_spec: CrateSpec::from_arcs(Arc::from("test-crate"), Arc::new(Version::new(1, 0, 0))),
+            crates_data: ProviderResult::Unavailable("not queried".into()),
+            hosting_data: ProviderResult::Unavailable("not queried".into()),
+            advisory_data: ProviderResult::Unavailable("not queried".into()),
+            codebase_data: ProviderResult::Unavailable("not queried".into()),
+            coverage_data: ProviderResult::Unavailable("not queried".into()),
+            docs_data: ProviderResult::Unavailable("not queried".into()),
+            package_data: ProviderResult::Unavailable("not queried".into()),
+            custom_data,
+            timestamps: DataTimestamps::default(),
+        }
+    }
+
+    #[test]
+    fn test_custom_facts() {
+        let mut custom_data = HashMap::default();
+        let _ = custom_data.insert("approvals", ProviderResult::Found(json!({ "approved": true })));
+        let _ = custom_data.insert("vulns", ProviderResult::Unavailable("no entry".into()));
+        let facts = make_facts(custom_data);
+
+        assert_eq!(facts.custom_facts("approvals"), Some(&json!({ "approved": true })));
+        assert_eq!(facts.custom_facts("vulns"), None);
+        assert_eq!(facts.custom_facts("unknown"), None);
+    }
+
+    #[test]
+    fn test_custom_metric_extraction() {
+        let mut custom_data = HashMap::default();
+        let _ = custom_data.insert("approvals", ProviderResult::Found(json!({ "approved": true })));
+
+        let extractor = APPROVAL_METRICS[0].extractor;
+        assert!(matches!(extractor(&make_facts(custom_data)), Some(MetricValue::Boolean(true))));
+        assert!(extractor(&make_facts(HashMap::default())).is_none());
+    }
+}
@@ -2,14 +2,20 @@
 //!
 //! Minimal GitHub API client for fetching repository and issue data.
 
+use super::GitHubApp;
+use crate::HashMap;
 use chrono::{DateTime, Utc};
-use reqwest::header::HeaderMap;
+use core::sync::atomic::{AtomicU64, Ordering};
+use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderValue};
 use serde::Deserialize;
+use std::sync::{Arc, Mutex};
 
 const LOG_TARGET: &str = "   hosting";
 
+/// Header naming the quota a response counted against, as GitHub meters REST, GraphQL, and search requests separately
+const RATE_LIMIT_RESOURCE_HEADER: &str = "x-ratelimit-resource";
+
 #[derive(Debug, Deserialize)]
-#[expect(clippy::struct_field_names, reason = "field names match GitHub API exactly")]
 pub struct Repository {
     #[serde(alias = "stars_count")]
     pub stargazers_count: Option<u32>,
@@ -19,6 +25,64 @@ pub struct Repository {
     /// Codeberg uses `watchers_count` instead of `subscribers_count`
     #[serde(default)]
     pub watchers_count: Option<i64>,
+    /// Only reported by GitHub
+    #[serde(default)]
+    pub has_discussions: Option<bool>,
+    #[serde(default)]
+    pub default_branch: Option<String>,
+    #[serde(default)]
+    pub owner: Option<RepositoryOwner>,
+    /// GitHub reports the single license it detected
+    #[serde(default)]
+    pub license: Option<RepositoryLicense>,
+    /// Codeberg reports every license it detected
+    #[serde(default)]
+    pub licenses: Vec<String>,
+}
+
+/// The license detected in a repository by GitHub
+#[derive(Debug, Deserialize)]
+pub struct RepositoryLicense {
+    /// `NOASSERTION` when GitHub found a license it couldn't identify
+    #[serde(default)]
+    pub spdx_id: Option<String>,
+}
+
+/// The account owning a repository
+#[derive(Debug, Deserialize)]
+pub struct RepositoryOwner {
+    /// `User` or `Organization`, only reported by GitHub
+    #[serde(default, rename = "type")]
+    pub kind: Option<String>,
+}
+
+/// Minimal GitHub organization info with only the fields we need
+#[derive(Debug, Deserialize)]
+pub struct Organization {
+    #[serde(default)]
+    pub is_verified: bool,
+    /// Only reported to members of the organization
+    #[serde(default)]
+    pub two_factor_requirement_enabled: Option<bool>,
+}
+
+/// Minimal commit info with only the fields we need
+#[derive(Debug, Deserialize)]
+pub struct Commit {
+    pub commit: CommitDetails,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommitDetails {
+    #[serde(default)]
+    pub verification: Option<CommitVerification>,
+}
+
+/// Whether the hosting service verified the signature of a commit
+#[derive(Debug, Deserialize)]
+pub struct CommitVerification {
+    #[serde(default)]
+    pub verified: bool,
 }
 
 /// Minimal GitHub issue/PR info with only the fields we need
@@ -28,6 +92,14 @@ pub struct Issue {
     pub closed_at: Option<DateTime<Utc>>,
     pub state: IssueState,
     pub pull_request: Option<PullRequestMarker>,
+    #[serde(default)]
+    pub labels: Vec<Label>,
+}
+
+/// A label attached to an issue
+#[derive(Debug, Deserialize)]
+pub struct Label {
+    pub name: String,
 }
 
 /// Issue state: open or closed
@@ -45,6 +117,63 @@ pub struct PullRequestMarker {
     pub merged_at: Option<DateTime<Utc>>,
 }
 
+/// Minimal release info with only the fields we need
+#[derive(Debug, Deserialize)]
+pub struct Release {
+    pub created_at: DateTime<Utc>,
+    pub published_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub draft: bool,
+    #[serde(default)]
+    pub prerelease: bool,
+    pub body: Option<String>,
+    #[serde(default)]
+    pub assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    #[serde(default)]
+    pub browser_download_url: Option<String>,
+}
+
+/// Minimal branch info with only the fields we need
+#[derive(Debug, Deserialize)]
+pub struct Branch {
+    #[serde(default)]
+    pub protected: bool,
+    /// GitHub summary of the protection rules, which omits required reviews
+    #[serde(default)]
+    pub protection: Option<BranchProtection>,
+    /// Codeberg reports the protection rules directly on the branch
+    #[serde(default)]
+    pub required_approvals: Option<u64>,
+    #[serde(default)]
+    pub enable_status_check: Option<bool>,
+}
+
+/// GitHub branch protection rules
+#[derive(Debug, Deserialize)]
+pub struct BranchProtection {
+    #[serde(default)]
+    pub required_pull_request_reviews: Option<RequiredReviews>,
+    #[serde(default)]
+    pub required_status_checks: Option<RequiredStatusChecks>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RequiredReviews {
+    #[serde(default)]
+    pub required_approving_review_count: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RequiredStatusChecks {
+    #[serde(default)]
+    pub contexts: Vec<String>,
+}
+
 /// Rate limit information from response headers
 #[derive(Debug, Clone, Copy)]
 pub struct RateLimitInfo {
@@ -52,6 +181,84 @@ pub struct RateLimitInfo {
     pub reset_at: DateTime<Utc>,
 }
 
+/// How much of a hosting API's request quota was used
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiUsage {
+    /// Display name of the hosting service
+    pub host: &'static str,
+    /// Number of API requests made
+    pub requests: u64,
+    /// Number of requests rejected because of rate limiting
+    pub rate_limited: u64,
+    /// Requests remaining in the current quota window, as last reported by the service
+    pub remaining: Option<usize>,
+    /// When the current quota window resets, as last reported by the service
+    pub reset_at: Option<DateTime<Utc>>,
+}
+
+/// Kind of request, which determines the quota it's expected to count against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CallKind {
+    Rest,
+    GraphQl,
+}
+
+impl CallKind {
+    /// Name of the quota requests of this kind count against, as reported in the `x-ratelimit-resource` header
+    const fn resource(self) -> &'static str {
+        match self {
+            Self::Rest => "core",
+            Self::GraphQl => "graphql",
+        }
+    }
+}
+
+/// Keep the quota reported for `resource` if it's more recent than the one known.
+///
+/// Concurrent responses arrive out of order, so keep the latest quota window and the lowest count within it.
+fn update_quota(quotas: &mut HashMap<String, RateLimitInfo>, resource: &str, rate_limit: RateLimitInfo) {
+    match quotas.get_mut(resource) {
+        Some(current) => {
+            if rate_limit.reset_at > current.reset_at || (rate_limit.reset_at == current.reset_at && rate_limit.remaining < current.remaining) {
+                *current = rate_limit;
+            }
+        }
+        None => {
+            let _ = quotas.insert(resource.to_string(), rate_limit);
+        }
+    }
+}
+
+/// Requests made through a client, along with the last quota reported for the REST API
+#[derive(Debug, Default)]
+struct UsageCounters {
+    requests: AtomicU64,
+    rate_limited: AtomicU64,
+    rate_limit: Mutex<Option<RateLimitInfo>>,
+}
+
+impl UsageCounters {
+    fn record<T>(&self, resource: &str, rate_limit: Option<RateLimitInfo>, result: &HostingApiResult<T>) {
+        let _ = self.requests.fetch_add(1, Ordering::Relaxed);
+        if matches!(result, HostingApiResult::RateLimited(_)) {
+            let _ = self.rate_limited.fetch_add(1, Ordering::Relaxed);
+        }
+
+        // Only the REST quota is reported, as the others are much smaller or metered in points
+        if let Some(rate_limit) = rate_limit
+            && resource == CallKind::Rest.resource()
+        {
+            // Responses arrive out of order, so keep the latest quota window and the lowest count within it
+            let mut last = self.rate_limit.lock().expect("lock poisoned");
+            if last.is_none_or(|current| {
+                rate_limit.reset_at > current.reset_at || (rate_limit.reset_at == current.reset_at && rate_limit.remaining < current.remaining)
+            }) {
+                *last = Some(rate_limit);
+            }
+        }
+    }
+}
+
 /// Result of a hosting API call
 pub enum HostingApiResult<T> {
     /// Request succeeded - contains data and optional rate limit info
@@ -67,34 +274,155 @@ pub enum HostingApiResult<T> {
     Failed(ohno::AppError, Option<RateLimitInfo>),
 }
 
+/// A token requests can be sent with, along with the quotas last reported for it, by resource
+#[derive(Debug)]
+struct Credential {
+    header: HeaderValue,
+    rate_limits: Mutex<HashMap<String, RateLimitInfo>>,
+}
+
+impl Credential {
+    fn new(token: &str) -> crate::Result<Self> {
+        let mut header = HeaderValue::from_str(&format!("token {token}"))?;
+        header.set_sensitive(true);
+        Ok(Self {
+            header,
+            rate_limits: Mutex::default(),
+        })
+    }
+
+    /// Requests the token can still make against `resource`, unknown quotas and quotas that were reset since
+    /// counting as unlimited.
+    fn remaining(&self, resource: &str, now: DateTime<Utc>) -> usize {
+        let rate_limit = self.rate_limits.lock().expect("credential lock not poisoned").get(resource).copied();
+        match rate_limit {
+            Some(rl) if rl.reset_at > now => rl.remaining,
+            _ => usize::MAX,
+        }
+    }
+
+    fn record<T>(&self, resource: &str, rate_limit: Option<RateLimitInfo>, result: &HostingApiResult<T>) {
+        let rate_limit = match result {
+            HostingApiResult::RateLimited(rl) => Some(RateLimitInfo { remaining: 0, ..*rl }),
+            _ => rate_limit,
+        };
+
+        if let Some(rate_limit) = rate_limit {
+            update_quota(&mut self.rate_limits.lock().expect("credential lock not poisoned"), resource, rate_limit);
+        }
+    }
+}
+
 /// Hosting API client (GitHub, Codeberg, etc.)
 #[derive(Debug, Clone)]
 pub struct Client {
-    client: reqwest::Client,
+    http: reqwest::Client,
     base_url: String,
+    authenticated: bool,
+    usage: Arc<UsageCounters>,
+
+    /// Tokens requests are spread across, each request using the one with the most quota left
+    credentials: Arc<[Credential]>,
+
+    /// GitHub App whose installation token authenticates requests, in place of fixed tokens
+    app: Option<Arc<GitHubApp>>,
 }
 
 impl Client {
-    /// Create a new hosting API client with optional authentication token and base URL
-    pub fn new(token: Option<&str>, base_url: impl Into<String>) -> crate::Result<Self> {
-        use reqwest::header::{AUTHORIZATION, HeaderValue};
+    /// Create a new hosting API client with the given authentication tokens, if any, and base URL
+    pub fn new(tokens: &[String], base_url: impl Into<String>) -> crate::Result<Self> {
+        let credentials = tokens.iter().map(|token| Credential::new(token)).collect::<crate::Result<Vec<_>>>()?;
+
+        Ok(Self {
+            http: reqwest::Client::builder().user_agent("cargo-aprz").build()?,
+            base_url: base_url.into(),
+            authenticated: !credentials.is_empty(),
+            usage: Arc::default(),
+            credentials: credentials.into(),
+            app: None,
+        })
+    }
 
-        let mut client_builder = reqwest::Client::builder().user_agent("cargo-aprz");
+    /// Authenticate requests as the given GitHub App rather than with fixed tokens.
+    #[must_use]
+    pub fn with_app(mut self, app: Arc<GitHubApp>) -> Self {
+        self.app = Some(app);
+        self.authenticated = true;
+        self
+    }
 
-        if let Some(t) = token {
-            let mut auth_val = HeaderValue::from_str(&format!("token {t}"))?;
-            auth_val.set_sensitive(true);
+    /// The token with the most quota left for requests of the given kind, if requests are authenticated with
+    /// fixed tokens.
+    fn pick_credential(&self, kind: CallKind) -> Option<&Credential> {
+        let now = Utc::now();
+        self.credentials
+            .iter()
+            .max_by_key(|credential| credential.remaining(kind.resource(), now))
+    }
 
-            let mut headers = HeaderMap::new();
+    /// Headers authenticating a request, with the installation token of the GitHub App if any, which is
+    /// refreshed as needed, or else with the given token.
+    async fn auth_headers(&self, credential: Option<&Credential>) -> crate::Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        if let Some(app) = &self.app {
+            let token = app.installation_token(&self.http, &self.base_url).await?;
+            let mut auth_val = HeaderValue::from_str(&format!("token {token}"))?;
+            auth_val.set_sensitive(true);
             let _ = headers.insert(AUTHORIZATION, auth_val);
+        } else if let Some(credential) = credential {
+            let _ = headers.insert(AUTHORIZATION, credential.header.clone());
+        }
+
+        Ok(headers)
+    }
 
-            client_builder = client_builder.default_headers(headers);
+    /// Send a request built by `send` with the given authentication headers, and classify the result. When a
+    /// token runs out of quota, the request is sent again with another token, until none has quota left.
+    async fn call<F, Fut>(&self, kind: CallKind, url: &str, send: F) -> HostingApiResult<reqwest::Response>
+    where
+        F: Fn(HeaderMap) -> Fut,
+        Fut: Future<Output = crate::Result<reqwest::Response>>,
+    {
+        let mut attempts_left = self.credentials.len().max(1);
+        loop {
+            attempts_left -= 1;
+            let credential = self.pick_credential(kind);
+            let headers = match self.auth_headers(credential).await {
+                Ok(headers) => headers,
+                Err(e) => return HostingApiResult::Failed(e, None),
+            };
+
+            let resp = match send(headers).await {
+                Ok(r) => r,
+                Err(e) => return HostingApiResult::Failed(e, None),
+            };
+
+            let rate_limit = extract_rate_limit_from_headers(resp.headers());
+            let resource = extract_rate_limit_resource(resp.headers()).unwrap_or_else(|| kind.resource().to_string());
+            let result = classify_response(resp, rate_limit, url);
+            self.usage.record(&resource, rate_limit, &result);
+            if let Some(credential) = credential {
+                credential.record(&resource, rate_limit, &result);
+            }
+
+            if attempts_left > 0
+                && matches!(result, HostingApiResult::RateLimited(_))
+                && self
+                    .pick_credential(kind)
+                    .is_some_and(|c| c.remaining(kind.resource(), Utc::now()) > 0)
+            {
+                log::debug!(target: LOG_TARGET, "Switching to another token for {url}");
+                continue;
+            }
+
+            return result;
         }
+    }
 
-        Ok(Self {
-            client: client_builder.build()?,
-            base_url: base_url.into(),
-        })
+    /// Whether requests are sent with an authentication token
+    #[must_use]
+    pub const fn is_authenticated(&self) -> bool {
+        self.authenticated
     }
 
     /// Get the base URL for this client
@@ -103,15 +431,33 @@ impl Client {
         &self.base_url
     }
 
+    /// Report the requests made through this client and the quota left
+    #[must_use]
+    pub fn api_usage(&self, host: &'static str) -> ApiUsage {
+        let rate_limit = *self.usage.rate_limit.lock().expect("lock poisoned");
+        ApiUsage {
+            host,
+            requests: self.usage.requests.load(Ordering::Relaxed),
+            rate_limited: self.usage.rate_limited.load(Ordering::Relaxed),
+            remaining: rate_limit.map(|rl| rl.remaining),
+            reset_at: rate_limit.map(|rl| rl.reset_at),
+        }
+    }
+
     /// Make an API call and classify the result
     pub async fn api_call(&self, url: &str) -> HostingApiResult<reqwest::Response> {
-        let resp = match crate::facts::resilient_http::resilient_get(&self.client, url).await {
-            Ok(r) => r,
-            Err(e) => return HostingApiResult::Failed(e, None),
-        };
+        self.call(CallKind::Rest, url, |headers| {
+            crate::facts::resilient_http::resilient_get_with_headers(&self.http, url, headers)
+        })
+            .await
+    }
 
-        let rate_limit = extract_rate_limit_from_headers(resp.headers());
-        classify_response(resp, rate_limit, url)
+    /// Make a GraphQL API call and classify the result
+    pub async fn graphql_call(&self, url: &str, body: &serde_json::Value) -> HostingApiResult<reqwest::Response> {
+        self.call(CallKind::GraphQl, url, |headers| async move {
+            Ok(self.http.post(url).headers(headers).json(body).send().await?)
+        })
+            .await
     }
 }
 
@@ -196,10 +542,18 @@ fn extract_rate_limit_from_headers(headers: &HeaderMap) -> Option<RateLimitInfo>
     Some(RateLimitInfo { remaining, reset_at })
 }
 
+/// Extract the name of the quota a response counted against, if the service reports it
+fn extract_rate_limit_resource(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(RATE_LIMIT_RESOURCE_HEADER)?
+        .to_str()
+        .ok()
+        .map(str::to_string)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use reqwest::header::HeaderValue;
 
     #[test]
     fn test_repository_deserialize_github() {
@@ -242,6 +596,41 @@ mod tests {
         assert_eq!(repo.watchers_count, None);
     }
 
+    #[test]
+    fn test_branch_deserialize_github() {
+        let json = r#"{
+            "name": "main",
+            "protected": true,
+            "protection": {
+                "enabled": true,
+                "required_status_checks": { "enforcement_level": "everyone", "contexts": ["ci"] }
+            }
+        }"#;
+
+        let branch: Branch = serde_json::from_str(json).unwrap();
+        assert!(branch.protected);
+        let protection = branch.protection.unwrap();
+        assert!(protection.required_pull_request_reviews.is_none());
+        assert_eq!(protection.required_status_checks.unwrap().contexts, ["ci"]);
+        assert!(branch.required_approvals.is_none());
+    }
+
+    #[test]
+    fn test_branch_deserialize_codeberg() {
+        let json = r#"{
+            "name": "main",
+            "protected": true,
+            "required_approvals": 2,
+            "enable_status_check": true
+        }"#;
+
+        let branch: Branch = serde_json::from_str(json).unwrap();
+        assert!(branch.protected);
+        assert!(branch.protection.is_none());
+        assert_eq!(branch.required_approvals, Some(2));
+        assert_eq!(branch.enable_status_check, Some(true));
+    }
+
     #[test]
     fn test_issue_deserialize() {
         let json = r#"{
@@ -254,6 +643,21 @@ mod tests {
         assert_eq!(issue.state, IssueState::Closed);
         assert!(issue.closed_at.is_some());
         assert!(issue.pull_request.is_none());
+        assert!(issue.labels.is_empty());
+    }
+
+    #[test]
+    fn test_issue_deserialize_with_labels() {
+        let json = r#"{
+            "created_at": "2024-01-01T00:00:00Z",
+            "closed_at": null,
+            "state": "open",
+            "labels": [{ "id": 1, "name": "question" }, { "id": 2, "name": "C-bug" }]
+        }"#;
+
+        let issue: Issue = serde_json::from_str(json).unwrap();
+        let names: Vec<_> = issue.labels.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, ["question", "C-bug"]);
     }
 
     #[test]
@@ -323,6 +727,84 @@ mod tests {
         assert_eq!(rate_limit.reset_at.timestamp(), 1_704_067_200);
     }
 
+    #[test]
+    fn test_usage_counters_keep_latest_quota() {
+        let counters = UsageCounters::default();
+        let reset_at = DateTime::from_timestamp(1_704_067_200, 0).unwrap();
+        let limit = |remaining, reset_at| RateLimitInfo { remaining, reset_at };
+
+        counters.record("core", Some(limit(10, reset_at)), &HostingApiResult::Success((), None));
+        counters.record("core", Some(limit(12, reset_at)), &HostingApiResult::<()>::NotFound(None));
+        counters.record("core", None, &HostingApiResult::<()>::RateLimited(limit(0, reset_at)));
+        counters.record("graphql", Some(limit(3, reset_at)), &HostingApiResult::Success((), None));
+
+        let last = counters.rate_limit.lock().unwrap().unwrap();
+        assert_eq!(last.remaining, 10);
+        assert_eq!(counters.requests.load(Ordering::Relaxed), 4);
+        assert_eq!(counters.rate_limited.load(Ordering::Relaxed), 1);
+
+        let next_window = reset_at + chrono::Duration::hours(1);
+        counters.record("core", Some(limit(4999, next_window)), &HostingApiResult::Success((), None));
+        assert_eq!(counters.rate_limit.lock().unwrap().unwrap().remaining, 4999);
+    }
+
+    #[test]
+    fn test_credential_keeps_lowest_remaining() {
+        let credential = Credential::new("token").unwrap();
+        let reset_at = DateTime::from_timestamp(1_704_067_200, 0).unwrap();
+        let now = reset_at - chrono::Duration::minutes(30);
+        let limit = |remaining, reset_at| RateLimitInfo { remaining, reset_at };
+
+        credential.record("core", Some(limit(10, reset_at)), &HostingApiResult::Success((), None));
+        credential.record("core", Some(limit(12, reset_at)), &HostingApiResult::Success((), None));
+        assert_eq!(credential.remaining("core", now), 10);
+
+        credential.record(
+            "core",
+            Some(limit(4999, reset_at + chrono::Duration::hours(1))),
+            &HostingApiResult::Success((), None),
+        );
+        assert_eq!(credential.remaining("core", now), 4999);
+    }
+
+    #[test]
+    fn test_credential_tracks_quota_per_resource() {
+        let credential = Credential::new("token").unwrap();
+        let reset_at = DateTime::from_timestamp(1_704_067_200, 0).unwrap();
+        let now = reset_at - chrono::Duration::minutes(30);
+        let limit = |remaining| RateLimitInfo { remaining, reset_at };
+
+        credential.record("core", None, &HostingApiResult::<()>::RateLimited(limit(0)));
+        credential.record("graphql", Some(limit(4000)), &HostingApiResult::Success((), None));
+        assert_eq!(credential.remaining("core", now), 0);
+        assert_eq!(credential.remaining("graphql", now), 4000);
+        assert_eq!(credential.remaining("search", now), usize::MAX);
+    }
+
+    #[test]
+    fn test_pick_credential_per_call_kind() {
+        let client = Client::new(&["first".to_string(), "second".to_string()], "https://api.github.com").unwrap();
+        let reset_at = Utc::now() + chrono::Duration::minutes(30);
+        let limit = |remaining| RateLimitInfo { remaining, reset_at };
+
+        // The first token ran out of REST quota, while the second used up most of its GraphQL quota
+        let mut credentials = client.credentials.iter();
+        credentials.next().unwrap().record("core", None, &HostingApiResult::<()>::RateLimited(limit(0)));
+        credentials.next().unwrap().record("graphql", Some(limit(10)), &HostingApiResult::Success((), None));
+
+        assert_eq!(client.pick_credential(CallKind::Rest).unwrap().header, "token second");
+        assert_eq!(client.pick_credential(CallKind::GraphQl).unwrap().header, "token first");
+    }
+
+    #[test]
+    fn test_extract_rate_limit_resource() {
+        let mut headers = HeaderMap::new();
+        assert!(extract_rate_limit_resource(&headers).is_none());
+
+        let _ = headers.insert(RATE_LIMIT_RESOURCE_HEADER, HeaderValue::from_static("graphql"));
+        assert_eq!(extract_rate_limit_resource(&headers).as_deref(), Some("graphql"));
+    }
+
     #[test]
     fn test_extract_rate_limit_missing_headers() {
         let headers = HeaderMap::new();
@@ -352,19 +834,51 @@ mod tests {
 
     #[test]
     fn test_client_new_without_token() {
-        let client = Client::new(None, "https://api.github.com").unwrap();
+        let client = Client::new(&[], "https://api.github.com").unwrap();
         assert_eq!(client.base_url(), "https://api.github.com");
     }
 
     #[test]
     fn test_client_new_with_token() {
-        let client = Client::new(Some("test_token"), "https://api.github.com").unwrap();
+        let client = Client::new(&["test_token".to_string()], "https://api.github.com").unwrap();
         assert_eq!(client.base_url(), "https://api.github.com");
     }
 
+    #[tokio::test]
+    #[cfg_attr(miri, ignore = "Miri cannot call CreateIoCompletionPort")]
+    async fn test_client_rotates_exhausted_tokens() {
+        use wiremock::matchers::header;
+
+        let server = MockServer::start().await;
+        let reset = (Utc::now() + chrono::Duration::hours(1)).timestamp().to_string();
+        Mock::given(header("authorization", "token spent"))
+            .respond_with(
+                ResponseTemplate::new(403)
+                    .insert_header("x-ratelimit-remaining", "0")
+                    .insert_header("x-ratelimit-reset", reset.as_str()),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(header("authorization", "token fresh"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("x-ratelimit-remaining", "4000")
+                    .insert_header("x-ratelimit-reset", reset.as_str()),
+            )
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        // The spent token is tried first, as neither quota is known yet and the last token wins ties
+        let client = Client::new(&["fresh".to_string(), "spent".to_string()], server.uri()).unwrap();
+        assert!(matches!(client.api_call(&server.uri()).await, HostingApiResult::Success(..)));
+        assert!(matches!(client.api_call(&server.uri()).await, HostingApiResult::Success(..)));
+    }
+
     #[test]
     fn test_client_base_url() {
-        let client = Client::new(None, "https://codeberg.org/api/v1").unwrap();
+        let client = Client::new(&[], "https://codeberg.org/api/v1").unwrap();
         assert_eq!(client.base_url(), "https://codeberg.org/api/v1");
     }
 
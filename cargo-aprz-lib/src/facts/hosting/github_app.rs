@@ -0,0 +1,236 @@
+//! Authentication as a GitHub App.
+//!
+//! A GitHub App authenticates with a short-lived JWT signed by its private key, which it trades for an
+//! installation access token valid for an hour. Installation tokens come with a higher rate limit than
+//! personal tokens. The current token is kept and traded for a new one shortly before it expires, so
+//! long runs keep going without interruption.
+
+use crate::Result;
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use ohno::{IntoAppError, bail};
+use reqwest::header::{ACCEPT, AUTHORIZATION};
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, PoisonError};
+
+const LOG_TARGET: &str = "   hosting";
+
+/// Installation tokens are refreshed when they expire within this many minutes
+const REFRESH_MARGIN_MINUTES: i64 = 5;
+
+#[derive(Serialize)]
+struct Claims {
+    iat: i64,
+    exp: i64,
+    iss: String,
+}
+
+#[derive(Deserialize)]
+struct Installation {
+    id: u64,
+}
+
+#[derive(Deserialize)]
+struct InstallationToken {
+    token: String,
+    expires_at: DateTime<Utc>,
+}
+
+/// Credentials of a GitHub App, along with the current installation token.
+pub struct GitHubApp {
+    app_id: u64,
+    key: EncodingKey,
+    installation_id: Option<u64>,
+    token: Mutex<Option<InstallationToken>>,
+}
+
+impl core::fmt::Debug for GitHubApp {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("GitHubApp")
+            .field("app_id", &self.app_id)
+            .field("installation_id", &self.installation_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl GitHubApp {
+    /// Create the credentials of the app with the given ID and PEM-encoded RSA private key. Without an
+    /// installation ID, the app must be installed on a single account or organization.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the private key can't be parsed.
+    pub fn new(app_id: u64, private_key_pem: &str, installation_id: Option<u64>) -> Result<Self> {
+        let key = EncodingKey::from_rsa_pem(private_key_pem.as_bytes()).into_app_err("parsing the GitHub App private key")?;
+        Ok(Self {
+            app_id,
+            key,
+            installation_id,
+            token: Mutex::new(None),
+        })
+    }
+
+    /// The current installation token, obtaining a new one from the API at `base_url` when there's none yet or
+    /// it's about to expire.
+    ///
+    /// The lock on the current token isn't held while requesting a new one, so requests racing past an expiring
+    /// token may each obtain one. The one lasting longest is kept.
+    pub async fn installation_token(&self, client: &reqwest::Client, base_url: &str) -> Result<String> {
+        if let Some(token) = self.current_token() {
+            return Ok(token);
+        }
+
+        let jwt = self.jwt()?;
+        let installation_id = match self.installation_id {
+            Some(id) => id,
+            None => self.find_installation(client, base_url, &jwt).await?,
+        };
+
+        log::debug!(target: LOG_TARGET, "Requesting an access token for installation {installation_id} of GitHub App {}", self.app_id);
+        let token: InstallationToken = client
+            .post(format!("{base_url}/app/installations/{installation_id}/access_tokens"))
+            .header(AUTHORIZATION, format!("Bearer {jwt}"))
+            .header(ACCEPT, "application/vnd.github+json")
+            .send()
+            .await?
+            .error_for_status()
+            .into_app_err("requesting a GitHub App installation token")?
+            .json()
+            .await?;
+
+        // The token is only ever replaced whole, so a poisoned lock still holds a consistent one
+        let mut current = self.token.lock().unwrap_or_else(PoisonError::into_inner);
+        let value = match current.as_ref() {
+            Some(kept) if kept.expires_at >= token.expires_at => kept.token.clone(),
+            _ => {
+                let value = token.token.clone();
+                *current = Some(token);
+                value
+            }
+        };
+        drop(current);
+
+        Ok(value)
+    }
+
+    /// The current installation token, unless there's none yet or it's about to expire.
+    fn current_token(&self) -> Option<String> {
+        let current = self.token.lock().unwrap_or_else(PoisonError::into_inner);
+        current
+            .as_ref()
+            .filter(|token| token.expires_at - Utc::now() > Duration::minutes(REFRESH_MARGIN_MINUTES))
+            .map(|token| token.token.clone())
+    }
+
+    /// The only installation of the app, as the app can't tell which one to use otherwise.
+    async fn find_installation(&self, client: &reqwest::Client, base_url: &str, jwt: &str) -> Result<u64> {
+        let installations: Vec<Installation> = client
+            .get(format!("{base_url}/app/installations"))
+            .header(AUTHORIZATION, format!("Bearer {jwt}"))
+            .header(ACCEPT, "application/vnd.github+json")
+            .send()
+            .await?
+            .error_for_status()
+            .into_app_err("listing the installations of the GitHub App")?
+            .json()
+            .await?;
+
+        match installations.as_slice() {
+            [installation] => Ok(installation.id),
+            [] => bail!("GitHub App {} isn't installed on any account", self.app_id),
+            _ => bail!(
+                "GitHub App {} has {} installations, use --github-app-installation-id to choose one",
+                self.app_id,
+                installations.len()
+            ),
+        }
+    }
+
+    /// A JWT identifying the app, backdated a minute to allow for clock drift and valid for ten minutes at most.
+    fn jwt(&self) -> Result<String> {
+        let now = Utc::now().timestamp();
+        let claims = Claims {
+            iat: now - 60,
+            exp: now + 9 * 60,
+            iss: self.app_id.to_string(),
+        };
+
+        jsonwebtoken::encode(&Header::new(Algorithm::RS256), &claims, &self.key).into_app_err("signing the GitHub App JWT")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header_exists, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const TEST_KEY: &str = include_str!("../../../tests/fixtures/github-app-test-key.pem");
+
+    #[test]
+    fn test_invalid_private_key() {
+        let _ = GitHubApp::new(1, "not a key", None).unwrap_err();
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore = "Miri cannot call CreateIoCompletionPort")]
+    async fn test_installation_token_is_reused() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/app/installations"))
+            .and(header_exists("authorization"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([{ "id": 42 }])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/app/installations/42/access_tokens"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+                "token": "ghs_test",
+                "expires_at": (Utc::now() + Duration::hours(1)).to_rfc3339(),
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let app = GitHubApp::new(1, TEST_KEY, None).unwrap();
+        let client = reqwest::Client::new();
+        assert_eq!(app.installation_token(&client, &server.uri()).await.unwrap(), "ghs_test");
+        assert_eq!(app.installation_token(&client, &server.uri()).await.unwrap(), "ghs_test");
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore = "Miri cannot call CreateIoCompletionPort")]
+    async fn test_expiring_token_is_refreshed() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/app/installations/7/access_tokens"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+                "token": "ghs_short",
+                "expires_at": (Utc::now() + Duration::minutes(2)).to_rfc3339(),
+            })))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let app = GitHubApp::new(1, TEST_KEY, Some(7)).unwrap();
+        let client = reqwest::Client::new();
+        let _ = app.installation_token(&client, &server.uri()).await.unwrap();
+        let _ = app.installation_token(&client, &server.uri()).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore = "Miri cannot call CreateIoCompletionPort")]
+    async fn test_ambiguous_installation() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/app/installations"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([{ "id": 1 }, { "id": 2 }])))
+            .mount(&server)
+            .await;
+
+        let app = GitHubApp::new(1, TEST_KEY, None).unwrap();
+        let err = app.installation_token(&reqwest::Client::new(), &server.uri()).await.unwrap_err();
+        assert!(err.to_string().contains("2 installations"));
+    }
+}
@@ -1,8 +1,11 @@
 use super::age_stats::AgeStats;
 use super::time_window_stats::TimeWindowStats;
+use chrono::{DateTime, Utc};
+use compact_str::CompactString;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[expect(clippy::struct_excessive_bools, reason = "Repository settings such as branch protection are on or off")]
 pub struct HostingData {
     pub stars: u64,
     pub forks: u64,
@@ -30,4 +33,48 @@ pub struct HostingData {
     pub merged_pr_age_last_90_days: AgeStats,
     pub merged_pr_age_last_180_days: AgeStats,
     pub merged_pr_age_last_365_days: AgeStats,
+    /// Fraction of the most recently merged pull requests merged without an approving review,
+    /// `None` when it couldn't be queried (no token or unsupported host) or nothing was merged
+    pub unreviewed_merge_ratio: Option<f64>,
+
+    // Releases
+
+    pub releases: TimeWindowStats,
+    pub prereleases: TimeWindowStats,
+    pub latest_release_has_notes: bool,
+    /// Whether the latest release ships signature or attestation assets
+    pub latest_release_signed: bool,
+    /// SLSA build level of the provenance attested for the latest release, 0 if none and at most 1 as attestations aren't verified
+    pub provenance_level: u64,
+
+    // Support channels
+
+    pub discussions_enabled: bool,
+    /// `None` when discussions couldn't be queried (no token, unsupported host, or disabled)
+    pub discussions_last_365_days: Option<u64>,
+    pub question_issues_last_365_days: u64,
+
+    // Default branch protection
+
+    pub branch_protected: bool,
+    /// Only known on GitHub when the token has admin access to the repository
+    pub required_approving_reviews: u64,
+    pub required_status_checks: bool,
+
+    // Owner
+
+    /// Whether the repository belongs to a GitHub organization that is verified or requires two-factor authentication
+    pub verified_org: bool,
+
+    // Commits
+
+    /// Fraction of the commits of the last 90 days with a verified signature, `None` when there were none
+    pub verified_commit_ratio: Option<f64>,
+
+    /// SPDX identifiers of the licenses the hosting service detected in the repository
+    pub repo_licenses: Vec<CompactString>,
+
+    /// When the data was fetched, `None` for data cached before fetch times were recorded
+    #[serde(default)]
+    pub fetched_at: Option<DateTime<Utc>>,
 }
@@ -1,10 +1,16 @@
 mod age_stats;
 mod client;
+mod github_app;
 mod hosting_data;
+mod provenance;
 mod provider;
+mod settings;
 mod time_window_stats;
 
 pub use age_stats::AgeStats;
+pub use client::ApiUsage;
+pub use github_app::GitHubApp;
 pub use hosting_data::HostingData;
 pub use provider::Provider;
+pub use settings::{CustomHost, HostKind, HostingSettings};
 pub use time_window_stats::TimeWindowStats;
@@ -0,0 +1,107 @@
+//! Assessment of SLSA provenance attestations published alongside releases.
+//!
+//! Attestations are read from in-toto JSON Lines files, either as bare DSSE envelopes (as produced by
+//! `slsa-github-generator`) or wrapped in Sigstore bundles. Neither the envelopes' signatures, their certificate
+//! chains, the builder identity, nor the attested subjects are verified, so an attestation only ever supports
+//! level 1: anyone able to upload a release asset can write one claiming any builder.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde_json::Value;
+
+/// Predicate type prefix shared by all SLSA provenance versions
+const SLSA_PROVENANCE_PREFIX: &str = "https://slsa.dev/provenance/";
+
+/// Highest level an unverified attestation can support
+const UNVERIFIED_LEVEL: u64 = 1;
+
+/// Determine the SLSA build level supported by a provenance file.
+///
+/// - 0: no SLSA provenance statement was found
+/// - 1: a provenance statement exists
+///
+/// Levels 2 and 3 require verifying the attestation, which isn't done yet.
+pub fn provenance_level(jsonl: &str) -> u64 {
+    jsonl
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .map(|value| envelope_level(&value))
+        .max()
+        .unwrap_or(0)
+}
+
+fn envelope_level(value: &Value) -> u64 {
+    let envelope = value.get("dsseEnvelope").unwrap_or(value);
+
+    let is_slsa = envelope
+        .get("payload")
+        .and_then(Value::as_str)
+        .and_then(|payload| STANDARD.decode(payload).ok())
+        .and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok())
+        .is_some_and(|statement| {
+            statement
+                .get("predicateType")
+                .and_then(Value::as_str)
+                .is_some_and(|t| t.starts_with(SLSA_PROVENANCE_PREFIX))
+        });
+
+    if is_slsa { UNVERIFIED_LEVEL } else { 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn envelope(predicate_type: &str, predicate: &Value, sig: &str) -> String {
+        let statement = json!({
+            "_type": "https://in-toto.io/Statement/v0.1",
+            "predicateType": predicate_type,
+            "subject": [],
+            "predicate": predicate,
+        });
+        json!({
+            "payloadType": "application/vnd.in-toto+json",
+            "payload": STANDARD.encode(statement.to_string()),
+            "signatures": [{ "keyid": "", "sig": sig }],
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_provenance_level_empty() {
+        assert_eq!(provenance_level(""), 0);
+        assert_eq!(provenance_level("not json\n"), 0);
+    }
+
+    #[test]
+    fn test_provenance_level_not_slsa() {
+        let line = envelope("https://spdx.dev/Document", &json!({}), "c2ln");
+        assert_eq!(provenance_level(&line), 0);
+    }
+
+    #[test]
+    fn test_provenance_level_unsigned() {
+        let line = envelope(
+            "https://slsa.dev/provenance/v0.2",
+            &json!({ "builder": { "id": "https://github.com/actions/runner" } }),
+            "",
+        );
+        assert_eq!(provenance_level(&line), 1);
+    }
+
+    #[test]
+    fn test_provenance_level_unverified_is_capped() {
+        let line = envelope(
+            "https://slsa.dev/provenance/v1",
+            &json!({ "runDetails": { "builder": { "id": "https://github.com/actions/runner/github-hosted" } } }),
+            "c2ln",
+        );
+        assert_eq!(provenance_level(&line), 1);
+
+        let builder = "https://github.com/slsa-framework/slsa-github-generator/.github/workflows/generator_generic_slsa3.yml@refs/tags/v1.10.0";
+        let line = envelope("https://slsa.dev/provenance/v0.2", &json!({ "builder": { "id": builder } }), "c2ln");
+        let bundle = json!({ "dsseEnvelope": serde_json::from_str::<Value>(&line).unwrap() }).to_string();
+        assert_eq!(provenance_level(&bundle), 1);
+    }
+}
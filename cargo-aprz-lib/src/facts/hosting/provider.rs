@@ -1,7 +1,8 @@
-use super::client::{Client, HostingApiResult, Issue, IssueState, RateLimitInfo, Repository};
-use super::{AgeStats, HostingData, TimeWindowStats};
+use super::client::{ApiUsage, Branch, BranchProtection, Client, Commit, HostingApiResult, Issue, IssueState, Organization, RateLimitInfo, Release, ReleaseAsset, Repository};
+use super::provenance::provenance_level;
+use super::{AgeStats, CustomHost, HostKind, HostingData, HostingSettings, TimeWindowStats};
 use crate::Result;
-use crate::facts::ProviderResult;
+use crate::facts::{Cancellation, ProviderResult};
 use crate::facts::RepoSpec;
 use crate::facts::cache::{Cache, CacheResult};
 use crate::facts::crate_spec::{self, CrateSpec};
@@ -15,6 +16,7 @@ use futures_util::future::join_all;
 use ohno::EnrichableExt;
 use reqwest::header::LINK;
 use crate::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 const LOG_TARGET: &str = "   hosting";
@@ -22,6 +24,35 @@ const SECONDS_PER_DAY: f64 = 86400.0;
 const ISSUE_LOOKBACK_DAYS: i64 = 365 * 10;
 const ISSUE_PAGE_SIZE: u8 = 100;
 const MAX_ISSUE_PAGES: u32 = 10;
+const RELEASE_PAGE_SIZE: u8 = 100;
+const DISCUSSION_PAGE_SIZE: u8 = 100;
+const MERGED_PR_PAGE_SIZE: u8 = 50;
+const COMMIT_PAGE_SIZE: u8 = 100;
+const COMMIT_LOOKBACK_DAYS: i64 = 90;
+
+/// File name suffixes of release assets holding signatures or provenance attestations.
+const SIGNATURE_ASSET_SUFFIXES: &[&str] = &[".sig", ".asc", ".minisig", ".sigstore", ".sigstore.json", ".intoto.jsonl"];
+
+/// File name suffix of release assets holding in-toto provenance attestations
+const PROVENANCE_ASSET_SUFFIX: &str = ".intoto.jsonl";
+
+/// Issue labels commonly used to mark user support questions
+const QUESTION_LABELS: &[&str] = &["question", "support", "help", "usage"];
+
+/// Fetches the creation times of the most recent discussions
+const DISCUSSIONS_QUERY: &str = "query($owner: String!, $repo: String!, $count: Int!) { \
+    repository(owner: $owner, name: $repo) { \
+        discussions(first: $count, orderBy: {field: CREATED_AT, direction: DESC}) { nodes { createdAt } } \
+    } \
+}";
+/// Fetches the number of approving reviews of the most recently merged pull requests
+const MERGED_PULLS_QUERY: &str = "query($owner: String!, $repo: String!, $count: Int!) { \
+    repository(owner: $owner, name: $repo) { \
+        pullRequests(states: MERGED, first: $count, orderBy: {field: UPDATED_AT, direction: DESC}) { \
+            nodes { reviews(states: APPROVED) { totalCount } } \
+        } \
+    } \
+}";
 const MAX_RATE_LIMIT_WAIT_SECS: u64 = 3600;
 const MAX_CONCURRENT_REQUESTS: usize = 5;
 
@@ -37,6 +68,8 @@ struct Host {
     display_name: &'static str,
     /// Whether to use `watchers_count` field instead of `subscribers_count`
     use_watchers_for_subscribers: bool,
+    /// GraphQL endpoint, used to query discussions
+    graphql_url: Option<&'static str>,
 }
 
 /// Supported hosting providers
@@ -46,15 +79,31 @@ static SUPPORTED_HOSTS: &[Host] = &[
         base_url: "https://api.github.com",
         display_name: "GitHub",
         use_watchers_for_subscribers: false,
+        graphql_url: Some("https://api.github.com/graphql"),
     },
     Host {
         host_domain: "codeberg.org",
         base_url: "https://codeberg.org/api/v1",
         display_name: "Codeberg",
         use_watchers_for_subscribers: true,
+        graphql_url: None,
     },
 ];
 
+impl Host {
+    /// Describe a self-hosted service. Its strings are leaked, as hosts live for the whole run.
+    fn custom(custom: &CustomHost) -> Self {
+        let leak = |s: String| -> &'static str { Box::leak(s.into_boxed_str()) };
+        Self {
+            host_domain: leak(custom.domain.clone()),
+            base_url: leak(custom.api_base_url.trim_end_matches('/').to_string()),
+            display_name: leak(custom.domain.clone()),
+            use_watchers_for_subscribers: custom.kind == HostKind::Gitea,
+            graphql_url: custom.graphql_url().map(leak),
+        }
+    }
+}
+
 /// Macro to unwrap `HostingApiResult` or propagate rate limit/error
 macro_rules! unwrap_or_return {
     ($expr:expr) => {
@@ -130,6 +179,11 @@ impl RepoData {
         }
     }
 
+    /// Create `RepoData` for a repository left alone because the run was cancelled
+    const fn cancelled(repo_spec: RepoSpec) -> Self {
+        Self::from_cache(repo_spec, ProviderResult::Cancelled)
+    }
+
     /// Create `RepoData` from successful fetch
     const fn success(repo_spec: RepoSpec, result: ProviderResult<HostingData>, rate_limit: Option<RateLimitInfo>) -> Self {
         Self {
@@ -139,6 +193,27 @@ impl RepoData {
             is_rate_limited: false,
         }
     }
+
+    /// Create `RepoData` for a repository to retry once the rate limit resets
+    fn rate_limited(repo_spec: RepoSpec, rate_limit: Option<RateLimitInfo>) -> Self {
+        Self {
+            repo_spec,
+            result: ProviderResult::Error(Arc::new(ohno::app_err!("rate limited"))),
+            rate_limit,
+            is_rate_limited: true,
+        }
+    }
+}
+
+/// Repository facts fetched on a best-effort basis, left at their defaults when they can't be had.
+#[derive(Debug, Default)]
+struct BestEffortStats {
+    discussions_last_365_days: Option<u64>,
+    unreviewed_merge_ratio: Option<f64>,
+    branch_protection: BranchProtectionStats,
+    verified_commit_ratio: Option<f64>,
+    provenance: u64,
+    verified_org: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -146,35 +221,75 @@ pub struct Provider {
     hosts: Vec<(Host, Client)>,
     cache: Cache,
     throttler: Arc<Throttler>,
+    cancellation: Cancellation,
 }
 
 impl Provider {
-    pub fn new(
-        github_token: Option<&str>,
-        codeberg_token: Option<&str>,
-        cache: Cache,
-    ) -> Result<Self> {
-        let mut hosts = Vec::with_capacity(SUPPORTED_HOSTS.len());
+    /// Create the provider for the public hosts and the configured self-hosted ones, authenticating to GitHub as
+    /// the GitHub App when given, and with the GitHub tokens otherwise. Requests are spread across the tokens of
+    /// each host according to the quota each has left.
+    pub fn new(settings: HostingSettings, cache: Cache) -> Result<Self> {
+        let github_app = settings.github_app.map(Arc::new);
+        let mut hosts = Vec::with_capacity(SUPPORTED_HOSTS.len() + settings.custom_hosts.len());
 
         for host in SUPPORTED_HOSTS {
-            // Map host domain to appropriate token
-            let token = match host.host_domain {
-                "github.com" => github_token,
-                "codeberg.org" => codeberg_token,
-                _ => None,
+            if settings.custom_hosts.iter().any(|custom| custom.domain == host.host_domain) {
+                continue;
+            }
+
+            // Map host domain to appropriate tokens
+            let tokens: &[String] = match host.host_domain {
+                "github.com" => &settings.github_tokens,
+                "codeberg.org" => &settings.codeberg_tokens,
+                _ => &[],
             };
 
-            let client = Client::new(token, host.base_url)?;
+            let mut client = Client::new(tokens, host.base_url)?;
+            if host.host_domain == "github.com"
+                && let Some(app) = &github_app
+            {
+                client = client.with_app(Arc::clone(app));
+            }
+
             hosts.push((*host, client));
         }
 
+        for custom in &settings.custom_hosts {
+            hosts.push((Host::custom(custom), Client::new(&custom.tokens, custom.api_base_url.trim_end_matches('/'))?));
+        }
+
         Ok(Self {
             hosts,
             cache,
             throttler: Throttler::new(MAX_CONCURRENT_REQUESTS),
+            cancellation: Cancellation::new(),
         })
     }
 
+    /// Stop starting requests once `cancellation` is cancelled, including while waiting out a rate limit.
+    #[must_use]
+    pub fn with_cancellation(mut self, cancellation: Cancellation) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// Report the API quota used on each hosting service queried so far.
+    #[must_use]
+    pub fn api_usage(&self) -> Vec<ApiUsage> {
+        self.hosts
+            .iter()
+            .map(|(host, client)| client.api_usage(host.display_name))
+            .filter(|usage| usage.requests > 0)
+            .collect()
+    }
+
+    /// The cached document holding the hosting data of a crate's repository, if any.
+    #[must_use]
+    pub fn cached_document(&self, crate_spec: &CrateSpec) -> Option<PathBuf> {
+        let repo_spec = crate_spec.repo_spec()?;
+        self.cache.entry_path(&Self::get_cache_filename(repo_spec.host(), repo_spec.owner(), repo_spec.repo()))
+    }
+
     pub async fn get_hosting_data(
         &self,
         crates: impl IntoIterator<Item = CrateSpec> + Send + 'static,
@@ -183,15 +298,15 @@ impl Provider {
         let repo_to_crates = crate_spec::by_repo(crates);
 
         // Group repos by host domain
-        let mut repos_by_host: HashMap<&'static str, Vec<RepoSpec>> = crate::hash_map_with_capacity(SUPPORTED_HOSTS.len());
-        let mut crates_by_host: HashMap<&'static str, HashMap<RepoSpec, Vec<CrateSpec>>> = crate::hash_map_with_capacity(SUPPORTED_HOSTS.len());
+        let mut repos_by_host: HashMap<&'static str, Vec<RepoSpec>> = crate::hash_map_with_capacity(self.hosts.len());
+        let mut crates_by_host: HashMap<&'static str, HashMap<RepoSpec, Vec<CrateSpec>>> = crate::hash_map_with_capacity(self.hosts.len());
         let mut unknown_host_crates: Vec<(CrateSpec, CompactString)> = Vec::new();
 
         for (repo_spec, crate_specs) in repo_to_crates {
             let host_domain = repo_spec.host();
 
             // Check if this host is supported
-            if let Some(host) = SUPPORTED_HOSTS.iter().find(|h| h.host_domain == host_domain) {
+            if let Some((host, _)) = self.hosts.iter().find(|(h, _)| h.host_domain == host_domain) {
                 repos_by_host.entry(host.host_domain).or_default().push(repo_spec.clone());
                 let _ = crates_by_host.entry(host.host_domain).or_default().insert(repo_spec, crate_specs);
             } else {
@@ -273,7 +388,11 @@ impl Provider {
         tracker: &RequestTracker,
     ) -> RepoData {
         loop {
-            let _permit = self.throttler.acquire().await;
+            let Some(_permit) = self.throttler.acquire_unless_cancelled(&self.cancellation).await else {
+                tracker.complete_request(TrackedTopic::Repos);
+                return RepoData::cancelled(repo_spec);
+            };
+
             let result = self.fetch_hosting_data_for_repo(client, host, repo_spec.clone()).await;
 
             if result.is_rate_limited {
@@ -359,12 +478,7 @@ impl Provider {
         // If the throttler is paused due to a rate limit detected by another task,
         // skip HTTP calls and signal rate-limited so the caller retries after the pause.
         if self.throttler.is_paused() {
-            return RepoData {
-                repo_spec,
-                result: ProviderResult::Error(Arc::new(ohno::app_err!("rate limited"))),
-                rate_limit: None,
-                is_rate_limited: true,
-            };
+            return RepoData::rate_limited(repo_spec, None);
         }
 
         log::info!(target: LOG_TARGET, "Querying {} for information on repository '{repo_spec}'", host.display_name);
@@ -381,19 +495,30 @@ impl Provider {
         // Use rate_limit: None so fetch_with_retry doesn't extend the pause with
         // primary rate limit info from the successful repo request.
         if self.throttler.is_paused() {
-            return RepoData {
-                repo_spec,
-                result: ProviderResult::Error(Arc::new(ohno::app_err!("rate limited"))),
-                rate_limit: None,
-                is_rate_limited: true,
-            };
+            return RepoData::rate_limited(repo_spec, None);
         }
 
         let issues_res = self.get_issues_and_pulls(client, owner, repo).await;
         let (issue_pull_stats, issues_rate_limit) = unwrap_repo_result!(issues_res, repo_spec, "issues and pull request info", self.cache, &filename, "issues/PRs");
 
+        if self.throttler.is_paused() {
+            return RepoData::rate_limited(repo_spec, None);
+        }
+
+        let releases_res = self.get_releases(client, owner, repo).await;
+        let (release_stats, releases_rate_limit) = unwrap_repo_result!(releases_res, repo_spec, "release info", self.cache, &filename, "releases");
+
+        let discussions_enabled = repo_data.has_discussions.unwrap_or(false);
+        let best_effort = match self
+            .fetch_best_effort_stats(client, host, &repo_spec, &repo_data, &release_stats, discussions_enabled)
+            .await
+        {
+            Ok(stats) => stats,
+            Err(rate_limit) => return RepoData::rate_limited(repo_spec, Some(rate_limit)),
+        };
+
         // Use the most conservative rate limit info (the one with the least remaining quota)
-        let rate_limit = [issues_rate_limit, repo_rate_limit]
+        let rate_limit = [releases_rate_limit, issues_rate_limit, repo_rate_limit]
             .into_iter()
             .flatten()
             .min_by_key(|rl| rl.remaining);
@@ -428,9 +553,25 @@ impl Provider {
             merged_pr_age_last_90_days: issue_pull_stats.merged_pr_age_last_90_days,
             merged_pr_age_last_180_days: issue_pull_stats.merged_pr_age_last_180_days,
             merged_pr_age_last_365_days: issue_pull_stats.merged_pr_age_last_365_days,
+            releases: release_stats.releases,
+            prereleases: release_stats.prereleases,
+            latest_release_has_notes: release_stats.latest_release_has_notes,
+            latest_release_signed: release_stats.latest_release_signed,
+            provenance_level: best_effort.provenance,
+            discussions_enabled,
+            discussions_last_365_days: best_effort.discussions_last_365_days,
+            question_issues_last_365_days: issue_pull_stats.question_issues_last_365_days,
+            branch_protected: best_effort.branch_protection.protected,
+            required_approving_reviews: best_effort.branch_protection.required_approving_reviews,
+            required_status_checks: best_effort.branch_protection.required_status_checks,
+            verified_org: best_effort.verified_org,
+            repo_licenses: repo_licenses(&repo_data),
+            unreviewed_merge_ratio: best_effort.unreviewed_merge_ratio,
+            verified_commit_ratio: best_effort.verified_commit_ratio,
+            fetched_at: Some(Utc::now()),
         };
 
-        let total_requests = 1 + issue_pull_stats.request_count;
+        let total_requests = 2 + issue_pull_stats.request_count;
         log::debug!(target: LOG_TARGET, "Completed {total_requests} {} API request(s) for repository '{repo_spec}'", host.display_name);
 
         let result = match self.cache.save(&filename, &hosting_data) {
@@ -441,6 +582,63 @@ impl Provider {
         RepoData::success(repo_spec, result, rate_limit)
     }
 
+    /// Fetch the repository facts which are only available to some tokens or for some repositories. Failures
+    /// leave the facts at their defaults, and only a rate limit is reported, as the repository must then be retried.
+    async fn fetch_best_effort_stats(
+        &self,
+        client: &Client,
+        host: &Host,
+        repo_spec: &RepoSpec,
+        repo_data: &Repository,
+        release_stats: &ReleaseStats,
+        discussions_enabled: bool,
+    ) -> Result<BestEffortStats, RateLimitInfo> {
+        let owner = repo_spec.owner();
+        let repo = repo_spec.repo();
+        let mut stats = BestEffortStats::default();
+
+        // Discussions are only available through GraphQL, which requires authentication.
+        // Failures are not fatal since question-labelled issues serve as a fallback signal.
+        if discussions_enabled
+            && client.is_authenticated()
+            && let Some(graphql_url) = host.graphql_url
+        {
+            let result = self.get_discussions(client, graphql_url, owner, repo).await;
+            stats.discussions_last_365_days = best_effort_value(result, "discussions", repo_spec)?;
+        }
+
+        // Reviews of merged pull requests also come from GraphQL, and are just as optional
+        if client.is_authenticated()
+            && let Some(graphql_url) = host.graphql_url
+        {
+            let result = self.get_unreviewed_merge_ratio(client, graphql_url, owner, repo).await;
+            stats.unreviewed_merge_ratio = best_effort_value(result, "pull request reviews", repo_spec)?.flatten();
+        }
+
+        // The default branch may be missing, and GitHub only reveals the full protection rules to tokens with admin access
+        if let Some(branch) = repo_data.default_branch.as_deref() {
+            let result = self.get_branch_protection(client, owner, repo, branch).await;
+            stats.branch_protection = best_effort_value(result, "branch protection", repo_spec)?.unwrap_or_default();
+        }
+
+        let result = self.get_verified_commit_ratio(client, owner, repo).await;
+        stats.verified_commit_ratio = best_effort_value(result, "commits", repo_spec)?.flatten();
+
+        // Provenance is only checked when the latest release publishes an attestation
+        if let Some(url) = release_stats.provenance_url.as_deref() {
+            let result = self.get_provenance_level(client, url).await;
+            stats.provenance = best_effort_value(result, "provenance", repo_spec)?.unwrap_or(0);
+        }
+
+        // GitHub only reports the two-factor requirement of an organization to its members
+        if repo_data.owner.as_ref().and_then(|o| o.kind.as_deref()) == Some("Organization") {
+            let result = self.get_organization(client, owner).await;
+            stats.verified_org = best_effort_value(result, "organization info", repo_spec)?.is_some_and(|org| is_verified_org(&org));
+        }
+
+        Ok(stats)
+    }
+
     /// Get the cache filename for a specific repository
     fn get_cache_filename(host_domain: &str, owner: &str, repo: &str) -> String {
         let safe_host = sanitize_path_component(host_domain);
@@ -531,6 +729,296 @@ impl Provider {
 
         HostingApiResult::Success(stats, latest_rate_limit)
     }
+
+    /// Fetch the most recent page of releases. A single page covers well over a year for nearly all repositories.
+    async fn get_releases(&self, client: &Client, owner: &str, repo: &str) -> HostingApiResult<ReleaseStats> {
+        let url = Self::repo_url(client, owner, repo, &format!("/releases?per_page={RELEASE_PAGE_SIZE}"));
+
+        let (resp, rate_limit) = unwrap_or_return!(client.api_call(&url).await);
+        match resp.json::<Vec<Release>>().await {
+            Ok(releases) => HostingApiResult::Success(compute_release_stats(&releases, Utc::now()), rate_limit),
+            Err(e) => HostingApiResult::Failed(e.into(), rate_limit),
+        }
+    }
+
+    /// Determine the fraction of the commits of the last 90 days whose signature the hosting service verified.
+    ///
+    /// Only the most recent page of commits is considered.
+    async fn get_verified_commit_ratio(&self, client: &Client, owner: &str, repo: &str) -> HostingApiResult<Option<f64>> {
+        let since = (Utc::now() - chrono::Duration::days(COMMIT_LOOKBACK_DAYS)).to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+        let url = Self::repo_url(client, owner, repo, &format!("/commits?since={since}&per_page={COMMIT_PAGE_SIZE}"));
+
+        let (resp, rate_limit) = unwrap_or_return!(client.api_call(&url).await);
+        match resp.json::<Vec<Commit>>().await {
+            Ok(commits) => HostingApiResult::Success(compute_verified_commit_ratio(&commits), rate_limit),
+            Err(e) => HostingApiResult::Failed(e.into(), rate_limit),
+        }
+    }
+
+    /// Fetch the protection rules of a branch.
+    ///
+    /// GitHub's branch summary omits required reviews, so when authenticated the full protection
+    /// rules are requested too. That request fails for tokens without admin access, which is not an error.
+    async fn get_branch_protection(
+        &self,
+        client: &Client,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+    ) -> HostingApiResult<BranchProtectionStats> {
+        let url = Self::repo_url(client, owner, repo, &format!("/branches/{branch}"));
+
+        let (resp, rate_limit) = unwrap_or_return!(client.api_call(&url).await);
+        let branch_info: Branch = match resp.json().await {
+            Ok(b) => b,
+            Err(e) => return HostingApiResult::Failed(e.into(), rate_limit),
+        };
+
+        let mut stats = compute_branch_protection_stats(&branch_info);
+        if !stats.protected || branch_info.required_approvals.is_some() || !client.is_authenticated() {
+            return HostingApiResult::Success(stats, rate_limit);
+        }
+
+        let url = Self::repo_url(client, owner, repo, &format!("/branches/{branch}/protection"));
+        match client.api_call(&url).await {
+            HostingApiResult::Success(resp, protection_rate_limit) => {
+                if let Ok(protection) = resp.json::<BranchProtection>().await {
+                    apply_branch_protection(&mut stats, &protection);
+                }
+                let rate_limit = [rate_limit, protection_rate_limit].into_iter().flatten().min_by_key(|rl| rl.remaining);
+                HostingApiResult::Success(stats, rate_limit)
+            }
+            HostingApiResult::RateLimited(rate_limit) => HostingApiResult::RateLimited(rate_limit),
+            HostingApiResult::NotFound(_) | HostingApiResult::Failed(..) => {
+                log::debug!(target: LOG_TARGET, "Branch protection rules for '{owner}/{repo}' are not visible with the current token");
+                HostingApiResult::Success(stats, rate_limit)
+            }
+        }
+    }
+
+    async fn get_organization(&self, client: &Client, org: &str) -> HostingApiResult<Organization> {
+        let url = format!("{}/orgs/{org}", client.base_url());
+
+        let (resp, rate_limit) = unwrap_or_return!(client.api_call(&url).await);
+        match resp.json().await {
+            Ok(org_info) => HostingApiResult::Success(org_info, rate_limit),
+            Err(e) => HostingApiResult::Failed(e.into(), rate_limit),
+        }
+    }
+
+    /// Download a release's provenance attestation and determine the SLSA level it supports.
+    async fn get_provenance_level(&self, client: &Client, url: &str) -> HostingApiResult<u64> {
+        let (resp, rate_limit) = unwrap_or_return!(client.api_call(url).await);
+        match resp.text().await {
+            Ok(text) => HostingApiResult::Success(provenance_level(&text), rate_limit),
+            Err(e) => HostingApiResult::Failed(e.into(), rate_limit),
+        }
+    }
+
+    /// Determine the fraction of the most recently merged pull requests which were merged without an approving review.
+    async fn get_unreviewed_merge_ratio(
+        &self,
+        client: &Client,
+        graphql_url: &str,
+        owner: &str,
+        repo: &str,
+    ) -> HostingApiResult<Option<f64>> {
+        let body = serde_json::json!({
+            "query": MERGED_PULLS_QUERY,
+            "variables": { "owner": owner, "repo": repo, "count": MERGED_PR_PAGE_SIZE },
+        });
+
+        let (resp, rate_limit) = unwrap_or_return!(client.graphql_call(graphql_url, &body).await);
+        match resp.json::<serde_json::Value>().await {
+            Ok(value) => HostingApiResult::Success(compute_unreviewed_merge_ratio(&value), rate_limit),
+            Err(e) => HostingApiResult::Failed(e.into(), rate_limit),
+        }
+    }
+
+    /// Count the discussions created in the last 365 days, looking at the most recent page only.
+    async fn get_discussions(&self, client: &Client, graphql_url: &str, owner: &str, repo: &str) -> HostingApiResult<u64> {
+        let body = serde_json::json!({
+            "query": DISCUSSIONS_QUERY,
+            "variables": { "owner": owner, "repo": repo, "count": DISCUSSION_PAGE_SIZE },
+        });
+
+        let (resp, rate_limit) = unwrap_or_return!(client.graphql_call(graphql_url, &body).await);
+        match resp.json::<serde_json::Value>().await {
+            Ok(value) => HostingApiResult::Success(count_recent_discussions(&value, Utc::now()), rate_limit),
+            Err(e) => HostingApiResult::Failed(e.into(), rate_limit),
+        }
+    }
+}
+
+/// Compute the fraction of commits carrying a verified signature, or `None` when there are no commits.
+#[expect(clippy::cast_precision_loss, reason = "acceptable for statistics")]
+fn compute_verified_commit_ratio(commits: &[Commit]) -> Option<f64> {
+    if commits.is_empty() {
+        return None;
+    }
+
+    let verified = commits
+        .iter()
+        .filter(|c| c.commit.verification.as_ref().is_some_and(|v| v.verified))
+        .count();
+
+    Some(verified as f64 / commits.len() as f64)
+}
+
+/// Compute the fraction of merged pull requests in a GraphQL response which had no approving review.
+///
+/// Returns `None` when the response holds no merged pull requests.
+#[expect(clippy::cast_precision_loss, reason = "acceptable for statistics")]
+fn compute_unreviewed_merge_ratio(response: &serde_json::Value) -> Option<f64> {
+    let nodes = response
+        .pointer("/data/repository/pullRequests/nodes")
+        .and_then(serde_json::Value::as_array)
+        .filter(|nodes| !nodes.is_empty())?;
+
+    let unreviewed = nodes
+        .iter()
+        .filter(|node| node.pointer("/reviews/totalCount").and_then(serde_json::Value::as_u64).unwrap_or(0) == 0)
+        .count();
+
+    Some(unreviewed as f64 / nodes.len() as f64)
+}
+
+/// Count the discussion nodes of a GraphQL response created within the last 365 days.
+fn count_recent_discussions(response: &serde_json::Value, now: DateTime<Utc>) -> u64 {
+    let cutoff = now - chrono::Duration::days(365);
+    response
+        .pointer("/data/repository/discussions/nodes")
+        .and_then(serde_json::Value::as_array)
+        .map_or(0, |nodes| {
+            nodes
+                .iter()
+                .filter_map(|node| node.get("createdAt")?.as_str()?.parse::<DateTime<Utc>>().ok())
+                .filter(|created| *created >= cutoff)
+                .count() as u64
+        })
+}
+
+/// The value of a best-effort request, or `None` when it failed. Only a rate limit is an error.
+fn best_effort_value<T>(result: HostingApiResult<T>, what: &str, repo_spec: &RepoSpec) -> Result<Option<T>, RateLimitInfo> {
+    match result {
+        HostingApiResult::Success(value, _) => Ok(Some(value)),
+        HostingApiResult::RateLimited(rate_limit) => Err(rate_limit),
+        HostingApiResult::NotFound(_) => Ok(None),
+        HostingApiResult::Failed(e, _) => {
+            log::debug!(target: LOG_TARGET, "Could not fetch {what} for '{repo_spec}': {e:#}");
+            Ok(None)
+        }
+    }
+}
+
+/// Whether an organization is verified or requires its members to use two-factor authentication.
+fn is_verified_org(org: &Organization) -> bool {
+    org.is_verified || org.two_factor_requirement_enabled == Some(true)
+}
+
+/// The SPDX identifiers of the licenses detected in a repository, leaving out those that weren't identified.
+fn repo_licenses(repo: &Repository) -> Vec<CompactString> {
+    repo.license
+        .iter()
+        .filter_map(|license| license.spdx_id.as_deref())
+        .chain(repo.licenses.iter().map(String::as_str))
+        .filter(|id| !id.is_empty() && *id != "NOASSERTION")
+        .map(CompactString::from)
+        .collect()
+}
+
+/// Whether an issue carries a label marking it as a user support question.
+fn is_question(issue: &Issue) -> bool {
+    issue
+        .labels
+        .iter()
+        .any(|label| QUESTION_LABELS.iter().any(|q| label.name.to_lowercase().contains(q)))
+}
+
+/// Protection rules of the default branch.
+#[derive(Debug, Default)]
+struct BranchProtectionStats {
+    protected: bool,
+    required_approving_reviews: u64,
+    required_status_checks: bool,
+}
+
+/// Compute branch protection statistics from the branch API data.
+fn compute_branch_protection_stats(branch: &Branch) -> BranchProtectionStats {
+    let mut stats = BranchProtectionStats {
+        protected: branch.protected,
+        required_approving_reviews: branch.required_approvals.unwrap_or(0),
+        required_status_checks: branch.enable_status_check.unwrap_or(false),
+    };
+
+    if let Some(protection) = &branch.protection {
+        apply_branch_protection(&mut stats, protection);
+    }
+
+    stats
+}
+
+/// Merge GitHub branch protection rules into the statistics.
+const fn apply_branch_protection(stats: &mut BranchProtectionStats, protection: &BranchProtection) {
+    if let Some(reviews) = &protection.required_pull_request_reviews {
+        stats.required_approving_reviews = reviews.required_approving_review_count;
+    }
+
+    if let Some(checks) = &protection.required_status_checks {
+        stats.required_status_checks |= !checks.contexts.is_empty();
+    }
+}
+
+/// Statistics computed from the releases API data.
+struct ReleaseStats {
+    releases: TimeWindowStats,
+    prereleases: TimeWindowStats,
+    latest_release_has_notes: bool,
+    latest_release_signed: bool,
+    /// Download URL of the latest release's provenance attestation
+    provenance_url: Option<String>,
+}
+
+/// Compute release statistics, ignoring drafts.
+fn compute_release_stats(releases: &[Release], now: DateTime<Utc>) -> ReleaseStats {
+    let cutoff_90 = now - chrono::Duration::days(90);
+    let cutoff_180 = now - chrono::Duration::days(180);
+    let cutoff_365 = now - chrono::Duration::days(365);
+
+    let mut stats = ReleaseStats {
+        releases: TimeWindowStats::default(),
+        prereleases: TimeWindowStats::default(),
+        latest_release_has_notes: false,
+        latest_release_signed: false,
+        provenance_url: None,
+    };
+
+    let mut latest: Option<(DateTime<Utc>, &Release)> = None;
+    for release in releases.iter().filter(|r| !r.draft) {
+        let published = release.published_at.unwrap_or(release.created_at);
+        let window = if release.prerelease { &mut stats.prereleases } else { &mut stats.releases };
+        increment_window(window, published, cutoff_90, cutoff_180, cutoff_365);
+
+        if !release.prerelease && latest.is_none_or(|(ts, _)| published > ts) {
+            latest = Some((published, release));
+        }
+    }
+
+    stats.latest_release_has_notes = latest.is_some_and(|(_, r)| r.body.as_deref().is_some_and(|b| !b.trim().is_empty()));
+    stats.latest_release_signed = latest.is_some_and(|(_, r)| r.assets.iter().any(is_signature_asset));
+    stats.provenance_url = latest.and_then(|(_, r)| {
+        r.assets
+            .iter()
+            .find(|a| a.name.to_lowercase().ends_with(PROVENANCE_ASSET_SUFFIX))
+            .and_then(|a| a.browser_download_url.clone())
+    });
+    stats
+}
+
+/// Whether a release asset holds a signature or provenance attestation.
+fn is_signature_asset(asset: &ReleaseAsset) -> bool {
+    let name = asset.name.to_lowercase();
+    SIGNATURE_ASSET_SUFFIXES.iter().any(|suffix| name.ends_with(suffix))
 }
 
 /// Compute age statistics from an iterator of durations in seconds.
@@ -604,6 +1092,7 @@ struct IssueAndPullStats {
     merged_pr_age_last_90_days: AgeStats,
     merged_pr_age_last_180_days: AgeStats,
     merged_pr_age_last_365_days: AgeStats,
+    question_issues_last_365_days: u64,
 }
 
 /// Increment time window counters for a given timestamp.
@@ -637,6 +1126,7 @@ fn compute_all_stats(all_issues: &[Issue], now: DateTime<Utc>) -> IssueAndPullSt
     let mut prs_opened = TimeWindowStats::default();
     let mut prs_merged = TimeWindowStats::default();
     let mut prs_closed = TimeWindowStats::default();
+    let mut question_issues_last_365_days = 0;
 
     // Classify issues/PRs and compute windowed counts in a single pass
     for issue in all_issues {
@@ -660,6 +1150,9 @@ fn compute_all_stats(all_issues: &[Issue], now: DateTime<Utc>) -> IssueAndPullSt
                 increment_window(&mut prs_merged, merged, cutoff_90, cutoff_180, cutoff_365);
             }
         } else {
+            if issue.created_at >= cutoff_365 && is_question(issue) {
+                question_issues_last_365_days += 1;
+            }
             increment_window(&mut issues_opened, issue.created_at, cutoff_90, cutoff_180, cutoff_365);
             if let Some(closed) = issue.closed_at {
                 increment_window(&mut issues_closed, closed, cutoff_90, cutoff_180, cutoff_365);
@@ -727,6 +1220,7 @@ fn compute_all_stats(all_issues: &[Issue], now: DateTime<Utc>) -> IssueAndPullSt
         merged_pr_age_last_90_days,
         merged_pr_age_last_180_days,
         merged_pr_age_last_365_days,
+        question_issues_last_365_days,
     }
 }
 
@@ -815,7 +1309,7 @@ mod tests {
     #[test]
     #[cfg_attr(miri, ignore = "Miri cannot call GetSystemTimePreciseAsFileTime")]
     fn test_repo_url() {
-        let client = Client::new(None, "https://api.github.com").unwrap();
+        let client = Client::new(&[], "https://api.github.com").unwrap();
 
         let url = Provider::repo_url(&client, "tokio-rs", "tokio", "");
         assert_eq!(url, "https://api.github.com/repos/tokio-rs/tokio");
@@ -849,6 +1343,22 @@ mod tests {
             merged_pr_age_last_90_days: AgeStats::default(),
             merged_pr_age_last_180_days: AgeStats::default(),
             merged_pr_age_last_365_days: AgeStats::default(),
+            releases: TimeWindowStats::default(),
+            prereleases: TimeWindowStats::default(),
+            latest_release_has_notes: false,
+            latest_release_signed: false,
+            provenance_level: 0,
+            discussions_enabled: false,
+            discussions_last_365_days: None,
+            question_issues_last_365_days: 0,
+            branch_protected: false,
+            required_approving_reviews: 0,
+            required_status_checks: false,
+            verified_org: false,
+            repo_licenses: Vec::new(),
+            unreviewed_merge_ratio: None,
+            verified_commit_ratio: None,
+            fetched_at: None,
         };
 
         let repo_data = RepoData::from_cache(repo_spec.clone(), ProviderResult::Found(hosting_data));
@@ -884,6 +1394,22 @@ mod tests {
             merged_pr_age_last_90_days: AgeStats::default(),
             merged_pr_age_last_180_days: AgeStats::default(),
             merged_pr_age_last_365_days: AgeStats::default(),
+            releases: TimeWindowStats::default(),
+            prereleases: TimeWindowStats::default(),
+            latest_release_has_notes: false,
+            latest_release_signed: false,
+            provenance_level: 0,
+            discussions_enabled: false,
+            discussions_last_365_days: None,
+            question_issues_last_365_days: 0,
+            branch_protected: false,
+            required_approving_reviews: 0,
+            required_status_checks: false,
+            verified_org: false,
+            repo_licenses: Vec::new(),
+            unreviewed_merge_ratio: None,
+            verified_commit_ratio: None,
+            fetched_at: None,
         };
 
         let rate_limit = Some(RateLimitInfo {
@@ -902,22 +1428,50 @@ mod tests {
     #[test]
     #[cfg_attr(miri, ignore = "Miri cannot call GetSystemTimePreciseAsFileTime")]
     fn test_provider_new() {
-        let provider = Provider::new(None, None, test_cache()).unwrap();
+        let provider = Provider::new(HostingSettings::default(), test_cache()).unwrap();
         assert_eq!(provider.hosts.len(), 2); // GitHub and Codeberg
     }
 
     #[test]
     #[cfg_attr(miri, ignore = "Miri cannot call GetSystemTimePreciseAsFileTime")]
     fn test_provider_new_with_tokens() {
-        let provider = Provider::new(
-            Some("github_token"),
-            Some("codeberg_token"),
-            test_cache(),
-        )
-        .unwrap();
+        let settings = HostingSettings {
+            github_tokens: vec!["github_token".to_string()],
+            codeberg_tokens: vec!["codeberg_token".to_string(), "second_codeberg_token".to_string()],
+            ..HostingSettings::default()
+        };
+        let provider = Provider::new(settings, test_cache()).unwrap();
         assert_eq!(provider.hosts.len(), 2);
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetSystemTimePreciseAsFileTime")]
+    fn test_provider_new_with_custom_hosts() {
+        let custom = |domain: &str, api_base_url: &str, kind| CustomHost {
+            domain: domain.to_string(),
+            api_base_url: api_base_url.to_string(),
+            kind,
+            tokens: vec!["token".to_string()],
+        };
+        let settings = HostingSettings {
+            custom_hosts: vec![
+                custom("github.example.com", "https://github.example.com/api/v3/", HostKind::GitHub),
+                custom("codeberg.org", "https://codeberg.example.com/api/v1", HostKind::Gitea),
+            ],
+            ..HostingSettings::default()
+        };
+        let provider = Provider::new(settings, test_cache()).unwrap();
+
+        let domains: Vec<_> = provider.hosts.iter().map(|(host, _)| host.host_domain).collect();
+        assert_eq!(domains, ["github.com", "github.example.com", "codeberg.org"]);
+
+        let (enterprise, client) = &provider.hosts[1];
+        assert_eq!(client.base_url(), "https://github.example.com/api/v3");
+        assert_eq!(enterprise.graphql_url, Some("https://github.example.com/api/graphql"));
+        assert!(!enterprise.use_watchers_for_subscribers);
+        assert!(provider.hosts[2].0.use_watchers_for_subscribers);
+    }
+
     #[test]
     fn test_compute_age_stats_filters_nan_and_negative() {
         let stats = compute_age_stats([f64::NAN, f64::INFINITY, -100.0, 86400.0].into_iter());
@@ -935,6 +1489,7 @@ mod tests {
             closed_at: Some(closed),
             state: IssueState::Closed,
             pull_request: None,
+            labels: vec![],
         };
         let age = closed_age_seconds(&issue).unwrap();
         assert!((age - 86400.0).abs() < 1.0);
@@ -948,6 +1503,7 @@ mod tests {
             closed_at: None,
             state: IssueState::Open,
             pull_request: None,
+            labels: vec![],
         };
         assert!(closed_age_seconds(&issue).is_none());
     }
@@ -962,6 +1518,7 @@ mod tests {
             closed_at: Some(merged),
             state: IssueState::Closed,
             pull_request: Some(PullRequestMarker { merged_at: Some(merged) }),
+            labels: vec![],
         };
         let age = merged_pr_age_seconds(&issue).unwrap();
         assert!((age - 172_800.0).abs() < 1.0); // 2 days
@@ -976,6 +1533,7 @@ mod tests {
             closed_at: None,
             state: IssueState::Open,
             pull_request: Some(PullRequestMarker { merged_at: None }),
+            labels: vec![],
         };
         assert!(merged_pr_age_seconds(&issue).is_none());
     }
@@ -988,6 +1546,7 @@ mod tests {
             closed_at: None,
             state: IssueState::Open,
             pull_request: None,
+            labels: vec![],
         };
         assert!(merged_pr_age_seconds(&issue).is_none());
     }
@@ -1065,6 +1624,7 @@ mod tests {
                 closed_at: None,
                 state: IssueState::Open,
                 pull_request: None,
+                labels: vec![],
             },
             // Closed issue
             Issue {
@@ -1072,6 +1632,7 @@ mod tests {
                 closed_at: Some(day_ago),
                 state: IssueState::Closed,
                 pull_request: None,
+                labels: vec![],
             },
             // Open PR
             Issue {
@@ -1079,6 +1640,7 @@ mod tests {
                 closed_at: None,
                 state: IssueState::Open,
                 pull_request: Some(PullRequestMarker { merged_at: None }),
+                labels: vec![],
             },
             // Merged PR
             Issue {
@@ -1086,6 +1648,7 @@ mod tests {
                 closed_at: Some(two_days_ago),
                 state: IssueState::Closed,
                 pull_request: Some(PullRequestMarker { merged_at: Some(two_days_ago) }),
+                labels: vec![],
             },
         ];
 
@@ -1099,6 +1662,241 @@ mod tests {
         assert_eq!(stats.prs_closed.total, 1);
     }
 
+    fn make_release(published_at: DateTime<Utc>, prerelease: bool, draft: bool, body: Option<&str>) -> Release {
+        Release {
+            created_at: published_at,
+            published_at: Some(published_at),
+            draft,
+            prerelease,
+            body: body.map(str::to_string),
+            assets: vec![],
+        }
+    }
+
+    #[test]
+    fn test_compute_release_stats_empty() {
+        let stats = compute_release_stats(&[], Utc::now());
+        assert_eq!(stats.releases.total, 0);
+        assert_eq!(stats.prereleases.total, 0);
+        assert!(!stats.latest_release_has_notes);
+    }
+
+    #[test]
+    fn test_compute_release_stats_mixed() {
+        let now = Utc::now();
+        let releases = vec![
+            make_release(now - chrono::Duration::days(400), false, false, Some("Old release")),
+            make_release(now - chrono::Duration::days(100), false, false, Some("")),
+            make_release(now - chrono::Duration::days(10), true, false, Some("Release candidate")),
+            make_release(now - chrono::Duration::days(1), false, true, Some("Unpublished draft")),
+        ];
+
+        let stats = compute_release_stats(&releases, now);
+        assert_eq!(stats.releases.total, 2);
+        assert_eq!(stats.releases.last_365_days, 1);
+        assert_eq!(stats.releases.last_90_days, 0);
+        assert_eq!(stats.prereleases.last_90_days, 1);
+
+        // The latest non-prerelease, non-draft release has an empty body
+        assert!(!stats.latest_release_has_notes);
+    }
+
+    #[test]
+    fn test_compute_release_stats_latest_has_notes() {
+        let now = Utc::now();
+        let releases = vec![
+            make_release(now - chrono::Duration::days(5), false, false, Some("## Changes\n- Fixed things")),
+            make_release(now - chrono::Duration::days(50), false, false, None),
+        ];
+
+        assert!(compute_release_stats(&releases, now).latest_release_has_notes);
+    }
+
+    #[test]
+    fn test_compute_release_stats_latest_signed() {
+        let now = Utc::now();
+        let asset = |name: &str| ReleaseAsset {
+            name: name.to_string(),
+            browser_download_url: Some(format!("https://example.com/{name}")),
+        };
+
+        let mut latest = make_release(now - chrono::Duration::days(5), false, false, None);
+        latest.assets = vec![asset("tool-x86_64.tar.gz"), asset("tool-x86_64.tar.gz.sigstore.json")];
+        let mut older = make_release(now - chrono::Duration::days(50), false, false, None);
+        older.assets = vec![asset("tool.tar.gz.asc")];
+
+        let stats = compute_release_stats(&[latest], now);
+        assert!(stats.latest_release_signed);
+        assert!(stats.provenance_url.is_none());
+
+        let unsigned = make_release(now - chrono::Duration::days(1), false, false, None);
+        assert!(!compute_release_stats(&[older, unsigned], now).latest_release_signed);
+    }
+
+    #[test]
+    fn test_compute_release_stats_provenance_url() {
+        let now = Utc::now();
+        let mut release = make_release(now - chrono::Duration::days(5), false, false, None);
+        release.assets = vec![ReleaseAsset {
+            name: "multiple.intoto.jsonl".to_string(),
+            browser_download_url: Some("https://example.com/multiple.intoto.jsonl".to_string()),
+        }];
+
+        let stats = compute_release_stats(&[release], now);
+        assert!(stats.latest_release_signed);
+        assert_eq!(stats.provenance_url.as_deref(), Some("https://example.com/multiple.intoto.jsonl"));
+    }
+
+    #[test]
+    fn test_compute_all_stats_counts_question_issues() {
+        use super::super::client::Label;
+        let now = Utc::now();
+        let label = |name: &str| Label { name: name.to_string() };
+
+        let issues = vec![
+            Issue {
+                created_at: now - chrono::Duration::days(10),
+                closed_at: None,
+                state: IssueState::Open,
+                pull_request: None,
+                labels: vec![label("Question")],
+            },
+            Issue {
+                created_at: now - chrono::Duration::days(20),
+                closed_at: None,
+                state: IssueState::Open,
+                pull_request: None,
+                labels: vec![label("C-bug"), label("needs-support")],
+            },
+            Issue {
+                created_at: now - chrono::Duration::days(400),
+                closed_at: None,
+                state: IssueState::Open,
+                pull_request: None,
+                labels: vec![label("question")],
+            },
+            Issue {
+                created_at: now - chrono::Duration::days(5),
+                closed_at: None,
+                state: IssueState::Open,
+                pull_request: None,
+                labels: vec![label("enhancement")],
+            },
+        ];
+
+        assert_eq!(compute_all_stats(&issues, now).question_issues_last_365_days, 2);
+    }
+
+    #[test]
+    fn test_compute_branch_protection_stats_codeberg() {
+        let branch: Branch =
+            serde_json::from_value(serde_json::json!({ "protected": true, "required_approvals": 2, "enable_status_check": true }))
+                .unwrap();
+
+        let stats = compute_branch_protection_stats(&branch);
+        assert!(stats.protected);
+        assert_eq!(stats.required_approving_reviews, 2);
+        assert!(stats.required_status_checks);
+    }
+
+    #[test]
+    fn test_apply_branch_protection_github() {
+        let branch: Branch = serde_json::from_value(serde_json::json!({
+            "protected": true,
+            "protection": { "required_status_checks": { "contexts": [] } },
+        }))
+        .unwrap();
+
+        let mut stats = compute_branch_protection_stats(&branch);
+        assert!(stats.protected);
+        assert_eq!(stats.required_approving_reviews, 0);
+        assert!(!stats.required_status_checks);
+
+        let protection: BranchProtection = serde_json::from_value(serde_json::json!({
+            "required_pull_request_reviews": { "required_approving_review_count": 1 },
+            "required_status_checks": { "contexts": ["ci"] },
+        }))
+        .unwrap();
+        apply_branch_protection(&mut stats, &protection);
+        assert_eq!(stats.required_approving_reviews, 1);
+        assert!(stats.required_status_checks);
+    }
+
+    #[test]
+    fn test_count_recent_discussions() {
+        let now = DateTime::parse_from_rfc3339("2024-06-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let response = serde_json::json!({
+            "data": { "repository": { "discussions": { "nodes": [
+                { "createdAt": "2024-05-01T00:00:00Z" },
+                { "createdAt": "2023-12-01T00:00:00Z" },
+                { "createdAt": "2022-01-01T00:00:00Z" },
+            ] } } }
+        });
+
+        assert_eq!(count_recent_discussions(&response, now), 2);
+        assert_eq!(count_recent_discussions(&serde_json::json!({ "errors": [] }), now), 0);
+    }
+
+    #[test]
+    fn test_compute_verified_commit_ratio() {
+        let commits: Vec<Commit> = serde_json::from_value(serde_json::json!([
+            { "commit": { "verification": { "verified": true, "reason": "valid" } } },
+            { "commit": { "verification": { "verified": false, "reason": "unsigned" } } },
+            { "commit": { "verification": { "verified": true, "reason": "valid" } } },
+            { "commit": {} },
+        ]))
+        .unwrap();
+
+        assert_eq!(compute_verified_commit_ratio(&commits), Some(0.5));
+        assert_eq!(compute_verified_commit_ratio(&[]), None);
+    }
+
+    #[test]
+    fn test_compute_unreviewed_merge_ratio() {
+        let response = serde_json::json!({
+            "data": { "repository": { "pullRequests": { "nodes": [
+                { "reviews": { "totalCount": 2 } },
+                { "reviews": { "totalCount": 0 } },
+                { "reviews": { "totalCount": 1 } },
+                { "reviews": { "totalCount": 0 } },
+            ] } } }
+        });
+
+        assert_eq!(compute_unreviewed_merge_ratio(&response), Some(0.5));
+        assert_eq!(
+            compute_unreviewed_merge_ratio(&serde_json::json!({ "data": { "repository": { "pullRequests": { "nodes": [] } } } })),
+            None
+        );
+        assert_eq!(compute_unreviewed_merge_ratio(&serde_json::json!({ "errors": [] })), None);
+    }
+
+    #[test]
+    fn test_repo_licenses() {
+        let github: Repository =
+            serde_json::from_value(serde_json::json!({ "license": { "key": "mit", "spdx_id": "MIT" } })).unwrap();
+        assert_eq!(repo_licenses(&github), ["MIT"]);
+
+        let unidentified: Repository =
+            serde_json::from_value(serde_json::json!({ "license": { "key": "other", "spdx_id": "NOASSERTION" } })).unwrap();
+        assert!(repo_licenses(&unidentified).is_empty());
+
+        let codeberg: Repository = serde_json::from_value(serde_json::json!({ "licenses": ["MIT", "Apache-2.0"] })).unwrap();
+        assert_eq!(repo_licenses(&codeberg), ["MIT", "Apache-2.0"]);
+    }
+
+    #[test]
+    fn test_is_verified_org() {
+        let org = |is_verified, two_factor_requirement_enabled| Organization {
+            is_verified,
+            two_factor_requirement_enabled,
+        };
+
+        assert!(is_verified_org(&org(true, None)));
+        assert!(is_verified_org(&org(false, Some(true))));
+        assert!(!is_verified_org(&org(false, Some(false))));
+        assert!(!is_verified_org(&org(false, None)));
+    }
+
     #[test]
     fn test_percentile_boundary_values() {
         let data = vec![1.0, 2.0, 3.0];
@@ -0,0 +1,86 @@
+use super::GitHubApp;
+use serde::{Deserialize, Serialize};
+
+/// The API a self-hosted service exposes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HostKind {
+    /// GitHub Enterprise Server
+    GitHub,
+
+    /// Gitea, or a fork of it such as Forgejo
+    Gitea,
+}
+
+/// A self-hosted service repositories can be hosted on, in addition to the public hosts
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomHost {
+    /// Domain of the repository URLs, such as `github.example.com`
+    pub domain: String,
+
+    /// Base URL of the REST API, such as `https://github.example.com/api/v3` or `https://gitea.example.com/api/v1`
+    pub api_base_url: String,
+
+    /// The API the service exposes
+    pub kind: HostKind,
+
+    /// Tokens requests are spread across, if any
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tokens: Vec<String>,
+}
+
+impl CustomHost {
+    /// The GraphQL endpoint of a GitHub Enterprise Server, which lives next to the versioned REST API.
+    pub(super) fn graphql_url(&self) -> Option<String> {
+        if self.kind != HostKind::GitHub {
+            return None;
+        }
+
+        let base = self.api_base_url.trim_end_matches('/');
+        Some(base.strip_suffix("/v3").map_or_else(|| format!("{base}/graphql"), |api| format!("{api}/graphql")))
+    }
+}
+
+/// How the hosting services are reached and authenticated to.
+#[derive(Debug, Default)]
+pub struct HostingSettings {
+    /// GitHub tokens requests are spread across
+    pub github_tokens: Vec<String>,
+
+    /// GitHub App to authenticate as, in place of the GitHub tokens
+    pub github_app: Option<GitHubApp>,
+
+    /// Codeberg tokens requests are spread across
+    pub codeberg_tokens: Vec<String>,
+
+    /// Self-hosted services, which replace the public host of the same domain if any
+    pub custom_hosts: Vec<CustomHost>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(kind: HostKind, api_base_url: &str) -> CustomHost {
+        CustomHost {
+            domain: "git.example.com".into(),
+            api_base_url: api_base_url.into(),
+            kind,
+            tokens: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_graphql_url() {
+        assert_eq!(
+            host(HostKind::GitHub, "https://git.example.com/api/v3").graphql_url().as_deref(),
+            Some("https://git.example.com/api/graphql")
+        );
+        assert_eq!(
+            host(HostKind::GitHub, "https://api.git.example.com/").graphql_url().as_deref(),
+            Some("https://api.git.example.com/graphql")
+        );
+        assert_eq!(host(HostKind::Gitea, "https://git.example.com/api/v1").graphql_url(), None);
+    }
+}
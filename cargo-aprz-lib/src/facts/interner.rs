@@ -0,0 +1,52 @@
+//! Interning of the strings repeated across the facts of many crates.
+//!
+//! The crates of a workspace share their repository, and most crates use one of a handful of licenses and
+//! owners, so each distinct string is kept once for the whole run and shared as an `Arc<str>`.
+
+use crate::HashSet;
+use serde::{Deserialize, Deserializer};
+use std::sync::{Arc, LazyLock, Mutex, PoisonError};
+
+static STRINGS: LazyLock<Mutex<HashSet<Arc<str>>>> = LazyLock::new(Mutex::default);
+
+/// Return the shared copy of a string, adding it the first time it is seen
+#[must_use]
+pub fn intern(s: &str) -> Arc<str> {
+    // Strings are only ever inserted whole, so a poisoned set is still consistent
+    let mut strings = STRINGS.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(interned) = strings.get(s) {
+        return Arc::clone(interned);
+    }
+
+    let interned: Arc<str> = Arc::from(s);
+    let _ = strings.insert(Arc::clone(&interned));
+    interned
+}
+
+/// Deserialize an interned string, for fields marked `#[serde(deserialize_with = "...")]`
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Arc<str>, D::Error> {
+    String::deserialize(deserializer).map(|s| intern(&s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern_shares_equal_strings() {
+        let a = intern("MIT OR Apache-2.0");
+        let b = intern(&String::from("MIT OR Apache-2.0"));
+        let c = intern("MIT");
+
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &c));
+        assert_eq!(&*c, "MIT");
+    }
+
+    #[test]
+    fn test_deserialize_interns() {
+        let mut de = serde_json::Deserializer::from_str(r#""tokio-rs""#);
+        let a = deserialize(&mut de).unwrap();
+        assert!(Arc::ptr_eq(&a, &intern("tokio-rs")));
+    }
+}
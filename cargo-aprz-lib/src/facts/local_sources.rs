@@ -0,0 +1,119 @@
+//! Crate sources already unpacked on the local machine.
+//!
+//! Build environments without network access usually have the sources of their dependencies on disk,
+//! either vendored with `cargo vendor` or unpacked by Cargo into `$CARGO_HOME/registry/src`. Analyzing
+//! those sources gives the package and codebase facts without downloading anything. Registry sources live
+//! in `<name>-<version>` directories, as do the sources vendored with `cargo vendor --versioned-dirs`,
+//! while plain `cargo vendor` only uses the crate name and leaves the version to the manifest.
+
+use super::crate_spec::CrateSpec;
+use super::path_utils::sanitize_path_component;
+use directories::BaseDirs;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const LOG_TARGET: &str = "   sources";
+
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    package: Option<ManifestPackage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ManifestPackage {
+    version: Option<String>,
+}
+
+/// Directories holding unpacked crate sources, searched in order.
+#[derive(Debug, Clone, Default)]
+pub struct LocalSources {
+    roots: Vec<PathBuf>,
+}
+
+impl LocalSources {
+    /// Search the given vendor directory, if any, followed by the registry sources Cargo unpacked.
+    #[must_use]
+    pub fn new(vendor_dir: Option<PathBuf>) -> Self {
+        let mut roots: Vec<PathBuf> = vendor_dir.into_iter().filter(|dir| dir.is_dir()).collect();
+
+        // Each registry gets its own directory, named after the registry and a hash of its index URL
+        if let Some(registry_src) = cargo_home().map(|home| home.join("registry").join("src"))
+            && let Ok(entries) = fs::read_dir(&registry_src)
+        {
+            let mut registries: Vec<_> = entries.filter_map(Result::ok).map(|e| e.path()).filter(|p| p.is_dir()).collect();
+            registries.sort();
+            roots.extend(registries);
+        }
+
+        for root in &roots {
+            log::debug!(target: LOG_TARGET, "Looking for crate sources in '{}'", root.display());
+        }
+
+        Self { roots }
+    }
+
+    /// The directory holding the sources of the given crate, if unpacked in any of the roots.
+    #[must_use]
+    pub fn find(&self, crate_spec: &CrateSpec) -> Option<PathBuf> {
+        let name = sanitize_path_component(crate_spec.name());
+        let version = crate_spec.version().to_string();
+        let versioned = format!("{name}-{}", sanitize_path_component(&version));
+
+        self.roots.iter().find_map(|root| {
+            let dir = root.join(&versioned);
+            if dir.join("Cargo.toml").is_file() {
+                return Some(dir);
+            }
+
+            let dir = root.join(&name);
+            (manifest_version(&dir).as_deref() == Some(version.as_str())).then_some(dir)
+        })
+    }
+}
+
+/// Cargo's home directory, as found by Cargo itself.
+pub(super) fn cargo_home() -> Option<PathBuf> {
+    std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| BaseDirs::new().map(|dirs| dirs.home_dir().join(".cargo")))
+}
+
+/// The version declared by the manifest of a crate directory.
+fn manifest_version(dir: &Path) -> Option<String> {
+    let text = fs::read_to_string(dir.join("Cargo.toml")).ok()?;
+    toml::from_str::<Manifest>(&text).ok()?.package?.version
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use semver::Version;
+    use std::sync::Arc;
+
+    fn spec(name: &str, version: &str) -> CrateSpec {
+        CrateSpec::from_arcs(Arc::from(name), Arc::new(Version::parse(version).unwrap()))
+    }
+
+    fn write_crate(dir: &Path, version: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join("Cargo.toml"), format!("[package]\nname = \"demo\"\nversion = \"{version}\"\n")).unwrap();
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_find() {
+        let tmp = tempfile::tempdir().unwrap();
+        let vendor = tmp.path().join("vendor");
+        write_crate(&vendor.join("demo"), "1.2.0");
+        write_crate(&vendor.join("other-0.3.1"), "0.3.1");
+
+        let sources = LocalSources {
+            roots: vec![vendor.clone()],
+        };
+        assert_eq!(sources.find(&spec("demo", "1.2.0")), Some(vendor.join("demo")));
+        assert_eq!(sources.find(&spec("demo", "1.3.0")), None);
+        assert_eq!(sources.find(&spec("other", "0.3.1")), Some(vendor.join("other-0.3.1")));
+        assert_eq!(sources.find(&spec("missing", "1.0.0")), None);
+    }
+}
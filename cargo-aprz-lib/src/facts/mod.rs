@@ -13,9 +13,11 @@
 //! - **Code analysis**: Line counts, unsafe usage, CI workflow detection
 //! - **Coverage data**: Test coverage percentages from external services
 //! - **Documentation**: Docs.rs metrics like doc coverage and broken links
+//! - **Package contents**: Files shipped in the published `.crate` package
+//! - **Custom providers**: Facts from providers registered through [`register_provider`]
 //!
 //! Each data source is wrapped in a [`ProviderResult`] which can be `Found`, `NotFound`,
-//! or `Error`, allowing the system to gracefully handle partial data availability.
+//! `Cancelled`, or `Error`, allowing the system to gracefully handle partial data availability.
 //!
 //! The [`Collector`] orchestrates parallel data fetching with caching and rate limiting.
 //! It uses a request tracker to deduplicate concurrent requests and maintains both
@@ -27,32 +29,46 @@ pub mod advisories;
 #[cfg(not(debug_assertions))]
 pub(crate) mod advisories;
 pub mod cache;
-mod cache_lock;
+pub(crate) mod cache_lock;
+mod cancellation;
 pub(crate) mod codebase;
 mod collector;
 pub mod coverage;
 mod crate_facts;
 mod crate_ref;
 mod crate_spec;
+mod evidence;
+mod fact_provider;
+mod interner;
+mod local_sources;
 pub mod crates;
 pub mod docs;
+pub(crate) mod package;
 pub(crate) mod hosting;
 mod path_utils;
 mod progress;
 mod provider_result;
 pub(crate) mod resilient_http;
 mod repo_spec;
+mod source_replacement;
 mod request_tracker;
 pub(crate) mod throttler;
 
+pub use cancellation::Cancellation;
 pub use collector::Collector;
-pub use crate_facts::CrateFacts;
+pub use crate_facts::{CrateFacts, DataTimestamps};
 pub use crate_ref::CrateRef;
 pub use crate_spec::CrateSpec;
+pub use fact_provider::{FactProvider, register_provider};
+pub(crate) use fact_provider::{registered_metric_defs, registered_providers};
 pub use crates::CratesData;
-pub use progress::Progress;
+pub use hosting::{ApiUsage, CustomHost, GitHubApp, HostKind, HostingSettings};
+pub use local_sources::LocalSources;
+pub use progress::{Progress, TopicProgress};
 pub use provider_result::ProviderResult;
 pub use repo_spec::RepoSpec;
+pub use source_replacement::{ReplacedSource, SourceReplacement};
+pub use request_tracker::TopicStatus;
 
 #[cfg(debug_assertions)]
 pub use request_tracker::RequestTracker;
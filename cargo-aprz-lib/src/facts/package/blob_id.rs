@@ -0,0 +1,105 @@
+//! Git blob IDs of package files.
+
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use core::str::FromStr;
+use ohno::app_err;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The SHA-1 ID git assigns to the contents of a file.
+///
+/// Packages hold thousands of files, so IDs are kept as their 20 bytes rather than the 40 hex digits they
+/// are written as, which wouldn't fit inline in a `CompactString`. They are serialized as hex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BlobId([u8; 20]);
+
+impl From<[u8; 20]> for BlobId {
+    fn from(bytes: [u8; 20]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl Display for BlobId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+impl FromStr for BlobId {
+    type Err = ohno::AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s.as_bytes();
+        if digits.len() != 40 {
+            return Err(app_err!("invalid git blob ID '{s}'"));
+        }
+
+        let mut bytes = [0; 20];
+        for (byte, pair) in bytes.iter_mut().zip(digits.chunks_exact(2)) {
+            *byte = parse_byte(pair).ok_or_else(|| app_err!("invalid git blob ID '{s}'"))?;
+        }
+
+        Ok(Self(bytes))
+    }
+}
+
+/// Parse a byte written as two hex digits
+fn parse_byte(pair: &[u8]) -> Option<u8> {
+    match *pair {
+        [high, low] => Some(hex_value(high)? << 4 | hex_value(low)?),
+        _ => None,
+    }
+}
+
+const fn hex_value(digit: u8) -> Option<u8> {
+    match digit {
+        b'0'..=b'9' => Some(digit - b'0'),
+        b'a'..=b'f' => Some(digit - b'a' + 10),
+        b'A'..=b'F' => Some(digit - b'A' + 10),
+        _ => None,
+    }
+}
+
+impl Serialize for BlobId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for BlobId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "ce013625030ba8dba906f756967f9e9ca394464a";
+
+    #[test]
+    fn test_round_trip() {
+        let id: BlobId = ID.parse().unwrap();
+        assert_eq!(id.to_string(), ID);
+        assert_eq!(ID.to_uppercase().parse::<BlobId>().unwrap(), id);
+
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(json, format!("\"{ID}\""));
+        assert_eq!(serde_json::from_str::<BlobId>(&json).unwrap(), id);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let _ = "ce0136".parse::<BlobId>().unwrap_err();
+        let _ = "ze013625030ba8dba906f756967f9e9ca394464a".parse::<BlobId>().unwrap_err();
+        let _ = serde_json::from_str::<BlobId>("\"readme\"").unwrap_err();
+    }
+}
@@ -0,0 +1,204 @@
+//! Heuristic assessment of code that runs while a crate is being compiled.
+//!
+//! Build scripts and procedural macros execute arbitrary code on the machine building a crate. This module
+//! scans that code, along with the dependencies it pulls in, for signs that it could reach the network or
+//! harvest the build environment. The scan is textual, so it flags capabilities rather than proven behavior.
+
+use std::collections::BTreeMap;
+use toml::{Table, Value};
+
+/// No build-time code, or nothing suspicious in it
+const RISK_NONE: u64 = 0;
+
+/// Build-time code spawns external processes, which is common for probing compilers
+const RISK_PROCESS: u64 = 1;
+
+/// Build-time code enumerates every environment variable, which can expose secrets
+const RISK_ENVIRONMENT: u64 = 2;
+
+/// Build-time code uses networking APIs, networking crates, or network tools
+const RISK_NETWORK: u64 = 3;
+
+/// Crates that provide network clients
+const NETWORK_CRATES: &[&str] = &["reqwest", "ureq", "curl", "hyper", "attohttpc", "isahc", "minreq", "http_req", "surf"];
+
+/// Source patterns indicating network access
+const NETWORK_PATTERNS: &[&str] = &[
+    "std::net",
+    "TcpStream",
+    "UdpSocket",
+    "ToSocketAddrs",
+    "reqwest::",
+    "ureq::",
+    "curl::",
+    "hyper::",
+    "Command::new(\"curl\")",
+    "Command::new(\"wget\")",
+];
+
+/// Source patterns indicating enumeration of the environment
+const ENVIRONMENT_PATTERNS: &[&str] = &["env::vars()", "env::vars_os()"];
+
+/// Source patterns indicating process spawning
+const PROCESS_PATTERNS: &[&str] = &["Command::new"];
+
+/// Assess the build-time risk of a package from its normalized manifest and its Rust sources.
+///
+/// `sources` maps paths relative to the package root to file contents. The result is one of the
+/// `RISK_*` levels, taking the highest level found.
+pub fn build_time_network_risk(manifest: &str, sources: &BTreeMap<String, String>) -> u64 {
+    let Ok(manifest) = manifest.parse::<Table>() else {
+        return RISK_NONE;
+    };
+
+    let is_proc_macro = manifest
+        .get("lib")
+        .and_then(|lib| lib.get("proc-macro").or_else(|| lib.get("proc_macro")))
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+
+    let build_script = match manifest.get("package").and_then(|p| p.get("build")) {
+        Some(Value::String(path)) => Some(path.replace('\\', "/")),
+        Some(Value::Boolean(false)) => None,
+        _ => sources.contains_key("build.rs").then(|| "build.rs".to_string()),
+    };
+
+    if build_script.is_none() && !is_proc_macro {
+        return RISK_NONE;
+    }
+
+    // A proc macro's regular dependencies run inside the compiler along with the macro itself
+    let mut dep_tables = vec!["build-dependencies"];
+    if is_proc_macro {
+        dep_tables.push("dependencies");
+    }
+
+    let network_dep = dep_tables
+        .iter()
+        .flat_map(|table| dependency_names(&manifest, table))
+        .any(|name| NETWORK_CRATES.contains(&name.as_str()));
+    if network_dep {
+        return RISK_NETWORK;
+    }
+
+    // Build scripts in their own directory, such as `build/main.rs`, often split their code into modules
+    let build_dir = build_script.as_deref().and_then(|path| path.rsplit_once('/')).map(|(dir, _)| format!("{dir}/"));
+
+    sources
+        .iter()
+        .filter(|(path, _)| {
+            Some(path.as_str()) == build_script.as_deref()
+                || build_dir.as_deref().is_some_and(|dir| path.starts_with(dir))
+                || (is_proc_macro && path.starts_with("src/"))
+        })
+        .map(|(_, text)| source_risk(text))
+        .max()
+        .unwrap_or(RISK_NONE)
+}
+
+/// Names of the crates listed in a dependency table, including its target-specific variants.
+fn dependency_names(manifest: &Table, table: &str) -> Vec<String> {
+    let targets = manifest
+        .get("target")
+        .and_then(Value::as_table)
+        .into_iter()
+        .flat_map(|targets| targets.values().filter_map(|target| target.get(table)));
+
+    manifest
+        .get(table)
+        .into_iter()
+        .chain(targets)
+        .filter_map(Value::as_table)
+        .flat_map(|deps| {
+            deps.iter().map(|(key, spec)| {
+                // Renamed dependencies name the actual crate in `package`
+                spec.get("package").and_then(Value::as_str).unwrap_or(key).to_string()
+            })
+        })
+        .collect()
+}
+
+/// Assess the risk level of a single source file.
+fn source_risk(text: &str) -> u64 {
+    let code: String = text
+        .lines()
+        .map(|line| line.split_once("//").map_or(line, |(code, _)| code))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    if NETWORK_PATTERNS.iter().any(|p| code.contains(p)) {
+        RISK_NETWORK
+    } else if ENVIRONMENT_PATTERNS.iter().any(|p| code.contains(p)) {
+        RISK_ENVIRONMENT
+    } else if PROCESS_PATTERNS.iter().any(|p| code.contains(p)) {
+        RISK_PROCESS
+    } else {
+        RISK_NONE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sources(files: &[(&str, &str)]) -> BTreeMap<String, String> {
+        files.iter().map(|(p, t)| ((*p).to_string(), (*t).to_string())).collect()
+    }
+
+    #[test]
+    fn test_no_build_time_code() {
+        let manifest = "[package]\nname = \"demo\"\n\n[dependencies]\nreqwest = \"0.12\"\n";
+        let files = sources(&[("src/lib.rs", "use std::net::TcpStream;")]);
+        assert_eq!(build_time_network_risk(manifest, &files), RISK_NONE);
+    }
+
+    #[test]
+    fn test_build_script_levels() {
+        let manifest = "[package]\nname = \"demo\"\nbuild = \"build.rs\"\n";
+        let check = |code: &str| build_time_network_risk(manifest, &sources(&[("build.rs", code)]));
+
+        assert_eq!(check("fn main() { println!(\"cargo:rerun-if-changed=build.rs\"); }"), RISK_NONE);
+        assert_eq!(check("let out = Command::new(\"rustc\").arg(\"-V\").output();"), RISK_PROCESS);
+        assert_eq!(check("for (k, v) in std::env::vars() {}"), RISK_ENVIRONMENT);
+        assert_eq!(check("let s = std::net::TcpStream::connect(addr);"), RISK_NETWORK);
+        assert_eq!(check("Command::new(\"curl\").arg(url).status();"), RISK_NETWORK);
+        assert_eq!(check("// TcpStream is not used here"), RISK_NONE);
+    }
+
+    #[test]
+    fn test_build_script_disabled() {
+        let manifest = "[package]\nname = \"demo\"\nbuild = false\n";
+        let files = sources(&[("build.rs", "use std::net::TcpStream;")]);
+        assert_eq!(build_time_network_risk(manifest, &files), RISK_NONE);
+    }
+
+    #[test]
+    fn test_build_script_directory() {
+        let manifest = "[package]\nname = \"demo\"\nbuild = \"build/main.rs\"\n";
+        let files = sources(&[("build/main.rs", "mod fetch;"), ("build/fetch.rs", "ureq::get(url).call();")]);
+        assert_eq!(build_time_network_risk(manifest, &files), RISK_NETWORK);
+    }
+
+    #[test]
+    fn test_network_build_dependency() {
+        let manifest = r#"
+[package]
+name = "demo"
+
+[target.'cfg(unix)'.build-dependencies]
+http = { package = "reqwest", version = "0.12" }
+"#;
+        let files = sources(&[("build.rs", "fn main() {}")]);
+        assert_eq!(build_time_network_risk(manifest, &files), RISK_NETWORK);
+    }
+
+    #[test]
+    fn test_proc_macro() {
+        let manifest = "[package]\nname = \"demo\"\n\n[lib]\nproc-macro = true\n";
+        let files = sources(&[("src/lib.rs", "let vars: Vec<_> = std::env::vars_os().collect();")]);
+        assert_eq!(build_time_network_risk(manifest, &files), RISK_ENVIRONMENT);
+
+        let manifest = "[package]\nname = \"demo\"\n\n[lib]\nproc-macro = true\n\n[dependencies]\nureq = \"2\"\n";
+        assert_eq!(build_time_network_risk(manifest, &BTreeMap::new()), RISK_NETWORK);
+    }
+}
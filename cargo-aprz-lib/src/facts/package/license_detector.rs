@@ -0,0 +1,140 @@
+//! Identification of the license texts shipped in a package.
+//!
+//! License files are recognized by distinctive phrases from the canonical license texts. This is far
+//! simpler than full text matching, but is enough to tell license families apart, which is what's needed
+//! to catch a crate declaring one license while shipping the text of another.
+
+/// Distinctive phrases of common licenses, all of which must appear for a license to be recognized.
+///
+/// Order matters: a text is attributed to the first matching entry, so licenses whose texts quote or
+/// extend other licenses come before them.
+const LICENSE_SIGNATURES: &[(&str, &[&str])] = &[
+    ("AGPL", &["gnu affero general public license"]),
+    ("LGPL", &["gnu lesser general public license"]),
+    ("LGPL", &["gnu library general public license"]),
+    ("GPL", &["gnu general public license"]),
+    ("MPL-2.0", &["mozilla public license", "version 2.0"]),
+    ("Apache-2.0", &["apache license", "version 2.0"]),
+    ("BSL-1.0", &["boost software license - version 1.0"]),
+    ("Unicode-3.0", &["unicode license v3"]),
+    ("Unicode-DFS", &["unicode, inc. license agreement - data files and software"]),
+    ("X11", &["permission is hereby granted, free of charge", "the name of the x consortium shall not be used"]),
+    (
+        "curl",
+        &[
+            "permission to use, copy, modify, and distribute this software for any purpose with or without fee is hereby granted",
+            "the name of a copyright holder shall not be used",
+        ],
+    ),
+    ("MIT", &["permission is hereby granted, free of charge"]),
+    (
+        "BSD-3-Clause",
+        &["redistribution and use in source and binary forms", "neither the name"],
+    ),
+    ("BSD-2-Clause", &["redistribution and use in source and binary forms"]),
+    (
+        "ISC",
+        &[
+            "permission to use, copy, modify, and/or distribute this software for any purpose",
+            "provided that the above copyright notice",
+        ],
+    ),
+    ("0BSD", &["permission to use, copy, modify, and/or distribute this software for any purpose"]),
+    ("Zlib", &["this software is provided 'as-is', without any express or implied"]),
+    ("Unlicense", &["this is free and unencumbered software released into the public domain"]),
+    ("CC0-1.0", &["cc0 1.0 universal"]),
+];
+
+/// File name prefixes of license files
+const LICENSE_FILE_PREFIXES: [&str; 4] = ["LICENSE", "LICENCE", "COPYING", "UNLICENSE"];
+
+/// Whether a path, relative to the package root, names a license file.
+///
+/// License files at the package root are recognized, as are files in a `LICENSES` directory, as used
+/// by the REUSE specification.
+pub fn is_license_file(path: &str) -> bool {
+    let path = path.to_uppercase();
+    let name = match path.split_once('/') {
+        None => path.as_str(),
+        Some(("LICENSES", name)) if !name.contains('/') => return true,
+        Some(_) => return false,
+    };
+
+    LICENSE_FILE_PREFIXES.iter().any(|prefix| name.starts_with(prefix))
+}
+
+/// Identify the license family of a license text, or `None` if it isn't recognized.
+pub fn detect_license(text: &str) -> Option<&'static str> {
+    let normalized = text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+
+    LICENSE_SIGNATURES
+        .iter()
+        .find(|(_, phrases)| phrases.iter().all(|phrase| normalized.contains(phrase)))
+        .map(|(license, _)| *license)
+}
+
+/// Whether any detected license is missing from a declared SPDX license expression.
+///
+/// An empty declaration, as for crates using `license-file`, is never considered a mismatch.
+pub fn license_mismatch(declared: &str, detected: impl IntoIterator<Item = impl AsRef<str>>) -> bool {
+    if declared.trim().is_empty() {
+        return false;
+    }
+
+    let declared: Vec<&str> = declared
+        .split(|c: char| c.is_whitespace() || c == '(' || c == ')' || c == '/')
+        .filter(|token| !token.is_empty())
+        .collect();
+
+    detected
+        .into_iter()
+        .any(|license| !declared.iter().any(|token| token.starts_with(license.as_ref())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIT_TEXT: &str = "MIT License\n\nCopyright (c) 2024 Someone\n\nPermission is hereby granted, free of charge, to any person";
+    const APACHE_TEXT: &str = "                                 Apache License\n                           Version 2.0, January 2004";
+    const GPL_TEXT: &str = "                    GNU GENERAL PUBLIC LICENSE\n                       Version 3, 29 June 2007";
+    const LGPL_TEXT: &str = "GNU LESSER GENERAL PUBLIC LICENSE\nVersion 3\n\nThis version of the GNU Lesser General Public License \
+                             incorporates the terms of version 3 of the GNU General Public License";
+    const UNICODE_TEXT: &str = "UNICODE LICENSE V3\n\nCOPYRIGHT AND PERMISSION NOTICE\n\nCopyright © 1991-2023 Unicode, Inc.\n\n\
+                                Permission is hereby granted, free of charge, to any person obtaining a copy of data files";
+    const X11_TEXT: &str = "Permission is hereby granted, free of charge, to any person obtaining a copy\n\
+                            Except as contained in this notice, the name of the X Consortium shall not be used in advertising";
+
+    #[test]
+    fn test_is_license_file() {
+        assert!(is_license_file("LICENSE"));
+        assert!(is_license_file("LICENSE-MIT"));
+        assert!(is_license_file("license.txt"));
+        assert!(is_license_file("COPYING"));
+        assert!(is_license_file("LICENSES/Apache-2.0.txt"));
+        assert!(!is_license_file("src/license.rs"));
+        assert!(!is_license_file("README.md"));
+    }
+
+    #[test]
+    fn test_detect_license() {
+        assert_eq!(detect_license(MIT_TEXT), Some("MIT"));
+        assert_eq!(detect_license(APACHE_TEXT), Some("Apache-2.0"));
+        assert_eq!(detect_license(GPL_TEXT), Some("GPL"));
+        assert_eq!(detect_license(LGPL_TEXT), Some("LGPL"));
+        assert_eq!(detect_license(UNICODE_TEXT), Some("Unicode-3.0"));
+        assert_eq!(detect_license(X11_TEXT), Some("X11"));
+        assert_eq!(detect_license("All rights reserved."), None);
+    }
+
+    #[test]
+    fn test_license_mismatch() {
+        assert!(!license_mismatch("MIT OR Apache-2.0", ["MIT", "Apache-2.0"]));
+        assert!(!license_mismatch("(MIT OR Apache-2.0) AND GPL-3.0-or-later", ["GPL"]));
+        assert!(!license_mismatch("MIT/Apache-2.0", ["Apache-2.0"]));
+        assert!(!license_mismatch("", ["GPL"]));
+        assert!(!license_mismatch("(MIT OR Apache-2.0) AND Unicode-3.0", ["Unicode-3.0", "MIT"]));
+        assert!(license_mismatch("MIT", ["GPL"]));
+        assert!(license_mismatch("LGPL-2.1", ["GPL"]));
+    }
+}
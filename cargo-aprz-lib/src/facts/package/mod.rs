@@ -0,0 +1,13 @@
+mod blob_id;
+mod build_risk_analyzer;
+mod license_detector;
+mod package_analyzer;
+mod package_data;
+mod provider;
+mod repo_diff;
+
+pub use blob_id::BlobId;
+pub use license_detector::license_mismatch;
+pub use package_data::PackageData;
+pub use provider::Provider;
+pub use repo_diff::count_repo_differences;
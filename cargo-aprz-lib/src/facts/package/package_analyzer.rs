@@ -0,0 +1,339 @@
+//! Analysis of the files inside a published `.crate` package, or of its unpacked sources.
+
+use super::{BlobId, PackageData};
+use super::build_risk_analyzer::build_time_network_risk;
+use super::license_detector::{detect_license, is_license_file};
+use crate::Result;
+use compact_str::CompactString;
+use flate2::read::GzDecoder;
+use ohno::IntoAppError;
+use serde::Deserialize;
+use sha1::{Digest, Sha1};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use tar::{Archive, EntryType};
+use walkdir::WalkDir;
+
+/// Number of leading bytes of each file inspected to classify it
+const HEAD_SIZE: usize = 8 * 1024;
+
+/// File written by `cargo package` to record the commit a package was built from
+const VCS_INFO_FILE: &str = ".cargo_vcs_info.json";
+
+/// Files Cargo adds next to the sources it unpacks or vendors, which aren't part of the package
+const UNPACKING_MARKERS: &[&str] = &[".cargo-ok", ".cargo-checksum.json"];
+
+/// Files at least this large which look binary are flagged even without a recognized executable format
+const LARGE_OPAQUE_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Magic numbers of executable, object, and library formats
+const BINARY_MAGIC: &[&[u8]] = &[
+    b"\x7fELF",         // ELF
+    b"\xfe\xed\xfa\xce", // Mach-O 32-bit
+    b"\xfe\xed\xfa\xcf", // Mach-O 64-bit
+    b"\xce\xfa\xed\xfe", // Mach-O 32-bit, little endian
+    b"\xcf\xfa\xed\xfe", // Mach-O 64-bit, little endian
+    b"\xca\xfe\xba\xbe", // Mach-O universal
+    b"\0asm",           // WebAssembly
+    b"!<arch>\n",       // Static libraries
+];
+
+/// Extensions of binary data files that are commonly shipped in crates and aren't executable
+const DATA_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "bmp", "ico", "webp", "ttf", "otf", "woff", "woff2", "pdf"];
+
+#[derive(Debug, Deserialize)]
+struct VcsInfo {
+    git: Option<GitInfo>,
+    #[serde(default)]
+    path_in_vcs: CompactString,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitInfo {
+    sha1: String,
+}
+
+/// Analyze a gzip-compressed `.crate` package.
+pub fn analyze_package(reader: impl Read) -> Result<PackageData> {
+    let mut archive = Archive::new(GzDecoder::new(reader));
+    let mut scanner = Scanner::default();
+
+    for entry in archive.entries().into_app_err("reading package entries")? {
+        let mut entry = entry.into_app_err("reading package entry")?;
+        if entry.header().entry_type() != EntryType::Regular {
+            continue;
+        }
+
+        // Packages hold a single `<name>-<version>/` top-level directory
+        let path = entry.path().into_app_err("reading package entry path")?;
+        let relative = path
+            .components()
+            .skip(1)
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let mut contents = Vec::new();
+        let _ = entry
+            .read_to_end(&mut contents)
+            .into_app_err_with(|| format!("reading package file '{relative}'"))?;
+
+        scanner.add_file(relative, &contents);
+    }
+
+    Ok(scanner.finish())
+}
+
+/// Analyze the unpacked sources of a package, as vendored by `cargo vendor` or unpacked by Cargo into its
+/// registry cache.
+pub fn analyze_directory(root: &Path) -> Result<PackageData> {
+    let mut scanner = Scanner::default();
+
+    for entry in WalkDir::new(root).follow_links(false).sort_by_file_name() {
+        let entry = entry.into_app_err_with(|| format!("walking package directory '{}'", root.display()))?;
+        let file_type = entry.file_type();
+        if file_type.is_dir() || file_type.is_symlink() {
+            continue;
+        }
+
+        let Ok(relative) = entry.path().strip_prefix(root) else {
+            continue;
+        };
+        let relative = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        if UNPACKING_MARKERS.contains(&relative.as_str()) {
+            continue;
+        }
+
+        let contents = fs::read(entry.path()).into_app_err_with(|| format!("reading package file '{}'", entry.path().display()))?;
+        scanner.add_file(relative, &contents);
+    }
+
+    Ok(scanner.finish())
+}
+
+/// Package facts accumulated one file at a time
+struct Scanner {
+    data: PackageData,
+    manifest: String,
+    sources: BTreeMap<String, String>,
+}
+
+impl Default for Scanner {
+    fn default() -> Self {
+        Self {
+            data: PackageData {
+                file_count: 0,
+                binary_files: Vec::new(),
+                vcs_commit: None,
+                path_in_vcs: CompactString::default(),
+                build_time_network_risk: 0,
+                license_files: BTreeMap::new(),
+                file_hashes: BTreeMap::new(),
+                repo_differences: None,
+            },
+            manifest: String::new(),
+            sources: BTreeMap::new(),
+        }
+    }
+}
+
+impl Scanner {
+    /// Add a file, given by its path relative to the package root.
+    fn add_file(&mut self, relative: String, contents: &[u8]) {
+        let data = &mut self.data;
+
+        if relative == VCS_INFO_FILE
+            && let Ok(info) = serde_json::from_slice::<VcsInfo>(contents)
+        {
+            // The commit ends up on a git command line, so only accept what looks like an object ID
+            data.vcs_commit = info.git.map(|git| git.sha1).filter(|sha| is_object_id(sha));
+            data.path_in_vcs = info.path_in_vcs;
+        }
+
+        if relative == "Cargo.toml" {
+            self.manifest = String::from_utf8_lossy(contents).into_owned();
+        } else if Path::new(&relative).extension().is_some_and(|ext| ext.eq_ignore_ascii_case("rs")) {
+            let _ = self.sources.insert(relative.clone(), String::from_utf8_lossy(contents).into_owned());
+        } else if is_license_file(&relative)
+            && let Some(license) = detect_license(&String::from_utf8_lossy(contents))
+        {
+            let _ = data.license_files.insert(relative.as_str().into(), license.into());
+        }
+
+        let head = contents.get(..HEAD_SIZE).unwrap_or(contents);
+        data.file_count += 1;
+        if is_binary(&relative, contents.len() as u64, head) {
+            data.binary_files.push(relative.as_str().into());
+        }
+
+        let _ = data.file_hashes.insert(relative.into(), git_blob_id(contents));
+    }
+
+    fn finish(mut self) -> PackageData {
+        self.data.build_time_network_risk = build_time_network_risk(&self.manifest, &self.sources);
+        self.data
+    }
+}
+
+/// Whether a file holds compiled code or large opaque binary data.
+fn is_binary(path: &str, size: u64, head: &[u8]) -> bool {
+    if BINARY_MAGIC.iter().any(|magic| head.starts_with(magic)) {
+        return true;
+    }
+
+    // PE executables start with a DOS header, which text files can too
+    let has_nul = head.contains(&0);
+    if head.starts_with(b"MZ") && has_nul {
+        return true;
+    }
+
+    let is_data = path
+        .rsplit_once('.')
+        .is_some_and(|(_, ext)| DATA_EXTENSIONS.iter().any(|e| ext.eq_ignore_ascii_case(e)));
+
+    size >= LARGE_OPAQUE_FILE_SIZE && has_nul && !is_data
+}
+
+/// Compute the ID git assigns to a blob with the given contents.
+fn git_blob_id(contents: &[u8]) -> BlobId {
+    let mut hasher = Sha1::new();
+    hasher.update(format!("blob {}\0", contents.len()));
+    hasher.update(contents);
+    BlobId::from(<[u8; 20]>::from(hasher.finalize()))
+}
+
+/// Whether a string is a full SHA-1 or SHA-256 git object ID.
+fn is_object_id(s: &str) -> bool {
+    (s.len() == 40 || s.len() == 64) && s.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+
+    fn make_package(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        for (path, contents) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, format!("demo-1.0.0/{path}"), *contents).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    #[test]
+    fn test_analyze_package_source_only() {
+        let package = make_package(&[("Cargo.toml", b"[package]\n"), ("src/lib.rs", b"pub fn f() {}\n")]);
+        let data = analyze_package(package.as_slice()).unwrap();
+        assert_eq!(data.file_count, 2);
+        assert!(data.binary_files.is_empty());
+    }
+
+    #[test]
+    fn test_analyze_package_finds_binaries() {
+        let package = make_package(&[
+            ("src/lib.rs", b"pub fn f() {}\n"),
+            ("bin/helper", b"\x7fELF\x02\x01\x01\0\0\0"),
+            ("lib/native.lib", b"!<arch>\n/               "),
+            ("assets/logo.png", b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"),
+        ]);
+        let data = analyze_package(package.as_slice()).unwrap();
+        assert_eq!(data.file_count, 4);
+        assert_eq!(data.binary_files, ["bin/helper", "lib/native.lib"]);
+    }
+
+    #[test]
+    fn test_is_binary() {
+        assert!(is_binary("tool.exe", 1024, b"MZ\x90\0\x03\0"));
+        assert!(!is_binary("README.md", 1024, b"MZ is a text file"));
+        assert!(is_binary("module.wasm", 10, b"\0asm\x01\0\0\0"));
+        assert!(is_binary("blob.bin", LARGE_OPAQUE_FILE_SIZE, b"\x01\x02\0\x03"));
+        assert!(!is_binary("blob.bin", 100, b"\x01\x02\0\x03"));
+        assert!(!is_binary("font.TTF", LARGE_OPAQUE_FILE_SIZE, b"\0\x01\0\0"));
+    }
+
+    #[test]
+    fn test_analyze_package_vcs_info() {
+        let package = make_package(&[
+            (".cargo_vcs_info.json", br#"{"git":{"sha1":"0123456789abcdef0123456789abcdef01234567"},"path_in_vcs":"crates/demo"}"#),
+            ("src/lib.rs", b""),
+        ]);
+        let data = analyze_package(package.as_slice()).unwrap();
+        assert_eq!(data.vcs_commit.as_deref(), Some("0123456789abcdef0123456789abcdef01234567"));
+        assert_eq!(data.path_in_vcs, "crates/demo");
+        assert_eq!(data.file_hashes["src/lib.rs"].to_string(), "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+    }
+
+    #[test]
+    fn test_analyze_package_rejects_bogus_commit() {
+        let package = make_package(&[(".cargo_vcs_info.json", br#"{"git":{"sha1":"--output=/tmp/x"}}"#)]);
+        let data = analyze_package(package.as_slice()).unwrap();
+        assert!(data.vcs_commit.is_none());
+    }
+
+    #[test]
+    fn test_analyze_package_build_script() {
+        let package = make_package(&[
+            ("Cargo.toml", b"[package]\nname = \"demo\"\nbuild = \"build.rs\"\n"),
+            ("build.rs", b"fn main() { let _ = std::net::TcpStream::connect(\"example.com:80\"); }"),
+        ]);
+        let data = analyze_package(package.as_slice()).unwrap();
+        assert_eq!(data.build_time_network_risk, 3);
+    }
+
+    #[test]
+    fn test_analyze_package_license_files() {
+        let package = make_package(&[
+            ("LICENSE-MIT", b"Permission is hereby granted, free of charge, to any person"),
+            ("LICENSE-OTHER", b"All rights reserved."),
+            ("src/lib.rs", b""),
+        ]);
+        let data = analyze_package(package.as_slice()).unwrap();
+        assert_eq!(data.license_files.len(), 1);
+        assert_eq!(data.license_files["LICENSE-MIT"], "MIT");
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_analyze_directory_matches_package() {
+        let files: &[(&str, &[u8])] = &[
+            ("Cargo.toml", b"[package]\nname = \"demo\"\n"),
+            ("src/lib.rs", b"pub fn f() {}\n"),
+            ("bin/helper", b"\x7fELF\x02\x01\x01\0\0\0"),
+        ];
+
+        let tmp = tempfile::tempdir().unwrap();
+        for (path, contents) in files {
+            let path = tmp.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+        fs::write(tmp.path().join(".cargo-checksum.json"), b"{}").unwrap();
+
+        let from_directory = analyze_directory(tmp.path()).unwrap();
+        let from_package = analyze_package(make_package(files).as_slice()).unwrap();
+        assert_eq!(from_directory.file_count, 3);
+        assert_eq!(from_directory.binary_files, from_package.binary_files);
+        assert_eq!(from_directory.file_hashes, from_package.file_hashes);
+    }
+
+    #[test]
+    fn test_git_blob_id() {
+        assert_eq!(git_blob_id(b"hello\n").to_string(), "ce013625030ba8dba906f756967f9e9ca394464a");
+    }
+
+    #[test]
+    fn test_analyze_package_invalid() {
+        let _ = analyze_package(&b"not a package"[..]).unwrap_err();
+    }
+}
@@ -0,0 +1,27 @@
+use super::BlobId;
+use compact_str::CompactString;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Facts about the contents of a crate's published `.crate` package.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PackageData {
+    pub file_count: u64,
+    /// Paths, relative to the package root, of files holding compiled code or opaque binary data
+    pub binary_files: Vec<CompactString>,
+    /// Commit the package was published from, as recorded in `.cargo_vcs_info.json`
+    pub vcs_commit: Option<String>,
+    /// Directory of the package within its repository, as recorded in `.cargo_vcs_info.json`
+    pub path_in_vcs: CompactString,
+    /// Heuristic risk of build scripts and procedural macros reaching the network: 0 for none, 1 for spawning
+    /// processes, 2 for enumerating environment variables, 3 for using networking code
+    pub build_time_network_risk: u64,
+    /// License families identified in the package's license files, keyed by path relative to the package root
+    pub license_files: BTreeMap<CompactString, CompactString>,
+    /// Git blob IDs of the package's files, keyed by path relative to the package root
+    pub file_hashes: BTreeMap<CompactString, BlobId>,
+    /// Number of package files that don't match the repository at `vcs_commit`, `None` if the comparison
+    /// couldn't be made. This is computed after collection and isn't cached.
+    #[serde(skip)]
+    pub repo_differences: Option<u64>,
+}
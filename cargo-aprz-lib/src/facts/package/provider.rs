@@ -0,0 +1,297 @@
+use super::PackageData;
+use super::package_analyzer::{analyze_directory, analyze_package};
+use crate::Result;
+use crate::facts::{Cancellation, ProviderResult};
+use crate::facts::cache::{Cache, CacheResult};
+use crate::facts::crate_spec::CrateSpec;
+use crate::facts::local_sources::LocalSources;
+use crate::facts::path_utils::sanitize_path_component;
+use crate::facts::request_tracker::{RequestTracker, TrackedTopic};
+use crate::facts::source_replacement::expand_download_template;
+use crate::facts::throttler::Throttler;
+use futures::stream::TryStreamExt;
+use futures_util::future::join_all;
+use ohno::{EnrichableExt, IntoAppError, app_err};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::task::spawn_blocking;
+
+const LOG_TARGET: &str = "   package";
+
+/// Default base URL for downloading `.crate` packages
+pub const PACKAGE_BASE_URL: &str = "https://static.crates.io/crates";
+
+const MAX_CONCURRENT_REQUESTS: usize = 5;
+
+#[derive(Debug, Clone)]
+pub struct Provider {
+    client: Arc<reqwest::Client>,
+    cache: Cache,
+    base_url: String,
+    download_template: Option<String>,
+    throttler: Arc<Throttler>,
+    local_sources: Option<Arc<LocalSources>>,
+    cancellation: Cancellation,
+}
+
+impl Provider {
+    /// Create a new package provider
+    #[must_use]
+    pub fn new(cache: Cache, base_url: Option<&str>) -> Self {
+        let client = reqwest::Client::builder()
+            .user_agent("cargo-aprz")
+            .build()
+            .expect("unable to create HTTP client");
+
+        Self {
+            client: Arc::new(client),
+            cache,
+            base_url: base_url.unwrap_or(PACKAGE_BASE_URL).to_string(),
+            download_template: None,
+            throttler: Throttler::new(MAX_CONCURRENT_REQUESTS),
+            local_sources: None,
+            cancellation: Cancellation::new(),
+        }
+    }
+
+    /// Analyze the sources of crates found unpacked on disk instead of downloading their packages.
+    #[must_use]
+    pub fn with_local_sources(mut self, local_sources: Arc<LocalSources>) -> Self {
+        self.local_sources = Some(local_sources);
+        self
+    }
+
+    /// Download packages from a registry mirror, given the download URL template of its `config.json`.
+    #[must_use]
+    pub fn with_download_template(mut self, template: String) -> Self {
+        self.download_template = Some(template);
+        self
+    }
+
+    /// Stop starting downloads and analyses once `cancellation` is cancelled.
+    #[must_use]
+    pub fn with_cancellation(mut self, cancellation: Cancellation) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// Where packages are downloaded from, for messages.
+    fn source(&self) -> &str {
+        self.download_template.as_deref().unwrap_or(&self.base_url)
+    }
+
+    /// The cached document holding the analysis of a crate's package, if any.
+    #[must_use]
+    pub fn cached_document(&self, crate_spec: &CrateSpec) -> Option<PathBuf> {
+        self.cache.entry_path(&Self::get_cache_filename(crate_spec))
+    }
+
+    /// Get package data for multiple crates
+    pub async fn get_package_data(
+        &self,
+        crates: impl IntoIterator<Item = CrateSpec> + Send + 'static,
+        tracker: &RequestTracker,
+    ) -> impl Iterator<Item = (CrateSpec, ProviderResult<PackageData>)> {
+        join_all(crates.into_iter().map(|crate_spec| {
+            tracker.add_requests(TrackedTopic::Packages, 1);
+
+            let provider = self.clone();
+            let tracker = tracker.clone();
+
+            tokio::spawn(provider.fetch_package_for_crate(crate_spec, tracker))
+        }))
+        .await
+        .into_iter()
+        .map(|task_result| task_result.expect("tasks must not panic"))
+        .inspect(|(crate_spec, result)| {
+            if let ProviderResult::Error(e) = result {
+                log::error!(target: LOG_TARGET, "Could not fetch package data for {crate_spec}: {e:#}");
+            } else if let ProviderResult::Unavailable(reason) = result {
+                log::warn!(target: LOG_TARGET, "Package unavailable for {crate_spec}: {reason}");
+            }
+        })
+    }
+
+    async fn fetch_package_for_crate(self, crate_spec: CrateSpec, tracker: RequestTracker) -> (CrateSpec, ProviderResult<PackageData>) {
+        let Some(_permit) = self.throttler.acquire_unless_cancelled(&self.cancellation).await else {
+            tracker.complete_request(TrackedTopic::Packages);
+            return (crate_spec, ProviderResult::Cancelled);
+        };
+
+        let result = self.fetch_package_for_crate_core(&crate_spec).await;
+        tracker.complete_request(TrackedTopic::Packages);
+
+        (crate_spec, result)
+    }
+
+    async fn fetch_package_for_crate_core(&self, crate_spec: &CrateSpec) -> ProviderResult<PackageData> {
+        let filename = Self::get_cache_filename(crate_spec);
+
+        match self.cache.load::<PackageData>(&filename) {
+            CacheResult::Data(data) => return ProviderResult::Found(data),
+            CacheResult::NoData(reason) => return ProviderResult::Unavailable(reason.into()),
+            CacheResult::Miss => {}
+        }
+
+        if let Some(dir) = self.local_sources.as_ref().and_then(|sources| sources.find(crate_spec)) {
+            log::info!(target: LOG_TARGET, "Analyzing local sources of {crate_spec} in '{}'", dir.display());
+            let analysis = spawn_blocking(move || analyze_directory(&dir)).await.expect("task must not panic");
+            return self.save_analysis(crate_spec, &filename, analysis);
+        }
+
+        log::info!(target: LOG_TARGET, "Downloading package for {crate_spec} from {}", self.source());
+
+        let provider = self.clone();
+        let spec = crate_spec.clone();
+
+        // resilient_download retries on Err, passes through Ok(None) for 404.
+        let result = crate::facts::resilient_http::resilient_download("package_download", spec, None, move |spec| {
+            let provider = provider.clone();
+            async move { provider.download_package_core(&spec).await }
+        })
+        .await;
+
+        let temp_file = match result {
+            Ok(Some(path)) => path,
+            Ok(None) => {
+                let reason = format!("could not find package for {crate_spec} at {}", self.source());
+                if let Err(e) = self.cache.save_no_data(&filename, &reason) {
+                    log::debug!(target: LOG_TARGET, "Could not save cache for {crate_spec}: {e:#}");
+                }
+                return ProviderResult::Unavailable(reason.into());
+            }
+            Err(e) => {
+                return ProviderResult::Error(Arc::new(e.enrich_with(|| format!("downloading package for {crate_spec}"))));
+            }
+        };
+
+        let analysis = fs::File::open(&temp_file)
+            .into_app_err_with(|| format!("opening package file '{}'", temp_file.display()))
+            .and_then(analyze_package);
+
+        tokio::fs::remove_file(&temp_file)
+            .await
+            .unwrap_or_else(|e| log::debug!(target: LOG_TARGET, "Could not remove temp file '{}': {e:#}", temp_file.display()));
+
+        self.save_analysis(crate_spec, &filename, analysis)
+    }
+
+    /// Cache the outcome of analyzing a package, recording why the package couldn't be analyzed on failure.
+    fn save_analysis(&self, crate_spec: &CrateSpec, filename: &str, analysis: Result<PackageData>) -> ProviderResult<PackageData> {
+        let package_data = match analysis {
+            Ok(data) => {
+                log::debug!(target: LOG_TARGET, "Analyzed {} files in package for {crate_spec}, {} binary", data.file_count, data.binary_files.len());
+                data
+            }
+            Err(e) => {
+                let reason = format!("{:#}", e.enrich_with(|| format!("analyzing package for {crate_spec}")));
+                if let Err(e) = self.cache.save_no_data(filename, &reason) {
+                    log::debug!(target: LOG_TARGET, "Could not save cache for {crate_spec}: {e:#}");
+                }
+                return ProviderResult::Unavailable(reason.into());
+            }
+        };
+
+        match self.cache.save(filename, &package_data) {
+            Ok(()) => ProviderResult::Found(package_data),
+            Err(e) => ProviderResult::Error(Arc::new(e)),
+        }
+    }
+
+    /// Get the cache filename for a specific crate and version
+    fn get_cache_filename(crate_spec: &CrateSpec) -> String {
+        let safe_name = sanitize_path_component(crate_spec.name());
+        let safe_version = sanitize_path_component(&crate_spec.version().to_string());
+        format!("{safe_name}@{safe_version}.json")
+    }
+
+    /// Download logic for a single attempt.
+    /// Returns `Ok(None)` for 404 (not retryable), `Ok(Some(path))` on success.
+    async fn download_package_core(&self, crate_spec: &CrateSpec) -> Result<Option<PathBuf>> {
+        let crate_name = crate_spec.name();
+        let version = crate_spec.version().to_string();
+
+        let url = self.download_template.as_ref().map_or_else(
+            || format!("{}/{crate_name}/{crate_name}-{version}.crate", self.base_url),
+            |template| expand_download_template(template, crate_name, &version),
+        );
+
+        let response = crate::facts::resilient_http::resilient_get(&self.client, &url).await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            if status == reqwest::StatusCode::NOT_FOUND {
+                return Ok(None);
+            }
+            return Err(app_err!("could not download package for {crate_spec}: HTTP {status}"));
+        }
+
+        let temp_dir = std::env::temp_dir();
+        let safe_name = sanitize_path_component(crate_name);
+        let safe_version = sanitize_path_component(&version);
+        let temp_file = temp_dir.join(format!("{safe_name}-{safe_version}.crate"));
+
+        let mut file = tokio::fs::File::create(&temp_file)
+            .await
+            .into_app_err_with(|| format!("creating temp file '{}'", temp_file.display()))?;
+
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.try_next().await.into_app_err("reading response chunk")? {
+            file.write_all(&chunk)
+                .await
+                .into_app_err_with(|| format!("writing to temp file '{}'", temp_file.display()))?;
+        }
+
+        file.flush()
+            .await
+            .into_app_err_with(|| format!("flushing temp file '{}'", temp_file.display()))?;
+
+        Ok(Some(temp_file))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use semver::Version;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn test_crate_spec(name: &str, version: &str) -> CrateSpec {
+        CrateSpec::from_arcs(Arc::from(name), Arc::new(Version::parse(version).unwrap()))
+    }
+
+    fn test_cache(dir: &std::path::Path) -> Cache {
+        Cache::new(dir, core::time::Duration::MAX, false)
+    }
+
+    #[test]
+    fn test_get_cache_filename() {
+        let spec = test_crate_spec("tokio", "1.2.3");
+        assert_eq!(Provider::get_cache_filename(&spec), "tokio@1.2.3.json");
+    }
+
+    #[test]
+    fn test_provider_new_default_url() {
+        let provider = Provider::new(test_cache(std::path::Path::new("/tmp/test")), None);
+        assert_eq!(provider.base_url, PACKAGE_BASE_URL);
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore = "Miri cannot call CreateIoCompletionPort")]
+    async fn test_fetch_package_not_found() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/missing/missing-1.0.0.crate"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let provider = Provider::new(test_cache(tmp.path()), Some(&server.uri()));
+        let result = provider.fetch_package_for_crate_core(&test_crate_spec("missing", "1.0.0")).await;
+        assert!(matches!(result, ProviderResult::Unavailable(_)));
+    }
+}
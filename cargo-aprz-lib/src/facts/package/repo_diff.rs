@@ -0,0 +1,111 @@
+//! Comparison of a published package against the repository commit it was built from.
+
+use super::{BlobId, PackageData};
+use crate::{HashMap, HashSet};
+
+/// Files that `cargo package` generates or rewrites, and so never match the repository
+const GENERATED_FILES: [&str; 3] = [".cargo_vcs_info.json", "Cargo.toml", "Cargo.lock"];
+
+/// Name under which `cargo package` preserves the original manifest
+const ORIGINAL_MANIFEST: &str = "Cargo.toml.orig";
+
+/// Count the package files whose contents can't be found in the repository.
+///
+/// `repo_blobs` maps every file path in the repository tree at the package's commit to its git blob ID.
+/// A package file is explained when it matches the file at the same location in the repository. Files at
+/// the root of the package may also match a file anywhere in the repository, as happens when a workspace-level
+/// `README` or license file is copied into the package.
+pub fn count_repo_differences(package: &PackageData, repo_blobs: &HashMap<String, String>) -> u64 {
+    let repo_blobs: HashMap<&str, BlobId> = repo_blobs
+        .iter()
+        .filter_map(|(path, id)| Some((path.as_str(), id.parse().ok()?)))
+        .collect();
+    let known_blobs: HashSet<BlobId> = repo_blobs.values().copied().collect();
+    let prefix = package.path_in_vcs.trim_matches('/');
+
+    package
+        .file_hashes
+        .iter()
+        .filter(|(path, _)| !GENERATED_FILES.contains(&path.as_str()))
+        .filter(|(path, blob_id)| {
+            let repo_path = if path.as_str() == ORIGINAL_MANIFEST { "Cargo.toml" } else { path.as_str() };
+            let repo_path = if prefix.is_empty() {
+                repo_path.to_string()
+            } else {
+                format!("{prefix}/{repo_path}")
+            };
+
+            let same_location = repo_blobs.get(repo_path.as_str()) == Some(*blob_id);
+            let copied = !path.contains('/') && known_blobs.contains(*blob_id);
+            !same_location && !copied
+        })
+        .count() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn package(path_in_vcs: &str, files: &[(&str, &str)]) -> PackageData {
+        PackageData {
+            file_count: files.len() as u64,
+            binary_files: Vec::new(),
+            vcs_commit: Some("0123456789abcdef0123456789abcdef01234567".into()),
+            path_in_vcs: path_in_vcs.into(),
+            build_time_network_risk: 0,
+            license_files: BTreeMap::new(),
+            file_hashes: files.iter().map(|(p, h)| ((*p).into(), blob(h))).collect::<BTreeMap<_, _>>(),
+            repo_differences: None,
+        }
+    }
+
+    fn repo(files: &[(&str, &str)]) -> HashMap<String, String> {
+        files.iter().map(|(p, h)| ((*p).to_string(), blob(h).to_string())).collect()
+    }
+
+    /// A blob ID standing for the file contents of the given name
+    fn blob(name: &str) -> BlobId {
+        let mut bytes = [0; 20];
+        for (byte, c) in bytes.iter_mut().zip(name.bytes()) {
+            *byte = c;
+        }
+        BlobId::from(bytes)
+    }
+
+    #[test]
+    fn test_matching_package() {
+        let package = package(
+            "crates/demo",
+            &[
+                ("Cargo.toml", "normalized"),
+                ("Cargo.toml.orig", "manifest"),
+                (".cargo_vcs_info.json", "vcs"),
+                ("src/lib.rs", "lib"),
+                ("README.md", "readme"),
+            ],
+        );
+        let repo = repo(&[
+            ("README.md", "readme"),
+            ("crates/demo/Cargo.toml", "manifest"),
+            ("crates/demo/src/lib.rs", "lib"),
+            ("crates/demo/tests/it.rs", "excluded"),
+        ]);
+        assert_eq!(count_repo_differences(&package, &repo), 0);
+    }
+
+    #[test]
+    fn test_diverging_package() {
+        let package = package(
+            "",
+            &[
+                ("Cargo.toml.orig", "edited"),
+                ("src/lib.rs", "lib"),
+                ("src/extra.rs", "extra"),
+                ("src/moved.rs", "moved"),
+            ],
+        );
+        let repo = repo(&[("Cargo.toml", "manifest"), ("src/lib.rs", "lib"), ("other/moved.rs", "moved")]);
+        assert_eq!(count_repo_differences(&package, &repo), 3);
+    }
+}
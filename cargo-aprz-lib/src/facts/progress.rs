@@ -1,3 +1,18 @@
+use super::request_tracker::TopicStatus;
+
+/// The state of one of several topics progressing concurrently, such as the requests made to a given service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TopicProgress {
+    /// Short display name of the topic.
+    pub name: &'static str,
+    /// Number of work items issued for the topic.
+    pub total: u64,
+    /// Number of work items completed for the topic.
+    pub completed: u64,
+    /// Visual status of the topic.
+    pub status: TopicStatus,
+}
+
 /// A trait for reporting progress of long-running operations.
 pub trait Progress: Send + Sync {
     /// Set the phase label for the current operation (e.g., "Preparing", "Collecting").
@@ -15,6 +30,12 @@ pub trait Progress: Send + Sync {
     /// where the total amount of work is unknown.
     fn set_indeterminate(&self, callback: Box<dyn Fn() -> String + Send + Sync + 'static>);
 
+    /// Configure a separate progress indicator for each of several concurrently progressing topics.
+    ///
+    /// This complements the overall progress configured with [`Self::set_determinate`]. The callback returns
+    /// the current state of the topics that have work to do. Reporters showing a single indicator ignore this.
+    fn set_topics(&self, _callback: Box<dyn Fn() -> Vec<TopicProgress> + Send + Sync + 'static>) {}
+
     /// Print a message line without disrupting the progress indicator.
     fn println(&self, msg: &str);
 
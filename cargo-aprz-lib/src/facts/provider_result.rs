@@ -17,6 +17,9 @@ pub enum ProviderResult<T> {
 
     /// An error occurred during the operation for this crate.
     Error(Arc<ohno::AppError>),
+
+    /// The run was cancelled before the data could be gathered.
+    Cancelled,
 }
 
 impl<T: Clone> ProviderResult<T> {
@@ -26,6 +29,12 @@ impl<T: Clone> ProviderResult<T> {
         matches!(self, Self::Found(_))
     }
 
+    /// Returns `true` if the result is `Cancelled`.
+    #[must_use]
+    pub const fn is_cancelled(&self) -> bool {
+        matches!(self, Self::Cancelled)
+    }
+
     /// Returns a reference to the contained data if `Found`, otherwise `None`.
     #[must_use]
     pub const fn as_ref(&self) -> Option<&T> {
@@ -65,6 +74,17 @@ mod tests {
         assert!(!result.is_found());
     }
 
+    #[test]
+    fn test_is_cancelled() {
+        let result: ProviderResult<String> = ProviderResult::Cancelled;
+        assert!(result.is_cancelled());
+        assert!(!result.is_found());
+        assert_eq!(result.as_ref(), None);
+
+        let result: ProviderResult<String> = ProviderResult::Found("data".to_string());
+        assert!(!result.is_cancelled());
+    }
+
     #[test]
     fn test_as_ref_for_found() {
         let result: ProviderResult<u32> = ProviderResult::Found(42);
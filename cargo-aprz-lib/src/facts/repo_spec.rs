@@ -1,3 +1,4 @@
+use super::interner::{self, intern};
 use crate::Result;
 use core::fmt::{Display, Formatter};
 use ohno::{IntoAppError, bail};
@@ -8,8 +9,11 @@ use url::Url;
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct RepoSpec {
     url: Arc<Url>,
+    #[serde(deserialize_with = "interner::deserialize")]
     host: Arc<str>,
+    #[serde(deserialize_with = "interner::deserialize")]
     owner: Arc<str>,
+    #[serde(deserialize_with = "interner::deserialize")]
     repo: Arc<str>,
 }
 
@@ -35,9 +39,9 @@ impl RepoSpec {
             .into_app_err("reconstructing repository URL")?;
 
         Ok(Self {
-            host: Arc::from(host),
-            owner: Arc::from(owner),
-            repo: Arc::from(repo),
+            host: intern(host),
+            owner: intern(owner),
+            repo: intern(repo),
             url: Arc::new(clean_url),
         })
     }
@@ -1,6 +1,6 @@
 //! Request tracking for monitoring outstanding HTTP requests.
 
-use super::progress::Progress;
+use super::progress::{Progress, TopicProgress};
 use core::sync::atomic::{AtomicU64, AtomicU8, Ordering};
 use owo_colors::OwoColorize;
 use std::sync::Arc;
@@ -12,22 +12,24 @@ pub enum TrackedTopic {
     Docs,
     Repos,
     Codebase,
+    Packages,
 }
 
 impl TrackedTopic {
     /// Get the display name for this topic.
-    const fn name(self) -> &'static str {
+    pub const fn name(self) -> &'static str {
         match self {
             Self::Coverage => "coverage",
             Self::Docs => "docs",
             Self::Repos => "repos",
             Self::Codebase => "codebase",
+            Self::Packages => "packages",
         }
     }
 
     /// Get all tracked topics in a consistent order.
-    const fn all() -> [Self; 4] {
-        [Self::Coverage, Self::Docs, Self::Repos, Self::Codebase]
+    const fn all() -> [Self; 5] {
+        [Self::Coverage, Self::Docs, Self::Repos, Self::Codebase, Self::Packages]
     }
 
     /// Convert to array index.
@@ -49,6 +51,38 @@ pub enum TopicStatus {
     Done = 2,
 }
 
+impl TopicStatus {
+    const fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::Blocked,
+            2 => Self::Done,
+            _ => Self::Active,
+        }
+    }
+
+    /// Style text according to this status, making it green when done and blinking yellow when blocked.
+    #[must_use]
+    pub fn style(self, text: String, use_colors: bool) -> String {
+        if !use_colors {
+            return text;
+        }
+
+        match self {
+            Self::Active => text,
+            Self::Done => format!("{}", text.green()),
+            Self::Blocked => {
+                // Toggle every 500ms for the blink effect
+                let ms = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis();
+
+                if (ms / 500).is_multiple_of(2) { format!("{}", text.yellow()) } else { text }
+            }
+        }
+    }
+}
+
 /// Counter for a specific tracked topic.
 #[derive(Debug, Default)]
 struct RequestCounter {
@@ -65,7 +99,7 @@ struct RequestCounter {
 /// Requests are tracked by topic, with separate counters for different request types.
 #[derive(Clone)]
 pub struct RequestTracker {
-    counters: Arc<[RequestCounter; 4]>,
+    counters: Arc<[RequestCounter; 5]>,
     progress: Arc<dyn Progress>,
 }
 
@@ -82,12 +116,15 @@ impl RequestTracker {
     /// Create a new request tracker with the given progress reporter.
     #[must_use]
     pub fn new(progress: &Arc<dyn Progress>) -> Self {
-        let counters: Arc<[RequestCounter; 4]> = Arc::default();
+        let counters: Arc<[RequestCounter; 5]> = Arc::default();
 
         let counters_clone = Arc::clone(&counters);
         let use_colors = progress.use_colors();
         progress.set_determinate(Box::new(move || Self::progress_reporter_callback(&counters_clone, use_colors)));
 
+        let counters_clone = Arc::clone(&counters);
+        progress.set_topics(Box::new(move || Self::topics(&counters_clone).collect()));
+
         Self {
             counters,
             progress: Arc::clone(progress),
@@ -123,45 +160,33 @@ impl RequestTracker {
         self.counters[topic.index()].status.store(status as u8, Ordering::Relaxed);
     }
 
+    /// Get the state of every topic that has issued requests, in a consistent order.
+    fn topics(counters: &[RequestCounter; 5]) -> impl Iterator<Item = TopicProgress> {
+        TrackedTopic::all().into_iter().filter_map(|topic| {
+            let counter = &counters[topic.index()];
+            let total = counter.issued.load(Ordering::Relaxed);
+
+            (total > 0).then(|| TopicProgress {
+                name: topic.name(),
+                total,
+                completed: counter.completed.load(Ordering::Relaxed),
+                status: TopicStatus::from_u8(counter.status.load(Ordering::Relaxed)),
+            })
+        })
+    }
+
     /// Compute current progress state from counters.
     ///
     /// Returns (`total_length`, `current_position`, `message_string`).
-    fn progress_reporter_callback(counters: &[RequestCounter; 4], use_colors: bool) -> (u64, u64, String) {
-        // Toggle every 500ms for the blink effect on blocked topics
-        let blink_on = use_colors && {
-            let ms = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_millis();
-            (ms / 500).is_multiple_of(2)
-        };
-
+    fn progress_reporter_callback(counters: &[RequestCounter; 5], use_colors: bool) -> (u64, u64, String) {
         let mut total_issued = 0u64;
         let mut total_completed = 0u64;
         let mut parts = Vec::with_capacity(TrackedTopic::all().len());
 
-        for topic in TrackedTopic::all() {
-            let counter = &counters[topic.index()];
-            let issued = counter.issued.load(Ordering::Relaxed);
-            let completed = counter.completed.load(Ordering::Relaxed);
-
-            if issued > 0 {
-                total_issued += issued;
-                total_completed += completed;
-
-                let text = format!("{completed}/{issued} {}", topic.name());
-                let status = counter.status.load(Ordering::Relaxed);
-
-                let styled = if use_colors && status == TopicStatus::Done as u8 {
-                    format!("{}", text.green())
-                } else if status == TopicStatus::Blocked as u8 && blink_on {
-                    format!("{}", text.yellow())
-                } else {
-                    text
-                };
-
-                parts.push(styled);
-            }
+        for topic in Self::topics(counters) {
+            total_issued += topic.total;
+            total_completed += topic.completed;
+            parts.push(topic.status.style(format!("{}/{} {}", topic.completed, topic.total, topic.name), use_colors));
         }
 
         let message = if parts.is_empty() {
@@ -218,16 +243,18 @@ mod tests {
         assert_eq!(TrackedTopic::Docs.name(), "docs");
         assert_eq!(TrackedTopic::Repos.name(), "repos");
         assert_eq!(TrackedTopic::Codebase.name(), "codebase");
+        assert_eq!(TrackedTopic::Packages.name(), "packages");
     }
 
     #[test]
     fn test_tracked_topic_all() {
         let all_topics = TrackedTopic::all();
-        assert_eq!(all_topics.len(), 4);
+        assert_eq!(all_topics.len(), 5);
         assert_eq!(all_topics[0], TrackedTopic::Coverage);
         assert_eq!(all_topics[1], TrackedTopic::Docs);
         assert_eq!(all_topics[2], TrackedTopic::Repos);
         assert_eq!(all_topics[3], TrackedTopic::Codebase);
+        assert_eq!(all_topics[4], TrackedTopic::Packages);
     }
 
     #[test]
@@ -236,6 +263,7 @@ mod tests {
         assert_eq!(TrackedTopic::Docs.index(), 1);
         assert_eq!(TrackedTopic::Repos.index(), 2);
         assert_eq!(TrackedTopic::Codebase.index(), 3);
+        assert_eq!(TrackedTopic::Packages.index(), 4);
     }
 
     #[test]
@@ -419,6 +447,35 @@ mod tests {
         assert!(parts[3].contains("codebase"));
     }
 
+    #[test]
+    fn test_topics() {
+        let tracker = test_tracker();
+        tracker.add_requests(TrackedTopic::Repos, 3);
+        tracker.add_requests(TrackedTopic::Docs, 2);
+        tracker.complete_request(TrackedTopic::Docs);
+        tracker.complete_request(TrackedTopic::Docs);
+        tracker.set_topic_status(TrackedTopic::Repos, TopicStatus::Blocked);
+
+        let topics: Vec<_> = RequestTracker::topics(&tracker.counters).collect();
+        assert_eq!(
+            topics,
+            [
+                TopicProgress {
+                    name: "docs",
+                    total: 2,
+                    completed: 2,
+                    status: TopicStatus::Done,
+                },
+                TopicProgress {
+                    name: "repos",
+                    total: 3,
+                    completed: 0,
+                    status: TopicStatus::Blocked,
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_tracker_clone() {
         let tracker1 = test_tracker();
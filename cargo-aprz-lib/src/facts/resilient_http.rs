@@ -62,6 +62,16 @@ fn should_retry_response(result: &crate::Result<reqwest::Response>) -> RecoveryI
 ///
 /// Retries on network errors, 5xx, and 429 responses with exponential backoff.
 pub async fn resilient_get(client: &reqwest::Client, url: &str) -> crate::Result<reqwest::Response> {
+    resilient_get_with_headers(client, url, reqwest::header::HeaderMap::new()).await
+}
+
+/// Send an HTTP GET request with additional headers, such as credentials that change over time, with
+/// automatic retry and timeout.
+pub async fn resilient_get_with_headers(
+    client: &reqwest::Client,
+    url: &str,
+    headers: reqwest::header::HeaderMap,
+) -> crate::Result<reqwest::Response> {
     let clock = Clock::new_tokio();
     let context = ResilienceContext::new(&clock).name("http_get");
 
@@ -85,9 +95,11 @@ pub async fn resilient_get(client: &reqwest::Client, url: &str) -> crate::Result
             .timeout(DEFAULT_REQUEST_TIMEOUT),
         Execute::new(move |url: String| {
             let client = client.clone();
+            let headers = headers.clone();
             async move {
                 client
                     .get(&url)
+                    .headers(headers)
                     .send()
                     .await
                     .map_err(ohno::AppError::from)
@@ -0,0 +1,312 @@
+//! Replacement of the crates.io source configured for Cargo.
+//!
+//! Cargo can fetch crates.io crates from somewhere else, such as a mirror of the registry or a directory of sources
+//! vendored with `cargo vendor`, when the `[source.crates-io]` table of its configuration has a `replace-with`
+//! entry. Cargo reads the `.cargo/config.toml` files of the current directory and its ancestors, followed by the one
+//! in Cargo's home, with the deeper ones taking precedence. Paths in a source's definition are relative to the
+//! directory holding the `.cargo` directory that defines it.
+//!
+//! The crates.io database dump isn't served by mirrors, so metrics still come from crates.io, but packages can be
+//! downloaded from sparse registry mirrors and vendored sources can be analyzed in place.
+
+use super::local_sources::cargo_home;
+use crate::Result;
+use ohno::{IntoAppError, bail};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const LOG_TARGET: &str = "   sources";
+
+/// Markers cargo replaces in the download URL template of a registry
+const DOWNLOAD_MARKERS: &[&str] = &["{crate}", "{version}", "{prefix}", "{lowerprefix}", "{sha256-checksum}"];
+
+#[derive(Debug, Default, Deserialize)]
+struct CargoConfig {
+    #[serde(default)]
+    source: BTreeMap<String, SourceDefinition>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct SourceDefinition {
+    replace_with: Option<String>,
+    registry: Option<String>,
+    local_registry: Option<PathBuf>,
+    directory: Option<PathBuf>,
+    git: Option<String>,
+
+    /// Directory the relative paths of the definition are resolved against
+    #[serde(skip)]
+    base_dir: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct RegistryConfig {
+    dl: String,
+}
+
+/// Where crates.io crates are fetched from in place of crates.io
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplacedSource {
+    /// A registry, such as a mirror, given by the URL of its index
+    Registry(String),
+
+    /// A registry whose index and packages are on disk
+    LocalRegistry(PathBuf),
+
+    /// Sources vendored with `cargo vendor`
+    Directory(PathBuf),
+
+    /// A git repository
+    Git(String),
+}
+
+/// The source Cargo uses in place of crates.io
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceReplacement {
+    /// Name of the replacing source in Cargo's configuration
+    pub name: String,
+
+    /// Where the replacing source fetches crates from
+    pub source: ReplacedSource,
+}
+
+impl core::fmt::Display for SourceReplacement {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match &self.source {
+            ReplacedSource::Registry(url) => write!(f, "registry '{}' at {url}", self.name),
+            ReplacedSource::LocalRegistry(path) => write!(f, "local registry '{}' in '{}'", self.name, path.display()),
+            ReplacedSource::Directory(path) => write!(f, "vendored sources '{}' in '{}'", self.name, path.display()),
+            ReplacedSource::Git(url) => write!(f, "git repository '{}' at {url}", self.name),
+        }
+    }
+}
+
+impl SourceReplacement {
+    /// The replacement of crates.io in effect for Cargo commands run in `dir`, if any.
+    #[must_use]
+    pub fn find(dir: &Path) -> Option<Self> {
+        let mut files: Vec<PathBuf> = dir.ancestors().filter_map(|dir| config_file(&dir.join(".cargo"))).collect();
+        if let Some(home_config) = cargo_home().and_then(|home| config_file(&home))
+            && !files.contains(&home_config)
+        {
+            files.push(home_config);
+        }
+
+        // Deeper files take precedence, so definitions already found are kept
+        let mut sources: BTreeMap<String, SourceDefinition> = BTreeMap::new();
+        for file in &files {
+            let config = match fs::read_to_string(file).map(|text| toml::from_str::<CargoConfig>(&text)) {
+                Ok(Ok(config)) => config,
+                Ok(Err(e)) => {
+                    log::warn!(target: LOG_TARGET, "Could not parse Cargo configuration '{}': {e}", file.display());
+                    continue;
+                }
+                Err(e) => {
+                    log::warn!(target: LOG_TARGET, "Could not read Cargo configuration '{}': {e}", file.display());
+                    continue;
+                }
+            };
+
+            // The configuration file lives in a `.cargo` directory, or directly in Cargo's home
+            let base_dir = file.parent().and_then(Path::parent).unwrap_or_else(|| Path::new("")).to_path_buf();
+            for (name, definition) in config.source {
+                let _ = sources.entry(name).or_insert_with(|| SourceDefinition {
+                    base_dir: base_dir.clone(),
+                    ..definition
+                });
+            }
+        }
+
+        resolve(&sources)
+    }
+
+    /// The directory of vendored sources, when crates.io is replaced by one.
+    #[must_use]
+    pub fn vendor_dir(&self) -> Option<&Path> {
+        match &self.source {
+            ReplacedSource::Directory(path) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// The URL template packages are downloaded with, asked to a sparse registry mirror.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the source isn't a sparse registry, or its configuration can't be fetched or used.
+    pub async fn download_template(&self, client: &reqwest::Client) -> Result<String> {
+        let ReplacedSource::Registry(url) = &self.source else {
+            bail!("source '{}' isn't a registry", self.name);
+        };
+        let Some(index_url) = url.strip_prefix("sparse+") else {
+            bail!(
+                "registry '{}' has a git index, only sparse registries can be used to download packages",
+                self.name
+            );
+        };
+
+        let config_url = format!("{}/config.json", index_url.trim_end_matches('/'));
+        let config: RegistryConfig = client
+            .get(&config_url)
+            .send()
+            .await
+            .into_app_err_with(|| format!("fetching '{config_url}'"))?
+            .error_for_status()
+            .into_app_err_with(|| format!("fetching '{config_url}'"))?
+            .json()
+            .await
+            .into_app_err_with(|| format!("parsing '{config_url}'"))?;
+
+        if config.dl.contains("{sha256-checksum}") {
+            bail!("registry '{}' downloads packages by checksum, which isn't known", self.name);
+        }
+
+        Ok(config.dl)
+    }
+}
+
+/// The configuration file of a `.cargo` directory or of Cargo's home, preferring the `.toml` name like Cargo.
+fn config_file(dir: &Path) -> Option<PathBuf> {
+    ["config.toml", "config"]
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// Follow the `replace-with` entries starting from crates.io, down to the source that replaces it.
+fn resolve(sources: &BTreeMap<String, SourceDefinition>) -> Option<SourceReplacement> {
+    let mut name = sources.get("crates-io")?.replace_with.clone()?;
+
+    // Each source can only be visited once, which stops cycles
+    for _ in 0..sources.len() {
+        let Some(definition) = sources.get(&name) else {
+            log::warn!(target: LOG_TARGET, "crates.io is replaced by source '{name}', which Cargo's configuration doesn't define");
+            return None;
+        };
+
+        if let Some(next) = &definition.replace_with {
+            name.clone_from(next);
+            continue;
+        }
+
+        let source = if let Some(url) = &definition.registry {
+            ReplacedSource::Registry(url.clone())
+        } else if let Some(path) = &definition.local_registry {
+            ReplacedSource::LocalRegistry(definition.base_dir.join(path))
+        } else if let Some(path) = &definition.directory {
+            ReplacedSource::Directory(definition.base_dir.join(path))
+        } else if let Some(url) = &definition.git {
+            ReplacedSource::Git(url.clone())
+        } else {
+            log::warn!(target: LOG_TARGET, "Source '{name}' of Cargo's configuration doesn't say where crates come from");
+            return None;
+        };
+
+        return Some(SourceReplacement { name, source });
+    }
+
+    log::warn!(target: LOG_TARGET, "The replacements of crates.io in Cargo's configuration form a cycle");
+    None
+}
+
+/// The URL of a package given the download URL template of a registry, as documented for `config.json`.
+#[must_use]
+pub fn expand_download_template(template: &str, name: &str, crate_version: &str) -> String {
+    if !DOWNLOAD_MARKERS.iter().any(|marker| template.contains(marker)) {
+        return format!("{}/{name}/{crate_version}/download", template.trim_end_matches('/'));
+    }
+
+    let chars: Vec<char> = name.chars().collect();
+    let index_prefix = match chars.as_slice() {
+        [] => String::new(),
+        [_] => "1".to_string(),
+        [_, _] => "2".to_string(),
+        [first, _, _] => format!("3/{first}"),
+        [a, b, c, d, ..] => format!("{a}{b}/{c}{d}"),
+    };
+
+    template
+        .replace("{crate}", name)
+        .replace("{version}", crate_version)
+        .replace("{lowerprefix}", &index_prefix.to_lowercase())
+        .replace("{prefix}", &index_prefix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definition(text: &str) -> SourceDefinition {
+        toml::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn test_resolve() {
+        let mut sources = BTreeMap::from([
+            ("crates-io".to_string(), definition("replace-with = 'corp'")),
+            ("corp".to_string(), definition("replace-with = 'mirror'")),
+            (
+                "mirror".to_string(),
+                definition("registry = 'sparse+https://mirror.example.com/index/'"),
+            ),
+        ]);
+        assert_eq!(
+            resolve(&sources),
+            Some(SourceReplacement {
+                name: "mirror".into(),
+                source: ReplacedSource::Registry("sparse+https://mirror.example.com/index/".into()),
+            })
+        );
+
+        let _ = sources.insert("mirror".into(), definition("replace-with = 'corp'"));
+        assert_eq!(resolve(&sources), None);
+
+        let _ = sources.insert("crates-io".into(), SourceDefinition::default());
+        assert_eq!(resolve(&sources), None);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    fn test_find_vendored_sources() {
+        let tmp = tempfile::tempdir().unwrap();
+        let project = tmp.path().join("project");
+        fs::create_dir_all(project.join(".cargo")).unwrap();
+        fs::write(
+            project.join(".cargo").join("config.toml"),
+            "[source.crates-io]\nreplace-with = 'vendored-sources'\n\n[source.vendored-sources]\ndirectory = 'vendor'\n",
+        )
+        .unwrap();
+
+        let replacement = SourceReplacement::find(&project.join("src")).unwrap();
+        assert_eq!(replacement.name, "vendored-sources");
+        assert_eq!(replacement.vendor_dir(), Some(project.join("vendor").as_path()));
+        assert!(replacement.to_string().starts_with("vendored sources 'vendored-sources'"));
+    }
+
+    #[test]
+    fn test_expand_download_template() {
+        assert_eq!(
+            expand_download_template("https://mirror.example.com/api/v1/crates", "serde", "1.0.0"),
+            "https://mirror.example.com/api/v1/crates/serde/1.0.0/download"
+        );
+        assert_eq!(
+            expand_download_template(
+                "https://mirror.example.com/{prefix}/{crate}/{crate}-{version}.crate",
+                "serde",
+                "1.0.0"
+            ),
+            "https://mirror.example.com/se/rd/serde/serde-1.0.0.crate"
+        );
+        assert_eq!(
+            expand_download_template("https://mirror.example.com/{lowerprefix}/{crate}", "Abc", "0.1.0"),
+            "https://mirror.example.com/3/a/Abc"
+        );
+        assert_eq!(
+            expand_download_template("https://m.example.com/{prefix}/{crate}", "ab", "1.0.0"),
+            "https://m.example.com/2/ab"
+        );
+    }
+}
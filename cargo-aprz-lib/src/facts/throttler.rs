@@ -1,3 +1,4 @@
+use super::Cancellation;
 use core::sync::atomic::{AtomicBool, Ordering};
 use core::time::Duration;
 use std::sync::Arc;
@@ -52,6 +53,18 @@ impl Throttler {
         }
     }
 
+    /// Like [`Throttler::acquire`], but gives up once `cancellation` is cancelled, returning `None`.
+    pub async fn acquire_unless_cancelled(&self, cancellation: &Cancellation) -> Option<tokio::sync::OwnedSemaphorePermit> {
+        if cancellation.is_cancelled() {
+            return None;
+        }
+
+        tokio::select! {
+            permit = self.acquire() => Some(permit),
+            () = cancellation.cancelled() => None,
+        }
+    }
+
     /// Returns whether the throttler is currently paused.
     pub fn is_paused(&self) -> bool {
         self.paused.load(Ordering::Acquire)
@@ -152,4 +165,18 @@ mod tests {
         // Should have waited at least ~200ms
         assert!(elapsed >= Duration::from_millis(150));
     }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore = "Miri cannot call CreateIoCompletionPort on Windows")]
+    async fn cancellation_stops_waiting() {
+        let throttler = Throttler::new(5);
+        let cancellation = Cancellation::new();
+        assert!(throttler.acquire_unless_cancelled(&cancellation).await.is_some());
+
+        // A paused throttler gives up as soon as the run is cancelled
+        let _ = throttler.pause_for(Duration::from_secs(60));
+        let timer = cancellation.cancel_after(Duration::from_millis(10), "interrupted");
+        assert!(throttler.acquire_unless_cancelled(&cancellation).await.is_none());
+        timer.await.unwrap();
+    }
 }
@@ -0,0 +1,193 @@
+//! Persistent history of appraisal runs
+//!
+//! When a `[history]` section is present in the configuration, the outcome of every run is recorded in a
+//! history store, along with the score, risk, and known vulnerabilities of every appraised crate. This makes
+//! it possible to track how the dependencies of a workspace evolve over time, and to react to changes.
+//!
+//! # Implementation Model
+//!
+//! Stores implement the [`HistoryStore`] trait, and [`open_store`] picks the implementation based on the
+//! scheme of the configured URL:
+//!
+//! - `sqlite://<path>` keeps the history in a local `SQLite` database, requiring the `history_sqlite` feature
+//! - `postgres://<connection string>` keeps it in a shared Postgres database, requiring the `history_postgres`
+//!   feature, so that large organizations can persist results centrally
+//!
+//! Both implementations use the same two tables, `aprz_runs` and `aprz_crates`, so the history can be
+//! queried with standard BI tools.
+
+#[cfg(feature = "history_postgres")]
+mod postgres;
+#[cfg(feature = "history_sqlite")]
+mod sqlite;
+
+use crate::Result;
+use crate::expr::Risk;
+use crate::reports::{ReportableCrate, workspace_score};
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use ohno::bail;
+
+/// One run recorded in the history
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunRecord {
+    /// Name of the appraised workspace
+    pub workspace: String,
+
+    /// The git commit the workspace was appraised at, if known
+    pub commit: Option<String>,
+
+    /// When the run happened
+    pub recorded_at: DateTime<Utc>,
+
+    /// The workspace score, when appraising workspace dependencies
+    pub score: Option<f64>,
+
+    /// Whether no `--error-if` check failed
+    pub passed: bool,
+
+    /// The appraised crates
+    pub crates: Vec<CrateRecord>,
+}
+
+/// One crate appraised in a recorded run
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrateRecord {
+    pub name: String,
+    pub version: String,
+
+    /// The appraisal score, unless the crate wasn't appraised
+    pub score: Option<f64>,
+
+    /// The appraisal risk, unless the crate wasn't appraised
+    pub risk: Option<Risk>,
+
+    /// Number of known vulnerabilities affecting the appraised version
+    pub vulnerabilities: u64,
+}
+
+impl RunRecord {
+    /// Build the record of a run from its appraised crates.
+    #[must_use]
+    pub fn new(workspace: String, commit: Option<String>, passed: bool, crates: &[ReportableCrate]) -> Self {
+        Self {
+            workspace,
+            commit,
+            recorded_at: Utc::now(),
+            score: workspace_score(crates).map(|w| w.score),
+            passed,
+            crates: crates
+                .iter()
+                .map(|c| CrateRecord {
+                    name: c.name.to_string(),
+                    version: c.version.to_string(),
+                    score: c.appraisal.as_ref().map(|a| a.score),
+                    risk: c.appraisal.as_ref().map(|a| a.risk),
+                    vulnerabilities: c.vulnerability_count(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A place where the history of runs is kept
+pub trait HistoryStore: Send + Sync {
+    /// Add a run to the history
+    fn record_run<'a>(&'a self, run: &'a RunRecord) -> BoxFuture<'a, Result<()>>;
+
+    /// Get the most recent run recorded for a workspace
+    fn latest_run<'a>(&'a self, workspace: &'a str) -> BoxFuture<'a, Result<Option<RunRecord>>>;
+}
+
+/// Check that a history URL refers to a store compiled into this build.
+pub fn check_url(url: &str) -> Result<()> {
+    match url.split_once("://").map(|(scheme, _)| scheme) {
+        Some("sqlite") if cfg!(feature = "history_sqlite") => Ok(()),
+        Some("postgres" | "postgresql") if cfg!(feature = "history_postgres") => Ok(()),
+        Some("sqlite") => bail!("cannot use history store '{url}', cargo-aprz was built without the 'history_sqlite' feature"),
+        Some("postgres" | "postgresql") => {
+            bail!("cannot use history store '{url}', cargo-aprz was built without the 'history_postgres' feature")
+        }
+        _ => bail!("unsupported history store '{url}', expected a sqlite:// or postgres:// URL"),
+    }
+}
+
+/// Open the history store at the given URL, creating its tables if needed.
+pub async fn open_store(url: &str) -> Result<Box<dyn HistoryStore>> {
+    #[cfg(feature = "history_sqlite")]
+    if let Some(path) = url.strip_prefix("sqlite://") {
+        return Ok(Box::new(sqlite::SqliteStore::open(path).await?));
+    }
+
+    #[cfg(feature = "history_postgres")]
+    if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+        return Ok(Box::new(postgres::PostgresStore::connect(url).await?));
+    }
+
+    check_url(url)?;
+    bail!("unsupported history store '{url}'")
+}
+
+#[cfg(any(feature = "history_sqlite", feature = "history_postgres"))]
+const fn risk_str(risk: Risk) -> &'static str {
+    match risk {
+        Risk::Low => "low",
+        Risk::Medium => "medium",
+        Risk::High => "high",
+    }
+}
+
+#[cfg(any(feature = "history_sqlite", feature = "history_postgres"))]
+fn parse_risk(s: &str) -> Option<Risk> {
+    match s {
+        "low" => Some(Risk::Low),
+        "medium" => Some(Risk::Medium),
+        "high" => Some(Risk::High),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::Appraisal;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_run_record_from_crates() {
+        let crates = [
+            ReportableCrate::new(
+                "serde".into(),
+                Arc::new("1.0.0".parse().unwrap()),
+                vec![],
+                Some(Appraisal::new(Risk::Low, vec![], 10, 9, 90.0)),
+            ),
+            ReportableCrate::new("unknown".into(), Arc::new("0.1.0".parse().unwrap()), vec![], None),
+        ];
+
+        let run = RunRecord::new("payments".into(), Some("abc123".into()), true, &crates);
+        assert!(run.score.is_none());
+        assert_eq!(run.crates.len(), 2);
+        assert_eq!(run.crates[0].risk, Some(Risk::Low));
+        assert_eq!(run.crates[0].score, Some(90.0));
+        assert_eq!(run.crates[1].version, "0.1.0");
+        assert!(run.crates[1].score.is_none());
+    }
+
+    #[test]
+    #[cfg(any(feature = "history_sqlite", feature = "history_postgres"))]
+    fn test_risk_round_trip() {
+        for risk in [Risk::Low, Risk::Medium, Risk::High] {
+            assert_eq!(parse_risk(risk_str(risk)), Some(risk));
+        }
+        assert_eq!(parse_risk("unknown"), None);
+    }
+
+    #[test]
+    fn test_check_url() {
+        assert_eq!(check_url("sqlite://history.db").is_ok(), cfg!(feature = "history_sqlite"));
+        assert_eq!(check_url("postgres://localhost/aprz").is_ok(), cfg!(feature = "history_postgres"));
+        assert!(check_url("mysql://localhost/aprz").is_err());
+        assert!(check_url("history.db").is_err());
+    }
+}
@@ -0,0 +1,232 @@
+//! History store kept in a shared Postgres database.
+//!
+//! Connections are encrypted according to the `sslmode` of the connection string: `disable` never uses TLS,
+//! `prefer`, the default, uses it when the server supports it, and `require` insists on it. Server certificates are
+//! always verified against the platform's trusted roots, so libpq's `verify-ca` and `verify-full` are accepted as
+//! synonyms of `require`, and `allow` as a synonym of `prefer`.
+
+use super::{CrateRecord, HistoryStore, RunRecord, parse_risk, risk_str};
+use crate::Result;
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use ohno::{AppError, IntoAppError};
+use rustls::ClientConfig;
+use rustls_platform_verifier::BuilderVerifierExt;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio_postgres::{Client, Config};
+use tokio_postgres_rustls::MakeRustlsConnect;
+
+const LOG_TARGET: &str = "   history";
+
+/// libpq `sslmode` values tokio-postgres doesn't know, and the mode they amount to
+const SSL_MODE_SYNONYMS: &[(&str, &str)] = &[
+    ("sslmode=allow", "sslmode=prefer"),
+    ("sslmode=verify-ca", "sslmode=require"),
+    ("sslmode=verify-full", "sslmode=require"),
+];
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS aprz_runs (
+    id BIGSERIAL PRIMARY KEY,
+    workspace TEXT NOT NULL,
+    commit_sha TEXT,
+    recorded_at TIMESTAMPTZ NOT NULL,
+    score DOUBLE PRECISION,
+    passed BOOLEAN NOT NULL
+);
+CREATE TABLE IF NOT EXISTS aprz_crates (
+    run_id BIGINT NOT NULL REFERENCES aprz_runs (id),
+    name TEXT NOT NULL,
+    version TEXT NOT NULL,
+    score DOUBLE PRECISION,
+    risk TEXT,
+    vulnerabilities BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS aprz_runs_workspace ON aprz_runs (workspace, recorded_at);
+CREATE INDEX IF NOT EXISTS aprz_crates_run ON aprz_crates (run_id);
+";
+
+pub struct PostgresStore {
+    client: Mutex<Client>,
+}
+
+impl PostgresStore {
+    /// Connect to the database at `url`, creating the tables if needed.
+    pub async fn connect(url: &str) -> Result<Self> {
+        let config = normalize_ssl_mode(url)
+            .parse::<Config>()
+            .into_app_err("parsing the history database connection string")?;
+        let (client, connection) = config
+            .connect(tls_connector()?)
+            .await
+            .into_app_err("connecting to the history database")?;
+
+        drop(tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                log::error!(target: LOG_TARGET, "Lost connection to the history database: {e}");
+            }
+        }));
+
+        client.batch_execute(SCHEMA).await.into_app_err("creating history tables")?;
+
+        Ok(Self { client: Mutex::new(client) })
+    }
+
+    async fn insert_run(&self, run: &RunRecord) -> Result<()> {
+        let mut client = self.client.lock().await;
+        let tx = client.transaction().await.into_app_err("starting history transaction")?;
+
+        let row = tx
+            .query_one(
+                "INSERT INTO aprz_runs (workspace, commit_sha, recorded_at, score, passed) VALUES ($1, $2, $3, $4, $5) RETURNING id",
+                &[&run.workspace, &run.commit, &run.recorded_at, &run.score, &run.passed],
+            )
+            .await
+            .into_app_err("recording run")?;
+        let run_id: i64 = row.get(0);
+
+        let insert = tx
+            .prepare("INSERT INTO aprz_crates (run_id, name, version, score, risk, vulnerabilities) VALUES ($1, $2, $3, $4, $5, $6)")
+            .await
+            .into_app_err("preparing crate insertion")?;
+        for c in &run.crates {
+            let vulnerabilities = i64::try_from(c.vulnerabilities).unwrap_or(i64::MAX);
+            let _ = tx
+                .execute(&insert, &[&run_id, &c.name, &c.version, &c.score, &c.risk.map(risk_str), &vulnerabilities])
+                .await
+                .into_app_err_with(|| format!("recording crate '{}'", c.name))?;
+        }
+
+        let result = tx.commit().await.into_app_err("committing history transaction");
+        drop(client);
+        result
+    }
+
+    async fn select_latest_run(&self, workspace: &str) -> Result<Option<RunRecord>> {
+        let client = self.client.lock().await;
+        let Some(row) = client
+            .query_opt(
+                "SELECT id, commit_sha, recorded_at, score, passed FROM aprz_runs WHERE workspace = $1 \
+                 ORDER BY recorded_at DESC, id DESC LIMIT 1",
+                &[&workspace],
+            )
+            .await
+            .into_app_err_with(|| format!("reading latest run of workspace '{workspace}'"))?
+        else {
+            return Ok(None);
+        };
+
+        let run_id: i64 = row.get(0);
+        let crates = client
+            .query(
+                "SELECT name, version, score, risk, vulnerabilities FROM aprz_crates WHERE run_id = $1 ORDER BY name, version",
+                &[&run_id],
+            )
+            .await
+            .into_app_err_with(|| format!("reading crates of the latest run of workspace '{workspace}'"))?
+            .iter()
+            .map(|row| CrateRecord {
+                name: row.get(0),
+                version: row.get(1),
+                score: row.get(2),
+                risk: row.get::<_, Option<&str>>(3).and_then(parse_risk),
+                vulnerabilities: u64::try_from(row.get::<_, i64>(4)).unwrap_or_default(),
+            })
+            .collect();
+        drop(client);
+
+        Ok(Some(RunRecord {
+            workspace: workspace.to_string(),
+            commit: row.get(1),
+            recorded_at: row.get::<_, DateTime<Utc>>(2),
+            score: row.get(3),
+            passed: row.get(4),
+            crates,
+        }))
+    }
+}
+
+/// Replace the `sslmode` values of libpq which tokio-postgres rejects with their equivalent.
+fn normalize_ssl_mode(url: &str) -> String {
+    SSL_MODE_SYNONYMS
+        .iter()
+        .fold(url.to_string(), |url, (mode, equivalent)| url.replace(mode, equivalent))
+}
+
+/// TLS connector verifying server certificates against the platform's trusted roots
+fn tls_connector() -> Result<MakeRustlsConnect> {
+    let config = ClientConfig::builder_with_provider(Arc::new(rustls::crypto::aws_lc_rs::default_provider()))
+        .with_safe_default_protocol_versions()
+        .and_then(BuilderVerifierExt::with_platform_verifier)
+        .map_err(AppError::new)
+        .into_app_err("configuring TLS for the history database")?
+        .with_no_client_auth();
+    Ok(MakeRustlsConnect::new(config))
+}
+
+impl HistoryStore for PostgresStore {
+    fn record_run<'a>(&'a self, run: &'a RunRecord) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.insert_run(run))
+    }
+
+    fn latest_run<'a>(&'a self, workspace: &'a str) -> BoxFuture<'a, Result<Option<RunRecord>>> {
+        Box::pin(self.select_latest_run(workspace))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::Risk;
+
+    /// Connection string of a scratch database to test against
+    const DATABASE_URL_VAR: &str = "APRZ_TEST_POSTGRES_URL";
+
+    #[test]
+    fn test_normalize_ssl_mode() {
+        assert_eq!(
+            normalize_ssl_mode("postgres://db.example.com/aprz?sslmode=verify-full"),
+            "postgres://db.example.com/aprz?sslmode=require"
+        );
+        assert_eq!(normalize_ssl_mode("host=db sslmode=allow"), "host=db sslmode=prefer");
+        assert_eq!(normalize_ssl_mode("host=db sslmode=disable"), "host=db sslmode=disable");
+        assert_eq!(
+            normalize_ssl_mode("host=db sslmode=verify-ca").parse::<Config>().unwrap().get_ssl_mode(),
+            tokio_postgres::config::SslMode::Require
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call into aws-lc")]
+    fn test_tls_connector() {
+        let _ = tls_connector().unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a scratch Postgres database, whose connection string is given in APRZ_TEST_POSTGRES_URL"]
+    async fn test_record_and_read_latest_run() {
+        let url = std::env::var(DATABASE_URL_VAR).unwrap();
+        let store = PostgresStore::connect(&url).await.unwrap();
+        let workspace = format!("test-{}", Utc::now().timestamp_nanos_opt().unwrap_or_default());
+        let run = RunRecord {
+            workspace: workspace.clone(),
+            commit: None,
+            recorded_at: Utc::now(),
+            score: Some(75.0),
+            passed: false,
+            crates: vec![CrateRecord {
+                name: "serde".into(),
+                version: "1.0.0".into(),
+                score: Some(75.0),
+                risk: Some(Risk::Medium),
+                vulnerabilities: 0,
+            }],
+        };
+
+        store.record_run(&run).await.unwrap();
+        let latest = store.latest_run(&workspace).await.unwrap().unwrap();
+        assert_eq!(latest.crates, run.crates);
+        assert!(!latest.passed);
+    }
+}
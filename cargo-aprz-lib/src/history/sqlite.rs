@@ -0,0 +1,204 @@
+//! History store kept in a local `SQLite` database.
+
+use super::{CrateRecord, HistoryStore, RunRecord, parse_risk, risk_str};
+use crate::Result;
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures::future::BoxFuture;
+use ohno::IntoAppError;
+use rusqlite::{Connection, OptionalExtension, params};
+use std::sync::{Arc, Mutex};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS aprz_runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    workspace TEXT NOT NULL,
+    commit_sha TEXT,
+    recorded_at TEXT NOT NULL,
+    score REAL,
+    passed INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS aprz_crates (
+    run_id INTEGER NOT NULL REFERENCES aprz_runs (id),
+    name TEXT NOT NULL,
+    version TEXT NOT NULL,
+    score REAL,
+    risk TEXT,
+    vulnerabilities INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS aprz_runs_workspace ON aprz_runs (workspace, recorded_at);
+CREATE INDEX IF NOT EXISTS aprz_crates_run ON aprz_crates (run_id);
+";
+
+pub struct SqliteStore {
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl SqliteStore {
+    /// Open the database at `path`, creating it and its tables if needed.
+    pub async fn open(path: &str) -> Result<Self> {
+        let path = path.to_string();
+        let connection = tokio::task::spawn_blocking(move || {
+            let connection = Connection::open(&path).into_app_err_with(|| format!("opening history database '{path}'"))?;
+            connection
+                .execute_batch(SCHEMA)
+                .into_app_err_with(|| format!("creating history tables in '{path}'"))?;
+            Ok::<_, ohno::AppError>(connection)
+        })
+        .await??;
+
+        Ok(Self {
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+
+    /// Run a blocking database operation off the async runtime.
+    async fn with_connection<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection) -> Result<T> + Send + 'static,
+    {
+        let connection = Arc::clone(&self.connection);
+        tokio::task::spawn_blocking(move || {
+            let mut connection = connection.lock().expect("history database lock must not be poisoned");
+            f(&mut connection)
+        })
+        .await?
+    }
+}
+
+impl HistoryStore for SqliteStore {
+    fn record_run<'a>(&'a self, run: &'a RunRecord) -> BoxFuture<'a, Result<()>> {
+        let run = run.clone();
+        Box::pin(self.with_connection(move |connection| insert_run(connection, &run)))
+    }
+
+    fn latest_run<'a>(&'a self, workspace: &'a str) -> BoxFuture<'a, Result<Option<RunRecord>>> {
+        let workspace = workspace.to_string();
+        Box::pin(self.with_connection(move |connection| select_latest_run(connection, &workspace)))
+    }
+}
+
+fn insert_run(connection: &mut Connection, run: &RunRecord) -> Result<()> {
+    let tx = connection.transaction().into_app_err("starting history transaction")?;
+    let _ = tx
+        .execute(
+            "INSERT INTO aprz_runs (workspace, commit_sha, recorded_at, score, passed) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![run.workspace, run.commit, run.recorded_at.to_rfc3339_opts(SecondsFormat::Nanos, true), run.score, run.passed],
+        )
+        .into_app_err("recording run")?;
+    let run_id = tx.last_insert_rowid();
+
+    {
+        let mut insert = tx
+            .prepare(
+                "INSERT INTO aprz_crates (run_id, name, version, score, risk, vulnerabilities) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )
+            .into_app_err("preparing crate insertion")?;
+        for c in &run.crates {
+            let _ = insert
+                .execute(params![run_id, c.name, c.version, c.score, c.risk.map(risk_str), c.vulnerabilities])
+                .into_app_err_with(|| format!("recording crate '{}'", c.name))?;
+        }
+    }
+
+    tx.commit().into_app_err("committing history transaction")
+}
+
+fn select_latest_run(connection: &Connection, workspace: &str) -> Result<Option<RunRecord>> {
+    let run = connection
+        .query_row(
+            "SELECT id, commit_sha, recorded_at, score, passed FROM aprz_runs WHERE workspace = ?1 \
+             ORDER BY recorded_at DESC, id DESC LIMIT 1",
+            params![workspace],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, Option<f64>>(3)?,
+                    row.get::<_, bool>(4)?,
+                ))
+            },
+        )
+        .optional()
+        .into_app_err_with(|| format!("reading latest run of workspace '{workspace}'"))?;
+
+    let Some((run_id, commit, recorded_at, score, passed)) = run else {
+        return Ok(None);
+    };
+
+    let mut select = connection
+        .prepare("SELECT name, version, score, risk, vulnerabilities FROM aprz_crates WHERE run_id = ?1 ORDER BY name, version")
+        .into_app_err("preparing crate query")?;
+    let crates = select
+        .query_map(params![run_id], |row| {
+            Ok(CrateRecord {
+                name: row.get(0)?,
+                version: row.get(1)?,
+                score: row.get(2)?,
+                risk: row.get::<_, Option<String>>(3)?.as_deref().and_then(parse_risk),
+                vulnerabilities: row.get(4)?,
+            })
+        })
+        .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+        .into_app_err_with(|| format!("reading crates of the latest run of workspace '{workspace}'"))?;
+
+    Ok(Some(RunRecord {
+        workspace: workspace.to_string(),
+        commit,
+        recorded_at: recorded_at.parse::<DateTime<Utc>>().into_app_err_with(|| format!("parsing run time '{recorded_at}'"))?,
+        score,
+        passed,
+        crates,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::Risk;
+    use chrono::{Duration, Utc};
+
+    fn make_run(workspace: &str, score: f64) -> RunRecord {
+        RunRecord {
+            workspace: workspace.into(),
+            commit: Some("abc123".into()),
+            recorded_at: Utc::now(),
+            score: Some(score),
+            passed: true,
+            crates: vec![CrateRecord {
+                name: "serde".into(),
+                version: "1.0.0".into(),
+                score: Some(score),
+                risk: Some(Risk::Low),
+                vulnerabilities: 1,
+            }],
+        }
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTempPathW")]
+    async fn test_record_and_read_latest_run() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("history.db");
+        let store = SqliteStore::open(path.to_str().unwrap()).await.unwrap();
+
+        assert!(store.latest_run("payments").await.unwrap().is_none());
+
+        let mut older = make_run("payments", 60.0);
+        older.recorded_at -= Duration::days(1);
+        store.record_run(&older).await.unwrap();
+        let newer = make_run("payments", 80.0);
+        store.record_run(&newer).await.unwrap();
+        store.record_run(&make_run("search", 40.0)).await.unwrap();
+
+        let latest = store.latest_run("payments").await.unwrap().unwrap();
+        assert_eq!(latest.score, Some(80.0));
+        assert_eq!(latest.crates, newer.crates);
+        assert_eq!(latest.recorded_at, newer.recorded_at);
+
+        // Reopening keeps the existing history
+        let store = SqliteStore::open(path.to_str().unwrap()).await.unwrap();
+        assert_eq!(store.latest_run("search").await.unwrap().unwrap().score, Some(40.0));
+    }
+}
@@ -15,6 +15,7 @@
 // - [`facts`]: Data collection and aggregation
 // - [`metrics`]: Metric extraction from facts
 // - [`expr`]: Expression-based evaluation
+// - [`history`]: Persistent history of appraisal runs
 // - [`reports`]: Report generation in multiple formats
 
 pub type Result<T, E = ohno::AppError> = core::result::Result<T, E>;
@@ -40,6 +41,10 @@ macro_rules! declare_modules {
     };
 }
 
-declare_modules!(commands, expr, facts, metrics, reports);
+declare_modules!(commands, expr, facts, history, metrics, reports);
 
 pub use crate::commands::{Host, run};
+
+// Types needed to implement custom fact providers
+pub use crate::facts::{CrateFacts, CrateSpec, FactProvider, ProviderResult, register_provider};
+pub use crate::metrics::{MetricCategory, MetricDef, MetricValue};
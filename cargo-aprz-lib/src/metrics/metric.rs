@@ -1,7 +1,7 @@
 use super::MetricCategory;
 use super::MetricValue;
 use super::metric_def::{METRIC_DEFINITIONS, MetricDef};
-use crate::facts::CrateFacts;
+use crate::facts::{CrateFacts, registered_metric_defs};
 
 #[cfg(test)]
 use crate::facts::{CratesData, ProviderResult};
@@ -18,6 +18,8 @@ use crate::facts::crates::{CrateOverallData, CrateVersionData};
 use crate::facts::docs::DocsData;
 #[cfg(test)]
 use crate::facts::hosting::HostingData;
+#[cfg(test)]
+use crate::facts::package::PackageData;
 
 #[derive(Debug, Clone)]
 pub struct Metric {
@@ -53,11 +55,28 @@ impl Metric {
     }
 }
 
+/// Return an iterator over the built-in metric definitions followed by those of registered custom providers
+fn metric_defs() -> impl Iterator<Item = &'static MetricDef> {
+    let defs = METRIC_DEFINITIONS.iter().chain(registered_metric_defs());
+
+    #[cfg(feature = "wasm_plugins")]
+    let defs = defs.chain(super::wasm_plugin::plugin_metric_defs());
+
+    defs
+}
+
 /// Flatten `CrateFacts` into an iterator of metrics with descriptive names
 pub fn flatten(facts: &CrateFacts) -> impl Iterator<Item = Metric> + '_ {
-    METRIC_DEFINITIONS
-        .iter()
-        .map(|def| (def.extractor)(facts).map_or_else(|| Metric::new(def), |value| Metric::with_value(def, value)))
+    let metrics = metric_defs().map(|def| (def.extractor)(facts).map_or_else(|| Metric::new(def), |value| Metric::with_value(def, value)));
+
+    #[cfg(feature = "wasm_plugins")]
+    let metrics = {
+        let mut metrics: Vec<Metric> = metrics.collect();
+        super::wasm_plugin::apply_plugins(&mut metrics);
+        metrics.into_iter()
+    };
+
+    metrics
 }
 
 /// Return an iterator of all known metrics with default values
@@ -65,19 +84,40 @@ pub fn flatten(facts: &CrateFacts) -> impl Iterator<Item = Metric> + '_ {
 /// This is useful for validation and testing purposes where you need metrics
 /// with placeholder values to evaluate expressions against.
 pub fn default_metrics() -> impl Iterator<Item = Metric> {
-    METRIC_DEFINITIONS
-        .iter()
-        .map(|def| (def.default_value)().map_or_else(|| Metric::new(def), |value| Metric::with_value(def, value)))
+    metric_defs().map(|def| (def.default_value)().map_or_else(|| Metric::new(def), |value| Metric::with_value(def, value)))
+}
+
+/// Rebuild the metrics of a crate from the values recorded in a JSON report, keyed by metric name
+///
+/// The default value of each metric tells how to read its JSON value. Metrics missing from the report have no value.
+pub fn metrics_from_json(values: &serde_json::Map<String, serde_json::Value>) -> impl Iterator<Item = Metric> + '_ {
+    metric_defs().map(|def| Metric {
+        def,
+        value: values.get(def.name).and_then(|value| MetricValue::from_json(value, (def.default_value)().as_ref())),
+    })
+}
+
+/// Return an iterator over the names of all known metrics
+pub fn metric_names() -> impl Iterator<Item = &'static str> {
+    metric_defs().map(|def| def.name)
+}
+
+/// Look up the category of the metric with the given name
+#[must_use]
+pub fn metric_category(name: &str) -> Option<MetricCategory> {
+    metric_defs().find(|def| def.name == name).map(|def| def.category)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::facts::CrateSpec;
     use crate::facts::advisories::AdvisoryCounts;
     use crate::facts::docs::DocsMetrics;
+    use crate::facts::crates::{Owner, OwnerKind};
     use crate::facts::hosting::{AgeStats, TimeWindowStats};
+    use crate::facts::{CrateSpec, RepoSpec};
     use chrono::Utc;
+    use compact_str::CompactString;
     use semver::Version;
     use std::collections::BTreeMap;
     use std::sync::Arc;
@@ -88,7 +128,11 @@ mod tests {
         let now = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
 
         CrateFacts {
-            crate_spec: CrateSpec::from_arcs(Arc::from("test-crate"), Arc::new(Version::parse("1.0.0").unwrap())),
+            crate_spec: CrateSpec::from_arcs_with_repo(
+                Arc::from("test-crate"),
+                Arc::new(Version::parse("1.0.0").unwrap()),
+                RepoSpec::parse(&"https://github.com/test-org/test-crate".parse().unwrap()).unwrap(),
+            ),
             crates_data: ProviderResult::Found(CratesData::new(
                 CrateVersionData {
                     description: "Test crate".into(),
@@ -101,6 +145,8 @@ mod tests {
                     created_at: now,
                     updated_at: now,
                     yanked: false,
+                    yanked_dependencies: vec![],
+                    previous_version: None,
                     downloads: 1000,
                     monthly_downloads: vec![],
                 },
@@ -110,13 +156,24 @@ mod tests {
                     repository: None,
                     categories: vec![],
                     keywords: vec!["test".into()],
-                    owners: vec![],
-                    monthly_downloads: vec![],
+                    owners: vec![Owner {
+                        login: "test-user".into(),
+                        kind: OwnerKind::User,
+                        name: None,
+                    }],
+                    monthly_downloads: (1..=9)
+                        .map(|month| (chrono::NaiveDate::from_ymd_opt(2024, month, 1).unwrap(), 100 * u64::from(month)))
+                        .collect(),
                     downloads: 5000,
+                    downloads_percentile: 75.0,
+                    age_percentile: 10.0,
                     dependents: 10,
+                    dependents_percentile: 90.0,
+                    dependents_trend: Some(0.5),
                     versions_last_90_days: 0,
                     versions_last_180_days: 0,
                     versions_last_365_days: 0,
+                    dep_update_latency_days: Some(30),
                 },
             )),
             hosting_data: ProviderResult::Found(HostingData {
@@ -152,6 +209,22 @@ mod tests {
                 merged_pr_age_last_90_days: AgeStats::default(),
                 merged_pr_age_last_180_days: AgeStats::default(),
                 merged_pr_age_last_365_days: AgeStats::default(),
+                releases: TimeWindowStats::default(),
+                prereleases: TimeWindowStats::default(),
+                latest_release_has_notes: true,
+                latest_release_signed: false,
+                provenance_level: 0,
+                discussions_enabled: false,
+                discussions_last_365_days: None,
+                question_issues_last_365_days: 0,
+                branch_protected: false,
+                required_approving_reviews: 0,
+                required_status_checks: false,
+                verified_org: false,
+                repo_licenses: Vec::new(),
+                unreviewed_merge_ratio: Some(0.1),
+                verified_commit_ratio: Some(0.5),
+                fetched_at: None,
             }),
             advisory_data: ProviderResult::Found(AdvisoryData {
                 per_version: AdvisoryCounts::default(),
@@ -165,6 +238,8 @@ mod tests {
                     unsound_warning_count: 0,
                     yanked_warning_count: 0,
                 },
+                oldest_unadopted_fix_days: 0,
+                unadopted_fix_penalty: 0.0,
             }),
             codebase_data: ProviderResult::Found(CodebaseData {
                 source_files_analyzed: 10,
@@ -174,15 +249,27 @@ mod tests {
                 comment_lines: 200,
                 unsafe_count: 2,
                 example_count: 3,
+                doc_test_count: 5,
                 transitive_dependencies: 25,
                 workflows_detected: true,
                 miri_detected: false,
                 clippy_detected: true,
+                ci_platform_count: 2,
+                ci_linux: true,
+                ci_windows: true,
+                ci_macos: false,
+                ci_arm: false,
+                ci_wasm: false,
+                has_contributing: true,
+                has_code_of_conduct: false,
+                has_governance: false,
                 contributors: 5,
                 commits_last_90_days: 50,
                 commits_last_180_days: 100,
                 commits_last_365_days: 200,
                 commit_count: 1000,
+                signed_commits_ratio: Some(0.4),
+                latest_tag_signed: None,
                 first_commit_at: now,
                 last_commit_at: now,
             }),
@@ -198,10 +285,31 @@ mod tests {
                     has_crate_level_docs: true,
                     broken_doc_links: 1,
                 },
+                public_api: vec![],
+                api_churn: Some(3),
             }),
+            package_data: ProviderResult::Found(PackageData {
+                file_count: 12,
+                binary_files: vec![],
+                vcs_commit: Some("0123456789abcdef0123456789abcdef01234567".into()),
+                path_in_vcs: CompactString::default(),
+                build_time_network_risk: 0,
+                license_files: BTreeMap::new(),
+                file_hashes: BTreeMap::new(),
+                repo_differences: Some(0),
+            }),
+            custom_data: crate::HashMap::default(),
+            timestamps: crate::facts::DataTimestamps::default(),
         }
     }
 
+    #[test]
+    fn test_metric_category() {
+        assert_eq!(metric_category("activity.open_issues"), Some(MetricCategory::Activity));
+        assert_eq!(metric_category("crate.name"), Some(MetricCategory::Metadata));
+        assert_eq!(metric_category("no.such_metric"), None);
+    }
+
     #[test]
     fn test_flatten_returns_metrics() {
         let facts = create_test_crate_facts();
@@ -1,11 +1,16 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
 use strum::{Display, EnumIter};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Display)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, EnumIter, Display, Deserialize, Serialize, ValueEnum)]
 pub enum MetricCategory {
     Metadata,
     Stability,
     Usage,
     Community,
+    // Activity is what shows whether a crate is still maintained, so it goes by either name
+    #[serde(alias = "Maintenance")]
+    #[value(alias = "maintenance")]
     Activity,
     Documentation,
     Trustworthiness,
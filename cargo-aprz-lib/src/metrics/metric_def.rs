@@ -1,7 +1,9 @@
 use super::{MetricCategory, MetricValue};
 use crate::facts::CrateFacts;
+use crate::facts::crates::owner_repo_mismatch;
+use crate::facts::package::license_mismatch;
 use chrono::DateTime;
-use compact_str::format_compact;
+use compact_str::{CompactString, format_compact};
 
 #[derive(Debug)]
 pub struct MetricDef {
@@ -28,6 +30,20 @@ fn calculate_recent_downloads(monthly_downloads: &[(chrono::NaiveDate, u64)]) ->
     monthly_downloads.iter().rev().take(3).map(|(_, count)| count).sum()
 }
 
+/// Percentage change in monthly downloads between the oldest and newest complete months of the last six months.
+///
+/// The first and last months of the series are left out, since they are usually only partly covered.
+#[expect(clippy::cast_precision_loss, reason = "Monthly download counts are far below the precision limit")]
+fn calculate_downloads_trend(monthly_downloads: &[(chrono::NaiveDate, u64)]) -> Option<f64> {
+    let complete = monthly_downloads.get(1..monthly_downloads.len().saturating_sub(1))?;
+    let window = &complete[complete.len().saturating_sub(7)..];
+    let [(_, first), .., (_, last)] = window else {
+        return None;
+    };
+
+    (*first > 0).then(|| (*last as f64 - *first as f64) * 100.0 / *first as f64)
+}
+
 pub const METRIC_DEFINITIONS: &[MetricDef] = &[
     metric_def!(
         "crate.name",
@@ -62,7 +78,7 @@ pub const METRIC_DEFINITIONS: &[MetricDef] = &[
         |facts| facts
             .crates_data
             .as_ref()
-            .map(|data| MetricValue::String(data.version_data.license.clone())),
+            .map(|data| MetricValue::String((*data.version_data.license).into())),
         || Some(MetricValue::String("".into()))
     ),
     metric_def!(
@@ -235,6 +251,23 @@ pub const METRIC_DEFINITIONS: &[MetricDef] = &[
         |facts| facts.codebase_data.as_ref().map(|data| MetricValue::UInt(data.example_count)),
         || Some(MetricValue::UInt(0))
     ),
+    metric_def!(
+        "docs.example_count",
+        "Number of example programs and doc tests in the source code",
+        Documentation,
+        |facts| facts
+            .codebase_data
+            .as_ref()
+            .map(|data| MetricValue::UInt(data.example_count + data.doc_test_count)),
+        || Some(MetricValue::UInt(0))
+    ),
+    metric_def!(
+        "docs.release_notes",
+        "Whether the latest repository release includes release notes",
+        Documentation,
+        |facts| facts.hosting_data.as_ref().map(|data| MetricValue::Boolean(data.latest_release_has_notes)),
+        || Some(MetricValue::Boolean(false))
+    ),
     metric_def!(
         "usage.total_downloads",
         "Crate downloads across all versions",
@@ -255,6 +288,27 @@ pub const METRIC_DEFINITIONS: &[MetricDef] = &[
             .map(|data| MetricValue::UInt(calculate_recent_downloads(&data.overall_data.monthly_downloads))),
         || Some(MetricValue::UInt(0))
     ),
+    metric_def!(
+        "usage.downloads_trend_6mo",
+        "Percentage change in monthly downloads over the last six months",
+        Usage,
+        |facts| facts
+            .crates_data
+            .as_ref()
+            .and_then(|data| calculate_downloads_trend(&data.overall_data.monthly_downloads))
+            .map(MetricValue::Float),
+        || Some(MetricValue::Float(0.0))
+    ),
+    metric_def!(
+        "usage.downloads_percentile",
+        "Percentage of crates.io crates with fewer downloads",
+        Usage,
+        |facts| facts
+            .crates_data
+            .as_ref()
+            .map(|data| MetricValue::Float(data.overall_data.downloads_percentile)),
+        || Some(MetricValue::Float(0.0))
+    ),
     metric_def!(
         "usage.version_downloads",
         "Crate downloads of this specific version",
@@ -285,6 +339,27 @@ pub const METRIC_DEFINITIONS: &[MetricDef] = &[
             .map(|data| MetricValue::UInt(data.overall_data.dependents)),
         || Some(MetricValue::UInt(0))
     ),
+    metric_def!(
+        "usage.dependents_percentile",
+        "Percentage of crates.io crates with fewer dependents",
+        Usage,
+        |facts| facts
+            .crates_data
+            .as_ref()
+            .map(|data| MetricValue::Float(data.overall_data.dependents_percentile)),
+        || Some(MetricValue::Float(0.0))
+    ),
+    metric_def!(
+        "usage.dependents_trend",
+        "Percentage change in the number of dependent crates over the last six months",
+        Usage,
+        |facts| facts
+            .crates_data
+            .as_ref()
+            .and_then(|data| data.overall_data.dependents_trend)
+            .map(MetricValue::Float),
+        || Some(MetricValue::Float(0.0))
+    ),
     metric_def!(
         "stability.crate_created_at",
         "When the crate was first published to crates.io",
@@ -297,6 +372,16 @@ pub const METRIC_DEFINITIONS: &[MetricDef] = &[
             DateTime::from_timestamp(0, 0).expect("epoch timestamp is always valid")
         ))
     ),
+    metric_def!(
+        "stability.age_percentile",
+        "Percentage of crates.io crates published after this one",
+        Stability,
+        |facts| facts
+            .crates_data
+            .as_ref()
+            .map(|data| MetricValue::Float(data.overall_data.age_percentile)),
+        || Some(MetricValue::Float(0.0))
+    ),
     metric_def!(
         "stability.crate_updated_at",
         "When the crate's metadata was last updated on crates.io",
@@ -343,6 +428,16 @@ pub const METRIC_DEFINITIONS: &[MetricDef] = &[
             .map(|data| MetricValue::Boolean(data.version_data.yanked)),
         || Some(MetricValue::Boolean(false))
     ),
+    metric_def!(
+        "stability.depends_on_yanked",
+        "Whether a dependency only resolves to yanked releases",
+        Stability,
+        |facts| facts
+            .crates_data
+            .as_ref()
+            .map(|data| MetricValue::Boolean(!data.version_data.yanked_dependencies.is_empty())),
+        || Some(MetricValue::Boolean(false))
+    ),
     metric_def!(
         "stability.versions_last_90_days",
         "Number of versions published in the last 90 days",
@@ -373,6 +468,17 @@ pub const METRIC_DEFINITIONS: &[MetricDef] = &[
             .map(|data| MetricValue::UInt(data.overall_data.versions_last_365_days)),
         || Some(MetricValue::UInt(0))
     ),
+    metric_def!(
+        "stability.api_churn",
+        "Public items added or removed since the previous release",
+        Stability,
+        |facts| facts
+            .docs_data
+            .as_ref()
+            .and_then(|data| data.api_churn)
+            .map(MetricValue::UInt),
+        || Some(MetricValue::UInt(0))
+    ),
     metric_def!(
         "crate.owners",
         "List of owner usernames",
@@ -381,7 +487,7 @@ pub const METRIC_DEFINITIONS: &[MetricDef] = &[
             data.overall_data
                 .owners
                 .iter()
-                .map(|o| MetricValue::String(o.login.clone()))
+                .map(|o| MetricValue::String((*o.login).into()))
                 .collect()
         )),
         || Some(MetricValue::List(Vec::new()))
@@ -414,6 +520,50 @@ pub const METRIC_DEFINITIONS: &[MetricDef] = &[
         |facts| facts.codebase_data.as_ref().map(|data| MetricValue::UInt(data.contributors)),
         || Some(MetricValue::UInt(0))
     ),
+    metric_def!(
+        "community.discussions_enabled",
+        "Whether GitHub Discussions are enabled for the repository",
+        Community,
+        |facts| facts.hosting_data.as_ref().map(|data| MetricValue::Boolean(data.discussions_enabled)),
+        || Some(MetricValue::Boolean(false))
+    ),
+    metric_def!(
+        "community.discussion_activity",
+        "Discussions, or question-labelled issues, opened in the last 365 days",
+        Community,
+        |facts| facts.hosting_data.as_ref().map(|data| {
+            MetricValue::UInt(data.discussions_last_365_days.unwrap_or(data.question_issues_last_365_days))
+        }),
+        || Some(MetricValue::UInt(0))
+    ),
+    metric_def!(
+        "community.has_contributing",
+        "Whether the repository has contribution guidelines",
+        Community,
+        |facts| facts.codebase_data.as_ref().map(|data| MetricValue::Boolean(data.has_contributing)),
+        || Some(MetricValue::Boolean(false))
+    ),
+    metric_def!(
+        "community.has_code_of_conduct",
+        "Whether the repository has a code of conduct",
+        Community,
+        |facts| facts.codebase_data.as_ref().map(|data| MetricValue::Boolean(data.has_code_of_conduct)),
+        || Some(MetricValue::Boolean(false))
+    ),
+    metric_def!(
+        "community.has_governance",
+        "Whether the repository documents its governance model",
+        Community,
+        |facts| facts.codebase_data.as_ref().map(|data| MetricValue::Boolean(data.has_governance)),
+        || Some(MetricValue::Boolean(false))
+    ),
+    metric_def!(
+        "community.verified_org",
+        "Whether the owning GitHub organization is verified or requires 2FA",
+        Community,
+        |facts| facts.hosting_data.as_ref().map(|data| MetricValue::Boolean(data.verified_org)),
+        || Some(MetricValue::Boolean(false))
+    ),
     metric_def!(
         "activity.commits_last_90_days",
         "Number of commits to the repository in the last 90 days",
@@ -974,6 +1124,31 @@ pub const METRIC_DEFINITIONS: &[MetricDef] = &[
         |facts| facts.hosting_data.as_ref().map(|data| MetricValue::UInt(u64::from(data.merged_pr_age_last_365_days.p95))),
         || Some(MetricValue::UInt(0))
     ),
+    metric_def!(
+        "activity.github_releases_last_365_days",
+        "Number of repository releases, excluding prereleases, in the last year",
+        Activity,
+        |facts| facts.hosting_data.as_ref().map(|data| MetricValue::UInt(data.releases.last_365_days)),
+        || Some(MetricValue::UInt(0))
+    ),
+    metric_def!(
+        "activity.github_prereleases_last_365_days",
+        "Number of repository prereleases in the last 365 days",
+        Activity,
+        |facts| facts.hosting_data.as_ref().map(|data| MetricValue::UInt(data.prereleases.last_365_days)),
+        || Some(MetricValue::UInt(0))
+    ),
+    metric_def!(
+        "activity.dep_update_latency_days",
+        "Median days taken to allow new releases of dependencies",
+        Activity,
+        |facts| facts
+            .crates_data
+            .as_ref()
+            .and_then(|data| data.overall_data.dep_update_latency_days)
+            .map(MetricValue::UInt),
+        || Some(MetricValue::UInt(0))
+    ),
     metric_def!(
         "advisories.total_low_severity_vulnerabilities",
         "Number of low severity vulnerabilities across all versions",
@@ -1114,6 +1289,26 @@ pub const METRIC_DEFINITIONS: &[MetricDef] = &[
             .map(|data| MetricValue::UInt(data.per_version.unsound_warning_count)),
         || Some(MetricValue::UInt(0))
     ),
+    metric_def!(
+        "advisories.version_oldest_unadopted_fix_days",
+        "Days since a fix was published for the oldest vulnerability affecting this version",
+        Advisories,
+        |facts| facts
+            .advisory_data
+            .as_ref()
+            .map(|data| MetricValue::UInt(data.oldest_unadopted_fix_days)),
+        || Some(MetricValue::UInt(0))
+    ),
+    metric_def!(
+        "advisories.version_unadopted_fix_penalty",
+        "Severity-weighted penalty for this version's fixable vulnerabilities, growing with each year the fix goes unadopted",
+        Advisories,
+        |facts| facts
+            .advisory_data
+            .as_ref()
+            .map(|data| MetricValue::Float(data.unadopted_fix_penalty)),
+        || Some(MetricValue::Float(0.0))
+    ),
     metric_def!(
         "code.source_files",
         "Number of source files",
@@ -1196,6 +1391,206 @@ pub const METRIC_DEFINITIONS: &[MetricDef] = &[
         |facts| facts.codebase_data.as_ref().map(|data| MetricValue::Boolean(data.clippy_detected)),
         || Some(MetricValue::Boolean(false))
     ),
+    metric_def!(
+        "trust.ci_platform_count",
+        "Number of platforms among Linux, Windows, macOS, ARM, and Wasm tested in CI",
+        Trustworthiness,
+        |facts| facts.codebase_data.as_ref().map(|data| MetricValue::UInt(data.ci_platform_count)),
+        || Some(MetricValue::UInt(0))
+    ),
+    metric_def!(
+        "trust.ci_linux",
+        "Whether CI runs on or targets Linux",
+        Trustworthiness,
+        |facts| facts.codebase_data.as_ref().map(|data| MetricValue::Boolean(data.ci_linux)),
+        || Some(MetricValue::Boolean(false))
+    ),
+    metric_def!(
+        "trust.ci_windows",
+        "Whether CI runs on or targets Windows",
+        Trustworthiness,
+        |facts| facts.codebase_data.as_ref().map(|data| MetricValue::Boolean(data.ci_windows)),
+        || Some(MetricValue::Boolean(false))
+    ),
+    metric_def!(
+        "trust.ci_macos",
+        "Whether CI runs on or targets macOS",
+        Trustworthiness,
+        |facts| facts.codebase_data.as_ref().map(|data| MetricValue::Boolean(data.ci_macos)),
+        || Some(MetricValue::Boolean(false))
+    ),
+    metric_def!(
+        "trust.ci_arm",
+        "Whether CI runs on or targets ARM",
+        Trustworthiness,
+        |facts| facts.codebase_data.as_ref().map(|data| MetricValue::Boolean(data.ci_arm)),
+        || Some(MetricValue::Boolean(false))
+    ),
+    metric_def!(
+        "trust.ci_wasm",
+        "Whether CI runs on or targets WebAssembly",
+        Trustworthiness,
+        |facts| facts.codebase_data.as_ref().map(|data| MetricValue::Boolean(data.ci_wasm)),
+        || Some(MetricValue::Boolean(false))
+    ),
+    metric_def!(
+        "trust.branch_protection",
+        "Whether the default branch of the repository is protected",
+        Trustworthiness,
+        |facts| facts.hosting_data.as_ref().map(|data| MetricValue::Boolean(data.branch_protected)),
+        || Some(MetricValue::Boolean(false))
+    ),
+    metric_def!(
+        "trust.required_reviews",
+        "Number of approving reviews required to merge into the default branch",
+        Trustworthiness,
+        |facts| facts
+            .hosting_data
+            .as_ref()
+            .map(|data| MetricValue::UInt(data.required_approving_reviews)),
+        || Some(MetricValue::UInt(0))
+    ),
+    metric_def!(
+        "trust.required_status_checks",
+        "Whether status checks must pass before merging into the default branch",
+        Trustworthiness,
+        |facts| facts.hosting_data.as_ref().map(|data| MetricValue::Boolean(data.required_status_checks)),
+        || Some(MetricValue::Boolean(false))
+    ),
+    metric_def!(
+        "trust.unreviewed_merge_ratio",
+        "Fraction of the last 50 merged pull requests without an approving review",
+        Trustworthiness,
+        |facts| facts
+            .hosting_data
+            .as_ref()
+            .and_then(|data| data.unreviewed_merge_ratio)
+            .map(MetricValue::Float),
+        || Some(MetricValue::Float(0.0))
+    ),
+    metric_def!(
+        "trust.signed_commits_ratio",
+        "Fraction of the last 100 commits that are signed",
+        Trustworthiness,
+        |facts| facts
+            .codebase_data
+            .as_ref()
+            .and_then(|data| data.signed_commits_ratio)
+            .map(MetricValue::Float),
+        || Some(MetricValue::Float(0.0))
+    ),
+    metric_def!(
+        "trust.verified_commit_ratio",
+        "Fraction of the commits of the last 90 days with a verified signature",
+        Trustworthiness,
+        |facts| facts
+            .hosting_data
+            .as_ref()
+            .and_then(|data| data.verified_commit_ratio)
+            .map(MetricValue::Float),
+        || Some(MetricValue::Float(0.0))
+    ),
+    metric_def!(
+        "trust.signed_releases",
+        "Whether the latest release ships signature assets or a signed tag",
+        Trustworthiness,
+        |facts| facts
+            .hosting_data
+            .as_ref()
+            .map(|data| data.latest_release_signed)
+            .into_iter()
+            .chain(facts.codebase_data.as_ref().and_then(|data| data.latest_tag_signed))
+            .reduce(|assets_signed, tag_signed| assets_signed || tag_signed)
+            .map(MetricValue::Boolean),
+        || Some(MetricValue::Boolean(false))
+    ),
+    metric_def!(
+        "trust.provenance_level",
+        "Unverified SLSA build level attested for the latest release, 0 or 1",
+        Trustworthiness,
+        |facts| facts.hosting_data.as_ref().map(|data| MetricValue::UInt(data.provenance_level)),
+        || Some(MetricValue::UInt(0))
+    ),
+    metric_def!(
+        "trust.contains_binaries",
+        "Whether the published package contains compiled code or binary blobs",
+        Trustworthiness,
+        |facts| facts
+            .package_data
+            .as_ref()
+            .map(|data| MetricValue::Boolean(!data.binary_files.is_empty())),
+        || Some(MetricValue::Boolean(false))
+    ),
+    metric_def!(
+        "trust.tarball_matches_repo",
+        "Whether the published package matches its repository commit",
+        Trustworthiness,
+        |facts| facts
+            .package_data
+            .as_ref()
+            .and_then(|data| data.repo_differences)
+            .map(|count| MetricValue::Boolean(count == 0)),
+        || Some(MetricValue::Boolean(false))
+    ),
+    metric_def!(
+        "trust.tarball_differing_files",
+        "Number of published package files not found in the repository",
+        Trustworthiness,
+        |facts| facts
+            .package_data
+            .as_ref()
+            .and_then(|data| data.repo_differences)
+            .map(MetricValue::UInt),
+        || Some(MetricValue::UInt(0))
+    ),
+    metric_def!(
+        "trust.build_time_network_risk",
+        "Risk of build scripts or procedural macros reaching the network, from 0 to 3",
+        Trustworthiness,
+        |facts| facts
+            .package_data
+            .as_ref()
+            .map(|data| MetricValue::UInt(data.build_time_network_risk)),
+        || Some(MetricValue::UInt(0))
+    ),
+    metric_def!(
+        "trust.license_file_mismatch",
+        "Whether shipped license texts differ from the declared license",
+        Trustworthiness,
+        |facts| {
+            let package = facts.package_data.as_ref()?;
+            let crates = facts.crates_data.as_ref()?;
+            Some(MetricValue::Boolean(license_mismatch(
+                &crates.version_data.license,
+                package.license_files.values().map(CompactString::as_str),
+            )))
+        },
+        || Some(MetricValue::Boolean(false))
+    ),
+    metric_def!(
+        "trust.repo_license_mismatch",
+        "Whether the repository's detected license differs from the declared license",
+        Trustworthiness,
+        |facts| {
+            let hosting = facts.hosting_data.as_ref()?;
+            let crates = facts.crates_data.as_ref()?;
+            Some(MetricValue::Boolean(license_mismatch(
+                &crates.version_data.license,
+                hosting.repo_licenses.iter().map(CompactString::as_str),
+            )))
+        },
+        || Some(MetricValue::Boolean(false))
+    ),
+    metric_def!(
+        "trust.owner_repo_mismatch",
+        "Whether no crate owner belongs to the GitHub account hosting its repository",
+        Trustworthiness,
+        |facts| {
+            let crates = facts.crates_data.as_ref()?;
+            owner_repo_mismatch(&crates.overall_data.owners, facts.crate_spec.repo_spec()?).map(MetricValue::Boolean)
+        },
+        || Some(MetricValue::Boolean(false))
+    ),
     metric_def!(
         "trust.code_coverage_percentage",
         "Percentage of code covered by tests",
@@ -1238,4 +1633,25 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_calculate_downloads_trend() {
+        let series = |counts: &[u64]| -> Vec<(chrono::NaiveDate, u64)> {
+            counts
+                .iter()
+                .enumerate()
+                .map(|(i, &count)| (chrono::NaiveDate::from_ymd_opt(2025, u32::try_from(i).unwrap() + 1, 1).unwrap(), count))
+                .collect()
+        };
+
+        // The partial first and last months are ignored
+        assert_eq!(calculate_downloads_trend(&series(&[5, 1000, 800, 500, 7])), Some(-50.0));
+
+        // Only the last six months count
+        assert_eq!(calculate_downloads_trend(&series(&[5, 1, 100, 100, 100, 100, 100, 100, 150, 7])), Some(50.0));
+
+        assert_eq!(calculate_downloads_trend(&series(&[5, 1000, 7])), None);
+        assert_eq!(calculate_downloads_trend(&series(&[5, 0, 100, 7])), None);
+        assert_eq!(calculate_downloads_trend(&[]), None);
+    }
 }
@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use compact_str::CompactString;
 
 #[derive(Debug, Clone)]
@@ -10,3 +10,64 @@ pub enum MetricValue {
     DateTime(DateTime<Utc>),
     List(Vec<Self>),
 }
+
+impl MetricValue {
+    /// Convert the value to JSON, rendering timestamps in RFC 3339 format
+    #[must_use]
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            Self::UInt(u) => (*u).into(),
+            Self::Float(f) => (*f).into(),
+            Self::Boolean(b) => (*b).into(),
+            Self::String(s) => s.as_str().into(),
+            Self::DateTime(dt) => dt.to_rfc3339().into(),
+            Self::List(values) => values.iter().map(Self::to_json).collect(),
+        }
+    }
+
+    /// Convert JSON written by [`Self::to_json`] or by a report back into a value, using `like`, another value of
+    /// the same metric, to tell timestamps apart from strings and floats apart from integers
+    #[must_use]
+    pub fn from_json(value: &serde_json::Value, like: Option<&Self>) -> Option<Self> {
+        match value {
+            serde_json::Value::Bool(b) => Some(Self::Boolean(*b)),
+            serde_json::Value::Number(n) => match (like, n.as_u64()) {
+                (Some(Self::Float(_)), _) | (_, None) => n.as_f64().map(Self::Float),
+                (_, Some(u)) => Some(Self::UInt(u)),
+            },
+            serde_json::Value::String(s) if matches!(like, Some(Self::DateTime(_))) => DateTime::parse_from_rfc3339(s)
+                .map(|dt| dt.with_timezone(&Utc))
+                .ok()
+                .or_else(|| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0).map(|dt| dt.and_utc()))
+                .map(Self::DateTime),
+            serde_json::Value::String(s) => Some(Self::String(s.as_str().into())),
+            serde_json::Value::Array(values) => {
+                let like = match like {
+                    Some(Self::List(items)) => items.first(),
+                    _ => None,
+                };
+                values.iter().map(|v| Self::from_json(v, like)).collect::<Option<Vec<_>>>().map(Self::List)
+            }
+            serde_json::Value::Null | serde_json::Value::Object(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_json() {
+        let date = MetricValue::DateTime(DateTime::UNIX_EPOCH);
+        let parsed = MetricValue::from_json(&serde_json::json!("2024-03-01"), Some(&date));
+        assert!(matches!(parsed, Some(MetricValue::DateTime(dt)) if dt.to_rfc3339() == "2024-03-01T00:00:00+00:00"));
+        assert!(matches!(MetricValue::from_json(&serde_json::json!("2024-03-01"), None), Some(MetricValue::String(_))));
+
+        assert!(matches!(MetricValue::from_json(&serde_json::json!(3), Some(&MetricValue::Float(0.0))), Some(MetricValue::Float(_))));
+        assert!(matches!(MetricValue::from_json(&serde_json::json!(3), None), Some(MetricValue::UInt(3))));
+        assert!(matches!(MetricValue::from_json(&serde_json::json!(-1), None), Some(MetricValue::Float(_))));
+        assert!(matches!(MetricValue::from_json(&serde_json::json!(["a", "b"]), None), Some(MetricValue::List(v)) if v.len() == 2));
+        assert!(MetricValue::from_json(&serde_json::Value::Null, None).is_none());
+    }
+}
@@ -26,10 +26,12 @@ mod metric;
 mod metric_category;
 mod metric_def;
 mod metric_value;
+#[cfg(feature = "wasm_plugins")]
+mod wasm_plugin;
 
-pub use metric::{Metric, default_metrics, flatten};
+pub use metric::{Metric, default_metrics, flatten, metric_category, metric_names, metrics_from_json};
 pub use metric_category::MetricCategory;
-pub use metric_value::MetricValue;
-
-#[cfg(any(test, debug_assertions))]
 pub use metric_def::MetricDef;
+pub use metric_value::MetricValue;
+#[cfg(feature = "wasm_plugins")]
+pub use wasm_plugin::load_plugin;
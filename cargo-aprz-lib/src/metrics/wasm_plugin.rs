@@ -0,0 +1,407 @@
+//! Metrics computed by sandboxed WebAssembly plugins.
+//!
+//! A plugin is a WebAssembly module without imports, run with wasmtime, which exports:
+//!
+//! - `memory`: its linear memory
+//! - `alloc(len: u32) -> u32`: reserve `len` bytes and return their address
+//! - `describe() -> u64`: return the metrics the plugin computes
+//! - `evaluate(ptr: u32, len: u32) -> u64`: compute the metrics of a crate
+//!
+//! Data is exchanged as UTF-8 JSON. Functions returning `u64` pack the address of their output in the
+//! upper 32 bits and its length in the lower 32 bits. `describe` returns an array of objects with `name`,
+//! `description`, `category`, and `type` fields, where the type is one of `uint`, `float`, `bool`, or
+//! `string`. `evaluate` receives an object mapping the name of each metric of a crate to its value, including
+//! those of previously loaded plugins, and returns an object mapping the names of the plugin's metrics to their values. Boolean metrics are how
+//! plugins express policy outcomes, which expressions can then refer to.
+//!
+//! Plugins have no access to the host beyond what they're given, and each call runs with a fuel budget and
+//! a memory cap so a misbehaving plugin can't stall an appraisal or exhaust the host's memory.
+
+use super::{Metric, MetricCategory, MetricDef, MetricValue};
+use crate::Result;
+use camino::Utf8Path;
+use compact_str::CompactString;
+use ohno::{AppError, IntoAppError, app_err};
+use serde::Deserialize;
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard};
+use wasmtime::{Engine, Instance, Memory, Module, Store, StoreLimits, StoreLimitsBuilder, TypedFunc};
+
+const LOG_TARGET: &str = "    plugin";
+
+/// Amount of fuel given to each call into a plugin, roughly the number of instructions it may execute
+const FUEL_PER_CALL: u64 = 1_000_000_000;
+
+/// Largest linear memory a plugin may grow to, in bytes
+const MAX_MEMORY_BYTES: usize = 256 * 1024 * 1024;
+
+/// Type of the values of a plugin metric
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum MetricKind {
+    UInt,
+    Float,
+    Bool,
+    String,
+}
+
+/// A metric as declared by a plugin's `describe` function
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MetricDecl {
+    name: String,
+    description: String,
+    category: MetricCategory,
+    #[serde(rename = "type")]
+    kind: MetricKind,
+}
+
+/// A loaded plugin along with the definitions of the metrics it computes
+struct WasmPlugin {
+    path: String,
+    engine: Engine,
+    module: Module,
+    metrics: Vec<(&'static MetricDef, MetricKind)>,
+}
+
+/// The functions exported by an instantiated plugin
+struct PluginInstance {
+    store: Store<StoreLimits>,
+    memory: Memory,
+    alloc: TypedFunc<u32, u32>,
+}
+
+static PLUGINS: RwLock<Vec<Arc<WasmPlugin>>> = RwLock::new(Vec::new());
+
+/// The loaded plugins. Plugins are only ever appended whole, so a poisoned registry is still consistent.
+fn registry() -> RwLockReadGuard<'static, Vec<Arc<WasmPlugin>>> {
+    PLUGINS.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Load a plugin and register the metrics it computes.
+///
+/// # Errors
+///
+/// Returns an error if the module can't be compiled or instantiated, lacks one of the required exports,
+/// or declares invalid metrics.
+pub fn load_plugin(path: &Utf8Path) -> Result<()> {
+    // The configuration may be loaded more than once in a run
+    if registry().iter().any(|plugin| plugin.path == path.as_str()) {
+        return Ok(());
+    }
+
+    let mut config = wasmtime::Config::new();
+    let _ = config.consume_fuel(true);
+    let engine = Engine::new(&config)
+        .map_err(AppError::new)
+        .into_app_err("creating WebAssembly engine")?;
+    let module = Module::from_file(&engine, path)
+        .map_err(AppError::new)
+        .into_app_err_with(|| format!("compiling WebAssembly plugin '{path}'"))?;
+
+    let mut plugin = WasmPlugin {
+        path: path.to_string(),
+        engine,
+        module,
+        metrics: Vec::new(),
+    };
+
+    let (mut instance, wasm_instance) = plugin.instantiate()?;
+    let describe = wasm_instance
+        .get_typed_func::<(), u64>(&mut instance.store, "describe")
+        .map_err(AppError::new)
+        .into_app_err_with(|| format!("finding 'describe' in WebAssembly plugin '{path}'"))?;
+    let packed = describe
+        .call(&mut instance.store, ())
+        .map_err(AppError::new)
+        .into_app_err_with(|| format!("calling 'describe' in WebAssembly plugin '{path}'"))?;
+    let output = instance.read_output(packed, &plugin.path)?;
+
+    let decls: Vec<MetricDecl> =
+        serde_json::from_slice(&output).into_app_err_with(|| format!("parsing the metrics described by WebAssembly plugin '{path}'"))?;
+
+    for decl in decls {
+        if super::metric_names().any(|name| name == decl.name) || plugin.metrics.iter().any(|(def, _)| def.name == decl.name) {
+            return Err(app_err!(
+                "WebAssembly plugin '{path}' declares metric '{}', which already exists",
+                decl.name
+            ));
+        }
+
+        if !decl.name.contains('.') {
+            return Err(app_err!(
+                "WebAssembly plugin '{path}' declares metric '{}', which must be of the form '<namespace>.<name>'",
+                decl.name
+            ));
+        }
+
+        // Metric definitions live for the whole run, so the few created for each plugin are leaked
+        let def: &'static MetricDef = Box::leak(Box::new(MetricDef {
+            name: Box::leak(decl.name.into_boxed_str()),
+            description: Box::leak(decl.description.into_boxed_str()),
+            category: decl.category,
+            extractor: |_| None,
+            default_value: default_value(decl.kind),
+        }));
+        plugin.metrics.push((def, decl.kind));
+    }
+
+    log::debug!(target: LOG_TARGET, "Loaded WebAssembly plugin '{path}' computing {} metrics", plugin.metrics.len());
+    PLUGINS.write().unwrap_or_else(PoisonError::into_inner).push(Arc::new(plugin));
+    Ok(())
+}
+
+/// Return the definitions of the metrics computed by the loaded plugins
+pub(super) fn plugin_metric_defs() -> Vec<&'static MetricDef> {
+    registry()
+        .iter()
+        .flat_map(|plugin| plugin.metrics.iter().map(|(def, _)| *def))
+        .collect()
+}
+
+/// Fill in the values of the plugin metrics of a crate, computed from its built-in metrics.
+///
+/// Plugins which fail are logged and leave their metrics without values.
+pub(super) fn apply_plugins(metrics: &mut [Metric]) {
+    let plugins = registry().clone();
+    if plugins.is_empty() {
+        return;
+    }
+
+    let input = metrics_to_json(metrics).to_string();
+    for plugin in plugins {
+        let output = match plugin.evaluate(&input) {
+            Ok(output) => output,
+            Err(e) => {
+                log::warn!(target: LOG_TARGET, "WebAssembly plugin '{}' failed: {e:#}", plugin.path);
+                continue;
+            }
+        };
+
+        for (def, kind) in &plugin.metrics {
+            if let Some(metric) = metrics.iter_mut().find(|metric| core::ptr::eq(metric.def, *def)) {
+                metric.value = output.get(def.name).and_then(|value| json_to_metric_value(*kind, value));
+            }
+        }
+    }
+}
+
+impl WasmPlugin {
+    fn instantiate(&self) -> Result<(PluginInstance, Instance)> {
+        let path = &self.path;
+        let limits = StoreLimitsBuilder::new().memory_size(MAX_MEMORY_BYTES).instances(1).build();
+        let mut store = Store::new(&self.engine, limits);
+        store.limiter(|limits| limits);
+        store
+            .set_fuel(FUEL_PER_CALL)
+            .map_err(AppError::new)
+            .into_app_err_with(|| format!("setting fuel for WebAssembly plugin '{path}'"))?;
+
+        let instance = Instance::new(&mut store, &self.module, &[])
+            .map_err(AppError::new)
+            .into_app_err_with(|| format!("instantiating WebAssembly plugin '{path}'"))?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| app_err!("WebAssembly plugin '{path}' doesn't export 'memory'"))?;
+        let alloc = instance
+            .get_typed_func::<u32, u32>(&mut store, "alloc")
+            .map_err(AppError::new)
+            .into_app_err_with(|| format!("finding 'alloc' in WebAssembly plugin '{path}'"))?;
+
+        Ok((PluginInstance { store, memory, alloc }, instance))
+    }
+
+    fn evaluate(&self, input: &str) -> Result<serde_json::Map<String, serde_json::Value>> {
+        let path = &self.path;
+        let (mut instance, wasm_instance) = self.instantiate()?;
+        let evaluate = wasm_instance
+            .get_typed_func::<(u32, u32), u64>(&mut instance.store, "evaluate")
+            .map_err(AppError::new)
+            .into_app_err_with(|| format!("finding 'evaluate' in WebAssembly plugin '{path}'"))?;
+
+        let (ptr, len) = instance.write_input(input.as_bytes(), path)?;
+        let packed = evaluate
+            .call(&mut instance.store, (ptr, len))
+            .map_err(AppError::new)
+            .into_app_err_with(|| format!("calling 'evaluate' in WebAssembly plugin '{path}'"))?;
+        let output = instance.read_output(packed, path)?;
+
+        serde_json::from_slice(&output).into_app_err_with(|| format!("parsing the output of WebAssembly plugin '{path}'"))
+    }
+}
+
+impl PluginInstance {
+    fn write_input(&mut self, bytes: &[u8], path: &str) -> Result<(u32, u32)> {
+        let len = u32::try_from(bytes.len()).into_app_err_with(|| format!("input for WebAssembly plugin '{path}' is too large"))?;
+        let ptr = self
+            .alloc
+            .call(&mut self.store, len)
+            .map_err(AppError::new)
+            .into_app_err_with(|| format!("calling 'alloc' in WebAssembly plugin '{path}'"))?;
+        self.memory
+            .write(&mut self.store, ptr as usize, bytes)
+            .into_app_err_with(|| format!("writing input for WebAssembly plugin '{path}'"))?;
+        Ok((ptr, len))
+    }
+
+    fn read_output(&self, packed: u64, path: &str) -> Result<Vec<u8>> {
+        let (ptr, len) = unpack(packed);
+        if ptr.checked_add(len).is_none_or(|end| end > self.memory.data_size(&self.store)) {
+            return Err(app_err!(
+                "WebAssembly plugin '{path}' returned {len} bytes at address {ptr}, which lie outside its memory"
+            ));
+        }
+
+        let mut output = vec![0; len];
+        self.memory
+            .read(&self.store, ptr, &mut output)
+            .into_app_err_with(|| format!("reading output of WebAssembly plugin '{path}'"))?;
+        Ok(output)
+    }
+}
+
+/// Split a packed address and length returned by a plugin
+const fn unpack(packed: u64) -> (usize, usize) {
+    ((packed >> 32) as usize, (packed & 0xFFFF_FFFF) as usize)
+}
+
+const fn default_value(kind: MetricKind) -> fn() -> Option<MetricValue> {
+    match kind {
+        MetricKind::UInt => || Some(MetricValue::UInt(0)),
+        MetricKind::Float => || Some(MetricValue::Float(0.0)),
+        MetricKind::Bool => || Some(MetricValue::Boolean(false)),
+        MetricKind::String => || Some(MetricValue::String(CompactString::default())),
+    }
+}
+
+/// Render the metrics of a crate as the JSON object given to plugins
+fn metrics_to_json(metrics: &[Metric]) -> serde_json::Value {
+    metrics
+        .iter()
+        .map(|metric| {
+            (
+                metric.name().to_string(),
+                metric.value.as_ref().map_or(serde_json::Value::Null, MetricValue::to_json),
+            )
+        })
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+/// Convert a value returned by a plugin, ignoring values of the wrong type
+fn json_to_metric_value(kind: MetricKind, value: &serde_json::Value) -> Option<MetricValue> {
+    match kind {
+        MetricKind::UInt => value.as_u64().map(MetricValue::UInt),
+        MetricKind::Float => value.as_f64().map(MetricValue::Float),
+        MetricKind::Bool => value.as_bool().map(MetricValue::Boolean),
+        MetricKind::String => value.as_str().map(|s| MetricValue::String(s.into())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    static STARS_DEF: MetricDef = MetricDef {
+        name: "community.repo_stars",
+        description: "Number of stars on the repository",
+        category: MetricCategory::Community,
+        extractor: |_| None,
+        default_value: || None,
+    };
+
+    static OWNERS_DEF: MetricDef = MetricDef {
+        name: "crate.owners",
+        description: "List of owner usernames",
+        category: MetricCategory::Metadata,
+        extractor: |_| None,
+        default_value: || None,
+    };
+
+    #[test]
+    fn test_parse_metric_decls() {
+        let decls: Vec<MetricDecl> = serde_json::from_value(json!([
+            { "name": "internal.approved", "description": "Approved", "category": "Trustworthiness", "type": "bool" },
+            { "name": "internal.score", "description": "Score", "category": "Metadata", "type": "uint" },
+        ]))
+        .unwrap();
+
+        assert_eq!(decls.len(), 2);
+        assert_eq!(decls[0].kind, MetricKind::Bool);
+        assert_eq!(decls[1].category, MetricCategory::Metadata);
+        assert_eq!(decls[1].kind, MetricKind::UInt);
+    }
+
+    #[test]
+    fn test_metrics_to_json() {
+        let metrics = [
+            Metric::with_value(&STARS_DEF, MetricValue::UInt(42)),
+            Metric::with_value(&OWNERS_DEF, MetricValue::List(vec![MetricValue::String("alice".into())])),
+        ];
+
+        assert_eq!(
+            metrics_to_json(&metrics),
+            json!({ "community.repo_stars": 42, "crate.owners": ["alice"] })
+        );
+        assert_eq!(metrics_to_json(&[Metric::new(&STARS_DEF)]), json!({ "community.repo_stars": null }));
+    }
+
+    #[test]
+    fn test_json_to_metric_value() {
+        assert!(matches!(
+            json_to_metric_value(MetricKind::Bool, &json!(true)),
+            Some(MetricValue::Boolean(true))
+        ));
+        assert!(matches!(
+            json_to_metric_value(MetricKind::UInt, &json!(7)),
+            Some(MetricValue::UInt(7))
+        ));
+        assert!(matches!(json_to_metric_value(MetricKind::String, &json!("ok")), Some(MetricValue::String(s)) if s == "ok"));
+        assert!(json_to_metric_value(MetricKind::UInt, &json!(-1)).is_none());
+        assert!(json_to_metric_value(MetricKind::Bool, &json!("yes")).is_none());
+    }
+
+    /// A plugin exporting the memory described by `memory_section` and an `alloc` function returning 0
+    fn test_plugin(memory_section: &[u8]) -> WasmPlugin {
+        let mut bytes = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+        bytes.extend([0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01, 0x7f]);
+        bytes.extend([0x03, 0x02, 0x01, 0x00]);
+        bytes.extend(memory_section);
+        bytes.extend([0x07, 0x12, 0x02, 0x06, b'm', b'e', b'm', b'o', b'r', b'y', 0x02, 0x00]);
+        bytes.extend([0x05, b'a', b'l', b'l', b'o', b'c', 0x00, 0x00]);
+        bytes.extend([0x0a, 0x06, 0x01, 0x04, 0x00, 0x41, 0x00, 0x0b]);
+
+        let mut config = wasmtime::Config::new();
+        let _ = config.consume_fuel(true);
+        let engine = Engine::new(&config).unwrap();
+        let module = Module::new(&engine, bytes).unwrap();
+        WasmPlugin {
+            path: "test.wasm".to_string(),
+            engine,
+            module,
+            metrics: Vec::new(),
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot run wasmtime")]
+    fn test_read_output_bounds() {
+        let (instance, _) = test_plugin(&[0x05, 0x03, 0x01, 0x00, 0x01]).instantiate().unwrap();
+
+        assert_eq!(instance.read_output((65_532 << 32) | 4, "test.wasm").unwrap().len(), 4);
+        let _ = instance.read_output((65_532 << 32) | 5, "test.wasm").unwrap_err();
+        let _ = instance.read_output(u64::MAX, "test.wasm").unwrap_err();
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot run wasmtime")]
+    fn test_memory_cap() {
+        // An initial memory of 4097 pages, just over the cap
+        assert!(test_plugin(&[0x05, 0x04, 0x01, 0x00, 0x81, 0x20]).instantiate().is_err());
+    }
+
+    #[test]
+    fn test_unpack() {
+        assert_eq!(unpack((16 << 32) | 5), (16, 5));
+    }
+}
@@ -0,0 +1,92 @@
+use super::ReportableCrate;
+use crate::Result;
+use crate::expr::Risk;
+use core::fmt::Write;
+
+/// Number of lowest-scoring crates listed in the `worst-offenders` annotation.
+const WORST_OFFENDER_COUNT: usize = 5;
+
+/// Generate a Backstage `catalog-info.yaml` fragment summarizing the appraisal.
+///
+/// The fragment holds a `metadata.annotations` block which can be merged into a component's
+/// catalog entry so that developer portals can display the health of its dependencies.
+/// The workspace score is the mean score of all appraised crates.
+pub fn generate<W: Write>(crates: &[ReportableCrate], writer: &mut W) -> Result<()> {
+    let mut appraised: Vec<_> = crates.iter().filter_map(|c| c.appraisal.as_ref().map(|a| (c, a))).collect();
+
+    let count_risk = |risk: Risk| appraised.iter().filter(|(_, a)| a.risk == risk).count();
+    let high = count_risk(Risk::High);
+    let medium = count_risk(Risk::Medium);
+    let low = count_risk(Risk::Low);
+
+    #[expect(clippy::cast_precision_loss, reason = "Crate counts will never exceed 2^52")]
+    let score = if appraised.is_empty() {
+        None
+    } else {
+        Some(appraised.iter().map(|(_, a)| a.score).sum::<f64>() / appraised.len() as f64)
+    };
+
+    appraised.sort_by(|(_, a), (_, b)| a.score.partial_cmp(&b.score).unwrap_or(core::cmp::Ordering::Equal));
+    let worst_offenders: Vec<_> = appraised
+        .iter()
+        .filter(|(_, a)| a.risk != Risk::Low)
+        .take(WORST_OFFENDER_COUNT)
+        .map(|(c, _)| format!("{}@{}", c.name, c.version))
+        .collect();
+
+    writeln!(writer, "metadata:")?;
+    writeln!(writer, "  annotations:")?;
+    if let Some(score) = score {
+        writeln!(writer, "    cargo-aprz/score: \"{score:.0}\"")?;
+    }
+    writeln!(writer, "    cargo-aprz/crates: \"{}\"", crates.len())?;
+    writeln!(writer, "    cargo-aprz/high-risk: \"{high}\"")?;
+    writeln!(writer, "    cargo-aprz/medium-risk: \"{medium}\"")?;
+    writeln!(writer, "    cargo-aprz/low-risk: \"{low}\"")?;
+    writeln!(writer, "    cargo-aprz/worst-offenders: \"{}\"", worst_offenders.join(", "))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::Appraisal;
+    use std::sync::Arc;
+
+    fn make_crate(name: &str, risk: Risk, score: f64) -> ReportableCrate {
+        ReportableCrate::new(
+            name.into(),
+            Arc::new("1.0.0".parse().unwrap()),
+            vec![],
+            Some(Appraisal::new(risk, vec![], 10, 0, score)),
+        )
+    }
+
+    #[test]
+    fn test_generate_empty() {
+        let mut output = String::new();
+        generate(&[], &mut output).unwrap();
+        assert!(!output.contains("cargo-aprz/score"));
+        assert!(output.contains("cargo-aprz/crates: \"0\""));
+        assert!(output.contains("cargo-aprz/worst-offenders: \"\""));
+    }
+
+    #[test]
+    fn test_generate_summary() {
+        let crates = vec![
+            make_crate("good", Risk::Low, 90.0),
+            make_crate("meh", Risk::Medium, 50.0),
+            make_crate("bad", Risk::High, 10.0),
+        ];
+        let mut output = String::new();
+        generate(&crates, &mut output).unwrap();
+
+        assert!(output.starts_with("metadata:\n  annotations:\n"));
+        assert!(output.contains("cargo-aprz/score: \"50\""));
+        assert!(output.contains("cargo-aprz/high-risk: \"1\""));
+        assert!(output.contains("cargo-aprz/medium-risk: \"1\""));
+        assert!(output.contains("cargo-aprz/low-risk: \"1\""));
+        assert!(output.contains("cargo-aprz/worst-offenders: \"bad@1.0.0, meh@1.0.0\""));
+    }
+}
@@ -4,11 +4,13 @@ use crate::expr::{Appraisal, ExpressionDisposition, ExpressionOutcome, Risk};
 use crate::metrics::{Metric, MetricCategory, MetricValue};
 use core::fmt;
 use crate::{HashMap, HashSet};
+use super::CategoryScore;
 
 /// Format a metric value as a string using consistent formatting rules.
 ///
 /// `DateTime` values are formatted as date-only (YYYY-MM-DD) for readability.
 /// `List` values are formatted as comma-separated strings.
+#[must_use]
 pub fn format_metric_value(value: &MetricValue) -> String {
     let mut buf = String::new();
     write_metric_value(&mut buf, value);
@@ -101,17 +103,133 @@ pub const fn format_risk_status(risk: Risk) -> &'static str {
     }
 }
 
+/// Format the score of a crate in a metric category along with its risk band.
+pub fn format_category_score(category_score: &CategoryScore) -> String {
+    format!("score {:.0}, {}", category_score.score, format_risk_status(category_score.risk))
+}
+
+/// Format an appraisal's score, followed by its margin when some expressions couldn't be evaluated.
+pub fn format_score(appraisal: &Appraisal) -> String {
+    let margin = appraisal.score_margin().round();
+    if margin > 0.0 {
+        format!("{:.0} ± {margin:.0}", appraisal.score)
+    } else {
+        format!("{:.0}", appraisal.score)
+    }
+}
+
 /// Format an appraisal as a detailed status string including score and points.
 pub fn format_appraisal_status(appraisal: &Appraisal) -> String {
     format!(
-        "{} (score = {:.0}, awarded points = {}, available points = {})",
+        "{} (score = {}, awarded points = {}, available points = {})",
         format_risk_status(appraisal.risk),
-        appraisal.score,
+        format_score(appraisal),
         appraisal.awarded_points,
         appraisal.available_points,
     )
 }
 
+/// Number of crates listed as the biggest movers in report summaries
+pub const MAX_MOVERS: usize = 5;
+
+/// Format a change in score with an arrow showing its direction, or `None` if the rounded score didn't change.
+pub fn format_score_delta(delta: f64) -> Option<String> {
+    let rounded = delta.round();
+    if rounded > 0.0 {
+        Some(format!("▲ {rounded:.0}"))
+    } else if rounded < 0.0 {
+        Some(format!("▼ {:.0}", -rounded))
+    } else {
+        None
+    }
+}
+
+/// Returns the crates whose score changed the most since the previous run, with their score change,
+/// largest change first.
+pub fn biggest_movers(crates: &[super::ReportableCrate], limit: usize) -> Vec<(&super::ReportableCrate, f64)> {
+    let mut movers: Vec<_> = crates
+        .iter()
+        .filter_map(|c| c.score_delta().map(|delta| (c, delta)))
+        .filter(|(_, delta)| format_score_delta(*delta).is_some())
+        .collect();
+
+    movers.sort_by(|a, b| {
+        b.1.abs()
+            .partial_cmp(&a.1.abs())
+            .unwrap_or(core::cmp::Ordering::Equal)
+            .then_with(|| a.0.name.cmp(&b.0.name))
+    });
+    movers.truncate(limit);
+    movers
+}
+
+/// Headline score of a workspace's dependencies.
+#[derive(Debug, Clone, Copy)]
+pub struct WorkspaceScore {
+    /// Mean score of the appraised crates, weighted by how much each crate counts towards the workspace
+    pub score: f64,
+
+    /// Letter grade for the score
+    pub grade: &'static str,
+
+    /// Number of appraised crates contributing to the score
+    pub crates: usize,
+}
+
+/// Compute the workspace score, or `None` if no appraised crate carries a workspace weight.
+#[must_use]
+pub fn workspace_score(crates: &[super::ReportableCrate]) -> Option<WorkspaceScore> {
+    let weighted: Vec<(f64, f64)> = crates
+        .iter()
+        .filter_map(|c| c.appraisal.as_ref().zip(c.weight).map(|(a, weight)| (a.score, weight)))
+        .filter(|(_, weight)| *weight > 0.0)
+        .collect();
+
+    let total_weight: f64 = weighted.iter().map(|(_, weight)| weight).sum();
+    if total_weight <= 0.0 {
+        return None;
+    }
+
+    let score = weighted.iter().map(|(score, weight)| score * weight).sum::<f64>() / total_weight;
+    Some(WorkspaceScore {
+        score,
+        grade: grade(score),
+        crates: weighted.len(),
+    })
+}
+
+/// Letter grade for a score, using the usual 10-point bands.
+#[must_use]
+pub fn grade(score: f64) -> &'static str {
+    match score.round() {
+        s if s >= 90.0 => "A",
+        s if s >= 80.0 => "B",
+        s if s >= 70.0 => "C",
+        s if s >= 60.0 => "D",
+        _ => "F",
+    }
+}
+
+/// Format a workspace score as a one-line summary.
+pub fn format_workspace_score(workspace: &WorkspaceScore) -> String {
+    format!(
+        "Workspace grade {} (score {:.0} across {} crate(s))",
+        workspace.grade, workspace.score, workspace.crates
+    )
+}
+
+/// Returns the sorted, de-duplicated names of the crates appraised at the given risk level.
+pub fn crate_names_with_risk(crates: &[super::ReportableCrate], risk: Risk) -> Vec<&str> {
+    let mut names: Vec<_> = crates
+        .iter()
+        .filter(|c| c.appraisal.as_ref().is_some_and(|a| a.risk == risk))
+        .map(|c| c.name.as_ref())
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    names
+}
+
 /// Returns the pass/fail icon for an expression outcome.
 pub const fn outcome_icon(outcome: &ExpressionOutcome) -> &'static str {
     match outcome.disposition {
@@ -221,6 +339,38 @@ mod tests {
         default_value: || None,
     };
 
+    #[test]
+    fn test_format_score_delta() {
+        assert_eq!(format_score_delta(4.6), Some("▲ 5".to_string()));
+        assert_eq!(format_score_delta(-12.0), Some("▼ 12".to_string()));
+        assert_eq!(format_score_delta(0.3), None);
+        assert_eq!(format_score_delta(-0.4), None);
+    }
+
+    #[test]
+    fn test_biggest_movers() {
+        use crate::expr::Risk;
+        use std::sync::Arc;
+
+        let make = |name: &str, score: f64, previous: Option<f64>| {
+            let appraisal = Appraisal::new(Risk::Low, Vec::new(), 0, 0, score);
+            let mut c = crate::reports::ReportableCrate::new(name.into(), Arc::new("1.0.0".parse().unwrap()), Vec::new(), Some(appraisal));
+            c.previous_score = previous;
+            c
+        };
+
+        let crates = vec![
+            make("steady", 80.0, Some(80.0)),
+            make("riser", 90.0, Some(70.0)),
+            make("new", 50.0, None),
+            make("faller", 40.0, Some(70.0)),
+            make("nudge", 60.0, Some(58.0)),
+        ];
+
+        let movers: Vec<_> = biggest_movers(&crates, 2).into_iter().map(|(c, d)| (c.name.to_string(), d)).collect();
+        assert_eq!(movers, vec![("faller".to_string(), -30.0), ("riser".to_string(), 20.0)]);
+    }
+
     #[test]
     fn test_format_metric_value_unsigned_integer() {
         assert_eq!(format_metric_value(&MetricValue::UInt(100)), "100");
@@ -300,6 +450,43 @@ mod tests {
         assert_eq!(format_keywords_or_categories_with_prefix(""), "");
     }
 
+    #[test]
+    fn test_workspace_score() {
+        use std::sync::Arc;
+
+        let make = |name: &str, score: f64, weight: Option<f64>| {
+            let appraisal = Appraisal::new(Risk::Low, Vec::new(), 0, 0, score);
+            let mut c = crate::reports::ReportableCrate::new(name.into(), Arc::new("1.0.0".parse().unwrap()), Vec::new(), Some(appraisal));
+            c.weight = weight;
+            c
+        };
+
+        assert!(workspace_score(&[make("a", 90.0, None)]).is_none());
+
+        let workspace = workspace_score(&[make("a", 90.0, Some(1.0)), make("b", 60.0, Some(0.5)), make("c", 0.0, None)]).unwrap();
+        assert!((workspace.score - 80.0).abs() < 1e-9);
+        assert_eq!(workspace.grade, "B");
+        assert_eq!(workspace.crates, 2);
+        assert_eq!(format_workspace_score(&workspace), "Workspace grade B (score 80 across 2 crate(s))");
+    }
+
+    #[test]
+    fn test_grade() {
+        assert_eq!(grade(100.0), "A");
+        assert_eq!(grade(89.6), "A");
+        assert_eq!(grade(85.0), "B");
+        assert_eq!(grade(70.0), "C");
+        assert_eq!(grade(64.0), "D");
+        assert_eq!(grade(12.0), "F");
+    }
+
+    #[test]
+    fn test_format_score() {
+        let appraisal = Appraisal::new(Risk::Low, vec![], 8, 6, 75.0);
+        assert_eq!(format_score(&appraisal), "75");
+        assert_eq!(format_score(&appraisal.with_unevaluated_points(2)), "75 ± 15");
+    }
+
     #[test]
     fn test_format_risk_status() {
         assert_eq!(format_risk_status(Risk::Low), "LOW RISK");
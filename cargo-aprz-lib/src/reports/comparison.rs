@@ -0,0 +1,424 @@
+//! Side-by-side comparison of alternative crates.
+//!
+//! Every metric and policy outcome is laid out as a row with one column per crate. Winners are decided
+//! by the policy: the crate with the highest score wins the score row, and the crates satisfying an
+//! expression that others fail win that expression's row. Metrics have no inherent direction, so metric
+//! rows only flag whether the crates differ.
+
+use super::html::html_escape;
+use super::{ReportableCrate, common};
+use crate::Result;
+use crate::expr::ExpressionDisposition;
+use crate::metrics::MetricCategory;
+use chrono::{DateTime, Local};
+use core::fmt::Write;
+use owo_colors::OwoColorize;
+use strum::IntoEnumIterator;
+
+/// Maximum number of characters shown for a value in the console matrix
+const MAX_CONSOLE_CELL_LEN: usize = 32;
+
+/// Marker placed in front of winning console cells
+const WINNER_MARKER: &str = "★ ";
+
+/// A group of rows in the comparison matrix.
+#[derive(Debug)]
+struct Section {
+    title: String,
+    rows: Vec<Row>,
+}
+
+/// A single metric or policy outcome, with one cell per crate.
+#[derive(Debug)]
+struct Row {
+    label: String,
+    cells: Vec<String>,
+    winners: Vec<bool>,
+    differs: bool,
+}
+
+impl Row {
+    fn new(label: impl Into<String>, cells: Vec<String>, winners: Vec<bool>) -> Self {
+        let differs = cells.first().is_some_and(|first| cells.iter().any(|cell| cell != first));
+        Self {
+            label: label.into(),
+            cells,
+            winners,
+            differs,
+        }
+    }
+}
+
+/// Write a comparison matrix of the given crates to the console.
+pub fn generate_console<W: Write>(crates: &[ReportableCrate], use_colors: bool, writer: &mut W) -> Result<()> {
+    let sections = build_sections(crates);
+    let headers: Vec<String> = crates.iter().map(|c| format!("{} v{}", c.name, c.version)).collect();
+
+    let label_width = sections
+        .iter()
+        .flat_map(|s| s.rows.iter().map(|r| r.label.chars().count()))
+        .chain(sections.iter().map(|s| s.title.chars().count()))
+        .max()
+        .unwrap_or(0);
+
+    let column_widths: Vec<usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(index, header)| {
+            sections
+                .iter()
+                .flat_map(|s| s.rows.iter())
+                .map(|r| console_cell(&r.cells[index], r.winners[index]).chars().count())
+                .chain(core::iter::once(header.chars().count()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    write!(writer, "{:label_width$}", "")?;
+    for (header, width) in headers.iter().zip(&column_widths) {
+        let padded = format!("{header:width$}");
+        if use_colors {
+            write!(writer, "  {}", padded.bold())?;
+        } else {
+            write!(writer, "  {padded}")?;
+        }
+    }
+    writeln!(writer)?;
+
+    for section in &sections {
+        writeln!(writer)?;
+        if use_colors {
+            writeln!(writer, "{}", section.title.bold())?;
+        } else {
+            writeln!(writer, "{}", section.title)?;
+        }
+
+        for row in &section.rows {
+            let label = format!("{:label_width$}", row.label);
+            if use_colors && row.differs {
+                write!(writer, "{}", label.bold())?;
+            } else {
+                write!(writer, "{label}")?;
+            }
+
+            for ((cell, &winner), width) in row.cells.iter().zip(&row.winners).zip(&column_widths) {
+                let padded = format!("{:width$}", console_cell(cell, winner));
+                if use_colors && winner {
+                    write!(writer, "  {}", padded.green().bold())?;
+                } else {
+                    write!(writer, "  {padded}")?;
+                }
+            }
+            writeln!(writer)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Write a comparison matrix of the given crates as a self-contained HTML document.
+pub fn generate_html<W: Write>(crates: &[ReportableCrate], timestamp: DateTime<Local>, writer: &mut W) -> Result<()> {
+    let sections = build_sections(crates);
+
+    writeln!(writer, "<!DOCTYPE html>\n<html>\n<head>")?;
+    writeln!(writer, "  <meta charset=\"UTF-8\">")?;
+    writeln!(writer, "  <meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">")?;
+    writeln!(writer, "  <title>Crate Comparison Report</title>")?;
+    writeln!(writer, "  <style>")?;
+    writeln!(writer, "    body {{ font-family: system-ui, sans-serif; margin: 2rem; color: #1a202c; background: #f0f2f5; }}")?;
+    writeln!(writer, "    table {{ border-collapse: collapse; background: #ffffff; }}")?;
+    writeln!(writer, "    th, td {{ border: 1px solid #e2e8f0; padding: 0.4rem 0.8rem; text-align: left; vertical-align: top; }}")?;
+    writeln!(writer, "    th.section {{ background: #fef3e2; color: #9a3412; }}")?;
+    writeln!(writer, "    tr.differs td.label {{ font-weight: bold; }}")?;
+    writeln!(writer, "    td.winner {{ background: #86efac; color: #14532d; font-weight: bold; }}")?;
+    writeln!(writer, "  </style>")?;
+    writeln!(writer, "</head>")?;
+    writeln!(writer, "<body>")?;
+    writeln!(writer, "  <h1>Crate Comparison Report</h1>")?;
+    writeln!(
+        writer,
+        "  <p>Produced by cargo-aprz {} on {}</p>",
+        env!("CARGO_PKG_VERSION"),
+        timestamp.format("%Y-%m-%d")
+    )?;
+
+    writeln!(writer, "  <table>")?;
+    write!(writer, "    <tr><th></th>")?;
+    for crate_info in crates {
+        write!(
+            writer,
+            "<th>{} v{}</th>",
+            html_escape(&crate_info.name),
+            html_escape(&crate_info.version.to_string())
+        )?;
+    }
+    writeln!(writer, "</tr>")?;
+
+    for section in &sections {
+        writeln!(
+            writer,
+            "    <tr><th class=\"section\" colspan=\"{}\">{}</th></tr>",
+            crates.len() + 1,
+            html_escape(&section.title)
+        )?;
+
+        for row in &section.rows {
+            let class = if row.differs { " class=\"differs\"" } else { "" };
+            write!(writer, "    <tr{class}><td class=\"label\">{}</td>", html_escape(&row.label))?;
+            for (cell, &winner) in row.cells.iter().zip(&row.winners) {
+                let class = if winner { " class=\"winner\"" } else { "" };
+                write!(writer, "<td{class}>{}</td>", html_escape(cell))?;
+            }
+            writeln!(writer, "</tr>")?;
+        }
+    }
+
+    writeln!(writer, "  </table>")?;
+    writeln!(writer, "</body>")?;
+    writeln!(writer, "</html>")?;
+
+    Ok(())
+}
+
+/// Lay out the appraisal results and metrics of the crates as matrix rows.
+fn build_sections(crates: &[ReportableCrate]) -> Vec<Section> {
+    let mut sections = Vec::new();
+
+    if crates.iter().any(|c| c.appraisal.is_some()) {
+        sections.push(appraisal_section(crates));
+        sections.push(policy_section(crates));
+    }
+
+    let metrics_by_category = common::group_all_metrics_by_category(crates.iter().map(|c| c.metrics.as_slice()));
+    let metric_maps = common::build_metric_lookup_maps(crates);
+
+    for category in MetricCategory::iter() {
+        let Some(metric_names) = metrics_by_category.get(&category) else {
+            continue;
+        };
+
+        let rows = metric_names
+            .iter()
+            .map(|&name| {
+                let cells = metric_maps
+                    .iter()
+                    .map(|map| {
+                        map.get(name)
+                            .and_then(|m| m.value.as_ref())
+                            .map_or_else(|| "n/a".to_string(), common::format_metric_value)
+                    })
+                    .collect();
+                Row::new(name, cells, vec![false; crates.len()])
+            })
+            .collect();
+
+        sections.push(Section {
+            title: category.to_string(),
+            rows,
+        });
+    }
+
+    sections
+}
+
+fn appraisal_section(crates: &[ReportableCrate]) -> Section {
+    let risks = crates
+        .iter()
+        .map(|c| c.appraisal.as_ref().map_or("not appraised", |a| common::format_risk_status(a.risk)).to_string())
+        .collect();
+
+    let best_risk = crates.iter().filter_map(|c| c.appraisal.as_ref().map(|a| a.risk)).min();
+    let risk_winners = winners(crates.iter().map(|c| c.appraisal.as_ref().map(|a| a.risk) == best_risk && best_risk.is_some()));
+
+    let scores = crates
+        .iter()
+        .map(|c| c.appraisal.as_ref().map_or_else(|| "n/a".to_string(), common::format_score))
+        .collect();
+
+    let best_score = crates
+        .iter()
+        .filter_map(|c| c.appraisal.as_ref().map(|a| a.score))
+        .fold(None, |best: Option<f64>, score| Some(best.map_or(score, |b| b.max(score))));
+    let score_winners = winners(crates.iter().map(|c| {
+        c.appraisal
+            .as_ref()
+            .zip(best_score)
+            .is_some_and(|(a, best)| (a.score - best).abs() < f64::EPSILON)
+    }));
+
+    Section {
+        title: "Appraisal".to_string(),
+        rows: vec![Row::new("risk", risks, risk_winners), Row::new("score", scores, score_winners)],
+    }
+}
+
+fn policy_section(crates: &[ReportableCrate]) -> Section {
+    let mut expression_names: Vec<&str> = Vec::new();
+    for appraisal in crates.iter().filter_map(|c| c.appraisal.as_ref()) {
+        for outcome in &appraisal.expression_outcomes {
+            if !expression_names.contains(&outcome.name.as_ref()) {
+                expression_names.push(&outcome.name);
+            }
+        }
+    }
+
+    let rows = expression_names
+        .into_iter()
+        .map(|name| {
+            let dispositions: Vec<Option<&ExpressionDisposition>> = crates
+                .iter()
+                .map(|c| {
+                    c.appraisal
+                        .as_ref()
+                        .and_then(|a| a.expression_outcomes.iter().find(|o| o.name.as_ref() == name))
+                        .map(|o| &o.disposition)
+                })
+                .collect();
+
+            let cells = dispositions
+                .iter()
+                .map(|d| match d {
+                    Some(ExpressionDisposition::True) => "pass",
+                    Some(ExpressionDisposition::False) => "fail",
+                    Some(ExpressionDisposition::Failed(_)) => "error",
+                    None => "n/a",
+                })
+                .map(str::to_string)
+                .collect();
+
+            let row_winners = winners(dispositions.iter().map(|d| matches!(d, Some(ExpressionDisposition::True))));
+            Row::new(name, cells, row_winners)
+        })
+        .collect();
+
+    Section {
+        title: "Policy".to_string(),
+        rows,
+    }
+}
+
+/// Keep the winning flags only when they single out some of the crates, since a tie between all crates has no winner.
+fn winners(flags: impl IntoIterator<Item = bool>) -> Vec<bool> {
+    let flags: Vec<bool> = flags.into_iter().collect();
+    if flags.iter().all(|&f| f) {
+        vec![false; flags.len()]
+    } else {
+        flags
+    }
+}
+
+/// Format a value for the console, truncating long values and marking winners.
+fn console_cell(value: &str, winner: bool) -> String {
+    let mut cell = if winner { WINNER_MARKER.to_string() } else { String::new() };
+
+    if value.chars().count() > MAX_CONSOLE_CELL_LEN {
+        cell.extend(value.chars().take(MAX_CONSOLE_CELL_LEN - 1));
+        cell.push('…');
+    } else {
+        cell.push_str(value);
+    }
+
+    cell
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::{Appraisal, ExpressionOutcome, Risk};
+    use crate::metrics::{Metric, MetricDef, MetricValue};
+    use std::sync::Arc;
+
+    static DOWNLOADS_DEF: MetricDef = MetricDef {
+        name: "usage.total_downloads",
+        description: "Total downloads",
+        category: MetricCategory::Usage,
+        extractor: |_| None,
+        default_value: || None,
+    };
+
+    static LICENSE_DEF: MetricDef = MetricDef {
+        name: "crate.license",
+        description: "License",
+        category: MetricCategory::Metadata,
+        extractor: |_| None,
+        default_value: || None,
+    };
+
+    fn create_crate(name: &str, downloads: u64, score: f64, risk: Risk, passes: bool) -> ReportableCrate {
+        let metrics = vec![
+            Metric::with_value(&LICENSE_DEF, MetricValue::String("MIT".into())),
+            Metric::with_value(&DOWNLOADS_DEF, MetricValue::UInt(downloads)),
+        ];
+        let disposition = if passes { ExpressionDisposition::True } else { ExpressionDisposition::False };
+        let appraisal = Appraisal::new(
+            risk,
+            vec![ExpressionOutcome::new("popular".into(), "Widely used".into(), disposition)],
+            1,
+            u32::from(passes),
+            score,
+        );
+        ReportableCrate::new(name.into(), Arc::new("1.0.0".parse().unwrap()), metrics, Some(appraisal))
+    }
+
+    fn crates() -> Vec<ReportableCrate> {
+        vec![
+            create_crate("tokio", 1000, 90.0, Risk::Low, true),
+            create_crate("smol", 10, 60.0, Risk::Medium, false),
+        ]
+    }
+
+    fn find_row<'a>(sections: &'a [Section], label: &str) -> &'a Row {
+        sections.iter().flat_map(|s| s.rows.iter()).find(|r| r.label == label).unwrap()
+    }
+
+    #[test]
+    fn test_winners() {
+        let sections = build_sections(&crates());
+        assert_eq!(find_row(&sections, "score").winners, vec![true, false]);
+        assert_eq!(find_row(&sections, "risk").winners, vec![true, false]);
+        assert_eq!(find_row(&sections, "popular").winners, vec![true, false]);
+        assert_eq!(find_row(&sections, "popular").cells, vec!["pass", "fail"]);
+    }
+
+    #[test]
+    fn test_metric_rows() {
+        let sections = build_sections(&crates());
+        let downloads = find_row(&sections, "usage.total_downloads");
+        assert!(downloads.differs);
+        assert_eq!(downloads.winners, vec![false, false]);
+        assert!(!find_row(&sections, "crate.license").differs);
+    }
+
+    #[test]
+    fn test_ties_have_no_winner() {
+        assert_eq!(winners([true, true]), vec![false, false]);
+        assert_eq!(winners([false, true]), vec![false, true]);
+    }
+
+    #[test]
+    fn test_generate_console() {
+        let mut output = String::new();
+        generate_console(&crates(), false, &mut output).unwrap();
+        assert!(output.contains("tokio v1.0.0"));
+        assert!(output.contains("smol v1.0.0"));
+        assert!(output.contains("★ 90"));
+        assert!(output.contains("Policy"));
+        assert!(!output.contains("\x1b["));
+    }
+
+    #[test]
+    fn test_generate_html() {
+        let mut output = String::new();
+        generate_html(&crates(), Local::now(), &mut output).unwrap();
+        assert!(output.contains("<td class=\"winner\">90</td>"));
+        assert!(output.contains("<tr class=\"differs\"><td class=\"label\">usage.total_downloads</td>"));
+    }
+
+    #[test]
+    fn test_console_cell_truncation() {
+        let long = "x".repeat(100);
+        assert_eq!(console_cell(&long, false).chars().count(), MAX_CONSOLE_CELL_LEN);
+        assert_eq!(console_cell("short", true), "★ short");
+    }
+}
@@ -1,7 +1,8 @@
 use super::{ReportableCrate, common};
 use crate::Result;
-use crate::expr::Risk;
+use crate::expr::{ExpressionDisposition, Risk};
 use crate::metrics::{Metric, MetricCategory};
+use clap::ValueEnum;
 use core::fmt::Write;
 use owo_colors::OwoColorize;
 use std::borrow::Cow;
@@ -9,8 +10,20 @@ use crate::HashMap;
 use strum::IntoEnumIterator;
 use terminal_size::{Width, terminal_size};
 
-/// Controls which sections are included in console output.
+/// How the metrics of the console output are arranged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ConsoleLayout {
+    /// Show each crate's metrics together
+    #[default]
+    Crate,
+
+    /// Show each category's metrics together, across all crates
+    Category,
+}
+
+/// Controls which sections are included in console output, and how they're rendered.
 #[derive(Debug, Clone)]
+#[expect(clippy::struct_excessive_bools, reason = "Sections and hyperlinks are toggled independently")]
 pub struct ConsoleOutputMode {
     /// Show the appraisal risk level
     pub appraisal: bool,
@@ -18,23 +31,60 @@ pub struct ConsoleOutputMode {
     pub reasons: bool,
     /// Show individual metrics
     pub metrics: bool,
+    /// Make crate names and URLs clickable with OSC 8 escape sequences
+    pub hyperlinks: bool,
+    /// Only show crates which fail a policy expression or aren't low risk, along with the expressions they fail
+    pub only_failures: bool,
+    /// Crates whose reasons and metrics are shown, or all crates when empty
+    pub explain_crates: Vec<String>,
+    /// Categories of metrics shown, or all categories when empty
+    pub explain_categories: Vec<MetricCategory>,
+    /// How metrics are arranged
+    pub layout: ConsoleLayout,
 }
 
 impl ConsoleOutputMode {
-    /// All sections enabled.
+    /// All sections enabled, without hyperlinks.
     #[must_use]
     pub const fn full() -> Self {
-        Self { appraisal: true, reasons: true, metrics: true }
+        Self {
+            appraisal: true,
+            reasons: true,
+            metrics: true,
+            hyperlinks: false,
+            only_failures: false,
+            explain_crates: Vec::new(),
+            explain_categories: Vec::new(),
+            layout: ConsoleLayout::Crate,
+        }
     }
 }
 
 pub fn generate<W: Write>(crates: &[ReportableCrate], use_colors: bool, mode: &ConsoleOutputMode, writer: &mut W) -> Result<()> {
-    for (index, crate_info) in crates.iter().enumerate() {
-        if index > 0 && (mode.metrics || mode.reasons) {
+    let shown: Vec<_> = crates.iter().filter(|c| !mode.only_failures || is_failing(c)).collect();
+    if shown.is_empty() && !crates.is_empty() {
+        writeln!(writer, "No crates fail the policy")?;
+    }
+
+    let mut by_category = Vec::new();
+    let mut previous_detailed = false;
+    for (index, crate_info) in shown.into_iter().enumerate() {
+        let explained =
+            mode.explain_crates.is_empty() || mode.explain_crates.iter().any(|name| **name == *crate_info.name);
+        let show_reasons = mode.reasons && explained;
+        let show_metrics = mode.metrics && explained && mode.layout == ConsoleLayout::Crate;
+        if mode.metrics && explained && mode.layout == ConsoleLayout::Category {
+            by_category.push(crate_info);
+        }
+
+        // Separate the crates shown in detail from their neighbors
+        let detailed = show_metrics || show_reasons;
+        if index > 0 && (detailed || previous_detailed) {
             writeln!(writer)?;
             writeln!(writer, "═══════════════════════════════════════")?;
             writeln!(writer)?;
         }
+        previous_detailed = detailed;
 
         // Show appraisal if one is available
         if mode.appraisal {
@@ -49,64 +99,173 @@ pub fn generate<W: Write>(crates: &[ReportableCrate], use_colors: bool, mode: &C
                 } else {
                     Cow::Owned(status_str)
                 };
-                writeln!(writer, "{} v{} is appraised as {colored_status}", crate_info.name, crate_info.version)?;
+                let delta = crate_info.score_delta().and_then(common::format_score_delta).map_or_else(String::new, |d| format!(" {d}"));
+                let name = crate_link(crate_info, mode.hyperlinks);
+                writeln!(writer, "{name} v{} is appraised as {colored_status}{delta}", crate_info.version)?;
+
+                if show_reasons {
+                    let outcomes = eval
+                        .expression_outcomes
+                        .iter()
+                        .filter(|outcome| !mode.only_failures || matches!(outcome.disposition, ExpressionDisposition::False));
+                    for outcome in outcomes {
+                        // Crates explained by name also show where each policy was defined
+                        match outcome.origin.as_deref().filter(|_| !mode.explain_crates.is_empty()) {
+                            Some(origin) => writeln!(writer, "  {} [{origin}]", common::outcome_icon_name(outcome))?,
+                            None => writeln!(writer, "  {}", common::outcome_icon_name(outcome))?,
+                        }
+                    }
 
-                if mode.reasons {
-                    for outcome in &eval.expression_outcomes {
-                        writeln!(writer, "  {}", common::outcome_icon_name(outcome))?;
+                    for note in &crate_info.notes {
+                        writeln!(writer, "  Note: {note}")?;
                     }
                 }
             } else {
-                writeln!(writer, "{} v{} was not appraised", crate_info.name, crate_info.version)?;
+                writeln!(writer, "{} v{} was not appraised", crate_link(crate_info, mode.hyperlinks), crate_info.version)?;
             }
         }
 
-        if !mode.metrics {
+        if !show_metrics {
             continue;
         }
 
-        // Build lookup map for quick metric access
         let metric_map: HashMap<&str, &Metric> = crate_info.metrics.iter().map(|m| (m.name(), m)).collect();
-
-        // Use common grouping function to get metric names by category
         let metrics_by_category = common::group_metrics_by_category(&crate_info.metrics);
 
         // Display metrics grouped by category
-        for category in MetricCategory::iter() {
+        for category in shown_categories(mode) {
             if let Some(metric_names) = metrics_by_category.get(&category) {
                 writeln!(writer)?;
-                if use_colors {
-                    let category_str = category.to_string();
-                    writeln!(writer, "{}", category_str.bold())?;
-                } else {
-                    writeln!(writer, "{category}")?;
+                write_heading(writer, &category.to_string(), use_colors)?;
+                if let Some(category_score) = crate_info.category_scores.get(&category) {
+                    let score = common::format_category_score(category_score);
+                    writeln!(writer, "  {}", color_by_risk(score, category_score.risk, use_colors))?;
                 }
+                write_metrics(writer, metric_names, &metric_map, 2, mode.hyperlinks)?;
+            }
+        }
+    }
+
+    if mode.metrics && mode.layout == ConsoleLayout::Category {
+        write_metrics_by_category(&by_category, use_colors, mode, writer)?;
+    }
+
+    if mode.appraisal {
+        write_biggest_movers(crates, use_colors, mode.hyperlinks, writer)?;
 
-                // Compute max metric name length for alignment
-                let max_name_len = metric_names.iter().map(|name| name.len()).max().unwrap_or(0);
+        if let Some(workspace) = common::workspace_score(crates) {
+            writeln!(writer)?;
+            let line = common::format_workspace_score(&workspace);
+            if use_colors {
+                writeln!(writer, "{}", line.bold())?;
+            } else {
+                writeln!(writer, "{line}")?;
+            }
+        }
+    }
 
-                // Get terminal width and calculate available space for values
-                let term_width = get_terminal_width();
-                // Indent for metric lines: "  " (2) + metric_name + " : " (3)
-                let value_indent = 2 + max_name_len + 3;
+    Ok(())
+}
+
+/// Write the metrics of every crate one category at a time, so the same kind of problem can be reviewed across crates.
+fn write_metrics_by_category<W: Write>(
+    crates: &[&ReportableCrate],
+    use_colors: bool,
+    mode: &ConsoleOutputMode,
+    writer: &mut W,
+) -> Result<()> {
+    let crate_metrics: Vec<_> = crates
+        .iter()
+        .map(|crate_info| {
+            let metric_map: HashMap<&str, &Metric> = crate_info.metrics.iter().map(|m| (m.name(), m)).collect();
+            (crate_info, metric_map, common::group_metrics_by_category(&crate_info.metrics))
+        })
+        .collect();
+
+    for category in shown_categories(mode) {
+        if !crate_metrics.iter().any(|(_, _, by_category)| by_category.contains_key(&category)) {
+            continue;
+        }
 
-                for &metric_name in metric_names {
-                    if let Some(&metric) = metric_map.get(metric_name) {
-                        let formatted_value: Cow<'_, str> = metric.value.as_ref().map_or(Cow::Borrowed("n/a"), |v| Cow::Owned(common::format_metric_value(v)));
+        writeln!(writer)?;
+        write_heading(writer, &category.to_string(), use_colors)?;
+        for (crate_info, metric_map, by_category) in &crate_metrics {
+            if let Some(metric_names) = by_category.get(&category) {
+                let score = crate_info.category_scores.get(&category).map_or_else(String::new, |category_score| {
+                    format!(" ({})", color_by_risk(common::format_category_score(category_score), category_score.risk, use_colors))
+                });
+                writeln!(writer, "  {} v{}{score}", crate_link(crate_info, mode.hyperlinks), crate_info.version)?;
+                write_metrics(writer, metric_names, metric_map, 4, mode.hyperlinks)?;
+            }
+        }
+    }
 
-                        // Wrap the value text
-                        let wrapped_lines = wrap_text(&formatted_value, term_width, value_indent);
+    Ok(())
+}
 
-                        // Write first line with metric name
-                        if let Some(first_line) = wrapped_lines.first() {
-                            writeln!(writer, "  {:<width$} : {}", metric.name(), first_line, width = max_name_len)?;
+/// The metric categories to show, in display order.
+fn shown_categories(mode: &ConsoleOutputMode) -> impl Iterator<Item = MetricCategory> + '_ {
+    MetricCategory::iter().filter(|category| mode.explain_categories.is_empty() || mode.explain_categories.contains(category))
+}
 
-                            // Write continuation lines
-                            for line in wrapped_lines.iter().skip(1) {
-                                writeln!(writer, "{line}")?;
-                            }
-                        }
-                    }
+/// Color a text after a risk level, the way appraisal statuses are.
+fn color_by_risk(text: String, risk: Risk, use_colors: bool) -> String {
+    if !use_colors {
+        return text;
+    }
+
+    match risk {
+        Risk::Low => text.green().to_string(),
+        Risk::Medium => text.yellow().to_string(),
+        Risk::High => text.red().to_string(),
+    }
+}
+
+fn write_heading<W: Write>(writer: &mut W, heading: &str, use_colors: bool) -> Result<()> {
+    if use_colors {
+        writeln!(writer, "{}", heading.bold())?;
+    } else {
+        writeln!(writer, "{heading}")?;
+    }
+
+    Ok(())
+}
+
+/// Write one aligned `name : value` line per metric, wrapping long values to the terminal's width.
+fn write_metrics<W: Write>(
+    writer: &mut W,
+    metric_names: &[&str],
+    metric_map: &HashMap<&str, &Metric>,
+    indent: usize,
+    hyperlinks: bool,
+) -> Result<()> {
+    // Compute max metric name length for alignment
+    let max_name_len = metric_names.iter().map(|name| name.len()).max().unwrap_or(0);
+
+    // Get terminal width and calculate available space for values
+    let term_width = get_terminal_width();
+    // Indent for continuation lines: indent + metric_name + " : " (3)
+    let value_indent = indent + max_name_len + 3;
+
+    for &metric_name in metric_names {
+        if let Some(&metric) = metric_map.get(metric_name) {
+            let formatted_value: Cow<'_, str> =
+                metric.value.as_ref().map_or(Cow::Borrowed("n/a"), |v| Cow::Owned(common::format_metric_value(v)));
+
+            // Links are written whole since wrapping would split the escape sequences
+            let wrapped_lines = if hyperlinks && common::is_url(&formatted_value) {
+                vec![hyperlink(&formatted_value, &formatted_value)]
+            } else {
+                wrap_text(&formatted_value, term_width, value_indent)
+            };
+
+            // Write first line with metric name
+            if let Some(first_line) = wrapped_lines.first() {
+                writeln!(writer, "{:indent$}{:<width$} : {}", "", metric.name(), first_line, width = max_name_len)?;
+
+                // Write continuation lines
+                for line in wrapped_lines.iter().skip(1) {
+                    writeln!(writer, "{line}")?;
                 }
             }
         }
@@ -115,6 +274,94 @@ pub fn generate<W: Write>(crates: &[ReportableCrate], use_colors: bool, mode: &C
     Ok(())
 }
 
+/// Whether a crate fails any of the policy's expressions or falls outside the low risk band.
+fn is_failing(crate_info: &ReportableCrate) -> bool {
+    crate_info.appraisal.as_ref().is_some_and(|appraisal| {
+        appraisal.risk != Risk::Low
+            || appraisal
+                .expression_outcomes
+                .iter()
+                .any(|outcome| matches!(outcome.disposition, ExpressionDisposition::False))
+    })
+}
+
+/// List the crates whose score changed the most since the previous run.
+fn write_biggest_movers<W: Write>(crates: &[ReportableCrate], use_colors: bool, hyperlinks: bool, writer: &mut W) -> Result<()> {
+    let movers = common::biggest_movers(crates, common::MAX_MOVERS);
+    if movers.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(writer)?;
+    if use_colors {
+        writeln!(writer, "{}", "Biggest movers".bold())?;
+    } else {
+        writeln!(writer, "Biggest movers")?;
+    }
+
+    for (crate_info, delta) in movers {
+        let change = common::format_score_delta(delta).unwrap_or_default();
+        let change: Cow<'_, str> = if use_colors {
+            if delta > 0.0 {
+                change.green().to_string().into()
+            } else {
+                change.red().to_string().into()
+            }
+        } else {
+            Cow::Owned(change)
+        };
+        writeln!(writer, "  {} v{} {change}", crate_link(crate_info, hyperlinks), crate_info.version)?;
+    }
+
+    Ok(())
+}
+
+/// The crate's name, linked to its page on crates.io when hyperlinks are enabled.
+fn crate_link(crate_info: &ReportableCrate, hyperlinks: bool) -> Cow<'_, str> {
+    if hyperlinks {
+        let url = format!("https://crates.io/crates/{}/{}", crate_info.name, crate_info.version);
+        Cow::Owned(hyperlink(&url, &crate_info.name))
+    } else {
+        Cow::Borrowed(&crate_info.name)
+    }
+}
+
+/// Wrap text in an OSC 8 escape sequence so terminals render it as a link to `url`.
+fn hyperlink(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\")
+}
+
+/// Whether the terminal is known to render OSC 8 hyperlinks.
+///
+/// Terminals which don't understand the escape sequences may print them verbatim, so only those which
+/// identify themselves through their environment are trusted. Setting `FORCE_HYPERLINK` overrides the detection.
+#[must_use]
+pub fn terminal_supports_hyperlinks() -> bool {
+    supports_hyperlinks(|name| std::env::var(name).ok())
+}
+
+fn supports_hyperlinks(var: impl Fn(&str) -> Option<String>) -> bool {
+    if let Some(force) = var("FORCE_HYPERLINK") {
+        return force != "0";
+    }
+
+    if var("CI").is_some() || var("TERM").as_deref() == Some("dumb") {
+        return false;
+    }
+
+    if var("DOMTERM").is_some() || var("WT_SESSION").is_some() || var("KONSOLE_VERSION").is_some() {
+        return true;
+    }
+
+    // GNOME Terminal and other VTE-based terminals support hyperlinks as of VTE 0.50
+    if var("VTE_VERSION").and_then(|v| v.parse::<u32>().ok()).is_some_and(|v| v >= 5000) {
+        return true;
+    }
+
+    matches!(var("TERM_PROGRAM").as_deref(), Some("Hyper" | "iTerm.app" | "terminology" | "WezTerm" | "vscode" | "ghostty"))
+        || matches!(var("TERM").as_deref(), Some("xterm-kitty" | "alacritty" | "foot" | "xterm-ghostty"))
+}
+
 /// Get the terminal width, defaulting to 80 if not detectable
 fn get_terminal_width() -> usize {
     terminal_size().map_or(80, |(Width(w), _)| w as usize)
@@ -235,6 +482,7 @@ mod tests {
             available_points: 1,
             awarded_points: 1,
             score: 100.0,
+            unevaluated_points: 0,
         };
         let crates = vec![create_test_crate("test_crate", "1.0.0", Some(eval))];
         let mut output = String::new();
@@ -252,6 +500,7 @@ mod tests {
             available_points: 1,
             awarded_points: 0,
             score: 0.0,
+            unevaluated_points: 0,
         };
         let crates = vec![create_test_crate("test_crate", "1.0.0", Some(eval))];
         let mut output = String::new();
@@ -279,6 +528,7 @@ mod tests {
             available_points: 0,
             awarded_points: 0,
             score: 100.0,
+            unevaluated_points: 0,
         };
         let crates = vec![create_test_crate("test", "1.0.0", Some(eval))];
         let mut output = String::new();
@@ -288,6 +538,204 @@ mod tests {
         assert!(!output.contains("\x1b["));
     }
 
+    #[test]
+    fn test_generate_score_deltas() {
+        let eval = |score: f64| Appraisal {
+            risk: Risk::Low,
+            expression_outcomes: vec![],
+            available_points: 0,
+            awarded_points: 0,
+            score,
+            unevaluated_points: 0,
+        };
+        let mut riser = create_test_crate("riser", "1.0.0", Some(eval(90.0)));
+        riser.previous_score = Some(75.0);
+        let mut faller = create_test_crate("faller", "1.0.0", Some(eval(50.0)));
+        faller.previous_score = Some(60.0);
+
+        let mut output = String::new();
+        generate(&[riser, faller], false, &ConsoleOutputMode::full(), &mut output).unwrap();
+        assert!(output.contains("available points = 0) ▲ 15"));
+        assert!(output.contains("Biggest movers\n  riser v1.0.0 ▲ 15\n  faller v1.0.0 ▼ 10\n"));
+        assert!(!output.contains("Workspace grade"));
+    }
+
+    #[test]
+    fn test_generate_workspace_score() {
+        let mut crate_info = create_test_crate("dep", "1.0.0", Some(Appraisal::new(Risk::Low, vec![], 0, 0, 72.0)));
+        crate_info.weight = Some(1.0);
+
+        let mut output = String::new();
+        generate(&[crate_info], false, &ConsoleOutputMode::full(), &mut output).unwrap();
+        assert!(output.ends_with("\nWorkspace grade C (score 72 across 1 crate(s))\n"));
+    }
+
+    #[test]
+    fn test_generate_only_failures() {
+        let outcome = |name: &str, disposition| ExpressionOutcome::new(name.into(), "".into(), disposition);
+        let failing = create_test_crate(
+            "failing",
+            "1.0.0",
+            Some(Appraisal::new(
+                Risk::Low,
+                vec![outcome("tested", ExpressionDisposition::True), outcome("audited", ExpressionDisposition::False)],
+                2,
+                1,
+                90.0,
+            )),
+        );
+        let risky = create_test_crate("risky", "1.0.0", Some(Appraisal::new(Risk::Medium, vec![], 0, 0, 60.0)));
+        let passing = create_test_crate(
+            "passing",
+            "1.0.0",
+            Some(Appraisal::new(Risk::Low, vec![outcome("tested", ExpressionDisposition::True)], 1, 1, 100.0)),
+        );
+        let unappraised = create_test_crate("unappraised", "1.0.0", None);
+
+        let mode = ConsoleOutputMode {
+            metrics: false,
+            only_failures: true,
+            ..ConsoleOutputMode::full()
+        };
+        let mut output = String::new();
+        generate(&[failing, risky, passing.clone(), unappraised], false, &mode, &mut output).unwrap();
+        assert!(output.contains("failing v1.0.0"), "{output}");
+        assert!(output.contains("audited"), "{output}");
+        assert!(!output.contains("tested"), "{output}");
+        assert!(output.contains("risky v1.0.0"), "{output}");
+        assert!(!output.contains("passing"), "{output}");
+        assert!(!output.contains("unappraised"), "{output}");
+
+        let mut output = String::new();
+        generate(&[passing], false, &mode, &mut output).unwrap();
+        assert_eq!(output, "No crates fail the policy\n");
+    }
+
+    #[test]
+    fn test_generate_explain_filters() {
+        static STARS_DEF: MetricDef = MetricDef {
+            name: "community.repo_stars",
+            description: "Stars",
+            category: MetricCategory::Community,
+            extractor: |_| None,
+            default_value: || None,
+        };
+
+        let make_crate = |name: &str| {
+            let mut crate_info = create_test_crate(name, "1.0.0", None);
+            crate_info.metrics.push(Metric::with_value(&STARS_DEF, MetricValue::UInt(42)));
+            crate_info
+        };
+
+        let mode = ConsoleOutputMode {
+            explain_crates: vec!["serde".into()],
+            explain_categories: vec![MetricCategory::Community],
+            ..ConsoleOutputMode::full()
+        };
+        let mut output = String::new();
+        generate(&[make_crate("tokio"), make_crate("serde")], false, &mode, &mut output).unwrap();
+
+        assert!(output.starts_with("tokio v1.0.0 was not appraised\n\n═"), "{output}");
+        assert!(output.ends_with("serde v1.0.0 was not appraised\n\nCommunity\n  community.repo_stars : 42\n"), "{output}");
+        assert!(!output.contains("Metadata"), "{output}");
+        assert_eq!(output.matches("community.repo_stars").count(), 1, "{output}");
+    }
+
+    #[test]
+    fn test_generate_explain_shows_origins() {
+        let outcome = ExpressionOutcome::new("security".into(), "Security issues".into(), ExpressionDisposition::False)
+            .with_origin(Some("aprz.toml:7".into()));
+        let crates = vec![create_test_crate("risky", "1.0.0", Some(Appraisal::new(Risk::High, vec![outcome], 0, 0, 0.0)))];
+
+        let mut output = String::new();
+        generate(&crates, false, &ConsoleOutputMode::full(), &mut output).unwrap();
+        assert!(!output.contains("aprz.toml:7"), "{output}");
+
+        let mode = ConsoleOutputMode {
+            explain_crates: vec!["risky".into()],
+            ..ConsoleOutputMode::full()
+        };
+        let mut output = String::new();
+        generate(&crates, false, &mode, &mut output).unwrap();
+        assert!(output.contains("security [aprz.toml:7]\n"), "{output}");
+    }
+
+    #[test]
+    fn test_generate_category_layout() {
+        static STARS_DEF: MetricDef = MetricDef {
+            name: "community.repo_stars",
+            description: "Stars",
+            category: MetricCategory::Community,
+            extractor: |_| None,
+            default_value: || None,
+        };
+
+        let make_crate = |name: &str, stars| {
+            let mut crate_info = create_test_crate(name, "1.0.0", None);
+            crate_info.metrics.push(Metric::with_value(&STARS_DEF, MetricValue::UInt(stars)));
+            crate_info
+        };
+
+        let mode = ConsoleOutputMode {
+            reasons: false,
+            layout: ConsoleLayout::Category,
+            ..ConsoleOutputMode::full()
+        };
+        let mut output = String::new();
+        generate(&[make_crate("tokio", 30), make_crate("serde", 10)], false, &mode, &mut output).unwrap();
+
+        assert!(output.starts_with("tokio v1.0.0 was not appraised\nserde v1.0.0 was not appraised\n"), "{output}");
+        assert!(
+            output.ends_with(
+                "\nCommunity\n  tokio v1.0.0\n    community.repo_stars : 30\n  serde v1.0.0\n    community.repo_stars : 10\n"
+            ),
+            "{output}"
+        );
+        assert!(output.contains("\nMetadata\n  tokio v1.0.0\n    name    : tokio\n"), "{output}");
+    }
+
+    #[test]
+    fn test_generate_hyperlinks() {
+        static REPO_DEF: MetricDef = MetricDef {
+            name: "crate.repository",
+            description: "Repository",
+            category: MetricCategory::Metadata,
+            extractor: |_| None,
+            default_value: || None,
+        };
+
+        let mut crate_info = create_test_crate("serde", "1.0.0", None);
+        crate_info.metrics.push(Metric::with_value(&REPO_DEF, MetricValue::String("https://github.com/serde-rs/serde".into())));
+
+        let mode = ConsoleOutputMode { hyperlinks: true, ..ConsoleOutputMode::full() };
+        let mut output = String::new();
+        generate(core::slice::from_ref(&crate_info), false, &mode, &mut output).unwrap();
+        let name_link = "\x1b]8;;https://crates.io/crates/serde/1.0.0\x1b\\serde\x1b]8;;\x1b\\";
+        let repo_link = "\x1b]8;;https://github.com/serde-rs/serde\x1b\\https://github.com/serde-rs/serde\x1b]8;;\x1b\\";
+        assert!(output.starts_with(&format!("{name_link} v1.0.0 was not appraised\n")), "{output}");
+        assert!(output.contains(&format!(": {repo_link}\n")), "{output}");
+
+        let mut output = String::new();
+        generate(&[crate_info], false, &ConsoleOutputMode::full(), &mut output).unwrap();
+        assert!(!output.contains("\x1b]8;;"));
+    }
+
+    #[test]
+    fn test_supports_hyperlinks() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|(n, _)| *n == name).map(|(_, v)| (*v).to_string())
+        };
+
+        assert!(!supports_hyperlinks(env(&[])));
+        assert!(!supports_hyperlinks(env(&[("TERM", "xterm-256color")])));
+        assert!(supports_hyperlinks(env(&[("TERM_PROGRAM", "WezTerm")])));
+        assert!(supports_hyperlinks(env(&[("VTE_VERSION", "6003")])));
+        assert!(!supports_hyperlinks(env(&[("VTE_VERSION", "4205")])));
+        assert!(!supports_hyperlinks(env(&[("WT_SESSION", "1"), ("CI", "true")])));
+        assert!(supports_hyperlinks(env(&[("FORCE_HYPERLINK", "1"), ("TERM", "dumb")])));
+        assert!(!supports_hyperlinks(env(&[("FORCE_HYPERLINK", "0"), ("TERM_PROGRAM", "vscode")])));
+    }
+
     #[test]
     fn test_wrap_text_short() {
         let text = "short text";
@@ -188,6 +188,7 @@ mod tests {
             available_points: 2,
             awarded_points: 2,
             score: 100.0,
+            unevaluated_points: 0,
         };
         let crates = vec![create_test_crate("test_crate", "1.0.0", Some(eval))];
         let mut output = String::new();
@@ -219,6 +220,7 @@ mod tests {
             available_points: 1,
             awarded_points: 1,
             score: 100.0,
+            unevaluated_points: 0,
         };
         let crates = vec![create_test_crate("test,\"crate\"", "1.0.0", Some(eval))];
         let mut output = String::new();
@@ -236,6 +238,7 @@ mod tests {
             available_points: 1,
             awarded_points: 0,
             score: 0.0,
+            unevaluated_points: 0,
         };
         let crates = vec![create_test_crate("bad_crate", "1.0.0", Some(eval))];
         let mut output = String::new();
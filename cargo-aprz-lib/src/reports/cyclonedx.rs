@@ -0,0 +1,95 @@
+use super::ReportableCrate;
+use crate::Result;
+use chrono::{DateTime, Local, SecondsFormat};
+use core::fmt::Write;
+use serde_json::json;
+
+/// Generate a `CycloneDX` 1.5 JSON SBOM listing every crate as a library component.
+///
+/// Appraisal results are attached to each component as `cargo-aprz:*` properties so that
+/// tools consuming the SBOM (such as Dependency-Track) can surface them.
+pub fn generate<W: Write>(crates: &[ReportableCrate], timestamp: DateTime<Local>, writer: &mut W) -> Result<()> {
+    let components: Vec<_> = crates
+        .iter()
+        .map(|crate_info| {
+            let purl = format!("pkg:cargo/{}@{}", crate_info.name, crate_info.version);
+            let mut component = json!({
+                "type": "library",
+                "bom-ref": purl,
+                "name": crate_info.name.as_ref(),
+                "version": crate_info.version.to_string(),
+                "purl": purl,
+            });
+
+            if let Some(appraisal) = &crate_info.appraisal {
+                component["properties"] = json!([
+                    { "name": "cargo-aprz:risk", "value": appraisal.risk },
+                    { "name": "cargo-aprz:score", "value": format!("{:.0}", appraisal.score) },
+                    { "name": "cargo-aprz:awarded_points", "value": appraisal.awarded_points.to_string() },
+                    { "name": "cargo-aprz:available_points", "value": appraisal.available_points.to_string() },
+                ]);
+            }
+
+            component
+        })
+        .collect();
+
+    let bom = json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "version": 1,
+        "metadata": {
+            "timestamp": timestamp.to_rfc3339_opts(SecondsFormat::Secs, true),
+            "tools": {
+                "components": [{
+                    "type": "application",
+                    "name": "cargo-aprz",
+                    "version": env!("CARGO_PKG_VERSION"),
+                }],
+            },
+        },
+        "components": components,
+    });
+
+    write!(writer, "{}", serde_json::to_string_pretty(&bom)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::{Appraisal, Risk};
+    use chrono::TimeZone;
+    use std::sync::Arc;
+
+    fn make_crate(name: &str, version: &str, appraisal: Option<Appraisal>) -> ReportableCrate {
+        ReportableCrate::new(name.into(), Arc::new(version.parse().unwrap()), vec![], appraisal)
+    }
+
+    fn generate_value(crates: &[ReportableCrate]) -> serde_json::Value {
+        let mut output = String::new();
+        generate(crates, Local.with_ymd_and_hms(2024, 1, 15, 10, 30, 0).unwrap(), &mut output).unwrap();
+        serde_json::from_str(&output).unwrap()
+    }
+
+    #[test]
+    fn test_generate_empty() {
+        let bom = generate_value(&[]);
+        assert_eq!(bom["bomFormat"], "CycloneDX");
+        assert_eq!(bom["components"].as_array().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_generate_components_with_properties() {
+        let crates = vec![
+            make_crate("serde", "1.0.0", Some(Appraisal::new(Risk::Medium, vec![], 10, 5, 50.0))),
+            make_crate("anyhow", "1.0.75", None),
+        ];
+        let bom = generate_value(&crates);
+
+        assert_eq!(bom["components"][0]["purl"], "pkg:cargo/serde@1.0.0");
+        assert_eq!(bom["components"][0]["properties"][0]["value"], "medium");
+        assert_eq!(bom["components"][0]["properties"][1]["value"], "50");
+        assert!(bom["components"][1]["properties"].is_null());
+    }
+}
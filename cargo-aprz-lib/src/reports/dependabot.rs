@@ -0,0 +1,73 @@
+use super::ReportableCrate;
+use super::common::crate_names_with_risk;
+use crate::Result;
+use crate::expr::Risk;
+use core::fmt::Write;
+
+/// Generate a Dependabot `updates` entry that groups updates for risky crates.
+///
+/// Dependabot only supports one schedule per ecosystem and directory, so all Cargo updates are
+/// checked weekly, with high-risk and medium-risk crates gathered into their own groups so
+/// their updates are easy to spot and review.
+pub fn generate<W: Write>(crates: &[ReportableCrate], writer: &mut W) -> Result<()> {
+    writeln!(writer, "version: 2")?;
+    writeln!(writer, "updates:")?;
+    writeln!(writer, "  - package-ecosystem: \"cargo\"")?;
+    writeln!(writer, "    directory: \"/\"")?;
+    writeln!(writer, "    schedule:")?;
+    writeln!(writer, "      interval: \"weekly\"")?;
+
+    let groups: Vec<_> = [("high-risk-crates", Risk::High), ("medium-risk-crates", Risk::Medium)]
+        .into_iter()
+        .map(|(group, risk)| (group, crate_names_with_risk(crates, risk)))
+        .filter(|(_, names)| !names.is_empty())
+        .collect();
+
+    if !groups.is_empty() {
+        writeln!(writer, "    groups:")?;
+        for (group, names) in groups {
+            writeln!(writer, "      {group}:")?;
+            writeln!(writer, "        patterns:")?;
+            for name in names {
+                writeln!(writer, "          - \"{name}\"")?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::Appraisal;
+    use std::sync::Arc;
+
+    fn make_crate(name: &str, risk: Risk) -> ReportableCrate {
+        ReportableCrate::new(
+            name.into(),
+            Arc::new("1.0.0".parse().unwrap()),
+            vec![],
+            Some(Appraisal::new(risk, vec![], 10, 0, 0.0)),
+        )
+    }
+
+    #[test]
+    fn test_generate_no_risky_crates() {
+        let mut output = String::new();
+        generate(&[make_crate("good", Risk::Low)], &mut output).unwrap();
+        assert!(output.contains("interval: \"weekly\""));
+        assert!(!output.contains("groups:"));
+    }
+
+    #[test]
+    fn test_generate_groups_by_risk() {
+        let crates = vec![make_crate("bad", Risk::High), make_crate("meh", Risk::Medium), make_crate("good", Risk::Low)];
+        let mut output = String::new();
+        generate(&crates, &mut output).unwrap();
+
+        assert!(output.contains("      high-risk-crates:\n        patterns:\n          - \"bad\"\n"));
+        assert!(output.contains("      medium-risk-crates:\n        patterns:\n          - \"meh\"\n"));
+        assert!(!output.contains("\"good\""));
+    }
+}
@@ -1,3 +1,4 @@
+use super::risk_matrix::likelihood_str;
 use super::{ReportableCrate, common};
 use crate::Result;
 use crate::expr::{Appraisal, Risk};
@@ -23,18 +24,7 @@ pub fn generate<W: Write>(crates: &[ReportableCrate], writer: &mut W) -> Result<
         .set_background_color(Color::RGB(0x00FE_D7AA))
         .set_align(FormatAlign::Left);
     let left_align_format = Format::new().set_align(FormatAlign::Left);
-    let low_risk_format = Format::new()
-        .set_background_color(Color::RGB(0x00C8_E6C9))
-        .set_font_color(Color::RGB(0x002E_7D32))
-        .set_bold();
-    let medium_risk_format = Format::new()
-        .set_background_color(Color::RGB(0x00FF_F9C4))
-        .set_font_color(Color::RGB(0x00F5_7F17))
-        .set_bold();
-    let high_risk_format = Format::new()
-        .set_background_color(Color::RGB(0x00FF_CDD2))
-        .set_font_color(Color::RGB(0x00C6_2828))
-        .set_bold();
+    let risk_formats = RiskFormats::new();
 
     // Write crate names as column headers (starting from column B)
     for (col_idx, crate_info) in crates.iter().enumerate() {
@@ -56,29 +46,8 @@ pub fn generate<W: Write>(crates: &[ReportableCrate], writer: &mut W) -> Result<
     let mut row = 1;
 
     // Add appraisal rows if any crate has one
-    let has_appraisals = crates.iter().any(|c| c.appraisal.is_some());
-    if has_appraisals {
-        // Result row with colored cells
-        worksheet.write_string_with_format(row, 0, "Appraisals", &bold_format)?;
-        for (col_idx, crate_info) in crates.iter().enumerate() {
-            if let Some(eval) = &crate_info.appraisal {
-                let value = common::format_appraisal_status(eval);
-                let format = match eval.risk {
-                    Risk::Low => &low_risk_format,
-                    Risk::Medium => &medium_risk_format,
-                    Risk::High => &high_risk_format,
-                };
-                #[expect(clippy::cast_possible_truncation, reason = "Column index limited by Excel's u16 column limit")]
-                worksheet.write_string_with_format(row, (col_idx + 1) as u16, value, format)?;
-            }
-        }
-        row += 1;
-
-        // Reasons row
-        worksheet.write_string_with_format(row, 0, "Reasons", &bold_format)?;
-        write_eval_row(worksheet, row, crates, |eval| common::join_with(
-            eval.expression_outcomes.iter().map(common::outcome_icon_name), "; "))?;
-        row += 1;
+    if crates.iter().any(|c| c.appraisal.is_some()) {
+        row = write_appraisal_rows(worksheet, row, crates, &bold_format, &risk_formats)?;
 
         // Add blank row after evaluation
         row += 1;
@@ -90,10 +59,19 @@ pub fn generate<W: Write>(crates: &[ReportableCrate], writer: &mut W) -> Result<
             // Write category header (uppercase and bold with background color)
             worksheet.write_string_with_format(row, 0, category.as_uppercase_str(), &category_format)?;
 
-            // Fill the rest of the category row with the same background color
-            #[expect(clippy::cast_possible_truncation, reason = "Column count is limited by Excel's u16 column limit")]
-            for c in 1..=crates.len() as u16 {
-                worksheet.write_blank(row, c, &category_format)?;
+            // Fill the rest of the category row with each crate's score in the category, colored after its risk band
+            for (col_idx, crate_info) in crates.iter().enumerate() {
+                #[expect(clippy::cast_possible_truncation, reason = "Column index limited by Excel's u16 column limit")]
+                let col = (col_idx + 1) as u16;
+                match crate_info.category_scores.get(&category) {
+                    Some(category_score) => {
+                        let format = risk_formats.for_risk(category_score.risk);
+                        worksheet.write_string_with_format(row, col, common::format_category_score(category_score), format)?;
+                    }
+                    None => {
+                        worksheet.write_blank(row, col, &category_format)?;
+                    }
+                }
             }
 
             row += 1;
@@ -122,6 +100,8 @@ pub fn generate<W: Write>(crates: &[ReportableCrate], writer: &mut W) -> Result<
     // Auto-fit all columns
     worksheet.autofit();
 
+    write_movers_sheet(&mut workbook, crates, &bold_format)?;
+
     // Write workbook to output
     let data = workbook.save_to_buffer()?;
     writer.write_all(&data)?;
@@ -129,6 +109,142 @@ pub fn generate<W: Write>(crates: &[ReportableCrate], writer: &mut W) -> Result<
     Ok(())
 }
 
+/// The formats of cells colored after a risk level.
+struct RiskFormats {
+    low: Format,
+    medium: Format,
+    high: Format,
+}
+
+impl RiskFormats {
+    fn new() -> Self {
+        Self {
+            low: Format::new()
+                .set_background_color(Color::RGB(0x00C8_E6C9))
+                .set_font_color(Color::RGB(0x002E_7D32))
+                .set_bold(),
+            medium: Format::new()
+                .set_background_color(Color::RGB(0x00FF_F9C4))
+                .set_font_color(Color::RGB(0x00F5_7F17))
+                .set_bold(),
+            high: Format::new()
+                .set_background_color(Color::RGB(0x00FF_CDD2))
+                .set_font_color(Color::RGB(0x00C6_2828))
+                .set_bold(),
+        }
+    }
+
+    const fn for_risk(&self, risk: Risk) -> &Format {
+        match risk {
+            Risk::Low => &self.low,
+            Risk::Medium => &self.medium,
+            Risk::High => &self.high,
+        }
+    }
+}
+
+/// Write the appraisal rows starting at the given row, returning the row following them.
+#[expect(unused_results, reason = "rust_xlsxwriter methods return &mut Worksheet for chaining")]
+fn write_appraisal_rows(
+    worksheet: &mut rust_xlsxwriter::Worksheet,
+    mut row: u32,
+    crates: &[ReportableCrate],
+    bold_format: &Format,
+    risk_formats: &RiskFormats,
+) -> Result<u32> {
+    // Result row with colored cells
+    worksheet.write_string_with_format(row, 0, "Appraisals", bold_format)?;
+    for (col_idx, crate_info) in crates.iter().enumerate() {
+        if let Some(eval) = &crate_info.appraisal {
+            let value = common::format_appraisal_status(eval);
+            let format = risk_formats.for_risk(eval.risk);
+            #[expect(clippy::cast_possible_truncation, reason = "Column index limited by Excel's u16 column limit")]
+            worksheet.write_string_with_format(row, (col_idx + 1) as u16, value, format)?;
+        }
+    }
+    row += 1;
+
+    // Score change row, when scores from a previous run are known
+    if crates.iter().any(|c| c.score_delta().is_some()) {
+        worksheet.write_string_with_format(row, 0, "Score Change", bold_format)?;
+        for (col_idx, crate_info) in crates.iter().enumerate() {
+            if let Some(delta) = crate_info.score_delta() {
+                let value = common::format_score_delta(delta).unwrap_or_else(|| "=".to_string());
+                #[expect(clippy::cast_possible_truncation, reason = "Column index limited by Excel's u16 column limit")]
+                worksheet.write_string(row, (col_idx + 1) as u16, value)?;
+            }
+        }
+        row += 1;
+    }
+
+    // Likelihood and impact rows, when crates were placed on a risk matrix
+    if crates.iter().any(|c| c.impact.is_some()) {
+        worksheet.write_string_with_format(row, 0, "Likelihood", bold_format)?;
+        worksheet.write_string_with_format(row + 1, 0, "Impact", bold_format)?;
+        for (col_idx, crate_info) in crates.iter().enumerate() {
+            if let Some(impact) = crate_info.impact
+                && let Some(eval) = &crate_info.appraisal
+            {
+                #[expect(clippy::cast_possible_truncation, reason = "Column index limited by Excel's u16 column limit")]
+                let col = (col_idx + 1) as u16;
+                worksheet.write_string(row, col, likelihood_str(eval.risk))?;
+                worksheet.write_string(row + 1, col, impact.as_str())?;
+            }
+        }
+        row += 2;
+    }
+
+    // MSRV row, when the workspace declares one
+    if crates.iter().any(|c| c.workspace_msrv.is_some()) {
+        worksheet.write_string_with_format(row, 0, "MSRV", bold_format)?;
+        for (col_idx, crate_info) in crates.iter().enumerate() {
+            if let Some(msrv) = crate_info.msrv_comparison() {
+                #[expect(clippy::cast_possible_truncation, reason = "Column index limited by Excel's u16 column limit")]
+                worksheet.write_string(row, (col_idx + 1) as u16, msrv.to_string())?;
+            }
+        }
+        row += 1;
+    }
+
+    // Reachability row, when appraising workspace dependencies
+    if crates.iter().any(|c| c.reachability.is_some()) {
+        worksheet.write_string_with_format(row, 0, "Reachability", bold_format)?;
+        for (col_idx, crate_info) in crates.iter().enumerate() {
+            if let Some(reachability) = crate_info.reachability {
+                #[expect(clippy::cast_possible_truncation, reason = "Column index limited by Excel's u16 column limit")]
+                worksheet.write_string(row, (col_idx + 1) as u16, reachability.as_str())?;
+            }
+        }
+        row += 1;
+    }
+
+    // Reasons row
+    worksheet.write_string_with_format(row, 0, "Reasons", bold_format)?;
+    write_eval_row(worksheet, row, crates, |eval| common::join_with(
+        eval.expression_outcomes.iter().map(common::outcome_icon_name), "; "))?;
+    row += 1;
+
+    Ok(row)
+}
+
+/// Add a sheet listing the crates whose scores changed the most since the baseline, if any did.
+#[expect(unused_results, reason = "rust_xlsxwriter methods return &mut Worksheet for chaining")]
+fn write_movers_sheet(workbook: &mut Workbook, crates: &[ReportableCrate], bold_format: &Format) -> Result<()> {
+    let movers = common::biggest_movers(crates, common::MAX_MOVERS);
+    if !movers.is_empty() {
+        let movers_sheet = workbook.add_worksheet().set_name("Biggest Movers")?;
+        movers_sheet.write_string_with_format(0, 0, "Crate", bold_format)?;
+        movers_sheet.write_string_with_format(0, 1, "Score Change", bold_format)?;
+        for (crate_row, (crate_info, delta)) in (1..).zip(movers) {
+            movers_sheet.write_string(crate_row, 0, format!("{} v{}", crate_info.name, crate_info.version))?;
+            movers_sheet.write_number(crate_row, 1, delta.round())?;
+        }
+        movers_sheet.autofit();
+    }
+
+    Ok(())
+}
+
 #[expect(unused_results, reason = "rust_xlsxwriter methods return &mut Worksheet for chaining")]
 #[expect(clippy::cast_precision_loss, reason = "Intentional conversion to f64 for Excel output")]
 fn write_metric_value(
@@ -246,6 +362,35 @@ mod tests {
         assert_eq!(&output[0..2], b"PK");
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetSystemTimePreciseAsFileTime (rust_xlsxwriter)")]
+    fn test_generate_with_score_deltas() {
+        let eval = Appraisal {
+            risk: Risk::Medium,
+            expression_outcomes: vec![],
+            available_points: 10,
+            awarded_points: 6,
+            score: 60.0,
+            unevaluated_points: 0,
+        };
+        let mut crate_info = create_test_crate("test_crate", "1.0.0", Some(eval));
+        crate_info.previous_score = Some(80.0);
+        let mut output = Vec::new();
+        generate(&[crate_info], &mut output).unwrap();
+        assert_eq!(&output[0..2], b"PK");
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetSystemTimePreciseAsFileTime (rust_xlsxwriter)")]
+    fn test_generate_with_impact() {
+        let eval = Appraisal::new(Risk::High, vec![], 10, 1, 10.0);
+        let mut crate_info = create_test_crate("test_crate", "1.0.0", Some(eval));
+        crate_info.impact = Some(crate::reports::Impact::Medium);
+        let mut output = Vec::new();
+        generate(&[crate_info], &mut output).unwrap();
+        assert_eq!(&output[0..2], b"PK");
+    }
+
     #[test]
     #[cfg_attr(miri, ignore = "Miri cannot call GetSystemTimePreciseAsFileTime (rust_xlsxwriter)")]
     fn test_generate_single_crate_with_evaluation() {
@@ -255,6 +400,7 @@ mod tests {
             available_points: 1,
             awarded_points: 1,
             score: 100.0,
+            unevaluated_points: 0,
         };
         let crates = vec![create_test_crate("test_crate", "1.0.0", Some(eval))];
         let mut output = Vec::new();
@@ -288,6 +434,7 @@ mod tests {
             available_points: 1,
             awarded_points: 0,
             score: 0.0,
+            unevaluated_points: 0,
         };
         let crates = vec![create_test_crate("bad_crate", "1.0.0", Some(eval))];
         let mut output = Vec::new();
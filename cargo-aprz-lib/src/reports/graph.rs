@@ -0,0 +1,113 @@
+use super::ReportableCrate;
+use crate::Result;
+use crate::expr::Risk;
+use crate::facts::CrateRef;
+use core::fmt::Write;
+
+/// The packages whose dependencies were appraised, along with the edges of the dependency graph linking them.
+#[derive(Debug, Clone, Default)]
+pub struct DependencyGraph {
+    /// Workspace packages at the top of the graph, which aren't appraised themselves
+    pub roots: Vec<CrateRef>,
+
+    /// Links from a package to each of its dependencies
+    pub edges: Vec<(CrateRef, CrateRef)>,
+}
+
+/// Generate a `GraphViz` DOT description of the dependency graph.
+///
+/// Each appraised crate is filled with the color of its risk and labeled with its score, so the
+/// graph can be rendered with `dot -Tsvg` and embedded in documentation.
+pub fn generate<W: Write>(crates: &[ReportableCrate], graph: &DependencyGraph, writer: &mut W) -> Result<()> {
+    writeln!(writer, "digraph dependencies {{")?;
+    writeln!(writer, "    rankdir=LR;")?;
+    writeln!(writer, "    node [shape=box, style=\"rounded,filled\", fontname=\"Helvetica\"];")?;
+
+    for root in &graph.roots {
+        writeln!(writer, "    {} [label={}, fillcolor=\"white\", penwidth=2];", quote(&root.to_string()), quote(root.name()))?;
+    }
+
+    for crate_info in crates {
+        let id = format!("{}@{}", crate_info.name, crate_info.version);
+        let (label, color) = crate_info.appraisal.as_ref().map_or_else(
+            || (format!("{}\n{}\nnot appraised", crate_info.name, crate_info.version), "lightgray"),
+            |appraisal| {
+                let color = match appraisal.risk {
+                    Risk::Low => "palegreen",
+                    Risk::Medium => "gold",
+                    Risk::High => "salmon",
+                };
+                (format!("{}\n{}\nscore {:.0}", crate_info.name, crate_info.version, appraisal.score), color)
+            },
+        );
+
+        writeln!(writer, "    {} [label={}, fillcolor=\"{color}\"];", quote(&id), quote(&label))?;
+    }
+
+    for (from, to) in &graph.edges {
+        writeln!(writer, "    {} -> {};", quote(&from.to_string()), quote(&to.to_string()))?;
+    }
+
+    writeln!(writer, "}}")?;
+    Ok(())
+}
+
+/// Quote a string as a DOT identifier, escaping what needs to be.
+fn quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::Appraisal;
+    use std::sync::Arc;
+
+    fn make_crate(name: &str, appraisal: Option<Appraisal>) -> ReportableCrate {
+        ReportableCrate::new(name.into(), Arc::new("1.0.0".parse().unwrap()), vec![], appraisal)
+    }
+
+    #[test]
+    fn test_generate_graph() {
+        let crates = [
+            make_crate("serde", Some(Appraisal::new(Risk::Low, vec![], 10, 9, 91.6))),
+            make_crate("leftpad", Some(Appraisal::new(Risk::High, vec![], 10, 2, 20.0))),
+            make_crate("unknown", None),
+        ];
+        let app: CrateRef = "app@0.1.0".parse().unwrap();
+        let serde: CrateRef = "serde@1.0.0".parse().unwrap();
+        let leftpad: CrateRef = "leftpad@1.0.0".parse().unwrap();
+        let graph = DependencyGraph {
+            roots: vec![app.clone()],
+            edges: vec![(app.clone(), serde.clone()), (app, leftpad.clone()), (serde, leftpad)],
+        };
+
+        let mut output = String::new();
+        generate(&crates, &graph, &mut output).unwrap();
+
+        assert!(output.starts_with("digraph dependencies {\n"), "{output}");
+        assert!(output.contains(r#""app@0.1.0" [label="app", fillcolor="white", penwidth=2];"#), "{output}");
+        assert!(output.contains(r#""serde@1.0.0" [label="serde\n1.0.0\nscore 92", fillcolor="palegreen"];"#), "{output}");
+        assert!(output.contains(r#""leftpad@1.0.0" [label="leftpad\n1.0.0\nscore 20", fillcolor="salmon"];"#), "{output}");
+        assert!(output.contains(r#""unknown@1.0.0" [label="unknown\n1.0.0\nnot appraised", fillcolor="lightgray"];"#), "{output}");
+        assert!(output.contains(r#""serde@1.0.0" -> "leftpad@1.0.0";"#), "{output}");
+        assert!(output.ends_with("}\n"), "{output}");
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote(r#"a"b\c"#), r#""a\"b\\c""#);
+        assert_eq!(quote("a\nb"), r#""a\nb""#);
+    }
+}
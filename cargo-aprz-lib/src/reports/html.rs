@@ -1,3 +1,4 @@
+use super::risk_matrix::{Impact, risk_matrix};
 use super::{ReportableCrate, common};
 use crate::Result;
 use crate::expr::{ExpressionDisposition, Risk};
@@ -76,6 +77,10 @@ pub fn generate<W: Write>(crates: &[ReportableCrate], timestamp: DateTime<Local>
     // Summary section
     let default_visible_anchor = if has_appraisals && total > 1 {
         write_summary(writer, total, &high_risk_crates, &medium_risk_crates, &low_risk_crates, &not_evaluated_crates)?;
+        write_biggest_movers(writer, crates)?;
+        write_workspace_score(writer, crates)?;
+        write_risk_matrix(writer, crates)?;
+        write_crate_table(writer, crates)?;
         // The first pill's crate should be visible by default
         high_risk_crates.first()
             .or_else(|| medium_risk_crates.first())
@@ -121,7 +126,15 @@ pub fn generate<W: Write>(crates: &[ReportableCrate], timestamp: DateTime<Local>
         }
         for cat in &crate_categories {
             let active = if tab_index == 0 { " active" } else { "" };
-            writeln!(writer, "          <button class=\"tab-btn{active}\" data-tab=\"{card_id}-{cat}\" onclick=\"switchTab(this)\">{cat}</button>")?;
+            let score = crate_info.category_scores.get(cat).map_or_else(String::new, |category_score| {
+                format!(
+                    " <span class=\"risk-badge {}\" title=\"{}\">{:.0}</span>",
+                    risk_class(category_score.risk),
+                    common::format_category_score(category_score),
+                    category_score.score
+                )
+            });
+            writeln!(writer, "          <button class=\"tab-btn{active}\" data-tab=\"{card_id}-{cat}\" onclick=\"switchTab(this)\">{cat}{score}</button>")?;
             tab_index += 1;
         }
         writeln!(writer, "        </div>")?;
@@ -254,6 +267,29 @@ fn write_styles<W: Write>(writer: &mut W) -> Result<()> {
 
     // Appraisal score
     writeln!(writer, "    .appraisal-score {{ font-size: 14px; font-weight: 600; white-space: nowrap; color: var(--text-secondary); }}")?;
+    writeln!(writer, "    .score-delta {{ font-weight: 700; }}")?;
+    writeln!(writer, "    .score-delta.up {{ color: var(--risk-low-text); background: var(--risk-low); padding: 1px 6px; border-radius: 8px; }}")?;
+    writeln!(writer, "    .score-delta.down {{ color: var(--risk-high-text); background: var(--risk-high); padding: 1px 6px; border-radius: 8px; }}")?;
+    writeln!(writer, "    .movers {{ background: var(--card-bg); border-radius: 10px; padding: 14px 20px; box-shadow: var(--shadow); border: 1px solid var(--border-color); margin-bottom: 12px; }}")?;
+    writeln!(writer, "    .movers h2 {{ font-size: 12px; text-transform: uppercase; letter-spacing: 0.5px; margin: 0 0 6px 0; }}")?;
+    writeln!(writer, "    .movers ul {{ margin: 0; padding-left: 20px; font-size: 14px; }}")?;
+    writeln!(writer, "    .workspace-score {{ background: var(--card-bg); border-radius: 10px; padding: 14px 20px; box-shadow: var(--shadow); border: 1px solid var(--border-color); margin-bottom: 12px; font-size: 14px; }}")?;
+    writeln!(writer, "    .risk-matrix {{ background: var(--card-bg); border-radius: 10px; padding: 14px 20px; box-shadow: var(--shadow); border: 1px solid var(--border-color); margin-bottom: 12px; }}")?;
+    writeln!(writer, "    .risk-matrix h2 {{ font-size: 12px; text-transform: uppercase; letter-spacing: 0.5px; margin: 0 0 6px 0; }}")?;
+    writeln!(writer, "    .risk-matrix td.cell {{ width: 30%; font-size: 13px; }}")?;
+    writeln!(writer, "    .risk-matrix td.cell.low {{ background: var(--risk-low); }}")?;
+    writeln!(writer, "    .risk-matrix td.cell.medium {{ background: var(--risk-medium); }}")?;
+    writeln!(writer, "    .risk-matrix td.cell.high {{ background: var(--risk-high); }}")?;
+    writeln!(writer, "    .risk-matrix td.cell a {{ color: inherit; margin-right: 8px; }}")?;
+    writeln!(writer, "    .crate-table {{ background: var(--card-bg); border-radius: 10px; padding: 14px 20px; box-shadow: var(--shadow); border: 1px solid var(--border-color); margin-bottom: 12px; }}")?;
+    writeln!(writer, "    .crate-table summary {{ font-size: 12px; text-transform: uppercase; letter-spacing: 0.5px; cursor: pointer; font-weight: 700; }}")?;
+    writeln!(writer, "    .table-controls {{ display: flex; gap: 8px; margin: 8px 0; }}")?;
+    writeln!(writer, "    .table-controls input {{ flex: 1; padding: 6px 10px; font-size: 14px; border: 1px solid var(--border-color); border-radius: 6px; background: var(--card-bg); color: var(--text-color); }}")?;
+    writeln!(writer, "    .table-controls button {{ background: var(--hover-bg); border: 1px solid var(--border-color); border-radius: 6px; padding: 6px 12px; font-size: 12px; font-weight: 600; color: var(--text-secondary); cursor: pointer; }}")?;
+    writeln!(writer, "    .table-controls button:hover {{ color: var(--text-color); }}")?;
+    writeln!(writer, "    th.sortable {{ cursor: pointer; user-select: none; }}")?;
+    writeln!(writer, "    th.sortable.asc::after {{ content: ' \u{25B2}'; }}")?;
+    writeln!(writer, "    th.sortable.desc::after {{ content: ' \u{25BC}'; }}")?;
 
     // Tables within cards
     writeln!(writer, "    .card-section {{ padding: 0; }}")?;
@@ -510,6 +546,141 @@ fn write_risk_crate_list<W: Write>(writer: &mut W, class: &str, title: &str, cra
     Ok(())
 }
 
+/// Render a score change as an arrow badge, or nothing if the rounded score didn't change.
+fn score_delta_span(delta: f64) -> String {
+    common::format_score_delta(delta).map_or_else(String::new, |text| {
+        let class = if delta > 0.0 { "up" } else { "down" };
+        format!(" <span class=\"score-delta {class}\">{text}</span>")
+    })
+}
+
+/// List the crates whose score changed the most since the previous run.
+fn write_biggest_movers<W: Write>(writer: &mut W, crates: &[ReportableCrate]) -> Result<()> {
+    let movers = common::biggest_movers(crates, common::MAX_MOVERS);
+    if movers.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(writer, "  <div class=\"movers\">")?;
+    writeln!(writer, "    <h2>Biggest Movers</h2>")?;
+    writeln!(writer, "    <ul>")?;
+    for (crate_info, delta) in movers {
+        writeln!(
+            writer,
+            "      <li>{} v{}{}</li>",
+            html_escape(&crate_info.name),
+            html_escape(&crate_info.version.to_string()),
+            score_delta_span(delta)
+        )?;
+    }
+    writeln!(writer, "    </ul>")?;
+    writeln!(writer, "  </div>")?;
+    Ok(())
+}
+
+/// Show the overall grade of the workspace's dependencies.
+fn write_workspace_score<W: Write>(writer: &mut W, crates: &[ReportableCrate]) -> Result<()> {
+    if let Some(workspace) = common::workspace_score(crates) {
+        writeln!(
+            writer,
+            "  <div class=\"workspace-score\">{}</div>",
+            html_escape(&common::format_workspace_score(&workspace))
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Lay the crates out on a grid of impact (rows, highest first) against likelihood (columns).
+fn write_risk_matrix<W: Write>(writer: &mut W, crates: &[ReportableCrate]) -> Result<()> {
+    let Some(matrix) = risk_matrix(crates) else {
+        return Ok(());
+    };
+
+    writeln!(writer, "  <div class=\"risk-matrix\">")?;
+    writeln!(writer, "    <h2>Risk Matrix</h2>")?;
+    writeln!(writer, "    <table>")?;
+    writeln!(writer, "      <tr><th>Impact / Likelihood</th><th>Low</th><th>Medium</th><th>High</th></tr>")?;
+    for impact in Impact::ALL.into_iter().rev() {
+        writeln!(writer, "      <tr>")?;
+        let label = match impact {
+            Impact::Low => "Low",
+            Impact::Medium => "Medium",
+            Impact::High => "High",
+        };
+        writeln!(writer, "        <td>{label}</td>")?;
+        for (likelihood, cell) in [Risk::Low, Risk::Medium, Risk::High].into_iter().zip(&matrix[impact as usize]) {
+            // Cells are shaded by the combined severity of their impact and likelihood
+            let severity = match impact as usize + likelihood as usize {
+                0 | 1 => "low",
+                2 => "medium",
+                _ => "high",
+            };
+            write!(writer, "        <td class=\"cell {severity}\">")?;
+            for crate_info in cell {
+                let version = crate_info.version.to_string();
+                let anchor = crate_anchor_id(&crate_info.name, &version);
+                write!(
+                    writer,
+                    "<a href=\"#{anchor}\" onclick=\"selectCrate('{anchor}', this, event)\">{} v{}</a>",
+                    html_escape(&crate_info.name),
+                    html_escape(&version)
+                )?;
+            }
+            writeln!(writer, "</td>")?;
+        }
+        writeln!(writer, "      </tr>")?;
+    }
+    writeln!(writer, "    </table>")?;
+    writeln!(writer, "  </div>")?;
+    Ok(())
+}
+
+/// List every crate in a table which can be filtered, sorted by any column, and exported as CSV.
+fn write_crate_table<W: Write>(writer: &mut W, crates: &[ReportableCrate]) -> Result<()> {
+    writeln!(writer, "  <details class=\"crate-table\">")?;
+    writeln!(writer, "    <summary>All Crates</summary>")?;
+    writeln!(writer, "    <div class=\"table-controls\">")?;
+    writeln!(writer, "      <input type=\"search\" id=\"crate-search\" placeholder=\"Filter crates\" aria-label=\"Filter crates\" oninput=\"filterCrates(this.value)\">")?;
+    writeln!(writer, "      <button type=\"button\" onclick=\"exportCsv()\" title=\"Export the visible rows as CSV\">Export CSV</button>")?;
+    writeln!(writer, "    </div>")?;
+    writeln!(writer, "    <table id=\"crate-table\">")?;
+    writeln!(writer, "      <thead><tr><th class=\"sortable\" onclick=\"sortTable(this, 'name', false)\">Crate</th><th>Version</th><th class=\"sortable\" onclick=\"sortTable(this, 'risk', true)\">Risk</th><th class=\"sortable\" onclick=\"sortTable(this, 'score', true)\">Score</th></tr></thead>")?;
+    writeln!(writer, "      <tbody>")?;
+    for crate_info in crates {
+        let name = html_escape(&crate_info.name);
+        let version = crate_info.version.to_string();
+        let anchor = crate_anchor_id(&crate_info.name, &version);
+        // Risks are ranked numerically so sorting orders them by severity rather than alphabetically
+        let (rank, risk, score) = crate_info.appraisal.as_ref().map_or((0, "Not Evaluated", String::new()), |appraisal| {
+            let (rank, risk) = match appraisal.risk {
+                Risk::Low => (1, "Low"),
+                Risk::Medium => (2, "Medium"),
+                Risk::High => (3, "High"),
+            };
+            (rank, risk, common::format_score(appraisal))
+        });
+        writeln!(
+            writer,
+            "        <tr data-name=\"{name}\" data-risk=\"{rank}\" data-score=\"{score}\"><td><a href=\"#{anchor}\" onclick=\"selectCrate('{anchor}', this, event)\">{name}</a></td><td>{}</td><td>{risk}</td><td>{score}</td></tr>",
+            html_escape(&version)
+        )?;
+    }
+    writeln!(writer, "      </tbody>")?;
+    writeln!(writer, "    </table>")?;
+    writeln!(writer, "  </details>")?;
+    Ok(())
+}
+
+/// Class of the risk badge showing a risk level.
+const fn risk_class(risk: Risk) -> &'static str {
+    match risk {
+        Risk::Low => "low",
+        Risk::Medium => "medium",
+        Risk::High => "high",
+    }
+}
+
 fn write_crate_card_header<W: Write>(writer: &mut W, crate_info: &ReportableCrate) -> Result<()> {
     let risk_class = crate_info.appraisal.as_ref().map_or("", |a| match a.risk {
         Risk::Low => " risk-low",
@@ -531,10 +702,11 @@ fn write_crate_card_header<W: Write>(writer: &mut W, crate_info: &ReportableCrat
             Risk::High => ("high", "HIGH RISK"),
         };
         writeln!(writer, "        <span class=\"header-right\">")?;
+        let delta = crate_info.score_delta().map_or_else(String::new, score_delta_span);
         writeln!(
             writer,
-            "          <span class=\"appraisal-score\">score {:.0} · {}/{} points</span>",
-            appraisal.score, appraisal.awarded_points, appraisal.available_points
+            "          <span class=\"appraisal-score\">score {}{delta} · {}/{} points</span>",
+            common::format_score(appraisal), appraisal.awarded_points, appraisal.available_points
         )?;
         writeln!(writer, "          <span class=\"risk-badge {class}\">{label}</span>")?;
         writeln!(writer, "        </span>")?;
@@ -560,7 +732,15 @@ fn write_appraisal_table<W: Write>(writer: &mut W, appraisal: &crate::expr::Appr
             ExpressionDisposition::Failed(reason) => html_escape(reason),
         };
         writeln!(writer, "          <tr>")?;
-        writeln!(writer, "            <td>{}</td>", html_escape(&outcome.name))?;
+        match &outcome.origin {
+            Some(origin) => writeln!(
+                writer,
+                "            <td title=\"Defined in {}\">{}</td>",
+                html_escape(origin),
+                html_escape(&outcome.name)
+            )?,
+            None => writeln!(writer, "            <td>{}</td>", html_escape(&outcome.name))?,
+        }
         writeln!(writer, "            <td><span class=\"disposition {disp_class}\">{disp_label}</span></td>")?;
         writeln!(writer, "            <td>{detail}</td>")?;
         writeln!(writer, "          </tr>")?;
@@ -710,6 +890,7 @@ fn write_scripts<W: Write>(writer: &mut W, has_risk_lists: bool) -> Result<()> {
         writeln!(writer, "      }}")?;
         writeln!(writer, "      links.forEach(l => container.appendChild(l));")?;
         writeln!(writer, "    }}")?;
+        write_table_scripts(writer)?;
         writeln!(writer, "    function toggleRiskList(risk) {{")?;
         writeln!(writer, "      const lists = document.querySelectorAll('.risk-list');")?;
         writeln!(writer, "      if (risk === 'all') {{")?;
@@ -739,7 +920,43 @@ fn write_scripts<W: Write>(writer: &mut W, has_risk_lists: bool) -> Result<()> {
     Ok(())
 }
 
-fn html_escape(s: &str) -> String {
+/// Write the scripts filtering, sorting, and exporting the crate table.
+fn write_table_scripts<W: Write>(writer: &mut W) -> Result<()> {
+    writeln!(writer, "    function filterCrates(query) {{")?;
+    writeln!(writer, "      const q = query.trim().toLowerCase();")?;
+    writeln!(writer, "      const matches = el => (el.dataset.name || '').toLowerCase().includes(q);")?;
+    writeln!(writer, "      document.querySelectorAll('#crate-table tbody tr').forEach(r => r.style.display = matches(r) ? '' : 'none');")?;
+    writeln!(writer, "      document.querySelectorAll('.risk-list .crate-names a').forEach(a => a.style.display = matches(a) ? '' : 'none');")?;
+    writeln!(writer, "    }}")?;
+    writeln!(writer, "    function sortTable(th, key, numeric) {{")?;
+    writeln!(writer, "      const asc = !th.classList.contains('asc');")?;
+    writeln!(writer, "      th.closest('tr').querySelectorAll('th').forEach(h => h.classList.remove('asc', 'desc'));")?;
+    writeln!(writer, "      th.classList.add(asc ? 'asc' : 'desc');")?;
+    writeln!(writer, "      const body = th.closest('table').tBodies[0];")?;
+    writeln!(writer, "      const rows = Array.from(body.rows);")?;
+    writeln!(writer, "      rows.sort((a, b) => {{")?;
+    writeln!(writer, "        const x = a.dataset[key] || '', y = b.dataset[key] || '';")?;
+    writeln!(writer, "        const order = numeric ? parseFloat(x || '-1') - parseFloat(y || '-1') : x.localeCompare(y);")?;
+    writeln!(writer, "        return asc ? order : -order;")?;
+    writeln!(writer, "      }});")?;
+    writeln!(writer, "      rows.forEach(r => body.appendChild(r));")?;
+    writeln!(writer, "    }}")?;
+    writeln!(writer, "    function exportCsv() {{")?;
+    writeln!(writer, "      const quote = v => /[\",\\n]/.test(v) ? '\"' + v.replace(/\"/g, '\"\"') + '\"' : v;")?;
+    writeln!(writer, "      const table = document.getElementById('crate-table');")?;
+    writeln!(writer, "      const toLine = row => Array.from(row.cells).map(c => quote(c.textContent.trim())).join(',');")?;
+    writeln!(writer, "      const visible = Array.from(table.tBodies[0].rows).filter(r => r.style.display !== 'none');")?;
+    writeln!(writer, "      const csv = [table.tHead.rows[0], ...visible].map(toLine).join('\\n') + '\\n';")?;
+    writeln!(writer, "      const link = document.createElement('a');")?;
+    writeln!(writer, "      link.href = URL.createObjectURL(new Blob([csv], {{ type: 'text/csv' }}));")?;
+    writeln!(writer, "      link.download = 'crates.csv';")?;
+    writeln!(writer, "      link.click();")?;
+    writeln!(writer, "      URL.revokeObjectURL(link.href);")?;
+    writeln!(writer, "    }}")?;
+    Ok(())
+}
+
+pub(super) fn html_escape(s: &str) -> String {
     let mut result = String::with_capacity(s.len());
     for c in s.chars() {
         match c {
@@ -1108,7 +1325,8 @@ mod tests {
             Some(Appraisal::new(
                 Risk::Medium,
                 vec![
-                    ExpressionOutcome::new("ok_check".into(), "Passed check".into(), ExpressionDisposition::True),
+                    ExpressionOutcome::new("ok_check".into(), "Passed check".into(), ExpressionDisposition::True)
+                        .with_origin(Some("aprz.toml:12".into())),
                     ExpressionOutcome::new("bad_check".into(), "Failed check".into(), ExpressionDisposition::False),
                 ],
                 2, 1, 50.0,
@@ -1119,8 +1337,8 @@ mod tests {
 
         assert!(output.contains("PASSED"));
         assert!(output.contains("FAILED"));
-        assert!(output.contains("ok_check"));
-        assert!(output.contains("bad_check"));
+        assert!(output.contains("<td title=\"Defined in aprz.toml:12\">ok_check</td>"));
+        assert!(output.contains("<td>bad_check</td>"));
     }
 
     // --- crate with empty expression outcomes (appraisal but no appraisal tab) ---
@@ -1306,6 +1524,52 @@ mod tests {
         assert!(output.contains("id=\"crate-risky-crate-0.5.0\""));
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTimeZoneInformationForYear")]
+    fn test_generate_risk_matrix() {
+        let mut core_crate = create_test_crate("core_crate", "1.0.0", Some(Appraisal::new(Risk::High, vec![], 1, 0, 0.0)));
+        core_crate.impact = Some(Impact::High);
+        let mut leaf_crate = create_test_crate("leaf_crate", "1.0.0", Some(Appraisal::new(Risk::Low, vec![], 1, 1, 100.0)));
+        leaf_crate.impact = Some(Impact::Low);
+
+        let mut output = String::new();
+        generate(&[core_crate.clone(), leaf_crate.clone()], test_timestamp(), &mut output).unwrap();
+        assert!(output.contains("<h2>Risk Matrix</h2>"));
+        assert!(output.contains("<td class=\"cell high\"><a href=\"#crate-core-crate-1.0.0\""));
+        assert!(output.contains("<td class=\"cell low\"><a href=\"#crate-leaf-crate-1.0.0\""));
+
+        core_crate.impact = None;
+        leaf_crate.impact = None;
+        let mut output = String::new();
+        generate(&[core_crate, leaf_crate], test_timestamp(), &mut output).unwrap();
+        assert!(!output.contains("<h2>Risk Matrix</h2>"));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri cannot call GetTimeZoneInformationForYear")]
+    fn test_generate_crate_table() {
+        let crates = vec![
+            create_test_crate("risky_crate", "0.5.0", Some(Appraisal::new(Risk::High, vec![], 1, 0, 0.0))),
+            create_test_crate("safe_crate", "1.0.0", Some(Appraisal::new(Risk::Low, vec![], 1, 1, 100.0))),
+            create_test_crate("other_crate", "2.0.0", None),
+        ];
+        let mut output = String::new();
+        generate(&crates, test_timestamp(), &mut output).unwrap();
+
+        assert!(output.contains("<table id=\"crate-table\">"));
+        assert!(output.contains("oninput=\"filterCrates(this.value)\""));
+        assert!(output.contains("onclick=\"exportCsv()\""));
+        assert!(output.contains("onclick=\"sortTable(this, 'score', true)\""));
+        assert!(output.contains("<tr data-name=\"risky_crate\" data-risk=\"3\" data-score=\"0\">"));
+        assert!(output.contains("<tr data-name=\"other_crate\" data-risk=\"0\" data-score=\"\">"));
+        assert!(output.contains("function exportCsv()"));
+
+        // A single crate has no summary to filter
+        let mut output = String::new();
+        generate(&crates[..1], test_timestamp(), &mut output).unwrap();
+        assert!(!output.contains("crate-table\""));
+    }
+
     // --- special characters in crate name ---
 
     #[test]
@@ -1,55 +1,221 @@
+use super::risk_matrix::likelihood_str;
 use super::{ReportableCrate, common};
-use crate::Result;
-use crate::expr::ExpressionDisposition;
-use crate::metrics::MetricValue;
+use crate::expr::{ExpressionDisposition, Risk};
+use crate::facts::{ApiUsage, DataTimestamps};
+use crate::{HashMap, Result};
+use crate::metrics::{Metric, MetricValue, metrics_from_json};
+use chrono::{DateTime, Utc};
 use core::fmt::Write;
 use serde_json::json;
 
-#[expect(unused_results, reason = "HashMap::insert intentionally overwrites values")]
-pub fn generate<W: Write>(crates: &[ReportableCrate], writer: &mut W) -> Result<()> {
+pub fn generate<W: Write>(crates: &[ReportableCrate], api_usage: &[ApiUsage], writer: &mut W) -> Result<()> {
     let mut crate_data = Vec::with_capacity(crates.len());
     let mut buf = String::new();
 
     for crate_info in crates {
-        let mut crate_obj = serde_json::Map::new();
-        crate_obj.insert("name".into(), json!(crate_info.name));
-        crate_obj.insert("version".into(), json!(crate_info.version.to_string()));
-
-        if let Some(appraisal) = &crate_info.appraisal {
-            let mut eval_obj = serde_json::Map::new();
-            eval_obj.insert("result".into(), json!(common::format_appraisal_status(appraisal)));
-            eval_obj.insert("reasons".into(), json!(appraisal.expression_outcomes.iter()
-                .map(|o| {
-                    if let ExpressionDisposition::Failed(reason) = &o.disposition {
-                        format!("{} (failure to evaluate: {reason})", o.name)
-                    } else {
-                        o.name.to_string()
-                    }
-                })
-                .collect::<Vec<_>>()));
-            crate_obj.insert("appraisal".into(), json!(eval_obj));
-        }
-
-        let mut metrics_obj = serde_json::Map::new();
-        for metric in &crate_info.metrics {
-            if let Some(ref value) = metric.value {
-                let json_value = metric_value_to_json(value, &mut buf);
-                metrics_obj.insert(metric.name().into(), json_value);
-            }
-        }
-
-        crate_obj.insert("metrics".into(), json!(metrics_obj));
-        crate_data.push(json!(crate_obj));
+        crate_data.push(crate_to_json(crate_info, &mut buf));
     }
 
-    let output = json!({
+    let mut output = json!({
         "crates": crate_data
     });
 
+    if let Some(workspace) = common::workspace_score(crates) {
+        output["workspace"] = json!({
+            "score": workspace.score,
+            "grade": workspace.grade,
+            "crates": workspace.crates,
+        });
+    }
+
+    if !api_usage.is_empty() {
+        output["api_usage"] = json!(api_usage
+            .iter()
+            .map(|usage| json!({
+                "host": usage.host,
+                "requests": usage.requests,
+                "rate_limited": usage.rate_limited,
+                "remaining": usage.remaining,
+                "reset_at": usage.reset_at.map(|reset_at| reset_at.to_rfc3339()),
+            }))
+            .collect::<Vec<_>>());
+    }
+
     write!(writer, "{}", serde_json::to_string_pretty(&output)?)?;
     Ok(())
 }
 
+/// Build the JSON object describing a single crate, using `buf` as scratch space.
+#[expect(unused_results, reason = "HashMap::insert intentionally overwrites values")]
+fn crate_to_json(crate_info: &ReportableCrate, buf: &mut String) -> serde_json::Value {
+    let mut crate_obj = serde_json::Map::new();
+    crate_obj.insert("name".into(), json!(crate_info.name));
+    crate_obj.insert("version".into(), json!(crate_info.version.to_string()));
+
+    if let Some(appraisal) = &crate_info.appraisal {
+        let mut eval_obj = serde_json::Map::new();
+        eval_obj.insert("result".into(), json!(common::format_appraisal_status(appraisal)));
+        eval_obj.insert("score".into(), json!(appraisal.score));
+        eval_obj.insert("score_margin".into(), json!(appraisal.score_margin()));
+        eval_obj.insert("confidence".into(), json!(appraisal.confidence()));
+        if let Some(impact) = crate_info.impact {
+            eval_obj.insert("likelihood".into(), json!(likelihood_str(appraisal.risk)));
+            eval_obj.insert("impact".into(), json!(impact.as_str()));
+        }
+        eval_obj.insert("reasons".into(), json!(appraisal.expression_outcomes.iter()
+            .map(|o| {
+                if let ExpressionDisposition::Failed(reason) = &o.disposition {
+                    format!("{} (failure to evaluate: {reason})", o.name)
+                } else {
+                    o.name.to_string()
+                }
+            })
+            .collect::<Vec<_>>()));
+        crate_obj.insert("appraisal".into(), json!(eval_obj));
+    }
+
+    if let Some(msrv) = crate_info.msrv_comparison() {
+        crate_obj.insert(
+            "msrv".into(),
+            json!({
+                "crate": msrv.crate_msrv,
+                "workspace": msrv.workspace_msrv,
+                "compatible": msrv.compatible(),
+            }),
+        );
+    }
+
+    if let Some(reachability) = crate_info.reachability {
+        crate_obj.insert("reachability".into(), json!(reachability.as_str()));
+    }
+
+    if !crate_info.category_scores.is_empty() {
+        let category_scores: serde_json::Map<_, _> = crate_info
+            .category_scores
+            .iter()
+            .map(|(category, category_score)| {
+                (
+                    category.to_string(),
+                    json!({ "score": category_score.score, "risk": category_score.risk }),
+                )
+            })
+            .collect();
+        crate_obj.insert("category_scores".into(), json!(category_scores));
+    }
+
+    if !crate_info.notes.is_empty() {
+        crate_obj.insert("notes".into(), json!(crate_info.notes));
+    }
+
+    if !crate_info.cancelled_sources.is_empty() {
+        crate_obj.insert("not_analyzed".into(), json!(crate_info.cancelled_sources));
+    }
+
+    let timestamps = &crate_info.data_timestamps;
+    if *timestamps != DataTimestamps::default() {
+        let rfc3339 = |timestamp: Option<DateTime<Utc>>| timestamp.map(|t| t.to_rfc3339());
+        crate_obj.insert(
+            "data_timestamps".into(),
+            json!({
+                "crates": rfc3339(timestamps.crates),
+                "hosting": rfc3339(timestamps.hosting),
+                "advisories": rfc3339(timestamps.advisories),
+            }),
+        );
+    }
+
+    let mut metrics_obj = serde_json::Map::new();
+    for metric in &crate_info.metrics {
+        if let Some(ref value) = metric.value {
+            let json_value = metric_value_to_json(value, buf);
+            metrics_obj.insert(metric.name().into(), json_value);
+        }
+    }
+
+    crate_obj.insert("metrics".into(), json!(metrics_obj));
+    json!(crate_obj)
+}
+
+/// Read the appraisal scores of the crates in a previously generated JSON report, keyed by crate name.
+///
+/// Reports written before the numeric `score` field was added are handled by extracting the score
+/// from the `result` text. Crates that weren't appraised are omitted.
+pub fn read_scores(text: &str) -> Result<HashMap<String, f64>> {
+    let report: serde_json::Value = serde_json::from_str(text)?;
+    let crates = report
+        .get("crates")
+        .and_then(serde_json::Value::as_array)
+        .ok_or_else(|| ohno::app_err!("report has no 'crates' array"))?;
+
+    Ok(crates
+        .iter()
+        .filter_map(|c| {
+            let name = c.get("name")?.as_str()?;
+            let score = appraisal_score(c.get("appraisal")?)?;
+            Some((name.to_string(), score))
+        })
+        .collect())
+}
+
+/// A crate as recorded in a previously generated JSON report
+#[derive(Debug)]
+pub struct ReportedCrate {
+    pub name: String,
+    pub version: String,
+
+    /// The risk band the crate was appraised in, or `None` if it wasn't appraised
+    pub risk: Option<Risk>,
+
+    /// The score the crate was appraised with, or `None` if it wasn't appraised
+    pub score: Option<f64>,
+
+    /// The metrics of the crate, rebuilt from the values in the report
+    pub metrics: Vec<Metric>,
+}
+
+/// Read the crates of a previously generated JSON report, along with their appraisal and metrics.
+///
+/// Timestamps are only recorded to the day in reports, so metrics counting days are approximated.
+pub fn read_crates(text: &str) -> Result<Vec<ReportedCrate>> {
+    let report: serde_json::Value = serde_json::from_str(text)?;
+    let crates = report
+        .get("crates")
+        .and_then(serde_json::Value::as_array)
+        .ok_or_else(|| ohno::app_err!("report has no 'crates' array"))?;
+
+    let empty = serde_json::Map::new();
+    Ok(crates
+        .iter()
+        .filter_map(|c| {
+            let appraisal = c.get("appraisal");
+            let risk = appraisal.and_then(|a| a.get("result")?.as_str()).and_then(|result| {
+                [Risk::Low, Risk::Medium, Risk::High]
+                    .into_iter()
+                    .find(|risk| result.starts_with(common::format_risk_status(*risk)))
+            });
+            let metrics = c.get("metrics").and_then(serde_json::Value::as_object).unwrap_or(&empty);
+
+            Some(ReportedCrate {
+                name: c.get("name")?.as_str()?.to_string(),
+                version: c.get("version").and_then(serde_json::Value::as_str).unwrap_or_default().to_string(),
+                risk,
+                score: appraisal.and_then(appraisal_score),
+                metrics: metrics_from_json(metrics).collect(),
+            })
+        })
+        .collect())
+}
+
+/// The score of an appraisal in a report, extracted from the `result` text for reports written before the
+/// numeric `score` field was added.
+fn appraisal_score(appraisal: &serde_json::Value) -> Option<f64> {
+    appraisal.get("score").and_then(serde_json::Value::as_f64).or_else(|| {
+        let result = appraisal.get("result")?.as_str()?;
+        let (_, rest) = result.split_once("score = ")?;
+        rest.split([',', ')']).next()?.trim().parse().ok()
+    })
+}
+
 fn metric_value_to_json(value: &MetricValue, buf: &mut String) -> serde_json::Value {
     match value {
         MetricValue::UInt(u) => json!(u),
@@ -72,7 +238,6 @@ mod tests {
     use super::*;
     use crate::expr::{Appraisal, ExpressionDisposition, ExpressionOutcome, Risk};
     use crate::metrics::{Metric, MetricCategory, MetricDef};
-    use chrono::{DateTime, Utc};
     use std::sync::Arc;
 
     static NAME_DEF: MetricDef = MetricDef {
@@ -91,6 +256,14 @@ mod tests {
         default_value: || None,
     };
 
+    static MSRV_DEF: MetricDef = MetricDef {
+        name: "crate.minimum_rust",
+        description: "Minimum Rust version (MSRV) required to compile this crate",
+        category: MetricCategory::Metadata,
+        extractor: |_| None,
+        default_value: || None,
+    };
+
     fn create_test_crate(name: &str, version: &str, evaluation: Option<Appraisal>) -> ReportableCrate {
         let metrics = vec![
             Metric::with_value(&NAME_DEF, MetricValue::String(name.into())),
@@ -99,6 +272,58 @@ mod tests {
         ReportableCrate::new(name.into(), Arc::new(version.parse().unwrap()), metrics, evaluation)
     }
 
+    #[test]
+    fn test_read_scores_round_trip() {
+        let eval = Appraisal::new(Risk::Medium, vec![], 10, 7, 70.0);
+        let crates = vec![create_test_crate("serde", "1.0.0", Some(eval)), create_test_crate("unappraised", "1.0.0", None)];
+        let mut output = String::new();
+        generate(&crates, &[], &mut output).unwrap();
+
+        let scores = read_scores(&output).unwrap();
+        assert_eq!(scores.len(), 1);
+        assert!((scores["serde"] - 70.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_read_scores_from_result_text() {
+        let report = r#"{"crates": [{"name": "tokio", "appraisal": {"result": "LOW RISK (score = 85, awarded points = 17, available points = 20)"}}]}"#;
+        let scores = read_scores(report).unwrap();
+        assert!((scores["tokio"] - 85.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_read_crates_round_trip() {
+        static STARS_DEF: MetricDef = MetricDef {
+            name: "community.repo_stars",
+            description: "Stars",
+            category: MetricCategory::Community,
+            extractor: |_| None,
+            default_value: || Some(MetricValue::UInt(0)),
+        };
+
+        let mut serde = create_test_crate("serde", "1.0.0", Some(Appraisal::new(Risk::Medium, vec![], 10, 7, 70.0)));
+        serde.metrics.push(Metric::with_value(&STARS_DEF, MetricValue::UInt(42)));
+        let crates = vec![serde, create_test_crate("unappraised", "1.0.0", None)];
+        let mut output = String::new();
+        generate(&crates, &[], &mut output).unwrap();
+
+        let reported = read_crates(&output).unwrap();
+        assert_eq!(reported.len(), 2);
+        assert_eq!(reported[0].name, "serde");
+        assert_eq!(reported[0].version, "1.0.0");
+        assert_eq!(reported[0].risk, Some(Risk::Medium));
+        assert!(reported[0].score.is_some_and(|score| (score - 70.0).abs() < f64::EPSILON));
+        let stars = reported[0].metrics.iter().find(|m| m.name() == "community.repo_stars").unwrap();
+        assert!(matches!(stars.value, Some(MetricValue::UInt(42))));
+        assert_eq!(reported[1].risk, None);
+    }
+
+    #[test]
+    fn test_read_scores_invalid_report() {
+        let _ = read_scores("{}").unwrap_err();
+        let _ = read_scores("not json").unwrap_err();
+    }
+
     #[test]
     fn test_metric_value_to_json_float() {
         let value = MetricValue::Float(1.234);
@@ -133,18 +358,152 @@ mod tests {
     fn test_generate_empty_crates() {
         let crates: Vec<ReportableCrate> = vec![];
         let mut output = String::new();
-        let result = generate(&crates, &mut output);
+        let result = generate(&crates, &[], &mut output);
         result.unwrap();
         let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
         assert!(parsed["crates"].is_array());
         assert_eq!(parsed["crates"].as_array().unwrap().len(), 0);
     }
 
+    #[test]
+    fn test_generate_workspace_score() {
+        let appraisal = |score: f64| Some(Appraisal::new(Risk::Low, vec![], 0, 0, score));
+        let mut runtime = create_test_crate("runtime", "1.0.0", appraisal(95.0));
+        runtime.weight = Some(1.0);
+        let mut tester = create_test_crate("tester", "1.0.0", appraisal(55.0));
+        tester.weight = Some(0.25);
+
+        let mut output = String::new();
+        generate(&[runtime, tester], &[], &mut output).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(parsed["workspace"]["score"], 87.0);
+        assert_eq!(parsed["workspace"]["grade"], "B");
+        assert_eq!(parsed["workspace"]["crates"], 2);
+    }
+
+    #[test]
+    fn test_generate_risk_matrix_columns() {
+        let appraisal = Appraisal::new(Risk::Medium, vec![], 0, 0, 50.0);
+        let mut crate_info = create_test_crate("dep", "1.0.0", Some(appraisal));
+        let mut output = String::new();
+        generate(core::slice::from_ref(&crate_info), &[], &mut output).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert!(parsed["crates"][0]["appraisal"]["impact"].is_null());
+
+        crate_info.impact = Some(crate::reports::Impact::High);
+        let mut output = String::new();
+        generate(&[crate_info], &[], &mut output).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(parsed["crates"][0]["appraisal"]["likelihood"], "medium");
+        assert_eq!(parsed["crates"][0]["appraisal"]["impact"], "high");
+    }
+
+    #[test]
+    fn test_generate_category_scores() {
+        let appraisal = Appraisal::new(Risk::Low, vec![], 0, 0, 80.0);
+        let mut crate_info = create_test_crate("dep", "1.0.0", Some(appraisal));
+        let _ = crate_info.category_scores.insert(
+            MetricCategory::Activity,
+            crate::reports::CategoryScore {
+                score: 45.0,
+                risk: Risk::Medium,
+            },
+        );
+
+        let mut output = String::new();
+        generate(&[crate_info], &[], &mut output).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(parsed["crates"][0]["category_scores"]["Activity"]["score"], 45.0);
+        assert_eq!(parsed["crates"][0]["category_scores"]["Activity"]["risk"], "medium");
+    }
+
+    #[test]
+    fn test_generate_data_timestamps() {
+        let mut crate_info = create_test_crate("dep", "1.0.0", None);
+        let mut output = String::new();
+        generate(core::slice::from_ref(&crate_info), &[], &mut output).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert!(parsed["crates"][0]["data_timestamps"].is_null());
+
+        crate_info.data_timestamps.hosting = Some(DateTime::UNIX_EPOCH);
+        let mut output = String::new();
+        generate(&[crate_info], &[], &mut output).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(parsed["crates"][0]["data_timestamps"]["hosting"], "1970-01-01T00:00:00+00:00");
+        assert!(parsed["crates"][0]["data_timestamps"]["crates"].is_null());
+    }
+
+    #[test]
+    fn test_generate_not_analyzed() {
+        let mut crate_info = create_test_crate("dep", "1.0.0", None);
+        crate_info.cancelled_sources = vec!["hosting", "docs"];
+        let mut output = String::new();
+        generate(&[crate_info], &[], &mut output).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(parsed["crates"][0]["not_analyzed"], json!(["hosting", "docs"]));
+    }
+
+    #[test]
+    fn test_generate_msrv() {
+        let mut old_msrv = create_test_crate("old", "1.0.0", None);
+        old_msrv.metrics.push(Metric::with_value(&MSRV_DEF, MetricValue::String("1.60".into())));
+        let mut new_msrv = create_test_crate("new", "1.0.0", None);
+        new_msrv.metrics.push(Metric::with_value(&MSRV_DEF, MetricValue::String("1.85.0".into())));
+        let unspecified = create_test_crate("unspecified", "1.0.0", None);
+
+        let mut crates = vec![new_msrv, old_msrv, unspecified];
+        let mut output = String::new();
+        generate(&crates, &[], &mut output).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert!(parsed["crates"][0]["msrv"].is_null());
+
+        for crate_info in &mut crates {
+            crate_info.workspace_msrv = Some("1.70".into());
+        }
+        assert_eq!(crates[0].msrv_comparison().unwrap().to_string(), "1.85.0 (above 1.70)");
+        assert_eq!(crates[1].msrv_comparison().unwrap().to_string(), "1.60 (within 1.70)");
+        assert_eq!(crates[2].msrv_comparison().unwrap().to_string(), "unspecified");
+
+        let mut output = String::new();
+        generate(&crates, &[], &mut output).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(parsed["crates"][0]["msrv"]["compatible"], false);
+        assert_eq!(parsed["crates"][1]["msrv"]["crate"], "1.60");
+        assert_eq!(parsed["crates"][1]["msrv"]["compatible"], true);
+        assert!(parsed["crates"][2]["msrv"]["compatible"].is_null());
+        assert_eq!(parsed["crates"][2]["msrv"]["workspace"], "1.70");
+    }
+
+    #[test]
+    fn test_generate_api_usage() {
+        let crates = vec![create_test_crate("dep", "1.0.0", None)];
+        let mut output = String::new();
+        generate(&crates, &[], &mut output).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert!(parsed.get("api_usage").is_none());
+
+        let usage = ApiUsage {
+            host: "GitHub",
+            requests: 120,
+            rate_limited: 1,
+            remaining: Some(4880),
+            reset_at: DateTime::from_timestamp(1_704_067_200, 0),
+        };
+        let mut output = String::new();
+        generate(&crates, &[usage], &mut output).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(parsed["api_usage"][0]["host"], "GitHub");
+        assert_eq!(parsed["api_usage"][0]["requests"], 120);
+        assert_eq!(parsed["api_usage"][0]["rate_limited"], 1);
+        assert_eq!(parsed["api_usage"][0]["remaining"], 4880);
+        assert_eq!(parsed["api_usage"][0]["reset_at"], "2024-01-01T00:00:00+00:00");
+    }
+
     #[test]
     fn test_generate_single_crate_no_evaluation() {
         let crates = vec![create_test_crate("test_crate", "1.2.3", None)];
         let mut output = String::new();
-        let result = generate(&crates, &mut output);
+        let result = generate(&crates, &[], &mut output);
         result.unwrap();
         let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
         assert_eq!(parsed["crates"][0]["name"], "test_crate");
@@ -161,10 +520,11 @@ mod tests {
             available_points: 1,
             awarded_points: 1,
             score: 100.0,
+            unevaluated_points: 0,
         };
         let crates = vec![create_test_crate("test_crate", "1.0.0", Some(eval))];
         let mut output = String::new();
-        let result = generate(&crates, &mut output);
+        let result = generate(&crates, &[], &mut output);
         result.unwrap();
         let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
         assert_eq!(parsed["crates"][0]["appraisal"]["result"], "LOW RISK (score = 100, awarded points = 1, available points = 1)");
@@ -178,7 +538,7 @@ mod tests {
             create_test_crate("crate_b", "2.0.0", None),
         ];
         let mut output = String::new();
-        let result = generate(&crates, &mut output);
+        let result = generate(&crates, &[], &mut output);
         result.unwrap();
         let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
         assert_eq!(parsed["crates"].as_array().unwrap().len(), 2);
@@ -194,10 +554,11 @@ mod tests {
             available_points: 1,
             awarded_points: 0,
             score: 0.0,
+            unevaluated_points: 0,
         };
         let crates = vec![create_test_crate("bad_crate", "1.0.0", Some(eval))];
         let mut output = String::new();
-        let result = generate(&crates, &mut output);
+        let result = generate(&crates, &[], &mut output);
         result.unwrap();
         let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
         assert_eq!(parsed["crates"][0]["appraisal"]["result"], "HIGH RISK (score = 0, awarded points = 0, available points = 1)");
@@ -207,7 +568,7 @@ mod tests {
     fn test_generate_pretty_formatting() {
         let crates = vec![create_test_crate("test", "1.0.0", None)];
         let mut output = String::new();
-        let result = generate(&crates, &mut output);
+        let result = generate(&crates, &[], &mut output);
         result.unwrap();
         // Pretty-printed JSON should have newlines and indentation
         assert!(output.contains('\n'));
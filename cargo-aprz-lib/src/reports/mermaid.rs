@@ -0,0 +1,173 @@
+use super::{DependencyGraph, ReportableCrate};
+use crate::HashMap;
+use crate::Result;
+use crate::expr::Risk;
+use crate::facts::CrateRef;
+use core::fmt::Write;
+
+/// Node styles for each risk, as Mermaid `classDef` statements.
+const CLASS_DEFS: &str = "\
+    classDef root fill:#ffffff,stroke:#000000,stroke-width:2px
+    classDef low fill:#c8e6c9,stroke:#2e7d32
+    classDef medium fill:#fff59d,stroke:#f9a825
+    classDef high fill:#ffab91,stroke:#c62828
+    classDef unappraised fill:#eeeeee,stroke:#9e9e9e";
+
+/// Generate Markdown holding Mermaid diagrams of the appraisal.
+///
+/// The document starts with a pie chart summarizing how many crates fall in each risk band. When a
+/// dependency graph is available, it's followed by a flowchart where each appraised crate is colored
+/// by its risk and labeled with its score. GitHub and most wikis render the diagrams natively.
+pub fn generate<W: Write>(crates: &[ReportableCrate], graph: Option<&DependencyGraph>, writer: &mut W) -> Result<()> {
+    write_summary(crates, writer)?;
+
+    if let Some(graph) = graph {
+        writeln!(writer)?;
+        write_flowchart(crates, graph, writer)?;
+    }
+
+    Ok(())
+}
+
+fn write_summary<W: Write>(crates: &[ReportableCrate], writer: &mut W) -> Result<()> {
+    let count_risk = |risk: Risk| {
+        crates
+            .iter()
+            .filter(|c| c.appraisal.as_ref().is_some_and(|a| a.risk == risk))
+            .count()
+    };
+    let unappraised = crates.iter().filter(|c| c.appraisal.is_none()).count();
+
+    writeln!(writer, "```mermaid")?;
+    writeln!(writer, "pie showData title Dependency risk")?;
+    for (label, count) in [
+        ("Low risk", count_risk(Risk::Low)),
+        ("Medium risk", count_risk(Risk::Medium)),
+        ("High risk", count_risk(Risk::High)),
+        ("Not appraised", unappraised),
+    ] {
+        // Mermaid rejects pie slices of zero
+        if count > 0 {
+            writeln!(writer, "    \"{label}\" : {count}")?;
+        }
+    }
+    writeln!(writer, "```")?;
+    Ok(())
+}
+
+fn write_flowchart<W: Write>(crates: &[ReportableCrate], graph: &DependencyGraph, writer: &mut W) -> Result<()> {
+    // Mermaid node ids can't hold the characters found in versions, so nodes are numbered instead
+    let mut ids: HashMap<String, usize> = HashMap::default();
+
+    writeln!(writer, "```mermaid")?;
+    writeln!(writer, "flowchart LR")?;
+
+    for root in &graph.roots {
+        let id = ids.len();
+        let _ = ids.insert(root.to_string(), id);
+        writeln!(writer, "    n{id}[\"{}\"]:::root", escape(root.name()))?;
+    }
+
+    for crate_info in crates {
+        let id = ids.len();
+        let _ = ids.insert(format!("{}@{}", crate_info.name, crate_info.version), id);
+        let (label, class) = crate_info.appraisal.as_ref().map_or_else(
+            || {
+                (
+                    format!("{}<br/>{}<br/>not appraised", crate_info.name, crate_info.version),
+                    "unappraised",
+                )
+            },
+            |appraisal| {
+                let class = match appraisal.risk {
+                    Risk::Low => "low",
+                    Risk::Medium => "medium",
+                    Risk::High => "high",
+                };
+                (
+                    format!("{}<br/>{}<br/>score {:.0}", crate_info.name, crate_info.version, appraisal.score),
+                    class,
+                )
+            },
+        );
+
+        writeln!(writer, "    n{id}[\"{}\"]:::{class}", escape(&label))?;
+    }
+
+    let id_of = |crate_ref: &CrateRef| ids.get(&crate_ref.to_string()).copied();
+    for (from, to) in &graph.edges {
+        if let (Some(from), Some(to)) = (id_of(from), id_of(to)) {
+            writeln!(writer, "    n{from} --> n{to}")?;
+        }
+    }
+
+    writeln!(writer, "{CLASS_DEFS}")?;
+    writeln!(writer, "```")?;
+    Ok(())
+}
+
+/// Escape the characters which would end a quoted Mermaid label.
+fn escape(s: &str) -> String {
+    s.replace('"', "#quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::Appraisal;
+    use std::sync::Arc;
+
+    fn make_crate(name: &str, appraisal: Option<Appraisal>) -> ReportableCrate {
+        ReportableCrate::new(name.into(), Arc::new("1.0.0".parse().unwrap()), vec![], appraisal)
+    }
+
+    fn crates() -> [ReportableCrate; 3] {
+        [
+            make_crate("serde", Some(Appraisal::new(Risk::Low, vec![], 10, 9, 91.6))),
+            make_crate("leftpad", Some(Appraisal::new(Risk::High, vec![], 10, 2, 20.0))),
+            make_crate("unknown", None),
+        ]
+    }
+
+    #[test]
+    fn test_generate_summary_only() {
+        let mut output = String::new();
+        generate(&crates(), None, &mut output).unwrap();
+
+        assert_eq!(
+            output,
+            "```mermaid\npie showData title Dependency risk\n    \"Low risk\" : 1\n    \"High risk\" : 1\n    \"Not appraised\" : 1\n```\n"
+        );
+    }
+
+    #[test]
+    fn test_generate_flowchart() {
+        let app: CrateRef = "app@0.1.0".parse().unwrap();
+        let serde: CrateRef = "serde@1.0.0".parse().unwrap();
+        let leftpad: CrateRef = "leftpad@1.0.0".parse().unwrap();
+        let graph = DependencyGraph {
+            roots: vec![app.clone()],
+            edges: vec![(app.clone(), serde.clone()), (app, leftpad.clone()), (serde, leftpad)],
+        };
+
+        let mut output = String::new();
+        generate(&crates(), Some(&graph), &mut output).unwrap();
+
+        assert!(output.contains("```\n\n```mermaid\nflowchart LR\n"), "{output}");
+        assert!(output.contains(r#"    n0["app"]:::root"#), "{output}");
+        assert!(output.contains(r#"    n1["serde<br/>1.0.0<br/>score 92"]:::low"#), "{output}");
+        assert!(output.contains(r#"    n2["leftpad<br/>1.0.0<br/>score 20"]:::high"#), "{output}");
+        assert!(
+            output.contains(r#"    n3["unknown<br/>1.0.0<br/>not appraised"]:::unappraised"#),
+            "{output}"
+        );
+        assert!(output.contains("    n0 --> n1\n    n0 --> n2\n    n1 --> n2\n"), "{output}");
+        assert!(output.contains("    classDef high fill:#ffab91,stroke:#c62828\n"), "{output}");
+        assert!(output.ends_with("```\n"), "{output}");
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape(r#"a"b"#), "a#quot;b");
+    }
+}
@@ -6,12 +6,27 @@
 //!
 //! # Implementation Model
 //!
-//! Five report generators are provided, each accessed through a `generate` function:
+//! Thirteen report generators are provided, each accessed through a `generate` function:
+//! - **Backstage**: `catalog-info.yaml` annotations summarizing dependency health
 //! - **Console**: Terminal output with ANSI colors and Unicode box drawing
 //! - **CSV**: Spreadsheet-compatible format with proper escaping
+//! - **`CycloneDX`**: JSON SBOM with appraisal results attached as component properties
 //! - **HTML**: Self-contained document with embedded CSS, dark mode, and search
+//! - **Dependabot**: `dependabot.yml` update groups for risky crates
 //! - **Excel**: Native .xlsx format with multiple sheets and formatting
+//! - **Graph**: `GraphViz` DOT dependency graph with crates colored by risk
 //! - **JSON**: Machine-readable structured data
+//! - **Mermaid**: Markdown with a risk summary chart and dependency flowchart for documentation
+//! - **Renovate**: `renovate.json` package rules prioritizing updates for risky crates
+//! - **Summary**: Small JSON file with the overall outcome, for scripts and CI gates
+//! - **Vet**: cargo-vet exemption entries for well-scoring crates
+//!
+//! The **Comparison** generators additionally lay alternative crates out side by side, as a
+//! console or HTML matrix with the winner of each policy outcome highlighted, and the **Timeline**
+//! generators list the releases of a single crate with the score change from one release to the next.
+//!
+//! When crates carry an impact, the HTML report also places them on a likelihood × impact
+//! risk matrix, and the JSON and Excel reports list each crate's likelihood and impact.
 //!
 //! All generators operate on the same input: a slice of `ReportableCrate` containing
 //! crate information, metrics, and optional evaluation outcomes. This uniform interface
@@ -26,21 +41,56 @@
 //! The generators support optional evaluation displays based
 //! on evaluation outcomes.
 
+mod backstage;
 mod common;
+mod comparison;
 mod console;
 mod csv;
+mod cyclonedx;
+mod dependabot;
 mod excel;
+mod graph;
 mod html;
 mod json;
+mod mermaid;
+mod renovate;
 mod reportable_crate;
+mod risk_matrix;
+mod summary;
+mod timeline;
+mod vet;
 
+pub use backstage::generate as generate_backstage;
+pub use common::format_metric_value;
+pub use common::grade;
+pub use common::workspace_score;
+pub use comparison::generate_console as generate_comparison_console;
+pub use comparison::generate_html as generate_comparison_html;
+pub use console::ConsoleLayout;
 pub use console::ConsoleOutputMode;
 pub use console::generate as generate_console;
+pub use console::terminal_supports_hyperlinks;
 pub use csv::generate as generate_csv;
+pub use cyclonedx::generate as generate_cyclonedx;
+pub use dependabot::generate as generate_dependabot;
 pub use excel::generate as generate_xlsx;
+pub use graph::DependencyGraph;
+pub use graph::generate as generate_graph;
 pub use html::generate as generate_html;
 pub use json::generate as generate_json;
+pub use json::ReportedCrate;
+pub use json::read_crates as read_json_crates;
+pub use json::read_scores as read_json_scores;
+pub use mermaid::generate as generate_mermaid;
+pub use renovate::generate as generate_renovate;
+pub use reportable_crate::CategoryScore;
+pub use reportable_crate::Reachability;
 pub use reportable_crate::ReportableCrate;
+pub use risk_matrix::Impact;
+pub use summary::generate as generate_summary;
+pub use timeline::generate_console as generate_timeline_console;
+pub use timeline::generate_json as generate_timeline_json;
+pub use vet::generate as generate_vet;
 
 #[cfg(test)]
 mod snapshot_tests {
@@ -220,7 +270,7 @@ mod snapshot_tests {
     fn test_json_report() {
         let crates = create_test_crates();
         let mut output = String::new();
-        generate_json(&crates, &mut output).unwrap();
+        generate_json(&crates, &[], &mut output).unwrap();
         insta::assert_snapshot!(output);
     }
 
@@ -264,7 +314,7 @@ mod snapshot_tests {
     fn test_empty_crates_json() {
         let crates: Vec<ReportableCrate> = vec![];
         let mut output = String::new();
-        generate_json(&crates, &mut output).unwrap();
+        generate_json(&crates, &[], &mut output).unwrap();
         insta::assert_snapshot!(output);
     }
 
@@ -318,7 +368,7 @@ mod snapshot_tests {
         insta::assert_snapshot!("single_crate_csv", csv_output);
 
         let mut json_output = String::new();
-        generate_json(&crates, &mut json_output).unwrap();
+        generate_json(&crates, &[], &mut json_output).unwrap();
         insta::assert_snapshot!("single_crate_json", json_output);
     }
 }
@@ -0,0 +1,90 @@
+use super::ReportableCrate;
+use super::common::crate_names_with_risk;
+use crate::Result;
+use crate::expr::Risk;
+use core::fmt::Write;
+use serde_json::json;
+
+/// Generate a Renovate configuration fragment that prioritizes updates for risky crates.
+///
+/// High-risk crates are grouped into a single weekly, high-priority update, and medium-risk
+/// crates into a monthly one. Low-risk crates are left to the repository's existing rules.
+pub fn generate<W: Write>(crates: &[ReportableCrate], writer: &mut W) -> Result<()> {
+    let mut package_rules = Vec::new();
+
+    let high = crate_names_with_risk(crates, Risk::High);
+    if !high.is_empty() {
+        package_rules.push(json!({
+            "description": "Crates appraised as high risk by cargo-aprz",
+            "matchManagers": ["cargo"],
+            "matchPackageNames": high,
+            "groupName": "high-risk crates",
+            "schedule": ["before 6am on monday"],
+            "prPriority": 10,
+            "labels": ["cargo-aprz:high-risk"],
+        }));
+    }
+
+    let medium = crate_names_with_risk(crates, Risk::Medium);
+    if !medium.is_empty() {
+        package_rules.push(json!({
+            "description": "Crates appraised as medium risk by cargo-aprz",
+            "matchManagers": ["cargo"],
+            "matchPackageNames": medium,
+            "groupName": "medium-risk crates",
+            "schedule": ["before 6am on the first day of the month"],
+            "prPriority": 5,
+            "labels": ["cargo-aprz:medium-risk"],
+        }));
+    }
+
+    let config = json!({ "packageRules": package_rules });
+    write!(writer, "{}", serde_json::to_string_pretty(&config)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::Appraisal;
+    use std::sync::Arc;
+
+    fn make_crate(name: &str, version: &str, risk: Risk) -> ReportableCrate {
+        ReportableCrate::new(
+            name.into(),
+            Arc::new(version.parse().unwrap()),
+            vec![],
+            Some(Appraisal::new(risk, vec![], 10, 0, 0.0)),
+        )
+    }
+
+    fn generate_value(crates: &[ReportableCrate]) -> serde_json::Value {
+        let mut output = String::new();
+        generate(crates, &mut output).unwrap();
+        serde_json::from_str(&output).unwrap()
+    }
+
+    #[test]
+    fn test_generate_no_risky_crates() {
+        let config = generate_value(&[make_crate("good", "1.0.0", Risk::Low)]);
+        assert_eq!(config["packageRules"].as_array().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_generate_groups_by_risk() {
+        let crates = vec![
+            make_crate("zebra", "1.0.0", Risk::High),
+            make_crate("alpha", "1.0.0", Risk::High),
+            make_crate("alpha", "2.0.0", Risk::High),
+            make_crate("meh", "1.0.0", Risk::Medium),
+            make_crate("good", "1.0.0", Risk::Low),
+        ];
+        let config = generate_value(&crates);
+        let rules = config["packageRules"].as_array().unwrap();
+
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0]["matchPackageNames"], json!(["alpha", "zebra"]));
+        assert_eq!(rules[0]["prPriority"], 10);
+        assert_eq!(rules[1]["matchPackageNames"], json!(["meh"]));
+    }
+}
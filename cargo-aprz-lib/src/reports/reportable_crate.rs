@@ -1,8 +1,85 @@
-use crate::expr::Appraisal;
-use crate::metrics::Metric;
+use super::Impact;
+use crate::expr::{Appraisal, Risk, compare_rust_versions};
+use crate::facts::DataTimestamps;
+use crate::metrics::{Metric, MetricCategory, MetricValue};
+use core::cmp::Ordering;
+use core::fmt;
 use semver::Version;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
+/// Metrics which count the known vulnerabilities affecting the appraised version.
+const VERSION_VULNERABILITY_METRICS: &[&str] = &[
+    "advisories.version_low_severity_vulnerabilities",
+    "advisories.version_medium_severity_vulnerabilities",
+    "advisories.version_high_severity_vulnerabilities",
+    "advisories.version_critical_severity_vulnerabilities",
+];
+
+/// Metric holding the MSRV of the appraised version
+const MSRV_METRIC: &str = "crate.minimum_rust";
+
+/// How the MSRV of a crate compares with the one declared by the workspace depending on it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MsrvComparison<'a> {
+    /// The MSRV of the crate, unless it doesn't declare one
+    pub crate_msrv: Option<&'a str>,
+
+    /// The MSRV declared by the workspace
+    pub workspace_msrv: &'a str,
+}
+
+impl MsrvComparison<'_> {
+    /// Whether the crate builds with the workspace's MSRV, unknown when the crate declares no MSRV.
+    #[must_use]
+    pub fn compatible(&self) -> Option<bool> {
+        let ordering = compare_rust_versions(self.crate_msrv?, self.workspace_msrv)?;
+        Some(ordering != Ordering::Greater)
+    }
+}
+
+impl fmt::Display for MsrvComparison<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.crate_msrv, self.compatible()) {
+            (Some(msrv), Some(true)) => write!(f, "{msrv} (within {})", self.workspace_msrv),
+            (Some(msrv), Some(false)) => write!(f, "{msrv} (above {})", self.workspace_msrv),
+            (Some(msrv), None) => write!(f, "{msrv}"),
+            (None, _) => write!(f, "unspecified"),
+        }
+    }
+}
+
+/// How a dependency is exposed at runtime, given the kinds of dependencies through which it's reached
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reachability {
+    /// Reached through regular dependencies, so part of what the workspace ships
+    Runtime,
+
+    /// Only reached through build dependencies, so only run while building
+    BuildOnly,
+
+    /// Only reached through development dependencies, so only used by tests, examples, and benchmarks
+    DevOnly,
+}
+
+impl Reachability {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Runtime => "runtime",
+            Self::BuildOnly => "build-only",
+            Self::DevOnly => "dev-only",
+        }
+    }
+}
+
+/// The score of a crate in one metric category, along with the risk band it falls in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CategoryScore {
+    pub score: f64,
+    pub risk: Risk,
+}
+
 /// A crate with its metrics and optional evaluation outcome, ready for reporting.
 #[derive(Debug, Clone)]
 pub struct ReportableCrate {
@@ -10,17 +87,87 @@ pub struct ReportableCrate {
     pub version: Arc<Version>,
     pub metrics: Vec<Metric>,
     pub appraisal: Option<Appraisal>,
+
+    /// Score the crate received in a previous run, if one is known
+    pub previous_score: Option<f64>,
+
+    /// How much the crate counts towards the workspace score, set when appraising workspace dependencies
+    pub weight: Option<f64>,
+
+    /// How much of the dependency graph relies on the crate, set when a risk matrix is requested
+    pub impact: Option<Impact>,
+
+    /// Remarks about the crate left by the post-processing script hook
+    pub notes: Vec<String>,
+
+    /// The MSRV declared by the workspace, set when appraising the dependencies of a workspace declaring one
+    pub workspace_msrv: Option<Arc<str>>,
+
+    /// How the crate is reached from the workspace, set when appraising workspace dependencies
+    pub reachability: Option<Reachability>,
+
+    /// Whether the crate is left out of the risk checks because it isn't a runtime dependency
+    pub scope_exempt: bool,
+
+    /// Score of the crate in each metric category with eval expressions, set when the crate is appraised
+    pub category_scores: BTreeMap<MetricCategory, CategoryScore>,
+
+    /// When the data of the sources that can go stale was acquired
+    pub data_timestamps: DataTimestamps,
+
+    /// Sources whose data wasn't gathered because the run was cancelled, in which case the crate isn't appraised
+    pub cancelled_sources: Vec<&'static str>,
 }
 
 impl ReportableCrate {
     #[must_use]
-    #[expect(clippy::missing_const_for_fn, reason = "Cannot be const due to non-const parameter types")]
     pub fn new(name: Arc<str>, version: Arc<Version>, metrics: Vec<Metric>, appraisal: Option<Appraisal>) -> Self {
         Self {
             name,
             version,
             metrics,
             appraisal,
+            previous_score: None,
+            weight: None,
+            impact: None,
+            notes: Vec::new(),
+            workspace_msrv: None,
+            reachability: None,
+            scope_exempt: false,
+            category_scores: BTreeMap::new(),
+            data_timestamps: DataTimestamps::default(),
+            cancelled_sources: Vec::new(),
         }
     }
+
+    /// Change in score since the previous run, if both runs appraised the crate.
+    #[must_use]
+    pub fn score_delta(&self) -> Option<f64> {
+        self.appraisal.as_ref().zip(self.previous_score).map(|(a, previous)| a.score - previous)
+    }
+
+    /// How the MSRV of the crate compares with the workspace's, when the workspace declares one.
+    #[must_use]
+    pub fn msrv_comparison(&self) -> Option<MsrvComparison<'_>> {
+        let workspace_msrv = self.workspace_msrv.as_deref()?;
+        let crate_msrv = self.metrics.iter().find(|m| m.name() == MSRV_METRIC).and_then(|m| match &m.value {
+            Some(MetricValue::String(msrv)) if !msrv.is_empty() => Some(msrv.as_str()),
+            _ => None,
+        });
+
+        Some(MsrvComparison { crate_msrv, workspace_msrv })
+    }
+
+    /// Number of known vulnerabilities affecting the appraised version.
+    #[must_use]
+    pub fn vulnerability_count(&self) -> u64 {
+        self.metrics
+            .iter()
+            .filter(|m| VERSION_VULNERABILITY_METRICS.contains(&m.name()))
+            .filter_map(|m| match m.value {
+                Some(MetricValue::UInt(n)) => Some(n),
+                _ => None,
+            })
+            .sum()
+    }
 }
@@ -0,0 +1,129 @@
+//! Placement of crates on a likelihood × impact risk matrix.
+//!
+//! Likelihood is the risk level of a crate's appraisal, while impact reflects how much of the dependency
+//! graph relies on the crate, directly or transitively.
+
+use super::ReportableCrate;
+use crate::expr::Risk;
+
+/// How much of the dependency graph is affected should a crate turn out to be a problem.
+///
+/// Variants are ordered from least to most impactful.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Impact {
+    Low,
+    Medium,
+    High,
+}
+
+impl Impact {
+    /// All impact levels, from least to most impactful.
+    pub const ALL: [Self; 3] = [Self::Low, Self::Medium, Self::High];
+
+    /// The impact of a crate that `dependents` of the `total` packages in the dependency graph rely upon.
+    ///
+    /// Crates relied upon by at least a quarter of the graph have a high impact, and those relied upon by at
+    /// least a twentieth have a medium impact.
+    #[must_use]
+    pub const fn from_dependents(dependents: usize, total: usize) -> Self {
+        if total == 0 {
+            return Self::Low;
+        }
+
+        if dependents * 4 >= total {
+            Self::High
+        } else if dependents * 20 >= total {
+            Self::Medium
+        } else {
+            Self::Low
+        }
+    }
+
+    /// Lowercase name of the impact level.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+}
+
+/// Lowercase name of the likelihood of a risk level.
+#[must_use]
+pub const fn likelihood_str(risk: Risk) -> &'static str {
+    match risk {
+        Risk::Low => "low",
+        Risk::Medium => "medium",
+        Risk::High => "high",
+    }
+}
+
+/// The crates in one cell of the risk matrix.
+pub type Cell<'a> = Vec<&'a ReportableCrate>;
+
+/// Group crates into a matrix indexed by impact then likelihood, both from low to high.
+///
+/// Returns `None` unless at least one crate has both an impact and an appraisal.
+#[must_use]
+pub fn risk_matrix(crates: &[ReportableCrate]) -> Option<[[Cell<'_>; 3]; 3]> {
+    let mut matrix: [[Cell<'_>; 3]; 3] = Default::default();
+    let mut placed = false;
+
+    for crate_info in crates {
+        if let Some(impact) = crate_info.impact
+            && let Some(appraisal) = &crate_info.appraisal
+        {
+            matrix[impact as usize][appraisal.risk as usize].push(crate_info);
+            placed = true;
+        }
+    }
+
+    placed.then_some(matrix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::Appraisal;
+    use std::sync::Arc;
+
+    fn make_crate(name: &str, risk: Risk, impact: Option<Impact>) -> ReportableCrate {
+        let appraisal = Appraisal::new(risk, Vec::new(), 0, 0, 50.0);
+        let mut c = ReportableCrate::new(name.into(), Arc::new("1.0.0".parse().unwrap()), Vec::new(), Some(appraisal));
+        c.impact = impact;
+        c
+    }
+
+    #[test]
+    fn test_impact_from_dependents() {
+        assert_eq!(Impact::from_dependents(0, 0), Impact::Low);
+        assert_eq!(Impact::from_dependents(1, 100), Impact::Low);
+        assert_eq!(Impact::from_dependents(5, 100), Impact::Medium);
+        assert_eq!(Impact::from_dependents(24, 100), Impact::Medium);
+        assert_eq!(Impact::from_dependents(25, 100), Impact::High);
+        assert_eq!(Impact::from_dependents(1, 3), Impact::High);
+    }
+
+    #[test]
+    fn test_risk_matrix() {
+        assert!(risk_matrix(&[make_crate("a", Risk::High, None)]).is_none());
+
+        let crates = [
+            make_crate("core", Risk::High, Some(Impact::High)),
+            make_crate("leaf", Risk::High, Some(Impact::Low)),
+            make_crate("util", Risk::Low, Some(Impact::High)),
+            make_crate("other", Risk::Low, None),
+        ];
+        let matrix = risk_matrix(&crates).unwrap();
+
+        let names = |impact: Impact, risk: Risk| -> Vec<&str> {
+            matrix[impact as usize][risk as usize].iter().map(|c| &*c.name).collect()
+        };
+        assert_eq!(names(Impact::High, Risk::High), ["core"]);
+        assert_eq!(names(Impact::Low, Risk::High), ["leaf"]);
+        assert_eq!(names(Impact::High, Risk::Low), ["util"]);
+        assert!(names(Impact::Medium, Risk::Medium).is_empty());
+    }
+}
@@ -0,0 +1,86 @@
+use super::ReportableCrate;
+use crate::Result;
+use crate::expr::Risk;
+use core::fmt::Write;
+use core::time::Duration;
+use serde_json::json;
+
+/// Generate a small JSON summary of the appraisal, for scripts which only need the outcome.
+///
+/// `passed` tells whether the run succeeded, which is to say whether no `--error-if` check failed.
+pub fn generate<W: Write>(crates: &[ReportableCrate], passed: bool, duration: Duration, writer: &mut W) -> Result<()> {
+    let count_risk = |risk: Risk| crates.iter().filter(|c| c.appraisal.as_ref().is_some_and(|a| a.risk == risk)).count();
+
+    let worst = crates
+        .iter()
+        .filter_map(|c| c.appraisal.as_ref().map(|a| (c, a.score)))
+        .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(core::cmp::Ordering::Equal))
+        .map(|(c, score)| {
+            json!({
+                "name": c.name,
+                "version": c.version.to_string(),
+                "score": score,
+            })
+        });
+
+    let output = json!({
+        "passed": passed,
+        "crates": crates.len(),
+        "risk": {
+            "low": count_risk(Risk::Low),
+            "medium": count_risk(Risk::Medium),
+            "high": count_risk(Risk::High),
+            "not_appraised": crates.iter().filter(|c| c.appraisal.is_none()).count(),
+        },
+        "worst": worst,
+        "duration_secs": duration.as_secs_f64(),
+    });
+
+    writeln!(writer, "{}", serde_json::to_string_pretty(&output)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::Appraisal;
+    use std::sync::Arc;
+
+    fn make_crate(name: &str, appraisal: Option<Appraisal>) -> ReportableCrate {
+        ReportableCrate::new(name.into(), Arc::new("1.0.0".parse().unwrap()), vec![], appraisal)
+    }
+
+    #[test]
+    fn test_generate_summary() {
+        let crates = [
+            make_crate("serde", Some(Appraisal::new(Risk::Low, vec![], 10, 9, 90.0))),
+            make_crate("leftpad", Some(Appraisal::new(Risk::High, vec![], 10, 2, 20.0))),
+            make_crate("unknown", None),
+        ];
+
+        let mut output = String::new();
+        generate(&crates, false, Duration::from_millis(1500), &mut output).unwrap();
+        let summary: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        assert_eq!(
+            summary,
+            json!({
+                "passed": false,
+                "crates": 3,
+                "risk": { "low": 1, "medium": 0, "high": 1, "not_appraised": 1 },
+                "worst": { "name": "leftpad", "version": "1.0.0", "score": 20.0 },
+                "duration_secs": 1.5,
+            })
+        );
+    }
+
+    #[test]
+    fn test_generate_summary_without_appraisals() {
+        let mut output = String::new();
+        generate(&[make_crate("unknown", None)], true, Duration::ZERO, &mut output).unwrap();
+        let summary: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        assert_eq!(summary["passed"], json!(true));
+        assert_eq!(summary["worst"], serde_json::Value::Null);
+    }
+}
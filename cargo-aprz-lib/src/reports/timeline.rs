@@ -0,0 +1,210 @@
+//! Score timeline of the releases of a crate.
+//!
+//! Each release is appraised on its own, so the timeline shows how the quality of a crate evolved from
+//! one release to the next. Releases are listed from the oldest to the newest, with the score change
+//! since the previous release.
+
+use super::ReportableCrate;
+use crate::Result;
+use crate::expr::Risk;
+use crate::metrics::MetricValue;
+use chrono::{DateTime, Utc};
+use core::fmt::Write;
+use owo_colors::OwoColorize;
+use serde_json::json;
+
+/// Metric holding when a release was published
+const PUBLISHED_METRIC: &str = "stability.version_created_at";
+
+/// Score changes smaller than this are reported as stable
+const STABLE_THRESHOLD: f64 = 1.0;
+
+fn published(crate_info: &ReportableCrate) -> Option<DateTime<Utc>> {
+    crate_info
+        .metrics
+        .iter()
+        .find(|m| m.def.name == PUBLISHED_METRIC)
+        .and_then(|m| match &m.value {
+            Some(MetricValue::DateTime(dt)) => Some(*dt),
+            _ => None,
+        })
+}
+
+/// The releases in chronological order.
+fn sorted(crates: &[ReportableCrate]) -> Vec<&ReportableCrate> {
+    let mut releases: Vec<_> = crates.iter().collect();
+    releases.sort_by(|a, b| a.version.cmp(&b.version));
+    releases
+}
+
+const fn risk_label(risk: Risk) -> &'static str {
+    match risk {
+        Risk::Low => "Low",
+        Risk::Medium => "Medium",
+        Risk::High => "High",
+    }
+}
+
+/// Write the timeline of the given releases of a crate to the console.
+pub fn generate_console<W: Write>(crates: &[ReportableCrate], use_colors: bool, writer: &mut W) -> Result<()> {
+    let releases = sorted(crates);
+    let version_width = releases.iter().map(|c| c.version.to_string().len()).max().unwrap_or(0).max("Version".len());
+
+    writeln!(writer, "{:version_width$}  {:10}  {:>5}  {:6}  {:>6}", "Version", "Published", "Score", "Risk", "Change")?;
+
+    let mut previous_score = None;
+    for crate_info in &releases {
+        let date = published(crate_info).map_or_else(String::new, |dt| dt.format("%Y-%m-%d").to_string());
+        let score = crate_info.appraisal.as_ref().map(|a| a.score);
+
+        let change = match (previous_score, score) {
+            (Some(before), Some(after)) => format!("{:+.0}", after - before),
+            _ => String::new(),
+        };
+
+        let (score_text, risk_text) = crate_info.appraisal.as_ref().map_or_else(
+            || ("-".to_string(), "-".to_string()),
+            |a| (format!("{:.0}", a.score), risk_label(a.risk).to_string()),
+        );
+        let risk_text = format!("{risk_text:6}");
+        let risk_text = match crate_info.appraisal.as_ref().map(|a| a.risk) {
+            Some(Risk::Low) if use_colors => risk_text.green().to_string(),
+            Some(Risk::Medium) if use_colors => risk_text.yellow().to_string(),
+            Some(Risk::High) if use_colors => risk_text.red().to_string(),
+            _ => risk_text,
+        };
+
+        writeln!(
+            writer,
+            "{:version_width$}  {date:10}  {score_text:>5}  {risk_text}  {change:>6}",
+            crate_info.version.to_string()
+        )?;
+
+        if score.is_some() {
+            previous_score = score;
+        }
+    }
+
+    if let Some(trend) = trend(&releases) {
+        writeln!(writer)?;
+        writeln!(writer, "{trend}")?;
+    }
+
+    Ok(())
+}
+
+/// Summarize how the score evolved between the oldest and newest appraised releases.
+fn trend(releases: &[&ReportableCrate]) -> Option<String> {
+    let mut scored = releases.iter().filter_map(|c| c.appraisal.as_ref().map(|a| (c, a.score)));
+    let (first, first_score) = scored.next()?;
+    let (last, last_score) = scored.next_back()?;
+
+    let direction = if last_score - first_score >= STABLE_THRESHOLD {
+        "improving"
+    } else if first_score - last_score >= STABLE_THRESHOLD {
+        "decaying"
+    } else {
+        "stable"
+    };
+
+    Some(format!(
+        "Score went from {first_score:.0} in v{} to {last_score:.0} in v{}: {direction}",
+        first.version, last.version
+    ))
+}
+
+/// Write the timeline of the given releases of a crate as JSON.
+pub fn generate_json<W: Write>(crates: &[ReportableCrate], writer: &mut W) -> Result<()> {
+    let releases = sorted(crates);
+
+    let output = json!({
+        "crate": releases.first().map(|c| c.name.as_ref()),
+        "releases": releases
+            .iter()
+            .map(|c| {
+                json!({
+                    "version": c.version.to_string(),
+                    "published": published(c),
+                    "score": c.appraisal.as_ref().map(|a| a.score),
+                    "risk": c.appraisal.as_ref().map(|a| a.risk),
+                })
+            })
+            .collect::<Vec<_>>(),
+    });
+
+    writeln!(writer, "{}", serde_json::to_string_pretty(&output)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::Appraisal;
+    use crate::metrics::{Metric, MetricCategory, MetricDef};
+    use std::sync::Arc;
+
+    static PUBLISHED_DEF: MetricDef = MetricDef {
+        name: PUBLISHED_METRIC,
+        description: "When this version was first published to crates.io",
+        category: MetricCategory::Stability,
+        extractor: |_| None,
+        default_value: || None,
+    };
+
+    fn make_release(version: &str, published: &str, score: Option<f64>) -> ReportableCrate {
+        let published: DateTime<Utc> = format!("{published}T00:00:00Z").parse().unwrap();
+        let risk = match score {
+            Some(s) if s >= 70.0 => Risk::Low,
+            Some(s) if s >= 40.0 => Risk::Medium,
+            _ => Risk::High,
+        };
+        ReportableCrate::new(
+            "foo".into(),
+            Arc::new(version.parse().unwrap()),
+            vec![Metric::with_value(&PUBLISHED_DEF, MetricValue::DateTime(published))],
+            score.map(|s| Appraisal::new(risk, vec![], 10, 5, s)),
+        )
+    }
+
+    #[test]
+    fn test_generate_console() {
+        let crates = [
+            make_release("2.0.0", "2024-06-01", Some(85.0)),
+            make_release("1.0.0", "2023-01-15", Some(60.0)),
+            make_release("1.1.0", "2023-09-30", None),
+        ];
+
+        let mut output = String::new();
+        generate_console(&crates, false, &mut output).unwrap();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines[0], "Version  Published   Score  Risk    Change");
+        assert_eq!(lines[1], "1.0.0    2023-01-15     60  Medium        ");
+        assert_eq!(lines[2], "1.1.0    2023-09-30      -  -             ");
+        assert_eq!(lines[3], "2.0.0    2024-06-01     85  Low        +25");
+        assert_eq!(lines[5], "Score went from 60 in v1.0.0 to 85 in v2.0.0: improving");
+    }
+
+    #[test]
+    fn test_trend() {
+        let decaying = [make_release("1.0.0", "2023-01-15", Some(80.0)), make_release("1.1.0", "2023-09-30", Some(50.0))];
+        assert!(trend(&sorted(&decaying)).unwrap().ends_with("decaying"));
+
+        let stable = [make_release("1.0.0", "2023-01-15", Some(80.0)), make_release("1.1.0", "2023-09-30", Some(80.4))];
+        assert!(trend(&sorted(&stable)).unwrap().ends_with("stable"));
+
+        assert!(trend(&sorted(&stable[..1])).is_none());
+    }
+
+    #[test]
+    fn test_generate_json() {
+        let crates = [make_release("1.1.0", "2023-09-30", Some(70.0)), make_release("1.0.0", "2023-01-15", None)];
+        let mut output = String::new();
+        generate_json(&crates, &mut output).unwrap();
+
+        let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(parsed["crate"], "foo");
+        assert_eq!(parsed["releases"][0]["version"], "1.0.0");
+        assert!(parsed["releases"][0]["score"].is_null());
+        assert_eq!(parsed["releases"][1]["risk"], "low");
+    }
+}
@@ -0,0 +1,77 @@
+use super::ReportableCrate;
+use crate::Result;
+use core::fmt::Write;
+
+/// Generate cargo-vet `[[exemptions.<crate>]]` entries for crates scoring at or above `min_score`.
+///
+/// The output is meant to be merged into `supply-chain/config.toml` as a starting point when
+/// adopting cargo-vet. Crates that were not appraised are skipped.
+pub fn generate<W: Write>(crates: &[ReportableCrate], min_score: f64, criteria: &str, writer: &mut W) -> Result<()> {
+    writeln!(writer, "# cargo-vet exemptions generated by cargo-aprz {}", env!("CARGO_PKG_VERSION"))?;
+    writeln!(writer, "# Crates with an appraisal score of at least {min_score:.0} are included.")?;
+
+    for crate_info in crates {
+        let Some(appraisal) = &crate_info.appraisal else {
+            continue;
+        };
+
+        if appraisal.score < min_score {
+            continue;
+        }
+
+        writeln!(writer)?;
+        writeln!(writer, "[[exemptions.{}]]", crate_info.name)?;
+        writeln!(writer, "version = \"{}\"", crate_info.version)?;
+        writeln!(writer, "criteria = \"{criteria}\"")?;
+        writeln!(
+            writer,
+            "notes = \"cargo-aprz score {:.0} ({} of {} points)\"",
+            appraisal.score, appraisal.awarded_points, appraisal.available_points
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::{Appraisal, Risk};
+    use std::sync::Arc;
+
+    fn make_crate(name: &str, version: &str, score: Option<f64>) -> ReportableCrate {
+        ReportableCrate::new(
+            name.into(),
+            Arc::new(version.parse().unwrap()),
+            vec![],
+            score.map(|s| Appraisal::new(Risk::Low, vec![], 10, 8, s)),
+        )
+    }
+
+    #[test]
+    fn test_generate_filters_by_score() {
+        let crates = vec![
+            make_crate("good", "1.0.0", Some(80.0)),
+            make_crate("bad", "2.0.0", Some(20.0)),
+            make_crate("unknown", "3.0.0", None),
+        ];
+        let mut output = String::new();
+        generate(&crates, 70.0, "safe-to-deploy", &mut output).unwrap();
+
+        assert!(output.contains("[[exemptions.good]]\nversion = \"1.0.0\"\ncriteria = \"safe-to-deploy\"\n"));
+        assert!(!output.contains("exemptions.bad"));
+        assert!(!output.contains("exemptions.unknown"));
+    }
+
+    #[test]
+    fn test_generate_is_valid_toml() {
+        let crates = vec![make_crate("serde_json", "1.0.100", Some(90.0)), make_crate("tokio-util", "0.7.0", Some(70.0))];
+        let mut output = String::new();
+        generate(&crates, 70.0, "safe-to-run", &mut output).unwrap();
+
+        let parsed: toml::Table = toml::from_str(&output).unwrap();
+        let exemptions = parsed["exemptions"].as_table().unwrap();
+        assert_eq!(exemptions.len(), 2);
+        assert_eq!(exemptions["tokio-util"][0]["criteria"].as_str(), Some("safe-to-run"));
+    }
+}
@@ -108,6 +108,9 @@ async fn test_coverage_provider_with_fixture() {
         ProviderResult::Unavailable(reason) => {
             panic!("Expected Found result, got Unavailable: {reason}");
         }
+        ProviderResult::Cancelled => {
+            panic!("Expected Found result, got Cancelled");
+        }
     }
 }
 
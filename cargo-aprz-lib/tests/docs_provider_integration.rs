@@ -94,6 +94,9 @@ async fn test_docs_provider_with_fixture() {
         ProviderResult::Unavailable(reason) => {
             panic!("Expected Found result, got Unavailable: {reason}");
         }
+        ProviderResult::Cancelled => {
+            panic!("Expected Found result, got Cancelled");
+        }
     }
 }
 
@@ -144,6 +147,8 @@ const fn make_sentinel_docs_data() -> DocsData {
             has_crate_level_docs: true,
             broken_doc_links: 0,
         },
+        public_api: Vec::new(),
+        api_churn: None,
     }
 }
 